@@ -58,6 +58,7 @@ use unc_primitives::challenge::{
     MaybeEncodedShardChunk, PartialState, SlashedValidator,
 };
 use unc_primitives::checked_feature;
+use unc_primitives::congestion_info::BlockCongestionInfo;
 #[cfg(feature = "new_epoch_sync")]
 use unc_primitives::epoch_manager::epoch_sync::EpochSyncInfo;
 #[cfg(feature = "new_epoch_sync")]
@@ -67,7 +68,7 @@ use unc_primitives::hash::{hash, CryptoHash};
 use unc_primitives::merkle::{
     combine_hash, merklize, verify_path, Direction, MerklePath, MerklePathItem, PartialMerkleTree,
 };
-use unc_primitives::receipt::Receipt;
+use unc_primitives::receipt::{Receipt, ReceiptEnum};
 use unc_primitives::sandbox::state_patch::SandboxStatePatch;
 use unc_primitives::shard_layout::{account_id_to_shard_id, ShardLayout, ShardUId};
 use unc_primitives::sharding::{
@@ -466,6 +467,7 @@ impl Chain {
                             0,
                             chain_genesis.gas_limit,
                             0,
+                            chunk_header.congestion_info(),
                         ),
                     );
                 }
@@ -1858,6 +1860,12 @@ impl Chain {
             metrics::VALIDATOR_AMOUNT_STAKED.set(i64::try_from(pledge).unwrap_or(i64::MAX));
             metrics::VALIDATOR_ACTIVE_TOTAL.set(i64::try_from(count).unwrap_or(i64::MAX));
 
+            if tip.epoch_id != prev_head.epoch_id {
+                if let Err(err) = self.precompile_contracts_for_next_epoch(me, &block) {
+                    warn!(target: "chain", ?err, "Failed to precompile contracts for the next epoch");
+                }
+            }
+
             self.last_time_head_updated = StaticClock::instant();
         };
 
@@ -1886,6 +1894,32 @@ impl Chain {
         Ok(AcceptedBlock { hash: *block.hash(), status: block_status, provenance })
     }
 
+    /// Starts compiling the contracts of the tracked shards for the VM config of the next epoch.
+    /// Called on the first block of an epoch, which is when the next epoch's protocol version
+    /// becomes known, so the compilation has a whole epoch to finish before the upgrade.
+    fn precompile_contracts_for_next_epoch(
+        &self,
+        me: &Option<AccountId>,
+        block: &Block,
+    ) -> Result<(), Error> {
+        let epoch_id = block.header().epoch_id();
+        let mut state_roots = vec![];
+        for shard_id in self.epoch_manager.shard_ids(epoch_id)? {
+            if !self.shard_tracker.care_about_shard(
+                me.as_ref(),
+                block.header().prev_hash(),
+                shard_id,
+                true,
+            ) {
+                continue;
+            }
+            let shard_uid = self.epoch_manager.shard_id_to_uid(shard_id, epoch_id)?;
+            let chunk_extra = self.chain_store.get_chunk_extra(block.hash(), &shard_uid)?;
+            state_roots.push((shard_uid, *chunk_extra.state_root()));
+        }
+        self.runtime_adapter.precompile_contracts_for_next_epoch(block.hash(), state_roots)
+    }
+
     fn garbage_collect_memtrie_roots(&self, block: &Block, shard_uid: ShardUId) {
         let tries = self.runtime_adapter.get_tries();
         let last_final_block = block.header().last_final_block();
@@ -2901,6 +2935,7 @@ impl Chain {
         &self,
         block_header: &BlockHeader,
         prev_block_header: &BlockHeader,
+        congestion_info: BlockCongestionInfo,
         is_new_chunk: bool,
     ) -> Result<ApplyChunkBlockContext, Error> {
         let epoch_id = block_header.epoch_id();
@@ -2915,7 +2950,7 @@ impl Chain {
             prev_block_header.next_gas_price()
         };
 
-        Ok(ApplyChunkBlockContext::from_header(block_header, gas_price))
+        Ok(ApplyChunkBlockContext::from_header(block_header, gas_price, congestion_info))
     }
 
     fn block_catch_up_postprocess(
@@ -3375,6 +3410,7 @@ impl Chain {
             let block_context = self.get_apply_chunk_block_context(
                 block.header(),
                 prev_block.header(),
+                block.block_congestion_info(),
                 is_new_chunk,
             )?;
             let storage_context =
@@ -3522,8 +3558,14 @@ impl Chain {
                     "Resharding occurred in blocks range, it is not supported yet",
                 )));
             }
+            let congestion_info = self.get_block(&block_hash)?.block_congestion_info();
             execution_contexts.push((
-                self.get_apply_chunk_block_context(&block_header, &prev_block_header, false)?,
+                self.get_apply_chunk_block_context(
+                    &block_header,
+                    &prev_block_header,
+                    congestion_info,
+                    false,
+                )?,
                 shard_context,
             ));
         }
@@ -3680,8 +3722,14 @@ impl Chain {
             if shard_context.need_to_reshard {
                 return Ok(None);
             }
+            let congestion_info = self.get_block(&prev_chunk_block_hash)?.block_congestion_info();
             (
-                self.get_apply_chunk_block_context(&block_header, &prev_block_header, true)?,
+                self.get_apply_chunk_block_context(
+                    &block_header,
+                    &prev_block_header,
+                    congestion_info,
+                    true,
+                )?,
                 shard_context,
             )
         };
@@ -3752,6 +3800,7 @@ impl Chain {
                     apply_result.total_gas_burnt,
                     gas_limit,
                     apply_result.total_balance_burnt,
+                    apply_result.congestion_info,
                 );
                 result.push((
                     prev_chunk_block_context.block_hash,
@@ -4428,7 +4477,7 @@ impl Chain {
     ) -> HashMap<ShardId, Vec<Receipt>> {
         let mut result = HashMap::new();
         for receipt in receipts {
-            let shard_id = account_id_to_shard_id(&receipt.receiver_id, shard_layout);
+            let shard_id = receipt.receiver_shard_id(shard_layout);
             let entry = result.entry(shard_id).or_insert_with(Vec::new);
             entry.push(receipt)
         }
@@ -4448,9 +4497,14 @@ impl Chain {
             shard_layout.shard_ids().map(|shard_id| (shard_id, vec![])).collect();
         let mut cache = HashMap::new();
         for receipt in receipts {
-            let &mut shard_id = cache
-                .entry(&receipt.receiver_id)
-                .or_insert_with(|| account_id_to_shard_id(&receipt.receiver_id, shard_layout));
+            let shard_id = match &receipt.receipt {
+                ReceiptEnum::GlobalContractDistribution(_) => {
+                    receipt.receiver_shard_id(shard_layout)
+                }
+                _ => *cache
+                    .entry(&receipt.receiver_id)
+                    .or_insert_with(|| account_id_to_shard_id(&receipt.receiver_id, shard_layout)),
+            };
             // This unwrap should be safe as we pre-populated the map with all
             // valid shard ids.
             result.get_mut(&shard_id).unwrap().push(receipt);
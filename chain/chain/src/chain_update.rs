@@ -26,7 +26,7 @@ use unc_primitives::block_header::BlockHeader;
 #[cfg(feature = "new_epoch_sync")]
 use unc_primitives::epoch_manager::{block_info::BlockInfo, epoch_sync::EpochSyncInfo};
 use unc_primitives::hash::CryptoHash;
-use unc_primitives::shard_layout::{account_id_to_shard_id, account_id_to_shard_uid, ShardUId};
+use unc_primitives::shard_layout::{account_id_to_shard_uid, ShardUId};
 use unc_primitives::sharding::ShardChunk;
 use unc_primitives::state_sync::{ReceiptProofResponse, ShardStateSyncResponseHeader};
 use unc_primitives::types::chunk_extra::ChunkExtra;
@@ -136,9 +136,7 @@ impl<'a> ChainUpdate<'a> {
         let shard_layout = self.epoch_manager.get_shard_layout_from_prev_block(hash)?;
         let outgoing_receipts = outgoing_receipts
             .iter()
-            .map(|receipt| {
-                (receipt.receipt_id, account_id_to_shard_id(&receipt.receiver_id, &shard_layout))
-            })
+            .map(|receipt| (receipt.receipt_id, receipt.receiver_shard_id(&shard_layout)))
             .collect();
         Ok(outgoing_receipts)
     }
@@ -291,6 +289,10 @@ impl<'a> ChainUpdate<'a> {
                         gas_burnt,
                         gas_limit,
                         balance_burnt,
+                        // The delayed receipts are split between the children, so their
+                        // congestion info is recomputed from the state when their first
+                        // chunk is applied.
+                        None,
                     );
                     sum_gas_used += gas_burnt;
                     sum_balance_burnt += balance_burnt;
@@ -362,6 +364,7 @@ impl<'a> ChainUpdate<'a> {
                         apply_result.total_gas_burnt,
                         gas_limit,
                         apply_result.total_balance_burnt,
+                        apply_result.congestion_info,
                     ),
                 );
 
@@ -749,6 +752,13 @@ impl<'a> ChainUpdate<'a> {
         // during protocol version RestoreReceiptsAfterFixApplyChunks.
         // TODO(nikurt): Determine the value correctly.
         let is_first_block_with_chunk_of_version = false;
+        // Blocks before the sync hash may not be downloaded during state sync,
+        // in which case the other shards are considered not congested.
+        let congestion_info = self
+            .chain_store_update
+            .get_block(block_header.hash())
+            .map(|block| block.block_congestion_info())
+            .unwrap_or_default();
 
         let apply_result = self.runtime_adapter.apply_chunk(
             RuntimeStorageConfig::new(chunk_header.prev_state_root(), true),
@@ -768,6 +778,7 @@ impl<'a> ChainUpdate<'a> {
                 gas_price,
                 challenges_result: block_header.challenges_result().clone(),
                 random_seed: *block_header.random_value(),
+                congestion_info,
             },
             &receipts,
             chunk.transactions(),
@@ -798,6 +809,7 @@ impl<'a> ChainUpdate<'a> {
             apply_result.total_gas_burnt,
             gas_limit,
             apply_result.total_balance_burnt,
+            apply_result.congestion_info,
         );
         self.chain_store_update.save_chunk_extra(block_header.hash(), &shard_uid, chunk_extra);
 
@@ -848,6 +860,11 @@ impl<'a> ChainUpdate<'a> {
         let shard_uid = self.epoch_manager.shard_id_to_uid(shard_id, block_header.epoch_id())?;
         let chunk_extra =
             self.chain_store_update.get_chunk_extra(prev_block_header.hash(), &shard_uid)?;
+        let congestion_info = self
+            .chain_store_update
+            .get_block(block_header.hash())
+            .map(|block| block.block_congestion_info())
+            .unwrap_or_default();
 
         let apply_result = self.runtime_adapter.apply_chunk(
             RuntimeStorageConfig::new(*chunk_extra.state_root(), true),
@@ -859,7 +876,11 @@ impl<'a> ChainUpdate<'a> {
                 is_new_chunk: false,
                 is_first_block_with_chunk_of_version: false,
             },
-            ApplyChunkBlockContext::from_header(&block_header, prev_block_header.next_gas_price()),
+            ApplyChunkBlockContext::from_header(
+                &block_header,
+                prev_block_header.next_gas_price(),
+                congestion_info,
+            ),
             &[],
             &[],
         )?;
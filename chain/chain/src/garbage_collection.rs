@@ -978,6 +978,7 @@ impl<'a> ChainStoreUpdate<'a> {
             | DBCol::BlockHeight  // block sync needs it + genesis should be accessible
             | DBCol::_Peers
             | DBCol::RecentOutboundConnections
+            | DBCol::PeerReputations
             | DBCol::BlockMerkleTree
             | DBCol::AccountAnnouncements
             | DBCol::EpochLightClientBlocks
@@ -999,6 +1000,8 @@ impl<'a> ChainStoreUpdate<'a> {
             | DBCol::FlatStateChanges
             | DBCol::FlatStateDeltaMetadata
             | DBCol::FlatStorageStatus
+            | DBCol::FlatStateHistory
+            | DBCol::FlatStateHistoryIndex
             | DBCol::Misc
             => unreachable!(),
             #[cfg(feature = "new_epoch_sync")]
@@ -2,7 +2,10 @@ pub use block_processing_utils::{BlockProcessingArtifact, DoneApplyChunkCallback
 pub use chain::{check_known, collect_receipts, Chain};
 pub use chain_update::ChainUpdate;
 pub use doomslug::{Doomslug, DoomslugBlockProductionReadiness, DoomslugThresholdMode};
-pub use lightclient::{create_light_client_block_view, get_epoch_block_producers_view};
+pub use lightclient::{
+    create_light_client_block_view, get_epoch_block_producers_view, validate_light_client_block,
+    validate_light_client_execution_proof,
+};
 pub use store::{ChainStore, ChainStoreAccess, ChainStoreUpdate};
 pub use store_validator::{ErrorMessage, StoreValidator};
 pub use types::{Block, BlockHeader, BlockStatus, ChainGenesis, Provenance};
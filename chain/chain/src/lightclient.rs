@@ -1,10 +1,16 @@
 use unc_chain_primitives::Error;
 use unc_epoch_manager::EpochManagerAdapter;
-use unc_primitives::block::BlockHeader;
+use unc_primitives::block::{Approval, ApprovalInner, BlockHeader};
+use unc_primitives::block_header::BlockHeaderInnerLite;
 use unc_primitives::hash::{hash, CryptoHash};
-use unc_primitives::types::EpochId;
+use unc_primitives::merkle::{combine_hash, compute_root_from_path, verify_hash, MerklePath};
+use unc_primitives::types::validator_power_and_pledge::ValidatorPowerAndPledge;
+use unc_primitives::types::{Balance, EpochId};
 use unc_primitives::views::validator_power_and_pledge_view::ValidatorPowerAndPledgeView;
-use unc_primitives::views::{BlockHeaderInnerLiteView, LightClientBlockView};
+use unc_primitives::views::{
+    BlockHeaderInnerLiteView, ExecutionOutcomeWithIdView, LightClientBlockLiteView,
+    LightClientBlockView,
+};
 
 use crate::ChainStoreAccess;
 
@@ -71,3 +77,108 @@ pub fn create_light_client_block_view(
         approvals_after_next,
     })
 }
+
+/// Validates the `LightClientBlockView` received by a light client against its current head.
+///
+/// # Arguments
+///  * `head` - the last block known to the light client
+///  * `epoch_block_producers` - the ordered list of block producers in the epoch of `block`
+///  * `block` - the block to validate
+///
+/// The block has to be signed by more than 2/3 of the pledge of the block producers of its
+/// epoch. If `block` is in the epoch after the one of `head`, it has to contain the block
+/// producers of its next epoch.
+pub fn validate_light_client_block(
+    head: &LightClientBlockLiteView,
+    epoch_block_producers: &[ValidatorPowerAndPledgeView],
+    block: &LightClientBlockView,
+) -> Result<(), Error> {
+    if block.inner_lite.height <= head.inner_lite.height {
+        return Err(Error::InvalidBlockHeight(block.inner_lite.height));
+    }
+    if block.inner_lite.epoch_id != head.inner_lite.epoch_id
+        && block.inner_lite.epoch_id != head.inner_lite.next_epoch_id
+    {
+        return Err(Error::InvalidEpochHash);
+    }
+    if block.inner_lite.epoch_id == head.inner_lite.next_epoch_id && block.next_bps.is_none() {
+        return Err(Error::InvalidNextBPHash);
+    }
+    if block.approvals_after_next.len() > epoch_block_producers.len() {
+        return Err(Error::InvalidApprovals);
+    }
+
+    let current_block_hash = combine_hash(
+        &combine_hash(
+            &hash(&borsh::to_vec(&BlockHeaderInnerLite::from(block.inner_lite.clone())).unwrap()),
+            &block.inner_rest_hash,
+        ),
+        &block.prev_block_hash,
+    );
+    let next_block_hash = combine_hash(&block.next_block_inner_hash, &current_block_hash);
+    let approval_message = Approval::get_data_for_sig(
+        &ApprovalInner::Endorsement(next_block_hash),
+        block.inner_lite.height + 2,
+    );
+
+    let mut total_pledge: Balance = 0;
+    let mut approved_pledge: Balance = 0;
+    for (bp, approval) in epoch_block_producers
+        .iter()
+        .zip(block.approvals_after_next.iter().chain(std::iter::repeat(&None)))
+    {
+        let bp = ValidatorPowerAndPledge::from(bp.clone());
+        total_pledge += bp.pledge();
+        let Some(signature) = approval else { continue };
+        if !signature.verify(&approval_message, bp.public_key()) {
+            return Err(Error::InvalidSignature);
+        }
+        approved_pledge += bp.pledge();
+    }
+    if approved_pledge * 3 <= total_pledge * 2 {
+        return Err(Error::NotEnoughApprovals);
+    }
+
+    if let Some(next_bps) = &block.next_bps {
+        let next_bp_hash = CryptoHash::hash_borsh_iter(
+            next_bps.iter().map(|bp| ValidatorPowerAndPledge::from(bp.clone())),
+        );
+        if next_bp_hash != block.inner_lite.next_bp_hash {
+            return Err(Error::InvalidNextBPHash);
+        }
+    }
+    Ok(())
+}
+
+/// Validates the proof of an execution outcome against the `block_merkle_root` of a block
+/// known to the light client.
+///
+/// # Arguments
+///  * `block_merkle_root` - the block merkle root of the light client head
+///  * `outcome_proof` - the outcome together with its proof within the shard outcome root
+///  * `outcome_root_proof` - the proof of the shard outcome root within the block outcome root
+///  * `block_header_lite` - the header of the block that includes the outcome
+///  * `block_proof` - the proof of `block_header_lite` within `block_merkle_root`
+pub fn validate_light_client_execution_proof(
+    block_merkle_root: &CryptoHash,
+    outcome_proof: &ExecutionOutcomeWithIdView,
+    outcome_root_proof: &MerklePath,
+    block_header_lite: &LightClientBlockLiteView,
+    block_proof: &MerklePath,
+) -> Result<(), Error> {
+    let outcome_hash = CryptoHash::hash_borsh(outcome_proof.to_hashes());
+    let shard_outcome_root = compute_root_from_path(&outcome_proof.proof, outcome_hash);
+    let block_outcome_root =
+        compute_root_from_path(outcome_root_proof, CryptoHash::hash_borsh(shard_outcome_root));
+    if block_outcome_root != block_header_lite.inner_lite.outcome_root {
+        return Err(Error::InvalidOutcomesProof);
+    }
+    let block_hash = block_header_lite.hash();
+    if block_hash != outcome_proof.block_hash {
+        return Err(Error::InvalidOutcomesProof);
+    }
+    if !verify_hash(*block_merkle_root, block_proof, block_hash) {
+        return Err(Error::InvalidBlockMerkleRoot);
+    }
+    Ok(())
+}
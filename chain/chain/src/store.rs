@@ -16,7 +16,6 @@ use unc_primitives::errors::InvalidTxError;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::merkle::{MerklePath, PartialMerkleTree};
 use unc_primitives::receipt::Receipt;
-use unc_primitives::shard_layout::account_id_to_shard_id;
 use unc_primitives::shard_layout::{get_block_shard_uid, ShardLayout, ShardUId};
 use unc_primitives::sharding::{
     ChunkHash, EncodedShardChunk, PartialEncodedChunk, ReceiptProof, ShardChunk, ShardChunkHeader,
@@ -375,8 +374,7 @@ fn filter_incoming_receipts_for_shard(
         let mut filtered_receipts = vec![];
         let ReceiptProof(receipts, shard_proof) = receipt_proof.clone();
         for receipt in receipts {
-            let receiver_shard_id =
-                account_id_to_shard_id(&receipt.receiver_id, target_shard_layout);
+            let receiver_shard_id = receipt.receiver_shard_id(target_shard_layout);
             if receiver_shard_id == target_shard_id {
                 tracing::trace!(target: "chain", receipt_id=?receipt.receipt_id, "including receipt");
                 filtered_receipts.push(receipt);
@@ -680,9 +678,7 @@ impl ChainStore {
         shard_layout: &ShardLayout,
         shard_id: ShardId,
     ) -> Result<(), Error> {
-        receipts.retain(|receipt| {
-            account_id_to_shard_id(&receipt.receiver_id, &shard_layout) == shard_id
-        });
+        receipts.retain(|receipt| receipt.receiver_shard_id(&shard_layout) == shard_id);
         Ok(())
     }
 
@@ -350,7 +350,7 @@ mod test {
 
     use crate::Chain;
 
-    use unc_primitives::shard_layout::{account_id_to_shard_id, ShardLayout};
+    use unc_primitives::shard_layout::ShardLayout;
 
     fn naive_build_receipt_hashes(
         receipts: &[Receipt],
@@ -360,9 +360,7 @@ mod test {
         for shard_id in shard_layout.shard_ids() {
             let shard_receipts: Vec<Receipt> = receipts
                 .iter()
-                .filter(|&receipt| {
-                    account_id_to_shard_id(&receipt.receiver_id, shard_layout) == shard_id
-                })
+                .filter(|&receipt| receipt.receiver_shard_id(shard_layout) == shard_id)
                 .cloned()
                 .collect();
             receipts_hashes.push(CryptoHash::hash_borsh(ReceiptList(shard_id, &shard_receipts)));
@@ -15,9 +15,11 @@ use unc_chain_primitives::Error;
 use unc_crypto::{KeyType, PublicKey, SecretKey, Signature};
 use unc_epoch_manager::types::BlockHeaderInfo;
 use unc_epoch_manager::{EpochManagerAdapter, RngSeed};
+use unc_parameters::RuntimeConfig;
 use unc_pool::types::PoolIterator;
 use unc_primitives::account::{AccessKey, Account};
 use unc_primitives::block_header::{Approval, ApprovalInner};
+use unc_primitives::congestion_info::BlockCongestionInfo;
 use unc_primitives::epoch_manager::block_info::BlockInfo;
 use unc_primitives::epoch_manager::epoch_info::EpochInfo;
 use unc_primitives::epoch_manager::EpochConfig;
@@ -1056,6 +1058,7 @@ impl RuntimeAdapter for KeyValueRuntime {
         _shard_id: ShardId,
         _state_root: StateRoot,
         _next_block_height: BlockHeight,
+        _congestion_info: &BlockCongestionInfo,
         transactions: &mut dyn PoolIterator,
         _chain_validate: &mut dyn FnMut(&SignedTransaction) -> bool,
         _current_protocol_version: ProtocolVersion,
@@ -1227,6 +1230,7 @@ impl RuntimeAdapter for KeyValueRuntime {
             total_balance_burnt: 0,
             proof: None,
             processed_delayed_receipts: vec![],
+            congestion_info: None,
         })
     }
 
@@ -1415,6 +1419,13 @@ impl RuntimeAdapter for KeyValueRuntime {
         unreachable!("get_protocol_config should not be called in KeyValueRuntime");
     }
 
+    fn get_runtime_config(
+        &self,
+        _protocol_version: ProtocolVersion,
+    ) -> Result<RuntimeConfig, Error> {
+        Ok(RuntimeConfig::test())
+    }
+
     fn will_shard_layout_change_next_epoch(
         &self,
         _parent_hash: &CryptoHash,
@@ -1436,4 +1447,12 @@ impl RuntimeAdapter for KeyValueRuntime {
     fn load_mem_tries_on_startup(&self, _shard_uids: &[ShardUId]) -> Result<(), StorageError> {
         Ok(())
     }
+
+    fn precompile_contracts_for_next_epoch(
+        &self,
+        _block_hash: &CryptoHash,
+        _state_roots: Vec<(ShardUId, StateRoot)>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
 }
@@ -13,9 +13,11 @@ use unc_store::StorageError;
 
 use unc_chain_configs::{Genesis, ProtocolConfig};
 use unc_chain_primitives::Error;
+use unc_parameters::RuntimeConfig;
 use unc_pool::types::PoolIterator;
 use unc_primitives::challenge::ChallengesResult;
 use unc_primitives::checked_feature;
+use unc_primitives::congestion_info::{BlockCongestionInfo, CongestionInfo};
 use unc_primitives::errors::InvalidTxError;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::merkle::{merklize, MerklePath};
@@ -112,6 +114,9 @@ pub struct ApplyChunkResult {
     pub total_balance_burnt: Balance,
     pub proof: Option<PartialStorage>,
     pub processed_delayed_receipts: Vec<Receipt>,
+    /// Congestion info of the shard after applying the chunk, if congestion
+    /// control is enabled.
+    pub congestion_info: Option<CongestionInfo>,
 }
 
 impl ApplyChunkResult {
@@ -289,10 +294,16 @@ pub struct ApplyChunkBlockContext {
     pub gas_price: Balance,
     pub challenges_result: ChallengesResult,
     pub random_seed: CryptoHash,
+    /// Congestion info of all shards, taken from the chunk headers of the block.
+    pub congestion_info: BlockCongestionInfo,
 }
 
 impl ApplyChunkBlockContext {
-    pub fn from_header(header: &BlockHeader, gas_price: Balance) -> Self {
+    pub fn from_header(
+        header: &BlockHeader,
+        gas_price: Balance,
+        congestion_info: BlockCongestionInfo,
+    ) -> Self {
         Self {
             height: header.height(),
             block_hash: *header.hash(),
@@ -301,6 +312,7 @@ impl ApplyChunkBlockContext {
             gas_price,
             challenges_result: header.challenges_result().clone(),
             random_seed: *header.random_value(),
+            congestion_info,
         }
     }
 }
@@ -377,6 +389,7 @@ pub trait RuntimeAdapter: Send + Sync {
         shard_id: ShardId,
         state_root: StateRoot,
         next_block_height: BlockHeight,
+        congestion_info: &BlockCongestionInfo,
         pool_iterator: &mut dyn PoolIterator,
         chain_validate: &mut dyn FnMut(&SignedTransaction) -> bool,
         current_protocol_version: ProtocolVersion,
@@ -469,10 +482,24 @@ pub trait RuntimeAdapter: Send + Sync {
 
     fn get_protocol_config(&self, epoch_id: &EpochId) -> Result<ProtocolConfig, Error>;
 
+    /// Get the runtime config of the given protocol version.
+    fn get_runtime_config(&self, protocol_version: ProtocolVersion)
+        -> Result<RuntimeConfig, Error>;
+
     /// Loads in-memory tries upon startup. The given shard_uids are possible candidates to load,
     /// but which exact shards to load depends on configuration. This may only be called when flat
     /// storage is ready.
     fn load_mem_tries_on_startup(&self, shard_uids: &[ShardUId]) -> Result<(), StorageError>;
+
+    /// Compiles the contracts of the given states for the VM config of the epoch after the one of
+    /// `block_hash`, if it differs from the current one, so that the contracts don't all need to
+    /// be compiled on their first call after the protocol upgrade. Compilation runs in the
+    /// background.
+    fn precompile_contracts_for_next_epoch(
+        &self,
+        block_hash: &CryptoHash,
+        state_roots: Vec<(ShardUId, StateRoot)>,
+    ) -> Result<(), Error>;
 }
 
 /// The last known / checked height and time when we have processed it.
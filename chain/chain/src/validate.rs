@@ -171,6 +171,10 @@ pub fn validate_chunk_with_chunk_extra_and_receipts_root(
         return Err(Error::InvalidBalanceBurnt);
     }
 
+    if prev_chunk_extra.congestion_info() != chunk_header.congestion_info() {
+        return Err(Error::InvalidCongestionInfo);
+    }
+
     if outgoing_receipts_root != &chunk_header.prev_outgoing_receipts_root() {
         return Err(Error::InvalidReceiptsProof);
     }
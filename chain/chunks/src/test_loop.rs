@@ -279,6 +279,7 @@ impl MockChainForShardsManager {
             &receipts,
             receipts_root,
             MerkleHash::default(),
+            None,
             &signer,
             &mut rs,
             PROTOCOL_VERSION,
@@ -18,10 +18,10 @@ use unc_primitives::types::{
 use unc_primitives::views::validator_power_and_pledge_view::ValidatorPowerAndPledgeView;
 use unc_primitives::views::{
     AllMinersView, BlockView, ChunkView, DownloadStatusView, EpochValidatorInfo,
-    ExecutionOutcomeWithIdView, GasPriceView, LightClientBlockLiteView, LightClientBlockView,
-    MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView, ShardSyncDownloadView,
-    SplitStorageInfoView, StateChangesKindsView, StateChangesRequestView, StateChangesView,
-    SyncStatusView, TxStatusView,
+    ExecutionOutcomeWithIdView, FunctionCallTraceView, GasPriceView, LightClientBlockLiteView,
+    LightClientBlockView, MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView,
+    ShardSyncDownloadView, SplitStorageInfoView, StateChangesKindsView, StateChangesRequestView,
+    StateChangesView, SyncStatusView, TxStatusView,
 };
 pub use unc_primitives::views::{StatusResponse, StatusSyncInfo};
 use yansi::Color::Magenta;
@@ -642,6 +642,14 @@ pub enum QueryError {
         block_height: unc_primitives::types::BlockHeight,
         block_hash: unc_primitives::hash::CryptoHash,
     },
+    #[error(
+        "The trie of block #{block_height} is garbage collected on this node and {request_kind} cannot be served from flat state history, use an archival node to fetch historical data"
+    )]
+    UnsupportedHistoricalQuery {
+        request_kind: String,
+        block_height: unc_primitives::types::BlockHeight,
+        block_hash: unc_primitives::hash::CryptoHash,
+    },
     #[error("Block either has never been observed on the node or has been garbage collected: {block_reference:?}")]
     UnknownBlock { block_reference: unc_primitives::types::BlockReference },
     // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
@@ -922,6 +930,13 @@ pub enum GetStateChangesError {
     UnknownBlock { error_message: String },
     #[error("There are no fully synchronized blocks yet")]
     NotSyncedYet,
+    #[error(
+        "State changes of block #{block_height} are garbage collected on this node, use an archival node to fetch historical data"
+    )]
+    GarbageCollectedBlock {
+        block_height: unc_primitives::types::BlockHeight,
+        block_hash: unc_primitives::hash::CryptoHash,
+    },
     // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
     // expected cases, we cannot statically guarantee that no other errors will be returned
     // in the future.
@@ -1127,6 +1142,42 @@ impl Message for GetReceipt {
     type Result = Result<Option<ReceiptView>, GetReceiptError>;
 }
 
+/// Re-applies the chunk in which the receipt was executed with execution
+/// tracing enabled and returns the traces of its function calls.
+#[derive(Debug)]
+pub struct TraceReceipt {
+    pub receipt_id: CryptoHash,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TraceReceiptError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("Receipt with id {0} has never been executed on this node")]
+    UnknownReceipt(unc_primitives::hash::CryptoHash),
+    #[error("Receipts can't be traced on validator nodes")]
+    ValidatorNode,
+    // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
+    // expected cases, we cannot statically guarantee that no other errors will be returned
+    // in the future.
+    // TODO #3851: Remove this variant once we can exhaustively match all the underlying errors
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/utnet-org/utility/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<unc_chain_primitives::Error> for TraceReceiptError {
+    fn from(error: unc_chain_primitives::Error) -> Self {
+        match error {
+            unc_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+impl Message for TraceReceipt {
+    type Result = Result<Vec<FunctionCallTraceView>, TraceReceiptError>;
+}
+
 #[derive(Debug)]
 pub struct GetProtocolConfig(pub BlockReference);
 
@@ -1,6 +1,11 @@
 use crate::client_actor::ClientActor;
 use crate::view_client::ViewClientActor;
-use unc_network::types::{NetworkInfo, ReasonForBan, StateResponseInfo};
+use std::sync::Arc;
+use unc_client_primitives::types::{GetBlockProof, GetExecutionOutcome, GetNextLightClientBlock};
+use unc_network::types::{
+    LightClientExecutionProof, LightClientProofRequest, NetworkInfo, ReasonForBan,
+    StateResponseInfo,
+};
 use unc_o11y::WithSpanContextExt;
 use unc_primitives::block::{Approval, Block, BlockHeader};
 use unc_primitives::challenge::Challenge;
@@ -13,6 +18,7 @@ use unc_primitives::types::{AccountId, BlockHeight, EpochId, Power, ShardId};
 use unc_primitives::views::validator_power_view::ValidatorPowerView;
 use unc_primitives::views::AllMinersView;
 use unc_primitives::views::FinalExecutionOutcomeView;
+use unc_primitives::views::LightClientBlockView;
 
 /// Transaction status query
 #[derive(actix::Message, Debug)]
@@ -148,6 +154,16 @@ pub struct ChunkStateWitnessMessage(pub ChunkStateWitness);
 #[rtype(result = "()")]
 pub struct ChunkEndorsementMessage(pub ChunkEndorsement);
 
+/// Light client block received from a peer, in response to `LightClientBlockRequest`.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "Result<(),ReasonForBan>")]
+pub(crate) struct LightClientBlockResponse(pub LightClientBlockView, pub PeerId);
+
+/// Execution proof received from a peer, in response to `LightClientProofRequest`.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "Result<(),ReasonForBan>")]
+pub(crate) struct LightClientProofResponse(pub LightClientExecutionProof, pub PeerId);
+
 pub struct Adapter {
     /// Address of the client actor.
     client_addr: actix::Addr<ClientActor>,
@@ -361,4 +377,109 @@ impl unc_network::client::Client for Adapter {
             Err(err) => tracing::error!("mailbox error: {err}"),
         }
     }
+
+    async fn light_client_block_request(
+        &self,
+        last_block_hash: CryptoHash,
+    ) -> Option<Box<LightClientBlockView>> {
+        match self
+            .view_client_addr
+            .send(GetNextLightClientBlock { last_block_hash }.with_span_context())
+            .await
+        {
+            Ok(Ok(block)) => block.map(|block| Box::new(Arc::unwrap_or_clone(block))),
+            Ok(Err(err)) => {
+                tracing::debug!(target: "network", ?err, "Failed to serve light client block");
+                None
+            }
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                None
+            }
+        }
+    }
+
+    async fn light_client_block(
+        &self,
+        block: LightClientBlockView,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        match self
+            .client_addr
+            .send(LightClientBlockResponse(block, peer_id).with_span_context())
+            .await
+        {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                Ok(())
+            }
+        }
+    }
+
+    async fn light_client_proof_request(
+        &self,
+        request: LightClientProofRequest,
+    ) -> Option<Box<LightClientExecutionProof>> {
+        let outcome = match self
+            .view_client_addr
+            .send(GetExecutionOutcome { id: request.id }.with_span_context())
+            .await
+        {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(err)) => {
+                tracing::debug!(target: "network", ?err, "Failed to serve light client proof");
+                return None;
+            }
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                return None;
+            }
+        };
+        let block_proof = match self
+            .view_client_addr
+            .send(
+                GetBlockProof {
+                    block_hash: outcome.outcome_proof.block_hash,
+                    head_block_hash: request.light_client_head,
+                }
+                .with_span_context(),
+            )
+            .await
+        {
+            Ok(Ok(block_proof)) => block_proof,
+            Ok(Err(err)) => {
+                tracing::debug!(target: "network", ?err, "Failed to serve light client proof");
+                return None;
+            }
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                return None;
+            }
+        };
+        Some(Box::new(LightClientExecutionProof {
+            outcome_proof: outcome.outcome_proof,
+            outcome_root_proof: outcome.outcome_root_proof,
+            block_header_lite: block_proof.block_header_lite,
+            block_proof: block_proof.proof,
+        }))
+    }
+
+    async fn light_client_proof(
+        &self,
+        proof: LightClientExecutionProof,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        match self
+            .client_addr
+            .send(LightClientProofResponse(proof, peer_id).with_span_context())
+            .await
+        {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                Ok(())
+            }
+        }
+    }
 }
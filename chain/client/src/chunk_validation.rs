@@ -17,6 +17,7 @@ use unc_primitives::checked_feature;
 use unc_primitives::chunk_validation::{
     ChunkEndorsement, ChunkEndorsementInner, ChunkStateTransition, ChunkStateWitness,
 };
+use unc_primitives::congestion_info::BlockCongestionInfo;
 use unc_primitives::hash::{hash, CryptoHash};
 use unc_primitives::merkle::merklize;
 use unc_primitives::receipt::Receipt;
@@ -268,6 +269,7 @@ fn get_state_transition_validation_params(
         chunk: chunk.clone(),
         block: block.header().clone(),
         gas_price: prev_block_header.next_gas_price(),
+        congestion_info: block.block_congestion_info(),
         is_first_block_with_chunk_of_version,
     })
 }
@@ -276,6 +278,7 @@ struct ChunkStateTransitionValidationParams {
     chunk: ShardChunkHeader,
     block: BlockHeader,
     gas_price: u128,
+    congestion_info: BlockCongestionInfo,
     is_first_block_with_chunk_of_version: bool,
 }
 
@@ -314,7 +317,11 @@ fn validate_chunk_state_witness(
                 .prev_validator_pledge_proposals(),
             shard_id: main_transition.chunk.shard_id(),
         },
-        ApplyChunkBlockContext::from_header(&main_transition.block, main_transition.gas_price),
+        ApplyChunkBlockContext::from_header(
+            &main_transition.block,
+            main_transition.gas_price,
+            main_transition.congestion_info,
+        ),
         &pre_validation_output.receipts_to_apply,
         &state_witness.transactions,
     )?;
@@ -361,6 +368,7 @@ fn validate_chunk_state_witness(
             ApplyChunkBlockContext::from_header(
                 &transition_params.block,
                 transition_params.gas_price,
+                transition_params.congestion_info,
             ),
             &[],
             &[],
@@ -410,6 +418,7 @@ fn apply_result_to_chunk_extra(
         apply_result.total_gas_burnt,
         chunk.gas_limit(),
         apply_result.total_balance_burnt,
+        apply_result.congestion_info,
     )
 }
 
@@ -64,8 +64,12 @@ use unc_pool::InsertTransactionResult;
 use unc_primitives::block::{Approval, ApprovalInner, ApprovalMessage, Block, BlockHeader, Tip};
 use unc_primitives::block_header::ApprovalType;
 use unc_primitives::challenge::{Challenge, ChallengeBody};
+use unc_primitives::checked_feature;
+use unc_primitives::congestion_info::{
+    BlockCongestionInfo, CongestionControl, RejectTransactionReason, ShardAcceptsTransactions,
+};
 use unc_primitives::epoch_manager::RngSeed;
-use unc_primitives::errors::EpochError;
+use unc_primitives::errors::{EpochError, InvalidTxError};
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::merkle::{merklize, MerklePath, PartialMerkleTree};
 use unc_primitives::network::PeerId;
@@ -83,7 +87,7 @@ use unc_primitives::types::{ApprovalPledge, Gas};
 use unc_primitives::unwrap_or_return;
 use unc_primitives::utils::MaybeValidated;
 use unc_primitives::validator_signer::ValidatorSigner;
-use unc_primitives::version::PROTOCOL_VERSION;
+use unc_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use unc_primitives::views::{CatchupStatusView, DroppedReason};
 use unc_store::metadata::DbKind;
 use unc_store::ShardUId;
@@ -857,12 +861,13 @@ impl Client {
             .get_chunk_extra(&prev_block_hash, &shard_uid)
             .map_err(|err| Error::ChunkProducer(format!("No chunk extra available: {}", err)))?;
 
-        let prev_block_header = self.chain.get_block_header(&prev_block_hash)?;
+        let prev_block = self.chain.get_block(&prev_block_hash)?;
         let transactions = self.prepare_transactions(
             shard_uid,
             chunk_extra.gas_limit(),
             *chunk_extra.state_root(),
-            &prev_block_header,
+            prev_block.header(),
+            &prev_block.block_congestion_info(),
         )?;
         #[cfg(feature = "test_features")]
         let transactions = Self::maybe_insert_invalid_transaction(
@@ -898,6 +903,7 @@ impl Client {
             &outgoing_receipts,
             outgoing_receipts_root,
             tx_root,
+            chunk_extra.congestion_info(),
             &*validator_signer,
             &mut self.rs_for_chunk_production,
             protocol_version,
@@ -973,6 +979,7 @@ impl Client {
         gas_limit: Gas,
         state_root: StateRoot,
         prev_block_header: &BlockHeader,
+        congestion_info: &BlockCongestionInfo,
     ) -> Result<Vec<SignedTransaction>, Error> {
         let Self { chain, sharded_tx_pool, epoch_manager, runtime_adapter: runtime, .. } = self;
 
@@ -992,6 +999,7 @@ impl Client {
                 // passing it will result in a more conservative check and will not accidentally allow
                 // invalid transactions to be included.
                 prev_block_header.height() + 1,
+                congestion_info,
                 &mut iter,
                 &mut |tx: &SignedTransaction| -> bool {
                     chain
@@ -2143,6 +2151,47 @@ impl Client {
         Ok(())
     }
 
+    /// Rejects transactions to shards that are too congested, according to the congestion info
+    /// of the head block. Chunk producers won't include such transactions anyway.
+    fn check_receiver_congestion(
+        &self,
+        tx: &SignedTransaction,
+        head: &Tip,
+        epoch_id: &EpochId,
+        protocol_version: ProtocolVersion,
+    ) -> Result<Option<InvalidTxError>, Error> {
+        if !checked_feature!("stable", CongestionControl, protocol_version) {
+            return Ok(None);
+        }
+        let receiver_shard =
+            self.epoch_manager.account_id_to_shard_id(&tx.transaction.receiver_id, epoch_id)?;
+        let block_congestion_info =
+            self.chain.get_block(&head.last_block_hash)?.block_congestion_info();
+        let Some(info) = block_congestion_info.get(&receiver_shard) else {
+            return Ok(None);
+        };
+        let runtime_config = self.runtime_adapter.get_runtime_config(protocol_version)?;
+        let congestion_control = CongestionControl::new(
+            runtime_config.congestion_control_config,
+            info.congestion_info,
+            info.missed_chunks_count,
+        );
+        let shard_id = receiver_shard as u32;
+        let err = match congestion_control.shard_accepts_transactions() {
+            ShardAcceptsTransactions::Yes => return Ok(None),
+            ShardAcceptsTransactions::No(RejectTransactionReason::Congested {
+                congestion_level,
+            }) => InvalidTxError::ShardCongested {
+                shard_id,
+                congestion_level_per_mille: (congestion_level * 1000.0) as u16,
+            },
+            ShardAcceptsTransactions::No(RejectTransactionReason::MissedChunks {
+                missed_chunks,
+            }) => InvalidTxError::ShardStuck { shard_id, missed_chunks },
+        };
+        Ok(Some(err))
+    }
+
     /// Process transaction and either add it to the mempool or return to redirect to another validator.
     fn process_tx_internal(
         &mut self,
@@ -2179,6 +2228,11 @@ impl Client {
             return Ok(ProcessTxResponse::InvalidTx(err));
         }
 
+        if let Some(err) = self.check_receiver_congestion(tx, &head, &epoch_id, protocol_version)? {
+            debug!(target: "client", tx_hash = ?tx.get_hash(), ?err, "Invalid tx: receiver shard is congested");
+            return Ok(ProcessTxResponse::InvalidTx(err));
+        }
+
         let shard_id =
             self.epoch_manager.account_id_to_shard_id(&tx.transaction.signer_id, &epoch_id)?;
         let care_about_shard =
@@ -2630,8 +2684,9 @@ impl Client {
     /// check_And_update_doomslug_tip, but that would require a bigger refactor.
     pub(crate) fn send_network_chain_info(&mut self) -> Result<(), Error> {
         let tip = self.chain.head()?;
-        // convert tracked shards, runtime will track all shards
-        let tracked_shards = self.epoch_manager.shard_ids(&tip.epoch_id)?;
+        // convert tracked shards, runtime will track all shards unless the node is light
+        let tracked_shards =
+            if self.config.light { vec![] } else { self.epoch_manager.shard_ids(&tip.epoch_id)? };
         let tier1_accounts = self.get_tier1_accounts(&tip)?;
         let block = self.chain.get_block(&tip.last_block_hash)?;
         self.network_adapter.send(SetChainInfo(ChainInfo {
@@ -7,8 +7,8 @@
 
 use crate::adapter::{
     BlockApproval, BlockHeadersResponse, BlockResponse, ChunkEndorsementMessage,
-    ChunkStateWitnessMessage, ProcessTxRequest, ProcessTxResponse, RecvChallenge, SetNetworkInfo,
-    StateResponse,
+    ChunkStateWitnessMessage, LightClientBlockResponse, LightClientProofResponse, ProcessTxRequest,
+    ProcessTxResponse, RecvChallenge, SetNetworkInfo, StateResponse,
 };
 #[cfg(feature = "test_features")]
 use crate::client::AdvProduceBlocksMode;
@@ -16,6 +16,9 @@ use crate::client::{Client, EPOCH_START_INFO_BLOCKS};
 use crate::config_updater::ConfigUpdater;
 use crate::debug::new_network_info_view;
 use crate::info::{display_sync_status, InfoHelper};
+use crate::light_client::{
+    GetLightClientExecutionProof, GetLightClientExecutionProofError, LightClient,
+};
 use crate::sync::adapter::{SyncMessage, SyncShardInfo};
 use crate::sync::state::{StateSync, StateSyncResult};
 use crate::sync_jobs_actor::{create_sync_job_scheduler, SyncJobsActor};
@@ -60,7 +63,8 @@ use unc_epoch_manager::shard_tracker::ShardTracker;
 use unc_epoch_manager::EpochManagerAdapter;
 use unc_network::types::ReasonForBan;
 use unc_network::types::{
-    NetworkInfo, NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest,
+    LightClientExecutionProof, NetworkInfo, NetworkRequests, PeerManagerAdapter,
+    PeerManagerMessageRequest,
 };
 use unc_o11y::{handler_debug_span, OpenTelemetrySpanExt, WithSpanContext, WithSpanContextExt};
 use unc_performance_metrics;
@@ -129,6 +133,10 @@ pub struct ClientActor {
 
     /// Manages updating the config.
     config_updater: Option<ConfigUpdater>,
+
+    /// Set if the node runs in the light mode. Light node follows the light client blocks
+    /// received from its peers instead of syncing the chain.
+    light_client: Option<LightClient>,
 }
 
 /// Blocks the program until given genesis time arrives.
@@ -179,6 +187,12 @@ impl ClientActor {
             info!(target: "client", "Starting validator node: {}", vs.validator_id());
         }
         let info_helper = InfoHelper::new(Some(telemetry_actor), &config, validator_signer.clone());
+        let light_client = if config.light {
+            info!(target: "client", "Starting light node");
+            Some(LightClient::new(client.chain.genesis(), client.epoch_manager.as_ref())?)
+        } else {
+            None
+        };
 
         let now = Utc::now();
         Ok(ClientActor {
@@ -222,6 +236,7 @@ impl ClientActor {
             fastforward_delta: 0,
             shutdown_signal,
             config_updater,
+            light_client,
         })
     }
 }
@@ -423,6 +438,10 @@ impl Handler<WithSpanContext<BlockResponse>> for ClientActor {
     fn handle(&mut self, msg: WithSpanContext<BlockResponse>, ctx: &mut Context<Self>) {
         self.wrap(msg, ctx, "BlockResponse", |this: &mut Self, msg|{
             let BlockResponse{ block, peer_id, was_requested } = msg;
+            if this.light_client.is_some() {
+                // Light node doesn't process the blocks.
+                return;
+            }
             info!(target: "client", block_height = block.header().height(), block_hash = ?block.header().hash(), "BlockResponse");
             let blocks_at_height = this
                 .client
@@ -473,6 +492,9 @@ impl Handler<WithSpanContext<BlockHeadersResponse>> for ClientActor {
     ) -> Self::Result {
         self.wrap(msg, ctx, "BlockHeadersResponse", |this, msg| {
             let BlockHeadersResponse(headers, peer_id) = msg;
+            if this.light_client.is_some() {
+                return Ok(());
+            }
             if this.receive_headers(headers, peer_id) {
                 Ok(())
             } else {
@@ -1137,7 +1159,19 @@ impl ClientActor {
         let now = Utc::now();
 
         let timer = metrics::CHECK_TRIGGERS_TIME.start_timer();
-        if self.sync_started {
+        if self.sync_started && self.light_client.is_some() {
+            self.sync_timer_next_attempt = self.run_timer(
+                self.client.config.sync_check_period,
+                self.sync_timer_next_attempt,
+                ctx,
+                |act, _| act.request_light_client_block(),
+                "light_client_sync",
+            );
+            delay = std::cmp::min(
+                delay,
+                self.sync_timer_next_attempt.signed_duration_since(now).to_std().unwrap_or(delay),
+            );
+        } else if self.sync_started {
             self.sync_timer_next_attempt = self.run_timer(
                 self.sync_wait_period(),
                 self.sync_timer_next_attempt,
@@ -1575,6 +1609,22 @@ impl ClientActor {
         }
     }
 
+    /// Requests the light client block following the light client head from a random peer.
+    /// Light peers are not included in `highest_height_peers`, since they don't serve blocks.
+    fn request_light_client_block(&mut self) {
+        let Some(light_client) = &self.light_client else { return };
+        let Some(peer) = self.network_info.highest_height_peers.choose(&mut thread_rng()) else {
+            debug!(target: "client", "No peers to request the light client block from");
+            return;
+        };
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::LightClientBlockRequest {
+                last_block_hash: light_client.head().hash(),
+                peer_id: peer.peer_info.id.clone(),
+            },
+        ));
+    }
+
     /// Main syncing job responsible for syncing client with other peers.
     /// Runs itself iff it was not ran as reaction for message with results of
     /// finishing state part job
@@ -2027,6 +2077,89 @@ impl Handler<WithSpanContext<ChunkEndorsementMessage>> for ClientActor {
     }
 }
 
+impl Handler<WithSpanContext<LightClientBlockResponse>> for ClientActor {
+    type Result = Result<(), ReasonForBan>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<LightClientBlockResponse>,
+        ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        self.wrap(msg, ctx, "LightClientBlockResponse", |this, msg| {
+            let LightClientBlockResponse(block, peer_id) = msg;
+            let Some(light_client) = &mut this.light_client else { return Ok(()) };
+            match light_client.apply_block(block) {
+                Ok(true) => {
+                    let head = light_client.head();
+                    info!(target: "client", height = head.inner_lite.height, hash = ?head.hash(), "Light client head updated");
+                    // Request the next block right away, the node is likely behind.
+                    this.sync_timer_next_attempt = Utc::now();
+                    Ok(())
+                }
+                Ok(false) => Ok(()),
+                Err(err) => {
+                    warn!(target: "client", ?err, ?peer_id, "Banning node for sending invalid light client block");
+                    Err(ReasonForBan::BadBlockHeader)
+                }
+            }
+        })
+    }
+}
+
+impl Handler<WithSpanContext<LightClientProofResponse>> for ClientActor {
+    type Result = Result<(), ReasonForBan>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<LightClientProofResponse>,
+        ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        self.wrap(msg, ctx, "LightClientProofResponse", |this, msg| {
+            let LightClientProofResponse(proof, peer_id) = msg;
+            let Some(light_client) = &mut this.light_client else { return Ok(()) };
+            light_client.apply_proof(proof).map_err(|err| {
+                warn!(target: "client", ?err, ?peer_id, "Banning node for sending invalid execution proof");
+                ReasonForBan::Abusive
+            })
+        })
+    }
+}
+
+impl Handler<WithSpanContext<GetLightClientExecutionProof>> for ClientActor {
+    type Result = Result<Option<LightClientExecutionProof>, GetLightClientExecutionProofError>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<GetLightClientExecutionProof>,
+        ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        self.wrap(msg, ctx, "GetLightClientExecutionProof", |this, msg| {
+            let light_client = this
+                .light_client
+                .as_mut()
+                .ok_or(GetLightClientExecutionProofError::NotLightNode)?;
+            if let Some(proof) = light_client.get_proof(&msg.id) {
+                return Ok(Some(proof));
+            }
+            let peer = this
+                .network_info
+                .highest_height_peers
+                .choose(&mut thread_rng())
+                .ok_or(GetLightClientExecutionProofError::NoPeers)?;
+            this.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::LightClientProofRequest {
+                    request: light_client.proof_request(msg.id),
+                    peer_id: peer.peer_info.id.clone(),
+                },
+            ));
+            Ok(None)
+        })
+    }
+}
+
 /// Returns random seed sampled from the current thread
 pub fn random_seed_from_thread() -> RngSeed {
     let mut rng_seed: RngSeed = [0; 32];
@@ -5,7 +5,7 @@ pub use unc_client_primitives::types::{
     GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetSplitStorageInfo, GetStateChanges,
     GetStateChangesInBlock, GetStateChangesWithCauseInBlock,
     GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfo, GetValidatorOrdered, Query,
-    QueryError, Status, StatusResponse, SyncStatus, TxStatus, TxStatusError,
+    QueryError, Status, StatusResponse, SyncStatus, TraceReceipt, TxStatus, TxStatusError,
 };
 
 pub use crate::adapter::{
@@ -16,6 +16,9 @@ pub use crate::client::Client;
 pub use crate::client_actor::NetworkAdversarialMessage;
 pub use crate::client_actor::{start_client, ClientActor};
 pub use crate::config_updater::ConfigUpdater;
+pub use crate::light_client::{
+    GetLightClientExecutionProof, GetLightClientExecutionProofError, LightClient,
+};
 pub use crate::sync::adapter::{SyncAdapter, SyncMessage};
 pub use crate::view_client::{start_view_client, ViewClientActor};
 pub use unc_client_primitives::debug::DebugStatus;
@@ -28,6 +31,7 @@ mod client_actor;
 mod config_updater;
 pub mod debug;
 mod info;
+mod light_client;
 mod metrics;
 pub mod sync;
 mod sync_jobs_actor;
@@ -0,0 +1,155 @@
+//! State of a node running in the light mode.
+//!
+//! A light node doesn't sync the chain. Instead it follows the light client blocks
+//! served by its peers, verifying each of them against the block producers known from
+//! the previous ones, starting from genesis. Execution outcomes are requested on demand
+//! and verified against the block merkle root of the light client head.
+use lru::LruCache;
+use std::collections::HashMap;
+use unc_chain::{
+    get_epoch_block_producers_view, validate_light_client_block,
+    validate_light_client_execution_proof, Error,
+};
+use unc_epoch_manager::EpochManagerAdapter;
+use unc_network::types::{LightClientExecutionProof, LightClientProofRequest};
+use unc_primitives::block::BlockHeader;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::types::{EpochId, TransactionOrReceiptId};
+use unc_primitives::views::validator_power_and_pledge_view::ValidatorPowerAndPledgeView;
+use unc_primitives::views::{LightClientBlockLiteView, LightClientBlockView};
+
+/// Number of verified execution proofs kept until they are queried.
+const VERIFIED_PROOFS_CACHE_SIZE: usize = 1000;
+/// Number of requested proofs awaited at once. The least recently requested ones
+/// are dropped, and their responses are ignored.
+const PENDING_PROOFS_CACHE_SIZE: usize = 1000;
+
+/// Query for the verified proof of an execution outcome. If the proof is not known yet,
+/// it is requested from the peers and `None` is returned, so the caller should poll
+/// until the proof arrives.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "Result<Option<LightClientExecutionProof>, GetLightClientExecutionProofError>")]
+pub struct GetLightClientExecutionProof {
+    pub id: TransactionOrReceiptId,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetLightClientExecutionProofError {
+    #[error("Node doesn't run in the light mode")]
+    NotLightNode,
+    #[error("No peers to request the proof from")]
+    NoPeers,
+}
+
+/// Block merkle root and hash of the light client head a proof was requested against.
+struct PendingProof {
+    block_merkle_root: CryptoHash,
+    light_client_head: CryptoHash,
+}
+
+pub struct LightClient {
+    /// The last verified block.
+    head: LightClientBlockLiteView,
+    /// Ordered block producers of the epoch of the head and of the next one.
+    epoch_block_producers: HashMap<EpochId, Vec<ValidatorPowerAndPledgeView>>,
+    /// Requested proofs, by the id of the transaction or receipt.
+    pending_proofs: LruCache<CryptoHash, PendingProof>,
+    /// Verified proofs, by the id of the transaction or receipt.
+    verified_proofs: LruCache<CryptoHash, LightClientExecutionProof>,
+}
+
+impl LightClient {
+    pub fn new(
+        genesis: &BlockHeader,
+        epoch_manager: &dyn EpochManagerAdapter,
+    ) -> Result<Self, Error> {
+        let mut epoch_block_producers = HashMap::new();
+        for epoch_id in [genesis.epoch_id(), genesis.next_epoch_id()] {
+            let bps = get_epoch_block_producers_view(epoch_id, genesis.hash(), epoch_manager)?;
+            epoch_block_producers.insert(epoch_id.clone(), bps);
+        }
+        Ok(Self {
+            head: genesis.clone().into(),
+            epoch_block_producers,
+            pending_proofs: LruCache::new(PENDING_PROOFS_CACHE_SIZE),
+            verified_proofs: LruCache::new(VERIFIED_PROOFS_CACHE_SIZE),
+        })
+    }
+
+    pub fn head(&self) -> &LightClientBlockLiteView {
+        &self.head
+    }
+
+    /// Verifies the block and moves the head to it.
+    /// Returns false if the block is not newer than the head, which may happen if the
+    /// same block was received from several peers.
+    pub fn apply_block(&mut self, block: LightClientBlockView) -> Result<bool, Error> {
+        if block.inner_lite.height <= self.head.inner_lite.height {
+            return Ok(false);
+        }
+        let epoch_id = EpochId(block.inner_lite.epoch_id);
+        let bps = self.epoch_block_producers.get(&epoch_id).ok_or(Error::InvalidEpochHash)?;
+        validate_light_client_block(&self.head, bps, &block)?;
+
+        let next_epoch_id = EpochId(block.inner_lite.next_epoch_id);
+        if let Some(next_bps) = block.next_bps {
+            self.epoch_block_producers.insert(next_epoch_id.clone(), next_bps);
+        }
+        self.epoch_block_producers.retain(|id, _| id == &epoch_id || id == &next_epoch_id);
+        self.head = LightClientBlockLiteView {
+            prev_block_hash: block.prev_block_hash,
+            inner_rest_hash: block.inner_rest_hash,
+            inner_lite: block.inner_lite,
+        };
+        Ok(true)
+    }
+
+    /// Returns the verified proof of the outcome, if known.
+    pub fn get_proof(&mut self, id: &TransactionOrReceiptId) -> Option<LightClientExecutionProof> {
+        self.verified_proofs.get(outcome_id(id)).cloned()
+    }
+
+    /// Makes the request for the proof of the outcome against the current head.
+    /// A proof which is already pending is requested again against the same head, so that
+    /// the response to the earlier request is still accepted once the head has moved.
+    pub fn proof_request(&mut self, id: TransactionOrReceiptId) -> LightClientProofRequest {
+        let light_client_head = match self.pending_proofs.get(outcome_id(&id)) {
+            Some(pending) => pending.light_client_head,
+            None => {
+                let light_client_head = self.head.hash();
+                self.pending_proofs.put(
+                    *outcome_id(&id),
+                    PendingProof {
+                        block_merkle_root: self.head.inner_lite.block_merkle_root,
+                        light_client_head,
+                    },
+                );
+                light_client_head
+            }
+        };
+        LightClientProofRequest { id, light_client_head }
+    }
+
+    /// Verifies the received proof. Proofs which were not requested are ignored.
+    pub fn apply_proof(&mut self, proof: LightClientExecutionProof) -> Result<(), Error> {
+        let id = proof.outcome_proof.id;
+        let Some(pending) = self.pending_proofs.peek(&id) else { return Ok(()) };
+        validate_light_client_execution_proof(
+            &pending.block_merkle_root,
+            &proof.outcome_proof,
+            &proof.outcome_root_proof,
+            &proof.block_header_lite,
+            &proof.block_proof,
+        )?;
+        self.pending_proofs.pop(&id);
+        self.verified_proofs.put(id, proof);
+        Ok(())
+    }
+}
+
+fn outcome_id(id: &TransactionOrReceiptId) -> &CryptoHash {
+    match id {
+        TransactionOrReceiptId::Transaction { transaction_hash, .. } => transaction_hash,
+        TransactionOrReceiptId::Receipt { receipt_id, .. } => receipt_id,
+    }
+}
@@ -486,6 +486,7 @@ mod test {
                 },
                 tracked_shards: vec![],
                 archival: false,
+                light: false,
                 last_block: Some(BlockInfo {
                     height: chain2.head().unwrap().height,
                     hash: chain2.head().unwrap().last_block_hash,
@@ -586,6 +587,7 @@ mod test {
                 },
                 tracked_shards: vec![],
                 archival: false,
+                light: false,
                 last_block: Some(BlockInfo {
                     height: chain2.head().unwrap().height,
                     hash: chain2.head().unwrap().last_block_hash,
@@ -812,6 +814,7 @@ mod test {
                 },
                 tracked_shards: vec![],
                 archival: false,
+                light: false,
                 last_block: Some(BlockInfo {
                     height: chain2.head().unwrap().height,
                     hash: chain2.head().unwrap().last_block_hash,
@@ -169,6 +169,7 @@ pub fn create_chunk(
             transactions,
             decoded_chunk.prev_outgoing_receipts(),
             header.prev_outgoing_receipts_root(),
+            header.congestion_info(),
             &*signer,
             PROTOCOL_VERSION,
         )
@@ -515,6 +515,7 @@ pub fn setup_mock_all_validators(
                                         }),
                                         tracked_shards: vec![0, 1, 2, 3],
                                         archival: true,
+                                        light: false,
                                     },
                                 },
                                 received_bytes_per_sec: 0,
@@ -826,6 +827,8 @@ pub fn setup_mock_all_validators(
                         | NetworkRequests::BanPeer { .. }
                         | NetworkRequests::TxStatus(_, _, _)
                         | NetworkRequests::SnapshotHostInfo { .. }
+                        | NetworkRequests::LightClientBlockRequest { .. }
+                        | NetworkRequests::LightClientProofRequest { .. }
                         | NetworkRequests::Challenge(_) => {}
                         NetworkRequests::ChunkStateWitness(_, _) => {
                             // TODO(#10265): Implement for integration tests.
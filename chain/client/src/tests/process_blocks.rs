@@ -77,6 +77,7 @@ fn test_bad_shard_id() {
         chunk.tx_root(),
         chunk.prev_validator_proposals().collect(),
         vec![],
+        chunk.congestion_info(),
         &validator_signer,
     );
     modified_chunk.height_included = 2;
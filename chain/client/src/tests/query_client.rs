@@ -9,11 +9,12 @@ use unc_primitives::test_utils::create_test_signer;
 use crate::adapter::{BlockResponse, ProcessTxRequest, ProcessTxResponse, StateRequestHeader};
 use crate::test_utils::{setup_mock_all_validators, setup_no_network, setup_only_view};
 use crate::{
-    GetBlock, GetBlockWithMerkleTree, GetExecutionOutcomesForBlock, Query, QueryError, Status,
-    TxStatus,
+    GetBlock, GetBlockWithMerkleTree, GetExecutionOutcomesForBlock, GetStateChangesInBlock, Query,
+    QueryError, Status, TxStatus,
 };
 use unc_actix_test_utils::run_actix;
 use unc_chain_configs::DEFAULT_GC_NUM_EPOCHS_TO_KEEP;
+use unc_client_primitives::types::GetStateChangesError;
 use unc_crypto::{InMemorySigner, KeyType};
 use unc_network::test_utils::MockPeerManagerAdapter;
 use unc_network::types::PeerInfo;
@@ -354,6 +355,37 @@ fn test_garbage_collection() {
                                     }),
                             ));
 
+                            // State changes of garbage collected blocks are reported as
+                            // such instead of being silently empty.
+                            let view_client = view_client_non_archival.clone();
+                            tests.push(actix::spawn(
+                                view_client_archival
+                                    .send(
+                                        GetBlock(BlockReference::BlockId(BlockId::Height(1)))
+                                            .with_span_context(),
+                                    )
+                                    .then(move |res| {
+                                        let block_hash = res.unwrap().unwrap().header.hash;
+                                        view_client.send(
+                                            GetStateChangesInBlock { block_hash }
+                                                .with_span_context(),
+                                        )
+                                    })
+                                    .then(move |res| {
+                                        match res.unwrap() {
+                                            Err(err) => assert!(matches!(
+                                                err,
+                                                GetStateChangesError::GarbageCollectedBlock {
+                                                    block_height: 1,
+                                                    ..
+                                                }
+                                            )),
+                                            Ok(_) => panic!("Unexpected Ok variant"),
+                                        }
+                                        futures::future::ready(())
+                                    }),
+                            ));
+
                             // On archival node old data is _not_ garbage collected.
                             tests.push(actix::spawn(
                                 view_client_archival
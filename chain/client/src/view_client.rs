@@ -17,7 +17,11 @@ use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
 use unc_async::messaging::CanSend;
-use unc_chain::types::{RuntimeAdapter, Tip};
+use unc_chain::chain::collect_receipts_from_response;
+use unc_chain::migrations::check_if_block_is_first_with_chunk_of_version;
+use unc_chain::types::{
+    ApplyChunkBlockContext, ApplyChunkShardContext, RuntimeAdapter, RuntimeStorageConfig, Tip,
+};
 use unc_chain::{
     get_epoch_block_producers_view, Chain, ChainGenesis, ChainStoreAccess, DoomslugThresholdMode,
 };
@@ -32,7 +36,7 @@ use unc_client_primitives::types::{
     GetProviderError, GetReceipt, GetReceiptError, GetSplitStorageInfo, GetSplitStorageInfoError,
     GetStateChangesError, GetStateChangesWithCauseInBlock,
     GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfoError, Query, QueryError,
-    TxStatus, TxStatusError,
+    TraceReceipt, TraceReceiptError, TxStatus, TxStatusError,
 };
 use unc_epoch_manager::shard_tracker::ShardTracker;
 use unc_epoch_manager::EpochManagerAdapter;
@@ -48,6 +52,7 @@ use unc_primitives::hash::CryptoHash;
 use unc_primitives::merkle::{merklize, PartialMerkleTree};
 use unc_primitives::network::AnnounceAccount;
 use unc_primitives::receipt::Receipt;
+use unc_primitives::shard_layout::ShardUId;
 use unc_primitives::sharding::ShardChunk;
 use unc_primitives::state_sync::{
     ShardStateSyncResponse, ShardStateSyncResponseHeader, ShardStateSyncResponseV3,
@@ -62,13 +67,13 @@ use unc_primitives::views::validator_power_and_pledge_view::ValidatorPowerAndPle
 use unc_primitives::views::{
     AllMinersView, BlockView, ChunkView, EpochValidatorInfo, ExecutionOutcomeWithIdView,
     ExecutionStatusView, FinalExecutionOutcomeView, FinalExecutionOutcomeViewEnum,
-    FinalExecutionStatus, GasPriceView, LightClientBlockView, MaintenanceWindowsView, QueryRequest,
-    QueryResponse, ReceiptView, SignedTransactionView, SplitStorageInfoView, StateChangesKindsView,
-    StateChangesView, TxExecutionStatus, TxStatusView,
+    FinalExecutionStatus, FunctionCallTraceView, GasPriceView, LightClientBlockView,
+    MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView, SignedTransactionView,
+    SplitStorageInfoView, StateChangesKindsView, StateChangesView, TxExecutionStatus, TxStatusView,
 };
 
 use unc_store::flat::{FlatStorageReadyStatus, FlatStorageStatus};
-use unc_store::{DBCol, COLD_HEAD_KEY, FINAL_HEAD_KEY, HEAD_KEY};
+use unc_store::{DBCol, Trie, COLD_HEAD_KEY, FINAL_HEAD_KEY, HEAD_KEY};
 
 /// Max number of queries that we keep.
 const QUERY_REQUEST_LIMIT: usize = 500;
@@ -309,6 +314,45 @@ impl ViewClientActor {
         Ok(windows)
     }
 
+    /// Whether queries at a block without chunk extra can be served from flat
+    /// state history. This is only the case for canonical blocks, as history
+    /// is not kept for forks.
+    fn is_served_from_flat_state_history(&self, header: &BlockHeader, shard_uid: ShardUId) -> bool {
+        if !self
+            .runtime
+            .get_flat_storage_manager()
+            .history_covers_height(shard_uid, header.height())
+        {
+            return false;
+        }
+        self.chain
+            .get_block_hash_by_height(header.height())
+            .map_or(false, |hash| &hash == header.hash())
+    }
+
+    /// State changes are removed together with the rest of the block data, so
+    /// reading them for a garbage collected block would silently return
+    /// nothing. They are not served from flat state history, which keeps
+    /// neither the causes of changes nor the changes of forks. Archival nodes
+    /// read them from `DBCol::StateChanges` without touching the trie.
+    fn check_state_changes_available(
+        &self,
+        block_hash: &CryptoHash,
+    ) -> Result<(), GetStateChangesError> {
+        if self.config.archive {
+            return Ok(());
+        }
+        let header = self.chain.get_block_header(block_hash)?;
+        let tip = self.chain.head()?;
+        if header.height() < self.runtime.get_gc_stop_height(&tip.last_block_hash) {
+            return Err(GetStateChangesError::GarbageCollectedBlock {
+                block_height: header.height(),
+                block_hash: *block_hash,
+            });
+        }
+        Ok(())
+    }
+
     fn handle_query(&mut self, msg: Query) -> Result<QueryResponse, QueryError> {
         let header = self.get_block_header_by_reference(&msg.block_reference);
         let header = match header {
@@ -342,32 +386,46 @@ impl ViewClientActor {
             .map_err(|err| QueryError::InternalError { error_message: err.to_string() })?;
 
         let tip = self.chain.head();
-        let chunk_extra =
-            self.chain.get_chunk_extra(header.hash(), &shard_uid).map_err(|err| match err {
-                unc_chain::unc_chain_primitives::Error::DBNotFoundErr(_) => match tip {
-                    Ok(tip) => {
-                        let gc_stop_height = self.runtime.get_gc_stop_height(&tip.last_block_hash);
-                        if !self.config.archive && header.height() < gc_stop_height {
-                            QueryError::GarbageCollectedBlock {
-                                block_height: header.height(),
-                                block_hash: *header.hash(),
+        let chunk_extra = self.chain.get_chunk_extra(header.hash(), &shard_uid);
+        let state_root = match chunk_extra {
+            Ok(chunk_extra) => *chunk_extra.state_root(),
+            // The chunk extra of a garbage collected block is gone, but the
+            // state can still be read from flat state history. The state root
+            // is unknown, which the runtime rejects only for queries that need
+            // proofs.
+            Err(unc_chain::unc_chain_primitives::Error::DBNotFoundErr(_))
+                if self.is_served_from_flat_state_history(&header, shard_uid) =>
+            {
+                Trie::EMPTY_ROOT
+            }
+            Err(err) => {
+                return Err(match err {
+                    unc_chain::unc_chain_primitives::Error::DBNotFoundErr(_) => match tip {
+                        Ok(tip) => {
+                            let gc_stop_height =
+                                self.runtime.get_gc_stop_height(&tip.last_block_hash);
+                            if !self.config.archive && header.height() < gc_stop_height {
+                                QueryError::GarbageCollectedBlock {
+                                    block_height: header.height(),
+                                    block_hash: *header.hash(),
+                                }
+                            } else {
+                                QueryError::UnavailableShard { requested_shard_id: shard_id }
                             }
-                        } else {
-                            QueryError::UnavailableShard { requested_shard_id: shard_id }
                         }
+                        Err(err) => QueryError::InternalError { error_message: err.to_string() },
+                    },
+                    unc_chain::unc_chain_primitives::Error::IOErr(error) => {
+                        QueryError::InternalError { error_message: error.to_string() }
                     }
-                    Err(err) => QueryError::InternalError { error_message: err.to_string() },
-                },
-                unc_chain::unc_chain_primitives::Error::IOErr(error) => {
-                    QueryError::InternalError { error_message: error.to_string() }
-                }
-                _ => QueryError::Unreachable { error_message: err.to_string() },
-            })?;
+                    _ => QueryError::Unreachable { error_message: err.to_string() },
+                })
+            }
+        };
 
-        let state_root = chunk_extra.state_root();
         match self.runtime.query(
             shard_uid,
-            state_root,
+            &state_root,
             header.height(),
             header.raw_timestamp(),
             header.prev_hash(),
@@ -424,6 +482,15 @@ impl ViewClientActor {
                     block_height,
                     block_hash,
                 },
+                unc_chain::unc_chain_primitives::error::QueryError::UnsupportedHistoricalQuery {
+                    request_kind,
+                    block_height,
+                    block_hash,
+                } => QueryError::UnsupportedHistoricalQuery {
+                    request_kind,
+                    block_height,
+                    block_hash,
+                },
             }),
         }
     }
@@ -644,6 +711,76 @@ impl ViewClientActor {
             _ => Ok(false),
         }
     }
+
+    /// Applies the chunk of `shard_id` in `block` again, on top of the state it
+    /// was originally applied to. The result is discarded, callers are only
+    /// interested in side channels such as execution traces.
+    fn reapply_chunk(&self, block: &Block, shard_id: ShardId) -> Result<(), unc_chain::Error> {
+        let chain_store = self.chain.chain_store();
+        let block_hash = block.hash();
+        let chunk_header = &block.chunks()[shard_id as usize];
+        if chunk_header.height_included() == block.header().height() {
+            let chunk = self.chain.get_chunk(&chunk_header.chunk_hash())?;
+            let prev_block = self.chain.get_block(block.header().prev_hash())?;
+            let receipt_proof_response = chain_store.get_incoming_receipts_for_shard(
+                self.epoch_manager.as_ref(),
+                shard_id,
+                *block_hash,
+                prev_block.chunks()[shard_id as usize].height_included(),
+            )?;
+            let receipts = collect_receipts_from_response(&receipt_proof_response);
+            let chunk_inner = chunk.cloned_header().take_inner();
+            let is_first_block_with_chunk_of_version =
+                check_if_block_is_first_with_chunk_of_version(
+                    chain_store,
+                    self.epoch_manager.as_ref(),
+                    block.header().prev_hash(),
+                    shard_id,
+                )?;
+            self.runtime.apply_chunk(
+                RuntimeStorageConfig::new(*chunk_inner.prev_state_root(), false),
+                ApplyChunkShardContext {
+                    shard_id,
+                    last_validator_power_proposals: chunk_inner.prev_validator_power_proposals(),
+                    last_validator_pledge_proposals: chunk_inner.prev_validator_pledge_proposals(),
+                    gas_limit: chunk_inner.gas_limit(),
+                    is_new_chunk: true,
+                    is_first_block_with_chunk_of_version,
+                },
+                ApplyChunkBlockContext::from_header(
+                    block.header(),
+                    prev_block.header().next_gas_price(),
+                    block.block_congestion_info(),
+                ),
+                &receipts,
+                chunk.transactions(),
+            )?;
+        } else {
+            let shard_uid =
+                self.epoch_manager.shard_id_to_uid(shard_id, block.header().epoch_id())?;
+            let chunk_extra =
+                chain_store.get_chunk_extra(block.header().prev_hash(), &shard_uid)?;
+            self.runtime.apply_chunk(
+                RuntimeStorageConfig::new(*chunk_extra.state_root(), false),
+                ApplyChunkShardContext {
+                    shard_id,
+                    last_validator_power_proposals: chunk_extra.validator_power_proposals(),
+                    last_validator_pledge_proposals: chunk_extra.validator_pledge_proposals(),
+                    gas_limit: chunk_extra.gas_limit(),
+                    is_new_chunk: false,
+                    is_first_block_with_chunk_of_version: false,
+                },
+                ApplyChunkBlockContext::from_header(
+                    block.header(),
+                    block.header().next_gas_price(),
+                    block.block_congestion_info(),
+                ),
+                &[],
+                &[],
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl Actor for ViewClientActor {
@@ -896,6 +1033,7 @@ impl Handler<WithSpanContext<GetStateChangesInBlock>> for ViewClientActor {
         let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
             .with_label_values(&["GetStateChangesInBlock"])
             .start_timer();
+        self.check_state_changes_available(&msg.block_hash)?;
         Ok(self
             .chain
             .chain_store()
@@ -920,6 +1058,7 @@ impl Handler<WithSpanContext<GetStateChanges>> for ViewClientActor {
         tracing::debug!(target: "client", ?msg);
         let _timer =
             metrics::VIEW_CLIENT_MESSAGE_TIME.with_label_values(&["GetStateChanges"]).start_timer();
+        self.check_state_changes_available(&msg.block_hash)?;
         Ok(self
             .chain
             .chain_store()
@@ -945,6 +1084,7 @@ impl Handler<WithSpanContext<GetStateChangesWithCauseInBlock>> for ViewClientAct
         let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
             .with_label_values(&["GetStateChangesWithCauseInBlock"])
             .start_timer();
+        self.check_state_changes_available(&msg.block_hash)?;
         Ok(self
             .chain
             .chain_store()
@@ -971,6 +1111,7 @@ impl Handler<WithSpanContext<GetStateChangesWithCauseInBlockForTrackedShards>> f
         let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
             .with_label_values(&["GetStateChangesWithCauseInBlockForTrackedShards"])
             .start_timer();
+        self.check_state_changes_available(&msg.block_hash)?;
         let state_changes_with_cause_in_block =
             self.chain.chain_store().get_state_changes_with_cause_in_block(&msg.block_hash)?;
 
@@ -1185,6 +1326,52 @@ impl Handler<WithSpanContext<GetReceipt>> for ViewClientActor {
     }
 }
 
+impl Handler<WithSpanContext<TraceReceipt>> for ViewClientActor {
+    type Result = Result<Vec<FunctionCallTraceView>, TraceReceiptError>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<TraceReceipt>,
+        _: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        tracing::debug!(target: "client", ?msg);
+        let _timer =
+            metrics::VIEW_CLIENT_MESSAGE_TIME.with_label_values(&["TraceReceipt"]).start_timer();
+        // Re-applying a chunk is expensive, keep it off nodes that produce blocks.
+        if self.validator_account_id.is_some() {
+            return Err(TraceReceiptError::ValidatorNode);
+        }
+        let receipt_id = msg.receipt_id;
+        let receipt = self
+            .chain
+            .chain_store()
+            .get_receipt(&receipt_id)?
+            .ok_or(TraceReceiptError::UnknownReceipt(receipt_id))?;
+        let block_hash = match self.chain.get_execution_outcome(&receipt_id) {
+            Ok(outcome) => outcome.block_hash,
+            Err(unc_chain::Error::DBNotFoundErr(_)) => {
+                return Err(TraceReceiptError::UnknownReceipt(receipt_id))
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let block = self.chain.get_block(&block_hash)?;
+        let shard_id = self
+            .epoch_manager
+            .account_id_to_shard_id(&receipt.receiver_id, block.header().epoch_id())
+            .into_chain_error()?;
+        let (result, traces) =
+            unc_vm_runner::logic::with_tracing(|| self.reapply_chunk(&block, shard_id));
+        result?;
+        Ok(traces
+            .into_iter()
+            .filter(|trace| trace.receipt_id == receipt_id)
+            .map(Into::into)
+            .collect())
+    }
+}
+
 impl Handler<WithSpanContext<GetBlockProof>> for ViewClientActor {
     type Result = Result<GetBlockProofResponse, GetBlockProofError>;
 
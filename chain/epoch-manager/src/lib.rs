@@ -155,6 +155,11 @@ impl EpochInfoProvider for EpochManagerHandle {
         let epoch_manager = self.read();
         epoch_manager.minimum_pledge(prev_block_hash)
     }
+
+    fn shard_layout(&self, epoch_id: &EpochId) -> Result<ShardLayout, EpochError> {
+        let epoch_manager = self.read();
+        epoch_manager.get_shard_layout(epoch_id)
+    }
 }
 
 /// Tracks epoch information across different forks, such as validators.
@@ -40,6 +40,7 @@ pub(crate) async fn convert_transactions_sir_into_local_receipts(
                                 unc_primitives::transaction::Action::try_from(action).unwrap()
                             })
                             .collect(),
+                        priority_fee: tx.transaction.priority_fee,
                     },
                     prev_block_gas_price,
                     true,
@@ -59,6 +60,7 @@ pub(crate) async fn convert_transactions_sir_into_local_receipts(
                         output_data_receivers: vec![],
                         input_data_ids: vec![],
                         actions: tx.transaction.actions.clone(),
+                        is_promise_yield: false,
                     },
                 }
             })
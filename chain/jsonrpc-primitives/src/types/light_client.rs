@@ -8,6 +8,13 @@ pub struct RpcLightClientExecutionProofRequest {
     pub light_client_head: unc_primitives::hash::CryptoHash,
 }
 
+/// Request for the proof of an execution outcome, verified by a node running in the light mode.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct RpcLightClientVerifiedProofRequest {
+    #[serde(flatten)]
+    pub id: unc_primitives::types::TransactionOrReceiptId,
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct RpcLightClientNextBlockRequest {
     pub last_block_hash: unc_primitives::hash::CryptoHash,
@@ -49,6 +56,12 @@ pub enum RpcLightClientProofError {
         transaction_or_receipt_id: unc_primitives::hash::CryptoHash,
         shard_id: unc_primitives::types::ShardId,
     },
+    #[error("Node doesn't run in the light mode")]
+    NotLightNode,
+    #[error("Node has no peers to request the proof from")]
+    NoPeers,
+    #[error("Timeout")]
+    TimeoutError,
     #[error("Internal error: {error_message}")]
     InternalError { error_message: String },
 }
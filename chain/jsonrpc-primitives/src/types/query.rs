@@ -20,6 +20,14 @@ pub enum RpcQueryError {
         block_height: unc_primitives::types::BlockHeight,
         block_hash: unc_primitives::hash::CryptoHash,
     },
+    #[error(
+        "The trie of block #{block_height} is garbage collected on this node and {request_kind} cannot be served from flat state history, use an archival node to fetch historical data"
+    )]
+    UnsupportedHistoricalQuery {
+        request_kind: String,
+        block_height: unc_primitives::types::BlockHeight,
+        block_hash: unc_primitives::hash::CryptoHash,
+    },
     #[error("Block either has never been observed on the node or has been garbage collected: {block_reference:?}")]
     UnknownBlock { block_reference: unc_primitives::types::BlockReference },
     #[error("Account ID {requested_account_id} is invalid")]
@@ -38,3 +38,40 @@ impl From<RpcReceiptError> for crate::errors::RpcError {
         Self::new_internal_or_handler_error(Some(error_data.clone()), error_data)
     }
 }
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcTraceReceiptRequest {
+    #[serde(flatten)]
+    pub receipt_reference: ReceiptReference,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcTraceReceiptResponse {
+    pub traces: Vec<unc_primitives::views::FunctionCallTraceView>,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcTraceReceiptError {
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+    #[error("Receipt with id {receipt_id} has never been executed on this node")]
+    UnknownReceipt { receipt_id: unc_primitives::hash::CryptoHash },
+    #[error("Receipts can't be traced on a validator node")]
+    ValidatorNode,
+}
+
+impl From<RpcTraceReceiptError> for crate::errors::RpcError {
+    fn from(error: RpcTraceReceiptError) -> Self {
+        let error_data = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcTraceReceiptError: {:?}", err),
+                )
+            }
+        };
+        Self::new_internal_or_handler_error(Some(error_data.clone()), error_data)
+    }
+}
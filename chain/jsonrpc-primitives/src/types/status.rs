@@ -4,9 +4,9 @@ use unc_client_primitives::debug::{
 };
 #[cfg(feature = "debug_types")]
 use unc_primitives::views::{
-    CatchupStatusView, ChainProcessingInfo, NetworkGraphView, NetworkRoutesView, PeerStoreView,
-    RecentOutboundConnectionsView, RequestedStatePartsView, SnapshotHostsView,
-    SplitStorageInfoView, SyncStatusView,
+    CatchupStatusView, ChainProcessingInfo, NetworkGraphView, NetworkRoutesView,
+    PeerReputationsView, PeerStoreView, RecentOutboundConnectionsView, RequestedStatePartsView,
+    SnapshotHostsView, SplitStorageInfoView, SyncStatusView,
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -36,6 +36,7 @@ pub enum DebugStatusResponse {
     Routes(NetworkRoutesView),
     SnapshotHosts(SnapshotHostsView),
     SplitStoreStatus(SplitStorageInfoView),
+    PeerReputations(PeerReputationsView),
 }
 
 #[cfg(feature = "debug_types")]
@@ -244,6 +244,14 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_receipt", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_trace_receipt(
+        &self,
+        request: unc_jsonrpc_primitives::types::receipts::RpcTraceReceiptRequest,
+    ) -> RpcRequest<unc_jsonrpc_primitives::types::receipts::RpcTraceReceiptResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_trace_receipt", request)
+    }
+
     #[allow(non_snake_case)]
     pub fn EXPERIMENTAL_protocol_config(
         &self,
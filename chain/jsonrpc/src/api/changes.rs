@@ -53,6 +53,9 @@ impl RpcFrom<GetStateChangesError> for RpcStateChangesError {
                 Self::UnknownBlock { error_message }
             }
             GetStateChangesError::NotSyncedYet => Self::NotSyncedYet,
+            GetStateChangesError::GarbageCollectedBlock { block_height, block_hash } => {
+                Self::GarbageCollectedBlock { block_height, block_hash }
+            }
             GetStateChangesError::Unreachable { ref error_message } => {
                 tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
                 crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
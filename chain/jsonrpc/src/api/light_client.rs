@@ -2,14 +2,17 @@ use std::sync::Arc;
 
 use serde_json::Value;
 
+use unc_client::GetLightClientExecutionProofError;
 use unc_client_primitives::types::{
     GetBlockProofError, GetExecutionOutcomeError, GetNextLightClientBlockError,
 };
 use unc_jsonrpc_primitives::errors::RpcParseError;
 use unc_jsonrpc_primitives::types::light_client::{
-    RpcLightClientExecutionProofRequest, RpcLightClientNextBlockError,
-    RpcLightClientNextBlockRequest, RpcLightClientNextBlockResponse, RpcLightClientProofError,
+    RpcLightClientExecutionProofRequest, RpcLightClientExecutionProofResponse,
+    RpcLightClientNextBlockError, RpcLightClientNextBlockRequest, RpcLightClientNextBlockResponse,
+    RpcLightClientProofError, RpcLightClientVerifiedProofRequest,
 };
+use unc_network::types::LightClientExecutionProof;
 use unc_primitives::views::LightClientBlockView;
 
 use super::{Params, RpcFrom, RpcRequest};
@@ -20,6 +23,12 @@ impl RpcRequest for RpcLightClientExecutionProofRequest {
     }
 }
 
+impl RpcRequest for RpcLightClientVerifiedProofRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
 impl RpcRequest for RpcLightClientNextBlockRequest {
     fn parse(value: Value) -> Result<Self, RpcParseError> {
         Params::new(value)
@@ -34,6 +43,18 @@ impl RpcFrom<Option<Arc<LightClientBlockView>>> for RpcLightClientNextBlockRespo
     }
 }
 
+impl RpcFrom<LightClientExecutionProof> for RpcLightClientExecutionProofResponse {
+    fn rpc_from(proof: LightClientExecutionProof) -> Self {
+        let LightClientExecutionProof {
+            outcome_proof,
+            outcome_root_proof,
+            block_header_lite,
+            block_proof,
+        } = proof;
+        Self { outcome_proof, outcome_root_proof, block_header_lite, block_proof }
+    }
+}
+
 impl RpcFrom<GetExecutionOutcomeError> for RpcLightClientProofError {
     fn rpc_from(error: GetExecutionOutcomeError) -> Self {
         match error {
@@ -93,6 +114,15 @@ impl RpcFrom<GetBlockProofError> for RpcLightClientProofError {
     }
 }
 
+impl RpcFrom<GetLightClientExecutionProofError> for RpcLightClientProofError {
+    fn rpc_from(error: GetLightClientExecutionProofError) -> Self {
+        match error {
+            GetLightClientExecutionProofError::NotLightNode => Self::NotLightNode,
+            GetLightClientExecutionProofError::NoPeers => Self::NoPeers,
+        }
+    }
+}
+
 impl RpcFrom<actix::MailboxError> for RpcLightClientNextBlockError {
     fn rpc_from(error: actix::MailboxError) -> Self {
         Self::InternalError { error_message: error.to_string() }
@@ -105,6 +105,9 @@ impl RpcFrom<QueryError> for RpcQueryError {
             QueryError::GarbageCollectedBlock { block_height, block_hash } => {
                 Self::GarbageCollectedBlock { block_height, block_hash }
             }
+            QueryError::UnsupportedHistoricalQuery { request_kind, block_height, block_hash } => {
+                Self::UnsupportedHistoricalQuery { request_kind, block_height, block_hash }
+            }
             QueryError::InvalidAccount { requested_account_id, block_height, block_hash } => {
                 Self::InvalidAccount { requested_account_id, block_height, block_hash }
             }
@@ -1,9 +1,10 @@
 use super::{Params, RpcFrom, RpcRequest};
 use serde_json::Value;
-use unc_client_primitives::types::{GetReceipt, GetReceiptError};
+use unc_client_primitives::types::{GetReceipt, GetReceiptError, TraceReceipt, TraceReceiptError};
 use unc_jsonrpc_primitives::errors::RpcParseError;
 use unc_jsonrpc_primitives::types::receipts::{
-    ReceiptReference, RpcReceiptError, RpcReceiptRequest,
+    ReceiptReference, RpcReceiptError, RpcReceiptRequest, RpcTraceReceiptError,
+    RpcTraceReceiptRequest,
 };
 
 impl RpcRequest for RpcReceiptRequest {
@@ -12,18 +13,36 @@ impl RpcRequest for RpcReceiptRequest {
     }
 }
 
+impl RpcRequest for RpcTraceReceiptRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Ok(Self { receipt_reference: Params::parse(value)? })
+    }
+}
+
 impl RpcFrom<actix::MailboxError> for RpcReceiptError {
     fn rpc_from(error: actix::MailboxError) -> Self {
         Self::InternalError { error_message: error.to_string() }
     }
 }
 
+impl RpcFrom<actix::MailboxError> for RpcTraceReceiptError {
+    fn rpc_from(error: actix::MailboxError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
 impl RpcFrom<ReceiptReference> for GetReceipt {
     fn rpc_from(receipt_reference: ReceiptReference) -> Self {
         Self { receipt_id: receipt_reference.receipt_id }
     }
 }
 
+impl RpcFrom<ReceiptReference> for TraceReceipt {
+    fn rpc_from(receipt_reference: ReceiptReference) -> Self {
+        Self { receipt_id: receipt_reference.receipt_id }
+    }
+}
+
 impl RpcFrom<GetReceiptError> for RpcReceiptError {
     fn rpc_from(error: GetReceiptError) -> Self {
         match error {
@@ -39,3 +58,20 @@ impl RpcFrom<GetReceiptError> for RpcReceiptError {
         }
     }
 }
+
+impl RpcFrom<TraceReceiptError> for RpcTraceReceiptError {
+    fn rpc_from(error: TraceReceiptError) -> Self {
+        match error {
+            TraceReceiptError::IOError(error_message) => Self::InternalError { error_message },
+            TraceReceiptError::UnknownReceipt(hash) => Self::UnknownReceipt { receipt_id: hash },
+            TraceReceiptError::ValidatorNode => Self::ValidatorNode,
+            TraceReceiptError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcTraceReceiptError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
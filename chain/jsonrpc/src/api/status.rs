@@ -69,6 +69,9 @@ impl RpcFrom<unc_network::debug::DebugStatus>
             unc_network::debug::DebugStatus::SnapshotHosts(x) => {
                 unc_jsonrpc_primitives::types::status::DebugStatusResponse::SnapshotHosts(x)
             }
+            unc_network::debug::DebugStatus::PeerReputations(x) => {
+                unc_jsonrpc_primitives::types::status::DebugStatusResponse::PeerReputations(x)
+            }
         }
     }
 }
@@ -18,10 +18,10 @@ use tracing::{error, info};
 use unc_chain_configs::GenesisConfig;
 use unc_client::{
     ClientActor, DebugStatus, GetBlock, GetBlockProof, GetChunk, GetClientConfig,
-    GetExecutionOutcome, GetGasPrice, GetMaintenanceWindows, GetNetworkInfo,
-    GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetStateChanges,
+    GetExecutionOutcome, GetGasPrice, GetLightClientExecutionProof, GetMaintenanceWindows,
+    GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetStateChanges,
     GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered, ProcessTxRequest,
-    ProcessTxResponse, Query, Status, TxStatus, ViewClientActor,
+    ProcessTxResponse, Query, Status, TraceReceipt, TxStatus, ViewClientActor,
 };
 use unc_client_primitives::types::{GetAllMiners, GetProvider, GetSplitStorageInfo};
 pub use unc_jsonrpc_client as client;
@@ -367,12 +367,19 @@ impl JsonRpcHandler {
                 })
                 .await
             }
+            "EXPERIMENTAL_light_client_verified_proof" => {
+                process_method_call(request, |params| self.light_client_verified_proof(params))
+                    .await
+            }
             "EXPERIMENTAL_protocol_config" => {
                 process_method_call(request, |params| self.protocol_config(params)).await
             }
             "EXPERIMENTAL_receipt" => {
                 process_method_call(request, |params| self.receipt(params)).await
             }
+            "EXPERIMENTAL_trace_receipt" => {
+                process_method_call(request, |params| self.trace_receipt(params)).await
+            }
             "EXPERIMENTAL_tx_status" => {
                 process_method_call(request, |params| self.tx_status_common(params, true)).await
             }
@@ -775,6 +782,10 @@ impl JsonRpcHandler {
                         .peer_manager_send(unc_network::debug::GetDebugStatus::SnapshotHosts)
                         .await?
                         .rpc_into(),
+                    "/debug/api/peer_reputations" => self
+                        .peer_manager_send(unc_network::debug::GetDebugStatus::PeerReputations)
+                        .await?
+                        .rpc_into(),
                     "/debug/api/split_store_info" => {
                         let split_storage_info: RpcSplitStorageInfoResponse = self
                             .split_storage_info(RpcSplitStorageInfoRequest {})
@@ -921,6 +932,18 @@ impl JsonRpcHandler {
         }
     }
 
+    async fn trace_receipt(
+        &self,
+        request_data: unc_jsonrpc_primitives::types::receipts::RpcTraceReceiptRequest,
+    ) -> Result<
+        unc_jsonrpc_primitives::types::receipts::RpcTraceReceiptResponse,
+        unc_jsonrpc_primitives::types::receipts::RpcTraceReceiptError,
+    > {
+        let traces =
+            self.view_client_send(TraceReceipt::rpc_from(request_data.receipt_reference)).await?;
+        Ok(unc_jsonrpc_primitives::types::receipts::RpcTraceReceiptResponse { traces })
+    }
+
     async fn changes_in_block(
         &self,
         request: unc_jsonrpc_primitives::types::changes::RpcStateChangesInBlockRequest,
@@ -1007,6 +1030,40 @@ impl JsonRpcHandler {
         })
     }
 
+    /// Returns the proof of the execution outcome verified by this node, which has to run
+    /// in the light mode. The proof is requested from the peers and polled for until it arrives.
+    async fn light_client_verified_proof(
+        &self,
+        request: unc_jsonrpc_primitives::types::light_client::RpcLightClientVerifiedProofRequest,
+    ) -> Result<
+        unc_jsonrpc_primitives::types::light_client::RpcLightClientExecutionProofResponse,
+        unc_jsonrpc_primitives::types::light_client::RpcLightClientProofError,
+    > {
+        let id = request.id;
+        timeout(self.polling_config.polling_timeout, async {
+            loop {
+                let proof: Result<
+                    _,
+                    unc_jsonrpc_primitives::types::light_client::RpcLightClientProofError,
+                > = self.client_send(GetLightClientExecutionProof { id: id.clone() }).await;
+                match proof {
+                    Ok(Some(proof)) => break Ok(proof.rpc_into()),
+                    Ok(None) => {}
+                    Err(err) => break Err(err),
+                }
+                sleep(self.polling_config.polling_interval).await;
+            }
+        })
+        .await
+        .map_err(|_| {
+            metrics::RPC_TIMEOUT_TOTAL.inc();
+            tracing::warn!(
+                target: "jsonrpc", "Timeout: light_client_verified_proof method. id {:?}", id,
+            );
+            unc_jsonrpc_primitives::types::light_client::RpcLightClientProofError::TimeoutError
+        })?
+    }
+
     async fn network_info(
         &self,
     ) -> Result<
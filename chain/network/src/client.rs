@@ -1,4 +1,6 @@
-use crate::network_protocol::StateResponseInfo;
+use crate::network_protocol::{
+    LightClientExecutionProof, LightClientProofRequest, StateResponseInfo,
+};
 
 use crate::types::{NetworkInfo, ReasonForBan};
 
@@ -9,7 +11,7 @@ use unc_primitives::hash::CryptoHash;
 use unc_primitives::network::{AnnounceAccount, PeerId};
 use unc_primitives::transaction::SignedTransaction;
 use unc_primitives::types::{AccountId, EpochId, ShardId};
-use unc_primitives::views::FinalExecutionOutcomeView;
+use unc_primitives::views::{FinalExecutionOutcomeView, LightClientBlockView};
 
 /// A strongly typed asynchronous API for the Client logic.
 /// It abstracts away the fact that client is implemented using actix
@@ -67,6 +69,28 @@ pub trait Client: Send + Sync + 'static {
     async fn chunk_state_witness(&self, witness: ChunkStateWitness);
 
     async fn chunk_endorsement(&self, endorsement: ChunkEndorsement);
+
+    async fn light_client_block_request(
+        &self,
+        last_block_hash: CryptoHash,
+    ) -> Option<Box<LightClientBlockView>>;
+
+    async fn light_client_block(
+        &self,
+        block: LightClientBlockView,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan>;
+
+    async fn light_client_proof_request(
+        &self,
+        request: LightClientProofRequest,
+    ) -> Option<Box<LightClientExecutionProof>>;
+
+    async fn light_client_proof(
+        &self,
+        proof: LightClientExecutionProof,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan>;
 }
 
 /// Implementation of Client which doesn't do anything and never returns errors.
@@ -138,4 +162,34 @@ impl Client for Noop {
     async fn chunk_state_witness(&self, _witness: ChunkStateWitness) {}
 
     async fn chunk_endorsement(&self, _endorsement: ChunkEndorsement) {}
+
+    async fn light_client_block_request(
+        &self,
+        _last_block_hash: CryptoHash,
+    ) -> Option<Box<LightClientBlockView>> {
+        None
+    }
+
+    async fn light_client_block(
+        &self,
+        _block: LightClientBlockView,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+
+    async fn light_client_proof_request(
+        &self,
+        _request: LightClientProofRequest,
+    ) -> Option<Box<LightClientExecutionProof>> {
+        None
+    }
+
+    async fn light_client_proof(
+        &self,
+        _proof: LightClientExecutionProof,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
 }
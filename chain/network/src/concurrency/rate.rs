@@ -1,3 +1,5 @@
+use unc_async::time;
+
 /// Config of a rate limiter algorithm, which behaves like a semaphore
 /// - with maximal capacity `burst`
 /// - with a new ticket added automatically every 1/qps seconds (qps stands for "queries per
@@ -25,3 +27,31 @@ impl Limit {
         Ok(())
     }
 }
+
+/// Non-blocking token bucket implementing the `Limit` algorithm.
+/// Useful where the excess requests should be dropped rather than delayed.
+pub struct Limiter {
+    limit: Limit,
+    tokens: f64,
+    last_refill: time::Instant,
+}
+
+impl Limiter {
+    /// Constructs a limiter with a full bucket.
+    pub fn new(limit: Limit, now: time::Instant) -> Self {
+        Self { limit, tokens: limit.burst as f64, last_refill: now }
+    }
+
+    /// Takes a token from the bucket, if available.
+    /// Returns false if the request should be rejected.
+    pub fn try_acquire(&mut self, now: time::Instant) -> bool {
+        let elapsed = (now - self.last_refill).as_seconds_f64().max(0.);
+        self.tokens = (self.tokens + elapsed * self.limit.qps).min(self.limit.burst as f64);
+        self.last_refill = now;
+        if self.tokens < 1. {
+            return false;
+        }
+        self.tokens -= 1.;
+        true
+    }
+}
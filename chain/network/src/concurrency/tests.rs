@@ -97,3 +97,24 @@ fn arc_mutex_update() {
     );
     assert_eq!(v3, *m.load());
 }
+
+#[test]
+fn rate_limiter() {
+    let clock = unc_async::time::FakeClock::default();
+    let mut l = rate::Limiter::new(rate::Limit { qps: 2., burst: 3 }, clock.now());
+    // Full burst is available immediately.
+    for _ in 0..3 {
+        assert!(l.try_acquire(clock.now()));
+    }
+    assert!(!l.try_acquire(clock.now()));
+    // A token is added every 1/qps seconds.
+    clock.advance(unc_async::time::Duration::milliseconds(500));
+    assert!(l.try_acquire(clock.now()));
+    assert!(!l.try_acquire(clock.now()));
+    // Bucket doesn't grow beyond burst.
+    clock.advance(unc_async::time::Duration::seconds(100));
+    for _ in 0..3 {
+        assert!(l.try_acquire(clock.now()));
+    }
+    assert!(!l.try_acquire(clock.now()));
+}
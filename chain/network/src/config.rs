@@ -1,5 +1,6 @@
 use crate::blacklist;
 use crate::concurrency::rate;
+use crate::dns_seeds;
 use crate::network_protocol::PeerAddr;
 use crate::network_protocol::PeerInfo;
 use crate::peer_manager::peer_manager_actor::Event;
@@ -10,7 +11,8 @@ use crate::stun;
 use crate::tcp;
 use crate::types::ROUTED_MESSAGE_TTL;
 use anyhow::Context;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use unc_async::time;
 use unc_crypto::{KeyType, SecretKey};
@@ -31,6 +33,10 @@ pub const MAX_PEER_ADDRS: usize = 10;
 /// Maximum number of peers to include in a PeersResponse message.
 pub const PEERS_RESPONSE_MAX_PEERS: u32 = 512;
 
+/// TEST-ONLY: environment variable selecting the transport ("tcp" or "quic")
+/// used by the nodes configured with `NetworkConfig::from_seed`.
+pub const TEST_TRANSPORT_ENV: &str = "UNC_NETWORK_TEST_TRANSPORT";
+
 /// ValidatorProxies are nodes with public IP (aka proxies) that this validator trusts to be honest
 /// and willing to forward traffic to this validator. Whenever this node is a TIER1 validator
 /// (i.e. whenever it is a block producer/chunk producer/approver for the given epoch),
@@ -68,6 +74,81 @@ impl ValidatorConfig {
     }
 }
 
+/// Compression of large messages sent to peers which accept compressed frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageCompression {
+    /// zstd compression level.
+    pub level: i32,
+    /// Minimal size of a message of the given type (see `PeerMessage::msg_variant`)
+    /// to be compressed. Messages of other types are never compressed.
+    pub min_size_by_msg_type: HashMap<String, usize>,
+}
+
+impl Default for MessageCompression {
+    /// Compresses the messages carrying blocks, chunks and state parts.
+    fn default() -> Self {
+        const MIN_SIZE: usize = 4 * 1024;
+        let min_size_by_msg_type = [
+            "Block",
+            "BlockHeaders",
+            "VersionedStateResponse",
+            "StateResponse",
+            "PartialEncodedChunkResponse",
+            "PartialEncodedChunkForward",
+            "VersionedPartialEncodedChunk",
+            "ChunkStateWitness",
+        ]
+        .into_iter()
+        .map(|msg_type| (msg_type.to_string(), MIN_SIZE))
+        .collect();
+        Self { level: 3, min_size_by_msg_type }
+    }
+}
+
+/// Priority class of the messages sent to peers. Every connection has a separate send
+/// queue per class, and a message is sent only once no message of a higher priority
+/// class is waiting, see `peer::send_queue`. Classes are listed from the highest priority.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::IntoStaticStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum MessageClass {
+    /// Approvals, endorsements, new blocks and the messages managing the connection.
+    Consensus,
+    /// Partial encoded chunks and chunk state witnesses.
+    ChunkDistribution,
+    /// Block and state sync requests and responses.
+    Sync,
+    /// Routing tables, accounts data, peers, transactions.
+    Gossip,
+}
+
+impl MessageClass {
+    /// All the classes, from the highest priority.
+    pub const ALL: [MessageClass; 4] =
+        [Self::Consensus, Self::ChunkDistribution, Self::Sync, Self::Gossip];
+}
+
+/// Recording of all the messages exchanged with peers, see `crate::recorder`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrafficRecording {
+    /// Directory to write the recording files to.
+    pub dir: PathBuf,
+    /// Size after which the current file is closed and a new one is started.
+    pub max_file_size: u64,
+    /// Number of most recent files to keep. Older files are deleted.
+    pub max_files: usize,
+}
+
 #[derive(Clone)]
 pub struct Tier1 {
     /// Interval between attempts to connect to proxies of other TIER1 nodes.
@@ -97,6 +178,8 @@ pub struct NetworkConfig {
     pub validator: Option<ValidatorConfig>,
 
     pub peer_store: peer_store::Config,
+    /// Signed DNS records to discover peers from, `None` if disabled.
+    pub dns_seeds: Option<dns_seeds::Config>,
     pub snapshot_hosts: snapshot_hosts::Config,
     pub whitelist_nodes: Vec<PeerInfo>,
     pub handshake_timeout: time::Duration,
@@ -149,12 +232,21 @@ pub struct NetworkConfig {
     pub inbound_disabled: bool,
     /// Whether this is an archival node.
     pub archive: bool,
+    /// Whether this is a light node, which tracks only the light client blocks.
+    /// Light nodes are not asked for blocks nor chunks by their peers.
+    pub light: bool,
     /// Maximal rate at which SyncAccountsData can be broadcasted.
     pub accounts_data_broadcast_rate_limit: rate::Limit,
     /// Maximal rate at which SyncSnapshotHosts can be broadcasted.
     pub snapshot_hosts_broadcast_rate_limit: rate::Limit,
     /// Maximal rate at which RoutingTable can be recomputed.
     pub routing_table_update_rate_limit: rate::Limit,
+    /// Maximal rate at which light client requests from a single peer are served.
+    /// Requests above the limit are dropped.
+    pub light_client_requests_rate_limit: rate::Limit,
+    /// Maximal rate at which `DeliveryReceipt`s are sent by this node, see
+    /// `RoutedMessageV2::trace`. Receipts above the limit are not sent.
+    pub delivery_receipts_rate_limit: rate::Limit,
     /// Config of the TIER1 network.
     pub tier1: Option<Tier1>,
 
@@ -165,6 +257,32 @@ pub struct NetworkConfig {
     //   * ignoring received deleted edges as well
     pub skip_tombstones: Option<time::Duration>,
 
+    /// Whether to encrypt connections to peers which support it, see
+    /// `ProtocolFeature::EncryptedPeerTransport`. Connections to peers which
+    /// don't support it, or have it disabled, stay in plaintext.
+    pub encrypted_transport: bool,
+    /// Whether to refuse plaintext TCP connections. Otherwise a
+    /// man-in-the-middle can downgrade a connection to plaintext by stripping
+    /// the encryption advertisement from the handshakes. Requires
+    /// `encrypted_transport`.
+    pub require_encrypted_transport: bool,
+    /// Compression of large messages, `None` if disabled.
+    pub message_compression: Option<MessageCompression>,
+    /// Recording of the network traffic, `None` if disabled.
+    pub traffic_recording: Option<TrafficRecording>,
+    /// Whether to accept QUIC connections (on the UDP port with the same number as `node_addr`)
+    /// and to connect over QUIC to the peers which accept them. TCP connections are still
+    /// accepted and used for peers which don't support QUIC.
+    pub quic_transport: bool,
+    /// Bandwidth caps on the messages of the given class sent to a single peer,
+    /// with `burst` in bytes and `qps` in bytes per second. Classes without a cap
+    /// are limited only by the connection.
+    pub bandwidth_caps: HashMap<MessageClass, rate::Limit>,
+    /// Whether to request delivery receipts for the routed messages authored by this node.
+    /// The receipts carry the path the message travelled through, which together with
+    /// the round trip latency is exposed in `GetDebugStatus::Routes`.
+    pub trace_routed_messages: bool,
+
     /// TEST-ONLY
     /// TODO(gprusak): make it pub(crate), once all integration tests
     /// are merged into unc_network.
@@ -218,6 +336,7 @@ impl NetworkConfig {
         node_key: SecretKey,
         validator_signer: Option<Arc<dyn ValidatorSigner>>,
         archive: bool,
+        light: bool,
     ) -> anyhow::Result<Self> {
         if cfg.public_addrs.len() > MAX_PEER_ADDRS {
             anyhow::bail!(
@@ -287,6 +406,15 @@ impl NetworkConfig {
                 ban_window: cfg.ban_window.try_into()?,
                 peer_expiration_duration: cfg.peer_expiration_duration.try_into()?,
             },
+            dns_seeds: match cfg.dns_seeds {
+                None => None,
+                Some(c) => Some(dns_seeds::Config {
+                    domains: c.domains,
+                    public_key: c.public_key,
+                    refresh_interval: c.refresh_interval.try_into()?,
+                    resolver: Arc::new(dns_seeds::SystemResolver::new().context("dns_seeds")?),
+                }),
+            },
             snapshot_hosts: snapshot_hosts::Config {
                 snapshot_hosts_cache_size: cfg.snapshot_hosts_cache_size,
             },
@@ -324,9 +452,12 @@ impl NetworkConfig {
             push_info_period: time::Duration::milliseconds(100),
             outbound_disabled: false,
             archive,
+            light,
             accounts_data_broadcast_rate_limit: rate::Limit { qps: 0.1, burst: 1 },
             snapshot_hosts_broadcast_rate_limit: rate::Limit { qps: 0.1, burst: 1 },
             routing_table_update_rate_limit: rate::Limit { qps: 1., burst: 1 },
+            light_client_requests_rate_limit: rate::Limit { qps: 2., burst: 10 },
+            delivery_receipts_rate_limit: rate::Limit { qps: 10., burst: 100 },
             tier1: Some(Tier1 {
                 connect_interval: cfg.experimental.tier1_connect_interval.try_into()?,
                 new_connections_per_attempt: cfg.experimental.tier1_new_connections_per_attempt,
@@ -340,6 +471,36 @@ impl NetworkConfig {
             } else {
                 None
             },
+            encrypted_transport: cfg.experimental.encrypted_transport,
+            require_encrypted_transport: cfg.experimental.require_encrypted_transport,
+            message_compression: cfg.experimental.message_compression.enabled.then(|| {
+                MessageCompression {
+                    level: cfg.experimental.message_compression.level,
+                    min_size_by_msg_type: cfg
+                        .experimental
+                        .message_compression
+                        .min_size_by_msg_type
+                        .clone(),
+                }
+            }),
+            traffic_recording: cfg.experimental.traffic_recording.map(|c| TrafficRecording {
+                dir: c.dir,
+                max_file_size: c.max_file_size_bytes,
+                max_files: c.max_files,
+            }),
+            quic_transport: cfg.experimental.quic_transport,
+            bandwidth_caps: cfg
+                .experimental
+                .bandwidth_caps
+                .iter()
+                .map(|(class, cap)| {
+                    (
+                        *class,
+                        rate::Limit { burst: cap.burst_bytes, qps: cap.bytes_per_second as f64 },
+                    )
+                })
+                .collect(),
+            trace_routed_messages: cfg.experimental.trace_routed_messages,
             event_sink: Sink::null(),
         };
         this.override_config(cfg.experimental.network_config_overrides);
@@ -351,13 +512,17 @@ impl NetworkConfig {
     }
 
     /// TEST-ONLY: Returns network config with given seed used for peer id.
+    /// QUIC transport is enabled if the TEST_TRANSPORT_ENV variable is set to "quic",
+    /// so that the same tests can be run over both transports.
     pub fn from_seed(seed: &str, node_addr: tcp::ListenerAddr) -> Self {
         let node_key = SecretKey::from_seed(KeyType::ED25519, seed);
+        let quic_transport = std::env::var(TEST_TRANSPORT_ENV).is_ok_and(|t| t == "quic");
         let validator = ValidatorConfig {
             signer: Arc::new(create_test_signer(seed)),
             proxies: ValidatorProxies::Static(vec![PeerAddr {
                 addr: *node_addr,
                 peer_id: PeerId::new(node_key.public_key()),
+                quic: quic_transport,
             }]),
         };
         NetworkConfig {
@@ -372,6 +537,7 @@ impl NetworkConfig {
                 peer_expiration_duration: time::Duration::seconds(60 * 60),
                 connect_only_to_boot_nodes: false,
             },
+            dns_seeds: None,
             snapshot_hosts: snapshot_hosts::Config { snapshot_hosts_cache_size: 1000 },
             whitelist_nodes: vec![],
             handshake_timeout: time::Duration::seconds(5),
@@ -394,9 +560,12 @@ impl NetworkConfig {
             outbound_disabled: false,
             inbound_disabled: false,
             archive: false,
+            light: false,
             accounts_data_broadcast_rate_limit: rate::Limit { qps: 100., burst: 1000000 },
             snapshot_hosts_broadcast_rate_limit: rate::Limit { qps: 100., burst: 1000000 },
             routing_table_update_rate_limit: rate::Limit { qps: 10., burst: 1 },
+            light_client_requests_rate_limit: rate::Limit { qps: 100., burst: 1000 },
+            delivery_receipts_rate_limit: rate::Limit { qps: 100., burst: 1000 },
             tier1: Some(Tier1 {
                 // Interval is very large, so that it doesn't happen spontaneously in tests.
                 // It should rather be triggered manually in tests.
@@ -407,6 +576,13 @@ impl NetworkConfig {
                 enable_outbound: true,
             }),
             skip_tombstones: None,
+            encrypted_transport: true,
+            require_encrypted_transport: false,
+            message_compression: Some(MessageCompression::default()),
+            traffic_recording: None,
+            quic_transport,
+            bandwidth_caps: HashMap::new(),
+            trace_routed_messages: false,
             event_sink: Sink::null(),
         }
     }
@@ -450,12 +626,25 @@ impl NetworkConfig {
             );
         }
 
+        if self.require_encrypted_transport && !self.encrypted_transport {
+            anyhow::bail!(
+                "require_encrypted_transport is set, but encrypted_transport is disabled"
+            );
+        }
+
         self.accounts_data_broadcast_rate_limit
             .validate()
             .context("accounts_Data_broadcast_rate_limit")?;
         self.routing_table_update_rate_limit
             .validate()
             .context("routing_table_update_rate_limit")?;
+        self.light_client_requests_rate_limit
+            .validate()
+            .context("light_client_requests_rate_limit")?;
+        self.delivery_receipts_rate_limit.validate().context("delivery_receipts_rate_limit")?;
+        for (class, cap) in &self.bandwidth_caps {
+            cap.validate().with_context(|| format!("bandwidth_caps[{class:?}]"))?;
+        }
         Ok(VerifiedConfig { node_id: self.node_id(), inner: self })
     }
 }
@@ -1,6 +1,10 @@
+use crate::config::{MessageClass, MessageCompression};
 use crate::network_protocol::PeerAddr;
 use crate::stun;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
+use unc_crypto::PublicKey;
 
 /// Time to persist Accounts Id in the router without removing them in seconds.
 pub const TTL_ACCOUNT_ID_ROUTER: u64 = 60 * 60;
@@ -60,6 +64,26 @@ fn default_peer_expiration_duration() -> Duration {
     Duration::from_secs(7 * 24 * 60 * 60)
 }
 
+fn default_encrypted_transport() -> bool {
+    true
+}
+
+/// Caps on the classes of large messages, so that a peer syncing from us (or a burst of
+/// chunks) doesn't starve the gossip sent over the same connection.
+fn default_bandwidth_caps() -> HashMap<MessageClass, BandwidthCapConfig> {
+    const MIB: u64 = 1024 * 1024;
+    HashMap::from([
+        (
+            MessageClass::ChunkDistribution,
+            BandwidthCapConfig { bytes_per_second: 50 * MIB, burst_bytes: 100 * MIB },
+        ),
+        (
+            MessageClass::Sync,
+            BandwidthCapConfig { bytes_per_second: 20 * MIB, burst_bytes: 40 * MIB },
+        ),
+    ])
+}
+
 /// This is a list of public STUN servers provided by Google,
 /// which are known to have good availability. To avoid trusting
 /// a centralized entity (and DNS used for domain resolution),
@@ -84,6 +108,10 @@ pub struct Config {
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@31.192.22.209:24567
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@uncnode.com:24567
     pub boot_nodes: String,
+    /// Domains with DNS TXT records listing the peers to connect to, signed by the given key.
+    /// Allows to rotate the boot nodes without changing the config. See `unc_network::dns_seeds`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_seeds: Option<DnsSeedsConfig>,
     /// Comma separated list of whitelisted nodes. Inbound connections from the nodes on
     /// the whitelist are accepted even if the limit of the inbound connection has been reached.
     /// For each whitelisted node specifying both PeerId and one of IP:port or Host:port is required:
@@ -222,11 +250,101 @@ pub struct ExperimentalConfig {
     /// See `unc_network::config::Tier1::new_connections_per_attempt`.
     pub tier1_new_connections_per_attempt: u64,
 
+    /// See `unc_network::config::NetworkConfig::encrypted_transport`.
+    #[serde(default = "default_encrypted_transport")]
+    pub encrypted_transport: bool,
+
+    /// See `unc_network::config::NetworkConfig::require_encrypted_transport`.
+    #[serde(default)]
+    pub require_encrypted_transport: bool,
+
+    /// Compression of large messages sent to peers which accept it.
+    #[serde(default)]
+    pub message_compression: MessageCompressionConfig,
+
+    /// If set, all the messages exchanged with peers are recorded to files,
+    /// so that they can be replayed later with the `traffic-replay` tool.
+    #[serde(default)]
+    pub traffic_recording: Option<TrafficRecordingConfig>,
+
+    /// See `unc_network::config::NetworkConfig::quic_transport`.
+    #[serde(default)]
+    pub quic_transport: bool,
+
+    /// Bandwidth caps on the messages sent to a single peer, per message class.
+    /// See `unc_network::config::NetworkConfig::bandwidth_caps`.
+    #[serde(default = "default_bandwidth_caps")]
+    pub bandwidth_caps: HashMap<MessageClass, BandwidthCapConfig>,
+
+    /// See `unc_network::config::NetworkConfig::trace_routed_messages`.
+    #[serde(default)]
+    pub trace_routed_messages: bool,
+
     /// See `NetworkConfig`.
     /// Fields set here will override the NetworkConfig fields.
     pub network_config_overrides: NetworkConfigOverrides,
 }
 
+/// See `unc_network::config::MessageCompression`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct MessageCompressionConfig {
+    pub enabled: bool,
+    /// zstd compression level.
+    pub level: i32,
+    /// Minimal size in bytes of a message of the given type to be compressed.
+    pub min_size_by_msg_type: HashMap<String, usize>,
+}
+
+impl Default for MessageCompressionConfig {
+    fn default() -> Self {
+        let MessageCompression { level, min_size_by_msg_type } = MessageCompression::default();
+        Self { enabled: true, level, min_size_by_msg_type }
+    }
+}
+
+/// See `unc_network::config::NetworkConfig::bandwidth_caps`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BandwidthCapConfig {
+    pub bytes_per_second: u64,
+    /// Number of bytes which can be sent at once after a period of inactivity.
+    pub burst_bytes: u64,
+}
+
+/// See `unc_network::dns_seeds::Config`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct DnsSeedsConfig {
+    pub domains: Vec<String>,
+    /// Key which the records have to be signed with.
+    pub public_key: PublicKey,
+    #[serde(default = "default_dns_seeds_refresh_interval")]
+    pub refresh_interval: Duration,
+}
+
+fn default_dns_seeds_refresh_interval() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+/// See `unc_network::config::TrafficRecording`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct TrafficRecordingConfig {
+    /// Directory to write the recording to. Relative paths are resolved
+    /// against the working directory of the node.
+    pub dir: PathBuf,
+    #[serde(default = "default_traffic_recording_max_file_size_bytes")]
+    pub max_file_size_bytes: u64,
+    #[serde(default = "default_traffic_recording_max_files")]
+    pub max_files: usize,
+}
+
+fn default_traffic_recording_max_file_size_bytes() -> u64 {
+    bytesize::MIB * 64
+}
+
+fn default_traffic_recording_max_files() -> usize {
+    16
+}
+
 /// Overrides values from NetworkConfig.
 /// This enables the user to override the hardcoded values.
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
@@ -254,6 +372,13 @@ impl Default for ExperimentalConfig {
             tier1_enable_outbound: true,
             tier1_connect_interval: Duration::from_secs(60),
             tier1_new_connections_per_attempt: 50,
+            encrypted_transport: default_encrypted_transport(),
+            require_encrypted_transport: false,
+            message_compression: Default::default(),
+            traffic_recording: None,
+            quic_transport: false,
+            bandwidth_caps: default_bandwidth_caps(),
+            trace_routed_messages: false,
             network_config_overrides: Default::default(),
         }
     }
@@ -264,6 +389,7 @@ impl Default for Config {
         Config {
             addr: "0.0.0.0:24567".to_string(),
             boot_nodes: "".to_string(),
+            dns_seeds: None,
             whitelist_nodes: "".to_string(),
             max_num_peers: default_max_num_peers(),
             minimum_outbound_peers: default_minimum_outbound_connections(),
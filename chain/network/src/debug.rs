@@ -1,7 +1,7 @@
 use ::actix::Message;
 use unc_primitives::views::{
-    NetworkGraphView, NetworkRoutesView, PeerStoreView, RecentOutboundConnectionsView,
-    SnapshotHostsView,
+    NetworkGraphView, NetworkRoutesView, PeerReputationsView, PeerStoreView,
+    RecentOutboundConnectionsView, SnapshotHostsView,
 };
 
 // Different debug requests that can be sent by HTML pages, via GET.
@@ -11,6 +11,7 @@ pub enum GetDebugStatus {
     RecentOutboundConnections,
     Routes,
     SnapshotHosts,
+    PeerReputations,
 }
 
 #[derive(actix::MessageResponse, Debug)]
@@ -20,6 +21,7 @@ pub enum DebugStatus {
     RecentOutboundConnections(RecentOutboundConnectionsView),
     Routes(NetworkRoutesView),
     SnapshotHosts(SnapshotHostsView),
+    PeerReputations(PeerReputationsView),
 }
 
 impl Message for GetDebugStatus {
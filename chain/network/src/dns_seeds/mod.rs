@@ -0,0 +1,229 @@
+//! Peer discovery via DNS seeds.
+//!
+//! A DNS seed is a domain with TXT records, each of which lists a single peer of the network
+//! (see `Record`) and is signed by a key configured in the node. It allows the operators to
+//! rotate the nodes used for bootstrapping without changing the config of every node.
+//!
+//! The records are looked up periodically and the listed peers are added to the peer store,
+//! just like the boot nodes. Records which have expired or are not signed by the configured
+//! key are ignored, so that whoever controls the DNS responses can't point the node to
+//! arbitrary peers.
+use crate::network_protocol::{ParsePeerInfoError, PeerInfo};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use unc_async::time;
+use unc_crypto::{ParseSignatureError, PublicKey, SecretKey, Signature};
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+pub(crate) mod testonly;
+
+const LOOKUP_TIMEOUT: time::Duration = time::Duration::seconds(5);
+
+/// Prefix of the DNS seed records. Other TXT records of the domain are ignored.
+const RECORD_PREFIX: &str = "unc-seed";
+
+/// Source of the TXT records of a domain.
+#[async_trait::async_trait]
+pub trait Resolver: Send + Sync + 'static {
+    /// Returns the TXT records of the domain.
+    /// Character strings of a single record are concatenated.
+    async fn lookup_txt(&self, domain: &str) -> anyhow::Result<Vec<String>>;
+}
+
+/// Resolver using the DNS configuration of the system (i.e. /etc/resolv.conf on unix).
+pub struct SystemResolver(hickory_resolver::TokioAsyncResolver);
+
+impl SystemResolver {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self(hickory_resolver::TokioAsyncResolver::tokio_from_system_conf()?))
+    }
+}
+
+#[async_trait::async_trait]
+impl Resolver for SystemResolver {
+    async fn lookup_txt(&self, domain: &str) -> anyhow::Result<Vec<String>> {
+        let lookup = self.0.txt_lookup(domain).await?;
+        Ok(lookup
+            .iter()
+            .map(|txt| txt.txt_data().iter().map(|s| String::from_utf8_lossy(s)).collect())
+            .collect())
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    /// Domains to look up the records at.
+    pub domains: Vec<String>,
+    /// Key which the records have to be signed with.
+    pub public_key: PublicKey,
+    /// Interval between the lookups.
+    pub refresh_interval: time::Duration,
+    pub resolver: Arc<dyn Resolver>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseRecordError {
+    #[error("not a DNS seed record")]
+    NotASeedRecord,
+    #[error("missing field {0:?}")]
+    MissingField(&'static str),
+    #[error("peer: {0}")]
+    PeerInfo(#[source] ParsePeerInfoError),
+    #[error("expires: {0}")]
+    Expires(String),
+    #[error("sig: {0}")]
+    Signature(#[source] ParseSignatureError),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum VerifyRecordError {
+    #[error("peer has no address")]
+    MissingAddr,
+    #[error("record has expired at {0}")]
+    Expired(time::Utc),
+    #[error("invalid signature")]
+    InvalidSignature,
+}
+
+/// A DNS seed record, of the format
+/// `unc-seed peer=<peer id>@<ip>:<port> expires=<unix timestamp> sig=<signature>`.
+///
+/// The signature covers the domain, so that a record cannot be copied to another seed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub peer_info: PeerInfo,
+    /// The record is ignored after this time, so that a record of a node which has been
+    /// rotated out cannot be served forever.
+    pub expires_at: time::Utc,
+    pub signature: Signature,
+}
+
+impl Record {
+    fn payload(domain: &str, peer_info: &PeerInfo, expires_at: time::Utc) -> Vec<u8> {
+        format!("{RECORD_PREFIX}\n{domain}\n{peer_info}\n{}", expires_at.unix_timestamp())
+            .into_bytes()
+    }
+
+    /// Signs a record for the given domain.
+    pub fn new(
+        domain: &str,
+        peer_info: PeerInfo,
+        expires_at: time::Utc,
+        secret_key: &SecretKey,
+    ) -> Self {
+        let signature = secret_key.sign(&Self::payload(domain, &peer_info, expires_at));
+        Self { peer_info, expires_at, signature }
+    }
+
+    pub fn verify(
+        &self,
+        domain: &str,
+        public_key: &PublicKey,
+        now: time::Utc,
+    ) -> Result<(), VerifyRecordError> {
+        if self.peer_info.addr.is_none() {
+            return Err(VerifyRecordError::MissingAddr);
+        }
+        if self.expires_at < now {
+            return Err(VerifyRecordError::Expired(self.expires_at));
+        }
+        let payload = Self::payload(domain, &self.peer_info, self.expires_at);
+        if !self.signature.verify(&payload, public_key) {
+            return Err(VerifyRecordError::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{RECORD_PREFIX} peer={} expires={} sig={}",
+            self.peer_info,
+            self.expires_at.unix_timestamp(),
+            self.signature
+        )
+    }
+}
+
+impl FromStr for Record {
+    type Err = ParseRecordError;
+    /// Unknown fields are ignored, so that new fields can be added to the records
+    /// without breaking the older nodes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+        if fields.next() != Some(RECORD_PREFIX) {
+            return Err(ParseRecordError::NotASeedRecord);
+        }
+        let (mut peer, mut expires, mut sig) = (None, None, None);
+        for field in fields {
+            match field.split_once('=') {
+                Some(("peer", v)) => peer = Some(v),
+                Some(("expires", v)) => expires = Some(v),
+                Some(("sig", v)) => sig = Some(v),
+                _ => {}
+            }
+        }
+        let peer_info = peer
+            .ok_or(ParseRecordError::MissingField("peer"))?
+            .parse()
+            .map_err(ParseRecordError::PeerInfo)?;
+        let expires = expires.ok_or(ParseRecordError::MissingField("expires"))?;
+        let expires_at = expires
+            .parse()
+            .ok()
+            .and_then(|ts| time::Utc::from_unix_timestamp(ts).ok())
+            .ok_or_else(|| ParseRecordError::Expires(expires.to_string()))?;
+        let signature = sig
+            .ok_or(ParseRecordError::MissingField("sig"))?
+            .parse()
+            .map_err(ParseRecordError::Signature)?;
+        Ok(Self { peer_info, expires_at, signature })
+    }
+}
+
+async fn lookup_domain(clock: &time::Clock, cfg: &Config, domain: &str) -> Vec<PeerInfo> {
+    // Note that both clock.sleep() and lookup_txt() are cancellable,
+    // so it is safe to use them in tokio::select!.
+    let txts = tokio::select! {
+        _ = clock.sleep(LOOKUP_TIMEOUT) => Err(anyhow::anyhow!("timeout")),
+        txts = cfg.resolver.lookup_txt(domain) => txts,
+    };
+    let txts = match txts {
+        Ok(txts) => txts,
+        Err(err) => {
+            tracing::warn!(target: "network", domain, ?err, "DNS seed lookup failed");
+            return vec![];
+        }
+    };
+    let now = clock.now_utc();
+    let mut peers = vec![];
+    for txt in txts {
+        let record = match txt.parse::<Record>() {
+            Ok(record) => record,
+            Err(ParseRecordError::NotASeedRecord) => continue,
+            Err(err) => {
+                tracing::warn!(target: "network", domain, txt, ?err, "malformed DNS seed record");
+                continue;
+            }
+        };
+        if let Err(err) = record.verify(domain, &cfg.public_key, now) {
+            tracing::warn!(target: "network", domain, txt, ?err, "invalid DNS seed record");
+            continue;
+        }
+        peers.push(record.peer_info);
+    }
+    peers
+}
+
+/// Looks up the records of all the configured domains in parallel and returns
+/// the peers listed in the valid ones. Failed lookups and invalid records are skipped.
+pub(crate) async fn lookup(clock: &time::Clock, cfg: &Config) -> Vec<PeerInfo> {
+    let lookups = cfg.domains.iter().map(|domain| lookup_domain(clock, cfg, domain));
+    futures::future::join_all(lookups).await.into_iter().flatten().collect()
+}
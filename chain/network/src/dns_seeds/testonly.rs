@@ -0,0 +1,33 @@
+use crate::dns_seeds::{Record, Resolver};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Local stand-in for the DNS, serving the TXT records set with `set()`.
+/// Lookup of a domain without records fails, like a lookup of a nonexistent domain.
+#[derive(Default)]
+pub(crate) struct Server {
+    records: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl Server {
+    /// Replaces the TXT records of the domain.
+    pub fn set(&self, domain: &str, txts: Vec<String>) {
+        self.records.lock().insert(domain.to_string(), txts);
+    }
+
+    /// Replaces the TXT records of the domain with the given seed records.
+    pub fn set_records(&self, domain: &str, records: &[Record]) {
+        self.set(domain, records.iter().map(|r| r.to_string()).collect());
+    }
+}
+
+#[async_trait::async_trait]
+impl Resolver for Server {
+    async fn lookup_txt(&self, domain: &str) -> anyhow::Result<Vec<String>> {
+        self.records
+            .lock()
+            .get(domain)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("no records for {domain}"))
+    }
+}
@@ -0,0 +1,87 @@
+use crate::dns_seeds;
+use crate::dns_seeds::{Record, VerifyRecordError};
+use crate::network_protocol::testonly as data;
+use crate::testonly::make_rng;
+use std::sync::Arc;
+use unc_async::time;
+use unc_o11y::testonly::init_test_logger;
+
+const DOMAIN: &str = "seeds.unc.test";
+
+#[test]
+fn record_roundtrip() {
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let key = data::make_secret_key(rng);
+    let expires_at = clock.now_utc() + time::Duration::hours(1);
+    let record = Record::new(DOMAIN, data::make_peer_info(rng), expires_at, &key);
+    let got: Record = record.to_string().parse().unwrap();
+    assert_eq!(record, got);
+    // Unknown fields are ignored.
+    let got: Record = format!("{record} foo=bar").parse().unwrap();
+    assert_eq!(record, got);
+    assert!("unc-seed peer=abc".parse::<Record>().is_err());
+}
+
+#[test]
+fn record_verify() {
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let now = clock.now_utc();
+    let key = data::make_secret_key(rng);
+    let expires_at = now + time::Duration::hours(1);
+    let record = Record::new(DOMAIN, data::make_peer_info(rng), expires_at, &key);
+    assert_eq!(Ok(()), record.verify(DOMAIN, &key.public_key(), now));
+    assert_eq!(
+        Err(VerifyRecordError::Expired(expires_at)),
+        record.verify(DOMAIN, &key.public_key(), expires_at + time::Duration::seconds(1))
+    );
+    assert_eq!(
+        Err(VerifyRecordError::InvalidSignature),
+        record.verify(DOMAIN, &data::make_secret_key(rng).public_key(), now)
+    );
+    assert_eq!(
+        Err(VerifyRecordError::InvalidSignature),
+        record.verify("other.unc.test", &key.public_key(), now)
+    );
+    let mut peer_info = data::make_peer_info(rng);
+    peer_info.addr = None;
+    let record = Record::new(DOMAIN, peer_info, expires_at, &key);
+    assert_eq!(Err(VerifyRecordError::MissingAddr), record.verify(DOMAIN, &key.public_key(), now));
+}
+
+#[tokio::test]
+async fn lookup() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let key = data::make_secret_key(rng);
+    let expires_at = clock.now_utc() + time::Duration::hours(1);
+    let server = Arc::new(dns_seeds::testonly::Server::default());
+    let cfg = dns_seeds::Config {
+        domains: vec![DOMAIN.to_string(), "missing.unc.test".to_string()],
+        public_key: key.public_key(),
+        refresh_interval: time::Duration::minutes(10),
+        resolver: server.clone(),
+    };
+
+    let valid: Vec<_> =
+        (0..3).map(|_| Record::new(DOMAIN, data::make_peer_info(rng), expires_at, &key)).collect();
+    let expired = Record::new(DOMAIN, data::make_peer_info(rng), clock.now_utc(), &key);
+    let wrong_key =
+        Record::new(DOMAIN, data::make_peer_info(rng), expires_at, &data::make_secret_key(rng));
+    let mut txts: Vec<_> = valid.iter().map(|r| r.to_string()).collect();
+    txts.push(expired.to_string());
+    txts.push(wrong_key.to_string());
+    txts.push("v=spf1 -all".to_string());
+    txts.push("unc-seed malformed".to_string());
+    server.set(DOMAIN, txts);
+
+    clock.advance(time::Duration::seconds(1));
+    let got = dns_seeds::lookup(&clock.clock(), &cfg).await;
+    let want: Vec<_> = valid.into_iter().map(|r| r.peer_info).collect();
+    assert_eq!(want, got);
+}
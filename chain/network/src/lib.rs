@@ -6,6 +6,7 @@ mod network_protocol;
 mod peer;
 mod peer_manager;
 mod private_actix;
+mod quic;
 mod snapshot_hosts;
 mod stats;
 mod store;
@@ -18,7 +19,9 @@ pub mod concurrency;
 pub mod config;
 pub mod config_json;
 pub mod debug;
+pub mod dns_seeds;
 pub mod raw;
+pub mod recorder;
 pub mod routing;
 pub mod shards_manager;
 pub mod state_sync;
@@ -14,6 +14,10 @@ impl From<&net::Handshake> for mem::Handshake {
             sender_chain_info: x.sender_chain_info.clone(),
             partial_edge_info: x.partial_edge_info.clone(),
             owned_account: None,
+            encrypted_transport: false,
+            message_compression: false,
+            quic_transport: false,
+            quic_session_signature: None,
         }
     }
 }
@@ -172,6 +176,7 @@ impl TryFrom<&net::PeerMessage> for mem::PeerMessage {
                 msg: *r,
                 created_at: None,
                 num_hops: Some(0),
+                trace: None,
             })),
             net::PeerMessage::Disconnect => mem::PeerMessage::Disconnect(mem::Disconnect {
                 // This flag is used by the disconnecting peer to advise the other peer that there
@@ -253,6 +258,14 @@ impl From<&mem::PeerMessage> for net::PeerMessage {
                 net::PeerMessage::VersionedStateResponse(sri)
             }
             mem::PeerMessage::SyncSnapshotHosts(ssh) => net::PeerMessage::SyncSnapshotHosts(ssh),
+
+            // These messages are not supported, we translate them to an empty RoutingTableUpdate.
+            mem::PeerMessage::LightClientBlockRequest(_)
+            | mem::PeerMessage::LightClientBlock(_)
+            | mem::PeerMessage::LightClientProofRequest(_)
+            | mem::PeerMessage::LightClientProof(_) => {
+                net::PeerMessage::SyncRoutingTable(net::RoutingTableUpdate::default())
+            }
         }
     }
 }
@@ -0,0 +1,26 @@
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::merkle::MerklePath;
+use unc_primitives::types::TransactionOrReceiptId;
+use unc_primitives::views::{ExecutionOutcomeWithIdView, LightClientBlockLiteView};
+
+/// Request for the proof of an execution outcome of a transaction or a receipt,
+/// sent by a light node to a full node.
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Clone, Debug, Eq, PartialEq)]
+pub struct LightClientProofRequest {
+    pub id: TransactionOrReceiptId,
+    /// Hash of the last light client block known to the requester.
+    /// The block proof is computed against its block merkle root.
+    pub light_client_head: CryptoHash,
+}
+
+/// Proof that an execution outcome has been included in the chain.
+/// Same as the response of the `EXPERIMENTAL_light_client_proof` RPC.
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Clone, Debug, Eq, PartialEq)]
+pub struct LightClientExecutionProof {
+    pub outcome_proof: ExecutionOutcomeWithIdView,
+    pub outcome_root_proof: MerklePath,
+    /// Header of the block containing the outcome root.
+    pub block_header_lite: LightClientBlockLiteView,
+    /// Proof of the block against the block merkle root of the light client head.
+    pub block_proof: MerklePath,
+}
@@ -3,10 +3,12 @@
 mod borsh_;
 mod borsh_conv;
 mod edge;
+mod light_client;
 mod peer;
 mod proto_conv;
 mod state_sync;
 pub use edge::*;
+pub use light_client::*;
 pub use peer::*;
 pub use state_sync::*;
 use unc_primitives::chunk_validation::ChunkEndorsement;
@@ -23,6 +25,7 @@ mod _proto {
 
 pub use _proto::network as proto;
 
+use crate::config::MessageClass;
 use crate::network_protocol::proto_conv::trace_context::{
     extract_span_context, inject_trace_context,
 };
@@ -50,17 +53,23 @@ use unc_primitives::transaction::SignedTransaction;
 use unc_primitives::types::AccountId;
 use unc_primitives::types::{BlockHeight, ShardId};
 use unc_primitives::validator_signer::ValidatorSigner;
-use unc_primitives::views::FinalExecutionOutcomeView;
+use unc_primitives::views::{FinalExecutionOutcomeView, LightClientBlockView};
 
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
 pub struct PeerAddr {
     pub addr: std::net::SocketAddr,
     pub peer_id: PeerId,
+    /// Whether the peer accepts QUIC connections at `addr` (UDP), see `crate::quic`.
+    pub quic: bool,
 }
 
+/// Suffix of the string representation of a PeerAddr which accepts QUIC connections.
+const QUIC_SUFFIX: &str = "/quic";
+
 impl serde::Serialize for PeerAddr {
     fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-        s.serialize_str(&format!("{}@{}", self.peer_id, self.addr))
+        let suffix = if self.quic { QUIC_SUFFIX } else { "" };
+        s.serialize_str(&format!("{}@{}{}", self.peer_id, self.addr, suffix))
     }
 }
 
@@ -72,7 +81,7 @@ impl<'a> serde::Deserialize<'a> for PeerAddr {
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePeerAddrError {
-    #[error("expected <PeerId>@<IP>:<port>, got \'{0}\'")]
+    #[error("expected <PeerId>@<IP>:<port>[/quic], got \'{0}\'")]
     Format(String),
     #[error("PeerId: {0}")]
     PeerId(#[source] unc_crypto::ParseKeyError),
@@ -87,9 +96,14 @@ impl std::str::FromStr for PeerAddr {
         if parts.len() != 2 {
             return Err(Self::Err::Format(s.to_string()));
         }
+        let (addr, quic) = match parts[1].strip_suffix(QUIC_SUFFIX) {
+            Some(addr) => (addr, true),
+            None => (parts[1], false),
+        };
         Ok(PeerAddr {
             peer_id: PeerId::new(parts[0].parse().map_err(Self::Err::PeerId)?),
-            addr: parts[1].parse().map_err(Self::Err::SocketAddr)?,
+            addr: addr.parse().map_err(Self::Err::SocketAddr)?,
+            quic,
         })
     }
 }
@@ -155,6 +169,11 @@ pub const MAX_ACCOUNT_DATA_SIZE_BYTES: usize = 10000; // 10kB
 /// increase the receive limit in one release then increase the send limit in the next.
 pub const MAX_SHARDS_PER_SNAPSHOT_HOST_INFO: usize = 512;
 
+/// Limit on the number of peers recorded in [`RoutedMessageV2::trace`]. The peers relaying
+/// a message with a full trace don't append themselves to it, and a message with a longer
+/// trace is rejected.
+pub const MAX_ROUTE_TRACE_HOPS: usize = 32;
+
 impl VersionedAccountData {
     /// Serializes AccountData to proto and signs it using `signer`.
     /// Panics if AccountData.account_id doesn't match signer.validator_id(),
@@ -350,6 +369,15 @@ pub struct Handshake {
     pub(crate) partial_edge_info: PartialEdgeInfo,
     /// Account owned by the sender.
     pub(crate) owned_account: Option<SignedOwnedAccount>,
+    /// Whether the sender wants to encrypt the connection.
+    pub(crate) encrypted_transport: bool,
+    /// Whether the sender accepts compressed frames.
+    pub(crate) message_compression: bool,
+    /// Whether the sender accepts QUIC connections, see `crate::quic`.
+    pub(crate) quic_transport: bool,
+    /// Signature of the TLS session of the QUIC connection by the sender, see `crate::quic`.
+    /// None for TCP connections.
+    pub(crate) quic_session_signature: Option<Signature>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, strum::IntoStaticStr)]
@@ -429,6 +457,12 @@ pub enum PeerMessage {
     StateRequestHeader(ShardId, CryptoHash),
     StateRequestPart(ShardId, CryptoHash, u64),
     VersionedStateResponse(StateResponseInfo),
+
+    /// Light client blocks and execution proofs, served by the full nodes to the light nodes.
+    LightClientBlockRequest(CryptoHash),
+    LightClientBlock(Box<LightClientBlockView>),
+    LightClientProofRequest(LightClientProofRequest),
+    LightClientProof(Box<LightClientExecutionProof>),
 }
 
 impl fmt::Display for PeerMessage {
@@ -497,6 +531,50 @@ impl PeerMessage {
             _ => self.into(),
         }
     }
+
+    /// Priority class of the message, when sent to a peer.
+    pub(crate) fn class(&self) -> MessageClass {
+        match self {
+            PeerMessage::Tier1Handshake(_)
+            | PeerMessage::Tier2Handshake(_)
+            | PeerMessage::HandshakeFailure(_, _)
+            | PeerMessage::LastEdge(_)
+            | PeerMessage::RequestUpdateNonce(_)
+            | PeerMessage::Disconnect(_)
+            | PeerMessage::Block(_)
+            | PeerMessage::Challenge(_) => MessageClass::Consensus,
+            PeerMessage::BlockHeadersRequest(_)
+            | PeerMessage::BlockHeaders(_)
+            | PeerMessage::BlockRequest(_)
+            | PeerMessage::StateRequestHeader(_, _)
+            | PeerMessage::StateRequestPart(_, _, _)
+            | PeerMessage::VersionedStateResponse(_)
+            | PeerMessage::LightClientBlockRequest(_)
+            | PeerMessage::LightClientBlock(_)
+            | PeerMessage::LightClientProofRequest(_)
+            | PeerMessage::LightClientProof(_) => MessageClass::Sync,
+            PeerMessage::SyncRoutingTable(_)
+            | PeerMessage::DistanceVector(_)
+            | PeerMessage::SyncAccountsData(_)
+            | PeerMessage::PeersRequest(_)
+            | PeerMessage::PeersResponse(_)
+            | PeerMessage::Transaction(_)
+            | PeerMessage::SyncSnapshotHosts(_) => MessageClass::Gossip,
+            PeerMessage::Routed(msg) => match &msg.body {
+                RoutedMessageBody::BlockApproval(_)
+                | RoutedMessageBody::ChunkEndorsement(_)
+                | RoutedMessageBody::Ping(_)
+                | RoutedMessageBody::Pong(_) => MessageClass::Consensus,
+                RoutedMessageBody::PartialEncodedChunkRequest(_)
+                | RoutedMessageBody::PartialEncodedChunkResponse(_)
+                | RoutedMessageBody::VersionedPartialEncodedChunk(_)
+                | RoutedMessageBody::PartialEncodedChunkForward(_)
+                | RoutedMessageBody::ChunkStateWitness(_) => MessageClass::ChunkDistribution,
+                RoutedMessageBody::StateResponse(_) => MessageClass::Sync,
+                _ => MessageClass::Gossip,
+            },
+        }
+    }
 }
 
 // TODO(#1313): Use Box
@@ -531,6 +609,7 @@ pub enum RoutedMessageBody {
     PartialEncodedChunkForward(PartialEncodedChunkForwardMsg),
     ChunkStateWitness(ChunkStateWitness),
     ChunkEndorsement(ChunkEndorsement),
+    DeliveryReceipt(DeliveryReceipt),
 }
 
 impl RoutedMessageBody {
@@ -598,6 +677,9 @@ impl fmt::Debug for RoutedMessageBody {
             RoutedMessageBody::_UnusedVersionedStateResponse => write!(f, "VersionedStateResponse"),
             RoutedMessageBody::ChunkStateWitness(_) => write!(f, "ChunkStateWitness"),
             RoutedMessageBody::ChunkEndorsement(_) => write!(f, "ChunkEndorsement"),
+            RoutedMessageBody::DeliveryReceipt(receipt) => {
+                write!(f, "DeliveryReceipt({})", receipt.msg_hash)
+            }
         }
     }
 }
@@ -635,6 +717,14 @@ pub struct RoutedMessageV2 {
     /// Number of peers this routed message travelled through.
     /// Doesn't include the peers that are the source and the destination of the message.
     pub num_hops: Option<i32>,
+    /// Set if the author requested a `DeliveryReceipt` for this message. Contains the peers
+    /// this routed message travelled through so far, in order, up to `MAX_ROUTE_TRACE_HOPS`.
+    /// Like `num_hops`, it is not covered by the signature: any relaying peer may request
+    /// a receipt or alter the hops. Therefore it should be used only for debugging,
+    /// the receipts are rate limited by the target (see
+    /// `NetworkConfig::delivery_receipts_rate_limit`) and the author accepts receipts only
+    /// for the messages it has traced itself.
+    pub trace: Option<Vec<PeerId>>,
 }
 
 impl std::ops::Deref for RoutedMessageV2 {
@@ -716,6 +806,15 @@ pub struct PartialEncodedChunkForwardMsg {
     pub parts: Vec<PartialEncodedChunkPart>,
 }
 
+/// Sent back to the author of a traced routed message by its target, see `RoutedMessageV2::trace`.
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, PartialEq, Eq, Clone, Debug)]
+pub struct DeliveryReceipt {
+    /// Hash of the delivered message.
+    pub msg_hash: CryptoHash,
+    /// Peers the message travelled through, in order.
+    pub hops: Vec<PeerId>,
+}
+
 /// Test code that someone become part of our protocol?
 #[derive(borsh::BorshSerialize, borsh::BorshDeserialize, PartialEq, Eq, Clone, Debug, Hash)]
 pub struct Ping {
@@ -837,6 +936,7 @@ impl RawRoutedMessage {
             },
             created_at: now,
             num_hops: Some(0),
+            trace: None,
         }
     }
 }
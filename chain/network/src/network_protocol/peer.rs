@@ -108,6 +108,10 @@ pub struct PeerChainInfoV2 {
     pub tracked_shards: Vec<ShardId>,
     /// Denote if a node is running in archival mode or not.
     pub archival: bool,
+    /// Denote if a node is a light node, which tracks only the headers and
+    /// serves neither blocks nor chunks. Not supported by borsh encoding.
+    #[borsh(skip)]
+    pub light: bool,
 }
 
 #[cfg(test)]
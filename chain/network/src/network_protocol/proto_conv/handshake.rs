@@ -44,6 +44,7 @@ impl From<&PeerChainInfoV2> for proto::PeerChainInfo {
             height: x.height,
             tracked_shards: x.tracked_shards.clone(),
             archival: x.archival,
+            light: x.light,
             ..Self::default()
         }
     }
@@ -57,6 +58,7 @@ impl TryFrom<&proto::PeerChainInfo> for PeerChainInfoV2 {
             height: p.height,
             tracked_shards: p.tracked_shards.clone(),
             archival: p.archival,
+            light: p.light,
         })
     }
 }
@@ -77,6 +79,8 @@ pub enum ParseHandshakeError {
     PartialEdgeInfo(ParseRequiredError<ParsePartialEdgeInfoError>),
     #[error("owned_account {0}")]
     OwnedAccount(ParseSignedOwnedAccountError),
+    #[error("quic_session_signature {0}")]
+    QuicSessionSignature(ParseSignatureError),
 }
 
 impl From<&Handshake> for proto::Handshake {
@@ -90,6 +94,10 @@ impl From<&Handshake> for proto::Handshake {
             sender_chain_info: MF::some((&x.sender_chain_info).into()),
             partial_edge_info: MF::some((&x.partial_edge_info).into()),
             owned_account: x.owned_account.as_ref().map(Into::into).into(),
+            encrypted_transport: x.encrypted_transport,
+            message_compression: x.message_compression,
+            quic_transport: x.quic_transport,
+            quic_session_signature: x.quic_session_signature.as_ref().map(Into::into).into(),
             ..Self::default()
         }
     }
@@ -120,6 +128,11 @@ impl TryFrom<&proto::Handshake> for Handshake {
                 .map_err(Self::Error::PartialEdgeInfo)?,
             owned_account: try_from_optional(&p.owned_account)
                 .map_err(Self::Error::OwnedAccount)?,
+            encrypted_transport: p.encrypted_transport,
+            message_compression: p.message_compression,
+            quic_transport: p.quic_transport,
+            quic_session_signature: try_from_optional(&p.quic_session_signature)
+                .map_err(Self::Error::QuicSessionSignature)?,
         })
     }
 }
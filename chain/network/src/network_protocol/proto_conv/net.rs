@@ -58,6 +58,7 @@ impl From<&PeerAddr> for proto::PeerAddr {
         Self {
             addr: MF::some((&x.addr).into()),
             peer_id: MF::some((&x.peer_id).into()),
+            quic: x.quic,
             ..Self::default()
         }
     }
@@ -69,6 +70,7 @@ impl TryFrom<&proto::PeerAddr> for PeerAddr {
         Ok(Self {
             addr: try_from_required(&x.addr).map_err(Self::Error::Addr)?,
             peer_id: try_from_required(&x.peer_id).map_err(Self::Error::PeerId)?,
+            quic: x.quic,
         })
     }
 }
@@ -5,10 +5,11 @@ use crate::network_protocol::proto::peer_message::Message_type as ProtoMT;
 use crate::network_protocol::proto::{self};
 use crate::network_protocol::state_sync::{SnapshotHostInfo, SyncSnapshotHosts};
 use crate::network_protocol::{
-    AdvertisedPeerDistance, Disconnect, DistanceVector, PeerMessage, PeersRequest, PeersResponse,
-    RoutingTableUpdate, SyncAccountsData,
+    AdvertisedPeerDistance, Disconnect, DistanceVector, LightClientExecutionProof,
+    LightClientProofRequest, PeerMessage, PeersRequest, PeersResponse, RoutingTableUpdate,
+    SyncAccountsData,
 };
-use crate::network_protocol::{RoutedMessage, RoutedMessageV2};
+use crate::network_protocol::{RoutedMessage, RoutedMessageV2, MAX_ROUTE_TRACE_HOPS};
 use crate::types::StateResponseInfo;
 use borsh::BorshDeserialize as _;
 use protobuf::MessageField as MF;
@@ -17,6 +18,7 @@ use unc_async::time::error::ComponentRange;
 use unc_primitives::block::{Block, BlockHeader};
 use unc_primitives::challenge::Challenge;
 use unc_primitives::transaction::SignedTransaction;
+use unc_primitives::views::LightClientBlockView;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParseRoutingTableUpdateError {
@@ -299,6 +301,10 @@ impl From<&PeerMessage> for proto::PeerMessage {
                     borsh: borsh::to_vec(&r.msg).unwrap(),
                     created_at: MF::from_option(r.created_at.as_ref().map(utc_to_proto)),
                     num_hops: r.num_hops,
+                    trace: MF::from_option(r.trace.as_ref().map(|hops| proto::RouteTrace {
+                        hops: hops.iter().map(Into::into).collect(),
+                        ..Default::default()
+                    })),
                     ..Default::default()
                 }),
                 PeerMessage::Disconnect(r) => ProtoMT::Disconnect(proto::Disconnect {
@@ -331,6 +337,30 @@ impl From<&PeerMessage> for proto::PeerMessage {
                         ..Default::default()
                     })
                 }
+                PeerMessage::LightClientBlockRequest(bh) => {
+                    ProtoMT::LightClientBlockRequest(proto::LightClientBlockRequest {
+                        last_block_hash: MF::some(bh.into()),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::LightClientBlock(b) => {
+                    ProtoMT::LightClientBlockResponse(proto::LightClientBlockResponse {
+                        borsh: borsh::to_vec(&b).unwrap(),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::LightClientProofRequest(r) => {
+                    ProtoMT::LightClientProofRequest(proto::LightClientProofRequest {
+                        borsh: borsh::to_vec(&r).unwrap(),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::LightClientProof(p) => {
+                    ProtoMT::LightClientProofResponse(proto::LightClientProofResponse {
+                        borsh: borsh::to_vec(&p).unwrap(),
+                        ..Default::default()
+                    })
+                }
             }),
             ..Default::default()
         }
@@ -341,6 +371,9 @@ pub type ParsePeersRequestError = std::io::Error;
 pub type ParseTransactionError = std::io::Error;
 pub type ParseRoutedError = std::io::Error;
 pub type ParseChallengeError = std::io::Error;
+pub type ParseLightClientBlockError = std::io::Error;
+pub type ParseLightClientProofRequestError = std::io::Error;
+pub type ParseLightClientProofError = std::io::Error;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePeerMessageError {
@@ -380,12 +413,24 @@ pub enum ParsePeerMessageError {
     Challenge(ParseChallengeError),
     #[error("routed_created_at: {0}")]
     RoutedCreatedAtTimestamp(ComponentRange),
+    #[error("routed_trace: {0}")]
+    RoutedTrace(ParseVecError<ParsePublicKeyError>),
+    #[error("routed_trace: got {0} hops, want at most {MAX_ROUTE_TRACE_HOPS}")]
+    RoutedTraceTooLong(usize),
     #[error("sync_accounts_data: {0}")]
     SyncAccountsData(ParseVecError<ParseSignedAccountDataError>),
     #[error("state_response: {0}")]
     StateResponse(ParseRequiredError<ParseStateInfoError>),
     #[error("sync_snapshot_hosts: {0}")]
     SyncSnapshotHosts(ParseSyncSnapshotHostsError),
+    #[error("light_client_block_request: {0}")]
+    LightClientBlockRequest(ParseRequiredError<ParseCryptoHashError>),
+    #[error("light_client_block_response: {0}")]
+    LightClientBlockResponse(ParseLightClientBlockError),
+    #[error("light_client_proof_request: {0}")]
+    LightClientProofRequest(ParseLightClientProofRequestError),
+    #[error("light_client_proof_response: {0}")]
+    LightClientProofResponse(ParseLightClientProofError),
 }
 
 impl TryFrom<&proto::PeerMessage> for PeerMessage {
@@ -465,6 +510,13 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
                     .transpose()
                     .map_err(Self::Error::RoutedCreatedAtTimestamp)?,
                 num_hops: r.num_hops,
+                trace: match r.trace.as_ref() {
+                    Some(t) if t.hops.len() > MAX_ROUTE_TRACE_HOPS => {
+                        return Err(Self::Error::RoutedTraceTooLong(t.hops.len()))
+                    }
+                    Some(t) => Some(try_from_slice(&t.hops).map_err(Self::Error::RoutedTrace)?),
+                    None => None,
+                },
             })),
             ProtoMT::Disconnect(d) => PeerMessage::Disconnect(Disconnect {
                 remove_from_connection_store: d.remove_from_connection_store,
@@ -487,6 +539,22 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             ProtoMT::SyncSnapshotHosts(srh) => PeerMessage::SyncSnapshotHosts(
                 srh.try_into().map_err(Self::Error::SyncSnapshotHosts)?,
             ),
+            ProtoMT::LightClientBlockRequest(r) => PeerMessage::LightClientBlockRequest(
+                try_from_required(&r.last_block_hash)
+                    .map_err(Self::Error::LightClientBlockRequest)?,
+            ),
+            ProtoMT::LightClientBlockResponse(r) => PeerMessage::LightClientBlock(Box::new(
+                LightClientBlockView::try_from_slice(&r.borsh)
+                    .map_err(Self::Error::LightClientBlockResponse)?,
+            )),
+            ProtoMT::LightClientProofRequest(r) => PeerMessage::LightClientProofRequest(
+                LightClientProofRequest::try_from_slice(&r.borsh)
+                    .map_err(Self::Error::LightClientProofRequest)?,
+            ),
+            ProtoMT::LightClientProofResponse(r) => PeerMessage::LightClientProof(Box::new(
+                LightClientExecutionProof::try_from_slice(&r.borsh)
+                    .map_err(Self::Error::LightClientProofResponse)?,
+            )),
         })
     }
 }
@@ -13,9 +13,10 @@ use std::net;
 use std::sync::Arc;
 use unc_async::time;
 use unc_crypto::{InMemorySigner, KeyType, SecretKey};
-use unc_primitives::block::{genesis_chunks, Block, BlockHeader, GenesisId};
+use unc_primitives::block::{genesis_chunks, ApprovalInner, Block, BlockHeader, GenesisId};
 use unc_primitives::challenge::{BlockDoubleSign, Challenge, ChallengeBody};
 use unc_primitives::hash::CryptoHash;
+use unc_primitives::merkle::{Direction, MerklePathItem};
 use unc_primitives::network::{AnnounceAccount, PeerId};
 use unc_primitives::num_rational::Ratio;
 use unc_primitives::sharding::{
@@ -23,9 +24,14 @@ use unc_primitives::sharding::{
     ReedSolomonWrapper, ShardChunk,
 };
 use unc_primitives::transaction::SignedTransaction;
+use unc_primitives::types::validator_power_and_pledge::ValidatorPowerAndPledge;
 use unc_primitives::types::{AccountId, BlockHeight, EpochId, StateRoot};
 use unc_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
 use unc_primitives::version;
+use unc_primitives::views::{
+    ExecutionMetadataView, ExecutionOutcomeView, ExecutionOutcomeWithIdView, ExecutionStatusView,
+    LightClientBlockView,
+};
 
 pub fn make_genesis_block(_clock: &time::Clock, chunks: Vec<ShardChunk>) -> Block {
     Block::genesis(
@@ -181,6 +187,58 @@ pub fn make_challenge<R: Rng>(rng: &mut R) -> Challenge {
     )
 }
 
+pub fn make_light_client_block<R: Rng>(rng: &mut R, chain: &Chain) -> LightClientBlockView {
+    let header = chain.blocks[5].header().clone();
+    let signer = make_validator_signer(rng);
+    let approval = ApprovalInner::Endorsement(make_hash(rng));
+    let bp = ValidatorPowerAndPledge::new_v1(
+        signer.validator_id().clone(),
+        signer.public_key(),
+        1,
+        1000,
+    );
+    LightClientBlockView {
+        prev_block_hash: *header.prev_hash(),
+        next_block_inner_hash: make_hash(rng),
+        inner_rest_hash: make_hash(rng),
+        next_bps: Some(vec![bp.into()]),
+        approvals_after_next: vec![
+            Some(Box::new(signer.sign_approval(&approval, header.height() + 2))),
+            None,
+        ],
+        inner_lite: header.into(),
+    }
+}
+
+pub fn make_light_client_execution_proof<R: Rng>(
+    rng: &mut R,
+    chain: &Chain,
+) -> LightClientExecutionProof {
+    let header = chain.blocks[5].header().clone();
+    LightClientExecutionProof {
+        outcome_proof: ExecutionOutcomeWithIdView {
+            proof: vec![MerklePathItem { hash: make_hash(rng), direction: Direction::Left }],
+            block_hash: *header.hash(),
+            id: make_hash(rng),
+            outcome: ExecutionOutcomeView {
+                logs: vec!["log".to_string()],
+                receipt_ids: vec![make_hash(rng)],
+                gas_burnt: rng.gen(),
+                tokens_burnt: rng.gen(),
+                executor_id: make_account_id(rng),
+                status: ExecutionStatusView::SuccessValue(rng.gen::<[u8; 10]>().to_vec()),
+                metadata: ExecutionMetadataView::default(),
+            },
+        },
+        outcome_root_proof: vec![MerklePathItem {
+            hash: make_hash(rng),
+            direction: Direction::Right,
+        }],
+        block_header_lite: header.into(),
+        block_proof: vec![MerklePathItem { hash: make_hash(rng), direction: Direction::Left }],
+    }
+}
+
 // Based on ShardsManager::prepare_partial_encoded_chunk_response_from_chunk.
 // I give no guarantee that it will produce correct data, I'm just approximating
 // the real thing, since this functionality is not encapsulated in
@@ -291,6 +349,7 @@ impl Chain {
             genesis_id: self.genesis_id.clone(),
             tracked_shards: Default::default(),
             archival: false,
+            light: false,
             height: self.height(),
         }
     }
@@ -342,6 +401,10 @@ pub fn make_handshake<R: Rng>(rng: &mut R, chain: &Chain) -> Handshake {
         sender_chain_info: chain.get_peer_chain_info(),
         partial_edge_info: make_partial_edge(rng),
         owned_account: None,
+        encrypted_transport: false,
+        message_compression: false,
+        quic_transport: false,
+        quic_session_signature: None,
     }
 }
 
@@ -367,7 +430,11 @@ pub fn make_addr<R: Rng>(rng: &mut R) -> net::SocketAddr {
 }
 
 pub fn make_peer_addr(rng: &mut impl Rng, ip: net::IpAddr) -> PeerAddr {
-    PeerAddr { addr: net::SocketAddr::new(ip, rng.gen()), peer_id: make_peer_id(rng) }
+    PeerAddr {
+        addr: net::SocketAddr::new(ip, rng.gen()),
+        peer_id: make_peer_id(rng),
+        quic: rng.gen(),
+    }
 }
 
 pub fn make_account_data(
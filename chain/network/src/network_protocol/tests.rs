@@ -5,9 +5,11 @@ use crate::testonly::make_rng;
 use crate::types::{Disconnect, HandshakeFailureReason, PeerMessage};
 use crate::types::{PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg};
 use anyhow::{bail, Context as _};
+use assert_matches::assert_matches;
 use itertools::Itertools as _;
 use rand::Rng as _;
 use unc_async::time;
+use unc_primitives::types::TransactionOrReceiptId;
 
 #[test]
 fn deduplicate_edges() {
@@ -56,13 +58,49 @@ fn bad_account_data_size() {
     assert!(ad.sign(&signer).is_err());
 }
 
+#[test]
+fn route_trace_is_limited() {
+    let mut rng = make_rng(19385389);
+    let rng = &mut rng;
+    let source = data::make_peer_id(rng);
+    let mut msg =
+        data::make_routed_message(rng, RoutedMessageBody::Ping(Ping { nonce: 0, source }));
+    msg.trace = Some((0..MAX_ROUTE_TRACE_HOPS).map(|_| data::make_peer_id(rng)).collect());
+    let msg = PeerMessage::Routed(Box::new(msg));
+    let got = PeerMessage::deserialize(Encoding::Proto, &msg.serialize(Encoding::Proto)).unwrap();
+    assert_eq!(msg, got);
+
+    let PeerMessage::Routed(mut msg) = msg else { unreachable!() };
+    msg.trace.as_mut().unwrap().push(data::make_peer_id(rng));
+    let msg = PeerMessage::Routed(msg);
+    assert_matches!(
+        PeerMessage::deserialize(Encoding::Proto, &msg.serialize(Encoding::Proto)),
+        Err(ParsePeerMessageError::ProtoConv(_))
+    );
+}
+
+#[test]
+fn parse_peer_addr() {
+    let peer_id = "ed25519:6DSjZ8mvsRZDvFqFxo8tCKePG96omXW7eVYVSySmDk8e";
+    let addr: PeerAddr = format!("{peer_id}@1.2.3.4:24567").parse().unwrap();
+    assert_eq!(addr.addr, "1.2.3.4:24567".parse().unwrap());
+    assert!(!addr.quic);
+    let addr: PeerAddr = format!("{peer_id}@1.2.3.4:24567/quic").parse().unwrap();
+    assert_eq!(addr.addr, "1.2.3.4:24567".parse().unwrap());
+    assert!(addr.quic);
+    assert!(format!("{peer_id}@1.2.3.4:24567/tcp").parse::<PeerAddr>().is_err());
+}
+
 #[test]
 fn serialize_deserialize_protobuf_only() {
     let mut rng = make_rng(39521947542);
     let mut clock = time::FakeClock::default();
     let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let mut light_handshake = data::make_handshake(&mut rng, &chain);
+    light_handshake.sender_chain_info.light = true;
     let msgs = [
         PeerMessage::Tier1Handshake(data::make_handshake(&mut rng, &chain)),
+        PeerMessage::Tier2Handshake(light_handshake),
         PeerMessage::SyncAccountsData(SyncAccountsData {
             accounts_data: (0..4)
                 .map(|_| Arc::new(data::make_signed_account_data(&mut rng, &clock.clock())))
@@ -70,6 +108,18 @@ fn serialize_deserialize_protobuf_only() {
             incremental: true,
             requesting_full_sync: true,
         }),
+        PeerMessage::LightClientBlockRequest(*chain.blocks[5].hash()),
+        PeerMessage::LightClientBlock(Box::new(data::make_light_client_block(&mut rng, &chain))),
+        PeerMessage::LightClientProofRequest(LightClientProofRequest {
+            id: TransactionOrReceiptId::Transaction {
+                transaction_hash: data::make_hash(&mut rng),
+                sender_id: data::make_account_id(&mut rng),
+            },
+            light_client_head: *chain.blocks[5].hash(),
+        }),
+        PeerMessage::LightClientProof(Box::new(data::make_light_client_execution_proof(
+            &mut rng, &chain,
+        ))),
     ];
     for m in msgs {
         let m2 = PeerMessage::deserialize(Encoding::Proto, &m.serialize(Encoding::Proto))
@@ -0,0 +1,175 @@
+//! Delivery of the messages received from peers to the Client and the ShardsManager.
+//!
+//! Shared by `PeerActor` and by the traffic `Replayer`, so that a replayed message
+//! reaches the node's components exactly the way a received one does.
+use crate::client;
+use crate::network_protocol::{PeerMessage, RoutedMessageBody, StateResponseInfo};
+use crate::shards_manager::ShardsManagerRequestFromNetwork;
+use crate::types::ReasonForBan;
+use unc_async::messaging::Sender;
+use unc_async::time;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::network::PeerId;
+
+/// Response to a message received from a peer.
+pub(crate) enum Response {
+    /// Message to be sent back to the peer.
+    Direct(PeerMessage),
+    /// Body of a routed message to be sent back along the route of the
+    /// received routed message with hash `route_back`.
+    Routed { route_back: CryptoHash, body: RoutedMessageBody },
+}
+
+/// Delivers a message received from `peer_id` to the Client or the ShardsManager.
+/// Routed messages have to be addressed to this node, forwarding them is up to the caller.
+/// `was_requested` tells whether a received block has been requested by this node.
+pub(crate) async fn receive_message(
+    clock: &time::Clock,
+    client: &dyn client::Client,
+    shards_manager: &Sender<ShardsManagerRequestFromNetwork>,
+    peer_id: PeerId,
+    msg: PeerMessage,
+    was_requested: bool,
+) -> Result<Option<Response>, ReasonForBan> {
+    Ok(match msg {
+        PeerMessage::Routed(msg) => {
+            let route_back = msg.hash();
+            receive_routed_message(clock, client, shards_manager, peer_id, route_back, msg.msg.body)
+                .await?
+                .map(|body| Response::Routed { route_back, body })
+        }
+        PeerMessage::BlockRequest(hash) => {
+            client.block_request(hash).await.map(|b| Response::Direct(PeerMessage::Block(*b)))
+        }
+        PeerMessage::BlockHeadersRequest(hashes) => client
+            .block_headers_request(hashes)
+            .await
+            .map(|headers| Response::Direct(PeerMessage::BlockHeaders(headers))),
+        PeerMessage::Block(block) => {
+            client.block(block, peer_id, was_requested).await;
+            None
+        }
+        PeerMessage::Transaction(transaction) => {
+            client.transaction(transaction, /*is_forwarded=*/ false).await;
+            None
+        }
+        PeerMessage::BlockHeaders(headers) => {
+            client.block_headers(headers, peer_id).await?;
+            None
+        }
+        PeerMessage::Challenge(challenge) => {
+            client.challenge(challenge).await;
+            None
+        }
+        PeerMessage::StateRequestHeader(shard_id, sync_hash) => client
+            .state_request_header(shard_id, sync_hash)
+            .await?
+            .map(|info| Response::Direct(PeerMessage::VersionedStateResponse(info))),
+        PeerMessage::StateRequestPart(shard_id, sync_hash, part_id) => client
+            .state_request_part(shard_id, sync_hash, part_id)
+            .await?
+            .map(|info| Response::Direct(PeerMessage::VersionedStateResponse(info))),
+        PeerMessage::VersionedStateResponse(info) => {
+            //TODO: Route to state sync actor.
+            client.state_response(info).await;
+            None
+        }
+        PeerMessage::LightClientBlockRequest(hash) => client
+            .light_client_block_request(hash)
+            .await
+            .map(|block| Response::Direct(PeerMessage::LightClientBlock(block))),
+        PeerMessage::LightClientBlock(block) => {
+            client.light_client_block(*block, peer_id).await?;
+            None
+        }
+        PeerMessage::LightClientProofRequest(request) => client
+            .light_client_proof_request(request)
+            .await
+            .map(|proof| Response::Direct(PeerMessage::LightClientProof(proof))),
+        PeerMessage::LightClientProof(proof) => {
+            client.light_client_proof(*proof, peer_id).await?;
+            None
+        }
+        msg => {
+            tracing::error!(target: "network", "Peer received unexpected type: {:?}", msg);
+            None
+        }
+    })
+}
+
+async fn receive_routed_message(
+    clock: &time::Clock,
+    client: &dyn client::Client,
+    shards_manager: &Sender<ShardsManagerRequestFromNetwork>,
+    peer_id: PeerId,
+    msg_hash: CryptoHash,
+    body: RoutedMessageBody,
+) -> Result<Option<RoutedMessageBody>, ReasonForBan> {
+    let _span = tracing::trace_span!(
+        target: "network",
+        "receive_routed_message",
+        "type" = <&RoutedMessageBody as Into<&'static str>>::into(&body)
+    )
+    .entered();
+    Ok(match body {
+        RoutedMessageBody::TxStatusRequest(account_id, tx_hash) => client
+            .tx_status_request(account_id, tx_hash)
+            .await
+            .map(|v| RoutedMessageBody::TxStatusResponse(*v)),
+        RoutedMessageBody::TxStatusResponse(tx_result) => {
+            client.tx_status_response(tx_result).await;
+            None
+        }
+        RoutedMessageBody::StateResponse(info) => {
+            client.state_response(StateResponseInfo::V1(info)).await;
+            None
+        }
+        RoutedMessageBody::BlockApproval(approval) => {
+            client.block_approval(approval, peer_id).await;
+            None
+        }
+        RoutedMessageBody::ForwardTx(transaction) => {
+            client.transaction(transaction, /*is_forwarded=*/ true).await;
+            None
+        }
+        RoutedMessageBody::PartialEncodedChunkRequest(request) => {
+            shards_manager.send(
+                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequest {
+                    partial_encoded_chunk_request: request,
+                    route_back: msg_hash,
+                },
+            );
+            None
+        }
+        RoutedMessageBody::PartialEncodedChunkResponse(response) => {
+            shards_manager.send(
+                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
+                    partial_encoded_chunk_response: response,
+                    received_time: clock.now().into(),
+                },
+            );
+            None
+        }
+        RoutedMessageBody::VersionedPartialEncodedChunk(chunk) => {
+            shards_manager.send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(chunk));
+            None
+        }
+        RoutedMessageBody::PartialEncodedChunkForward(msg) => {
+            shards_manager
+                .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(msg));
+            None
+        }
+        RoutedMessageBody::ChunkStateWitness(witness) => {
+            client.chunk_state_witness(witness).await;
+            None
+        }
+        RoutedMessageBody::ChunkEndorsement(endorsement) => {
+            client.chunk_endorsement(endorsement).await;
+            None
+        }
+        body => {
+            tracing::error!(target: "network", "Peer received unexpected message type: {:?}", body);
+            None
+        }
+    })
+}
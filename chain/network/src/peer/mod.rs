@@ -1,4 +1,7 @@
+pub(crate) mod dispatch;
 pub(crate) mod peer_actor;
+pub(crate) mod noise;
+mod send_queue;
 mod stream;
 mod tracker;
 mod transfer_stats;
@@ -0,0 +1,246 @@
+//! Noise-based encryption of peer connections.
+//!
+//! After both peers advertised support in their `Handshake`, they run the Noise
+//! XX handshake over the framed TCP stream and encrypt all subsequent frames.
+//! Noise static keys are generated per connection; each side authenticates its
+//! static key by signing it with its node key, so that the encrypted channel is
+//! bound to the `PeerId` which was already verified by the `Handshake`.
+//!
+//! Note that the negotiation itself happens over the plaintext `Handshake`, so
+//! a man-in-the-middle can downgrade the connection to plaintext, but it cannot
+//! read or tamper with an encrypted one.
+use borsh::BorshDeserialize as _;
+use unc_crypto::{SecretKey, Signature};
+use unc_primitives::network::PeerId;
+
+/// Noise protocol name. XX lets both sides learn the static key of the
+/// other side, which is then verified against the node key signature.
+const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+/// Maximal length of a single Noise message.
+const MAX_MESSAGE_LEN: usize = 65535;
+/// Length of the authentication tag added to every encrypted message.
+const TAG_LEN: usize = 16;
+/// Maximal length of plaintext which fits into a single Noise message.
+const MAX_PLAINTEXT_LEN: usize = MAX_MESSAGE_LEN - TAG_LEN;
+/// Domain separator of the signature of the Noise static key.
+const STATIC_KEY_SIGNATURE_PREFIX: &[u8] = b"unc-network-noise-static-key:";
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("noise: {0}")]
+    Noise(#[from] snow::Error),
+    #[error("invalid signature of the noise static key")]
+    InvalidStaticKeySignature,
+    #[error("unexpected message after the noise handshake has finished")]
+    UnexpectedHandshakeMessage,
+    #[error("empty encrypted frame")]
+    EmptyFrame,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Role {
+    /// Side which sends the first handshake message, i.e. the outbound peer.
+    Initiator,
+    Responder,
+}
+
+/// Upper bound of the length of `plaintext_len` bytes after encryption.
+pub(crate) const fn encrypted_len(plaintext_len: usize) -> usize {
+    let chunks = if plaintext_len == 0 { 1 } else { plaintext_len.div_ceil(MAX_PLAINTEXT_LEN) };
+    plaintext_len + chunks * TAG_LEN
+}
+
+fn static_key_signature_payload(static_key: &[u8]) -> Vec<u8> {
+    [STATIC_KEY_SIGNATURE_PREFIX, static_key].concat()
+}
+
+/// State of the Noise handshake in progress.
+pub(crate) struct Handshake {
+    state: snow::HandshakeState,
+    role: Role,
+    /// Signature of the local static key with the node key.
+    static_key_signature: Vec<u8>,
+    /// Expected id of the other side of the connection.
+    peer_id: PeerId,
+}
+
+impl Handshake {
+    /// Starts the handshake. For the initiator also returns the first message
+    /// to be sent to the responder.
+    pub fn new(
+        role: Role,
+        node_key: &SecretKey,
+        peer_id: PeerId,
+    ) -> Result<(Self, Option<Vec<u8>>), Error> {
+        let params: snow::params::NoiseParams = NOISE_PARAMS.parse()?;
+        let builder = snow::Builder::new(params);
+        let keypair = builder.generate_keypair()?;
+        let builder = builder.local_private_key(&keypair.private);
+        let state = match role {
+            Role::Initiator => builder.build_initiator()?,
+            Role::Responder => builder.build_responder()?,
+        };
+        let static_key_signature =
+            borsh::to_vec(&node_key.sign(&static_key_signature_payload(&keypair.public)))
+                .expect("serializing a signature cannot fail");
+        let mut this = Self { state, role, static_key_signature, peer_id };
+        let first_message = match role {
+            // -> e
+            Role::Initiator => Some(this.write_message(&[])?),
+            Role::Responder => None,
+        };
+        Ok((this, first_message))
+    }
+
+    fn write_message(&mut self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0; MAX_MESSAGE_LEN];
+        let n = self.state.write_message(payload, &mut buf)?;
+        buf.truncate(n);
+        Ok(buf)
+    }
+
+    /// Verifies the signature of the remote static key, sent as a payload of
+    /// the message which transmitted the key.
+    fn verify_remote_static_key(&self, payload: &[u8]) -> Result<(), Error> {
+        let remote_static =
+            self.state.get_remote_static().ok_or(Error::InvalidStaticKeySignature)?;
+        let signature =
+            Signature::try_from_slice(payload).map_err(|_| Error::InvalidStaticKeySignature)?;
+        if !signature
+            .verify(&static_key_signature_payload(remote_static), self.peer_id.public_key())
+        {
+            return Err(Error::InvalidStaticKeySignature);
+        }
+        Ok(())
+    }
+
+    /// Processes a handshake message of the other side. Returns the message
+    /// to be sent back, if any.
+    pub fn read_message(&mut self, message: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        if self.state.is_handshake_finished() {
+            return Err(Error::UnexpectedHandshakeMessage);
+        }
+        let mut payload = vec![0; MAX_MESSAGE_LEN];
+        let n = self.state.read_message(message, &mut payload)?;
+        payload.truncate(n);
+        match self.role {
+            // <- e
+            // -> e, ee, s, es
+            Role::Responder if !self.state.is_handshake_finished() => {
+                let signature = self.static_key_signature.clone();
+                Ok(Some(self.write_message(&signature)?))
+            }
+            // <- s, se
+            Role::Responder => {
+                self.verify_remote_static_key(&payload)?;
+                Ok(None)
+            }
+            // <- e, ee, s, es
+            // -> s, se
+            Role::Initiator => {
+                self.verify_remote_static_key(&payload)?;
+                let signature = self.static_key_signature.clone();
+                Ok(Some(self.write_message(&signature)?))
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state.is_handshake_finished()
+    }
+
+    pub fn into_transport(self) -> Result<Transport, Error> {
+        Ok(Transport(self.state.into_transport_mode()?))
+    }
+}
+
+/// Encryption state of a connection after the handshake has finished.
+pub(crate) struct Transport(snow::TransportState);
+
+impl Transport {
+    /// Encrypts a frame. Frames longer than a single Noise message are split
+    /// into chunks of `MAX_PLAINTEXT_LEN`, so that the receiver can split the
+    /// ciphertext without any additional framing.
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut ciphertext = vec![0; encrypted_len(plaintext.len())];
+        let mut offset = 0;
+        let mut chunks = plaintext.chunks(MAX_PLAINTEXT_LEN);
+        // An empty frame is encrypted as a single empty chunk.
+        let first = chunks.next().unwrap_or(&[]);
+        for chunk in std::iter::once(first).chain(chunks) {
+            offset += self.0.write_message(chunk, &mut ciphertext[offset..])?;
+        }
+        ciphertext.truncate(offset);
+        Ok(ciphertext)
+    }
+
+    pub fn decrypt(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        if ciphertext.is_empty() {
+            return Err(Error::EmptyFrame);
+        }
+        let mut plaintext = vec![0; ciphertext.len()];
+        let mut offset = 0;
+        for chunk in ciphertext.chunks(MAX_MESSAGE_LEN) {
+            offset += self.0.read_message(chunk, &mut plaintext[offset..])?;
+        }
+        plaintext.truncate(offset);
+        Ok(plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unc_crypto::KeyType;
+
+    fn handshake(
+        initiator_key: &SecretKey,
+        responder_key: &SecretKey,
+        responder_id: PeerId,
+    ) -> Result<(Transport, Transport), Error> {
+        let initiator_id = PeerId::new(initiator_key.public_key());
+        let (mut initiator, msg1) = Handshake::new(Role::Initiator, initiator_key, responder_id)?;
+        let (mut responder, _) = Handshake::new(Role::Responder, responder_key, initiator_id)?;
+        let msg2 = responder.read_message(&msg1.unwrap())?.unwrap();
+        let msg3 = initiator.read_message(&msg2)?.unwrap();
+        assert!(initiator.is_finished());
+        assert_eq!(responder.read_message(&msg3)?, None);
+        assert!(responder.is_finished());
+        Ok((initiator.into_transport()?, responder.into_transport()?))
+    }
+
+    #[test]
+    fn encrypted_frames_roundtrip() {
+        let initiator_key = SecretKey::from_seed(KeyType::ED25519, "initiator");
+        let responder_key = SecretKey::from_seed(KeyType::ED25519, "responder");
+        let (mut initiator, mut responder) =
+            handshake(&initiator_key, &responder_key, PeerId::new(responder_key.public_key()))
+                .unwrap();
+        for len in [0, 1, MAX_PLAINTEXT_LEN, MAX_PLAINTEXT_LEN + 1, 3 * MAX_MESSAGE_LEN] {
+            let frame: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let encrypted = initiator.encrypt(&frame).unwrap();
+            assert_eq!(encrypted.len(), encrypted_len(len));
+            if len > 0 {
+                assert_ne!(encrypted[..len], frame[..]);
+            }
+            assert_eq!(responder.decrypt(&encrypted).unwrap(), frame);
+            let encrypted = responder.encrypt(&frame).unwrap();
+            assert_eq!(initiator.decrypt(&encrypted).unwrap(), frame);
+        }
+        // Tampered frames are rejected.
+        let mut encrypted = initiator.encrypt(b"transaction").unwrap();
+        encrypted[0] ^= 1;
+        assert!(responder.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn handshake_with_unexpected_peer_fails() {
+        let initiator_key = SecretKey::from_seed(KeyType::ED25519, "initiator");
+        let responder_key = SecretKey::from_seed(KeyType::ED25519, "responder");
+        let other_key = SecretKey::from_seed(KeyType::ED25519, "other");
+        assert!(matches!(
+            handshake(&initiator_key, &responder_key, PeerId::new(other_key.public_key())),
+            Err(Error::InvalidStaticKeySignature)
+        ));
+    }
+}
@@ -1,24 +1,28 @@
 use crate::accounts_data::AccountDataError;
 use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
+use crate::concurrency::rate;
 use crate::config::PEERS_RESPONSE_MAX_PEERS;
 use crate::network_protocol::SnapshotHostInfoVerificationError;
 use crate::network_protocol::{
     DistanceVector, Edge, EdgeState, Encoding, OwnedAccount, ParsePeerMessageError,
     PartialEdgeInfo, PeerChainInfoV2, PeerIdOrHash, PeerInfo, PeersRequest, PeersResponse,
     RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate, StateResponseInfo, SyncAccountsData,
-    SyncSnapshotHosts,
+    SyncSnapshotHosts, MAX_ROUTE_TRACE_HOPS,
 };
+use crate::peer::dispatch;
+use crate::peer::noise;
 use crate::peer::stream;
-use crate::peer::tracker::Tracker;
+use crate::peer::tracker::{PendingRequest, Tracker};
 use crate::peer_manager::connection;
 use crate::peer_manager::network_state::{NetworkState, PRUNE_EDGES_AFTER};
 use crate::peer_manager::peer_manager_actor::Event;
 use crate::peer_manager::peer_manager_actor::MAX_TIER2_PEERS;
 use crate::private_actix::{RegisterPeerError, SendMessage};
+use crate::quic;
+use crate::recorder::Direction;
 use crate::routing::edge::verify_nonce;
 use crate::routing::NetworkTopologyChange;
-use crate::shards_manager::ShardsManagerRequestFromNetwork;
 use crate::snapshot_hosts::SnapshotHostInfoError;
 use crate::stats::metrics;
 use crate::tcp;
@@ -47,7 +51,7 @@ use unc_primitives::network::{AnnounceAccount, PeerId};
 use unc_primitives::types::EpochId;
 use unc_primitives::utils::DisplayOption;
 use unc_primitives::version::{
-    ProtocolVersion, PEER_MIN_ALLOWED_PROTOCOL_VERSION, PROTOCOL_VERSION,
+    ProtocolFeature, ProtocolVersion, PEER_MIN_ALLOWED_PROTOCOL_VERSION, PROTOCOL_VERSION,
 };
 
 /// How often to request peers from active peers.
@@ -68,6 +72,11 @@ pub(crate) const DROP_DUPLICATED_MESSAGES_PERIOD: time::Duration = time::Duratio
 const SYNC_LATEST_BLOCK_INTERVAL: time::Duration = time::Duration::seconds(60);
 /// How often to perform a full sync of AccountsData with the peer.
 const ACCOUNTS_DATA_FULL_SYNC_INTERVAL: time::Duration = time::Duration::minutes(10);
+/// How long to wait for a response to a block, headers or state request
+/// before counting it as a timeout in the peer's reputation.
+const PENDING_REQUEST_TIMEOUT: time::Duration = time::Duration::seconds(30);
+/// How often to check for timed out requests.
+const PENDING_REQUESTS_CHECK_INTERVAL: time::Duration = time::Duration::seconds(5);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConnectionClosedEvent {
@@ -114,6 +123,10 @@ pub(crate) enum ClosingReason {
     TooLargeClockSkew,
     #[error("owned_account.peer_id doesn't match handshake.sender_peer_id")]
     OwnedAccountMismatch,
+    #[error("QUIC session is not signed by handshake.sender_peer_id")]
+    QuicSessionMismatch,
+    #[error("plaintext connections are not allowed")]
+    PlaintextNotAllowed,
     #[error("PeerActor stopped NOT via PeerActor::stop()")]
     Unknown,
 }
@@ -135,7 +148,9 @@ impl ClosingReason {
             ClosingReason::DisconnectMessage => false, // graceful disconnect
             ClosingReason::TooLargeClockSkew => true, // reconnect will fail for the same reason
             ClosingReason::OwnedAccountMismatch => true, // misbehaving peer
-            ClosingReason::Unknown => false,        // only happens in tests
+            ClosingReason::QuicSessionMismatch => false, // may be a man-in-the-middle rather than the peer
+            ClosingReason::PlaintextNotAllowed => false, // may be a man-in-the-middle rather than the peer
+            ClosingReason::Unknown => false,             // only happens in tests
         }
     }
 }
@@ -164,6 +179,8 @@ pub(crate) struct PeerActor {
     stats: Arc<connection::Stats>,
     /// Cache of recently routed messages, this allows us to drop duplicates
     routed_message_cache: LruCache<(PeerId, PeerIdOrHash, Signature), time::Instant>,
+    /// Rate limit of the light client requests served to the peer.
+    light_client_requests: rate::Limiter,
     /// Whether we detected support for protocol buffers during handshake.
     protocol_buffers_supported: bool,
     /// Whether the PeerActor should skip protobuf support detection and use
@@ -303,7 +320,17 @@ impl PeerActor {
                 let peer_addr = stream.peer_addr;
                 let stream_type = stream.type_.clone();
                 let stats = Arc::new(connection::Stats::default());
-                let framed = stream::FramedStream::spawn(ctx, stream, stats.clone());
+                let framed = stream::FramedStream::spawn(
+                    ctx,
+                    &clock,
+                    stream,
+                    &network_state.config.bandwidth_caps,
+                    stats.clone(),
+                );
+                let light_client_requests = rate::Limiter::new(
+                    network_state.config.light_client_requests_rate_limit,
+                    clock.now(),
+                );
                 Self {
                     closing_reason: None,
                     clock,
@@ -319,6 +346,7 @@ impl PeerActor {
                     tracker: Default::default(),
                     stats,
                     routed_message_cache: LruCache::new(ROUTED_MESSAGE_CACHE_SIZE),
+                    light_client_requests,
                     protocol_buffers_supported: false,
                     force_encoding,
                     peer_info: match &stream_type {
@@ -373,6 +401,9 @@ impl PeerActor {
         if let (PeerStatus::Ready(conn), PeerMessage::PeersRequest(_)) = (&self.peer_status, msg) {
             conn.last_time_peer_requested.store(Some(self.clock.now()));
         }
+        if let Some(recorder) = &self.network_state.traffic_recorder {
+            recorder.record(&self.clock, Direction::Outbound, self.other_peer_id().cloned(), msg);
+        }
         if let Some(enc) = self.encoding() {
             return self.send_message_with_encoding(msg, enc);
         }
@@ -394,7 +425,24 @@ impl PeerActor {
             // peers to update its height at the peer. In the future we will introduce a new
             // peer message type for that and then we can enable this check again.
             //PeerMessage::Block(b) if self.tracker.lock().has_received(b.hash()) => return,
-            PeerMessage::BlockRequest(h) => self.tracker.lock().push_request(*h),
+            PeerMessage::BlockRequest(h) => {
+                let mut tracker = self.tracker.lock();
+                tracker.push_request(*h);
+                tracker.push_pending(PendingRequest::Block(*h), self.clock.now());
+            }
+            PeerMessage::BlockHeadersRequest(_) => {
+                self.tracker.lock().push_pending(PendingRequest::BlockHeaders, self.clock.now())
+            }
+            PeerMessage::StateRequestHeader(shard_id, sync_hash) => self
+                .tracker
+                .lock()
+                .push_pending(PendingRequest::StateHeader(*shard_id, *sync_hash), self.clock.now()),
+            PeerMessage::StateRequestPart(shard_id, sync_hash, part_id) => {
+                self.tracker.lock().push_pending(
+                    PendingRequest::StatePart(*shard_id, *sync_hash, *part_id),
+                    self.clock.now(),
+                )
+            }
             PeerMessage::SyncAccountsData(d) => metrics::SYNC_ACCOUNTS_DATA
                 .with_label_values(&[
                     "sent",
@@ -412,7 +460,8 @@ impl PeerActor {
         self.tracker.lock().increment_sent(&self.clock, bytes.len() as u64);
         let bytes_len = bytes.len();
         tracing::trace!(target: "network", msg_len = bytes_len);
-        self.framed.send(stream::Frame(bytes));
+        let compression = self.compression(msg_type, bytes_len);
+        self.framed.push(msg.class(), stream::Frame(bytes), compression);
         metrics::PEER_DATA_SENT_BYTES.inc_by(bytes_len as u64);
         metrics::PEER_MESSAGE_SENT_BY_TYPE_TOTAL.with_label_values(&[msg_type]).inc();
         metrics::PEER_MESSAGE_SENT_BY_TYPE_BYTES
@@ -420,6 +469,61 @@ impl PeerActor {
             .inc_by(bytes_len as u64);
     }
 
+    /// If the message is a response to one of our pending requests, updates
+    /// the reputation of the peer according to the latency and the size of
+    /// the data received.
+    fn record_response(&self, conn: &connection::Connection, msg: &PeerMessage, len: usize) {
+        let (req, useful) = match msg {
+            PeerMessage::Block(b) => (PendingRequest::Block(*b.hash()), true),
+            PeerMessage::BlockHeaders(headers) => {
+                (PendingRequest::BlockHeaders, !headers.is_empty())
+            }
+            PeerMessage::VersionedStateResponse(info) => {
+                let (part_id, has_header) = match info {
+                    StateResponseInfo::V1(info) => {
+                        (info.state_response.part_id(), info.state_response.header.is_some())
+                    }
+                    StateResponseInfo::V2(info) => {
+                        (info.state_response.part_id(), info.state_response.has_header())
+                    }
+                };
+                match part_id {
+                    Some(part_id) => (
+                        PendingRequest::StatePart(info.shard_id(), info.sync_hash(), part_id),
+                        true,
+                    ),
+                    None => {
+                        (PendingRequest::StateHeader(info.shard_id(), info.sync_hash()), has_header)
+                    }
+                }
+            }
+            _ => return,
+        };
+        let Some(latency) = self.tracker.lock().pop_pending(&req, self.clock.now()) else {
+            return;
+        };
+        self.network_state.reputations.record_response(
+            &self.clock,
+            &conn.peer_info.id,
+            latency,
+            if useful { len } else { 0 },
+        );
+    }
+
+    /// Compression of a message of the given type and size, in case the peer
+    /// accepts compressed frames.
+    fn compression(&self, msg_type: &'static str, len: usize) -> Option<stream::Compression> {
+        let PeerStatus::Ready(conn) = &self.peer_status else {
+            return None;
+        };
+        if !conn.message_compression {
+            return None;
+        }
+        let config = self.network_state.config.message_compression.as_ref()?;
+        let min_size = *config.min_size_by_msg_type.get(msg_type)?;
+        (len >= min_size).then_some(stream::Compression { level: config.level, msg_type })
+    }
+
     fn send_handshake(&self, spec: HandshakeSpec) {
         let (height, tracked_shards) =
             if let Some(chain_info) = self.network_state.chain_info.load().as_ref() {
@@ -439,6 +543,7 @@ impl PeerActor {
                 height,
                 tracked_shards,
                 archival: self.network_state.config.archive,
+                light: self.network_state.config.light,
             },
             partial_edge_info: spec.partial_edge_info,
             owned_account: self.network_state.config.validator.as_ref().map(|vc| {
@@ -449,6 +554,18 @@ impl PeerActor {
                 }
                 .sign(vc.signer.as_ref())
             }),
+            encrypted_transport: self.network_state.config.encrypted_transport
+                && ProtocolFeature::EncryptedPeerTransport.protocol_version()
+                    <= spec.protocol_version,
+            message_compression: self.network_state.config.message_compression.is_some(),
+            quic_transport: self.network_state.config.quic_transport
+                && self.network_state.config.node_addr.is_some(),
+            quic_session_signature: self.framed.quic_session_id().map(|session_id| {
+                self.network_state
+                    .config
+                    .node_key
+                    .sign(&quic::session_signature_payload(session_id))
+            }),
         };
         let msg = match spec.tier {
             tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
@@ -588,6 +705,20 @@ impl PeerActor {
             }
         }
 
+        // QUIC certificates are not verified, so the peer has to prove that the QUIC session
+        // is established with it, rather than with a man-in-the-middle. The peer is not banned,
+        // since it is not the peer who is misbehaving in that case.
+        if let Some(session_id) = self.framed.quic_session_id() {
+            let payload = quic::session_signature_payload(session_id);
+            if !handshake.quic_session_signature.as_ref().map_or(false, |signature| {
+                signature.verify(&payload, handshake.sender_peer_id.public_key())
+            }) {
+                tracing::warn!(target: "network", "QUIC session is not signed by peer {}", handshake.sender_peer_id);
+                self.stop(ctx, ClosingReason::QuicSessionMismatch);
+                return;
+            }
+        }
+
         // Merge partial edges.
         let nonce = handshake.partial_edge_info.nonce;
         let partial_edge_info = match cs {
@@ -619,6 +750,26 @@ impl PeerActor {
             account_id: None,
         };
 
+        // Both sides advertise encryption only if the negotiated protocol
+        // version supports it, but the peer's advertisement is not trusted.
+        // QUIC connections are always encrypted by QUIC itself, and the QUIC
+        // session has been verified to be established with the peer above.
+        let encrypted = self.network_state.config.encrypted_transport
+            && handshake.encrypted_transport
+            && ProtocolFeature::EncryptedPeerTransport.protocol_version()
+                <= handshake.protocol_version
+            && !self.framed.is_quic();
+        // A peer not advertising encryption may be legitimate, or its
+        // advertisement may have been stripped, so it is not banned either.
+        if self.network_state.config.require_encrypted_transport
+            && !encrypted
+            && !self.framed.is_quic()
+        {
+            tracing::warn!(target: "network", "Refusing plaintext connection to peer {}", handshake.sender_peer_id);
+            self.stop(ctx, ClosingReason::PlaintextNotAllowed);
+            return;
+        }
+
         let now = self.clock.now();
         let conn = Arc::new(connection::Connection {
             tier,
@@ -628,8 +779,12 @@ impl PeerActor {
             genesis_id: handshake.sender_chain_info.genesis_id.clone(),
             tracked_shards: handshake.sender_chain_info.tracked_shards.clone(),
             archival: handshake.sender_chain_info.archival,
+            light: handshake.sender_chain_info.light,
             last_block: Default::default(),
             peer_type: self.peer_type,
+            encrypted,
+            message_compression: handshake.message_compression,
+            quic: self.framed.is_quic(),
             stats: self.stats.clone(),
             _peer_connections_metric: metrics::PEER_CONNECTIONS.new_point(&metrics::Connection {
                 type_: self.peer_type,
@@ -666,6 +821,24 @@ impl PeerActor {
             })
         });
 
+        // Penalize the peer for the requests it didn't respond to in time.
+        let mut interval = time::Interval::new(self.clock.now(), PENDING_REQUESTS_CHECK_INTERVAL);
+        ctx.spawn({
+            let tracker = self.tracker.clone();
+            let clock = self.clock.clone();
+            let network_state = self.network_state.clone();
+            let peer_id = conn.peer_info.id.clone();
+            wrap_future(async move {
+                loop {
+                    interval.tick(&clock).await;
+                    let expired = tracker.lock().pop_expired(clock.now(), PENDING_REQUEST_TIMEOUT);
+                    for _ in 0..expired {
+                        network_state.reputations.record_timeout(&clock, &peer_id);
+                    }
+                }
+            })
+        });
+
         // This time is used to figure out when the first run of the callbacks it run.
         // It is important that it is set here (rather than calling clock.now() within the future) - as it makes testing a lot easier (and more deterministic).
 
@@ -687,6 +860,10 @@ impl PeerActor {
                     Ok(()) => {
                         act.peer_info = Some(peer_info).into();
                         act.peer_status = PeerStatus::Ready(conn.clone());
+                        // Next time we connect to this peer, we can use QUIC.
+                        if handshake.quic_transport {
+                            act.network_state.quic_peers.lock().put(handshake.sender_peer_id.clone(), ());
+                        }
                         // Respond to handshake if it's inbound and connection was consolidated.
                         if act.peer_type == PeerType::Inbound {
                             act.send_handshake(HandshakeSpec{
@@ -696,6 +873,22 @@ impl PeerActor {
                                 partial_edge_info: partial_edge_info,
                             });
                         }
+                        // The Handshake is the last plaintext frame sent by each side.
+                        if conn.encrypted {
+                            let role = match act.peer_type {
+                                PeerType::Outbound => noise::Role::Initiator,
+                                PeerType::Inbound => noise::Role::Responder,
+                            };
+                            if let Err(err) = act.framed.start_encryption(
+                                role,
+                                &act.network_state.config.node_key,
+                                handshake.sender_peer_id.clone(),
+                            ) {
+                                tracing::warn!(target: "network", ?err, "Failed to start encryption of the connection to {}", handshake.sender_peer_id);
+                                act.stop(ctx, ClosingReason::StreamError);
+                                return;
+                            }
+                        }
                         // TIER1 is strictly reserved for BFT consensensus messages,
                         // so all kinds of periodical syncs happen only on TIER2 connections.
                         if tier==tcp::Tier::T2 {
@@ -916,86 +1109,6 @@ impl PeerActor {
         }
     }
 
-    async fn receive_routed_message(
-        clock: &time::Clock,
-        network_state: &NetworkState,
-        peer_id: PeerId,
-        msg_hash: CryptoHash,
-        body: RoutedMessageBody,
-    ) -> Result<Option<RoutedMessageBody>, ReasonForBan> {
-        let _span = tracing::trace_span!(
-            target: "network",
-            "receive_routed_message",
-            "type" = <&RoutedMessageBody as Into<&'static str>>::into(&body)
-        )
-        .entered();
-        Ok(match body {
-            RoutedMessageBody::TxStatusRequest(account_id, tx_hash) => network_state
-                .client
-                .tx_status_request(account_id, tx_hash)
-                .await
-                .map(|v| RoutedMessageBody::TxStatusResponse(*v)),
-            RoutedMessageBody::TxStatusResponse(tx_result) => {
-                network_state.client.tx_status_response(tx_result).await;
-                None
-            }
-            RoutedMessageBody::StateResponse(info) => {
-                network_state.client.state_response(StateResponseInfo::V1(info)).await;
-                None
-            }
-            RoutedMessageBody::BlockApproval(approval) => {
-                network_state.client.block_approval(approval, peer_id).await;
-                None
-            }
-            RoutedMessageBody::ForwardTx(transaction) => {
-                network_state.client.transaction(transaction, /*is_forwarded=*/ true).await;
-                None
-            }
-            RoutedMessageBody::PartialEncodedChunkRequest(request) => {
-                network_state.shards_manager_adapter.send(
-                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequest {
-                        partial_encoded_chunk_request: request,
-                        route_back: msg_hash,
-                    },
-                );
-                None
-            }
-            RoutedMessageBody::PartialEncodedChunkResponse(response) => {
-                network_state.shards_manager_adapter.send(
-                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
-                        partial_encoded_chunk_response: response,
-                        received_time: clock.now().into(),
-                    },
-                );
-                None
-            }
-            RoutedMessageBody::VersionedPartialEncodedChunk(chunk) => {
-                network_state
-                    .shards_manager_adapter
-                    .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(chunk));
-                None
-            }
-            RoutedMessageBody::PartialEncodedChunkForward(msg) => {
-                network_state
-                    .shards_manager_adapter
-                    .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(msg));
-                None
-            }
-            RoutedMessageBody::ChunkStateWitness(witness) => {
-                network_state.client.chunk_state_witness(witness).await;
-                None
-            }
-            RoutedMessageBody::ChunkEndorsement(endorsement) => {
-                network_state.client.chunk_endorsement(endorsement).await;
-                None
-            }
-            body => {
-                tracing::error!(target: "network", "Peer received unexpected message type: {:?}", body);
-                None
-            }
-        })
-    }
-
     fn receive_message(
         &self,
         ctx: &mut actix::Context<Self>,
@@ -1031,61 +1144,30 @@ impl PeerActor {
         let clock = self.clock.clone();
         let network_state = self.network_state.clone();
         let peer_id = conn.peer_info.id.clone();
-        ctx.spawn(wrap_future(async move {
-            Ok(match msg {
-                PeerMessage::Routed(msg) => {
-                    let msg_hash = msg.hash();
-                    Self::receive_routed_message(&clock, &network_state, peer_id, msg_hash, msg.msg.body).await?.map(
-                        |body| {
+        ctx.spawn(
+            wrap_future(
+                async move {
+                    let response = dispatch::receive_message(
+                        &clock,
+                        network_state.client.as_ref(),
+                        &network_state.shards_manager_adapter,
+                        peer_id,
+                        msg,
+                        was_requested,
+                    )
+                    .await?;
+                    Ok(response.map(|response| match response {
+                        dispatch::Response::Direct(msg) => msg,
+                        dispatch::Response::Routed { route_back, body } => {
                             PeerMessage::Routed(network_state.sign_message(
                                 &clock,
-                                RawRoutedMessage { target: PeerIdOrHash::Hash(msg_hash), body },
+                                RawRoutedMessage { target: PeerIdOrHash::Hash(route_back), body },
                             ))
-                        },
-                    )
-                }
-                PeerMessage::BlockRequest(hash) => {
-                    network_state.client.block_request(hash).await.map(|b|PeerMessage::Block(*b))
-                }
-                PeerMessage::BlockHeadersRequest(hashes) => {
-                    network_state.client.block_headers_request(hashes).await.map(PeerMessage::BlockHeaders)
-                }
-                PeerMessage::Block(block) => {
-                    network_state.client.block(block, peer_id, was_requested).await;
-                    None
-                }
-                PeerMessage::Transaction(transaction) => {
-                    network_state.client.transaction(transaction, /*is_forwarded=*/ false).await;
-                    None
-                }
-                PeerMessage::BlockHeaders(headers) => {
-                    network_state.client.block_headers(headers, peer_id).await?;
-                    None
-                }
-                PeerMessage::Challenge(challenge) => {
-                    network_state.client.challenge(challenge).await;
-                    None
-                }
-                PeerMessage::StateRequestHeader(shard_id, sync_hash) => network_state
-                    .client
-                    .state_request_header(shard_id, sync_hash)
-                    .await?
-                    .map(PeerMessage::VersionedStateResponse),
-                PeerMessage::StateRequestPart(shard_id, sync_hash, part_id) => network_state
-                    .client
-                    .state_request_part(shard_id, sync_hash, part_id)
-                    .await?
-                    .map(PeerMessage::VersionedStateResponse),
-                PeerMessage::VersionedStateResponse(info) => {
-                        //TODO: Route to state sync actor.
-                    network_state.client.state_response(info).await;
-                    None
-                }
-                msg => {
-                    tracing::error!(target: "network", "Peer received unexpected type: {:?}", msg);
-                    None
+                        }
+                    }))
                 }
-            })}.in_current_span())
+                .in_current_span(),
+            )
             .map(|res, act: &mut PeerActor, ctx| {
                 match res {
                     // TODO(gprusak): make sure that for routed messages we drop routeback info correctly.
@@ -1139,7 +1221,20 @@ impl PeerActor {
                 if let Some(max_peers) = max_peers {
                     num_peers = min(num_peers, max_peers);
                 }
-                let peers = self.network_state.peer_store.healthy_peers(num_peers as usize);
+                // Sample more peers than needed, so that the ones with bad reputation can be
+                // skipped, and the best ones are advertised.
+                let reputations = &self.network_state.reputations;
+                let mut peers: Vec<_> = self
+                    .network_state
+                    .peer_store
+                    .healthy_peers(2 * num_peers as usize)
+                    .into_iter()
+                    .filter(|p| !reputations.is_demoted(&self.clock, &p.id))
+                    .map(|p| (reputations.score(&self.clock, &p.id), p))
+                    .collect();
+                peers.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+                let peers: Vec<_> =
+                    peers.into_iter().take(num_peers as usize).map(|(_, p)| p).collect();
 
                 let mut direct_peers = self.network_state.get_direct_peers();
                 if let Some(max_direct_peers) = max_direct_peers {
@@ -1352,6 +1447,14 @@ impl PeerActor {
 
                 self.network_state.add_route_back(&self.clock, &conn, msg.as_ref());
                 if for_me {
+                    if let Some(hops) = msg.trace.take() {
+                        self.network_state.send_delivery_receipt(
+                            &self.clock,
+                            msg.author.clone(),
+                            msg.hash(),
+                            hops,
+                        );
+                    }
                     // Handle Ping and Pong message if they are for us without sending to client.
                     // i.e. Return false in case of Ping and Pong
                     match &msg.body {
@@ -1371,9 +1474,22 @@ impl PeerActor {
                             self.network_state.config.event_sink.push(Event::Pong(pong.clone()));
                             message_processed_event();
                         }
+                        RoutedMessageBody::DeliveryReceipt(receipt) => {
+                            self.network_state.delivery_traces.lock().delivered(
+                                &self.clock,
+                                &msg.author,
+                                receipt.clone(),
+                            );
+                            message_processed_event();
+                        }
                         _ => self.receive_message(ctx, &conn, PeerMessage::Routed(msg)),
                     }
                 } else {
+                    if let Some(hops) = &mut msg.trace {
+                        if hops.len() < MAX_ROUTE_TRACE_HOPS {
+                            hops.push(self.network_state.config.node_id());
+                        }
+                    }
                     if msg.decrease_ttl() {
                         self.network_state.send_message_to_peer(&self.clock, conn.tier, msg);
                     } else {
@@ -1385,6 +1501,15 @@ impl PeerActor {
                     }
                 }
             }
+            PeerMessage::LightClientBlockRequest(_) | PeerMessage::LightClientProofRequest(_)
+                if !self.light_client_requests.try_acquire(self.clock.now()) =>
+            {
+                tracing::debug!(target: "network", "Dropping light client request from {}: rate limit exceeded.", self.peer_info);
+                metrics::LIGHT_CLIENT_REQUESTS_DROPPED
+                    .with_label_values(&[peer_msg.msg_variant()])
+                    .inc();
+                message_processed_event();
+            }
             msg => self.receive_message(ctx, &conn, msg),
         }
     }
@@ -1554,6 +1679,11 @@ impl actix::Handler<stream::Error> for PeerActor {
             }
             // It is expected in a sense that the peer might be just slow.
             stream::Error::Send(stream::SendError::QueueOverflow { .. }) => true,
+            // Peer failed the noise handshake or sent a frame which doesn't decrypt.
+            stream::Error::Recv(stream::RecvError::Encryption(_)) => true,
+            stream::Error::Send(stream::SendError::Encryption(_)) => false,
+            // Peer sent a compressed frame which doesn't decompress.
+            stream::Error::Recv(stream::RecvError::Decompression(_)) => true,
             stream::Error::Recv(stream::RecvError::IO(err))
             | stream::Error::Send(stream::SendError::IO(err)) => match err.kind() {
                 // Connection has been closed.
@@ -1606,11 +1736,24 @@ impl actix::Handler<stream::Frame> for PeerActor {
             Ok(msg) => msg,
             Err(err) => {
                 tracing::debug!(target: "network", "Received invalid data {} from {}: {}", unc_fmt::AbbrBytes(&msg), self.peer_info, err);
+                if let PeerStatus::Ready(conn) = &self.peer_status {
+                    self.network_state
+                        .reputations
+                        .record_invalid_data(&self.clock, &conn.peer_info.id);
+                }
                 return;
             }
         };
 
         tracing::trace!(target: "network", "Received message: {}", peer_msg);
+        if let Some(recorder) = &self.network_state.traffic_recorder {
+            recorder.record(
+                &self.clock,
+                Direction::Inbound,
+                self.other_peer_id().cloned(),
+                &peer_msg,
+            );
+        }
 
         {
             let labels = [peer_msg.msg_variant()];
@@ -1651,6 +1794,7 @@ impl actix::Handler<stream::Frame> for PeerActor {
                         }
                     }
                 }
+                self.record_response(conn, &peer_msg, msg.len());
                 // Handle the message.
                 self.handle_msg_ready(ctx, conn.clone(), peer_msg);
             }
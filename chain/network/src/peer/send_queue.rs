@@ -0,0 +1,209 @@
+//! Send queue of a single connection, consisting of a separate FIFO queue per `MessageClass`.
+//!
+//! An item is dequeued only once the queues of all the higher priority classes are empty,
+//! so that a burst of large low priority messages (e.g. state parts) doesn't delay
+//! the consensus messages. Strict priorities may starve the lower priority classes,
+//! which is prevented by capping the bandwidth of the higher priority ones.
+//!
+//! A bandwidth cap of a class is enforced with a token bucket (in bytes): an item of
+//! the class is dequeued only if the bucket is not in deficit. Items larger than
+//! the bucket are still dequeued, leaving the bucket in deficit until it is refilled.
+//! A class which has exceeded its cap doesn't block the lower priority classes.
+use crate::concurrency::rate;
+use crate::config::MessageClass;
+use crate::stats::metrics;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use unc_async::time;
+
+struct Entry<T> {
+    item: T,
+    bytes: usize,
+    enqueued: time::Instant,
+}
+
+struct Bucket {
+    limit: rate::Limit,
+    /// Negative in case of a deficit.
+    tokens: f64,
+    updated: time::Instant,
+}
+
+impl Bucket {
+    fn new(limit: rate::Limit, now: time::Instant) -> Self {
+        Self { limit, tokens: limit.burst as f64, updated: now }
+    }
+
+    fn refill(&mut self, now: time::Instant) {
+        let elapsed = (now - self.updated).as_seconds_f64().max(0.);
+        self.tokens = (self.tokens + elapsed * self.limit.qps).min(self.limit.burst as f64);
+        self.updated = now;
+    }
+
+    /// Time at which the deficit of the bucket will be paid off.
+    fn ready_at(&self) -> time::Instant {
+        if self.tokens >= 0. {
+            return self.updated;
+        }
+        self.updated + time::Duration::seconds_f64(-self.tokens / self.limit.qps)
+    }
+}
+
+struct Queue<T> {
+    class: MessageClass,
+    entries: VecDeque<Entry<T>>,
+    bucket: Option<Bucket>,
+}
+
+enum Pop<T> {
+    Item(T),
+    /// All the non-empty queues are over their caps until the given time.
+    Throttled(time::Instant),
+    Empty,
+    Closed,
+}
+
+struct Inner<T> {
+    /// Queues ordered from the highest priority.
+    queues: Vec<Queue<T>>,
+    sender_closed: bool,
+    receiver_closed: bool,
+}
+
+impl<T> Inner<T> {
+    fn pop(&mut self, now: time::Instant) -> Pop<T> {
+        let mut throttled: Option<time::Instant> = None;
+        for q in &mut self.queues {
+            if q.entries.is_empty() {
+                continue;
+            }
+            if let Some(bucket) = &mut q.bucket {
+                bucket.refill(now);
+                if bucket.tokens < 0. {
+                    let ready_at = bucket.ready_at();
+                    throttled = Some(throttled.map_or(ready_at, |t| t.min(ready_at)));
+                    continue;
+                }
+            }
+            let entry = q.entries.pop_front().unwrap();
+            if let Some(bucket) = &mut q.bucket {
+                bucket.tokens -= entry.bytes as f64;
+            }
+            let class: &'static str = q.class.into();
+            metrics::PEER_SEND_QUEUE_BYTES.with_label_values(&[class]).sub(entry.bytes as i64);
+            metrics::PEER_SENT_BY_CLASS_BYTES
+                .with_label_values(&[class])
+                .inc_by(entry.bytes as u64);
+            metrics::PEER_SEND_QUEUE_DELAY
+                .with_label_values(&[class])
+                .observe((now - entry.enqueued).as_seconds_f64());
+            return Pop::Item(entry.item);
+        }
+        match throttled {
+            Some(t) => Pop::Throttled(t),
+            None if self.sender_closed => Pop::Closed,
+            None => Pop::Empty,
+        }
+    }
+
+    fn clear(&mut self) {
+        for q in &mut self.queues {
+            let bytes: usize = q.entries.drain(..).map(|e| e.bytes).sum();
+            let class: &'static str = q.class.into();
+            metrics::PEER_SEND_QUEUE_BYTES.with_label_values(&[class]).sub(bytes as i64);
+        }
+    }
+}
+
+struct Shared<T> {
+    clock: time::Clock,
+    inner: Mutex<Inner<T>>,
+    notify: tokio::sync::Notify,
+}
+
+pub(crate) struct Sender<T>(Arc<Shared<T>>);
+pub(crate) struct Receiver<T>(Arc<Shared<T>>);
+
+/// Constructs a send queue, with bandwidth caps of the given classes.
+pub(crate) fn new<T>(
+    clock: &time::Clock,
+    bandwidth_caps: &HashMap<MessageClass, rate::Limit>,
+) -> (Sender<T>, Receiver<T>) {
+    let now = clock.now();
+    let queues = MessageClass::ALL
+        .into_iter()
+        .map(|class| Queue {
+            class,
+            entries: VecDeque::new(),
+            bucket: bandwidth_caps.get(&class).map(|limit| Bucket::new(*limit, now)),
+        })
+        .collect();
+    let shared = Arc::new(Shared {
+        clock: clock.clone(),
+        inner: Mutex::new(Inner { queues, sender_closed: false, receiver_closed: false }),
+        notify: tokio::sync::Notify::new(),
+    });
+    (Sender(shared.clone()), Receiver(shared))
+}
+
+impl<T> Sender<T> {
+    /// Pushes the item of the given size (accounted in the bandwidth cap) to the queue.
+    /// The item is dropped if the Receiver has been dropped.
+    pub fn push(&self, class: MessageClass, item: T, bytes: usize) {
+        let mut inner = self.0.inner.lock();
+        if inner.receiver_closed {
+            return;
+        }
+        let enqueued = self.0.clock.now();
+        let q = inner.queues.iter_mut().find(|q| q.class == class).unwrap();
+        q.entries.push_back(Entry { item, bytes, enqueued });
+        let class: &'static str = class.into();
+        metrics::PEER_SEND_QUEUE_BYTES.with_label_values(&[class]).add(bytes as i64);
+        drop(inner);
+        self.0.notify.notify_one();
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.0.inner.lock().sender_closed = true;
+        self.0.notify.notify_one();
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Takes the next item from the queue, if there is one which can be sent right away.
+    pub fn try_recv(&mut self) -> Option<T> {
+        match self.0.inner.lock().pop(self.0.clock.now()) {
+            Pop::Item(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Waits for the next item which can be sent. Returns None once the Sender has been
+    /// dropped and all the queued items have been received.
+    /// Cancel-safe: an item is taken from the queue only when it is returned.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            let pop = self.0.inner.lock().pop(self.0.clock.now());
+            match pop {
+                Pop::Item(item) => return Some(item),
+                Pop::Closed => return None,
+                Pop::Empty => self.0.notify.notified().await,
+                Pop::Throttled(t) => tokio::select! {
+                    _ = self.0.notify.notified() => {}
+                    _ = self.0.clock.sleep_until(t) => {}
+                },
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut inner = self.0.inner.lock();
+        inner.receiver_closed = true;
+        inner.clear();
+    }
+}
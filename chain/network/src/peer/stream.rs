@@ -1,24 +1,43 @@
+use crate::concurrency::rate;
+use crate::config::MessageClass;
+use crate::peer::noise;
+use crate::peer::send_queue;
 use crate::peer_manager::connection;
+use crate::quic;
 use crate::stats::metrics;
 use crate::tcp;
 use actix::fut::future::wrap_future;
 use actix::AsyncContext as _;
 use bytesize::{GIB, MIB};
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::io;
-use std::net::SocketAddr;
+use std::io::Read as _;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::io::AsyncReadExt as _;
 use tokio::io::AsyncWriteExt as _;
+use tokio::io::{AsyncRead, AsyncWrite};
+use unc_async::time;
+use unc_crypto::SecretKey;
+use unc_primitives::network::PeerId;
 
 /// Maximum size of network message in encoded format.
 /// We encode length as `u32`, and therefore maximum size can't be larger than `u32::MAX`.
 const NETWORK_MESSAGE_MAX_SIZE_BYTES: usize = 512 * MIB as usize;
+/// Maximum size of a frame on the wire, which for encrypted connections
+/// includes the encryption overhead.
+const NETWORK_FRAME_MAX_SIZE_BYTES: usize = noise::encrypted_len(NETWORK_MESSAGE_MAX_SIZE_BYTES);
+/// Flag set in the length prefix of frames compressed with zstd.
+/// Frames are never larger than `NETWORK_FRAME_MAX_SIZE_BYTES`, so the top bit is free.
+const COMPRESSED_FRAME_FLAG: u32 = 1 << 31;
+const _: () = assert!(NETWORK_FRAME_MAX_SIZE_BYTES < COMPRESSED_FRAME_FLAG as usize);
 /// Maximum capacity of write buffer in bytes.
 const MAX_WRITE_BUFFER_CAPACITY_BYTES: usize = GIB as usize;
 
-type ReadHalf = tokio::io::ReadHalf<tokio::net::TcpStream>;
-type WriteHalf = tokio::io::WriteHalf<tokio::net::TcpStream>;
+type ReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+type WriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
+type Writer = tokio::io::BufWriter<WriteHalf>;
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum SendError {
@@ -26,6 +45,8 @@ pub(crate) enum SendError {
     IO(#[source] io::Error),
     #[error("queue is full, got {got_bytes}B, max capacity is {want_max_bytes}")]
     QueueOverflow { got_bytes: usize, want_max_bytes: usize },
+    #[error("encryption: {0}")]
+    Encryption(#[source] noise::Error),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -34,12 +55,25 @@ pub(crate) enum RecvError {
     IO(#[source] io::Error),
     #[error("message too large: got {got_bytes}B, want <={want_max_bytes}B")]
     MessageTooLarge { got_bytes: usize, want_max_bytes: usize },
+    #[error("encryption: {0}")]
+    Encryption(#[source] noise::Error),
+    #[error("decompression: {0}")]
+    Decompression(#[source] io::Error),
 }
 
 #[derive(actix::Message, PartialEq, Eq, Clone, Debug)]
 #[rtype(result = "()")]
 pub(crate) struct Frame(pub Vec<u8>);
 
+/// Compression of a sent frame, see `FramedStream::send_compressed`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Compression {
+    /// zstd compression level.
+    pub level: i32,
+    /// Type of the message in the frame, used in metrics.
+    pub msg_type: &'static str,
+}
+
 /// Stream critical error.
 /// Actor is responsible for calling ctx.stop() after receiving stream::Error.
 /// Actor might receive more than 1 stream::Error, but should call ctx.stop() just after the
@@ -56,8 +90,39 @@ pub(crate) enum Error {
     Recv(#[source] RecvError),
 }
 
+/// Encryption state of the stream, shared by the send and recv loops.
+enum Encryption {
+    /// Frames are sent in plaintext.
+    None,
+    /// Noise handshake is in progress, see `FramedStream::start_encryption`.
+    Handshake(noise::Handshake),
+    Established(noise::Transport),
+}
+
+enum SendItem {
+    Frame(Frame, Option<Compression>),
+    /// All frames queued after this item are encrypted. Contains the first
+    /// handshake message in case of the initiator.
+    StartEncryption(Option<Vec<u8>>),
+}
+
+/// Progress of the noise handshake, reported by the recv loop to the send loop.
+enum HandshakeEvent {
+    /// Handshake message to send to the peer.
+    Send(Vec<u8>),
+    /// Handshake has finished, frames can be encrypted.
+    Finished,
+}
+
 pub(crate) struct FramedStream<Actor: actix::Actor> {
-    queue_send: tokio::sync::mpsc::UnboundedSender<Frame>,
+    queue_send: send_queue::Sender<SendItem>,
+    /// Send queue of the bulk stream of QUIC connections, which carries
+    /// the `MessageClass::Sync` frames. Frames sent over different streams
+    /// may be received in a different order.
+    bulk_queue_send: Option<send_queue::Sender<SendItem>>,
+    /// TLS session of QUIC connections.
+    quic_session_id: Option<quic::SessionId>,
+    encryption: Arc<Mutex<Encryption>>,
     stats: Arc<connection::Stats>,
     send_buf_size_metric: Arc<metrics::IntGaugeGuard>,
     addr: actix::Addr<Actor>,
@@ -71,37 +136,184 @@ where
 {
     pub fn spawn(
         ctx: &mut actix::Context<Actor>,
+        clock: &time::Clock,
         stream: tcp::Stream,
+        bandwidth_caps: &HashMap<MessageClass, rate::Limit>,
         stats: Arc<connection::Stats>,
     ) -> Self {
-        let (tcp_recv, tcp_send) = tokio::io::split(stream.stream);
-        let (queue_send, queue_recv) = tokio::sync::mpsc::unbounded_channel();
+        let (read, write, bulk, quic_session_id) = match stream.stream {
+            tcp::Transport::Tcp(stream) => {
+                let (read, write) = tokio::io::split(stream);
+                (Box::new(read) as ReadHalf, Box::new(write) as WriteHalf, None, None)
+            }
+            tcp::Transport::Quic(conn) => {
+                let (write, read) = conn.consensus;
+                let (bulk_write, bulk_read) = conn.bulk;
+                (
+                    Box::new(read) as ReadHalf,
+                    Box::new(write) as WriteHalf,
+                    Some((Box::new(bulk_read) as ReadHalf, Box::new(bulk_write) as WriteHalf)),
+                    Some(conn.session_id),
+                )
+            }
+        };
+        let peer_addr = stream.peer_addr.to_string();
+        let (queue_send, queue_recv) = send_queue::new(clock, bandwidth_caps);
+        let (handshake_send, handshake_recv) = tokio::sync::mpsc::unbounded_channel();
+        let encryption = Arc::new(Mutex::new(Encryption::None));
         let send_buf_size_metric = Arc::new(metrics::MetricGuard::new(
             &*metrics::PEER_DATA_WRITE_BUFFER_SIZE,
-            vec![stream.peer_addr.to_string()],
+            vec![peer_addr.clone()],
         ));
-        ctx.spawn(wrap_future({
-            let addr = ctx.address();
-            let stats = stats.clone();
-            let m = send_buf_size_metric.clone();
-            async move {
-                if let Err(err) = Self::run_send_loop(tcp_send, queue_recv, stats, m).await {
-                    addr.do_send(Error::Send(SendError::IO(err)));
-                }
+        Self::spawn_send_loop(
+            ctx,
+            write,
+            queue_recv,
+            handshake_recv,
+            encryption.clone(),
+            stats.clone(),
+            send_buf_size_metric.clone(),
+        );
+        let first_frame_received = Arc::new(tokio::sync::Notify::new());
+        Self::spawn_recv_loop(
+            ctx,
+            peer_addr.clone(),
+            read,
+            None,
+            Some(first_frame_received.clone()),
+            encryption.clone(),
+            handshake_send.clone(),
+            stats.clone(),
+        );
+        let bulk_queue_send = bulk.map(|(read, write)| {
+            // Encryption is provided by QUIC, so there is no noise handshake.
+            let (queue_send, queue_recv) = send_queue::new(clock, bandwidth_caps);
+            let (_, handshake_recv) = tokio::sync::mpsc::unbounded_channel();
+            Self::spawn_send_loop(
+                ctx,
+                write,
+                queue_recv,
+                handshake_recv,
+                encryption.clone(),
+                stats.clone(),
+                send_buf_size_metric.clone(),
+            );
+            // Bulk frames are delivered to the Actor only after the first frame of
+            // the consensus stream (i.e. the Handshake), since the peer starts sending
+            // them right after it.
+            Self::spawn_recv_loop(
+                ctx,
+                format!("{peer_addr}/bulk"),
+                read,
+                Some(first_frame_received),
+                None,
+                encryption.clone(),
+                handshake_send,
+                stats.clone(),
+            );
+            queue_send
+        });
+        Self {
+            queue_send,
+            bulk_queue_send,
+            quic_session_id,
+            encryption,
+            stats,
+            send_buf_size_metric,
+            addr: ctx.address(),
+        }
+    }
+
+    fn spawn_send_loop(
+        ctx: &mut actix::Context<Actor>,
+        write: WriteHalf,
+        queue_recv: send_queue::Receiver<SendItem>,
+        handshake_recv: tokio::sync::mpsc::UnboundedReceiver<HandshakeEvent>,
+        encryption: Arc<Mutex<Encryption>>,
+        stats: Arc<connection::Stats>,
+        buf_size_metric: Arc<metrics::IntGaugeGuard>,
+    ) {
+        let addr = ctx.address();
+        ctx.spawn(wrap_future(async move {
+            if let Err(err) = Self::run_send_loop(
+                write,
+                queue_recv,
+                handshake_recv,
+                encryption,
+                stats,
+                buf_size_metric,
+            )
+            .await
+            {
+                addr.do_send(Error::Send(err));
             }
         }));
-        ctx.spawn(wrap_future({
-            let addr = ctx.address();
-            let stats = stats.clone();
-            async move {
-                if let Err(err) =
-                    Self::run_recv_loop(stream.peer_addr, tcp_recv, addr.clone(), stats).await
-                {
-                    addr.do_send(Error::Recv(err));
-                }
+    }
+
+    /// Spawns the recv loop. If `wait_for` is set, the loop starts only once it is notified.
+    /// `notify` is notified once the first frame is delivered to the Actor.
+    fn spawn_recv_loop(
+        ctx: &mut actix::Context<Actor>,
+        metric_label: String,
+        read: ReadHalf,
+        wait_for: Option<Arc<tokio::sync::Notify>>,
+        notify: Option<Arc<tokio::sync::Notify>>,
+        encryption: Arc<Mutex<Encryption>>,
+        handshake_send: tokio::sync::mpsc::UnboundedSender<HandshakeEvent>,
+        stats: Arc<connection::Stats>,
+    ) {
+        let addr = ctx.address();
+        ctx.spawn(wrap_future(async move {
+            if let Some(wait_for) = wait_for {
+                wait_for.notified().await;
+            }
+            if let Err(err) = Self::run_recv_loop(
+                metric_label,
+                read,
+                addr.clone(),
+                notify,
+                encryption,
+                handshake_send,
+                stats,
+            )
+            .await
+            {
+                addr.do_send(Error::Recv(err));
             }
         }));
-        Self { queue_send, stats, send_buf_size_metric, addr: ctx.address() }
+    }
+
+    /// Whether the frames sent over different channels go over different streams.
+    pub fn is_quic(&self) -> bool {
+        self.bulk_queue_send.is_some()
+    }
+
+    /// TLS session of the QUIC connection, which has to be signed by both peers.
+    pub fn quic_session_id(&self) -> Option<&quic::SessionId> {
+        self.quic_session_id.as_ref()
+    }
+
+    /// Starts the noise handshake, after which all frames are encrypted.
+    /// Frames sent before this call are still sent in plaintext, frames sent
+    /// after it are delayed until the handshake finishes.
+    ///
+    /// Both sides of the connection have to call it. The initiator has to call
+    /// it only after it has received the last plaintext frame of the responder,
+    /// i.e. its `Handshake`, so that the frames of the responder which follow
+    /// are interpreted as the handshake messages.
+    ///
+    /// Frames of the other classes are not ordered with respect to this call, so only
+    /// `MessageClass::Consensus` frames may be sent before it.
+    pub fn start_encryption(
+        &self,
+        role: noise::Role,
+        node_key: &SecretKey,
+        peer_id: PeerId,
+    ) -> Result<(), noise::Error> {
+        let (handshake, first_message) = noise::Handshake::new(role, node_key, peer_id)?;
+        *self.encryption.lock() = Encryption::Handshake(handshake);
+        self.queue_send.push(MessageClass::Consensus, SendItem::StartEncryption(first_message), 0);
+        Ok(())
     }
 
     /// Pushes `msg` to the send queue.
@@ -109,6 +321,19 @@ where
     /// If the message is too large, it will be silently dropped inside run_send_loop.
     /// Emits a critical error to Actor if send queue is full.
     pub fn send(&self, frame: Frame) {
+        self.push(MessageClass::Consensus, frame, None);
+    }
+
+    /// Same as `send`, but the frame is compressed with zstd, unless that
+    /// doesn't make it smaller. The peer has to support compressed frames,
+    /// which it advertises in the `Handshake`.
+    pub fn send_compressed(&self, frame: Frame, compression: Compression) {
+        self.push(MessageClass::Consensus, frame, Some(compression));
+    }
+
+    /// Same as `send` or `send_compressed`, but the frame is queued with the priority
+    /// of the given class, see `send_queue`.
+    pub fn push(&self, class: MessageClass, frame: Frame, compression: Option<Compression>) {
         let msg = &frame.0;
         let mut buf_size =
             self.stats.bytes_to_send.fetch_add(msg.len() as u64, Ordering::Acquire) as usize;
@@ -125,7 +350,12 @@ where
                 want_max_bytes: MAX_WRITE_BUFFER_CAPACITY_BYTES,
             }));
         }
-        let _ = self.queue_send.send(frame);
+        let queue_send = match (class, &self.bulk_queue_send) {
+            (MessageClass::Sync, Some(bulk_queue_send)) => bulk_queue_send,
+            _ => &self.queue_send,
+        };
+        let len = frame.0.len();
+        queue_send.push(class, SendItem::Frame(frame, compression), len);
     }
 
     /// Event loop receiving and processing messages.
@@ -137,26 +367,29 @@ where
     // TODO(gprusak): once borsh support is dropped, we can parse a proto
     // directly from the stream.
     async fn run_recv_loop(
-        peer_addr: SocketAddr,
+        metric_label: String,
         read: ReadHalf,
         addr: actix::Addr<Actor>,
+        mut notify: Option<Arc<tokio::sync::Notify>>,
+        encryption: Arc<Mutex<Encryption>>,
+        handshake_send: tokio::sync::mpsc::UnboundedSender<HandshakeEvent>,
         stats: Arc<connection::Stats>,
     ) -> Result<(), RecvError> {
         const READ_BUFFER_CAPACITY: usize = 8 * 1024;
         let mut read = tokio::io::BufReader::with_capacity(READ_BUFFER_CAPACITY, read);
 
         let msg_size_metric =
-            metrics::MetricGuard::new(&metrics::PEER_MSG_SIZE_BYTES, vec![peer_addr.to_string()]);
-        let buf_size_metric = metrics::MetricGuard::new(
-            &metrics::PEER_DATA_READ_BUFFER_SIZE,
-            vec![peer_addr.to_string()],
-        );
+            metrics::MetricGuard::new(&metrics::PEER_MSG_SIZE_BYTES, vec![metric_label.clone()]);
+        let buf_size_metric =
+            metrics::MetricGuard::new(&metrics::PEER_DATA_READ_BUFFER_SIZE, vec![metric_label]);
         loop {
-            let n = read.read_u32_le().await.map_err(RecvError::IO)? as usize;
-            if n > NETWORK_MESSAGE_MAX_SIZE_BYTES {
+            let header = read.read_u32_le().await.map_err(RecvError::IO)?;
+            let compressed = header & COMPRESSED_FRAME_FLAG != 0;
+            let n = (header & !COMPRESSED_FRAME_FLAG) as usize;
+            if n > NETWORK_FRAME_MAX_SIZE_BYTES {
                 return Err(RecvError::MessageTooLarge {
                     got_bytes: n,
-                    want_max_bytes: NETWORK_MESSAGE_MAX_SIZE_BYTES,
+                    want_max_bytes: NETWORK_FRAME_MAX_SIZE_BYTES,
                 });
             }
             msg_size_metric.observe(n as f64);
@@ -168,38 +401,188 @@ where
             buf_size_metric.set(0);
             stats.received_messages.fetch_add(1, Ordering::Relaxed);
             stats.received_bytes.fetch_add(n as u64, Ordering::Relaxed);
+            let Some(mut buf) = Self::decrypt_frame(&encryption, &handshake_send, buf)
+                .map_err(RecvError::Encryption)?
+            else {
+                continue;
+            };
+            if compressed {
+                buf = Self::decompress(&buf)?;
+            }
             if let Err(_) = addr.send(Frame(buf)).await {
                 // We got mailbox error, which means that Actor has stopped,
                 // so we should just close the stream.
                 return Ok(());
             }
+            if let Some(notify) = notify.take() {
+                notify.notify_one();
+            }
+        }
+    }
+
+    /// Decrypts a received frame. Returns `None` for the frames of the noise
+    /// handshake, which are processed here rather than passed to the Actor.
+    fn decrypt_frame(
+        encryption: &Mutex<Encryption>,
+        handshake_send: &tokio::sync::mpsc::UnboundedSender<HandshakeEvent>,
+        buf: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, noise::Error> {
+        let mut encryption = encryption.lock();
+        let handshake = match &mut *encryption {
+            Encryption::None => return Ok(Some(buf)),
+            Encryption::Established(transport) => return transport.decrypt(&buf).map(Some),
+            Encryption::Handshake(handshake) => handshake,
+        };
+        if let Some(reply) = handshake.read_message(&buf)? {
+            let _ = handshake_send.send(HandshakeEvent::Send(reply));
+        }
+        if handshake.is_finished() {
+            let Encryption::Handshake(handshake) =
+                std::mem::replace(&mut *encryption, Encryption::None)
+            else {
+                unreachable!();
+            };
+            *encryption = Encryption::Established(handshake.into_transport()?);
+            let _ = handshake_send.send(HandshakeEvent::Finished);
+        }
+        Ok(None)
+    }
+
+    /// Decompresses a received frame. The size of the result is limited, so
+    /// that a malicious peer cannot make us allocate an arbitrary amount of memory.
+    fn decompress(compressed: &[u8]) -> Result<Vec<u8>, RecvError> {
+        let mut buf = vec![];
+        zstd::stream::read::Decoder::new(compressed)
+            .and_then(|decoder| {
+                decoder.take(NETWORK_MESSAGE_MAX_SIZE_BYTES as u64 + 1).read_to_end(&mut buf)
+            })
+            .map_err(RecvError::Decompression)?;
+        if buf.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
+            return Err(RecvError::MessageTooLarge {
+                got_bytes: buf.len(),
+                want_max_bytes: NETWORK_MESSAGE_MAX_SIZE_BYTES,
+            });
+        }
+        Ok(buf)
+    }
+
+    /// Compresses a frame to be sent. Returns `None` if compression doesn't
+    /// make the frame smaller.
+    fn compress(msg: &[u8], compression: Compression) -> Option<Vec<u8>> {
+        let compressed = match zstd::bulk::compress(msg, compression.level) {
+            Ok(compressed) => compressed,
+            Err(err) => {
+                tracing::warn!(target: "network", ?err, "Failed to compress a message");
+                return None;
+            }
+        };
+        if compressed.len() >= msg.len() {
+            return None;
         }
+        metrics::PEER_MESSAGE_COMPRESSED_BY_TYPE_TOTAL
+            .with_label_values(&[compression.msg_type])
+            .inc();
+        metrics::PEER_MESSAGE_COMPRESSION_SAVED_BY_TYPE_BYTES
+            .with_label_values(&[compression.msg_type])
+            .inc_by((msg.len() - compressed.len()) as u64);
+        Some(compressed)
+    }
+
+    /// Writes a frame prefixed with its length, combined with the `flags`.
+    async fn write_frame(writer: &mut Writer, flags: u32, msg: &[u8]) -> Result<(), SendError> {
+        writer.write_u32_le(msg.len() as u32 | flags).await.map_err(SendError::IO)?;
+        writer.write_all(msg).await.map_err(SendError::IO)
     }
+
+    /// Writes a handshake message, returns whether the handshake has finished.
+    async fn handle_handshake_event(
+        writer: &mut Writer,
+        event: HandshakeEvent,
+    ) -> Result<bool, SendError> {
+        match event {
+            HandshakeEvent::Send(msg) => {
+                Self::write_frame(writer, 0, &msg).await?;
+                writer.flush().await.map_err(SendError::IO)?;
+                Ok(false)
+            }
+            HandshakeEvent::Finished => Ok(true),
+        }
+    }
+
     async fn run_send_loop(
-        tcp_send: WriteHalf,
-        mut queue_recv: tokio::sync::mpsc::UnboundedReceiver<Frame>,
+        write: WriteHalf,
+        mut queue_recv: send_queue::Receiver<SendItem>,
+        mut handshake_recv: tokio::sync::mpsc::UnboundedReceiver<HandshakeEvent>,
+        encryption: Arc<Mutex<Encryption>>,
         stats: Arc<connection::Stats>,
         buf_size_metric: Arc<metrics::IntGaugeGuard>,
-    ) -> io::Result<()> {
+    ) -> Result<(), SendError> {
         const WRITE_BUFFER_CAPACITY: usize = 8 * 1024;
-        let mut writer = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_CAPACITY, tcp_send);
-        while let Some(Frame(mut msg)) = queue_recv.recv().await {
+        let mut writer = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_CAPACITY, write);
+        // Whether the frames taken from the queue have to be encrypted.
+        let mut encrypting = false;
+        let mut handshake_finished = false;
+        loop {
+            let mut item = tokio::select! {
+                biased;
+                Some(event) = handshake_recv.recv() => {
+                    handshake_finished |= Self::handle_handshake_event(&mut writer, event).await?;
+                    continue;
+                }
+                item = queue_recv.recv() => match item {
+                    Some(item) => item,
+                    None => break,
+                },
+            };
             // Try writing a batch of messages and flush once at the end.
             loop {
-                // TODO(gprusak): sending a too large message should probably be treated as a bug,
-                // since dropping messages may lead to hard-to-debug high-level issues.
-                if msg.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
-                    metrics::MessageDropped::InputTooLong.inc_unknown_msg();
-                } else {
-                    writer.write_u32_le(msg.len() as u32).await?;
-                    writer.write_all(&msg[..]).await?;
+                match item {
+                    SendItem::StartEncryption(first_message) => {
+                        if let Some(msg) = first_message {
+                            Self::write_frame(&mut writer, 0, &msg).await?;
+                        }
+                        encrypting = true;
+                    }
+                    SendItem::Frame(Frame(msg), compression) => {
+                        // TODO(gprusak): sending a too large message should probably be treated as a bug,
+                        // since dropping messages may lead to hard-to-debug high-level issues.
+                        if msg.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
+                            metrics::MessageDropped::InputTooLong.inc_unknown_msg();
+                        } else {
+                            let compressed = compression.and_then(|c| Self::compress(&msg, c));
+                            let flags =
+                                if compressed.is_some() { COMPRESSED_FRAME_FLAG } else { 0 };
+                            let payload = compressed.as_deref().unwrap_or(&msg);
+                            if encrypting {
+                                // Frames are held back until the handshake finishes.
+                                while !handshake_finished {
+                                    writer.flush().await.map_err(SendError::IO)?;
+                                    let Some(event) = handshake_recv.recv().await else {
+                                        // Recv loop has stopped, the stream is being closed.
+                                        return Ok(());
+                                    };
+                                    handshake_finished |=
+                                        Self::handle_handshake_event(&mut writer, event).await?;
+                                }
+                                let encrypted = match &mut *encryption.lock() {
+                                    Encryption::Established(transport) => {
+                                        transport.encrypt(payload).map_err(SendError::Encryption)?
+                                    }
+                                    _ => unreachable!("noise handshake has finished"),
+                                };
+                                Self::write_frame(&mut writer, flags, &encrypted).await?;
+                            } else {
+                                Self::write_frame(&mut writer, flags, payload).await?;
+                            }
+                        }
+                        stats.messages_to_send.fetch_sub(1, Ordering::Release);
+                        stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
+                        buf_size_metric.sub(msg.len() as i64);
+                    }
                 }
-                stats.messages_to_send.fetch_sub(1, Ordering::Release);
-                stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
-                buf_size_metric.sub(msg.len() as i64);
-                msg = match queue_recv.try_recv() {
-                    Ok(Frame(it)) => it,
-                    Err(_) => break,
+                item = match queue_recv.try_recv() {
+                    Some(it) => it,
+                    None => break,
                 };
             }
             // This is an unconditional flush, which means that even if new messages
@@ -208,7 +591,7 @@ where
             // and added to the queue at a rate similar to flush latency. To fix that
             // we would need to put writer.flush() and queue_recv.recv() into a tokio::select
             // and make sure that both are cancellation-safe.
-            writer.flush().await?;
+            writer.flush().await.map_err(SendError::IO)?;
         }
         Ok(())
     }
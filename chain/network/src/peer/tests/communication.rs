@@ -1,3 +1,4 @@
+use crate::concurrency::rate;
 use crate::network_protocol::testonly as data;
 use crate::network_protocol::{
     Encoding, Handshake, HandshakeFailureReason, PartialEdgeInfo, PeerMessage, PeersRequest,
@@ -6,6 +7,7 @@ use crate::network_protocol::{
 use crate::peer::testonly::{Event, PeerConfig, PeerHandle};
 use crate::peer_manager::peer_manager_actor::Event as PME;
 use crate::tcp;
+use crate::testonly::fake_client;
 use crate::testonly::make_rng;
 use crate::testonly::stream::Stream;
 use crate::types::{PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg};
@@ -210,6 +212,10 @@ async fn test_handshake(outbound_encoding: Option<Encoding>, inbound_encoding: O
         sender_chain_info: outbound_cfg.chain.get_peer_chain_info(),
         partial_edge_info: outbound_cfg.partial_edge_info(&inbound.cfg.id(), 1),
         owned_account: None,
+        encrypted_transport: false,
+        message_compression: false,
+        quic_transport: false,
+        quic_session_signature: None,
     };
     // We will also introduce chain_id mismatch, but ProtocolVersionMismatch is expected to take priority.
     handshake.sender_chain_info.genesis_id.chain_id = "unknown_chain".to_string();
@@ -266,3 +272,67 @@ async fn handshake() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[tokio::test]
+// Verifies that the light client requests exceeding the rate limit are dropped.
+async fn light_client_requests_rate_limit() {
+    init_test_logger();
+    let mut rng = make_rng(89028037453);
+    let mut clock = time::FakeClock::default();
+
+    let chain = Arc::new(data::Chain::make(&mut clock, &mut rng, 12));
+    let mut inbound_network = chain.make_config(&mut rng);
+    inbound_network.light_client_requests_rate_limit = rate::Limit { qps: 1., burst: 3 };
+    let inbound_cfg = PeerConfig {
+        chain: chain.clone(),
+        network: inbound_network,
+        force_encoding: Some(Encoding::Proto),
+    };
+    let outbound_cfg = PeerConfig {
+        chain: chain.clone(),
+        network: chain.make_config(&mut rng),
+        force_encoding: Some(Encoding::Proto),
+    };
+    let (outbound_stream, inbound_stream) =
+        tcp::Stream::loopback(inbound_cfg.id(), tcp::Tier::T2).await;
+    let mut inbound = PeerHandle::start_endpoint(clock.clock(), inbound_cfg, inbound_stream).await;
+    let mut outbound =
+        PeerHandle::start_endpoint(clock.clock(), outbound_cfg, outbound_stream).await;
+    outbound.complete_handshake().await;
+    inbound.complete_handshake().await;
+
+    // Sends the requests and returns how many of them have reached the client.
+    let want = PeerMessage::LightClientBlockRequest(*chain.blocks[5].hash());
+    let send_requests = |n| {
+        let inbound = &inbound;
+        let outbound = &outbound;
+        let want = want.clone();
+        async move {
+            let mut events = inbound.events.from_now();
+            for _ in 0..n {
+                outbound.send(want.clone()).await;
+            }
+            let mut served = 0;
+            for _ in 0..n {
+                events
+                    .recv_until(|ev| match ev {
+                        Event::Client(fake_client::Event::LightClientBlockRequest(_)) => {
+                            served += 1;
+                            None
+                        }
+                        Event::Network(PME::MessageProcessed(_, got)) if got == want => Some(()),
+                        _ => None,
+                    })
+                    .await;
+            }
+            served
+        }
+    };
+
+    tracing::info!(target:"test","requests above the burst are dropped");
+    assert_eq!(3, send_requests(5).await);
+
+    tracing::info!(target:"test","tokens are refilled over time");
+    clock.advance(time::Duration::seconds(1));
+    assert_eq!(1, send_requests(2).await);
+}
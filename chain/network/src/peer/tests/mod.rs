@@ -1,2 +1,3 @@
 mod communication;
+mod send_queue;
 mod stream;
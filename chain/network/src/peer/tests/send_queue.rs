@@ -0,0 +1,116 @@
+use crate::concurrency::rate;
+use crate::config::MessageClass;
+use crate::config_json;
+use crate::peer::send_queue;
+use std::collections::HashMap;
+use unc_async::time;
+
+#[test]
+fn classes_are_prioritized() {
+    let clock = time::FakeClock::default();
+    let (send, mut recv) = send_queue::new(&clock.clock(), &HashMap::new());
+    send.push(MessageClass::Gossip, 0, 10);
+    send.push(MessageClass::Sync, 1, 10);
+    send.push(MessageClass::Gossip, 2, 10);
+    send.push(MessageClass::ChunkDistribution, 3, 10);
+    send.push(MessageClass::Consensus, 4, 10);
+    let got: Vec<_> = std::iter::from_fn(|| recv.try_recv()).collect();
+    assert_eq!(got, vec![4, 3, 1, 0, 2]);
+}
+
+#[test]
+fn bandwidth_cap() {
+    let clock = time::FakeClock::default();
+    let caps = HashMap::from([(MessageClass::Sync, rate::Limit { burst: 100, qps: 100. })]);
+    let (send, mut recv) = send_queue::new(&clock.clock(), &caps);
+    for i in 0..4 {
+        send.push(MessageClass::Sync, i, 100);
+    }
+    send.push(MessageClass::Gossip, 10, 100);
+    // The burst is spent by the first item, the second one puts the bucket into deficit.
+    // Then the lower priority class is not blocked by the capped one.
+    let got: Vec<_> = std::iter::from_fn(|| recv.try_recv()).collect();
+    assert_eq!(got, vec![0, 1, 10]);
+    // The deficit is paid off after 1s.
+    clock.advance(time::Duration::milliseconds(500));
+    assert_eq!(recv.try_recv(), None);
+    clock.advance(time::Duration::milliseconds(500));
+    assert_eq!(recv.try_recv(), Some(2));
+    assert_eq!(recv.try_recv(), None);
+    // Messages of other classes are not limited.
+    send.push(MessageClass::Consensus, 20, 1000);
+    send.push(MessageClass::Consensus, 21, 1000);
+    let got: Vec<_> = std::iter::from_fn(|| recv.try_recv()).collect();
+    assert_eq!(got, vec![20, 21]);
+}
+
+/// Simulates a connection which can send `sends_per_tick` items every 10ms, with Sync items
+/// offered faster than that. Returns the number of Gossip items which have been sent.
+fn gossip_sent_under_sync_load(caps: &HashMap<MessageClass, rate::Limit>) -> usize {
+    const TICKS: usize = 1000;
+    const SYNC_BYTES: usize = 256 * 1024;
+    const GOSSIP: usize = 1_000_000;
+    let clock = time::FakeClock::default();
+    let (send, mut recv) = send_queue::new(&clock.clock(), caps);
+    let sends_per_tick = 2;
+    let mut gossip_sent = 0;
+    for i in 0..TICKS {
+        send.push(MessageClass::Sync, 2 * i, SYNC_BYTES);
+        send.push(MessageClass::Sync, 2 * i + 1, SYNC_BYTES);
+        send.push(MessageClass::Gossip, GOSSIP + i, 1024);
+        for _ in 0..sends_per_tick {
+            match recv.try_recv() {
+                Some(item) if item >= GOSSIP => gossip_sent += 1,
+                _ => {}
+            }
+        }
+        clock.advance(time::Duration::milliseconds(10));
+    }
+    gossip_sent
+}
+
+#[test]
+fn gossip_progresses_under_sustained_sync_load() {
+    // Without caps the Sync messages take the whole connection.
+    assert_eq!(gossip_sent_under_sync_load(&HashMap::new()), 0);
+    // With the default caps, once the burst is spent, the Sync class gets less than
+    // the connection can send and the Gossip gets the rest.
+    let caps: HashMap<_, _> = config_json::ExperimentalConfig::default()
+        .bandwidth_caps
+        .iter()
+        .map(|(class, cap)| {
+            (*class, rate::Limit { burst: cap.burst_bytes, qps: cap.bytes_per_second as f64 })
+        })
+        .collect();
+    assert!(caps.contains_key(&MessageClass::Sync));
+    assert!(gossip_sent_under_sync_load(&caps) >= 900);
+}
+
+#[tokio::test]
+async fn recv_waits_for_the_cap() {
+    let clock = time::FakeClock::default();
+    let caps = HashMap::from([(MessageClass::Sync, rate::Limit { burst: 100, qps: 100. })]);
+    let (send, mut recv) = send_queue::new(&clock.clock(), &caps);
+    send.push(MessageClass::Sync, 0, 200);
+    send.push(MessageClass::Sync, 1, 100);
+    assert_eq!(recv.recv().await, Some(0));
+    let handle = tokio::spawn(async move { (recv.recv().await, recv) });
+    clock.advance(time::Duration::seconds(1));
+    let (got, mut recv) = handle.await.unwrap();
+    assert_eq!(got, Some(1));
+    // Once the sender is dropped, the receiver gets None.
+    drop(send);
+    assert_eq!(recv.recv().await, None);
+}
+
+#[tokio::test]
+async fn queued_items_are_received_after_sender_is_dropped() {
+    let clock = time::FakeClock::default();
+    let (send, mut recv) = send_queue::new(&clock.clock(), &HashMap::new());
+    send.push(MessageClass::Gossip, 0, 10);
+    send.push(MessageClass::Consensus, 1, 10);
+    drop(send);
+    assert_eq!(recv.recv().await, Some(1));
+    assert_eq!(recv.recv().await, Some(0));
+    assert_eq!(recv.recv().await, None);
+}
@@ -8,6 +8,7 @@ use actix::ActorContext as _;
 use rand::Rng as _;
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use unc_async::time;
 
 struct Actor {
     stream: stream::FramedStream<Actor>,
@@ -20,12 +21,15 @@ impl actix::Actor for Actor {
 
 #[derive(actix::Message)]
 #[rtype("()")]
-struct SendFrame(stream::Frame);
+struct SendFrame(stream::Frame, Option<stream::Compression>);
 
 impl actix::Handler<SendFrame> for Actor {
     type Result = ();
-    fn handle(&mut self, SendFrame(frame): SendFrame, _ctx: &mut Self::Context) {
-        self.stream.send(frame);
+    fn handle(&mut self, SendFrame(frame, compression): SendFrame, _ctx: &mut Self::Context) {
+        match compression {
+            Some(compression) => self.stream.send_compressed(frame, compression),
+            None => self.stream.send(frame),
+        }
     }
 }
 
@@ -55,7 +59,13 @@ impl Actor {
             queue_recv,
             system: ActixSystem::spawn(|| {
                 Actor::create(|ctx| {
-                    let stream = stream::FramedStream::spawn(ctx, s, Arc::default());
+                    let stream = stream::FramedStream::spawn(
+                        ctx,
+                        &time::Clock::real(),
+                        s,
+                        &Default::default(),
+                        Arc::default(),
+                    );
                     Self { stream, queue_send }
                 })
             })
@@ -82,7 +92,7 @@ async fn send_recv() {
             })
             .collect();
         for msg in &msgs {
-            a1.system.addr.send(SendFrame(msg.clone())).await.unwrap();
+            a1.system.addr.send(SendFrame(msg.clone(), None)).await.unwrap();
         }
         for want in &msgs {
             let got = a2.queue_recv.recv().await.unwrap();
@@ -90,3 +100,35 @@ async fn send_recv() {
         }
     }
 }
+
+#[tokio::test]
+async fn send_recv_compressed() {
+    let mut rng = make_rng(98324532);
+    let (s1, s2) = tcp::Stream::loopback(data::make_peer_id(&mut rng), tcp::Tier::T2).await;
+    let a1 = Actor::spawn(s1).await;
+    let mut a2 = Actor::spawn(s2).await;
+
+    let compression = stream::Compression { level: 3, msg_type: "test" };
+    for _ in 0..5 {
+        let n = rng.gen_range(1..10);
+        // Mix of compressible and incompressible frames, some of which are sent
+        // without compression.
+        let msgs: Vec<_> = (0..n)
+            .map(|_| {
+                let size = rng.gen_range(0..10000);
+                let mut msg = vec![0; size];
+                if rng.gen() {
+                    rng.fill(&mut msg[..]);
+                }
+                (stream::Frame(msg), rng.gen::<bool>().then_some(compression))
+            })
+            .collect();
+        for (msg, compression) in &msgs {
+            a1.system.addr.send(SendFrame(msg.clone(), *compression)).await.unwrap();
+        }
+        for (want, _) in &msgs {
+            let got = a2.queue_recv.recv().await.unwrap();
+            assert_eq!(&got, want);
+        }
+    }
+}
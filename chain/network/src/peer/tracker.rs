@@ -1,9 +1,35 @@
 use crate::peer::transfer_stats::TransferStats;
+use std::collections::HashMap;
 use unc_async::time;
 use unc_primitives::hash::CryptoHash;
+use unc_primitives::types::ShardId;
 
 /// Maximum number of requests and responses to track.
 const MAX_TRACK_SIZE: usize = 30;
+/// Maximum number of requests awaiting a response to track.
+const MAX_PENDING_REQUESTS: usize = 100;
+
+/// Request sent to the peer, for which we expect a response.
+/// Used to measure the latency and usefulness of the peer's responses.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum PendingRequest {
+    Block(CryptoHash),
+    /// Responses to the headers requests don't identify the request,
+    /// so there can be only one of them tracked at a time.
+    BlockHeaders,
+    StateHeader(ShardId, CryptoHash),
+    StatePart(ShardId, CryptoHash, u64),
+}
+
+impl PendingRequest {
+    /// Whether the peer is expected to respond to the request, so that
+    /// the lack of a response is a timeout. Peers don't respond to the
+    /// requests for blocks they don't have, e.g. the ones they haven't
+    /// received yet, which is not their fault.
+    fn expects_response(&self) -> bool {
+        !matches!(self, PendingRequest::Block(_))
+    }
+}
 
 /// Internal structure to keep a circular queue within a tracker with unique hashes.
 struct CircularUniqueQueue {
@@ -49,6 +75,8 @@ pub(crate) struct Tracker {
     requested: CircularUniqueQueue,
     /// Received elements.
     received: CircularUniqueQueue,
+    /// Requests awaiting a response, with the time they were sent at.
+    pending: HashMap<PendingRequest, time::Instant>,
 }
 
 impl Default for Tracker {
@@ -58,6 +86,7 @@ impl Default for Tracker {
             received_bytes: TransferStats::default(),
             requested: CircularUniqueQueue::new(MAX_TRACK_SIZE),
             received: CircularUniqueQueue::new(MAX_TRACK_SIZE),
+            pending: HashMap::new(),
         }
     }
 }
@@ -89,6 +118,39 @@ impl Tracker {
     pub(crate) fn push_request(&mut self, hash: CryptoHash) {
         self.requested.push(hash);
     }
+
+    /// Starts tracking a request. If the same request is already pending,
+    /// the original send time is kept.
+    pub(crate) fn push_pending(&mut self, req: PendingRequest, now: time::Instant) {
+        if self.pending.len() < MAX_PENDING_REQUESTS {
+            self.pending.entry(req).or_insert(now);
+        }
+    }
+
+    /// Stops tracking a request, returning the time it took to get the response.
+    /// Returns None if the request was not pending.
+    pub(crate) fn pop_pending(
+        &mut self,
+        req: &PendingRequest,
+        now: time::Instant,
+    ) -> Option<time::Duration> {
+        self.pending.remove(req).map(|sent| now - sent)
+    }
+
+    /// Stops tracking requests sent more than `timeout` ago.
+    /// Returns the number of such requests which the peer was expected
+    /// to respond to, see `PendingRequest::expects_response`.
+    pub(crate) fn pop_expired(&mut self, now: time::Instant, timeout: time::Duration) -> usize {
+        let mut expired = 0;
+        self.pending.retain(|req, sent| {
+            if now - *sent < timeout {
+                return true;
+            }
+            expired += req.expects_response() as usize;
+            false
+        });
+        expired
+    }
 }
 
 #[cfg(test)]
@@ -97,6 +159,33 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_pending_requests() {
+        let clock = time::FakeClock::default();
+        let mut tracker = Tracker::default();
+        let req = PendingRequest::Block(hash(&[1]));
+        tracker.push_pending(req.clone(), clock.now());
+        tracker.push_pending(PendingRequest::BlockHeaders, clock.now());
+        clock.advance(time::Duration::seconds(2));
+        assert_eq!(tracker.pop_pending(&req, clock.now()), Some(time::Duration::seconds(2)));
+        assert_eq!(tracker.pop_pending(&req, clock.now()), None);
+        assert_eq!(tracker.pop_expired(clock.now(), time::Duration::seconds(3)), 0);
+        clock.advance(time::Duration::seconds(1));
+        assert_eq!(tracker.pop_expired(clock.now(), time::Duration::seconds(3)), 1);
+        assert_eq!(tracker.pop_pending(&PendingRequest::BlockHeaders, clock.now()), None);
+    }
+
+    #[test]
+    fn test_unanswered_block_request_is_not_timeout() {
+        let clock = time::FakeClock::default();
+        let mut tracker = Tracker::default();
+        let req = PendingRequest::Block(hash(&[1]));
+        tracker.push_pending(req.clone(), clock.now());
+        clock.advance(time::Duration::seconds(3));
+        assert_eq!(tracker.pop_expired(clock.now(), time::Duration::seconds(3)), 0);
+        assert_eq!(tracker.pop_pending(&req, clock.now()), None);
+    }
+
     #[test]
     #[should_panic]
     fn test_circular_queue_zero_capacity() {
@@ -86,10 +86,18 @@ pub(crate) struct Connection {
     pub tracked_shards: Vec<ShardId>,
     /// Denote if a node is running in archival mode or not.
     pub archival: bool,
+    /// Denote if a node is a light node.
+    pub light: bool,
     pub last_block: ArcSwap<Option<BlockInfo>>,
 
     /// Who started connection. Inbound (other) or Outbound (us).
     pub peer_type: PeerType,
+    /// Whether the frames are encrypted, see `peer::noise`.
+    pub encrypted: bool,
+    /// Whether the peer accepts compressed frames.
+    pub message_compression: bool,
+    /// Whether the connection uses the QUIC transport, see `crate::quic`.
+    pub quic: bool,
     /// Time where the connection was established.
     pub established_time: time::Instant,
 
@@ -125,6 +133,7 @@ impl Connection {
             last_block: *self.last_block.load().as_ref(),
             tracked_shards: self.tracked_shards.clone(),
             archival: self.archival,
+            light: self.light,
         };
         FullPeerInfo { peer_info: self.peer_info.clone(), chain_info }
     }
@@ -2,11 +2,13 @@ use crate::accounts_data::{AccountDataCache, AccountDataError};
 use crate::announce_accounts::AnnounceAccountCache;
 use crate::client;
 use crate::concurrency::demux;
+use crate::concurrency::rate;
 use crate::concurrency::runtime::Runtime;
 use crate::config;
+use crate::dns_seeds;
 use crate::network_protocol::{
-    Edge, EdgeState, PartialEdgeInfo, PeerIdOrHash, PeerInfo, PeerMessage, RawRoutedMessage,
-    RoutedMessageBody, RoutedMessageV2, SignedAccountData, SnapshotHostInfo,
+    DeliveryReceipt, Edge, EdgeState, PartialEdgeInfo, PeerIdOrHash, PeerInfo, PeerMessage,
+    RawRoutedMessage, RoutedMessageBody, RoutedMessageV2, SignedAccountData, SnapshotHostInfo,
 };
 use crate::peer::peer_actor::PeerActor;
 use crate::peer::peer_actor::{ClosingReason, ConnectionClosedEvent};
@@ -14,7 +16,10 @@ use crate::peer_manager::connection;
 use crate::peer_manager::connection_store;
 use crate::peer_manager::peer_manager_actor::Event;
 use crate::peer_manager::peer_store;
+use crate::peer_manager::reputation;
 use crate::private_actix::RegisterPeerError;
+use crate::recorder;
+use crate::routing::delivery_traces::DeliveryTraces;
 use crate::routing::route_back_cache::RouteBackCache;
 use crate::routing::NetworkTopologyChange;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
@@ -52,6 +57,9 @@ const IMPORTANT_MESSAGE_RESENT_COUNT: usize = 3;
 /// production of 1 block should fit).
 const RECENT_ROUTED_MESSAGES_CACHE_SIZE: usize = 10000;
 
+/// Number of peers for which we remember that they accept QUIC connections.
+const QUIC_PEERS_CACHE_SIZE: usize = 1000;
+
 /// How long a peer has to be unreachable, until we prune it from the in-memory graph.
 const PRUNE_UNREACHABLE_PEERS_AFTER: time::Duration = time::Duration::hours(1);
 
@@ -118,6 +126,10 @@ pub(crate) struct NetworkState {
     pub snapshot_hosts: Arc<SnapshotHostsCache>,
     /// Connection store that provides read/write access to stored connections.
     pub connection_store: connection_store::ConnectionStore,
+    /// Reputations of peers, based on the quality of data they served us.
+    pub reputations: reputation::Reputations,
+    /// Records the traffic of all the peers, if enabled in the config.
+    pub traffic_recorder: Option<recorder::TrafficRecorder>,
     /// List of peers to which we should re-establish a connection
     pub pending_reconnect: Mutex<Vec<PeerInfo>>,
     /// A graph of the whole UNC network.
@@ -129,6 +141,14 @@ pub(crate) struct NetworkState {
     /// Hashes of the body of recently received routed messages.
     /// It allows us to determine whether messages arrived faster over TIER1 or TIER2 network.
     pub recent_routed_messages: Mutex<lru::LruCache<CryptoHash, ()>>,
+    /// Routed messages sent by this node with a delivery trace requested,
+    /// see `NetworkConfig::trace_routed_messages`.
+    pub(crate) delivery_traces: Mutex<DeliveryTraces>,
+    /// Limits the rate of the `DeliveryReceipt`s sent by this node, since anyone can
+    /// request them, see `NetworkConfig::delivery_receipts_rate_limit`.
+    delivery_receipts: Mutex<rate::Limiter>,
+    /// Peers which have advertised in the Handshake that they accept QUIC connections.
+    pub quic_peers: Mutex<lru::LruCache<PeerId, ()>>,
 
     /// Hash of messages that requires routing back to respective previous hop.
     pub tier2_route_back: Mutex<RouteBackCache>,
@@ -194,6 +214,11 @@ impl NetworkState {
             peer_store,
             snapshot_hosts: Arc::new(SnapshotHostsCache::new(config.snapshot_hosts.clone())),
             connection_store: connection_store::ConnectionStore::new(store.clone()).unwrap(),
+            reputations: reputation::Reputations::new(store.clone()),
+            traffic_recorder: config
+                .traffic_recording
+                .clone()
+                .map(recorder::TrafficRecorder::spawn),
             pending_reconnect: Mutex::new(Vec::<PeerInfo>::new()),
             accounts_data: Arc::new(AccountDataCache::new()),
             account_announcements: Arc::new(AnnounceAccountCache::new(store)),
@@ -202,6 +227,12 @@ impl NetworkState {
             recent_routed_messages: Mutex::new(lru::LruCache::new(
                 RECENT_ROUTED_MESSAGES_CACHE_SIZE,
             )),
+            delivery_traces: Mutex::new(DeliveryTraces::new()),
+            delivery_receipts: Mutex::new(rate::Limiter::new(
+                config.delivery_receipts_rate_limit,
+                clock.now(),
+            )),
+            quic_peers: Mutex::new(lru::LruCache::new(QUIC_PEERS_CACHE_SIZE)),
             txns_since_last_block: AtomicUsize::new(0),
             whitelist_nodes,
             add_edges_demux: demux::Demux::new(config.routing_table_update_rate_limit),
@@ -392,6 +423,7 @@ impl NetworkState {
             // Save the fact that we are disconnecting to the PeerStore.
             let res = match reason {
                 ClosingReason::Ban(ban_reason) => {
+                    this.reputations.record_invalid_data(&clock, &conn.peer_info.id);
                     this.peer_store.peer_ban(&clock, &conn.peer_info.id, ban_reason)
                 }
                 _ => this.peer_store.peer_disconnected(&clock, &conn.peer_info.id),
@@ -412,6 +444,29 @@ impl NetworkState {
         });
     }
 
+    /// Establishes a connection to the given peer. QUIC is used if it is enabled in the config
+    /// and the peer is known to accept QUIC connections: either `quic` is set (the peer has
+    /// advertised it in its PeerAddr) or the peer has advertised it in an earlier Handshake.
+    /// Falls back to TCP if the QUIC connection cannot be established.
+    pub async fn connect(
+        &self,
+        peer_info: &PeerInfo,
+        tier: tcp::Tier,
+        quic: bool,
+    ) -> anyhow::Result<tcp::Stream> {
+        let quic =
+            self.config.quic_transport && (quic || self.quic_peers.lock().contains(&peer_info.id));
+        if quic {
+            match crate::quic::connect(peer_info, tier).await {
+                Ok(stream) => return Ok(stream),
+                Err(err) => {
+                    tracing::debug!(target: "network", ?err, %peer_info, "QUIC connect failed, falling back to TCP");
+                }
+            }
+        }
+        tcp::Stream::connect(peer_info, tier).await.context("tcp::Stream::connect()")
+    }
+
     /// Attempt to connect to the given peer until successful, up to max_attempts times
     pub async fn reconnect(
         self: &Arc<Self>,
@@ -424,9 +479,7 @@ impl NetworkState {
             interval.tick(&clock).await;
 
             let result = async {
-                let stream = tcp::Stream::connect(&peer_info, tcp::Tier::T2)
-                    .await
-                    .context("tcp::Stream::connect()")?;
+                let stream = self.connect(&peer_info, tcp::Tier::T2, false).await?;
                 PeerActor::spawn_and_handshake(clock.clone(), stream, None, self.clone())
                     .await
                     .context("PeerActor::spawn()")?;
@@ -479,11 +532,38 @@ impl NetworkState {
     }
 
     pub fn sign_message(&self, clock: &time::Clock, msg: RawRoutedMessage) -> Box<RoutedMessageV2> {
-        Box::new(msg.sign(
+        // Only messages addressed to a peer can be traced, since the receipt is accepted
+        // only from the target. Receipts themselves are never traced.
+        let trace = self.config.trace_routed_messages
+            && matches!(msg.target, PeerIdOrHash::PeerId(_))
+            && !matches!(msg.body, RoutedMessageBody::DeliveryReceipt(_));
+        let mut msg = Box::new(msg.sign(
             &self.config.node_key,
             self.config.routed_message_ttl,
             Some(clock.now_utc()),
-        ))
+        ));
+        if trace {
+            msg.trace = Some(vec![]);
+        }
+        msg
+    }
+
+    /// Sends a receipt for a traced message back to its author.
+    pub fn send_delivery_receipt(
+        &self,
+        clock: &time::Clock,
+        author: PeerId,
+        msg_hash: CryptoHash,
+        hops: Vec<PeerId>,
+    ) {
+        if !self.delivery_receipts.lock().try_acquire(clock.now()) {
+            tracing::debug!(target: "network", ?author, "Not sending delivery receipt: rate limit exceeded.");
+            metrics::DELIVERY_RECEIPTS_DROPPED.inc();
+            return;
+        }
+        let body = RoutedMessageBody::DeliveryReceipt(DeliveryReceipt { msg_hash, hops });
+        let msg = RawRoutedMessage { target: PeerIdOrHash::PeerId(author), body };
+        self.send_message_to_peer(clock, tcp::Tier::T2, self.sign_message(clock, msg));
     }
 
     /// Route signed message to target peer.
@@ -503,6 +583,14 @@ impl NetworkState {
                 metrics::CONNECTED_TO_MYSELF.inc();
                 return false;
             }
+            if msg.author == my_peer_id && msg.trace.is_some() {
+                self.delivery_traces.lock().sent(
+                    clock,
+                    msg.hash(),
+                    target.clone(),
+                    msg.body_variant(),
+                );
+            }
         }
         match tier {
             tcp::Tier::T1 => {
@@ -786,6 +874,16 @@ impl NetworkState {
         self.connection_store.update(clock, &self.tier2.load());
     }
 
+    /// Looks up the DNS seeds and adds the peers listed there to the peer store.
+    pub async fn refresh_dns_seeds(&self, clock: &time::Clock) {
+        let Some(cfg) = &self.config.dns_seeds else {
+            return;
+        };
+        let peers = dns_seeds::lookup(clock, cfg).await;
+        tracing::debug!(target: "network", num_peers = peers.len(), "got peers from DNS seeds");
+        self.peer_store.add_seed_peers(clock, peers.into_iter());
+    }
+
     /// Clears pending_reconnect and returns the cleared values
     pub fn poll_pending_reconnect(&self) -> Vec<PeerInfo> {
         let mut pending_reconnect = self.pending_reconnect.lock();
@@ -11,11 +11,12 @@ use crate::routing::NetworkTopologyChange;
 use crate::stats::metrics;
 use crate::tcp;
 use crate::types::ReasonForBan;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::sync::Arc;
 use unc_async::time;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::network::{AnnounceAccount, PeerId};
+use unc_primitives::views::RouteComparisonView;
 
 impl NetworkState {
     // TODO(gprusak): eventually, this should be blocking, as it should be up to the caller
@@ -170,6 +171,23 @@ impl NetworkState {
         }
     }
 
+    /// Routes to every peer reachable by any of the routing protocols, for debugging.
+    pub(crate) fn route_comparison(&self) -> Vec<RouteComparisonView> {
+        let v1 = self.graph.routing_table.info().next_hops;
+        let v2 = self.graph_v2.routing_table.info().next_hops;
+        let peers: BTreeSet<&PeerId> = v1.keys().chain(v2.keys()).collect();
+        peers
+            .into_iter()
+            .map(|peer_id| RouteComparisonView {
+                peer_id: peer_id.clone(),
+                v1_distance: self.graph.routing_table.get_distance(peer_id),
+                v2_distance: self.graph_v2.routing_table.get_distance(peer_id),
+                v1_next_hops: v1.get(peer_id).cloned().unwrap_or_default(),
+                v2_next_hops: v2.get(peer_id).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+
     pub(crate) fn tier2_find_route(
         &self,
         clock: &time::Clock,
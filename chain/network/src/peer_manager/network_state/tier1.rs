@@ -47,15 +47,17 @@ impl super::NetworkState {
             }
             handles.push(async move {
                 let res = async {
-                    let stream = tcp::Stream::connect(
-                        &PeerInfo {
-                            id: proxy.peer_id.clone(),
-                            addr: Some(proxy.addr),
-                            account_id: None,
-                        },
-                        tcp::Tier::T1,
-                    )
-                    .await?;
+                    let stream = self
+                        .connect(
+                            &PeerInfo {
+                                id: proxy.peer_id.clone(),
+                                addr: Some(proxy.addr),
+                                account_id: None,
+                            },
+                            tcp::Tier::T1,
+                            proxy.quic,
+                        )
+                        .await?;
                     anyhow::Ok(PeerActor::spawn_and_handshake(clock.clone(), stream, None, self.clone()).await?)
                 }.await;
                 if let Err(err) = res {
@@ -131,6 +133,7 @@ impl super::NetworkState {
                     vec![PeerAddr {
                         peer_id: self.config.node_id(),
                         addr: std::net::SocketAddr::new(node_ips[0], node_addr.port()),
+                        quic: self.config.quic_transport,
                     }]
                 }
             }
@@ -148,7 +151,11 @@ impl super::NetworkState {
                     log_assert!(PeerType::Outbound == conn.peer_type);
                     log_assert!(conn.peer_info.addr.is_some());
                     match conn.peer_info.addr {
-                        Some(addr) => vec![PeerAddr { peer_id: self.config.node_id(), addr }],
+                        Some(addr) => vec![PeerAddr {
+                            peer_id: self.config.node_id(),
+                            addr,
+                            quic: self.config.quic_transport,
+                        }],
                         None => vec![],
                     }
                 }
@@ -320,15 +327,17 @@ impl super::NetworkState {
                 if let Some(proxy) = proxy {
                     let proxy = (*proxy).clone();
                     handles.push(async move {
-                        let stream = tcp::Stream::connect(
-                            &PeerInfo {
-                                id: proxy.peer_id,
-                                addr: Some(proxy.addr),
-                                account_id: None,
-                            },
-                            tcp::Tier::T1,
-                        )
-                        .await?;
+                        let stream = self
+                            .connect(
+                                &PeerInfo {
+                                    id: proxy.peer_id,
+                                    addr: Some(proxy.addr),
+                                    account_id: None,
+                                },
+                                tcp::Tier::T1,
+                                proxy.quic,
+                            )
+                            .await?;
                         PeerActor::spawn_and_handshake(clock.clone(), stream, None, self.clone())
                             .await
                     });
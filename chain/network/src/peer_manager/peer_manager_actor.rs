@@ -8,6 +8,7 @@ use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::connection;
 use crate::peer_manager::network_state::{NetworkState, WhitelistNode};
 use crate::peer_manager::peer_store;
+use crate::quic;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
 use crate::stats::metrics;
 use crate::store;
@@ -37,8 +38,9 @@ use unc_performance_metrics_macros::perf;
 use unc_primitives::block::GenesisId;
 use unc_primitives::network::{AnnounceAccount, PeerId};
 use unc_primitives::views::{
-    ConnectionInfoView, EdgeView, KnownPeerStateView, NetworkGraphView, PeerStoreView,
-    RecentOutboundConnectionsView, SnapshotHostInfoView, SnapshotHostsView,
+    ConnectionInfoView, EdgeView, KnownPeerStateView, NetworkGraphView, PeerReputationView,
+    PeerReputationsView, PeerStoreView, RecentOutboundConnectionsView, SnapshotHostInfoView,
+    SnapshotHostsView,
 };
 
 /// Ratio between consecutive attempts to establish connection with another peer.
@@ -80,6 +82,8 @@ const PREFER_PREVIOUSLY_CONNECTED_PEER: f64 = 0.6;
 
 /// How often to update the connections in storage.
 pub(crate) const UPDATE_CONNECTION_STORE_INTERVAL: time::Duration = time::Duration::minutes(1);
+/// How often to save the peer reputations to the DB.
+pub(crate) const FLUSH_PEER_REPUTATIONS_INTERVAL: time::Duration = time::Duration::minutes(1);
 /// How often to poll the NetworkState for closed connections we'd like to re-establish.
 pub(crate) const POLL_CONNECTION_STORE_INTERVAL: time::Duration = time::Duration::minutes(1);
 
@@ -178,6 +182,17 @@ impl actix::Actor for PeerManagerActor {
             }
         }));
 
+        // Periodically save the peer reputations.
+        let clock = self.clock.clone();
+        let state = self.state.clone();
+        ctx.spawn(wrap_future(async move {
+            let mut interval = time::Interval::new(clock.now(), FLUSH_PEER_REPUTATIONS_INTERVAL);
+            loop {
+                interval.tick(&clock).await;
+                state.reputations.flush();
+            }
+        }));
+
         // Periodically prints bandwidth stats for each peer.
         self.report_bandwidth_stats_trigger(ctx, REPORT_BANDWIDTH_STATS_TRIGGER_INTERVAL);
 
@@ -251,6 +266,40 @@ impl PeerManagerActor {
                             panic!("failed to start listening on server_addr={server_addr:?} e={e:?}")
                         }
                     };
+                    if state.config.quic_transport {
+                        let mut listener = match quic::Listener::bind(server_addr) {
+                            Ok(it) => it,
+                            Err(e) => {
+                                panic!("failed to start QUIC listener on server_addr={server_addr:?} e={e:?}")
+                            }
+                        };
+                        arbiter.spawn({
+                            let clock = clock.clone();
+                            let state = state.clone();
+                            let arbiter = arbiter.clone();
+                            async move {
+                                while let Some(incoming) = listener.accept().await {
+                                    let clock = clock.clone();
+                                    let state = state.clone();
+                                    // Establishing the streams requires a roundtrip,
+                                    // so it shouldn't block accepting other connections.
+                                    arbiter.spawn(async move {
+                                        let stream = match incoming.establish().await {
+                                            Ok(stream) => stream,
+                                            Err(err) => {
+                                                tracing::info!(target:"network", ?err, "quic::Incoming::establish()");
+                                                return;
+                                            }
+                                        };
+                                        tracing::debug!(target: "network", from = ?stream.peer_addr, "got new QUIC connection");
+                                        if let Err(err) = PeerActor::spawn(clock, stream, None, state) {
+                                            tracing::info!(target:"network", ?err, "PeerActor::spawn()");
+                                        }
+                                    });
+                                }
+                            }
+                        });
+                    }
                     state.config.event_sink.push(Event::ServerStarted);
                     arbiter.spawn({
                         let clock = clock.clone();
@@ -302,6 +351,20 @@ impl PeerManagerActor {
                         }
                     });
                 }
+                if let Some(cfg) = &state.config.dns_seeds {
+                    // Periodically add the peers from the DNS seeds to the peer store.
+                    arbiter.spawn({
+                        let clock = clock.clone();
+                        let state = state.clone();
+                        let mut interval = time::Interval::new(clock.now(), cfg.refresh_interval);
+                        async move {
+                            loop {
+                                interval.tick(&clock).await;
+                                state.refresh_dns_seeds(&clock).await;
+                            }
+                        }
+                    });
+                }
                 // Periodically poll the connection store for connections we'd like to re-establish
                 arbiter.spawn({
                     let clock = clock.clone();
@@ -408,6 +471,8 @@ impl PeerManagerActor {
             .load()
             .ready
             .values()
+            // Light peers don't serve blocks.
+            .filter(|p| !p.light)
             .filter_map(|p| p.full_peer_info().into())
             .collect();
 
@@ -523,18 +588,30 @@ impl PeerManagerActor {
 
         // Sort by established time.
         active_peers.sort_by_key(|p| p.established_time);
-        // Saturate safe set with recently active peers.
+        // Saturate safe set with recently active peers, unless they have a bad reputation.
         let set_limit = self.state.config.safe_set_size as usize;
         for p in active_peers {
             if safe_set.len() >= set_limit {
                 break;
             }
+            if self.state.reputations.is_demoted(&self.clock, &p.peer_info.id) {
+                continue;
+            }
             safe_set.insert(p.peer_info.id.clone());
         }
 
         // Build valid candidate list to choose the peer to be removed. All peers outside the safe set.
-        let candidates = tier2.ready.values().filter(|p| !safe_set.contains(&p.peer_info.id));
-        if let Some(p) = candidates.choose(&mut rand::thread_rng()) {
+        // Among the candidates, the peer with the lowest reputation is removed. Candidates are
+        // shuffled first, so that the ties (e.g. peers we know nothing about) are broken randomly.
+        let mut candidates: Vec<_> =
+            tier2.ready.values().filter(|p| !safe_set.contains(&p.peer_info.id)).collect();
+        candidates.shuffle(&mut rand::thread_rng());
+        let worst = candidates.into_iter().min_by(|a, b| {
+            let a = self.state.reputations.score(&self.clock, &a.peer_info.id);
+            let b = self.state.reputations.score(&self.clock, &b.peer_info.id);
+            a.total_cmp(&b)
+        });
+        if let Some(p) = worst {
             tracing::debug!(target: "network", id = ?p.peer_info.id,
                 tier2_len = tier2.ready.len(),
                 ideal_connections_hi = self.state.config.ideal_connections_hi,
@@ -580,6 +657,8 @@ impl PeerManagerActor {
                     || self.state.config.node_addr.as_ref().map(|a|**a) == peer_state.peer_info.addr
                     // Or to peers we are currently trying to connect to
                     || tier2.outbound_handshakes.contains(&peer_state.peer_info.id)
+                    // Or to peers which served us bad data recently
+                    || self.state.reputations.is_demoted(&self.clock, &peer_state.peer_info.id)
                 },
                 prefer_previously_connected_peer,
             ) {
@@ -593,7 +672,7 @@ impl PeerManagerActor {
                     let clock = self.clock.clone();
                     async move {
                         let result = async {
-                            let stream = tcp::Stream::connect(&peer_info, tcp::Tier::T2).await.context("tcp::Stream::connect()")?;
+                            let stream = state.connect(&peer_info, tcp::Tier::T2, false).await?;
                             PeerActor::spawn_and_handshake(clock.clone(),stream,None,state.clone()).await.context("PeerActor::spawn()")?;
                             anyhow::Ok(())
                         }.await;
@@ -765,6 +844,27 @@ impl PeerManagerActor {
                     NetworkResponses::RouteNotFound
                 }
             }
+            NetworkRequests::LightClientBlockRequest { last_block_hash, peer_id } => {
+                if self.state.tier2.send_message(
+                    peer_id,
+                    Arc::new(PeerMessage::LightClientBlockRequest(last_block_hash)),
+                ) {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
+            NetworkRequests::LightClientProofRequest { request, peer_id } => {
+                if self
+                    .state
+                    .tier2
+                    .send_message(peer_id, Arc::new(PeerMessage::LightClientProofRequest(request)))
+                {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
             NetworkRequests::StateRequestHeader { shard_id, sync_hash, peer_id } => {
                 if self.state.tier2.send_message(
                     peer_id,
@@ -1124,7 +1224,13 @@ impl actix::Handler<GetDebugStatus> for PeerManagerActor {
                         .collect::<Vec<_>>(),
                 })
             }
-            GetDebugStatus::Routes => DebugStatus::Routes(self.state.graph_v2.get_debug_view()),
+            GetDebugStatus::Routes => {
+                let mut routes = self.state.graph_v2.get_debug_view();
+                routes.routed_message_traces =
+                    self.state.delivery_traces.lock().debug_view(&self.clock);
+                routes.route_comparison = self.state.route_comparison();
+                DebugStatus::Routes(routes)
+            }
             GetDebugStatus::SnapshotHosts => DebugStatus::SnapshotHosts(SnapshotHostsView {
                 hosts: self
                     .state
@@ -1139,6 +1245,25 @@ impl actix::Handler<GetDebugStatus> for PeerManagerActor {
                     })
                     .collect::<Vec<_>>(),
             }),
+            GetDebugStatus::PeerReputations => {
+                let mut peers = self
+                    .state
+                    .reputations
+                    .load(&self.clock)
+                    .into_iter()
+                    .map(|r| PeerReputationView {
+                        score: r.score(),
+                        avg_latency_millis: r.avg_latency().map(|l| l.whole_milliseconds() as i64),
+                        useful_bytes: r.useful_bytes,
+                        invalid_data: r.invalid_data,
+                        timeouts: r.timeouts,
+                        demoted: r.is_demoted(),
+                        peer_id: r.peer_id,
+                    })
+                    .collect::<Vec<_>>();
+                peers.sort_by(|a, b| b.score.total_cmp(&a.score));
+                DebugStatus::PeerReputations(PeerReputationsView { peers })
+            }
         }
     }
 }
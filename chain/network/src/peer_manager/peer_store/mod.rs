@@ -501,6 +501,20 @@ impl PeerStore {
         }
     }
 
+    /// Adds peers listed in the signed DNS seed records, see `crate::dns_seeds`.
+    ///
+    /// The records are signed by a key from the config, so the peers are trusted
+    /// just like the boot nodes.
+    pub fn add_seed_peers(&self, clock: &time::Clock, peers: impl Iterator<Item = PeerInfo>) {
+        let mut inner = self.0.lock();
+        for peer_info in peers {
+            if peer_info.addr.map_or(false, |addr| inner.config.blacklist.contains(addr)) {
+                continue;
+            }
+            inner.add_signed_peer(clock, peer_info);
+        }
+    }
+
     /// Adds a peer we’ve connected to but haven’t verified ID yet.
     ///
     /// We've connected to the host (thus know that the address is correct) and
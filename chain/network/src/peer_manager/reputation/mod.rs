@@ -0,0 +1,177 @@
+use crate::store;
+use crate::types::PeerReputation;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use unc_async::time;
+use unc_primitives::network::PeerId;
+
+#[cfg(test)]
+mod tests;
+
+/// Time after which the weight of a recorded event is halved.
+pub(crate) const HALF_LIFE: time::Duration = time::Duration::hours(6);
+/// Peers with score below this threshold are not selected for outbound connections,
+/// are not advertised to other peers and are the first candidates for eviction.
+pub(crate) const DEMOTION_THRESHOLD: f64 = -5.0;
+/// Maximal number of peers for which the reputation is tracked.
+/// Once reached, the least recently updated entry is dropped.
+pub(crate) const MAX_PEERS: usize = 1000;
+
+/// Score awarded for ln(1 + MiB of useful data served).
+const USEFUL_MIB_WEIGHT: f64 = 1.0;
+/// Penalty for each second of average response latency.
+const LATENCY_SECS_WEIGHT: f64 = 1.0;
+/// Penalty for each request which has timed out.
+const TIMEOUT_WEIGHT: f64 = 1.0;
+/// Penalty for each invalid message received. A peer is demoted after 4 invalid
+/// messages within a half-life rather than a single one, since a message may be
+/// invalid for us without the peer misbehaving, e.g. because of a fork.
+const INVALID_DATA_WEIGHT: f64 = 1.5;
+
+impl PeerReputation {
+    fn new(peer_id: PeerId, now: time::Utc) -> Self {
+        Self {
+            peer_id,
+            responses: 0.,
+            latency_secs: 0.,
+            useful_bytes: 0.,
+            invalid_data: 0.,
+            timeouts: 0.,
+            updated_at: now,
+        }
+    }
+
+    /// Scales down all the counters according to the time elapsed since the last update.
+    pub(crate) fn decay(&mut self, now: time::Utc) {
+        let elapsed = now - self.updated_at;
+        if elapsed <= time::Duration::ZERO {
+            return;
+        }
+        let factor = 0.5f64.powf(elapsed / HALF_LIFE);
+        self.responses *= factor;
+        self.latency_secs *= factor;
+        self.useful_bytes *= factor;
+        self.invalid_data *= factor;
+        self.timeouts *= factor;
+        self.updated_at = now;
+    }
+
+    /// Average latency of the responses, or None if there were no responses.
+    pub(crate) fn avg_latency(&self) -> Option<time::Duration> {
+        if self.responses <= 0. {
+            return None;
+        }
+        Some(time::Duration::seconds_f64(self.latency_secs / self.responses))
+    }
+
+    /// Score of the peer. 0 is neutral, positive values are good.
+    pub(crate) fn score(&self) -> f64 {
+        let useful_mib = self.useful_bytes / bytesize::MIB as f64;
+        let avg_latency_secs = self.avg_latency().map_or(0., |l| l.as_seconds_f64());
+        USEFUL_MIB_WEIGHT * useful_mib.ln_1p()
+            - LATENCY_SECS_WEIGHT * avg_latency_secs
+            - TIMEOUT_WEIGHT * self.timeouts
+            - INVALID_DATA_WEIGHT * self.invalid_data
+    }
+
+    pub(crate) fn is_demoted(&self) -> bool {
+        self.score() < DEMOTION_THRESHOLD
+    }
+}
+
+struct Inner {
+    store: store::Store,
+    peers: HashMap<PeerId, PeerReputation>,
+}
+
+impl Inner {
+    fn update(&mut self, now: time::Utc, peer_id: &PeerId, f: impl FnOnce(&mut PeerReputation)) {
+        if !self.peers.contains_key(peer_id) && self.peers.len() >= MAX_PEERS {
+            let oldest = self.peers.values().min_by_key(|r| r.updated_at).unwrap().peer_id.clone();
+            self.peers.remove(&oldest);
+        }
+        let r = self
+            .peers
+            .entry(peer_id.clone())
+            .or_insert_with(|| PeerReputation::new(peer_id.clone(), now));
+        r.decay(now);
+        f(r);
+    }
+
+    fn get(&self, now: time::Utc, peer_id: &PeerId) -> Option<PeerReputation> {
+        let mut r = self.peers.get(peer_id)?.clone();
+        r.decay(now);
+        Some(r)
+    }
+}
+
+/// Reputations of the peers, based on the quality of data they have served us.
+/// Kept in memory and periodically flushed to the DB, so that they survive restarts.
+pub(crate) struct Reputations(Mutex<Inner>);
+
+impl Reputations {
+    pub fn new(store: store::Store) -> Self {
+        let peers =
+            store.get_peer_reputations().into_iter().map(|r| (r.peer_id.clone(), r)).collect();
+        Self(Mutex::new(Inner { store, peers }))
+    }
+
+    /// Records a response to one of our requests.
+    /// `useful_bytes` is 0 if the response didn't contain the requested data.
+    pub fn record_response(
+        &self,
+        clock: &time::Clock,
+        peer_id: &PeerId,
+        latency: time::Duration,
+        useful_bytes: usize,
+    ) {
+        self.0.lock().update(clock.now_utc(), peer_id, |r| {
+            r.responses += 1.;
+            r.latency_secs += latency.as_seconds_f64().max(0.);
+            r.useful_bytes += useful_bytes as f64;
+        });
+    }
+
+    /// Records a request which didn't get a response in time.
+    pub fn record_timeout(&self, clock: &time::Clock, peer_id: &PeerId) {
+        self.0.lock().update(clock.now_utc(), peer_id, |r| r.timeouts += 1.);
+    }
+
+    /// Records a message which failed to parse or validate.
+    pub fn record_invalid_data(&self, clock: &time::Clock, peer_id: &PeerId) {
+        self.0.lock().update(clock.now_utc(), peer_id, |r| r.invalid_data += 1.);
+    }
+
+    /// Score of the peer. Peers we don't know anything about have a neutral score of 0.
+    pub fn score(&self, clock: &time::Clock, peer_id: &PeerId) -> f64 {
+        self.0.lock().get(clock.now_utc(), peer_id).map_or(0., |r| r.score())
+    }
+
+    pub fn is_demoted(&self, clock: &time::Clock, peer_id: &PeerId) -> bool {
+        self.0.lock().get(clock.now_utc(), peer_id).map_or(false, |r| r.is_demoted())
+    }
+
+    /// Returns the up-to-date reputations of all tracked peers.
+    pub fn load(&self, clock: &time::Clock) -> Vec<PeerReputation> {
+        let now = clock.now_utc();
+        let inner = self.0.lock();
+        inner
+            .peers
+            .values()
+            .map(|r| {
+                let mut r = r.clone();
+                r.decay(now);
+                r
+            })
+            .collect()
+    }
+
+    /// Saves the reputations to the DB.
+    pub fn flush(&self) {
+        let mut inner = self.0.lock();
+        let reputations: Vec<_> = inner.peers.values().cloned().collect();
+        if let Err(err) = inner.store.set_peer_reputations(&reputations) {
+            tracing::error!(target: "network", ?err, "Failed to save peer reputations");
+        }
+    }
+}
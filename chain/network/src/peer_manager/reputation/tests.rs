@@ -0,0 +1,124 @@
+use crate::network_protocol::testonly as data;
+use crate::peer_manager::reputation::{Reputations, HALF_LIFE, MAX_PEERS};
+use crate::store;
+use crate::testonly::make_rng;
+use unc_async::time;
+
+fn make_reputations() -> Reputations {
+    Reputations::new(store::Store::from(unc_store::db::TestDB::new()))
+}
+
+#[test]
+fn unknown_peer_is_neutral() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let reputations = make_reputations();
+    let peer_id = data::make_peer_id(&mut rng);
+    assert_eq!(reputations.score(&clock.clock(), &peer_id), 0.);
+    assert!(!reputations.is_demoted(&clock.clock(), &peer_id));
+}
+
+#[test]
+fn useful_data_beats_slow_and_invalid_data() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let clock = clock.clock();
+    let reputations = make_reputations();
+    let good = data::make_peer_id(&mut rng);
+    let slow = data::make_peer_id(&mut rng);
+    let bad = data::make_peer_id(&mut rng);
+
+    reputations.record_response(&clock, &good, time::Duration::milliseconds(100), 10_000_000);
+    reputations.record_response(&clock, &slow, time::Duration::seconds(3), 10_000_000);
+    reputations.record_response(&clock, &bad, time::Duration::milliseconds(100), 10_000_000);
+    for _ in 0..3 {
+        reputations.record_invalid_data(&clock, &bad);
+    }
+
+    let good_score = reputations.score(&clock, &good);
+    let slow_score = reputations.score(&clock, &slow);
+    let bad_score = reputations.score(&clock, &bad);
+    assert!(good_score > 0.);
+    assert!(good_score > slow_score);
+    assert!(slow_score > bad_score);
+}
+
+#[test]
+fn timeouts_demote_peer() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let clock = clock.clock();
+    let reputations = make_reputations();
+    let peer_id = data::make_peer_id(&mut rng);
+    for _ in 0..10 {
+        reputations.record_timeout(&clock, &peer_id);
+    }
+    assert!(reputations.is_demoted(&clock, &peer_id));
+}
+
+#[test]
+fn several_invalid_messages_demote_peer() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let clock = clock.clock();
+    let reputations = make_reputations();
+    let peer_id = data::make_peer_id(&mut rng);
+    for _ in 0..3 {
+        reputations.record_invalid_data(&clock, &peer_id);
+        assert!(!reputations.is_demoted(&clock, &peer_id));
+    }
+    reputations.record_invalid_data(&clock, &peer_id);
+    assert!(reputations.is_demoted(&clock, &peer_id));
+}
+
+#[test]
+fn penalties_decay_over_time() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let reputations = make_reputations();
+    let peer_id = data::make_peer_id(&mut rng);
+    for _ in 0..12 {
+        reputations.record_timeout(&clock.clock(), &peer_id);
+    }
+    let before = reputations.score(&clock.clock(), &peer_id);
+    clock.advance(HALF_LIFE);
+    let after = reputations.score(&clock.clock(), &peer_id);
+    assert!((after - before / 2.).abs() < 1e-6);
+    assert!(reputations.is_demoted(&clock.clock(), &peer_id));
+    clock.advance(HALF_LIFE * 2);
+    assert!(!reputations.is_demoted(&clock.clock(), &peer_id));
+}
+
+#[test]
+fn number_of_peers_is_bounded() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let reputations = make_reputations();
+    let first = data::make_peer_id(&mut rng);
+    reputations.record_timeout(&clock.clock(), &first);
+    for _ in 0..MAX_PEERS {
+        clock.advance(time::Duration::seconds(1));
+        reputations.record_timeout(&clock.clock(), &data::make_peer_id(&mut rng));
+    }
+    let peers = reputations.load(&clock.clock());
+    assert_eq!(peers.len(), MAX_PEERS);
+    assert!(peers.iter().all(|r| r.peer_id != first));
+}
+
+#[test]
+fn reload_from_storage() {
+    let mut rng = make_rng(921853233);
+    let clock = time::FakeClock::default();
+    let clock = clock.clock();
+    let store = store::Store::from(unc_store::db::TestDB::new());
+    let peer_id = data::make_peer_id(&mut rng);
+    let score = {
+        let reputations = Reputations::new(store.clone());
+        reputations.record_response(&clock, &peer_id, time::Duration::seconds(1), 1000);
+        reputations.record_invalid_data(&clock, &peer_id);
+        reputations.flush();
+        reputations.score(&clock, &peer_id)
+    };
+    let reputations = Reputations::new(store);
+    assert_eq!(reputations.score(&clock, &peer_id), score);
+}
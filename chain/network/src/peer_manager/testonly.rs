@@ -155,6 +155,15 @@ impl RawConnection {
     }
 }
 
+/// Connects to the peer over QUIC if `quic` is set, and over TCP otherwise.
+async fn connect(peer_info: &PeerInfo, tier: tcp::Tier, quic: bool) -> tcp::Stream {
+    if quic {
+        crate::quic::connect(peer_info, tier).await.unwrap()
+    } else {
+        tcp::Stream::connect(peer_info, tier).await.unwrap()
+    }
+}
+
 impl ActorHandler {
     pub fn peer_info(&self) -> PeerInfo {
         PeerInfo {
@@ -167,7 +176,7 @@ impl ActorHandler {
     pub async fn send_outbound_connect(&self, peer_info: &PeerInfo, tier: tcp::Tier) {
         let addr = self.actix.addr.clone();
         let peer_info = peer_info.clone();
-        let stream = tcp::Stream::connect(&peer_info, tier).await.unwrap();
+        let stream = connect(&peer_info, tier, self.cfg.quic_transport).await;
         addr.do_send(PeerManagerMessageRequest::OutboundTcpConnect(stream).with_span_context());
     }
 
@@ -179,8 +188,9 @@ impl ActorHandler {
         let addr = self.actix.addr.clone();
         let events = self.events.clone();
         let peer_info = peer_info.clone();
+        let quic = self.cfg.quic_transport;
         async move {
-            let stream = tcp::Stream::connect(&peer_info, tier).await.unwrap();
+            let stream = connect(&peer_info, tier, quic).await;
             let mut events = events.from_now();
             let stream_id = stream.id();
             addr.do_send(PeerManagerMessageRequest::OutboundTcpConnect(stream).with_span_context());
@@ -536,6 +546,15 @@ impl ActorHandler {
         .await;
     }
 
+    /// Executes `NetworkState::refresh_dns_seeds` method.
+    pub async fn refresh_dns_seeds(&self, clock: &time::Clock) {
+        let clock = clock.clone();
+        self.with_state(move |s| async move {
+            s.refresh_dns_seeds(&clock).await;
+        })
+        .await;
+    }
+
     /// Executes `NetworkState::update_connection_store` method.
     pub async fn update_connection_store(&self, clock: &time::Clock) {
         let clock = clock.clone();
@@ -103,6 +103,10 @@ async fn loop_connection() {
                 &pm.cfg.node_key,
             ),
             owned_account: None,
+            encrypted_transport: false,
+            message_compression: false,
+            quic_transport: false,
+            quic_session_signature: None,
         }))
         .await;
     let reason = events
@@ -171,6 +175,10 @@ async fn owned_account_mismatch() {
                 }
                 .sign(vc.signer.as_ref()),
             ),
+            encrypted_transport: false,
+            message_compression: false,
+            quic_transport: false,
+            quic_session_signature: None,
         }))
         .await;
     let reason = events
@@ -284,6 +292,10 @@ async fn invalid_edge() {
                     }
                     .sign(vc.signer.as_ref()),
                 ),
+                encrypted_transport: false,
+                message_compression: false,
+                quic_transport: false,
+                quic_session_signature: None,
             };
             let handshake = match tier {
                 tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
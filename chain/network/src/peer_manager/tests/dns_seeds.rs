@@ -0,0 +1,63 @@
+use crate::dns_seeds;
+use crate::network_protocol::testonly as data;
+use crate::peer_manager::testonly::start as start_pm;
+use crate::testonly::{abort_on_panic, make_rng};
+use std::sync::Arc;
+use unc_async::time;
+use unc_store::db::TestDB;
+
+const DOMAIN: &str = "seeds.unc.test";
+
+// Node discovers its peers only from the DNS seed and connects to them.
+// Peers from the records which are not signed by the configured key are ignored.
+#[tokio::test]
+async fn connect_to_dns_seed_peers() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+    let seed_key = data::make_secret_key(rng);
+    let server = Arc::new(dns_seeds::testonly::Server::default());
+
+    let pm1 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let pm2 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let expires_at = clock.now_utc() + time::Duration::hours(1);
+    server.set_records(
+        DOMAIN,
+        &[
+            dns_seeds::Record::new(DOMAIN, pm1.peer_info(), expires_at, &seed_key),
+            dns_seeds::Record::new(
+                DOMAIN,
+                pm2.peer_info(),
+                expires_at,
+                &data::make_secret_key(rng),
+            ),
+        ],
+    );
+
+    tracing::info!(target:"test", "start a node with the DNS seed configured");
+    let mut cfg = chain.make_config(rng);
+    cfg.dns_seeds = Some(dns_seeds::Config {
+        domains: vec![DOMAIN.to_string()],
+        public_key: seed_key.public_key(),
+        // Only the lookup at startup happens, further ones are triggered manually.
+        refresh_interval: time::Duration::hours(1000),
+        resolver: server.clone(),
+    });
+    let pm0 = start_pm(clock.clock(), TestDB::new(), cfg, chain.clone()).await;
+
+    tracing::info!(target:"test", "wait for the connection to the seed peer");
+    pm0.wait_for_direct_connection(pm1.cfg.node_id()).await;
+    let id2 = pm2.cfg.node_id();
+    let state = pm0.with_state(move |s| async move { s.peer_store.get_peer_state(&id2) }).await;
+    assert!(state.is_none());
+
+    tracing::info!(target:"test", "rotate the seed to the other peer");
+    server.set_records(
+        DOMAIN,
+        &[dns_seeds::Record::new(DOMAIN, pm2.peer_info(), expires_at, &seed_key)],
+    );
+    pm0.refresh_dns_seeds(&clock.clock()).await;
+    pm0.wait_for_direct_connection(pm2.cfg.node_id()).await;
+}
@@ -0,0 +1,97 @@
+use crate::network_protocol::testonly as data;
+use crate::network_protocol::{Ping, Pong};
+use crate::peer::peer_actor::ClosingReason;
+use crate::peer_manager::testonly::start as start_pm;
+use crate::peer_manager::testonly::ActorHandler;
+use crate::peer_manager::tests::routing::{wait_for_ping, wait_for_pong};
+use crate::tcp;
+use crate::testonly::{abort_on_panic, make_rng};
+use std::sync::Arc;
+use unc_async::time;
+use unc_primitives::network::PeerId;
+use unc_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
+use unc_store::db::TestDB;
+
+async fn is_encrypted(pm: &ActorHandler, peer_id: PeerId) -> bool {
+    pm.with_state(move |s| async move { s.tier2.load().ready.get(&peer_id).unwrap().encrypted })
+        .await
+}
+
+// Nodes with encryption enabled and disabled are connected into a line
+// and a ping is routed through both plaintext and encrypted connections.
+#[tokio::test]
+async fn plaintext_and_encrypted_peers() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    tracing::info!(target:"test", "start two encrypted and one plaintext node");
+    let pm0 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let pm1 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let mut cfg2 = chain.make_config(rng);
+    cfg2.encrypted_transport = false;
+    let pm2 = start_pm(clock.clock(), TestDB::new(), cfg2, chain.clone()).await;
+
+    let id0 = pm0.cfg.node_id();
+    let id1 = pm1.cfg.node_id();
+    let id2 = pm2.cfg.node_id();
+
+    tracing::info!(target:"test", "connect nodes in a line");
+    pm0.connect_to(&pm1.peer_info(), tcp::Tier::T2).await;
+    pm2.connect_to(&pm1.peer_info(), tcp::Tier::T2).await;
+
+    tracing::info!(target:"test", "wait for {id0} routing table");
+    pm0.wait_for_routing_table(&[
+        (id1.clone(), vec![id1.clone()]),
+        (id2.clone(), vec![id1.clone()]),
+    ])
+    .await;
+    tracing::info!(target:"test", "wait for {id2} routing table");
+    pm2.wait_for_routing_table(&[
+        (id0.clone(), vec![id1.clone()]),
+        (id1.clone(), vec![id1.clone()]),
+    ])
+    .await;
+
+    tracing::info!(target:"test", "check which connections are encrypted");
+    let supported = ProtocolFeature::EncryptedPeerTransport.protocol_version() <= PROTOCOL_VERSION;
+    assert_eq!(is_encrypted(&pm0, id1.clone()).await, supported);
+    assert_eq!(is_encrypted(&pm1, id0.clone()).await, supported);
+    assert!(!is_encrypted(&pm1, id2.clone()).await);
+    assert!(!is_encrypted(&pm2, id1.clone()).await);
+
+    let mut pm0_ev = pm0.events.from_now();
+    let mut pm2_ev = pm2.events.from_now();
+
+    tracing::info!(target:"test", "send ping from {id2} to {id0}");
+    pm2.send_ping(&clock.clock(), 0, id0.clone()).await;
+    tracing::info!(target:"test", "await ping at {id0}");
+    wait_for_ping(&mut pm0_ev, Ping { nonce: 0, source: id2.clone() }).await;
+    tracing::info!(target:"test", "await pong at {id2}");
+    wait_for_pong(&mut pm2_ev, Pong { nonce: 0, source: id0.clone() }).await;
+}
+
+// A node requiring encryption refuses the handshake of a plaintext peer.
+#[tokio::test]
+async fn plaintext_peer_refused() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut cfg = chain.make_config(rng);
+    cfg.require_encrypted_transport = true;
+    let pm = start_pm(clock.clock(), TestDB::new(), cfg, chain.clone()).await;
+
+    tracing::info!(target:"test", "connect a plaintext peer");
+    let mut peer_cfg = chain.make_config(rng);
+    peer_cfg.encrypted_transport = false;
+    let conn = pm.start_inbound(chain.clone(), peer_cfg).await;
+    assert_eq!(
+        ClosingReason::PlaintextNotAllowed,
+        conn.manager_fail_handshake(&clock.clock()).await
+    );
+}
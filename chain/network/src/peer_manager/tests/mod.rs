@@ -1,6 +1,9 @@
 mod accounts_data;
 mod connection_pool;
+mod dns_seeds;
+mod encryption;
 mod nonce;
+mod quic;
 mod routing;
 mod snapshot_hosts;
 mod tier1;
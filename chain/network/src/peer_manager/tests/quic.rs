@@ -0,0 +1,134 @@
+use crate::network_protocol::testonly as data;
+use crate::network_protocol::{Encoding, Handshake, PartialEdgeInfo, PeerMessage};
+use crate::peer::peer_actor::ClosingReason;
+use crate::peer_manager::peer_manager_actor::Event as PME;
+use crate::peer_manager::testonly::start as start_pm;
+use crate::peer_manager::testonly::Event;
+use crate::quic;
+use crate::tcp;
+use crate::testonly::stream::Stream;
+use crate::testonly::{abort_on_panic, make_rng};
+use std::sync::Arc;
+use unc_async::time;
+use unc_primitives::version::PROTOCOL_VERSION;
+use unc_store::db::TestDB;
+
+/// Key which signs the QUIC session in the Handshake.
+enum SessionSigner {
+    None,
+    WrongKey,
+    NodeKey,
+}
+
+// A peer has to sign the QUIC session with the key of the PeerId it claims in the Handshake,
+// otherwise a man-in-the-middle could relay the Handshake over its own QUIC sessions.
+#[tokio::test]
+async fn quic_session_signature() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut cfg = chain.make_config(rng);
+    cfg.quic_transport = true;
+    let pm = start_pm(clock.clock(), TestDB::new(), cfg, chain.clone()).await;
+
+    for signer in [SessionSigner::None, SessionSigner::WrongKey, SessionSigner::NodeKey] {
+        let cfg = chain.make_config(rng);
+        let stream = quic::connect(&pm.peer_info(), tcp::Tier::T2).await.unwrap();
+        let port = stream.local_addr.port();
+        let tcp::Transport::Quic(conn) = &stream.stream else { unreachable!() };
+        let payload = quic::session_signature_payload(&conn.session_id);
+        let quic_session_signature = match signer {
+            SessionSigner::None => None,
+            SessionSigner::WrongKey => Some(data::make_secret_key(rng).sign(&payload)),
+            SessionSigner::NodeKey => Some(cfg.node_key.sign(&payload)),
+        };
+        let mut events = pm.events.from_now();
+        let mut stream = Stream::new(Some(Encoding::Proto), stream);
+        stream
+            .write(&PeerMessage::Tier2Handshake(Handshake {
+                protocol_version: PROTOCOL_VERSION,
+                oldest_supported_version: PROTOCOL_VERSION,
+                sender_peer_id: cfg.node_id(),
+                target_peer_id: pm.cfg.node_id(),
+                sender_listen_port: Some(port),
+                sender_chain_info: chain.get_peer_chain_info(),
+                partial_edge_info: PartialEdgeInfo::new(
+                    &cfg.node_id(),
+                    &pm.cfg.node_id(),
+                    1,
+                    &cfg.node_key,
+                ),
+                owned_account: None,
+                encrypted_transport: false,
+                message_compression: false,
+                quic_transport: true,
+                quic_session_signature,
+            }))
+            .await;
+        // The local address of the QUIC endpoint is unspecified, so only the port identifies it.
+        let result = events
+            .recv_until(|ev| match ev {
+                Event::PeerManager(PME::ConnectionClosed(ev))
+                    if ev.stream_id.outbound.port() == port =>
+                {
+                    Some(Err(ev.reason))
+                }
+                Event::PeerManager(PME::HandshakeCompleted(ev))
+                    if ev.stream_id.outbound.port() == port =>
+                {
+                    Some(Ok(()))
+                }
+                _ => None,
+            })
+            .await;
+        match signer {
+            SessionSigner::None | SessionSigner::WrongKey => {
+                assert_eq!(Err(ClosingReason::QuicSessionMismatch), result)
+            }
+            SessionSigner::NodeKey => assert_eq!(Ok(()), result),
+        }
+    }
+}
+
+// QUIC is used for the peers which have advertised it in a Handshake,
+// and the connection falls back to TCP if the peer doesn't accept QUIC.
+#[tokio::test]
+async fn quic_is_used_once_advertised() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut cfg0 = chain.make_config(rng);
+    cfg0.quic_transport = true;
+    let mut cfg1 = chain.make_config(rng);
+    cfg1.quic_transport = true;
+    let mut cfg2 = chain.make_config(rng);
+    cfg2.quic_transport = false;
+    let pm0 = start_pm(clock.clock(), TestDB::new(), cfg0, chain.clone()).await;
+    let pm1 = start_pm(clock.clock(), TestDB::new(), cfg1, chain.clone()).await;
+    let pm2 = start_pm(clock.clock(), TestDB::new(), cfg2, chain.clone()).await;
+
+    let is_quic = |peer_info, quic| {
+        pm0.with_state(move |s| async move {
+            let stream = s.connect(&peer_info, tcp::Tier::T2, quic).await.unwrap();
+            matches!(stream.stream, tcp::Transport::Quic(_))
+        })
+    };
+
+    tracing::info!(target:"test", "connect over TCP to a peer which hasn't advertised QUIC yet");
+    assert!(!is_quic(pm1.peer_info(), false).await);
+
+    tracing::info!(target:"test", "exchange Handshakes");
+    pm0.connect_to(&pm1.peer_info(), tcp::Tier::T2).await;
+
+    tracing::info!(target:"test", "connect over QUIC to the peer which has advertised it");
+    assert!(is_quic(pm1.peer_info(), false).await);
+
+    tracing::info!(target:"test", "fall back to TCP if the peer doesn't accept QUIC");
+    assert!(!is_quic(pm2.peer_info(), true).await);
+}
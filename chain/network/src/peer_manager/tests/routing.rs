@@ -417,6 +417,70 @@ async fn ping_jump() {
     wait_for_pong(&mut pm0_ev, Pong { nonce: 0, source: id2.clone() }).await;
 }
 
+// test that a traced ping over several hops gets a delivery receipt with the path it travelled
+#[tokio::test]
+async fn traced_ping_jump() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    tracing::info!(target:"test", "start four nodes");
+    let mut cfg0 = chain.make_config(rng);
+    cfg0.trace_routed_messages = true;
+    let pm0 = start_pm(clock.clock(), TestDB::new(), cfg0, chain.clone()).await;
+    let pm1 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let pm2 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let pm3 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+
+    let id1 = pm1.cfg.node_id();
+    let id2 = pm2.cfg.node_id();
+    let id3 = pm3.cfg.node_id();
+
+    tracing::info!(target:"test", "connect nodes in a line");
+    pm0.connect_to(&pm1.peer_info(), tcp::Tier::T2).await;
+    pm1.connect_to(&pm2.peer_info(), tcp::Tier::T2).await;
+    pm2.connect_to(&pm3.peer_info(), tcp::Tier::T2).await;
+
+    tracing::info!(target:"test", "wait for routing tables");
+    pm0.wait_for_routing_table(&[
+        (id1.clone(), vec![id1.clone()]),
+        (id2.clone(), vec![id1.clone()]),
+        (id3.clone(), vec![id1.clone()]),
+    ])
+    .await;
+    pm3.wait_for_routing_table(&[
+        (pm0.cfg.node_id(), vec![id2.clone()]),
+        (id1.clone(), vec![id2.clone()]),
+        (id2.clone(), vec![id2.clone()]),
+    ])
+    .await;
+
+    let mut pm0_ev = pm0.events.from_now();
+    tracing::info!(target:"test", "send ping from {} to {id3}", pm0.cfg.node_id());
+    pm0.send_ping(&clock.clock(), 0, id3.clone()).await;
+
+    tracing::info!(target:"test", "await the delivery receipt");
+    pm0_ev
+        .recv_until(|ev| match ev {
+            Event::PeerManager(PME::MessageProcessed(_, PeerMessage::Routed(msg)))
+                if matches!(msg.body, RoutedMessageBody::DeliveryReceipt(_)) =>
+            {
+                Some(())
+            }
+            _ => None,
+        })
+        .await;
+    let traces = {
+        let clock = clock.clock();
+        pm0.with_state(|s| async move { s.delivery_traces.lock().debug_view(&clock) }).await
+    };
+    let trace = traces.iter().find(|t| t.target == id3).unwrap();
+    assert_eq!(Some(vec![id1, id2]), trace.hops);
+    assert!(trace.round_trip_millis.is_some());
+}
+
 // test that ping over an indirect connection with ttl=2 is delivered
 #[tokio::test]
 async fn test_dont_drop_after_ttl() {
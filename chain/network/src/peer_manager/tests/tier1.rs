@@ -136,7 +136,11 @@ async fn first_proxy_advertisement() {
     let got = pm.tier1_advertise_proxies(&clock.clock()).await;
     assert_eq!(
         got.unwrap().proxies,
-        vec![PeerAddr { peer_id: pm.cfg.node_id(), addr: **pm.cfg.node_addr.as_ref().unwrap() }]
+        vec![PeerAddr {
+            peer_id: pm.cfg.node_id(),
+            addr: **pm.cfg.node_addr.as_ref().unwrap(),
+            quic: pm.cfg.quic_transport,
+        }]
     );
 }
 
@@ -215,6 +219,7 @@ async fn proxy_connections() {
             config::ValidatorProxies::Static(vec![PeerAddr {
                 peer_id: proxies[i].cfg.node_id(),
                 addr: **proxies[i].cfg.node_addr.as_ref().unwrap(),
+                quic: proxies[i].cfg.quic_transport,
             }]);
         validators
             .push(start_pm(clock.clock(), unc_store::db::TestDB::new(), cfg, chain.clone()).await);
@@ -308,8 +313,16 @@ async fn proxy_change() {
     let p1cfg = chain.make_config(rng);
     let mut v0cfg = chain.make_config(rng);
     v0cfg.validator.as_mut().unwrap().proxies = config::ValidatorProxies::Static(vec![
-        PeerAddr { peer_id: p0cfg.node_id(), addr: **p0cfg.node_addr.as_ref().unwrap() },
-        PeerAddr { peer_id: p1cfg.node_id(), addr: **p1cfg.node_addr.as_ref().unwrap() },
+        PeerAddr {
+            peer_id: p0cfg.node_id(),
+            addr: **p0cfg.node_addr.as_ref().unwrap(),
+            quic: p0cfg.quic_transport,
+        },
+        PeerAddr {
+            peer_id: p1cfg.node_id(),
+            addr: **p1cfg.node_addr.as_ref().unwrap(),
+            quic: p1cfg.quic_transport,
+        },
     ]);
     let mut v1cfg = chain.make_config(rng);
     v1cfg.validator.as_mut().unwrap().proxies = config::ValidatorProxies::Static(vec![]);
@@ -409,7 +422,11 @@ async fn stun_self_discovery() {
     let chain_info = peer_manager::testonly::make_chain_info(&chain, &[&pm.cfg]);
     pm.set_chain_info(chain_info).await;
     let got = pm.tier1_advertise_proxies(&clock.clock()).await.unwrap();
-    let want = vec![PeerAddr { peer_id: pm.cfg.node_id(), addr: *pm.cfg.node_addr.unwrap() }];
+    let want = vec![PeerAddr {
+        peer_id: pm.cfg.node_id(),
+        addr: *pm.cfg.node_addr.unwrap(),
+        quic: pm.cfg.quic_transport,
+    }];
     assert_eq!(want, got.proxies);
 
     tracing::info!(target:"test", "close the stun servers");
@@ -0,0 +1,222 @@
+//! QUIC transport for the peer connections, an alternative to TCP.
+//!
+//! A QUIC connection carries 2 bidirectional streams, so that large messages
+//! (blocks, state parts) don't delay the consensus-critical ones (approvals,
+//! chunk parts), as it happens with a single TCP stream:
+//! * the consensus stream, which carries the Handshake and all the messages
+//!   which are not bulk.
+//! * the bulk stream, which carries the `MessageClass::Sync` messages.
+//!
+//! Both streams are opened by the connecting side right after the connection is established.
+//! The first byte of each stream identifies it, since streams become visible to the other
+//! side only once some data is sent over them.
+//!
+//! QUIC always encrypts the traffic, however the certificates are self-signed and not
+//! verified. Instead, each side signs the TLS session of the connection (keying material
+//! exported from it, see RFC 5705) with its node key and sends the signature in its
+//! Handshake. A man-in-the-middle terminates 2 different TLS sessions, so it cannot
+//! provide a valid signature of the session it has with either side.
+use crate::network_protocol::PeerInfo;
+use crate::tcp;
+use anyhow::{anyhow, Context as _};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+/// Application protocol negotiated in the TLS handshake.
+const ALPN: &[u8] = b"unc/1";
+/// Server name used in TLS, it is not verified.
+const SERVER_NAME: &str = "unc";
+/// Same as the connect timeout of TCP, see `tcp::Stream::connect`.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+const MAX_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Flow control window of a single stream. Larger than the default,
+/// so that bulk transfers are not throttled on high-latency links.
+const STREAM_RECEIVE_WINDOW: u32 = 8 * 1024 * 1024;
+
+/// Label of the keying material exported from the TLS session, see `session_id`.
+const SESSION_ID_LABEL: &[u8] = b"EXPORTER-unc-network-session";
+/// Domain separator of the signature of the TLS session.
+const SESSION_SIGNATURE_PREFIX: &[u8] = b"unc-network-quic-session:";
+
+const CONSENSUS_STREAM_TAG: u8 = 0;
+const BULK_STREAM_TAG: u8 = 1;
+
+type BiStream = (quinn::SendStream, quinn::RecvStream);
+
+/// Identifies the TLS session of a connection: both sides of the connection
+/// derive the same id, while different sessions have different ids.
+pub(crate) type SessionId = [u8; 32];
+
+fn session_id(conn: &quinn::Connection) -> SessionId {
+    let mut id = SessionId::default();
+    conn.export_keying_material(&mut id, SESSION_ID_LABEL, &[])
+        .expect("keying material of this length can always be exported");
+    id
+}
+
+/// Payload signed by each side of the connection in its Handshake.
+pub(crate) fn session_signature_payload(session_id: &SessionId) -> Vec<u8> {
+    [SESSION_SIGNATURE_PREFIX, &session_id[..]].concat()
+}
+
+/// An established QUIC connection with both of its streams.
+#[derive(Debug)]
+pub(crate) struct Connection {
+    /// The connection is kept open as long as any of its streams is in use.
+    pub(crate) conn: quinn::Connection,
+    pub(crate) session_id: SessionId,
+    pub(crate) consensus: BiStream,
+    pub(crate) bulk: BiStream,
+}
+
+fn transport_config() -> quinn::TransportConfig {
+    let mut cfg = quinn::TransportConfig::default();
+    cfg.max_concurrent_bidi_streams(2u32.into());
+    cfg.max_concurrent_uni_streams(0u32.into());
+    cfg.stream_receive_window(STREAM_RECEIVE_WINDOW.into());
+    cfg.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    cfg.max_idle_timeout(Some(MAX_IDLE_TIMEOUT.try_into().unwrap()));
+    cfg
+}
+
+fn server_config() -> anyhow::Result<quinn::ServerConfig> {
+    let cert = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])?;
+    let key = rustls::PrivateKey(cert.serialize_private_key_der());
+    let cert = rustls::Certificate(cert.serialize_der()?);
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)?;
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+    let mut cfg = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    cfg.transport_config(Arc::new(transport_config()));
+    Ok(cfg)
+}
+
+/// Accepts any server certificate, see the module comment.
+struct SkipServerVerification;
+
+impl rustls::client::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+fn client_config() -> quinn::ClientConfig {
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+    let mut cfg = quinn::ClientConfig::new(Arc::new(crypto));
+    cfg.transport_config(Arc::new(transport_config()));
+    cfg
+}
+
+async fn open_stream(conn: &quinn::Connection, tag: u8) -> anyhow::Result<BiStream> {
+    let (mut send, recv) = conn.open_bi().await.context("open_bi()")?;
+    send.write_u8(tag).await?;
+    Ok((send, recv))
+}
+
+/// Connects to the QUIC listener of the peer, at the same address as its TCP listener.
+pub(crate) async fn connect(peer_info: &PeerInfo, tier: tcp::Tier) -> anyhow::Result<tcp::Stream> {
+    let addr = peer_info.addr.ok_or(anyhow!("Trying to connect to peer with no public address"))?;
+    // Every outbound connection uses a separate endpoint, so that it has a unique local port,
+    // just like an outbound TCP connection.
+    let bind_addr: SocketAddr = match addr {
+        SocketAddr::V4(_) => "0.0.0.0:0".parse().unwrap(),
+        SocketAddr::V6(_) => "[::]:0".parse().unwrap(),
+    };
+    let endpoint = quinn::Endpoint::client(bind_addr).context("Endpoint::client()")?;
+    let local_addr = endpoint.local_addr()?;
+    let conn = tokio::time::timeout(CONNECT_TIMEOUT, async {
+        let conn = endpoint.connect_with(client_config(), addr, SERVER_NAME)?.await?;
+        let consensus = open_stream(&conn, CONSENSUS_STREAM_TAG).await?;
+        let bulk = open_stream(&conn, BULK_STREAM_TAG).await?;
+        anyhow::Ok(Connection { session_id: session_id(&conn), conn, consensus, bulk })
+    })
+    .await
+    .context("QUIC connect timeout")??;
+    Ok(tcp::Stream {
+        peer_addr: conn.conn.remote_address(),
+        local_addr,
+        stream: tcp::Transport::Quic(conn),
+        type_: tcp::StreamType::Outbound { peer_id: peer_info.id.clone(), tier },
+    })
+}
+
+pub(crate) struct Listener {
+    endpoint: quinn::Endpoint,
+    local_addr: SocketAddr,
+}
+
+impl Listener {
+    pub fn bind(addr: &tcp::ListenerAddr) -> anyhow::Result<Self> {
+        let socket = addr.udp_socket().context("bind()")?;
+        let local_addr = socket.local_addr()?;
+        let endpoint = quinn::Endpoint::new(
+            quinn::EndpointConfig::default(),
+            Some(server_config()?),
+            socket,
+            Arc::new(quinn::TokioRuntime),
+        )?;
+        Ok(Self { endpoint, local_addr })
+    }
+
+    /// Waits for the next incoming connection. Returns None once the listener is closed.
+    /// The connection has to be established with `Incoming::establish()`, which may take
+    /// a while, so it shouldn't block accepting further connections.
+    pub async fn accept(&mut self) -> Option<Incoming> {
+        let connecting = self.endpoint.accept().await?;
+        Some(Incoming { connecting, local_addr: self.local_addr })
+    }
+}
+
+pub(crate) struct Incoming {
+    connecting: quinn::Connecting,
+    local_addr: SocketAddr,
+}
+
+impl Incoming {
+    pub async fn establish(self) -> anyhow::Result<tcp::Stream> {
+        let conn = tokio::time::timeout(CONNECT_TIMEOUT, async {
+            let conn = self.connecting.await?;
+            let mut consensus = None;
+            let mut bulk = None;
+            while consensus.is_none() || bulk.is_none() {
+                let (send, mut recv) = conn.accept_bi().await.context("accept_bi()")?;
+                match recv.read_u8().await? {
+                    CONSENSUS_STREAM_TAG => consensus = Some((send, recv)),
+                    BULK_STREAM_TAG => bulk = Some((send, recv)),
+                    tag => anyhow::bail!("unknown stream tag {tag}"),
+                }
+            }
+            anyhow::Ok(Connection {
+                session_id: session_id(&conn),
+                conn,
+                consensus: consensus.unwrap(),
+                bulk: bulk.unwrap(),
+            })
+        })
+        .await
+        .context("QUIC accept timeout")??;
+        Ok(tcp::Stream {
+            peer_addr: conn.conn.remote_address(),
+            local_addr: self.local_addr,
+            stream: tcp::Transport::Quic(conn),
+            type_: tcp::StreamType::Inbound,
+        })
+    }
+}
@@ -211,9 +211,14 @@ fn new_handshake(
             height: head_height,
             tracked_shards,
             archival,
+            light: false,
         },
         partial_edge_info: PartialEdgeInfo::new(my_peer_id, target_peer_id, nonce, secret_key),
         owned_account: None,
+        encrypted_transport: false,
+        message_compression: false,
+        quic_transport: false,
+        quic_session_signature: None,
     })
 }
 
@@ -0,0 +1,207 @@
+//! Recording of the network traffic of a node.
+//!
+//! When enabled in the config, every `PeerMessage` sent or received by a `PeerActor`
+//! is written (together with the time and the id of the peer) to a sequence of files
+//! in the configured directory. Once the current file exceeds the size limit a new one
+//! is started and the oldest files are deleted, so that the recording never grows
+//! beyond `max_files * max_file_size`.
+//!
+//! The recording can be read back with `read_recording` and fed into the node's
+//! components with `replay::Replayer`, to reproduce the exact sequence of messages
+//! a node has received.
+use crate::config::TrafficRecording;
+use crate::network_protocol::{Encoding, ParsePeerMessageError, PeerMessage};
+use crate::stats::metrics;
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use unc_async::time;
+use unc_primitives::network::PeerId;
+
+pub mod replay;
+#[cfg(test)]
+mod tests;
+
+/// Extension of the recording files.
+const FILE_EXTENSION: &str = "traffic";
+
+/// Maximal number of messages waiting to be written. When the writer thread
+/// falls behind, new messages are dropped rather than buffered without a bound.
+const MAX_QUEUED_MESSAGES: usize = 10_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+/// A single message sent or received by the node.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
+pub struct RecordedMessage {
+    /// UNIX timestamp in nanos.
+    pub timestamp: u64,
+    pub direction: Direction,
+    /// Peer which has sent or received the message.
+    /// None for inbound messages received before the handshake.
+    pub peer_id: Option<PeerId>,
+    /// The message, in `Encoding::Proto`.
+    pub message: Vec<u8>,
+}
+
+impl RecordedMessage {
+    pub fn new(
+        time: time::Utc,
+        direction: Direction,
+        peer_id: Option<PeerId>,
+        msg: &PeerMessage,
+    ) -> Self {
+        Self {
+            timestamp: time.unix_timestamp_nanos() as u64,
+            direction,
+            peer_id,
+            message: msg.serialize(Encoding::Proto),
+        }
+    }
+
+    pub fn time(&self) -> time::Utc {
+        time::Utc::from_unix_timestamp_nanos(self.timestamp as i128).unwrap()
+    }
+
+    pub(crate) fn message(&self) -> Result<PeerMessage, ParsePeerMessageError> {
+        PeerMessage::deserialize(Encoding::Proto, &self.message)
+    }
+}
+
+impl fmt::Debug for RecordedMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg_type = self.message().map_or("<invalid>", |msg| msg.msg_variant());
+        f.debug_struct("RecordedMessage")
+            .field("time", &self.time())
+            .field("direction", &self.direction)
+            .field("peer_id", &self.peer_id)
+            .field("type", &msg_type)
+            .field("len", &self.message.len())
+            .finish()
+    }
+}
+
+/// Writes the recorded messages to the files on a dedicated thread,
+/// so that the disk IO doesn't block the PeerActors.
+pub(crate) struct TrafficRecorder {
+    sender: mpsc::SyncSender<RecordedMessage>,
+}
+
+impl TrafficRecorder {
+    /// Starts the writer thread. Errors are logged and stop the recording,
+    /// they don't affect the node otherwise.
+    pub fn spawn(config: TrafficRecording) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(MAX_QUEUED_MESSAGES);
+        std::thread::spawn(move || {
+            if let Err(err) = write_recording(config, receiver) {
+                tracing::error!(target: "network", ?err, "Traffic recording failed");
+            }
+        });
+        Self { sender }
+    }
+
+    pub fn record(
+        &self,
+        clock: &time::Clock,
+        direction: Direction,
+        peer_id: Option<PeerId>,
+        msg: &PeerMessage,
+    ) {
+        let msg = RecordedMessage::new(clock.now_utc(), direction, peer_id, msg);
+        // A disconnected channel means that the recording has failed, which has already been logged.
+        if let Err(mpsc::TrySendError::Full(_)) = self.sender.try_send(msg) {
+            metrics::TRAFFIC_RECORDING_DROPPED_MESSAGES.inc();
+        }
+    }
+}
+
+/// Paths of the recording files in the directory, oldest first.
+fn recording_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == FILE_EXTENSION) {
+            files.push(path);
+        }
+    }
+    // File names are zero-padded sequence numbers, so they sort chronologically.
+    files.sort();
+    Ok(files)
+}
+
+fn write_recording(
+    config: TrafficRecording,
+    receiver: mpsc::Receiver<RecordedMessage>,
+) -> io::Result<()> {
+    fs::create_dir_all(&config.dir)?;
+    let mut files = recording_files(&config.dir)?;
+    // Continue the sequence after the files of the previous runs.
+    let mut next_index = match files.last() {
+        Some(path) => {
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            stem.parse::<u64>().map_or(0, |i| i + 1)
+        }
+        None => 0,
+    };
+    let mut file: Option<(io::BufWriter<fs::File>, u64)> = None;
+    while let Ok(msg) = receiver.recv() {
+        let mut msgs = vec![msg];
+        msgs.extend(receiver.try_iter());
+        for msg in msgs {
+            if file.as_ref().map_or(true, |(_, size)| *size >= config.max_file_size) {
+                if let Some((mut writer, _)) = file.take() {
+                    writer.flush()?;
+                }
+                let path = config.dir.join(format!("{next_index:020}.{FILE_EXTENSION}"));
+                next_index += 1;
+                files.push(path.clone());
+                while files.len() > config.max_files.max(1) {
+                    fs::remove_file(files.remove(0))?;
+                }
+                file = Some((io::BufWriter::new(fs::File::create(path)?), 0));
+            }
+            let (writer, size) = file.as_mut().unwrap();
+            let data = borsh::to_vec(&msg)?;
+            writer.write_all(&(data.len() as u32).to_le_bytes())?;
+            writer.write_all(&data)?;
+            *size += 4 + data.len() as u64;
+        }
+        // Flush whenever there is nothing more to write, so that the recording
+        // is up to date even if the node gets killed.
+        if let Some((writer, _)) = &mut file {
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads all the messages recorded in the directory, oldest first.
+/// A truncated message at the end of a file (e.g. because the node was
+/// killed while writing it) is skipped.
+pub fn read_recording(dir: &Path) -> io::Result<Vec<RecordedMessage>> {
+    let mut msgs = vec![];
+    for path in recording_files(dir)? {
+        let mut reader = io::BufReader::new(fs::File::open(&path)?);
+        loop {
+            let mut len = [0u8; 4];
+            let mut data = vec![];
+            let res = reader.read_exact(&mut len).and_then(|()| {
+                data.resize(u32::from_le_bytes(len) as usize, 0);
+                reader.read_exact(&mut data)
+            });
+            match res {
+                Ok(()) => msgs.push(RecordedMessage::try_from_slice(&data)?),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(msgs)
+}
@@ -0,0 +1,83 @@
+//! Delivery of the recorded messages to the components of a node.
+use crate::client;
+use crate::network_protocol::{PeerIdOrHash, PeerMessage};
+use crate::peer::dispatch;
+use crate::recorder::{Direction, RecordedMessage};
+use crate::shards_manager::ShardsManagerRequestFromNetwork;
+use std::collections::HashSet;
+use std::sync::Arc;
+use unc_async::messaging::Sender;
+use unc_async::time;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::network::PeerId;
+
+/// Feeds the recorded inbound messages into the Client and the ShardsManager,
+/// with the same dispatch logic as `PeerActor` uses for the received messages.
+/// Responses produced by the node are dropped, since there is no peer to send them to.
+pub struct Replayer {
+    node_id: PeerId,
+    client: Arc<dyn client::Client>,
+    shards_manager: Sender<ShardsManagerRequestFromNetwork>,
+    /// Blocks requested by the recorded node, so that the received blocks
+    /// are reported to the Client with the same `was_requested` flag.
+    requested_blocks: HashSet<CryptoHash>,
+}
+
+impl Replayer {
+    /// `node_id` is the id of the node which has recorded the traffic:
+    /// routed messages addressed to other peers are skipped.
+    pub fn new(
+        node_id: PeerId,
+        client: Arc<dyn client::Client>,
+        shards_manager: Sender<ShardsManagerRequestFromNetwork>,
+    ) -> Self {
+        Self { node_id, client, shards_manager, requested_blocks: HashSet::new() }
+    }
+
+    /// Delivers a single recorded message.
+    /// Returns an error if the message cannot be parsed or if the node would have banned the peer.
+    pub async fn replay(
+        &mut self,
+        clock: &time::Clock,
+        msg: &RecordedMessage,
+    ) -> anyhow::Result<()> {
+        let peer_msg = msg.message()?;
+        let peer_id = match (msg.direction, &msg.peer_id) {
+            (Direction::Outbound, _) => {
+                if let PeerMessage::BlockRequest(hash) = &peer_msg {
+                    self.requested_blocks.insert(*hash);
+                }
+                return Ok(());
+            }
+            // Messages received before the handshake are only relevant to the connection itself.
+            (Direction::Inbound, None) => return Ok(()),
+            (Direction::Inbound, Some(peer_id)) => peer_id.clone(),
+        };
+        if let PeerMessage::Routed(msg) = &peer_msg {
+            let for_me = match &msg.target {
+                PeerIdOrHash::PeerId(target) => target == &self.node_id,
+                // Route back messages are responses to requests of the recorded node.
+                PeerIdOrHash::Hash(_) => true,
+            };
+            if !for_me {
+                return Ok(());
+            }
+        }
+        let was_requested = match &peer_msg {
+            PeerMessage::Block(block) => self.requested_blocks.remove(block.hash()),
+            _ => false,
+        };
+        // Responses produced by the node are dropped, since there is no peer to send them to.
+        dispatch::receive_message(
+            clock,
+            self.client.as_ref(),
+            &self.shards_manager,
+            peer_id,
+            peer_msg,
+            was_requested,
+        )
+        .await
+        .map_err(|ban| anyhow::anyhow!("peer would have been banned: {ban:?}"))?;
+        Ok(())
+    }
+}
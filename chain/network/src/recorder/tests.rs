@@ -0,0 +1,176 @@
+use crate::config::TrafficRecording;
+use crate::network_protocol::testonly as data;
+use crate::network_protocol::{
+    Encoding, PeerIdOrHash, PeerMessage, RawRoutedMessage, RoutedMessageBody,
+};
+use crate::recorder::replay::Replayer;
+use crate::recorder::{
+    read_recording, recording_files, write_recording, Direction, RecordedMessage, TrafficRecorder,
+};
+use crate::sink::Sink;
+use crate::stats::metrics;
+use crate::testonly::fake_client::{Event, Fake};
+use crate::testonly::make_rng;
+use parking_lot::Mutex;
+use std::sync::{mpsc, Arc};
+use unc_async::messaging::IntoSender as _;
+use unc_async::time;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::network::PeerId;
+
+fn make_messages(n: usize) -> Vec<RecordedMessage> {
+    let mut rng = make_rng(89028037453);
+    let clock = time::FakeClock::default();
+    (0..n)
+        .map(|i| {
+            clock.advance(time::Duration::milliseconds(10));
+            let msg = PeerMessage::BlockRequest(CryptoHash::hash_bytes(&i.to_le_bytes()));
+            RecordedMessage {
+                timestamp: clock.now_utc().unix_timestamp_nanos() as u64,
+                direction: if i % 2 == 0 { Direction::Inbound } else { Direction::Outbound },
+                peer_id: Some(data::make_peer_id(&mut rng)),
+                message: msg.serialize(Encoding::Proto),
+            }
+        })
+        .collect()
+}
+
+fn write(config: &TrafficRecording, msgs: &[RecordedMessage]) {
+    let (sender, receiver) = mpsc::channel();
+    for msg in msgs {
+        sender.send(msg.clone()).unwrap();
+    }
+    drop(sender);
+    write_recording(config.clone(), receiver).unwrap();
+}
+
+#[test]
+fn write_and_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = TrafficRecording {
+        dir: dir.path().to_path_buf(),
+        max_file_size: bytesize::MIB,
+        max_files: 16,
+    };
+    let msgs = make_messages(100);
+    write(&config, &msgs[..50]);
+    // A restarted node continues the recording in a new file.
+    write(&config, &msgs[50..]);
+    assert_eq!(recording_files(&config.dir).unwrap().len(), 2);
+    let got = read_recording(&config.dir).unwrap();
+    assert_eq!(got, msgs);
+    for (got, want) in std::iter::zip(&got, &msgs) {
+        assert_eq!(
+            got.message().unwrap(),
+            PeerMessage::deserialize(Encoding::Proto, &want.message).unwrap()
+        );
+    }
+}
+
+#[test]
+fn oldest_files_are_deleted() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = TrafficRecording { dir: dir.path().to_path_buf(), max_file_size: 1, max_files: 3 };
+    // Every message exceeds the size limit, so each one is written to a separate file.
+    let msgs = make_messages(10);
+    write(&config, &msgs);
+    assert_eq!(recording_files(&config.dir).unwrap().len(), 3);
+    assert_eq!(read_recording(&config.dir).unwrap(), msgs[7..]);
+}
+
+#[test]
+fn truncated_message_is_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = TrafficRecording {
+        dir: dir.path().to_path_buf(),
+        max_file_size: bytesize::MIB,
+        max_files: 16,
+    };
+    let msgs = make_messages(5);
+    write(&config, &msgs);
+    let path = recording_files(&config.dir).unwrap().pop().unwrap();
+    let data = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &data[..data.len() - 3]).unwrap();
+    assert_eq!(read_recording(&config.dir).unwrap(), msgs[..4]);
+}
+
+#[test]
+fn full_queue_drops_messages() {
+    let (sender, receiver) = mpsc::sync_channel(2);
+    let recorder = TrafficRecorder { sender };
+    let clock = time::FakeClock::default();
+    let dropped = metrics::TRAFFIC_RECORDING_DROPPED_MESSAGES.get();
+    for i in 0..3u64 {
+        let msg = PeerMessage::BlockRequest(CryptoHash::hash_bytes(&i.to_le_bytes()));
+        recorder.record(&clock.clock(), Direction::Inbound, None, &msg);
+    }
+    assert_eq!(metrics::TRAFFIC_RECORDING_DROPPED_MESSAGES.get(), dropped + 1);
+    assert_eq!(receiver.try_iter().count(), 2);
+}
+
+fn record(direction: Direction, peer_id: Option<PeerId>, msg: &PeerMessage) -> RecordedMessage {
+    RecordedMessage { timestamp: 0, direction, peer_id, message: msg.serialize(Encoding::Proto) }
+}
+
+/// Checks that only the inbound messages addressed to the recording node
+/// are delivered to the Client and the ShardsManager.
+#[tokio::test]
+async fn replayer_delivers_inbound_messages() {
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let events = Arc::new(Mutex::new(vec![]));
+    let client = Arc::new(Fake {
+        event_sink: Sink::new({
+            let events = events.clone();
+            move |event| events.lock().push(event)
+        }),
+    });
+    let node_id = data::make_peer_id(rng);
+    let peer_id = data::make_peer_id(rng);
+    let mut replayer = Replayer::new(node_id.clone(), client.clone(), client.as_sender());
+
+    let signer = data::make_signer(rng);
+    let routed = |target: PeerId, body: RoutedMessageBody| {
+        let msg = RawRoutedMessage { target: PeerIdOrHash::PeerId(target), body };
+        PeerMessage::Routed(Box::new(msg.sign(&signer.secret_key, /*ttl=*/ 1, None)))
+    };
+    let tx = data::make_signed_transaction(rng);
+    let forwarded_tx = data::make_signed_transaction(rng);
+    let block = data::make_genesis_block(&clock.clock(), vec![]);
+    let msgs = [
+        // Outbound messages are not delivered.
+        record(
+            Direction::Outbound,
+            Some(peer_id.clone()),
+            &PeerMessage::BlockRequest(*block.hash()),
+        ),
+        // Messages received before the handshake are not delivered.
+        record(Direction::Inbound, None, &PeerMessage::Transaction(tx.clone())),
+        record(Direction::Inbound, Some(peer_id.clone()), &PeerMessage::Transaction(tx.clone())),
+        record(Direction::Inbound, Some(peer_id.clone()), &PeerMessage::Block(block.clone())),
+        // Routed messages addressed to other peers are not delivered.
+        record(
+            Direction::Inbound,
+            Some(peer_id.clone()),
+            &routed(data::make_peer_id(rng), RoutedMessageBody::ForwardTx(tx.clone())),
+        ),
+        record(
+            Direction::Inbound,
+            Some(peer_id.clone()),
+            &routed(node_id, RoutedMessageBody::ForwardTx(forwarded_tx.clone())),
+        ),
+    ];
+    for msg in &msgs {
+        replayer.replay(&clock.clock(), msg).await.unwrap();
+    }
+    assert_eq!(
+        *events.lock(),
+        vec![Event::Transaction(tx), Event::Block(block), Event::Transaction(forwarded_tx)]
+    );
+
+    // A message which cannot be parsed is reported as an error.
+    let mut invalid = msgs[2].clone();
+    invalid.message = vec![0xff; 3];
+    assert!(replayer.replay(&clock.clock(), &invalid).await.is_err());
+}
@@ -0,0 +1,148 @@
+use crate::network_protocol::DeliveryReceipt;
+use std::collections::VecDeque;
+use unc_async::time;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::network::PeerId;
+use unc_primitives::views::RoutedMessageTraceView;
+
+/// Maximal number of traced messages awaiting a receipt.
+const PENDING_CAPACITY: usize = 10_000;
+/// Number of the most recent traces kept for the debug page.
+const RECENT_CAPACITY: usize = 200;
+/// Messages without a receipt after this time are reported as undelivered.
+pub(crate) const RECEIPT_TIMEOUT: time::Duration = time::Duration::seconds(30);
+
+struct Pending {
+    target: PeerId,
+    body_type: &'static str,
+    sent_at: time::Instant,
+    sent_at_utc: time::Utc,
+}
+
+/// Traces of the routed messages authored by this node,
+/// see `NetworkConfig::trace_routed_messages`.
+pub(crate) struct DeliveryTraces {
+    /// Sent messages awaiting a receipt, from the oldest to the newest.
+    pending: lru::LruCache<CryptoHash, Pending>,
+    /// Delivered and expired messages, from the oldest to the newest.
+    recent: VecDeque<RoutedMessageTraceView>,
+}
+
+impl DeliveryTraces {
+    pub fn new() -> Self {
+        Self { pending: lru::LruCache::new(PENDING_CAPACITY), recent: VecDeque::new() }
+    }
+
+    /// Records a traced message sent by this node.
+    pub fn sent(
+        &mut self,
+        clock: &time::Clock,
+        msg_hash: CryptoHash,
+        target: PeerId,
+        body_type: &'static str,
+    ) {
+        self.expire(clock.now());
+        // The same message may be sent over both TIER1 and TIER2, keep the first one.
+        if self.pending.contains(&msg_hash) {
+            return;
+        }
+        let pending =
+            Pending { target, body_type, sent_at: clock.now(), sent_at_utc: clock.now_utc() };
+        if let Some((msg_hash, pending)) = self.pending.push(msg_hash, pending) {
+            self.push_recent(msg_hash, pending, None);
+        }
+    }
+
+    /// Records the receipt sent back by the target of a traced message.
+    /// Returns false if no such message is awaiting a receipt from `from`, which happens
+    /// for late and duplicated receipts.
+    pub fn delivered(
+        &mut self,
+        clock: &time::Clock,
+        from: &PeerId,
+        receipt: DeliveryReceipt,
+    ) -> bool {
+        match self.pending.peek(&receipt.msg_hash) {
+            Some(pending) if &pending.target == from => {}
+            _ => return false,
+        }
+        let pending = self.pending.pop(&receipt.msg_hash).unwrap();
+        let round_trip = clock.now() - pending.sent_at;
+        self.push_recent(receipt.msg_hash, pending, Some((receipt.hops, round_trip)));
+        true
+    }
+
+    /// Most recent traces, from the newest to the oldest.
+    pub fn debug_view(&mut self, clock: &time::Clock) -> Vec<RoutedMessageTraceView> {
+        self.expire(clock.now());
+        self.recent.iter().rev().cloned().collect()
+    }
+
+    /// Reports the messages which didn't get a receipt in time as undelivered.
+    fn expire(&mut self, now: time::Instant) {
+        while let Some((_, pending)) = self.pending.peek_lru() {
+            if now - pending.sent_at < RECEIPT_TIMEOUT {
+                break;
+            }
+            let (msg_hash, pending) = self.pending.pop_lru().unwrap();
+            self.push_recent(msg_hash, pending, None);
+        }
+    }
+
+    fn push_recent(
+        &mut self,
+        msg_hash: CryptoHash,
+        pending: Pending,
+        delivery: Option<(Vec<PeerId>, time::Duration)>,
+    ) {
+        let (hops, round_trip) = delivery.unzip();
+        if self.recent.len() == RECENT_CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(RoutedMessageTraceView {
+            msg_hash,
+            body_type: pending.body_type.to_string(),
+            target: pending.target,
+            sent_at: pending.sent_at_utc.unix_timestamp(),
+            hops,
+            round_trip_millis: round_trip.map(|d| d.whole_milliseconds() as i64),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unc_primitives::hash::hash;
+
+    #[test]
+    fn delivered_and_expired() {
+        let clock = time::FakeClock::default();
+        let mut traces = DeliveryTraces::new();
+        let target = PeerId::random();
+        let hop = PeerId::random();
+        let (hash0, hash1) = (hash(&[0]), hash(&[1]));
+
+        traces.sent(&clock.clock(), hash0, target.clone(), "Ping");
+        traces.sent(&clock.clock(), hash1, target.clone(), "Ping");
+        clock.advance(time::Duration::milliseconds(150));
+        let receipt = DeliveryReceipt { msg_hash: hash0, hops: vec![hop.clone()] };
+        // Receipts are accepted only from the target of the message, and only once.
+        assert!(!traces.delivered(&clock.clock(), &hop, receipt.clone()));
+        assert!(traces.delivered(&clock.clock(), &target, receipt.clone()));
+        assert!(!traces.delivered(&clock.clock(), &target, receipt));
+
+        let got = traces.debug_view(&clock.clock());
+        assert_eq!(1, got.len());
+        assert_eq!(hash0, got[0].msg_hash);
+        assert_eq!(Some(vec![hop]), got[0].hops);
+        assert_eq!(Some(150), got[0].round_trip_millis);
+
+        clock.advance(RECEIPT_TIMEOUT);
+        let got = traces.debug_view(&clock.clock());
+        assert_eq!(2, got.len());
+        assert_eq!(hash1, got[0].msg_hash);
+        assert_eq!(None, got[0].hops);
+        assert_eq!(None, got[0].round_trip_millis);
+    }
+}
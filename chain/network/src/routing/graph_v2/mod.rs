@@ -759,6 +759,9 @@ impl GraphV2 {
                 })
                 .collect(),
             my_distances: inner.my_distances.clone(),
+            // Filled in by the NetworkState, which owns both routing protocols.
+            routed_message_traces: vec![],
+            route_comparison: vec![],
         }
     }
 }
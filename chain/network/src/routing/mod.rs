@@ -1,4 +1,5 @@
 mod bfs;
+pub(crate) mod delivery_traces;
 pub(crate) mod edge;
 mod edge_cache;
 mod graph;
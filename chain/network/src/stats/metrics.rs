@@ -160,6 +160,31 @@ pub(crate) static PEER_DATA_WRITE_BUFFER_SIZE: Lazy<IntGaugeVec> = Lazy::new(||
     )
     .unwrap()
 });
+pub(crate) static PEER_SEND_QUEUE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "unc_peer_send_queue_bytes",
+        "Total size of the messages waiting in the send queues of all peers, by message class",
+        &["class"],
+    )
+    .unwrap()
+});
+pub(crate) static PEER_SENT_BY_CLASS_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "unc_peer_sent_by_class_bytes",
+        "Number of bytes of the messages taken from the send queues, by message class",
+        &["class"],
+    )
+    .unwrap()
+});
+pub(crate) static PEER_SEND_QUEUE_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "unc_peer_send_queue_delay",
+        "Time that a message spends in the send queue, by message class",
+        &["class"],
+        Some(exponential_buckets(0.0001, 2., 20).unwrap()),
+    )
+    .unwrap()
+});
 pub(crate) static PEER_MESSAGE_RECEIVED_BY_TYPE_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
         "unc_peer_message_received_by_type_bytes",
@@ -192,6 +217,23 @@ pub(crate) static PEER_MESSAGE_SENT_BY_TYPE_TOTAL: Lazy<IntCounterVec> = Lazy::n
     )
     .unwrap()
 });
+pub(crate) static PEER_MESSAGE_COMPRESSED_BY_TYPE_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "unc_peer_message_compressed_by_type_total",
+        "Number of messages sent to peers compressed, by message types",
+        &["type"],
+    )
+    .unwrap()
+});
+pub(crate) static PEER_MESSAGE_COMPRESSION_SAVED_BY_TYPE_BYTES: Lazy<IntCounterVec> =
+    Lazy::new(|| {
+        try_create_int_counter_vec(
+            "unc_peer_message_compression_saved_by_type_bytes",
+            "Number of bytes saved by compressing messages sent to peers, by message types",
+            &["type"],
+        )
+        .unwrap()
+    });
 pub(crate) static SYNC_ACCOUNTS_DATA: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
         "unc_sync_accounts_data",
@@ -209,6 +251,31 @@ pub(crate) static SYNC_SNAPSHOT_HOSTS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static LIGHT_CLIENT_REQUESTS_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "unc_light_client_requests_dropped",
+        "Number of light client requests dropped because of the rate limit, by message type",
+        &["type"],
+    )
+    .unwrap()
+});
+
+pub(crate) static DELIVERY_RECEIPTS_DROPPED: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "unc_delivery_receipts_dropped_total",
+        "Number of delivery receipts not sent because of the rate limit",
+    )
+    .unwrap()
+});
+
+pub(crate) static TRAFFIC_RECORDING_DROPPED_MESSAGES: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "unc_traffic_recording_dropped_messages_total",
+        "Number of messages not recorded because the traffic recording fell behind",
+    )
+    .unwrap()
+});
+
 pub(crate) static REQUEST_COUNT_BY_TYPE_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
         "unc_requests_count_by_type_total",
@@ -59,6 +59,47 @@ impl BorshRepr for ConnectionInfoRepr {
     }
 }
 
+/// A Borsh representation of the primitives::PeerReputation.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(super) struct PeerReputationRepr {
+    peer_id: PeerId,
+    responses: f64,
+    latency_secs: f64,
+    useful_bytes: f64,
+    invalid_data: f64,
+    timeouts: f64,
+    /// UNIX timestamp in nanos.
+    updated_at: u64,
+}
+
+impl BorshRepr for PeerReputationRepr {
+    type T = primitives::PeerReputation;
+    fn to_repr(s: &primitives::PeerReputation) -> Self {
+        Self {
+            peer_id: s.peer_id.clone(),
+            responses: s.responses,
+            latency_secs: s.latency_secs,
+            useful_bytes: s.useful_bytes,
+            invalid_data: s.invalid_data,
+            timeouts: s.timeouts,
+            updated_at: s.updated_at.unix_timestamp_nanos() as u64,
+        }
+    }
+
+    fn from_repr(s: Self) -> Result<primitives::PeerReputation, Error> {
+        Ok(primitives::PeerReputation {
+            peer_id: s.peer_id,
+            responses: s.responses,
+            latency_secs: s.latency_secs,
+            useful_bytes: s.useful_bytes,
+            invalid_data: s.invalid_data,
+            timeouts: s.timeouts,
+            updated_at: time::Utc::from_unix_timestamp_nanos(s.updated_at as i128)
+                .map_err(invalid_data)?,
+        })
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub(super) struct EdgeRepr {
     key: (PeerId, PeerId),
@@ -103,6 +144,13 @@ impl Column for RecentOutboundConnections {
     type Value = Vec<ConnectionInfoRepr>;
 }
 
+pub(super) struct PeerReputations;
+impl Column for PeerReputations {
+    const COL: DBCol = DBCol::PeerReputations;
+    type Key = Borsh<()>;
+    type Value = Vec<PeerReputationRepr>;
+}
+
 pub(super) struct PeerComponent;
 impl Column for PeerComponent {
     const COL: DBCol = DBCol::PeerComponent;
@@ -1,9 +1,14 @@
 use crate::network_protocol::PeerInfo;
+use crate::quic;
 use anyhow::{anyhow, Context as _};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::fmt;
+use std::io;
+use std::pin::Pin;
 use std::sync::Mutex;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use unc_primitives::network::PeerId;
 
 const LISTENER_BACKLOG: u32 = 128;
@@ -13,6 +18,11 @@ const LISTENER_BACKLOG: u32 = 128;
 static RESERVED_LISTENER_ADDRS: Lazy<Mutex<HashMap<std::net::SocketAddr, tokio::net::TcpSocket>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// TEST-ONLY: UDP sockets bound to the ports of the reserved listener addresses,
+/// handed over to the QUIC listener (see `quic::Listener::bind`).
+static RESERVED_UDP_SOCKETS: Lazy<Mutex<HashMap<std::net::SocketAddr, std::net::UdpSocket>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// TCP connections established by a node belong to different logical networks (aka tiers),
 /// which serve different purpose.
 // TODO(gprusak): add a link to the design on github docs (but first write those docs).
@@ -33,9 +43,60 @@ pub(crate) enum StreamType {
     Outbound { peer_id: PeerId, tier: Tier },
 }
 
+/// Transport of a connection. QUIC connections are accepted on the UDP port
+/// with the same number as the TCP listener port.
+#[derive(Debug)]
+pub(crate) enum Transport {
+    Tcp(tokio::net::TcpStream),
+    Quic(quic::Connection),
+}
+
+/// Reads and writes go to the TCP stream, or to the consensus stream of a QUIC connection.
+/// Used by the tools which don't care about the transport, FramedStream uses
+/// all the streams of a QUIC connection.
+impl AsyncRead for Transport {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(s) => Pin::new(s).poll_read(cx, buf),
+            Transport::Quic(c) => Pin::new(&mut c.consensus.1).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Transport {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Transport::Tcp(s) => Pin::new(s).poll_write(cx, buf),
+            Transport::Quic(c) => Pin::new(&mut c.consensus.0).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(s) => Pin::new(s).poll_flush(cx),
+            Transport::Quic(c) => Pin::new(&mut c.consensus.0).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(s) => Pin::new(s).poll_shutdown(cx),
+            Transport::Quic(c) => Pin::new(&mut c.consensus.0).poll_shutdown(cx),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Stream {
-    pub(crate) stream: tokio::net::TcpStream,
+    pub(crate) stream: Transport,
     pub(crate) type_: StreamType,
     /// cached stream.local_addr()
     pub(crate) local_addr: std::net::SocketAddr,
@@ -79,7 +140,12 @@ impl Socket {
 
 impl Stream {
     fn new(stream: tokio::net::TcpStream, type_: StreamType) -> std::io::Result<Self> {
-        Ok(Self { peer_addr: stream.peer_addr()?, local_addr: stream.local_addr()?, stream, type_ })
+        Ok(Self {
+            peer_addr: stream.peer_addr()?,
+            local_addr: stream.local_addr()?,
+            stream: Transport::Tcp(stream),
+            type_,
+        })
     }
 
     pub async fn connect(peer_info: &PeerInfo, tier: Tier) -> anyhow::Result<Stream> {
@@ -179,14 +245,29 @@ impl ListenerAddr {
     }
 
     /// TEST-ONLY: reserves a random port on localhost for a TCP listener.
+    /// The UDP port with the same number is reserved as well, for the QUIC listener.
     pub fn reserve_for_test() -> Self {
-        let guard = tokio::net::TcpSocket::new_v6().unwrap();
-        guard.set_reuseaddr(true).unwrap();
-        guard.set_reuseport(true).unwrap();
-        guard.bind("[::1]:0".parse().unwrap()).unwrap();
-        let addr = guard.local_addr().unwrap();
-        RESERVED_LISTENER_ADDRS.lock().unwrap().insert(addr, guard);
-        Self(addr)
+        loop {
+            let guard = tokio::net::TcpSocket::new_v6().unwrap();
+            guard.set_reuseaddr(true).unwrap();
+            guard.set_reuseport(true).unwrap();
+            guard.bind("[::1]:0".parse().unwrap()).unwrap();
+            let addr = guard.local_addr().unwrap();
+            // The UDP port might be in use even though the TCP port isn't, in which case we retry.
+            let Ok(udp) = std::net::UdpSocket::bind(addr) else { continue };
+            RESERVED_LISTENER_ADDRS.lock().unwrap().insert(addr, guard);
+            RESERVED_UDP_SOCKETS.lock().unwrap().insert(addr, udp);
+            return Self(addr);
+        }
+    }
+
+    /// Binds a UDP socket for the QUIC listener. In tests the socket reserved
+    /// by `reserve_for_test()` is used, if not taken already.
+    pub(crate) fn udp_socket(&self) -> std::io::Result<std::net::UdpSocket> {
+        if let Some(socket) = RESERVED_UDP_SOCKETS.lock().unwrap().remove(&self.0) {
+            return Ok(socket);
+        }
+        std::net::UdpSocket::bind(self.0)
     }
 
     /// Constructs a std::net::TcpListener, for usage outside of unc_network.
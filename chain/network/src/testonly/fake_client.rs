@@ -1,5 +1,7 @@
 use crate::client;
-use crate::network_protocol::StateResponseInfo;
+use crate::network_protocol::{
+    LightClientExecutionProof, LightClientProofRequest, StateResponseInfo,
+};
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
 use crate::sink::Sink;
 use crate::types::{NetworkInfo, ReasonForBan, StateResponseInfoV2};
@@ -13,7 +15,7 @@ use unc_primitives::sharding::{ChunkHash, PartialEncodedChunkPart};
 use unc_primitives::state_sync::{ShardStateSyncResponse, ShardStateSyncResponseV2};
 use unc_primitives::transaction::SignedTransaction;
 use unc_primitives::types::{AccountId, EpochId, ShardId};
-use unc_primitives::views::FinalExecutionOutcomeView;
+use unc_primitives::views::{FinalExecutionOutcomeView, LightClientBlockView};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[allow(clippy::large_enum_variant)]
@@ -29,6 +31,10 @@ pub enum Event {
     ChunkEndorsement(ChunkEndorsement),
     ChunkRequest(ChunkHash),
     ChunkStateWitness(ChunkStateWitness),
+    LightClientBlock(LightClientBlockView),
+    LightClientBlockRequest(CryptoHash),
+    LightClientProof(LightClientExecutionProof),
+    LightClientProofRequest(LightClientProofRequest),
     Transaction(SignedTransaction),
 }
 
@@ -129,6 +135,40 @@ impl client::Client for Fake {
     async fn chunk_endorsement(&self, endorsement: ChunkEndorsement) {
         self.event_sink.push(Event::ChunkEndorsement(endorsement));
     }
+
+    async fn light_client_block_request(
+        &self,
+        last_block_hash: CryptoHash,
+    ) -> Option<Box<LightClientBlockView>> {
+        self.event_sink.push(Event::LightClientBlockRequest(last_block_hash));
+        None
+    }
+
+    async fn light_client_block(
+        &self,
+        block: LightClientBlockView,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        self.event_sink.push(Event::LightClientBlock(block));
+        Ok(())
+    }
+
+    async fn light_client_proof_request(
+        &self,
+        request: LightClientProofRequest,
+    ) -> Option<Box<LightClientExecutionProof>> {
+        self.event_sink.push(Event::LightClientProofRequest(request));
+        None
+    }
+
+    async fn light_client_proof(
+        &self,
+        proof: LightClientExecutionProof,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        self.event_sink.push(Event::LightClientProof(proof));
+        Ok(())
+    }
 }
 
 impl messaging::CanSend<ShardsManagerRequestFromNetwork> for Fake {
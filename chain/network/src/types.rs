@@ -5,9 +5,10 @@ pub use crate::network_protocol::{
 };
 /// Exported types, which are part of network protocol.
 pub use crate::network_protocol::{
-    Edge, PartialEdgeInfo, PartialEncodedChunkForwardMsg, PartialEncodedChunkRequestMsg,
-    PartialEncodedChunkResponseMsg, PeerChainInfoV2, PeerInfo, SnapshotHostInfo, StateResponseInfo,
-    StateResponseInfoV1, StateResponseInfoV2,
+    Edge, LightClientExecutionProof, LightClientProofRequest, PartialEdgeInfo,
+    PartialEncodedChunkForwardMsg, PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg,
+    PeerChainInfoV2, PeerInfo, SnapshotHostInfo, StateResponseInfo, StateResponseInfoV1,
+    StateResponseInfoV2,
 };
 use crate::routing::routing_table_view::RoutingTableInfo;
 pub use crate::state_sync::{StateSync, StateSyncResponse};
@@ -126,6 +127,26 @@ pub struct ConnectionInfo {
     pub time_connected_until: time::Utc,
 }
 
+/// Exponentially decayed statistics about the data a peer served us.
+/// Counters are `f64`, because they are scaled down over time, so that
+/// old misbehavior (or old merit) gradually stops affecting the score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerReputation {
+    pub peer_id: PeerId,
+    /// Number of responses to our requests.
+    pub responses: f64,
+    /// Sum of latencies (in seconds) of these responses.
+    pub latency_secs: f64,
+    /// Bytes of data in these responses which we asked for.
+    pub useful_bytes: f64,
+    /// Number of messages which failed to parse or validate.
+    pub invalid_data: f64,
+    /// Number of requests which didn't get a response in time.
+    pub timeouts: f64,
+    /// Time at which the counters have been decayed last.
+    pub updated_at: time::Utc,
+}
+
 impl KnownPeerStatus {
     pub fn is_banned(&self) -> bool {
         matches!(self, KnownPeerStatus::Banned(_, _))
@@ -229,6 +250,10 @@ pub enum NetworkRequests {
     StateRequestHeader { shard_id: ShardId, sync_hash: CryptoHash, peer_id: PeerId },
     /// Request state part for given shard at given state root.
     StateRequestPart { shard_id: ShardId, sync_hash: CryptoHash, part_id: u64, peer_id: PeerId },
+    /// Request the light client block following the given one from given peer.
+    LightClientBlockRequest { last_block_hash: CryptoHash, peer_id: PeerId },
+    /// Request the proof of an execution outcome from given peer.
+    LightClientProofRequest { request: LightClientProofRequest, peer_id: PeerId },
     /// Ban given peer.
     BanPeer { peer_id: PeerId, ban_reason: ReasonForBan },
     /// Announce account
@@ -323,6 +348,8 @@ pub struct PeerChainInfo {
     pub tracked_shards: Vec<ShardId>,
     /// Denote if a node is running in archival mode or not.
     pub archival: bool,
+    /// Denote if a node is a light node, which doesn't serve blocks nor chunks.
+    pub light: bool,
 }
 
 // Information about the connected peer that is shared with the rest of the system.
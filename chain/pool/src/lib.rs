@@ -1,15 +1,15 @@
+use std::cmp::Reverse;
 use std::collections::btree_map::Entry;
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
 
 use crate::types::{PoolIterator, PoolKey, TransactionGroup};
 
-use std::ops::Bound;
 use unc_crypto::PublicKey;
 use unc_o11y::metrics::prometheus::core::{AtomicI64, GenericGauge};
 use unc_primitives::epoch_manager::RngSeed;
 use unc_primitives::hash::{hash, CryptoHash};
 use unc_primitives::transaction::SignedTransaction;
-use unc_primitives::types::AccountId;
+use unc_primitives::types::{AccountId, Balance};
 
 mod metrics;
 pub mod types;
@@ -24,19 +24,34 @@ pub enum InsertTransactionResult {
     NoSpaceLeft,
 }
 
+/// What the pool remembers about every transaction it holds, to find it by hash.
+struct PoolEntry {
+    /// The key of the group the transaction belongs to.
+    key: PoolKey,
+    /// The priority fee offered by the transaction.
+    tip: Balance,
+    /// The size of the transaction in bytes.
+    size: u64,
+}
+
 /// Transaction pool: keeps track of transactions that were not yet accepted into the block chain.
 pub struct TransactionPool {
     /// Transactions are grouped by a pair of (account ID, signer public key).
     /// NOTE: It's more efficient on average to keep transactions unsorted and with potentially
     /// conflicting nonce than to create a BTreeMap for every transaction.
     transactions: BTreeMap<PoolKey, Vec<SignedTransaction>>,
-    /// Set of all hashes to quickly check if the given transaction is in the pool.
-    unique_transactions: HashSet<CryptoHash>,
+    /// All transactions in the pool by hash, to quickly check if the given transaction is in
+    /// the pool.
+    unique_transactions: HashMap<CryptoHash, PoolEntry>,
+    /// All transactions in the pool ordered by their priority fee, to find the ones to evict
+    /// when the pool is full.
+    transactions_by_tip: BTreeSet<(Balance, CryptoHash)>,
     /// A uniquely generated key seed to randomize PoolKey order.
     key_seed: RngSeed,
     /// The key after which the pool iterator starts. Doesn't have to be present in the pool.
     last_used_key: PoolKey,
-    /// If set, new transactions that bring the size of the pool over this limit will be rejected.
+    /// If set, new transactions that bring the size of the pool over this limit will be rejected,
+    /// unless they offer a higher priority fee than the transactions they can evict.
     total_transaction_size_limit: Option<u64>,
     /// Total size of transactions in the pool measured in bytes.
     total_transaction_size: u64,
@@ -62,7 +77,8 @@ impl TransactionPool {
         Self {
             key_seed,
             transactions: BTreeMap::new(),
-            unique_transactions: HashSet::new(),
+            unique_transactions: HashMap::new(),
+            transactions_by_tip: BTreeSet::new(),
             last_used_key: CryptoHash::default(),
             total_transaction_size_limit,
             total_transaction_size: 0,
@@ -79,42 +95,141 @@ impl TransactionPool {
     }
 
     /// Inserts a signed transaction that passed validation into the pool.
+    ///
+    /// Pending transactions of the same group with the same nonce and a lower priority fee are
+    /// replaced by the new one. If the pool is full, transactions with the lowest priority fees
+    /// are evicted to make space, as long as they offer less than the new one. Transactions are
+    /// evicted from the end of their group, so that no transaction is left behind a missing nonce.
     #[must_use]
     pub fn insert_transaction(
         &mut self,
         signed_transaction: SignedTransaction,
     ) -> InsertTransactionResult {
-        if !self.unique_transactions.insert(signed_transaction.get_hash()) {
+        let tx_hash = signed_transaction.get_hash();
+        if self.unique_transactions.contains_key(&tx_hash) {
             // The hash of this transaction was already seen, skip it.
             return InsertTransactionResult::Duplicate;
         }
+        let transaction = &signed_transaction.transaction;
+        let key = self.key(&transaction.signer_id, &transaction.public_key);
+        let tip = transaction.tip();
+        let size = signed_transaction.get_size();
+
+        let replaced: Vec<CryptoHash> = self
+            .transactions
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .filter(|tx| tx.transaction.nonce == transaction.nonce && tx.transaction.tip() < tip)
+            .map(|tx| tx.get_hash())
+            .collect();
         // We never expect the total size to go over `u64` during real operation as that would
         // be more than 10^9 GiB of RAM consumed for transaction pool, so panicing here is intended
         // to catch a logic error in estimation of transaction size.
-        let new_total_transaction_size = self
-            .total_transaction_size
-            .checked_add(signed_transaction.get_size())
+        let replaced_size: u64 =
+            replaced.iter().map(|hash| self.unique_transactions[hash].size).sum();
+        let mut new_total_transaction_size = (self.total_transaction_size - replaced_size)
+            .checked_add(size)
             .expect("Total transaction size is too large");
+        let mut evicted = HashSet::new();
         if let Some(limit) = self.total_transaction_size_limit {
+            for (evicted_tip, evicted_hash) in &self.transactions_by_tip {
+                if new_total_transaction_size <= limit || *evicted_tip >= tip {
+                    break;
+                }
+                if replaced.contains(evicted_hash) || evicted.contains(evicted_hash) {
+                    continue;
+                }
+                let Some(tail) = self.group_tail(evicted_hash, &key, transaction.nonce, tip) else {
+                    continue;
+                };
+                // Evict the highest nonces first, so the transactions left in the group can
+                // still be included.
+                for hash in tail {
+                    if new_total_transaction_size <= limit {
+                        break;
+                    }
+                    if replaced.contains(&hash) || evicted.contains(&hash) {
+                        continue;
+                    }
+                    new_total_transaction_size -= self.unique_transactions[&hash].size;
+                    evicted.insert(hash);
+                }
+            }
             if new_total_transaction_size > limit {
                 return InsertTransactionResult::NoSpaceLeft;
             }
         }
 
         // At this point transaction is accepted to the pool.
+        for removed_hash in replaced.iter().chain(&evicted) {
+            self.remove_transaction(removed_hash);
+        }
         self.total_transaction_size = new_total_transaction_size;
-        let signer_id = &signed_transaction.transaction.signer_id;
-        let signer_public_key = &signed_transaction.transaction.public_key;
-        self.transactions
-            .entry(self.key(signer_id, signer_public_key))
-            .or_insert_with(Vec::new)
-            .push(signed_transaction);
-
-        self.transaction_pool_count_metric.inc();
+        self.unique_transactions.insert(tx_hash, PoolEntry { key, tip, size });
+        self.transactions_by_tip.insert((tip, tx_hash));
+        self.transactions.entry(key).or_insert_with(Vec::new).push(signed_transaction);
+
+        self.transaction_pool_count_metric.set(self.unique_transactions.len() as i64);
         self.transaction_pool_size_metric.set(self.total_transaction_size as i64);
         InsertTransactionResult::Success
     }
 
+    /// Returns the transaction with the given hash and the transactions of its group with higher
+    /// nonces, which can't be included once it's evicted, ordered from the highest nonce.
+    ///
+    /// Returns `None` if any of them offers at least `tip`, or if the transaction to be inserted
+    /// with `key` and `nonce` would follow it.
+    fn group_tail(
+        &self,
+        hash: &CryptoHash,
+        key: &PoolKey,
+        nonce: u64,
+        tip: Balance,
+    ) -> Option<Vec<CryptoHash>> {
+        let entry = &self.unique_transactions[hash];
+        let group = &self.transactions[&entry.key];
+        let tail_nonce = group.iter().find(|tx| tx.get_hash() == *hash)?.transaction.nonce;
+        if entry.key == *key && tail_nonce < nonce {
+            return None;
+        }
+        let mut tail: Vec<_> = group
+            .iter()
+            .filter(|tx| tx.transaction.nonce > tail_nonce || tx.get_hash() == *hash)
+            .collect();
+        if tail.iter().any(|tx| tx.transaction.tip() >= tip) {
+            return None;
+        }
+        tail.sort_by_key(|tx| Reverse(tx.transaction.nonce));
+        Some(tail.into_iter().map(|tx| tx.get_hash()).collect())
+    }
+
+    /// Removes a single transaction that is present in the pool from its group.
+    fn remove_transaction(&mut self, hash: &CryptoHash) {
+        let Some(entry) = self.forget_transaction(hash) else {
+            return;
+        };
+        if let Entry::Occupied(mut group) = self.transactions.entry(entry.key) {
+            group.get_mut().retain(|tx| tx.get_hash() != *hash);
+            if group.get().is_empty() {
+                group.remove_entry();
+            }
+        }
+        // See the comment in `insert_transaction` where we increase the size for reasoning why
+        // panicing here catches a logic error.
+        self.total_transaction_size = self
+            .total_transaction_size
+            .checked_sub(entry.size)
+            .expect("Total transaction size dropped below zero");
+    }
+
+    /// Drops the transaction from the pool indexes. Doesn't touch its group or the total size.
+    fn forget_transaction(&mut self, hash: &CryptoHash) -> Option<PoolEntry> {
+        let entry = self.unique_transactions.remove(hash)?;
+        self.transactions_by_tip.remove(&(entry.tip, *hash));
+        Some(entry)
+    }
+
     /// Returns a pool iterator wrapper that implements an iterator-like trait to iterate over
     /// transaction groups in the proper order defined by the protocol.
     /// When the iterator is dropped, all remaining groups are inserted back into the pool.
@@ -130,14 +245,12 @@ impl TransactionPool {
         let mut grouped_transactions = HashMap::new();
         for tx in transactions {
             // If transaction is not present in the pool, skip it.
-            if !self.unique_transactions.remove(&tx.get_hash()) {
+            let Some(entry) = self.forget_transaction(&tx.get_hash()) else {
                 continue;
-            }
+            };
 
-            let signer_id = &tx.transaction.signer_id;
-            let signer_public_key = &tx.transaction.public_key;
             grouped_transactions
-                .entry(self.key(signer_id, signer_public_key))
+                .entry(entry.key)
                 .or_insert_with(HashSet::new)
                 .insert(tx.get_hash());
         }
@@ -184,108 +297,146 @@ pub struct PoolIteratorWrapper<'a> {
     /// Mutable reference to the pool, to avoid exposing it while the iterator exists.
     pool: &'a mut TransactionPool,
 
-    /// Queue of transaction groups. Each group there is sorted by nonce.
-    sorted_groups: VecDeque<TransactionGroup>,
+    /// Queue of non-empty transaction groups. Each group there is sorted by nonce.
+    queued_groups: BinaryHeap<QueuedGroup>,
+    /// The group returned by the last call to `next()`.
+    current_group: Option<TransactionGroup>,
+    /// Number of times a group was queued so far, used to order groups with equal fees.
+    queued_count: u64,
+    /// Number of groups taken from the pool, which are queued first in the pool key order.
+    pool_group_count: u64,
+}
+
+/// A transaction group waiting in the pool iterator queue.
+struct QueuedGroup {
+    /// The priority fee of the next transaction in the group.
+    tip: Balance,
+    /// Groups with equal fees are served in the order they were queued.
+    order: Reverse<u64>,
+    group: TransactionGroup,
+}
+
+impl PartialEq for QueuedGroup {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for QueuedGroup {}
+
+impl PartialOrd for QueuedGroup {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedGroup {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.tip, self.order).cmp(&(other.tip, other.order))
+    }
 }
 
 impl<'a> PoolIteratorWrapper<'a> {
     pub fn new(pool: &'a mut TransactionPool) -> Self {
-        Self { pool, sorted_groups: Default::default() }
+        let transactions = std::mem::take(&mut pool.transactions);
+        let last_used_key = pool.last_used_key;
+        let mut iterator = Self {
+            pool,
+            queued_groups: BinaryHeap::with_capacity(transactions.len()),
+            current_group: None,
+            queued_count: 0,
+            pool_group_count: transactions.len() as u64,
+        };
+        // Groups are queued starting from the key after the last used one, so that groups with
+        // equal fees take turns across iterators.
+        let (after_last_used, up_to_last_used): (Vec<_>, Vec<_>) =
+            transactions.into_iter().partition(|(key, _)| *key > last_used_key);
+        for (key, mut transactions) in after_last_used.into_iter().chain(up_to_last_used) {
+            transactions.sort_by_key(|st| Reverse(st.transaction.nonce));
+            iterator.queue(TransactionGroup {
+                key,
+                transactions,
+                removed_transaction_hashes: vec![],
+                removed_transaction_size: 0,
+            });
+        }
+        iterator
+    }
+
+    fn queue(&mut self, group: TransactionGroup) {
+        let tip = group.transactions.last().map_or(0, |tx| tx.transaction.tip());
+        self.queued_groups.push(QueuedGroup { tip, order: Reverse(self.queued_count), group });
+        self.queued_count += 1;
+    }
+
+    /// Updates the pool for the transactions that were pulled from the group.
+    fn release(&mut self, group: TransactionGroup) {
+        for hash in group.removed_transaction_hashes {
+            self.pool.forget_transaction(&hash);
+        }
+        // See the comment in `insert_transaction` where we increase the size for reasoning
+        // why panicing here catches a logic error.
+        self.pool.total_transaction_size = self
+            .pool
+            .total_transaction_size
+            .checked_sub(group.removed_transaction_size)
+            .expect("Total transaction size dropped below zero");
+
+        if !group.transactions.is_empty() {
+            self.pool.transactions.insert(group.key, group.transactions);
+        }
     }
 }
 
 /// The iterator works with the following algorithm:
-/// On next(), the iterator tries to get a transaction group from the pool, sorts transactions in
-/// it, and add it to the back of the sorted groups queue.
-/// Remembers the last used key, so it can continue from the next key.
-///
-/// If the pool is empty, the iterator gets the group from the front of the sorted groups queue.
+/// When created, the iterator takes all transaction groups from the pool, sorts transactions in
+/// each of them and queues them in the order of keys, starting after the last used key.
 ///
-/// If this group is empty (no transactions left inside), then the iterator discards it and
-/// updates `unique_transactions` in the pool. Then gets the next one.
+/// On next(), the group returned by the previous call is queued again, unless it's empty (no
+/// transactions left inside). In that case the iterator discards it and updates
+/// `unique_transactions` in the pool.
 ///
-/// Once a non-empty group is found, this group is pushed to the back of the sorted groups queue
-/// and the iterator returns a mutable reference to this group.
+/// Then the iterator takes the group whose next transaction offers the highest priority fee from
+/// the queue, and returns a mutable reference to this group. Groups with equal fees are taken in
+/// the order they were queued, so without priority fees the groups are served round robin.
+/// Remembers the last used key among groups taken for the first time, so the next iterator can
+/// continue from the next key.
 ///
-/// If the sorted groups queue is empty, the iterator returns None.
+/// If the queue is empty, the iterator returns None.
 ///
 /// When the iterator is dropped, `unique_transactions` in the pool is updated for every group.
-/// And all non-empty group from the sorted groups queue are inserted back into the pool.
+/// And all non-empty groups are inserted back into the pool.
 impl<'a> PoolIterator for PoolIteratorWrapper<'a> {
     fn next(&mut self) -> Option<&mut TransactionGroup> {
-        if !self.pool.transactions.is_empty() {
-            let key = *self
-                .pool
-                .transactions
-                .range((Bound::Excluded(self.pool.last_used_key), Bound::Unbounded))
-                .next()
-                .map(|(k, _v)| k)
-                .unwrap_or_else(|| {
-                    self.pool
-                        .transactions
-                        .keys()
-                        .next()
-                        .expect("we've just checked that the map is not empty")
-                });
-            self.pool.last_used_key = key;
-            let mut transactions =
-                self.pool.transactions.remove(&key).expect("just checked existence");
-            transactions.sort_by_key(|st| std::cmp::Reverse(st.transaction.nonce));
-            self.sorted_groups.push_back(TransactionGroup {
-                key,
-                transactions,
-                removed_transaction_hashes: vec![],
-                removed_transaction_size: 0,
-            });
-            Some(self.sorted_groups.back_mut().expect("just pushed"))
-        } else {
-            while let Some(sorted_group) = self.sorted_groups.pop_front() {
-                if sorted_group.transactions.is_empty() {
-                    for hash in sorted_group.removed_transaction_hashes {
-                        self.pool.unique_transactions.remove(&hash);
-                    }
-                    // See the comment in `insert_transaction` where we increase the size for reasoning
-                    // why panicing here catches a logic error.
-                    self.pool.total_transaction_size = self
-                        .pool
-                        .total_transaction_size
-                        .checked_sub(sorted_group.removed_transaction_size)
-                        .expect("Total transaction size dropped below zero");
-
-                    self.pool
-                        .transaction_pool_count_metric
-                        .set(self.pool.unique_transactions.len() as i64);
-                    self.pool.transaction_pool_size_metric.set(self.pool.transaction_size() as i64);
-                } else {
-                    self.sorted_groups.push_back(sorted_group);
-                    return Some(self.sorted_groups.back_mut().expect("just pushed"));
-                }
+        if let Some(group) = self.current_group.take() {
+            if group.transactions.is_empty() {
+                self.release(group);
+                self.pool
+                    .transaction_pool_count_metric
+                    .set(self.pool.unique_transactions.len() as i64);
+                self.pool.transaction_pool_size_metric.set(self.pool.transaction_size() as i64);
+            } else {
+                self.queue(group);
             }
-            None
         }
+        let QueuedGroup { order, group, .. } = self.queued_groups.pop()?;
+        if order.0 < self.pool_group_count {
+            self.pool.last_used_key = group.key;
+        }
+        Some(self.current_group.insert(group))
     }
 }
 
-/// When a pool iterator is dropped, all remaining non empty transaction groups from the sorted
-/// groups queue are inserted back into the pool. And removed transactions hashes from groups are
-/// removed from the pool's unique_transactions.
+/// When a pool iterator is dropped, all remaining non empty transaction groups are inserted back
+/// into the pool. And removed transactions hashes from groups are removed from the pool's
+/// unique_transactions.
 impl<'a> Drop for PoolIteratorWrapper<'a> {
     fn drop(&mut self) {
-        for group in self.sorted_groups.drain(..) {
-            for hash in group.removed_transaction_hashes {
-                self.pool.unique_transactions.remove(&hash);
-            }
-            // See the comment in `insert_transaction` where we increase the size for reasoning
-            // why panicing here catches a logic error.
-            self.pool.total_transaction_size = self
-                .pool
-                .total_transaction_size
-                .checked_sub(group.removed_transaction_size)
-                .expect("Total transaction size dropped below zero");
-
-            if !group.transactions.is_empty() {
-                self.pool.transactions.insert(group.key, group.transactions);
-            }
+        if let Some(group) = self.current_group.take() {
+            self.release(group);
+        }
+        for QueuedGroup { group, .. } in std::mem::take(&mut self.queued_groups) {
+            self.release(group);
         }
         // We can update metrics only once for the whole batch of transactions.
         self.pool.transaction_pool_count_metric.set(self.pool.unique_transactions.len() as i64);
@@ -301,9 +452,10 @@ mod tests {
     use rand::seq::SliceRandom;
     use rand::thread_rng;
 
-    use unc_crypto::{InMemorySigner, KeyType};
+    use unc_crypto::{InMemorySigner, KeyType, Signer};
 
     use unc_primitives::hash::CryptoHash;
+    use unc_primitives::transaction::Transaction;
     use unc_primitives::types::Balance;
 
     const TEST_SEED: RngSeed = [3; 32];
@@ -331,6 +483,30 @@ mod tests {
             .collect()
     }
 
+    /// Generates a transaction with the given priority fee. Uses the signer ID as the key seed, the
+    /// same as `generate_transactions` called with equal signer ID and seed.
+    fn generate_transaction_with_tip(
+        signer_id: &str,
+        nonce: u64,
+        tip: Balance,
+    ) -> SignedTransaction {
+        let signer_id: AccountId = signer_id.parse().unwrap();
+        let signer =
+            InMemorySigner::from_seed(signer_id.clone(), KeyType::ED25519, signer_id.as_ref());
+        Transaction {
+            priority_fee: Some(tip),
+            ..Transaction::new(
+                signer_id,
+                signer.public_key(),
+                "bob.unc".parse().unwrap(),
+                nonce,
+                CryptoHash::default(),
+            )
+            .transfer(nonce as Balance)
+        }
+        .sign(&signer)
+    }
+
     fn process_txs_to_nonces(
         mut transactions: Vec<SignedTransaction>,
         expected_weight: u32,
@@ -594,4 +770,106 @@ mod tests {
             }
         }
     }
+
+    /// Groups are served by the priority fee of their next transaction, highest first.
+    #[test]
+    fn test_pool_iterator_orders_by_tip() {
+        let mut transactions = generate_transactions("alice.unc", "alice.unc", 1, 3);
+        transactions.push(generate_transaction_with_tip("bob.unc", 1, 10));
+        transactions.push(generate_transaction_with_tip("bob.unc", 2, 1));
+        transactions.push(generate_transaction_with_tip("carol.unc", 1, 5));
+        transactions.shuffle(&mut thread_rng());
+
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        let order: Vec<_> = prepare_transactions(&mut pool, 6)
+            .into_iter()
+            .map(|tx| (tx.transaction.signer_id.to_string(), tx.transaction.nonce))
+            .collect();
+        let expected = [
+            ("bob.unc", 1),
+            ("carol.unc", 1),
+            ("bob.unc", 2),
+            ("alice.unc", 1),
+            ("alice.unc", 2),
+            ("alice.unc", 3),
+        ];
+        assert_eq!(order, expected.map(|(signer_id, nonce)| (signer_id.to_string(), nonce)));
+        assert_eq!(pool.len(), 0);
+    }
+
+    /// A transaction with a higher priority fee replaces pending ones with the same nonce.
+    #[test]
+    fn test_replace_transaction_by_tip() {
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        let tx = generate_transaction_with_tip("alice.unc", 1, 1);
+        assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+
+        let replacement = generate_transaction_with_tip("alice.unc", 1, 2);
+        let replacement_size = replacement.get_size();
+        assert_eq!(pool.insert_transaction(replacement.clone()), InsertTransactionResult::Success);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.transaction_size(), replacement_size);
+
+        // A lower priority fee doesn't replace anything.
+        let tx = generate_transactions("alice.unc", "alice.unc", 1, 1).pop().unwrap();
+        assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        assert_eq!(pool.len(), 2);
+
+        pool.remove_transactions(&[replacement]);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(prepare_transactions(&mut pool, 2)[0].transaction.tip(), 0);
+    }
+
+    /// When the pool is full, transactions with lower priority fees are evicted to make space.
+    #[test]
+    fn test_transaction_pool_size_limit_evicts_lower_tips() {
+        let transactions = generate_transactions("alice.unc", "alice.unc", 1, 10);
+        let pool_size_limit = transactions.iter().map(|tx| tx.get_size()).sum::<u64>();
+        let mut pool = TransactionPool::new(TEST_SEED, Some(pool_size_limit), "");
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+
+        let tx = generate_transaction_with_tip("bob.unc", 1, 5);
+        assert_eq!(pool.insert_transaction(tx.clone()), InsertTransactionResult::Success);
+        assert!(pool.len() < 10);
+        assert!(pool.transaction_size() <= pool_size_limit);
+
+        // Transactions without a priority fee can't evict anything.
+        let tx_without_tip = generate_transactions("carol.unc", "carol.unc", 1, 1).pop().unwrap();
+        assert_eq!(pool.insert_transaction(tx_without_tip), InsertTransactionResult::NoSpaceLeft);
+        assert_eq!(prepare_transactions(&mut pool, 1), vec![tx]);
+    }
+
+    /// Transactions are evicted from the end of their group, so that the remaining ones can still
+    /// be included.
+    #[test]
+    fn test_transaction_pool_size_limit_evicts_group_tail() {
+        let transactions = [
+            generate_transaction_with_tip("alice.unc", 1, 1),
+            generate_transaction_with_tip("alice.unc", 2, 3),
+            generate_transaction_with_tip("alice.unc", 3, 3),
+        ];
+        let pool_size_limit = transactions.iter().map(|tx| tx.get_size()).sum::<u64>();
+        let mut pool = TransactionPool::new(TEST_SEED, Some(pool_size_limit), "");
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+
+        // The transaction with the lowest priority fee is followed by ones offering more.
+        let tx = generate_transaction_with_tip("bob.unc", 1, 2);
+        assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::NoSpaceLeft);
+
+        let tx = generate_transaction_with_tip("bob.unc", 1, 4);
+        assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        let remaining: Vec<_> = prepare_transactions(&mut pool, 3)
+            .into_iter()
+            .map(|tx| (tx.transaction.signer_id.to_string(), tx.transaction.nonce))
+            .collect();
+        let expected = [("bob.unc", 1), ("alice.unc", 1), ("alice.unc", 2)];
+        assert_eq!(remaining, expected.map(|(signer_id, nonce)| (signer_id.to_string(), nonce)));
+    }
 }
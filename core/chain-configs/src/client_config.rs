@@ -360,6 +360,8 @@ pub struct ClientConfig {
     pub gc: GCConfig,
     /// Not clear old data, set `true` for archive nodes.
     pub archive: bool,
+    /// Follow only the light client blocks received from peers instead of syncing the chain.
+    pub light: bool,
     /// save_trie_changes should be set to true iff
     /// - archive if false - non-archivale nodes need trie changes to perform garbage collection
     /// - archive is true, cold_store is configured and migration to split_storage is finished - node
@@ -458,6 +460,7 @@ impl ClientConfig {
             block_header_fetch_horizon: 50,
             gc: GCConfig { gc_blocks_limit: 100, ..GCConfig::default() },
             archive,
+            light: false,
             save_trie_changes,
             log_summary_style: LogSummaryStyle::Colored,
             view_client_threads: 1,
@@ -2,8 +2,9 @@
 use crate::config_store::INITIAL_TESTNET_CONFIG;
 use crate::cost::RuntimeFeesConfig;
 use crate::parameter_table::ParameterTable;
+use num_rational::Rational32;
 use unc_account_id::AccountId;
-use unc_primitives_core::types::Balance;
+use unc_primitives_core::types::{Balance, Gas};
 use unc_primitives_core::version::PROTOCOL_VERSION;
 
 use super::parameter_table::InvalidConfigError;
@@ -23,6 +24,8 @@ pub struct RuntimeConfig {
     pub wasm_config: crate::vm::Config,
     /// Config that defines rules for account creation.
     pub account_creation_config: AccountCreationConfig,
+    /// The configuration for congestion control.
+    pub congestion_control_config: CongestionControlConfig,
 }
 
 impl RuntimeConfig {
@@ -39,24 +42,26 @@ impl RuntimeConfig {
 
     pub fn test() -> Self {
         let config_store = super::config_store::RuntimeConfigStore::new(None);
-        let wasm_config =
-            crate::vm::Config::clone(&config_store.get_config(PROTOCOL_VERSION).wasm_config);
+        let runtime_config = config_store.get_config(PROTOCOL_VERSION);
+        let wasm_config = crate::vm::Config::clone(&runtime_config.wasm_config);
         RuntimeConfig {
             fees: RuntimeFeesConfig::test(),
             wasm_config,
             account_creation_config: AccountCreationConfig::default(),
+            congestion_control_config: runtime_config.congestion_control_config,
         }
     }
 
     pub fn free() -> Self {
         let config_store = super::config_store::RuntimeConfigStore::new(None);
-        let mut wasm_config =
-            crate::vm::Config::clone(&config_store.get_config(PROTOCOL_VERSION).wasm_config);
+        let runtime_config = config_store.get_config(PROTOCOL_VERSION);
+        let mut wasm_config = crate::vm::Config::clone(&runtime_config.wasm_config);
         wasm_config.make_free();
         Self {
             fees: RuntimeFeesConfig::free(),
             wasm_config,
             account_creation_config: AccountCreationConfig::default(),
+            congestion_control_config: runtime_config.congestion_control_config,
         }
     }
 
@@ -83,3 +88,57 @@ impl Default for AccountCreationConfig {
         }
     }
 }
+
+/// The configuration for congestion control, see `CongestionControl` in the primitives.
+///
+/// A shard is congested when it has a lot of gas in its delayed or outgoing receipt
+/// queues, uses a lot of memory for them, or misses chunks. Each of these is measured
+/// relative to its `max_congestion_*` value and the largest of them is the congestion
+/// level of the shard, between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CongestionControlConfig {
+    /// How much gas in the delayed receipts queue makes the shard fully congested.
+    pub max_congestion_incoming_gas: Gas,
+    /// How much gas in the outgoing receipts buffers makes the shard fully congested.
+    pub max_congestion_outgoing_gas: Gas,
+    /// How many bytes of receipts in the delayed and outgoing queues make the shard fully
+    /// congested. This caps the memory used by these queues.
+    pub max_congestion_memory_consumption: u64,
+    /// How many missed chunks in a row make the shard fully congested.
+    pub max_congestion_missed_chunks: u64,
+    /// How much gas can be sent to a single shard per chunk when it's not congested.
+    pub max_outgoing_gas: Gas,
+    /// How much gas can be sent to a single shard per chunk when it's almost fully
+    /// congested.
+    pub min_outgoing_gas: Gas,
+    /// How much gas can be sent per chunk to a fully congested shard, by the one shard which
+    /// is allowed to send to it in that chunk.
+    pub allowed_shard_outgoing_gas: Gas,
+    /// The maximum gas of the transactions converted to receipts per chunk when the shard
+    /// is not congested.
+    pub max_tx_gas: Gas,
+    /// The minimum gas of the transactions converted to receipts per chunk, used when the
+    /// shard is almost fully congested.
+    pub min_tx_gas: Gas,
+    /// Transactions to a shard with a congestion level above this threshold are rejected.
+    pub reject_tx_congestion_threshold: Rational32,
+}
+
+impl CongestionControlConfig {
+    /// Congestion control which never limits the shards.
+    pub fn test_disabled() -> Self {
+        let max_value = u64::MAX;
+        Self {
+            max_congestion_incoming_gas: max_value,
+            max_congestion_outgoing_gas: max_value,
+            max_congestion_memory_consumption: max_value,
+            max_congestion_missed_chunks: max_value,
+            max_outgoing_gas: max_value,
+            min_outgoing_gas: max_value,
+            allowed_shard_outgoing_gas: max_value,
+            max_tx_gas: max_value,
+            min_tx_gas: max_value,
+            reject_tx_congestion_threshold: Rational32::from_integer(1),
+        }
+    }
+}
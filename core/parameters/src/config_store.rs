@@ -40,6 +40,16 @@ static CONFIG_DIFFS: &[(ProtocolVersion, &str)] = &[
     (129, include_config!("129.yaml")),
     // Introduce ETH-implicit accounts.
     (138, include_config!("138.yaml")),
+    // Introduce host functions for the RSA2048 chip actions.
+    (140, include_config!("140.yaml")),
+    // Introduce the `rsa2048_pkcs1v15_verify` host function.
+    (141, include_config!("141.yaml")),
+    // Introduce the `promise_yield_create` and `promise_yield_resume` host functions.
+    (142, include_config!("142.yaml")),
+    // Introduce the `bls12381_*` host functions.
+    (145, include_config!("145.yaml")),
+    // Introduce the bulk memory and reference types WebAssembly proposals.
+    (147, include_config!("147.yaml")),
 ];
 
 /// Testnet parameters for versions <= 29, which (incorrectly) differed from mainnet parameters
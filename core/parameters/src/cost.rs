@@ -91,6 +91,12 @@ impl ExtCostsConfig {
             ExtCosts::ripemd160_base => SAFETY_MULTIPLIER * 284558362,
             ExtCosts::ed25519_verify_base => SAFETY_MULTIPLIER * 1513656750,
             ExtCosts::ed25519_verify_byte => SAFETY_MULTIPLIER * 7157035,
+            ExtCosts::rsa2048_pkcs1v15_verify_base => SAFETY_MULTIPLIER * 8476477800,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte => SAFETY_MULTIPLIER * 8039117,
+            ExtCosts::yield_create_base => SAFETY_MULTIPLIER * 51137259758,
+            ExtCosts::yield_create_byte => SAFETY_MULTIPLIER * 5214663,
+            ExtCosts::yield_resume_base => SAFETY_MULTIPLIER * 398542428403,
+            ExtCosts::yield_resume_byte => SAFETY_MULTIPLIER * 5737337,
             ExtCosts::ripemd160_block => SAFETY_MULTIPLIER * 226702528,
             ExtCosts::ecrecover_base => SAFETY_MULTIPLIER * 1121789875000,
             ExtCosts::log_base => SAFETY_MULTIPLIER * 1181104350,
@@ -130,6 +136,20 @@ impl ExtCostsConfig {
             ExtCosts::alt_bn128_pairing_check_element => 5_102_000_000_000,
             ExtCosts::alt_bn128_g1_sum_base => 3_000_000_000,
             ExtCosts::alt_bn128_g1_sum_element => 5_000_000_000,
+            ExtCosts::bls12381_p1_sum_base => 16_500_000_000,
+            ExtCosts::bls12381_p1_sum_element => 6_000_000_000,
+            ExtCosts::bls12381_p2_sum_base => 18_600_000_000,
+            ExtCosts::bls12381_p2_sum_element => 15_000_000_000,
+            ExtCosts::bls12381_g1_multiexp_base => 16_500_000_000,
+            ExtCosts::bls12381_g1_multiexp_element => 930_000_000_000,
+            ExtCosts::bls12381_g2_multiexp_base => 18_600_000_000,
+            ExtCosts::bls12381_g2_multiexp_element => 1_995_000_000_000,
+            ExtCosts::bls12381_map_fp_to_g1_base => 1_500_000_000,
+            ExtCosts::bls12381_map_fp_to_g1_element => 252_000_000_000,
+            ExtCosts::bls12381_map_fp2_to_g2_base => 1_500_000_000,
+            ExtCosts::bls12381_map_fp2_to_g2_element => 900_000_000_000,
+            ExtCosts::bls12381_pairing_check_base => 2_130_000_000_000,
+            ExtCosts::bls12381_pairing_check_element => 2_130_000_000_000,
             ExtCosts::validator_power_base => SAFETY_MULTIPLIER * 3_000_000_000,
             ExtCosts::validator_total_power_base => SAFETY_MULTIPLIER * 3_000_000_000,
         }
@@ -225,6 +245,26 @@ pub enum ExtCosts {
     ed25519_verify_byte = 60,
     validator_power_base = 61,
     validator_total_power_base = 62,
+    rsa2048_pkcs1v15_verify_base = 63,
+    rsa2048_pkcs1v15_verify_byte = 64,
+    yield_create_base = 65,
+    yield_create_byte = 66,
+    yield_resume_base = 67,
+    yield_resume_byte = 68,
+    bls12381_p1_sum_base = 69,
+    bls12381_p1_sum_element = 70,
+    bls12381_p2_sum_base = 71,
+    bls12381_p2_sum_element = 72,
+    bls12381_g1_multiexp_base = 73,
+    bls12381_g1_multiexp_element = 74,
+    bls12381_g2_multiexp_base = 75,
+    bls12381_g2_multiexp_element = 76,
+    bls12381_map_fp_to_g1_base = 77,
+    bls12381_map_fp_to_g1_element = 78,
+    bls12381_map_fp2_to_g2_base = 79,
+    bls12381_map_fp2_to_g2_element = 80,
+    bls12381_pairing_check_base = 81,
+    bls12381_pairing_check_element = 82,
 }
 
 // Type of an action, used in fees logic.
@@ -261,6 +301,9 @@ pub enum ActionCosts {
     delegate = 15,
     register_rsa2048_keys = 16,
     create_rsa2048_challenge = 17,
+    deploy_global_contract_base = 18,
+    deploy_global_contract_byte = 19,
+    use_global_contract = 20,
 }
 
 impl ExtCosts {
@@ -300,6 +343,12 @@ impl ExtCosts {
             ExtCosts::ecrecover_base => Parameter::WasmEcrecoverBase,
             ExtCosts::ed25519_verify_base => Parameter::WasmEd25519VerifyBase,
             ExtCosts::ed25519_verify_byte => Parameter::WasmEd25519VerifyByte,
+            ExtCosts::rsa2048_pkcs1v15_verify_base => Parameter::WasmRsa2048Pkcs1v15VerifyBase,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte => Parameter::WasmRsa2048Pkcs1v15VerifyByte,
+            ExtCosts::yield_create_base => Parameter::WasmYieldCreateBase,
+            ExtCosts::yield_create_byte => Parameter::WasmYieldCreateByte,
+            ExtCosts::yield_resume_base => Parameter::WasmYieldResumeBase,
+            ExtCosts::yield_resume_byte => Parameter::WasmYieldResumeByte,
             ExtCosts::log_base => Parameter::WasmLogBase,
             ExtCosts::log_byte => Parameter::WasmLogByte,
             ExtCosts::storage_write_base => Parameter::WasmStorageWriteBase,
@@ -335,6 +384,20 @@ impl ExtCosts {
             ExtCosts::alt_bn128_pairing_check_element => Parameter::WasmAltBn128PairingCheckElement,
             ExtCosts::alt_bn128_g1_sum_base => Parameter::WasmAltBn128G1SumBase,
             ExtCosts::alt_bn128_g1_sum_element => Parameter::WasmAltBn128G1SumElement,
+            ExtCosts::bls12381_p1_sum_base => Parameter::WasmBls12381P1SumBase,
+            ExtCosts::bls12381_p1_sum_element => Parameter::WasmBls12381P1SumElement,
+            ExtCosts::bls12381_p2_sum_base => Parameter::WasmBls12381P2SumBase,
+            ExtCosts::bls12381_p2_sum_element => Parameter::WasmBls12381P2SumElement,
+            ExtCosts::bls12381_g1_multiexp_base => Parameter::WasmBls12381G1MultiexpBase,
+            ExtCosts::bls12381_g1_multiexp_element => Parameter::WasmBls12381G1MultiexpElement,
+            ExtCosts::bls12381_g2_multiexp_base => Parameter::WasmBls12381G2MultiexpBase,
+            ExtCosts::bls12381_g2_multiexp_element => Parameter::WasmBls12381G2MultiexpElement,
+            ExtCosts::bls12381_map_fp_to_g1_base => Parameter::WasmBls12381MapFpToG1Base,
+            ExtCosts::bls12381_map_fp_to_g1_element => Parameter::WasmBls12381MapFpToG1Element,
+            ExtCosts::bls12381_map_fp2_to_g2_base => Parameter::WasmBls12381MapFp2ToG2Base,
+            ExtCosts::bls12381_map_fp2_to_g2_element => Parameter::WasmBls12381MapFp2ToG2Element,
+            ExtCosts::bls12381_pairing_check_base => Parameter::WasmBls12381PairingCheckBase,
+            ExtCosts::bls12381_pairing_check_element => Parameter::WasmBls12381PairingCheckElement,
             ExtCosts::validator_power_base => Parameter::WasmValidatorPledgeBase,
             ExtCosts::validator_total_power_base => Parameter::WasmValidatorTotalPledgeBase,
         }
@@ -470,6 +533,21 @@ impl RuntimeFeesConfig {
                     send_not_sir: 115123062500,
                     execution: 115123062500,
                 },
+                ActionCosts::deploy_global_contract_base => Fee {
+                    send_sir: 184765750000,
+                    send_not_sir: 184765750000,
+                    execution: 184765750000,
+                },
+                ActionCosts::deploy_global_contract_byte => Fee {
+                    send_sir: 6812999,
+                    send_not_sir: 6812999,
+                    execution: 64572944,
+                },
+                ActionCosts::use_global_contract => Fee {
+                    send_sir: 184765750000,
+                    send_not_sir: 184765750000,
+                    execution: 184765750000,
+                },
             },
         }
     }
@@ -80,6 +80,12 @@ pub enum Parameter {
     WasmEcrecoverBase,
     WasmEd25519VerifyBase,
     WasmEd25519VerifyByte,
+    WasmRsa2048Pkcs1v15VerifyBase,
+    WasmRsa2048Pkcs1v15VerifyByte,
+    WasmYieldCreateBase,
+    WasmYieldCreateByte,
+    WasmYieldResumeBase,
+    WasmYieldResumeByte,
     WasmLogBase,
     WasmLogByte,
     WasmStorageWriteBase,
@@ -117,6 +123,20 @@ pub enum Parameter {
     WasmAltBn128PairingCheckElement,
     WasmAltBn128G1SumBase,
     WasmAltBn128G1SumElement,
+    WasmBls12381P1SumBase,
+    WasmBls12381P1SumElement,
+    WasmBls12381P2SumBase,
+    WasmBls12381P2SumElement,
+    WasmBls12381G1MultiexpBase,
+    WasmBls12381G1MultiexpElement,
+    WasmBls12381G2MultiexpBase,
+    WasmBls12381G2MultiexpElement,
+    WasmBls12381MapFpToG1Base,
+    WasmBls12381MapFpToG1Element,
+    WasmBls12381MapFp2ToG2Base,
+    WasmBls12381MapFp2ToG2Element,
+    WasmBls12381PairingCheckBase,
+    WasmBls12381PairingCheckElement,
 
     // Smart contract limits
     MaxGasBurnt,
@@ -145,7 +165,11 @@ pub enum Parameter {
     MaxFunctionsNumberPerContract,
     Wasmer2StackLimit,
     MaxLocalsPerContract,
+    MaxTablesPerContract,
+    MaxElementsPerContractTable,
     AccountIdValidityRulesVersion,
+    YieldTimeoutLengthInBlocks,
+    MaxYieldPayloadSize,
 
     // Contract runtime features
     #[strum(serialize = "disable_9393_fix")]
@@ -159,9 +183,29 @@ pub enum Parameter {
     FunctionCallWeight,
     VmKind,
     EthAccounts,
+    Rsa2048Actions,
+    Rsa2048Verify,
+    YieldResume,
+    Bls12381,
+    WasmFeatureExpansion,
+
+    // Congestion control config
+    MaxCongestionIncomingGas,
+    MaxCongestionOutgoingGas,
+    MaxCongestionMemoryConsumption,
+    MaxCongestionMissedChunks,
+    MaxOutgoingGas,
+    MinOutgoingGas,
+    AllowedShardOutgoingGas,
+    MaxTxGas,
+    MinTxGas,
+    RejectTxCongestionThreshold,
 
     ActionRegisterRSA2048Keys,
     ActionCreateRSA2048Challenge,
+    ActionDeployGlobalContract,
+    ActionDeployGlobalContractPerByte,
+    ActionUseGlobalContract,
 }
 
 #[derive(
@@ -196,6 +240,9 @@ pub enum FeeParameter {
     ActionDelegate,
     ActionRegisterRSA2048Keys,
     ActionCreateRSA2048Challenge,
+    ActionDeployGlobalContract,
+    ActionDeployGlobalContractPerByte,
+    ActionUseGlobalContract,
 }
 
 impl Parameter {
@@ -228,7 +275,11 @@ impl Parameter {
             Parameter::MaxFunctionsNumberPerContract,
             Parameter::Wasmer2StackLimit,
             Parameter::MaxLocalsPerContract,
+            Parameter::MaxTablesPerContract,
+            Parameter::MaxElementsPerContractTable,
             Parameter::AccountIdValidityRulesVersion,
+            Parameter::YieldTimeoutLengthInBlocks,
+            Parameter::MaxYieldPayloadSize,
         ]
         .iter()
     }
@@ -257,6 +308,9 @@ impl From<ActionCosts> for FeeParameter {
             ActionCosts::new_data_receipt_byte => Self::DataReceiptCreationPerByte,
             ActionCosts::register_rsa2048_keys => Self::ActionRegisterRSA2048Keys,
             ActionCosts::create_rsa2048_challenge => Self::ActionCreateRSA2048Challenge,
+            ActionCosts::deploy_global_contract_base => Self::ActionDeployGlobalContract,
+            ActionCosts::deploy_global_contract_byte => Self::ActionDeployGlobalContractPerByte,
+            ActionCosts::use_global_contract => Self::ActionUseGlobalContract,
         }
     }
 }
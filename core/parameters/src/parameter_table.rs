@@ -1,4 +1,4 @@
-use super::config::{AccountCreationConfig, RuntimeConfig};
+use super::config::{AccountCreationConfig, CongestionControlConfig, RuntimeConfig};
 use crate::cost::{
     ActionCosts, ExtCostsConfig, Fee, ParameterCost, RuntimeFeesConfig, StorageUsageConfig,
 };
@@ -325,12 +325,31 @@ impl TryFrom<&ParameterTable> for RuntimeConfig {
                 alt_bn128: params.get(Parameter::AltBn128)?,
                 function_call_weight: params.get(Parameter::FunctionCallWeight)?,
                 eth_accounts: params.get(Parameter::EthAccounts)?,
+                rsa2048_actions: params.get(Parameter::Rsa2048Actions)?,
+                rsa2048_verify: params.get(Parameter::Rsa2048Verify)?,
+                yield_resume_host_functions: params.get(Parameter::YieldResume)?,
+                bls12381: params.get(Parameter::Bls12381)?,
+                wasm_feature_expansion: params.get(Parameter::WasmFeatureExpansion)?,
             },
             account_creation_config: AccountCreationConfig {
                 min_allowed_top_level_account_length: params
                     .get(Parameter::MinAllowedTopLevelAccountLength)?,
                 registrar_account_id: params.get(Parameter::RegistrarAccountId)?,
             },
+            congestion_control_config: CongestionControlConfig {
+                max_congestion_incoming_gas: params.get(Parameter::MaxCongestionIncomingGas)?,
+                max_congestion_outgoing_gas: params.get(Parameter::MaxCongestionOutgoingGas)?,
+                max_congestion_memory_consumption: params
+                    .get(Parameter::MaxCongestionMemoryConsumption)?,
+                max_congestion_missed_chunks: params.get(Parameter::MaxCongestionMissedChunks)?,
+                max_outgoing_gas: params.get(Parameter::MaxOutgoingGas)?,
+                min_outgoing_gas: params.get(Parameter::MinOutgoingGas)?,
+                allowed_shard_outgoing_gas: params.get(Parameter::AllowedShardOutgoingGas)?,
+                max_tx_gas: params.get(Parameter::MaxTxGas)?,
+                min_tx_gas: params.get(Parameter::MinTxGas)?,
+                reject_tx_congestion_threshold: params
+                    .get(Parameter::RejectTxCongestionThreshold)?,
+            },
         })
     }
 }
@@ -221,6 +221,16 @@ pub struct VMConfigView {
     pub function_call_weight: bool,
     /// See [`VMConfig::eth_accounts`].
     pub eth_accounts: bool,
+    /// See [`VMConfig::rsa2048_actions`].
+    pub rsa2048_actions: bool,
+    /// See [`VMConfig::rsa2048_verify`].
+    pub rsa2048_verify: bool,
+    /// See [`VMConfig::yield_resume_host_functions`].
+    pub yield_resume_host_functions: bool,
+    /// See [`VMConfig::bls12381`].
+    pub bls12381: bool,
+    /// See [`VMConfig::wasm_feature_expansion`].
+    pub wasm_feature_expansion: bool,
 
     /// Describes limits for VM and Runtime.
     ///
@@ -246,6 +256,11 @@ impl From<crate::vm::Config> for VMConfigView {
             function_call_weight: config.function_call_weight,
             vm_kind: config.vm_kind,
             eth_accounts: config.eth_accounts,
+            rsa2048_actions: config.rsa2048_actions,
+            rsa2048_verify: config.rsa2048_verify,
+            yield_resume_host_functions: config.yield_resume_host_functions,
+            bls12381: config.bls12381,
+            wasm_feature_expansion: config.wasm_feature_expansion,
         }
     }
 }
@@ -267,6 +282,11 @@ impl From<VMConfigView> for crate::vm::Config {
             function_call_weight: view.function_call_weight,
             vm_kind: view.vm_kind,
             eth_accounts: view.eth_accounts,
+            rsa2048_actions: view.rsa2048_actions,
+            rsa2048_verify: view.rsa2048_verify,
+            yield_resume_host_functions: view.yield_resume_host_functions,
+            bls12381: view.bls12381,
+            wasm_feature_expansion: view.wasm_feature_expansion,
         }
     }
 }
@@ -338,6 +358,20 @@ pub struct ExtCostsConfigView {
     /// Cost of getting ed25519 per byte
     pub ed25519_verify_byte: Gas,
 
+    /// Cost of verifying an RSA2048 PKCS#1 v1.5 signature base
+    pub rsa2048_pkcs1v15_verify_base: Gas,
+    /// Cost of verifying an RSA2048 PKCS#1 v1.5 signature per message byte
+    pub rsa2048_pkcs1v15_verify_byte: Gas,
+
+    /// Cost of creating a yielded promise base
+    pub yield_create_base: Gas,
+    /// Cost of creating a yielded promise per byte of the method name and arguments
+    pub yield_create_byte: Gas,
+    /// Cost of resuming a yielded promise base
+    pub yield_resume_base: Gas,
+    /// Cost of resuming a yielded promise per byte of the payload
+    pub yield_resume_byte: Gas,
+
     /// Cost of calling ecrecover
     pub ecrecover_base: Gas,
 
@@ -440,6 +474,38 @@ pub struct ExtCostsConfigView {
     pub alt_bn128_pairing_check_base: Gas,
     /// Per element cost for pairing check
     pub alt_bn128_pairing_check_element: Gas,
+
+    // #############
+    // # BLS12-381 #
+    // #############
+    /// Base cost for sum of G1 points
+    pub bls12381_p1_sum_base: Gas,
+    /// Per element cost for sum of G1 points
+    pub bls12381_p1_sum_element: Gas,
+    /// Base cost for sum of G2 points
+    pub bls12381_p2_sum_base: Gas,
+    /// Per element cost for sum of G2 points
+    pub bls12381_p2_sum_element: Gas,
+    /// Base cost for G1 multiexp
+    pub bls12381_g1_multiexp_base: Gas,
+    /// Per element cost for G1 multiexp
+    pub bls12381_g1_multiexp_element: Gas,
+    /// Base cost for G2 multiexp
+    pub bls12381_g2_multiexp_base: Gas,
+    /// Per element cost for G2 multiexp
+    pub bls12381_g2_multiexp_element: Gas,
+    /// Base cost for mapping Fp to G1
+    pub bls12381_map_fp_to_g1_base: Gas,
+    /// Per element cost for mapping Fp to G1
+    pub bls12381_map_fp_to_g1_element: Gas,
+    /// Base cost for mapping Fp2 to G2
+    pub bls12381_map_fp2_to_g2_base: Gas,
+    /// Per element cost for mapping Fp2 to G2
+    pub bls12381_map_fp2_to_g2_element: Gas,
+    /// Base cost for pairing check
+    pub bls12381_pairing_check_base: Gas,
+    /// Per element cost for pairing check
+    pub bls12381_pairing_check_element: Gas,
 }
 
 impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
@@ -470,6 +536,12 @@ impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
             ripemd160_block: config.gas_cost(ExtCosts::ripemd160_block),
             ed25519_verify_base: config.gas_cost(ExtCosts::ed25519_verify_base),
             ed25519_verify_byte: config.gas_cost(ExtCosts::ed25519_verify_byte),
+            rsa2048_pkcs1v15_verify_base: config.gas_cost(ExtCosts::rsa2048_pkcs1v15_verify_base),
+            rsa2048_pkcs1v15_verify_byte: config.gas_cost(ExtCosts::rsa2048_pkcs1v15_verify_byte),
+            yield_create_base: config.gas_cost(ExtCosts::yield_create_base),
+            yield_create_byte: config.gas_cost(ExtCosts::yield_create_byte),
+            yield_resume_base: config.gas_cost(ExtCosts::yield_resume_base),
+            yield_resume_byte: config.gas_cost(ExtCosts::yield_resume_byte),
             ecrecover_base: config.gas_cost(ExtCosts::ecrecover_base),
             log_base: config.gas_cost(ExtCosts::log_base),
             log_byte: config.gas_cost(ExtCosts::log_byte),
@@ -512,6 +584,22 @@ impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
             alt_bn128_pairing_check_base: config.gas_cost(ExtCosts::alt_bn128_pairing_check_base),
             alt_bn128_pairing_check_element: config
                 .gas_cost(ExtCosts::alt_bn128_pairing_check_element),
+            bls12381_p1_sum_base: config.gas_cost(ExtCosts::bls12381_p1_sum_base),
+            bls12381_p1_sum_element: config.gas_cost(ExtCosts::bls12381_p1_sum_element),
+            bls12381_p2_sum_base: config.gas_cost(ExtCosts::bls12381_p2_sum_base),
+            bls12381_p2_sum_element: config.gas_cost(ExtCosts::bls12381_p2_sum_element),
+            bls12381_g1_multiexp_base: config.gas_cost(ExtCosts::bls12381_g1_multiexp_base),
+            bls12381_g1_multiexp_element: config.gas_cost(ExtCosts::bls12381_g1_multiexp_element),
+            bls12381_g2_multiexp_base: config.gas_cost(ExtCosts::bls12381_g2_multiexp_base),
+            bls12381_g2_multiexp_element: config.gas_cost(ExtCosts::bls12381_g2_multiexp_element),
+            bls12381_map_fp_to_g1_base: config.gas_cost(ExtCosts::bls12381_map_fp_to_g1_base),
+            bls12381_map_fp_to_g1_element: config.gas_cost(ExtCosts::bls12381_map_fp_to_g1_element),
+            bls12381_map_fp2_to_g2_base: config.gas_cost(ExtCosts::bls12381_map_fp2_to_g2_base),
+            bls12381_map_fp2_to_g2_element: config
+                .gas_cost(ExtCosts::bls12381_map_fp2_to_g2_element),
+            bls12381_pairing_check_base: config.gas_cost(ExtCosts::bls12381_pairing_check_base),
+            bls12381_pairing_check_element: config
+                .gas_cost(ExtCosts::bls12381_pairing_check_element),
             // removed parameters
             contract_compile_base: 0,
             contract_compile_bytes: 0,
@@ -547,6 +635,12 @@ impl From<ExtCostsConfigView> for crate::ExtCostsConfig {
                 ExtCosts::ripemd160_block => view.ripemd160_block,
                 ExtCosts::ed25519_verify_base => view.ed25519_verify_base,
                 ExtCosts::ed25519_verify_byte => view.ed25519_verify_byte,
+                ExtCosts::rsa2048_pkcs1v15_verify_base => view.rsa2048_pkcs1v15_verify_base,
+                ExtCosts::rsa2048_pkcs1v15_verify_byte => view.rsa2048_pkcs1v15_verify_byte,
+                ExtCosts::yield_create_base => view.yield_create_base,
+                ExtCosts::yield_create_byte => view.yield_create_byte,
+                ExtCosts::yield_resume_base => view.yield_resume_base,
+                ExtCosts::yield_resume_byte => view.yield_resume_byte,
                 ExtCosts::ecrecover_base => view.ecrecover_base,
                 ExtCosts::log_base => view.log_base,
                 ExtCosts::log_byte => view.log_byte,
@@ -585,6 +679,20 @@ impl From<ExtCostsConfigView> for crate::ExtCostsConfig {
                 ExtCosts::alt_bn128_g1_sum_element => view.alt_bn128_g1_sum_element,
                 ExtCosts::alt_bn128_pairing_check_base => view.alt_bn128_pairing_check_base,
                 ExtCosts::alt_bn128_pairing_check_element => view.alt_bn128_pairing_check_element,
+                ExtCosts::bls12381_p1_sum_base => view.bls12381_p1_sum_base,
+                ExtCosts::bls12381_p1_sum_element => view.bls12381_p1_sum_element,
+                ExtCosts::bls12381_p2_sum_base => view.bls12381_p2_sum_base,
+                ExtCosts::bls12381_p2_sum_element => view.bls12381_p2_sum_element,
+                ExtCosts::bls12381_g1_multiexp_base => view.bls12381_g1_multiexp_base,
+                ExtCosts::bls12381_g1_multiexp_element => view.bls12381_g1_multiexp_element,
+                ExtCosts::bls12381_g2_multiexp_base => view.bls12381_g2_multiexp_base,
+                ExtCosts::bls12381_g2_multiexp_element => view.bls12381_g2_multiexp_element,
+                ExtCosts::bls12381_map_fp_to_g1_base => view.bls12381_map_fp_to_g1_base,
+                ExtCosts::bls12381_map_fp_to_g1_element => view.bls12381_map_fp_to_g1_element,
+                ExtCosts::bls12381_map_fp2_to_g2_base => view.bls12381_map_fp2_to_g2_base,
+                ExtCosts::bls12381_map_fp2_to_g2_element => view.bls12381_map_fp2_to_g2_element,
+                ExtCosts::bls12381_pairing_check_base => view.bls12381_pairing_check_base,
+                ExtCosts::bls12381_pairing_check_element => view.bls12381_pairing_check_element,
         }
         .map(|_, value| ParameterCost { gas: value, compute: value });
         Self { costs }
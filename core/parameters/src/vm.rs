@@ -128,10 +128,22 @@ pub struct LimitConfig {
     /// If present, stores max number of locals declared globally in one contract
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_locals_per_contract: Option<u64>,
+    /// If present, stores max number of tables declared in one contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tables_per_contract: Option<u32>,
+    /// If present, stores max number of elements in a single table of a contract, both when it is
+    /// declared and after `table.grow`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_elements_per_contract_table: Option<usize>,
     /// Whether to enforce account_id well-formedness where it wasn't enforced
     /// historically.
     #[serde(default = "AccountIdValidityRulesVersion::v0")]
     pub account_id_validity_rules_version: AccountIdValidityRulesVersion,
+    /// Number of blocks after which a yielded promise times out and is resumed without
+    /// a payload.
+    pub yield_timeout_length_in_blocks: u64,
+    /// Maximum number of bytes for a payload passed over a yield resume.
+    pub max_yield_payload_size: u64,
 }
 
 /// Dynamic configuration parameters required for the WASM runtime to
@@ -183,6 +195,21 @@ pub struct Config {
     /// Enable the `EthAccounts` protocol feature.
     pub eth_accounts: bool,
 
+    /// Enable the host functions added by the `Rsa2048Actions` protocol feature.
+    pub rsa2048_actions: bool,
+
+    /// Enable the `rsa2048_pkcs1v15_verify` host function.
+    pub rsa2048_verify: bool,
+
+    /// Enable the `promise_yield_create` and `promise_yield_resume` host functions.
+    pub yield_resume_host_functions: bool,
+
+    /// Enable the `bls12381_*` host functions for operations on the BLS12-381 curve.
+    pub bls12381: bool,
+
+    /// Enable the bulk memory and reference types WebAssembly proposals.
+    pub wasm_feature_expansion: bool,
+
     /// Describes limits for VM and Runtime.
     pub limit_config: LimitConfig,
 }
@@ -130,6 +130,35 @@ pub enum ProtocolFeature {
     /// NEP: https://github.com/Utility/UEPs/pull/509
     ChunkValidation,
     EthAccounts,
+    /// Encrypts peer-to-peer connections with a Noise transport keyed by the
+    /// node key, negotiated in the network `Handshake`.
+    EncryptedPeerTransport,
+    /// `promise_batch_action_register_rsa2048_keys` and
+    /// `promise_batch_action_create_rsa2048_challenge` host functions.
+    Rsa2048Actions,
+    /// `rsa2048_pkcs1v15_verify` host function.
+    Rsa2048Verify,
+    /// `promise_yield_create` and `promise_yield_resume` host functions, which allow a
+    /// contract to suspend a function call until it's resumed with a payload or times out.
+    YieldExecution,
+    /// Shards publish their congestion in chunk headers, and the other shards throttle the
+    /// transactions and receipts sent to them accordingly.
+    CongestionControl,
+    /// `DeployGlobalContract` and `UseGlobalContract` actions, which store contract code once
+    /// by its hash and let accounts run it without keeping a copy of their own.
+    GlobalContracts,
+    /// `bls12381_*` host functions for point addition, multiexponentiation, hashing to
+    /// the curve and pairing checks on the BLS12-381 curve.
+    Bls12381,
+    /// `DeployContractWithMigration` action, which deploys a contract and calls its migration
+    /// method atomically.
+    ContractMigration,
+    /// Enables the bulk memory and reference types WebAssembly proposals for contracts. The
+    /// multi-value proposal stays disabled as the unc-vm compiler does not support it yet.
+    WasmFeatureExpansion,
+    /// Transactions may carry an optional priority fee, paid on top of their regular cost to get
+    /// ahead in the transaction pool.
+    PriorityFees,
 }
 
 impl ProtocolFeature {
@@ -186,6 +215,16 @@ impl ProtocolFeature {
             ProtocolFeature::RejectBlocksWithOutdatedProtocolVersions => 132,
             ProtocolFeature::ChunkValidation => 137,
             ProtocolFeature::EthAccounts => 138,
+            ProtocolFeature::EncryptedPeerTransport => 139,
+            ProtocolFeature::Rsa2048Actions => 140,
+            ProtocolFeature::Rsa2048Verify => 141,
+            ProtocolFeature::YieldExecution => 142,
+            ProtocolFeature::CongestionControl => 143,
+            ProtocolFeature::GlobalContracts => 144,
+            ProtocolFeature::Bls12381 => 145,
+            ProtocolFeature::ContractMigration => 146,
+            ProtocolFeature::WasmFeatureExpansion => 147,
+            ProtocolFeature::PriorityFees => 148,
         }
     }
 }
@@ -198,7 +237,7 @@ const STABLE_PROTOCOL_VERSION: ProtocolVersion = 64;
 /// Largest protocol version supported by the current binary.
 pub const PROTOCOL_VERSION: ProtocolVersion = if cfg!(feature = "nightly_protocol") {
     // On nightly, pick big enough version to support all features.
-    139
+    148
 } else {
     // Enable all stable features.
     STABLE_PROTOCOL_VERSION
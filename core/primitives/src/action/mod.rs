@@ -7,6 +7,7 @@ use std::fmt;
 use unc_crypto::PublicKey;
 use unc_primitives_core::{
     account::AccessKey,
+    hash::CryptoHash,
     serialize::dec_format,
     types::{AccountId, Balance, Gas},
 };
@@ -94,6 +95,71 @@ impl fmt::Debug for DeployContractAction {
     }
 }
 
+/// Deploy contract with migration action: deploys the code and calls the migration method on it
+/// within the same action. The code change is reverted if the migration fails.
+#[serde_as]
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
+)]
+pub struct DeployContractWithMigrationAction {
+    /// WebAssembly binary
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+    /// Method of the new code which migrates the contract state
+    pub migrate_method_name: String,
+    #[serde_as(as = "Base64")]
+    pub migrate_args: Vec<u8>,
+    /// Gas attached to the migration call
+    pub gas: Gas,
+}
+
+impl fmt::Debug for DeployContractWithMigrationAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeployContractWithMigrationAction")
+            .field("code", &format_args!("{}", base64(&self.code)))
+            .field("migrate_method_name", &format_args!("{}", &self.migrate_method_name))
+            .field("migrate_args", &format_args!("{}", base64(&self.migrate_args)))
+            .field("gas", &format_args!("{}", &self.gas))
+            .finish()
+    }
+}
+
+/// Deploy global contract action: stores the code once under its hash so that
+/// any account can start using it with `UseGlobalContractAction`.
+#[serde_as]
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
+)]
+pub struct DeployGlobalContractAction {
+    /// WebAssembly binary
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+}
+
+impl fmt::Debug for DeployGlobalContractAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeployGlobalContractAction")
+            .field("code", &format_args!("{}", base64(&self.code)))
+            .finish()
+    }
+}
+
+/// Use global contract action: makes the receiver account run a previously
+/// deployed global contract identified by its code hash.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct UseGlobalContractAction {
+    pub code_hash: CryptoHash,
+}
+
 #[serde_as]
 #[derive(
     BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
@@ -229,6 +295,13 @@ pub enum Action {
     Delegate(Box<delegate::SignedDelegateAction>),
     RegisterRsa2048Keys(Box<RegisterRsa2048KeysAction>),
     CreateRsa2048Challenge(Box<CreateRsa2048ChallengeAction>),
+    /// Stores a Wasm code by its hash so that it can be shared between accounts
+    DeployGlobalContract(DeployGlobalContractAction),
+    /// Sets a previously deployed global contract as the receiver_id code
+    UseGlobalContract(Box<UseGlobalContractAction>),
+    /// Sets a Wasm code to a receiver_id and calls its migration method, reverting the code
+    /// if the migration fails
+    DeployContractWithMigration(Box<DeployContractWithMigrationAction>),
 }
 
 const _: () = assert!(
@@ -243,6 +316,7 @@ impl Action {
     pub fn get_prepaid_gas(&self) -> Gas {
         match self {
             Action::FunctionCall(a) => a.gas,
+            Action::DeployContractWithMigration(a) => a.gas,
             _ => 0,
         }
     }
@@ -314,3 +388,21 @@ impl From<CreateRsa2048ChallengeAction> for Action {
         Self::CreateRsa2048Challenge(Box::new(create_rsa2048_challenge_action))
     }
 }
+
+impl From<DeployGlobalContractAction> for Action {
+    fn from(deploy_global_contract_action: DeployGlobalContractAction) -> Self {
+        Self::DeployGlobalContract(deploy_global_contract_action)
+    }
+}
+
+impl From<UseGlobalContractAction> for Action {
+    fn from(use_global_contract_action: UseGlobalContractAction) -> Self {
+        Self::UseGlobalContract(Box::new(use_global_contract_action))
+    }
+}
+
+impl From<DeployContractWithMigrationAction> for Action {
+    fn from(deploy_contract_with_migration_action: DeployContractWithMigrationAction) -> Self {
+        Self::DeployContractWithMigration(Box::new(deploy_contract_with_migration_action))
+    }
+}
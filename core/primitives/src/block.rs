@@ -5,6 +5,7 @@ use crate::block::BlockValidityError::{
 pub use crate::block_header::*;
 use crate::challenge::{Challenges, ChallengesResult};
 use crate::checked_feature;
+use crate::congestion_info::{BlockCongestionInfo, CongestionInfo, ExtendedCongestionInfo};
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::{merklize, verify_path, MerklePath};
 use crate::num_rational::Rational32;
@@ -20,6 +21,7 @@ use crate::version::{ProtocolVersion, SHARD_CHUNK_HEADER_UPGRADE_VERSION};
 use borsh::{BorshDeserialize, BorshSerialize};
 use chrono::{DateTime, Utc};
 use primitive_types::U256;
+use std::collections::BTreeMap;
 use std::ops::Index;
 use std::sync::Arc;
 use unc_crypto::Signature;
@@ -106,6 +108,9 @@ pub fn genesis_chunks(
         std::iter::repeat(state_roots[0]).take(shard_ids.len()).collect()
     };
 
+    let congestion_info = checked_feature!("stable", CongestionControl, genesis_protocol_version)
+        .then(CongestionInfo::default);
+
     shard_ids
         .into_iter()
         .zip(state_roots)
@@ -126,6 +131,7 @@ pub fn genesis_chunks(
                 vec![],
                 &[],
                 CryptoHash::default(),
+                congestion_info,
                 &EmptyValidatorSigner::default(),
                 genesis_protocol_version,
             )
@@ -527,6 +533,27 @@ impl Block {
         }
     }
 
+    /// Collects the congestion info published by the chunks of this block.
+    ///
+    /// For shards whose chunk is missing in this block, the info of the last
+    /// included chunk is used and the number of missed chunks is recorded.
+    /// Shards with chunks predating congestion control are left out.
+    pub fn block_congestion_info(&self) -> BlockCongestionInfo {
+        let height = self.header().height();
+        let mut result = BTreeMap::new();
+        for chunk in self.chunks().iter() {
+            let Some(congestion_info) = chunk.congestion_info() else {
+                continue;
+            };
+            let missed_chunks_count = height.saturating_sub(chunk.height_included());
+            result.insert(
+                chunk.shard_id(),
+                ExtendedCongestionInfo::new(congestion_info, missed_chunks_count),
+            );
+        }
+        BlockCongestionInfo::new(result)
+    }
+
     #[inline]
     pub fn challenges(&self) -> &Challenges {
         match self {
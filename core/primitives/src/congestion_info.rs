@@ -0,0 +1,433 @@
+//! Congestion control of the cross-shard receipts.
+//!
+//! Each shard tracks how much gas and memory its delayed receipts and its outgoing
+//! receipts buffers take, and publishes it as `CongestionInfo` in the chunk header.
+//! The other shards use it to limit the gas of the receipts they forward to the shard,
+//! keeping the rest in their outgoing buffers, and to reject the transactions sent to it.
+use crate::errors::IntegerOverflowError;
+use crate::types::ShardId;
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::BTreeMap;
+use unc_parameters::config::CongestionControlConfig;
+use unc_primitives_core::types::Gas;
+
+/// Congestion info of a shard, as of the end of its last chunk.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+)]
+pub enum CongestionInfo {
+    V1(CongestionInfoV1),
+}
+
+impl Default for CongestionInfo {
+    fn default() -> Self {
+        Self::V1(CongestionInfoV1::default())
+    }
+}
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+)]
+pub struct CongestionInfoV1 {
+    /// Sum of the gas of the receipts in the delayed receipts queue.
+    pub delayed_receipts_gas: u128,
+    /// Sum of the gas of the receipts in all outgoing receipts buffers.
+    pub buffered_receipts_gas: u128,
+    /// Size of the borsh-serialized receipts in the delayed queue and the outgoing buffers.
+    pub receipt_bytes: u64,
+    /// The only shard allowed to send receipts to this shard while it's fully congested.
+    pub allowed_shard: u16,
+}
+
+impl CongestionInfo {
+    pub fn delayed_receipts_gas(&self) -> u128 {
+        match self {
+            Self::V1(inner) => inner.delayed_receipts_gas,
+        }
+    }
+
+    pub fn buffered_receipts_gas(&self) -> u128 {
+        match self {
+            Self::V1(inner) => inner.buffered_receipts_gas,
+        }
+    }
+
+    pub fn receipt_bytes(&self) -> u64 {
+        match self {
+            Self::V1(inner) => inner.receipt_bytes,
+        }
+    }
+
+    pub fn allowed_shard(&self) -> ShardId {
+        match self {
+            Self::V1(inner) => inner.allowed_shard as ShardId,
+        }
+    }
+
+    pub fn add_delayed_receipt_gas(&mut self, gas: Gas) -> Result<(), IntegerOverflowError> {
+        match self {
+            Self::V1(inner) => {
+                inner.delayed_receipts_gas = inner
+                    .delayed_receipts_gas
+                    .checked_add(gas as u128)
+                    .ok_or(IntegerOverflowError)?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove_delayed_receipt_gas(&mut self, gas: Gas) -> Result<(), IntegerOverflowError> {
+        match self {
+            Self::V1(inner) => {
+                inner.delayed_receipts_gas = inner
+                    .delayed_receipts_gas
+                    .checked_sub(gas as u128)
+                    .ok_or(IntegerOverflowError)?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_buffered_receipt_gas(&mut self, gas: Gas) -> Result<(), IntegerOverflowError> {
+        match self {
+            Self::V1(inner) => {
+                inner.buffered_receipts_gas = inner
+                    .buffered_receipts_gas
+                    .checked_add(gas as u128)
+                    .ok_or(IntegerOverflowError)?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove_buffered_receipt_gas(&mut self, gas: Gas) -> Result<(), IntegerOverflowError> {
+        match self {
+            Self::V1(inner) => {
+                inner.buffered_receipts_gas = inner
+                    .buffered_receipts_gas
+                    .checked_sub(gas as u128)
+                    .ok_or(IntegerOverflowError)?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_receipt_bytes(&mut self, bytes: u64) -> Result<(), IntegerOverflowError> {
+        match self {
+            Self::V1(inner) => {
+                inner.receipt_bytes =
+                    inner.receipt_bytes.checked_add(bytes).ok_or(IntegerOverflowError)?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove_receipt_bytes(&mut self, bytes: u64) -> Result<(), IntegerOverflowError> {
+        match self {
+            Self::V1(inner) => {
+                inner.receipt_bytes =
+                    inner.receipt_bytes.checked_sub(bytes).ok_or(IntegerOverflowError)?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_allowed_shard(&mut self, allowed_shard: ShardId) {
+        match self {
+            Self::V1(inner) => inner.allowed_shard = allowed_shard as u16,
+        }
+    }
+}
+
+/// Congestion info of a shard, together with the number of chunks it has missed in a row.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ExtendedCongestionInfo {
+    pub congestion_info: CongestionInfo,
+    pub missed_chunks_count: u64,
+}
+
+impl ExtendedCongestionInfo {
+    pub fn new(congestion_info: CongestionInfo, missed_chunks_count: u64) -> Self {
+        Self { congestion_info, missed_chunks_count }
+    }
+}
+
+/// Congestion info of all shards, taken from the chunk headers of a block.
+/// Shards whose chunks don't have the congestion info yet are missing.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct BlockCongestionInfo {
+    shards_congestion_info: BTreeMap<ShardId, ExtendedCongestionInfo>,
+}
+
+impl BlockCongestionInfo {
+    pub fn new(shards_congestion_info: BTreeMap<ShardId, ExtendedCongestionInfo>) -> Self {
+        Self { shards_congestion_info }
+    }
+
+    pub fn get(&self, shard_id: &ShardId) -> Option<&ExtendedCongestionInfo> {
+        self.shards_congestion_info.get(shard_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ShardId, &ExtendedCongestionInfo)> {
+        self.shards_congestion_info.iter()
+    }
+
+    pub fn all_shards(&self) -> Vec<ShardId> {
+        self.shards_congestion_info.keys().copied().collect()
+    }
+}
+
+/// Whether a shard accepts new transactions, see `CongestionControl::shard_accepts_transactions`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ShardAcceptsTransactions {
+    Yes,
+    No(RejectTransactionReason),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RejectTransactionReason {
+    /// The delayed receipts, the outgoing buffers or their memory are above the limits.
+    Congested { congestion_level: f64 },
+    /// The shard has missed too many chunks to take any more work.
+    MissedChunks { missed_chunks: u64 },
+}
+
+/// Congestion control decisions for a shard, derived from its congestion info.
+///
+/// Each kind of congestion is a number between 0 and 1, relative to its limit in
+/// `CongestionControlConfig`. The congestion level of the shard is the largest of them.
+pub struct CongestionControl {
+    config: CongestionControlConfig,
+    info: CongestionInfo,
+    missed_chunks_count: u64,
+}
+
+impl CongestionControl {
+    pub fn new(
+        config: CongestionControlConfig,
+        info: CongestionInfo,
+        missed_chunks_count: u64,
+    ) -> Self {
+        Self { config, info, missed_chunks_count }
+    }
+
+    pub fn congestion_info(&self) -> &CongestionInfo {
+        &self.info
+    }
+
+    pub fn congestion_level(&self) -> f64 {
+        self.incoming_congestion()
+            .max(self.outgoing_congestion())
+            .max(self.memory_congestion())
+            .max(self.missed_chunks_congestion())
+    }
+
+    pub fn incoming_congestion(&self) -> f64 {
+        clamped_f64_fraction(
+            self.info.delayed_receipts_gas(),
+            self.config.max_congestion_incoming_gas,
+        )
+    }
+
+    pub fn outgoing_congestion(&self) -> f64 {
+        clamped_f64_fraction(
+            self.info.buffered_receipts_gas(),
+            self.config.max_congestion_outgoing_gas,
+        )
+    }
+
+    pub fn memory_congestion(&self) -> f64 {
+        clamped_f64_fraction(
+            self.info.receipt_bytes() as u128,
+            self.config.max_congestion_memory_consumption,
+        )
+    }
+
+    pub fn missed_chunks_congestion(&self) -> f64 {
+        clamped_f64_fraction(
+            self.missed_chunks_count as u128,
+            self.config.max_congestion_missed_chunks,
+        )
+    }
+
+    /// How much gas the `sender_shard` may forward to this shard in its next chunk.
+    ///
+    /// The limit goes down from `max_outgoing_gas` to `min_outgoing_gas` as the shard gets
+    /// congested. A fully congested shard only accepts receipts from the allowed shard, which
+    /// rotates from chunk to chunk, so that the receipts of each shard make progress.
+    pub fn outgoing_gas_limit(&self, sender_shard: ShardId) -> Gas {
+        let congestion = self
+            .incoming_congestion()
+            .max(self.outgoing_congestion())
+            .max(self.memory_congestion());
+        if congestion >= 1.0 {
+            if sender_shard == self.info.allowed_shard() {
+                self.config.allowed_shard_outgoing_gas
+            } else {
+                0
+            }
+        } else {
+            mix(self.config.max_outgoing_gas, self.config.min_outgoing_gas, congestion)
+        }
+    }
+
+    /// How much gas of transactions the shard may convert to receipts in its next chunk.
+    pub fn process_tx_limit(&self) -> Gas {
+        mix(self.config.max_tx_gas, self.config.min_tx_gas, self.incoming_congestion())
+    }
+
+    /// Whether transactions with receivers on this shard should be accepted.
+    pub fn shard_accepts_transactions(&self) -> ShardAcceptsTransactions {
+        let threshold = *self.config.reject_tx_congestion_threshold.numer() as f64
+            / *self.config.reject_tx_congestion_threshold.denom() as f64;
+        let missed_chunks_congestion = self.missed_chunks_congestion();
+        let congestion_level = self.congestion_level();
+        if congestion_level < threshold {
+            ShardAcceptsTransactions::Yes
+        } else if missed_chunks_congestion >= congestion_level {
+            ShardAcceptsTransactions::No(RejectTransactionReason::MissedChunks {
+                missed_chunks: self.missed_chunks_count,
+            })
+        } else {
+            ShardAcceptsTransactions::No(RejectTransactionReason::Congested { congestion_level })
+        }
+    }
+
+    /// Picks the shard allowed to send receipts to this shard in its next chunk.
+    ///
+    /// Unless the shard is fully congested, it's the shard itself, which means that every
+    /// shard may send receipts. Otherwise one of the other shards is picked, based on the
+    /// `congestion_seed` which should change from chunk to chunk.
+    pub fn finalize_allowed_shard(
+        &mut self,
+        own_shard: ShardId,
+        all_shards: &[ShardId],
+        congestion_seed: u64,
+    ) {
+        let congestion = self
+            .incoming_congestion()
+            .max(self.outgoing_congestion())
+            .max(self.memory_congestion());
+        let other_shards: Vec<ShardId> =
+            all_shards.iter().copied().filter(|shard_id| *shard_id != own_shard).collect();
+        let allowed_shard = if congestion < 1.0 || other_shards.is_empty() {
+            own_shard
+        } else {
+            other_shards[(congestion_seed % other_shards.len() as u64) as usize]
+        };
+        self.info.set_allowed_shard(allowed_shard);
+    }
+}
+
+/// Returns `value / max`, clamped to 1.
+fn clamped_f64_fraction(value: u128, max: u64) -> f64 {
+    assert!(max > 0);
+    if max as u128 <= value {
+        1.0
+    } else {
+        value as f64 / max as f64
+    }
+}
+
+/// Linear interpolation between `left` (ratio 0) and `right` (ratio 1).
+fn mix(left: u64, right: u64, ratio: f64) -> u64 {
+    debug_assert!((0.0..=1.0).contains(&ratio));
+    let left_part = left as f64 * (1.0 - ratio);
+    let right_part = right as f64 * ratio;
+    // Truncation is fine, the result only needs to be deterministic.
+    (left_part + right_part) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_rational::Rational32;
+
+    fn config() -> CongestionControlConfig {
+        CongestionControlConfig {
+            max_congestion_incoming_gas: 100,
+            max_congestion_outgoing_gas: 100,
+            max_congestion_memory_consumption: 1000,
+            max_congestion_missed_chunks: 5,
+            max_outgoing_gas: 300,
+            min_outgoing_gas: 100,
+            allowed_shard_outgoing_gas: 50,
+            max_tx_gas: 200,
+            min_tx_gas: 20,
+            reject_tx_congestion_threshold: Rational32::new(1, 2),
+        }
+    }
+
+    #[test]
+    fn test_not_congested() {
+        let control = CongestionControl::new(config(), CongestionInfo::default(), 0);
+        assert_eq!(0.0, control.congestion_level());
+        assert_eq!(300, control.outgoing_gas_limit(1));
+        assert_eq!(200, control.process_tx_limit());
+        assert_eq!(ShardAcceptsTransactions::Yes, control.shard_accepts_transactions());
+    }
+
+    #[test]
+    fn test_partially_congested() {
+        let mut info = CongestionInfo::default();
+        info.add_delayed_receipt_gas(50).unwrap();
+        let control = CongestionControl::new(config(), info, 0);
+        assert_eq!(0.5, control.congestion_level());
+        assert_eq!(200, control.outgoing_gas_limit(1));
+        assert_eq!(110, control.process_tx_limit());
+        assert_eq!(
+            ShardAcceptsTransactions::No(RejectTransactionReason::Congested {
+                congestion_level: 0.5
+            }),
+            control.shard_accepts_transactions()
+        );
+    }
+
+    #[test]
+    fn test_fully_congested() {
+        let mut info = CongestionInfo::default();
+        info.add_receipt_bytes(2000).unwrap();
+        let mut control = CongestionControl::new(config(), info, 0);
+        assert_eq!(1.0, control.congestion_level());
+
+        control.finalize_allowed_shard(0, &[0, 1, 2], 3);
+        assert_eq!(2, control.congestion_info().allowed_shard());
+        assert_eq!(0, control.outgoing_gas_limit(1));
+        assert_eq!(50, control.outgoing_gas_limit(2));
+
+        control.info.remove_receipt_bytes(2000).unwrap();
+        control.finalize_allowed_shard(0, &[0, 1, 2], 4);
+        assert_eq!(0, control.congestion_info().allowed_shard());
+        assert_eq!(300, control.outgoing_gas_limit(1));
+    }
+
+    #[test]
+    fn test_missed_chunks() {
+        let control = CongestionControl::new(config(), CongestionInfo::default(), 5);
+        // Missed chunks don't limit the receipts, only the transactions.
+        assert_eq!(300, control.outgoing_gas_limit(1));
+        assert_eq!(
+            ShardAcceptsTransactions::No(RejectTransactionReason::MissedChunks {
+                missed_chunks: 5
+            }),
+            control.shard_accepts_transactions()
+        );
+    }
+}
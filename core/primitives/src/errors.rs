@@ -176,6 +176,22 @@ pub enum InvalidTxError {
     ActionsValidation(ActionsValidationError),
     /// The size of serialized transaction exceeded the limit.
     TransactionSizeExceeded { size: u64, limit: u64 },
+    /// The receiver shard of the transaction is too congested to accept new transactions.
+    ShardCongested {
+        /// The congested shard.
+        shard_id: u32,
+        /// The congestion level of the shard, in thousandths.
+        congestion_level_per_mille: u16,
+    },
+    /// The receiver shard of the transaction missed too many chunks to accept new transactions.
+    ShardStuck {
+        /// The shard which fails to produce chunks.
+        shard_id: u32,
+        /// The number of blocks since the last included chunk of the shard.
+        missed_chunks: u64,
+    },
+    /// The transaction sets a priority fee, which the current protocol version doesn't support.
+    PriorityFeeNotSupported,
 }
 
 impl std::error::Error for InvalidTxError {}
@@ -504,6 +520,11 @@ pub enum ActionErrorKind {
 
     /// The public key used for an  not existed  rsa key
     RsaKeysNotFound { account_id: AccountId, public_key: Box<PublicKey> },
+    /// `UseGlobalContract` refers to a code hash that has not been deployed as a global contract
+    GlobalContractDoesNotExist { code_hash: CryptoHash },
+    /// The migration method called by `DeployContractWithMigration` failed, so the previous code
+    /// of the account was restored
+    ContractMigrationFailed { account_id: AccountId, method_name: String, error: FunctionCallError },
 }
 
 impl From<ActionErrorKind> for ActionError {
@@ -565,6 +586,23 @@ impl Display for InvalidTxError {
             InvalidTxError::TransactionSizeExceeded { size, limit } => {
                 write!(f, "Size of serialized transaction {} exceeded the limit {}", size, limit)
             }
+            InvalidTxError::ShardCongested { shard_id, congestion_level_per_mille } => {
+                write!(
+                    f,
+                    "Shard {} is too congested to accept new transactions, its congestion level is {}/1000",
+                    shard_id, congestion_level_per_mille
+                )
+            }
+            InvalidTxError::ShardStuck { shard_id, missed_chunks } => {
+                write!(
+                    f,
+                    "Shard {} missed {} chunks and doesn't accept new transactions",
+                    shard_id, missed_chunks
+                )
+            }
+            InvalidTxError::PriorityFeeNotSupported => {
+                write!(f, "Priority fees are not supported by the current protocol version")
+            }
         }
     }
 }
@@ -639,6 +677,8 @@ pub struct BalanceMismatchError {
     pub processed_delayed_receipts_balance: Balance,
     #[serde(with = "dec_format")]
     pub initial_postponed_receipts_balance: Balance,
+    #[serde(with = "dec_format", default)]
+    pub forwarded_buffered_receipts_balance: Balance,
     // Output balances
     #[serde(with = "dec_format")]
     pub final_accounts_balance: Balance,
@@ -648,6 +688,8 @@ pub struct BalanceMismatchError {
     pub new_delayed_receipts_balance: Balance,
     #[serde(with = "dec_format")]
     pub final_postponed_receipts_balance: Balance,
+    #[serde(with = "dec_format", default)]
+    pub new_buffered_receipts_balance: Balance,
     #[serde(with = "dec_format")]
     pub tx_burnt_amount: Balance,
     #[serde(with = "dec_format")]
@@ -664,12 +706,14 @@ impl Display for BalanceMismatchError {
             .saturating_add(self.initial_accounts_balance)
             .saturating_add(self.incoming_receipts_balance)
             .saturating_add(self.processed_delayed_receipts_balance)
-            .saturating_add(self.initial_postponed_receipts_balance);
+            .saturating_add(self.initial_postponed_receipts_balance)
+            .saturating_add(self.forwarded_buffered_receipts_balance);
         let final_balance = self
             .final_accounts_balance
             .saturating_add(self.outgoing_receipts_balance)
             .saturating_add(self.new_delayed_receipts_balance)
             .saturating_add(self.final_postponed_receipts_balance)
+            .saturating_add(self.new_buffered_receipts_balance)
             .saturating_add(self.tx_burnt_amount)
             .saturating_add(self.slashed_burnt_amount)
             .saturating_add(self.other_burnt_amount);
@@ -682,11 +726,13 @@ impl Display for BalanceMismatchError {
              \tIncoming receipts balance sum: {}\n\
              \tProcessed delayed receipts balance sum: {}\n\
              \tInitial postponed receipts balance sum: {}\n\
+             \tForwarded buffered receipts sum: {}\n\
              Outputs:\n\
              \tFinal accounts balance sum: {}\n\
              \tOutgoing receipts balance sum: {}\n\
              \tNew delayed receipts balance sum: {}\n\
              \tFinal postponed receipts balance sum: {}\n\
+             \tNew buffered receipts balance sum: {}\n\
              \tTx fees burnt amount: {}\n\
              \tSlashed amount: {}\n\
              \tOther burnt amount: {}",
@@ -697,10 +743,12 @@ impl Display for BalanceMismatchError {
             self.incoming_receipts_balance,
             self.processed_delayed_receipts_balance,
             self.initial_postponed_receipts_balance,
+            self.forwarded_buffered_receipts_balance,
             self.final_accounts_balance,
             self.outgoing_receipts_balance,
             self.new_delayed_receipts_balance,
             self.final_postponed_receipts_balance,
+            self.new_buffered_receipts_balance,
             self.tx_burnt_amount,
             self.slashed_burnt_amount,
             self.other_burnt_amount,
@@ -828,6 +876,12 @@ impl Display for ActionErrorKind {
                 "The public key {:?} is doesn't exist rsa key",
                 public_key
             ),
+            ActionErrorKind::GlobalContractDoesNotExist { code_hash } => write!(f, "Global contract with code hash {} does not exist", code_hash),
+            ActionErrorKind::ContractMigrationFailed { account_id, method_name, error } => write!(
+                f,
+                "Migration method {} of the contract deployed to {} failed: {:?}",
+                method_name, account_id, error
+            ),
         }
     }
 }
@@ -1102,6 +1156,10 @@ pub enum PrepareError {
     TooManyFunctions,
     /// Contract contains too many locals.
     TooManyLocals,
+    /// Contract contains too many tables.
+    TooManyTables,
+    /// Contract contains a table with too many elements.
+    TooManyTableElements,
 }
 
 /// A kind of a trap happened during execution of a binary
@@ -1219,6 +1277,16 @@ pub enum HostError {
     /// Invalid input to ed25519 signature verification function (e.g. signature cannot be
     /// derived from bytes).
     Ed25519VerifyInvalidInput { msg: String },
+    /// Invalid input to RSA2048 signature verification function (e.g. signature of a wrong
+    /// length).
+    Rsa2048VerifyInvalidInput { msg: String },
+    /// Yield-resume payload is too large.
+    YieldPayloadLength { length: u64, limit: u64 },
+    /// Malformed data id passed to `promise_yield_resume`.
+    DataIdMalformed,
+    /// Invalid input to bls12381 family of functions (e.g. point which isn't
+    /// on the curve or in the subgroup).
+    Bls12381InvalidInput { msg: String },
 }
 
 #[derive(
@@ -1326,6 +1394,8 @@ impl From<unc_vm_runner::logic::errors::PrepareError> for PrepareError {
             PE::Memory => Self::Memory,
             PE::TooManyFunctions => Self::TooManyFunctions,
             PE::TooManyLocals => Self::TooManyLocals,
+            PE::TooManyTables => Self::TooManyTables,
+            PE::TooManyTableElements => Self::TooManyTableElements,
         }
     }
 }
@@ -1,12 +1,13 @@
 use crate::hash::CryptoHash;
 use crate::serialize::dec_format;
+use crate::shard_layout::{account_id_to_shard_id, ShardLayout};
 use crate::transaction::{Action, TransferAction};
-use crate::types::{AccountId, Balance, ShardId};
+use crate::types::{AccountId, Balance, BlockHeight, ShardId};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde_with::base64::Base64;
 use serde_with::serde_as;
 use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use unc_crypto::{KeyType, PublicKey};
 use unc_fmt::AbbrBytes;
@@ -49,6 +50,15 @@ impl Receipt {
         self.receipt_id
     }
 
+    /// The shard the receipt is sent to. Global contract distribution receipts name their shard,
+    /// all the other receipts go to the shard of their `receiver_id`.
+    pub fn receiver_shard_id(&self, shard_layout: &ShardLayout) -> ShardId {
+        match &self.receipt {
+            ReceiptEnum::GlobalContractDistribution(distribution) => distribution.target_shard,
+            _ => account_id_to_shard_id(&self.receiver_id, shard_layout),
+        }
+    }
+
     /// Generates a receipt with a transfer from system for a given balance without a receipt_id.
     /// This should be used for token refunds instead of gas refunds. It doesn't refund the
     /// allowance of the access key. For gas refunds use `new_gas_refund`.
@@ -98,6 +108,14 @@ impl Receipt {
 }
 
 /// Receipt could be either ActionReceipt or DataReceipt
+///
+/// `PromiseYield` and `PromiseResume` are the variants of `Action` and `Data`
+/// used to suspend and resume an execution, see `promise_yield_create` and
+/// `promise_yield_resume` host functions. They are always sent by an account to
+/// itself.
+///
+/// `GlobalContractDistribution` carries the code of a newly deployed global contract
+/// to the other shards.
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -111,6 +129,13 @@ impl Receipt {
 pub enum ReceiptEnum {
     Action(ActionReceipt),
     Data(DataReceipt),
+    /// An action receipt awaiting a single input data, which is stored in the
+    /// state until it is resumed or times out.
+    PromiseYield(ActionReceipt),
+    /// The input data resuming a `PromiseYield` receipt.
+    PromiseResume(DataReceipt),
+    /// The code of a global contract, to be stored on the target shard.
+    GlobalContractDistribution(GlobalContractDistributionReceipt),
 }
 
 /// ActionReceipt is derived from an Action from `Transaction or from Receipt`
@@ -172,6 +197,36 @@ impl fmt::Debug for DataReceipt {
     }
 }
 
+/// Sent by the shard where a global contract is deployed to every other shard, so that each
+/// shard keeps a copy of the code and its accounts can use the contract.
+#[serde_as]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Hash,
+    PartialEq,
+    Eq,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct GlobalContractDistributionReceipt {
+    /// The shard which stores the code when applying the receipt.
+    pub target_shard: ShardId,
+    /// WebAssembly binary
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+}
+
+impl fmt::Debug for GlobalContractDistributionReceipt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlobalContractDistributionReceipt")
+            .field("target_shard", &self.target_shard)
+            .field("code", &format_args!("{}", AbbrBytes(&self.code)))
+            .finish()
+    }
+}
+
 /// A temporary data which is created by processing of DataReceipt
 /// stored in a state trie with a key = `account_id` + `data_id` until
 /// `input_data_ids` of all incoming Receipts are satisfied
@@ -204,5 +259,54 @@ impl DelayedReceiptIndices {
     }
 }
 
+/// Stores indices for a persistent queue of the `PromiseYield` timeouts.
+#[derive(Default, BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
+pub struct PromiseYieldIndices {
+    // First inclusive index in the queue.
+    pub first_index: u64,
+    // Exclusive end index of the queue
+    pub next_available_index: u64,
+}
+
+impl PromiseYieldIndices {
+    pub fn len(&self) -> u64 {
+        self.next_available_index - self.first_index
+    }
+}
+
+/// Stores indices for the persistent queues of the outgoing receipts which didn't fit into
+/// the outgoing gas limits of their receiving shards, one queue per receiving shard.
+#[derive(Default, BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
+pub struct BufferedReceiptIndices {
+    pub shard_buffers: BTreeMap<ShardId, ShardBufferIndices>,
+}
+
+/// Stores indices for the persistent queue of the outgoing receipts to a single shard.
+#[derive(Default, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Debug)]
+pub struct ShardBufferIndices {
+    // First inclusive index in the queue.
+    pub first_index: u64,
+    // Exclusive end index of the queue
+    pub next_available_index: u64,
+}
+
+impl ShardBufferIndices {
+    pub fn len(&self) -> u64 {
+        self.next_available_index - self.first_index
+    }
+}
+
+/// Entry of the queue of the `PromiseYield` timeouts. Entries are ordered by
+/// `expires_at`, since all yields of a shard have the same timeout length.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug)]
+pub struct PromiseYieldTimeout {
+    /// The account on which the yielded promise was created.
+    pub account_id: AccountId,
+    /// The `data_id` of the input data awaited by the yielded promise.
+    pub data_id: CryptoHash,
+    /// The block height after which the yielded promise is resumed with an error.
+    pub expires_at: BlockHeight,
+}
+
 /// Map of shard to list of receipts to send to it.
 pub type ReceiptResult = HashMap<ShardId, Vec<Receipt>>;
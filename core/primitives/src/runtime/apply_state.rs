@@ -1,7 +1,8 @@
+use crate::congestion_info::BlockCongestionInfo;
 use crate::runtime::migration_data::{MigrationData, MigrationFlags};
 use crate::{
     hash::CryptoHash,
-    types::{Balance, BlockHeight, EpochHeight, EpochId, Gas},
+    types::{Balance, BlockHeight, EpochHeight, EpochId, Gas, ShardId},
     version::ProtocolVersion,
 };
 use std::sync::Arc;
@@ -16,6 +17,8 @@ pub struct ApplyState {
     pub prev_block_hash: CryptoHash,
     /// Current block hash
     pub block_hash: CryptoHash,
+    /// The shard the chunk is applied for.
+    pub shard_id: ShardId,
     /// Current epoch id
     pub epoch_id: EpochId,
     /// Current epoch height
@@ -42,4 +45,6 @@ pub struct ApplyState {
     pub migration_data: Arc<MigrationData>,
     /// Flags for migrations indicating whether they can be applied at this block
     pub migration_flags: MigrationFlags,
+    /// Congestion info of all shards, as of the block the chunk is applied in.
+    pub congestion_info: BlockCongestionInfo,
 }
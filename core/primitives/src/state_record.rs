@@ -43,6 +43,11 @@ pub enum StateRecord {
     /// Delayed Receipt.
     /// The receipt was delayed because the shard was overwhelmed.
     DelayedReceipt(Box<Receipt>),
+    /// Global contract code encoded in base64, stored by every shard under its hash.
+    GlobalContractCode {
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+    },
 }
 
 impl StateRecord {
@@ -103,6 +108,16 @@ impl StateRecord {
                 let receipt = Receipt::try_from_slice(&value)?;
                 Some(StateRecord::DelayedReceipt(Box::new(receipt)))
             }
+            // Yielded promises await input from a later transaction and are not
+            // carried over into genesis records.
+            col::PROMISE_YIELD_INDICES => None,
+            col::PROMISE_YIELD_TIMEOUT => None,
+            col::PROMISE_YIELD_RECEIPT => None,
+            // Buffered receipts are forwarded to the other shards in the next chunks and
+            // are not carried over into genesis records.
+            col::BUFFERED_RECEIPT_INDICES => None,
+            col::BUFFERED_RECEIPT => None,
+            col::GLOBAL_CONTRACT_CODE => Some(StateRecord::GlobalContractCode { code: value }),
             _ => {
                 println!("key[0]: {} is unreachable", key[0]);
                 None
@@ -119,6 +134,7 @@ impl StateRecord {
             StateRecord::PostponedReceipt { .. } => "PostponedReceipt",
             StateRecord::ReceivedData { .. } => "ReceivedData",
             StateRecord::DelayedReceipt { .. } => "DelayedReceipt",
+            StateRecord::GlobalContractCode { .. } => "GlobalContractCode",
         }
         .to_string()
     }
@@ -152,6 +168,9 @@ impl Display for StateRecord {
             ),
             StateRecord::PostponedReceipt(receipt) => write!(f, "Postponed receipt {:?}", receipt),
             StateRecord::DelayedReceipt(receipt) => write!(f, "Delayed receipt {:?}", receipt),
+            StateRecord::GlobalContractCode { code } => {
+                write!(f, "Global contract code {}: ...", hash(code))
+            }
         }
     }
 }
@@ -171,16 +190,19 @@ fn to_printable(blob: &[u8]) -> String {
     }
 }
 
-pub fn state_record_to_account_id(state_record: &StateRecord) -> &AccountId {
+/// Returns the account the record belongs to. Global contract code doesn't belong to any
+/// account, it's stored by every shard.
+pub fn state_record_to_account_id(state_record: &StateRecord) -> Option<&AccountId> {
     match state_record {
         StateRecord::Account { account_id, .. }
         | StateRecord::AccessKey { account_id, .. }
         | StateRecord::Contract { account_id, .. }
         | StateRecord::ReceivedData { account_id, .. }
-        | StateRecord::Data { account_id, .. } => account_id,
+        | StateRecord::Data { account_id, .. } => Some(account_id),
         StateRecord::PostponedReceipt(receipt) | StateRecord::DelayedReceipt(receipt) => {
-            &receipt.receiver_id
+            Some(&receipt.receiver_id)
         }
+        StateRecord::GlobalContractCode { .. } => None,
     }
 }
 
@@ -211,6 +211,14 @@ impl ShardStateSyncResponse {
         }
     }
 
+    pub fn has_header(&self) -> bool {
+        match self {
+            Self::V1(response) => response.header.is_some(),
+            Self::V2(response) => response.header.is_some(),
+            Self::V3(response) => response.header.is_some(),
+        }
+    }
+
     pub fn part(&self) -> &Option<(u64, Vec<u8>)> {
         match self {
             Self::V1(response) => &response.part,
@@ -13,6 +13,7 @@ use crate::errors::EpochError;
 use crate::hash::CryptoHash;
 use crate::merkle::PartialMerkleTree;
 use crate::num_rational::Ratio;
+use crate::shard_layout::ShardLayout;
 use crate::sharding::{ShardChunkHeader, ShardChunkHeaderV3};
 use crate::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
@@ -36,7 +37,15 @@ impl Transaction {
         nonce: Nonce,
         block_hash: CryptoHash,
     ) -> Self {
-        Self { signer_id, public_key, nonce, receiver_id, block_hash, actions: vec![] }
+        Self {
+            signer_id,
+            public_key,
+            nonce,
+            receiver_id,
+            block_hash,
+            actions: vec![],
+            priority_fee: None,
+        }
     }
 
     pub fn sign(self, signer: &dyn Signer) -> SignedTransaction {
@@ -111,6 +120,7 @@ impl SignedTransaction {
             receiver_id,
             block_hash,
             actions,
+            priority_fee: None,
         }
         .sign(signer)
     }
@@ -491,11 +501,18 @@ impl Block {
 #[derive(Default)]
 pub struct MockEpochInfoProvider {
     pub validators: HashMap<AccountId, (Power, Balance)>,
+    /// Shard layout of every epoch, a single shard if not set.
+    pub shard_layout: Option<ShardLayout>,
 }
 
 impl MockEpochInfoProvider {
     pub fn new(validators: impl Iterator<Item = (AccountId, (Power, Balance))>) -> Self {
-        MockEpochInfoProvider { validators: validators.collect() }
+        MockEpochInfoProvider { validators: validators.collect(), shard_layout: None }
+    }
+
+    pub fn with_shard_layout(mut self, shard_layout: ShardLayout) -> Self {
+        self.shard_layout = Some(shard_layout);
+        self
     }
 }
 
@@ -551,6 +568,10 @@ impl EpochInfoProvider for MockEpochInfoProvider {
     fn minimum_pledge(&self, _prev_block_hash: &CryptoHash) -> Result<Balance, EpochError> {
         Ok(0)
     }
+
+    fn shard_layout(&self, _epoch_id: &EpochId) -> Result<ShardLayout, EpochError> {
+        Ok(self.shard_layout.clone().unwrap_or_else(ShardLayout::v0_single_shard))
+    }
 }
 
 /// Encode array of `u64` to be passed as a smart contract argument.
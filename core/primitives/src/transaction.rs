@@ -1,6 +1,7 @@
 use crate::errors::TxExecutionError;
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::MerklePath;
+use crate::serialize::dec_format;
 use crate::types::{AccountId, Balance, Gas, Nonce};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::de::Error as DecodeError;
@@ -8,6 +9,7 @@ use serde::ser::Error as EncodeError;
 use std::borrow::Borrow;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
 use unc_crypto::{PublicKey, Signature};
 use unc_fmt::{AbbrBytes, Slice};
 use unc_primitives_core::serialize::{from_base64, to_base64};
@@ -16,13 +18,19 @@ use unc_vm_runner::{ProfileDataV2, ProfileDataV3};
 
 pub use crate::action::{
     Action, AddKeyAction, CreateAccountAction, CreateRsa2048ChallengeAction, DeleteAccountAction,
-    DeleteKeyAction, DeployContractAction, FunctionCallAction, PledgeAction,
-    RegisterRsa2048KeysAction, TransferAction,
+    DeleteKeyAction, DeployContractAction, DeployContractWithMigrationAction,
+    DeployGlobalContractAction, FunctionCallAction, PledgeAction, RegisterRsa2048KeysAction,
+    TransferAction, UseGlobalContractAction,
 };
 
 pub type LogEntry = String;
 
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, PartialEq, Eq, Debug, Clone)]
+/// Transactions with a priority fee are encoded with this prefix, followed by the fields of a
+/// transaction without one and then the fee. A transaction without a priority fee starts with the
+/// length of `signer_id`, which is never below 2, so the two encodings can't be confused.
+const TRANSACTION_V1_PREFIX: u8 = 1;
+
+#[derive(serde::Serialize, PartialEq, Eq, Debug, Clone)]
 pub struct Transaction {
     /// An account on which behalf transaction is signed
     pub signer_id: AccountId,
@@ -38,6 +46,10 @@ pub struct Transaction {
     pub block_hash: CryptoHash,
     /// A list of actions to be applied
     pub actions: Vec<Action>,
+    /// An optional fee paid on top of the regular cost of the transaction to get it included
+    /// ahead of others. Only allowed with `ProtocolFeature::PriorityFees`.
+    #[serde(with = "dec_format", skip_serializing_if = "Option::is_none")]
+    pub priority_fee: Option<Balance>,
 }
 
 impl Transaction {
@@ -46,6 +58,55 @@ impl Transaction {
         let bytes = borsh::to_vec(&self).expect("Failed to deserialize");
         (hash(&bytes), bytes.len() as u64)
     }
+
+    /// Returns the priority fee offered by the transaction, zero if it doesn't set one.
+    pub fn tip(&self) -> Balance {
+        self.priority_fee.unwrap_or(0)
+    }
+
+    fn deserialize_v0_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            signer_id: BorshDeserialize::deserialize_reader(reader)?,
+            public_key: BorshDeserialize::deserialize_reader(reader)?,
+            nonce: BorshDeserialize::deserialize_reader(reader)?,
+            receiver_id: BorshDeserialize::deserialize_reader(reader)?,
+            block_hash: BorshDeserialize::deserialize_reader(reader)?,
+            actions: BorshDeserialize::deserialize_reader(reader)?,
+            priority_fee: None,
+        })
+    }
+}
+
+impl BorshSerialize for Transaction {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if self.priority_fee.is_some() {
+            TRANSACTION_V1_PREFIX.serialize(writer)?;
+        }
+        self.signer_id.serialize(writer)?;
+        self.public_key.serialize(writer)?;
+        self.nonce.serialize(writer)?;
+        self.receiver_id.serialize(writer)?;
+        self.block_hash.serialize(writer)?;
+        self.actions.serialize(writer)?;
+        if let Some(priority_fee) = self.priority_fee {
+            priority_fee.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for Transaction {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let prefix = u8::deserialize_reader(reader)?;
+        if prefix == TRANSACTION_V1_PREFIX {
+            let mut transaction = Self::deserialize_v0_reader(reader)?;
+            transaction.priority_fee = Some(Balance::deserialize_reader(reader)?);
+            Ok(transaction)
+        } else {
+            // There is no prefix, the byte we've read is the start of `signer_id`.
+            Self::deserialize_v0_reader(&mut [prefix].as_slice().chain(reader))
+        }
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Eq, Debug, Clone)]
@@ -325,6 +386,7 @@ mod tests {
             receiver_id: "test".parse().unwrap(),
             block_hash: Default::default(),
             actions: vec![],
+            priority_fee: None,
         }
         .sign(&signer);
         let wrong_public_key = PublicKey::from_seed(KeyType::ED25519, "wrong");
@@ -380,6 +442,7 @@ mod tests {
                     beneficiary_id: "123".parse().unwrap(),
                 }),
             ],
+            priority_fee: None,
         };
         let signed_tx = SignedTransaction::new(Signature::empty(KeyType::ED25519), transaction);
         let new_signed_tx =
@@ -391,6 +454,38 @@ mod tests {
         );
     }
 
+    /// Setting a priority fee switches to the prefixed encoding, which must round-trip and must not
+    /// be mistaken for a transaction without a fee.
+    #[test]
+    fn test_serialize_transaction_with_priority_fee() {
+        let signer =
+            InMemorySigner::from_seed("test.unc".parse().unwrap(), KeyType::ED25519, "test");
+        let transaction = Transaction {
+            signer_id: "test.unc".parse().unwrap(),
+            public_key: signer.public_key(),
+            nonce: 1,
+            receiver_id: "123".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![Action::Transfer(TransferAction { deposit: 123 })],
+            priority_fee: None,
+        };
+        let transaction_with_fee = Transaction { priority_fee: Some(1_000), ..transaction.clone() };
+
+        let bytes = borsh::to_vec(&transaction).unwrap();
+        let bytes_with_fee = borsh::to_vec(&transaction_with_fee).unwrap();
+        assert_eq!(bytes_with_fee[0], TRANSACTION_V1_PREFIX);
+        assert_eq!(&bytes_with_fee[1..bytes.len() + 1], &bytes[..]);
+        assert_eq!(Transaction::try_from_slice(&bytes).unwrap(), transaction);
+        assert_eq!(Transaction::try_from_slice(&bytes_with_fee).unwrap(), transaction_with_fee);
+        assert_ne!(transaction.get_hash_and_size().0, transaction_with_fee.get_hash_and_size().0);
+
+        let signed_tx = transaction_with_fee.sign(&signer);
+        let decoded_tx =
+            SignedTransaction::try_from_slice(&borsh::to_vec(&signed_tx).unwrap()).unwrap();
+        assert_eq!(decoded_tx.transaction.tip(), 1_000);
+        assert!(verify_transaction_signature(&decoded_tx, &[signer.public_key()]));
+    }
+
     #[test]
     fn test_outcome_to_hashes() {
         let outcome = ExecutionOutcome {
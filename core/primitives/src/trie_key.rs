@@ -5,7 +5,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use unc_crypto::PublicKey;
 
 use crate::hash::CryptoHash;
-use crate::types::AccountId;
+use crate::types::{AccountId, ShardId};
 
 pub(crate) const ACCOUNT_DATA_SEPARATOR: u8 = b',';
 // The use of `ACCESS_KEY` as a separator is a historical artefact.
@@ -47,8 +47,26 @@ pub mod col {
     pub const CONTRACT_DATA: u8 = 9;
 
     pub const RSA2048_KEY: u8 = 10;
+    /// This column id is used when storing the indices of the PromiseYield timeout queue
+    /// (a singleton per shard).
+    pub const PROMISE_YIELD_INDICES: u8 = 11;
+    /// This column id is used when storing the PromiseYield timeout queue entries
+    /// (`primitives::receipt::PromiseYieldTimeout`).
+    pub const PROMISE_YIELD_TIMEOUT: u8 = 12;
+    /// This column id is used when storing the postponed PromiseYield receipts
+    /// (`primitives::receipt::Receipt`).
+    pub const PROMISE_YIELD_RECEIPT: u8 = 13;
+    /// This column id is used when storing the indices of the outgoing receipts buffers
+    /// (a singleton per shard).
+    pub const BUFFERED_RECEIPT_INDICES: u8 = 14;
+    /// This column id is used when storing the receipts in the outgoing receipts buffers
+    /// (`primitives::receipt::Receipt`).
+    pub const BUFFERED_RECEIPT: u8 = 15;
+    /// This column id is used when storing the code of global contracts, keyed by the code
+    /// hash (`primitives::contract::ContractCode`).
+    pub const GLOBAL_CONTRACT_CODE: u8 = 16;
     /// All columns
-    pub const NON_DELAYED_RECEIPT_COLUMNS: [(u8, &str); 8] = [
+    pub const NON_DELAYED_RECEIPT_COLUMNS: [(u8, &str); 9] = [
         (ACCOUNT, "Account"),
         (CONTRACT_CODE, "ContractCode"),
         (ACCESS_KEY, "AccessKey"),
@@ -57,6 +75,7 @@ pub mod col {
         (PENDING_DATA_COUNT, "PendingDataCount"),
         (POSTPONED_RECEIPT, "PostponedReceipt"),
         (CONTRACT_DATA, "ContractData"),
+        (PROMISE_YIELD_RECEIPT, "PromiseYieldReceipt"),
     ];
 }
 
@@ -98,6 +117,26 @@ pub enum TrieKey {
 
     ///ca rsakeys
     Rsa2048Keys { account_id: AccountId, public_key: PublicKey },
+    /// Used to store indices of the PromiseYield timeout queue
+    /// (`primitives::receipt::PromiseYieldIndices`).
+    /// NOTE: It is a singleton per shard.
+    PromiseYieldIndices,
+    /// Used to store a PromiseYield timeout `primitives::receipt::PromiseYieldTimeout` for a
+    /// given index `u64` in the timeout queue. The queue is unique per shard.
+    PromiseYieldTimeout { index: u64 },
+    /// Used to store the postponed PromiseYield receipt `primitives::receipt::Receipt` for a
+    /// given receiver's `AccountId` and the `data_id` of the input data it awaits.
+    PromiseYieldReceipt { receiver_id: AccountId, data_id: CryptoHash },
+    /// Used to store indices of the outgoing receipts buffers
+    /// (`primitives::receipt::BufferedReceiptIndices`).
+    /// NOTE: It is a singleton per shard.
+    BufferedReceiptIndices,
+    /// Used to store a receipt `primitives::receipt::Receipt` for a given index `u64` in the
+    /// buffer of the receipts waiting to be forwarded to the `receiving_shard`.
+    BufferedReceipt { receiving_shard: ShardId, index: u64 },
+    /// Used to store `Vec<u8>` contract code of a global contract for a given code hash.
+    /// NOTE: The code is shared by all accounts which use this global contract.
+    GlobalContractCode { code_hash: CryptoHash },
 }
 
 /// Provides `len` function.
@@ -160,6 +199,23 @@ impl TrieKey {
             TrieKey::Rsa2048Keys { account_id, public_key } => {
                 col::RSA2048_KEY.len() * 2 + account_id.len() + public_key.len()
             }
+            TrieKey::PromiseYieldIndices => col::PROMISE_YIELD_INDICES.len(),
+            TrieKey::PromiseYieldTimeout { .. } => {
+                col::PROMISE_YIELD_TIMEOUT.len() + size_of::<u64>()
+            }
+            TrieKey::PromiseYieldReceipt { receiver_id, data_id } => {
+                col::PROMISE_YIELD_RECEIPT.len()
+                    + receiver_id.len()
+                    + ACCOUNT_DATA_SEPARATOR.len()
+                    + data_id.as_ref().len()
+            }
+            TrieKey::BufferedReceiptIndices => col::BUFFERED_RECEIPT_INDICES.len(),
+            TrieKey::BufferedReceipt { .. } => {
+                col::BUFFERED_RECEIPT.len() + size_of::<ShardId>() + size_of::<u64>()
+            }
+            TrieKey::GlobalContractCode { code_hash } => {
+                col::GLOBAL_CONTRACT_CODE.len() + code_hash.as_ref().len()
+            }
         }
     }
 
@@ -225,6 +281,31 @@ impl TrieKey {
                 buf.push(RSA2048_KEY_SEPARATOR);
                 buf.extend(borsh::to_vec(&public_key).unwrap());
             }
+            TrieKey::PromiseYieldIndices => {
+                buf.push(col::PROMISE_YIELD_INDICES);
+            }
+            TrieKey::PromiseYieldTimeout { index } => {
+                buf.push(col::PROMISE_YIELD_TIMEOUT);
+                buf.extend(&index.to_le_bytes());
+            }
+            TrieKey::PromiseYieldReceipt { receiver_id, data_id } => {
+                buf.push(col::PROMISE_YIELD_RECEIPT);
+                buf.extend(receiver_id.as_bytes());
+                buf.push(ACCOUNT_DATA_SEPARATOR);
+                buf.extend(data_id.as_ref());
+            }
+            TrieKey::BufferedReceiptIndices => {
+                buf.push(col::BUFFERED_RECEIPT_INDICES);
+            }
+            TrieKey::BufferedReceipt { receiving_shard, index } => {
+                buf.push(col::BUFFERED_RECEIPT);
+                buf.extend(&receiving_shard.to_le_bytes());
+                buf.extend(&index.to_le_bytes());
+            }
+            TrieKey::GlobalContractCode { code_hash } => {
+                buf.push(col::GLOBAL_CONTRACT_CODE);
+                buf.extend(code_hash.as_ref());
+            }
         };
         debug_assert_eq!(expected_len, buf.len() - start_len);
     }
@@ -249,6 +330,12 @@ impl TrieKey {
             TrieKey::DelayedReceipt { .. } => None,
             TrieKey::ContractData { account_id, .. } => Some(account_id.clone()),
             TrieKey::Rsa2048Keys { account_id, .. } => Some(account_id.clone()),
+            TrieKey::PromiseYieldIndices => None,
+            TrieKey::PromiseYieldTimeout { .. } => None,
+            TrieKey::PromiseYieldReceipt { receiver_id, .. } => Some(receiver_id.clone()),
+            TrieKey::BufferedReceiptIndices => None,
+            TrieKey::BufferedReceipt { .. } => None,
+            TrieKey::GlobalContractCode { .. } => None,
         }
     }
 }
@@ -706,6 +793,52 @@ mod tests {
         assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
     }
 
+    #[test]
+    fn test_key_for_promise_yield_consistency() {
+        let key = TrieKey::PromiseYieldIndices;
+        let raw_key = key.to_vec();
+        assert_eq!(raw_key.len(), key.len());
+        assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        let key = TrieKey::PromiseYieldTimeout { index: 0 };
+        let raw_key = key.to_vec();
+        assert_eq!(raw_key.len(), key.len());
+        assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        for account_id in OK_ACCOUNT_IDS.iter().map(|x| x.parse::<AccountId>().unwrap()) {
+            let key = TrieKey::PromiseYieldReceipt {
+                receiver_id: account_id.clone(),
+                data_id: CryptoHash::default(),
+            };
+            let raw_key = key.to_vec();
+            assert_eq!(raw_key.len(), key.len());
+            assert_eq!(
+                trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().unwrap(),
+                account_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_for_buffered_receipts_consistency() {
+        let key = TrieKey::BufferedReceiptIndices;
+        let raw_key = key.to_vec();
+        assert_eq!(raw_key.len(), key.len());
+        assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        let key = TrieKey::BufferedReceipt { receiving_shard: 3, index: 0 };
+        let raw_key = key.to_vec();
+        assert_eq!(raw_key.len(), key.len());
+        assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_key_for_global_contract_code_consistency() {
+        let key = TrieKey::GlobalContractCode { code_hash: CryptoHash::hash_bytes(b"code") };
+        let raw_key = key.to_vec();
+        assert_eq!(raw_key.len(), key.len());
+        assert_eq!(raw_key[0], col::GLOBAL_CONTRACT_CODE);
+        assert!(trie_key_parsers::parse_account_id_from_raw_key(&raw_key).unwrap().is_none());
+        assert_eq!(key.get_account_id(), None);
+    }
+
     #[test]
     fn test_account_id_from_trie_key() {
         for account_id_str in OK_ACCOUNT_IDS {
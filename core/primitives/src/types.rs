@@ -5,6 +5,7 @@ use crate::errors::EpochError;
 pub use crate::hash::CryptoHash;
 use crate::receipt::Receipt;
 use crate::serialize::dec_format;
+use crate::shard_layout::ShardLayout;
 use crate::trie_key::TrieKey;
 use borsh::{BorshDeserialize, BorshSerialize};
 use once_cell::sync::Lazy;
@@ -413,6 +414,12 @@ impl StateChanges {
                 TrieKey::PostponedReceipt { .. } => {}
                 TrieKey::DelayedReceiptIndices => {}
                 TrieKey::DelayedReceipt { .. } => {}
+                TrieKey::PromiseYieldIndices => {}
+                TrieKey::PromiseYieldTimeout { .. } => {}
+                TrieKey::PromiseYieldReceipt { .. } => {}
+                TrieKey::BufferedReceiptIndices => {}
+                TrieKey::BufferedReceipt { .. } => {}
+                TrieKey::GlobalContractCode { .. } => {}
                 TrieKey::Rsa2048Keys { account_id, public_key } => {
                     state_changes.extend(changes.into_iter().map(
                         |RawStateChange { cause, data }| StateChangeWithCause {
@@ -1153,6 +1160,7 @@ pub struct BlockExtra {
 }
 
 pub mod chunk_extra {
+    use crate::congestion_info::CongestionInfo;
     use crate::types::validator_power::{ValidatorPower, ValidatorPowerIter};
     use crate::types::validator_stake::{ValidatorPledge, ValidatorPledgeIter};
     use crate::types::StateRoot;
@@ -1167,6 +1175,7 @@ pub mod chunk_extra {
     pub enum ChunkExtra {
         V1(ChunkExtraV1),
         V2(ChunkExtraV2),
+        V3(ChunkExtraV3),
     }
 
     #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize, Clone, Eq)]
@@ -1187,9 +1196,30 @@ pub mod chunk_extra {
         pub balance_burnt: Balance,
     }
 
+    /// V2 -> V3: Add congestion info.
+    #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize, Clone, Eq)]
+    pub struct ChunkExtraV3 {
+        /// Post state root after applying give chunk.
+        pub state_root: StateRoot,
+        /// Root of merklizing results of receipts (transactions) execution.
+        pub outcome_root: CryptoHash,
+        /// Validator proposals produced by given chunk.
+        pub validator_power_proposals: Vec<ValidatorPower>,
+        /// Validator proposals produced by given chunk.
+        pub validator_pledge_proposals: Vec<ValidatorPledge>,
+        /// Actually how much gas were used.
+        pub gas_used: Gas,
+        /// Gas limit, allows to increase or decrease limit based on expected time vs real time for computing the chunk.
+        pub gas_limit: Gas,
+        /// Total balance burnt after processing the current chunk.
+        pub balance_burnt: Balance,
+        /// Congestion info of the shard after processing the current chunk.
+        pub congestion_info: CongestionInfo,
+    }
+
     impl ChunkExtra {
         pub fn new_with_only_state_root(state_root: &StateRoot) -> Self {
-            Self::new(state_root, CryptoHash::default(), vec![], vec![], 0, 0, 0)
+            Self::new(state_root, CryptoHash::default(), vec![], vec![], 0, 0, 0, None)
         }
 
         pub fn new(
@@ -1200,16 +1230,30 @@ pub mod chunk_extra {
             gas_used: Gas,
             gas_limit: Gas,
             balance_burnt: Balance,
+            congestion_info: Option<CongestionInfo>,
         ) -> Self {
-            Self::V2(ChunkExtraV2 {
-                state_root: *state_root,
-                outcome_root,
-                validator_power_proposals,
-                validator_pledge_proposals,
-                gas_used,
-                gas_limit,
-                balance_burnt,
-            })
+            if let Some(congestion_info) = congestion_info {
+                Self::V3(ChunkExtraV3 {
+                    state_root: *state_root,
+                    outcome_root,
+                    validator_power_proposals,
+                    validator_pledge_proposals,
+                    gas_used,
+                    gas_limit,
+                    balance_burnt,
+                    congestion_info,
+                })
+            } else {
+                Self::V2(ChunkExtraV2 {
+                    state_root: *state_root,
+                    outcome_root,
+                    validator_power_proposals,
+                    validator_pledge_proposals,
+                    gas_used,
+                    gas_limit,
+                    balance_burnt,
+                })
+            }
         }
 
         #[inline]
@@ -1217,6 +1261,7 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => &v1.outcome_root,
                 Self::V2(v2) => &v2.outcome_root,
+                Self::V3(v3) => &v3.outcome_root,
             }
         }
 
@@ -1225,6 +1270,7 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => &v1.state_root,
                 Self::V2(v2) => &v2.state_root,
+                Self::V3(v3) => &v3.state_root,
             }
         }
 
@@ -1233,6 +1279,7 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => &mut v1.state_root,
                 Self::V2(v2) => &mut v2.state_root,
+                Self::V3(v3) => &mut v3.state_root,
             }
         }
 
@@ -1241,6 +1288,7 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => ValidatorPowerIter::v1(&v1.validator_power_proposals),
                 Self::V2(v2) => ValidatorPowerIter::new(&v2.validator_power_proposals),
+                Self::V3(v3) => ValidatorPowerIter::new(&v3.validator_power_proposals),
             }
         }
 
@@ -1249,6 +1297,7 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => ValidatorPledgeIter::v1(&v1.validator_pledge_proposals),
                 Self::V2(v2) => ValidatorPledgeIter::new(&v2.validator_pledge_proposals),
+                Self::V3(v3) => ValidatorPledgeIter::new(&v3.validator_pledge_proposals),
             }
         }
 
@@ -1257,6 +1306,7 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => v1.gas_limit,
                 Self::V2(v2) => v2.gas_limit,
+                Self::V3(v3) => v3.gas_limit,
             }
         }
 
@@ -1265,6 +1315,7 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => v1.gas_used,
                 Self::V2(v2) => v2.gas_used,
+                Self::V3(v3) => v3.gas_used,
             }
         }
 
@@ -1273,6 +1324,15 @@ pub mod chunk_extra {
             match self {
                 Self::V1(v1) => v1.balance_burnt,
                 Self::V2(v2) => v2.balance_burnt,
+                Self::V3(v3) => v3.balance_burnt,
+            }
+        }
+
+        #[inline]
+        pub fn congestion_info(&self) -> Option<CongestionInfo> {
+            match self {
+                Self::V1(_) | Self::V2(_) => None,
+                Self::V3(v3) => Some(v3.congestion_info),
             }
         }
     }
@@ -1435,7 +1495,16 @@ pub enum ValidatorKickoutReason {
     DidNotGetASeat,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TransactionOrReceiptId {
     Transaction { transaction_hash: CryptoHash, sender_id: AccountId },
@@ -1480,6 +1549,9 @@ pub trait EpochInfoProvider {
     ) -> Result<Balance, EpochError>;
 
     fn minimum_pledge(&self, prev_block_hash: &CryptoHash) -> Result<Balance, EpochError>;
+
+    /// Get the shard layout of the given epoch.
+    fn shard_layout(&self, epoch_id: &EpochId) -> Result<ShardLayout, EpochError>;
 }
 
 /// Mode of the trie cache.
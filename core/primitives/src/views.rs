@@ -13,15 +13,19 @@ use crate::block_header::{
 use crate::block_header::{BlockHeaderInnerRestV4, BlockHeaderV4};
 use crate::challenge::{Challenge, ChallengesResult};
 use crate::checked_feature;
+use crate::congestion_info::{CongestionInfo, CongestionInfoV1};
 use crate::errors::TxExecutionError;
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::{combine_hash, MerklePath};
 use crate::network::PeerId;
-use crate::receipt::{ActionReceipt, DataReceipt, DataReceiver, Receipt, ReceiptEnum};
+use crate::receipt::{
+    ActionReceipt, DataReceipt, DataReceiver, GlobalContractDistributionReceipt, Receipt,
+    ReceiptEnum,
+};
 use crate::serialize::dec_format;
 use crate::sharding::{
     ChunkHash, ShardChunk, ShardChunkHeader, ShardChunkHeaderInner, ShardChunkHeaderInnerV2,
-    ShardChunkHeaderV3,
+    ShardChunkHeaderInnerV3, ShardChunkHeaderV3,
 };
 use crate::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
@@ -36,7 +40,10 @@ use crate::types::{
     ValidatorKickoutReason,
 };
 
-use crate::action::{CreateRsa2048ChallengeAction, RegisterRsa2048KeysAction};
+use crate::action::{
+    CreateRsa2048ChallengeAction, DeployContractWithMigrationAction, DeployGlobalContractAction,
+    RegisterRsa2048KeysAction, UseGlobalContractAction,
+};
 use crate::types::validator_power_and_pledge::{
     ValidatorPowerAndPledge, ValidatorPowerAndPledgeIter,
 };
@@ -487,6 +494,22 @@ pub struct RecentOutboundConnectionsView {
     pub recent_outbound_connections: Vec<ConnectionInfoView>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone)]
+pub struct PeerReputationView {
+    pub peer_id: PeerId,
+    pub score: f64,
+    pub avg_latency_millis: Option<i64>,
+    pub useful_bytes: f64,
+    pub invalid_data: f64,
+    pub timeouts: f64,
+    pub demoted: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+pub struct PeerReputationsView {
+    pub peers: Vec<PeerReputationView>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
 pub struct SnapshotHostsView {
     pub hosts: Vec<SnapshotHostInfoView>,
@@ -530,6 +553,33 @@ pub struct NetworkRoutesView {
     pub local_edges: HashMap<PeerId, EdgeView>,
     pub peer_distances: HashMap<PeerId, PeerDistancesView>,
     pub my_distances: HashMap<PeerId, u32>,
+    /// Most recent routed messages traced by this node, from the newest to the oldest.
+    #[serde(default)]
+    pub routed_message_traces: Vec<RoutedMessageTraceView>,
+    /// Routes to the reachable peers as computed by both routing protocols.
+    #[serde(default)]
+    pub route_comparison: Vec<RouteComparisonView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RoutedMessageTraceView {
+    pub msg_hash: CryptoHash,
+    pub body_type: String,
+    pub target: PeerId,
+    /// Unix timestamp of sending the message.
+    pub sent_at: i64,
+    /// Peers the message travelled through. None if no receipt arrived in time.
+    pub hops: Option<Vec<PeerId>>,
+    pub round_trip_millis: Option<i64>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct RouteComparisonView {
+    pub peer_id: PeerId,
+    pub v1_distance: Option<u32>,
+    pub v2_distance: Option<u32>,
+    pub v1_next_hops: Vec<PeerId>,
+    pub v2_next_hops: Vec<PeerId>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
@@ -1121,9 +1171,45 @@ pub struct ChunkHeaderView {
     pub tx_root: CryptoHash,
     pub validator_power_proposals: Vec<ValidatorPowerView>,
     pub validator_pledge_proposals: Vec<ValidatorPledgeView>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub congestion_info: Option<CongestionInfoView>,
     pub signature: Signature,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CongestionInfoView {
+    #[serde(with = "dec_format")]
+    pub delayed_receipts_gas: u128,
+    #[serde(with = "dec_format")]
+    pub buffered_receipts_gas: u128,
+    pub receipt_bytes: u64,
+    pub allowed_shard: u16,
+}
+
+impl From<CongestionInfo> for CongestionInfoView {
+    fn from(congestion_info: CongestionInfo) -> Self {
+        match congestion_info {
+            CongestionInfo::V1(info) => Self {
+                delayed_receipts_gas: info.delayed_receipts_gas,
+                buffered_receipts_gas: info.buffered_receipts_gas,
+                receipt_bytes: info.receipt_bytes,
+                allowed_shard: info.allowed_shard,
+            },
+        }
+    }
+}
+
+impl From<CongestionInfoView> for CongestionInfo {
+    fn from(view: CongestionInfoView) -> Self {
+        CongestionInfo::V1(CongestionInfoV1 {
+            delayed_receipts_gas: view.delayed_receipts_gas,
+            buffered_receipts_gas: view.buffered_receipts_gas,
+            receipt_bytes: view.receipt_bytes,
+            allowed_shard: view.allowed_shard,
+        })
+    }
+}
+
 impl From<ShardChunkHeader> for ChunkHeaderView {
     fn from(chunk: ShardChunkHeader) -> Self {
         let hash = chunk.chunk_hash();
@@ -1155,6 +1241,7 @@ impl From<ShardChunkHeader> for ChunkHeaderView {
                 .prev_validator_pledge_proposals()
                 .map(Into::into)
                 .collect(),
+            congestion_info: inner.congestion_info().map(Into::into),
             signature,
         }
     }
@@ -1162,8 +1249,33 @@ impl From<ShardChunkHeader> for ChunkHeaderView {
 
 impl From<ChunkHeaderView> for ShardChunkHeader {
     fn from(view: ChunkHeaderView) -> Self {
-        let mut header = ShardChunkHeaderV3 {
-            inner: ShardChunkHeaderInner::V2(ShardChunkHeaderInnerV2 {
+        let inner = match view.congestion_info {
+            Some(congestion_info) => ShardChunkHeaderInner::V3(ShardChunkHeaderInnerV3 {
+                prev_block_hash: view.prev_block_hash,
+                prev_state_root: view.prev_state_root,
+                prev_outcome_root: view.outcome_root,
+                encoded_merkle_root: view.encoded_merkle_root,
+                encoded_length: view.encoded_length,
+                height_created: view.height_created,
+                shard_id: view.shard_id,
+                prev_gas_used: view.gas_used,
+                gas_limit: view.gas_limit,
+                prev_balance_burnt: view.balance_burnt,
+                prev_outgoing_receipts_root: view.outgoing_receipts_root,
+                tx_root: view.tx_root,
+                prev_validator_power_proposals: view
+                    .validator_power_proposals
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+                prev_validator_pledge_proposals: view
+                    .validator_pledge_proposals
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+                congestion_info: congestion_info.into(),
+            }),
+            None => ShardChunkHeaderInner::V2(ShardChunkHeaderInnerV2 {
                 prev_block_hash: view.prev_block_hash,
                 prev_state_root: view.prev_state_root,
                 prev_outcome_root: view.outcome_root,
@@ -1187,6 +1299,9 @@ impl From<ChunkHeaderView> for ShardChunkHeader {
                     .map(Into::into)
                     .collect(),
             }),
+        };
+        let mut header = ShardChunkHeaderV3 {
+            inner,
             height_included: view.height_included,
             signature: view.signature,
             hash: ChunkHash::default(),
@@ -1332,6 +1447,20 @@ pub enum ActionView {
         #[serde_as(as = "Base64")]
         args: Vec<u8>,
     },
+    DeployGlobalContract {
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+    },
+    UseGlobalContract {
+        code_hash: CryptoHash,
+    },
+    DeployContractWithMigration {
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+        migrate_method_name: String,
+        migrate_args: FunctionArgs,
+        gas: Gas,
+    },
 }
 
 impl From<Action> for ActionView {
@@ -1374,6 +1503,22 @@ impl From<Action> for ActionView {
                 challenge_key: action.challenge_key,
                 args: action.args.into(),
             },
+            Action::DeployGlobalContract(action) => {
+                let code = hash(&action.code).as_ref().to_vec();
+                ActionView::DeployGlobalContract { code }
+            }
+            Action::UseGlobalContract(action) => {
+                ActionView::UseGlobalContract { code_hash: action.code_hash }
+            }
+            Action::DeployContractWithMigration(action) => {
+                let code = hash(&action.code).as_ref().to_vec();
+                ActionView::DeployContractWithMigration {
+                    code,
+                    migrate_method_name: action.migrate_method_name,
+                    migrate_args: action.migrate_args.into(),
+                    gas: action.gas,
+                }
+            }
         }
     }
 }
@@ -1425,6 +1570,23 @@ impl TryFrom<ActionView> for Action {
                     args: args.into(),
                 }))
             }
+            ActionView::DeployGlobalContract { code } => {
+                Action::DeployGlobalContract(DeployGlobalContractAction { code })
+            }
+            ActionView::UseGlobalContract { code_hash } => {
+                Action::UseGlobalContract(Box::new(UseGlobalContractAction { code_hash }))
+            }
+            ActionView::DeployContractWithMigration {
+                code,
+                migrate_method_name,
+                migrate_args,
+                gas,
+            } => Action::DeployContractWithMigration(Box::new(DeployContractWithMigrationAction {
+                code,
+                migrate_method_name,
+                migrate_args: migrate_args.into(),
+                gas,
+            })),
         })
     }
 }
@@ -1447,6 +1609,8 @@ pub struct SignedTransactionView {
     pub actions: Vec<ActionView>,
     pub signature: Signature,
     pub hash: CryptoHash,
+    #[serde(default, with = "dec_format", skip_serializing_if = "Option::is_none")]
+    pub priority_fee: Option<Balance>,
 }
 
 impl From<SignedTransaction> for SignedTransactionView {
@@ -1465,6 +1629,7 @@ impl From<SignedTransaction> for SignedTransactionView {
                 .collect(),
             signature: signed_tx.signature,
             hash,
+            priority_fee: signed_tx.transaction.priority_fee,
         }
     }
 }
@@ -2221,42 +2386,74 @@ pub enum ReceiptEnumView {
         output_data_receivers: Vec<DataReceiverView>,
         input_data_ids: Vec<CryptoHash>,
         actions: Vec<ActionView>,
+        #[serde(default = "default_is_promise")]
+        is_promise_yield: bool,
     },
     Data {
         data_id: CryptoHash,
         #[serde_as(as = "Option<Base64>")]
         data: Option<Vec<u8>>,
+        #[serde(default = "default_is_promise")]
+        is_promise_resume: bool,
+    },
+    GlobalContractDistribution {
+        target_shard: ShardId,
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
     },
 }
 
+// Default value used when deserializing ReceiptEnumViews which are missing either the
+// `is_promise_yield` or `is_promise_resume` fields. Data which is missing this field was
+// serialized before the introduction of yield execution.
+fn default_is_promise() -> bool {
+    false
+}
+
 impl From<Receipt> for ReceiptView {
     fn from(receipt: Receipt) -> Self {
+        let is_promise_yield = matches!(&receipt.receipt, ReceiptEnum::PromiseYield(_));
+        let is_promise_resume = matches!(&receipt.receipt, ReceiptEnum::PromiseResume(_));
+
         ReceiptView {
             predecessor_id: receipt.predecessor_id,
             receiver_id: receipt.receiver_id,
             receipt_id: receipt.receipt_id,
             receipt: match receipt.receipt {
-                ReceiptEnum::Action(action_receipt) => ReceiptEnumView::Action {
-                    signer_id: action_receipt.signer_id,
-                    signer_public_key: action_receipt.signer_public_key,
-                    gas_price: action_receipt.gas_price,
-                    output_data_receivers: action_receipt
-                        .output_data_receivers
-                        .into_iter()
-                        .map(|data_receiver| DataReceiverView {
-                            data_id: data_receiver.data_id,
-                            receiver_id: data_receiver.receiver_id,
-                        })
-                        .collect(),
-                    input_data_ids: action_receipt
-                        .input_data_ids
-                        .into_iter()
-                        .map(Into::into)
-                        .collect(),
-                    actions: action_receipt.actions.into_iter().map(Into::into).collect(),
-                },
-                ReceiptEnum::Data(data_receipt) => {
-                    ReceiptEnumView::Data { data_id: data_receipt.data_id, data: data_receipt.data }
+                ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
+                    ReceiptEnumView::Action {
+                        signer_id: action_receipt.signer_id,
+                        signer_public_key: action_receipt.signer_public_key,
+                        gas_price: action_receipt.gas_price,
+                        output_data_receivers: action_receipt
+                            .output_data_receivers
+                            .into_iter()
+                            .map(|data_receiver| DataReceiverView {
+                                data_id: data_receiver.data_id,
+                                receiver_id: data_receiver.receiver_id,
+                            })
+                            .collect(),
+                        input_data_ids: action_receipt
+                            .input_data_ids
+                            .into_iter()
+                            .map(Into::into)
+                            .collect(),
+                        actions: action_receipt.actions.into_iter().map(Into::into).collect(),
+                        is_promise_yield,
+                    }
+                }
+                ReceiptEnum::Data(data_receipt) | ReceiptEnum::PromiseResume(data_receipt) => {
+                    ReceiptEnumView::Data {
+                        data_id: data_receipt.data_id,
+                        data: data_receipt.data,
+                        is_promise_resume,
+                    }
+                }
+                ReceiptEnum::GlobalContractDistribution(distribution) => {
+                    ReceiptEnumView::GlobalContractDistribution {
+                        target_shard: distribution.target_shard,
+                        code: distribution.code,
+                    }
                 }
             },
         }
@@ -2279,31 +2476,75 @@ impl TryFrom<ReceiptView> for Receipt {
                     output_data_receivers,
                     input_data_ids,
                     actions,
-                } => ReceiptEnum::Action(ActionReceipt {
-                    signer_id,
-                    signer_public_key,
-                    gas_price,
-                    output_data_receivers: output_data_receivers
-                        .into_iter()
-                        .map(|data_receiver_view| DataReceiver {
-                            data_id: data_receiver_view.data_id,
-                            receiver_id: data_receiver_view.receiver_id,
-                        })
-                        .collect(),
-                    input_data_ids: input_data_ids.into_iter().map(Into::into).collect(),
-                    actions: actions
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<Result<Vec<_>, _>>()?,
-                }),
-                ReceiptEnumView::Data { data_id, data } => {
-                    ReceiptEnum::Data(DataReceipt { data_id, data })
+                    is_promise_yield,
+                } => {
+                    let action_receipt = ActionReceipt {
+                        signer_id,
+                        signer_public_key,
+                        gas_price,
+                        output_data_receivers: output_data_receivers
+                            .into_iter()
+                            .map(|data_receiver_view| DataReceiver {
+                                data_id: data_receiver_view.data_id,
+                                receiver_id: data_receiver_view.receiver_id,
+                            })
+                            .collect(),
+                        input_data_ids: input_data_ids.into_iter().map(Into::into).collect(),
+                        actions: actions
+                            .into_iter()
+                            .map(TryInto::try_into)
+                            .collect::<Result<Vec<_>, _>>()?,
+                    };
+
+                    if is_promise_yield {
+                        ReceiptEnum::PromiseYield(action_receipt)
+                    } else {
+                        ReceiptEnum::Action(action_receipt)
+                    }
+                }
+                ReceiptEnumView::Data { data_id, data, is_promise_resume } => {
+                    let data_receipt = DataReceipt { data_id, data };
+
+                    if is_promise_resume {
+                        ReceiptEnum::PromiseResume(data_receipt)
+                    } else {
+                        ReceiptEnum::Data(data_receipt)
+                    }
+                }
+                ReceiptEnumView::GlobalContractDistribution { target_shard, code } => {
+                    ReceiptEnum::GlobalContractDistribution(GlobalContractDistributionReceipt {
+                        target_shard,
+                        code,
+                    })
                 }
             },
         })
     }
 }
 
+/// Execution trace of a single function call made while applying a receipt.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct FunctionCallTraceView {
+    pub receipt_id: CryptoHash,
+    pub receiver_id: AccountId,
+    pub method_name: String,
+    /// Contract loading, wasm execution between host calls, host calls and
+    /// storage accesses of the call, in execution order. Gas of wasm code is
+    /// not broken down per basic block or per instruction.
+    pub steps: Vec<unc_vm_runner::logic::trace::TraceStep>,
+}
+
+impl From<unc_vm_runner::logic::FunctionCallTrace> for FunctionCallTraceView {
+    fn from(trace: unc_vm_runner::logic::FunctionCallTrace) -> Self {
+        Self {
+            receipt_id: trace.receipt_id,
+            receiver_id: trace.receiver_id,
+            method_name: trace.method_name,
+            steps: trace.trace.steps,
+        }
+    }
+}
+
 /// Information about this epoch validators and next epoch validators
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct EpochValidatorInfo {
@@ -2404,7 +2645,16 @@ pub struct LightClientBlockView {
     pub approvals_after_next: Vec<Option<Box<Signature>>>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, BorshDeserialize, BorshSerialize)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshDeserialize,
+    BorshSerialize,
+)]
 pub struct LightClientBlockLiteView {
     pub prev_block_hash: CryptoHash,
     pub inner_rest_hash: CryptoHash,
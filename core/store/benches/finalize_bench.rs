@@ -127,6 +127,7 @@ fn create_chunk_header(height: u64, shard_id: u64) -> ShardChunkHeader {
         CryptoHash::default(),
         CryptoHash::default(),
         vec![],
+        None,
         &validator_signer(),
     ))
 }
@@ -193,6 +194,7 @@ fn create_encoded_shard_chunk(
         receipts,
         Default::default(),
         Default::default(),
+        None,
         &validator_signer(),
         &mut rs,
         100,
@@ -69,6 +69,11 @@ pub enum DBCol {
     /// - *Rows*: single row (empty row name)
     /// - *Content type*: Vec of [network_primitives::types::ConnectionInfo]
     RecentOutboundConnections,
+    /// Reputation scores of peers we've exchanged data with, used to pick
+    /// outbound peers and eviction candidates. Survives node restarts.
+    /// - *Rows*: single row (empty row name)
+    /// - *Content type*: Vec of [network_primitives::types::PeerReputation]
+    PeerReputations,
     /// Mapping from EpochId to EpochInfo
     /// - *Rows*: EpochId (CryptoHash)
     /// - *Content type*: [unc_primitives::epoch_manager::epoch_info::EpochInfo]
@@ -274,6 +279,16 @@ pub enum DBCol {
     /// - *Rows*: `shard_uid`
     /// - *Column type*: `FlatStorageStatus`
     FlatStorageStatus,
+    /// Versioned flat state, kept only if `StoreConfig::flat_storage_history_num_epochs`
+    /// is set. Stores the value a key had *before* the block at the given height
+    /// changed it, which allows to read the state at blocks older than flat head.
+    /// - *Rows*: `shard_uid` + trie key length (u32, big endian) + trie key + block height (u64, big endian)
+    /// - *Column type*: `Option<FlatStateValue>`
+    FlatStateHistory,
+    /// Index of `DBCol::FlatStateHistory` by block height, used to compact old history.
+    /// - *Rows*: `shard_uid` + block height (u64, big endian)
+    /// - *Column type*: `Vec<Vec<u8>>`, trie keys changed at the block
+    FlatStateHistoryIndex,
     /// Column to persist pieces of miscellaneous small data. Should only be used to store
     /// constant or small (for example per-shard) amount of data.
     /// - *Rows*: arbitrary string, see `crate::db::FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS_KEY` for example
@@ -458,6 +473,7 @@ impl DBCol {
             | DBCol::BlockHeight
             | DBCol::_Peers
             | DBCol::RecentOutboundConnections
+            | DBCol::PeerReputations
             | DBCol::BlockMerkleTree
             | DBCol::AccountAnnouncements
             | DBCol::EpochLightClientBlocks
@@ -478,7 +494,9 @@ impl DBCol {
             | DBCol::FlatState
             | DBCol::FlatStateChanges
             | DBCol::FlatStateDeltaMetadata
-            | DBCol::FlatStorageStatus  => false,
+            | DBCol::FlatStorageStatus
+            | DBCol::FlatStateHistory
+            | DBCol::FlatStateHistoryIndex => false,
             #[cfg(feature = "new_epoch_sync")]
             DBCol::EpochSyncInfo => false
         }
@@ -505,6 +523,7 @@ impl DBCol {
             DBCol::IncomingReceipts => &[DBKeyType::BlockHash, DBKeyType::ShardId],
             DBCol::_Peers => &[DBKeyType::PeerId],
             DBCol::RecentOutboundConnections => &[DBKeyType::Empty],
+            DBCol::PeerReputations => &[DBKeyType::Empty],
             DBCol::EpochInfo => &[DBKeyType::EpochId],
             DBCol::BlockInfo => &[DBKeyType::BlockHash],
             DBCol::Chunks => &[DBKeyType::ChunkHash],
@@ -549,6 +568,10 @@ impl DBCol {
             DBCol::FlatStateChanges => &[DBKeyType::ShardUId, DBKeyType::BlockHash],
             DBCol::FlatStateDeltaMetadata => &[DBKeyType::ShardUId, DBKeyType::BlockHash],
             DBCol::FlatStorageStatus => &[DBKeyType::ShardUId],
+            DBCol::FlatStateHistory => {
+                &[DBKeyType::ShardUId, DBKeyType::TrieKey, DBKeyType::BlockHeight]
+            }
+            DBCol::FlatStateHistoryIndex => &[DBKeyType::ShardUId, DBKeyType::BlockHeight],
             #[cfg(feature = "new_epoch_sync")]
             DBCol::EpochSyncInfo => &[DBKeyType::EpochId],
         }
@@ -561,11 +584,41 @@ impl fmt::Display for DBCol {
     }
 }
 
+/// Columns are (de)serialized by the name of their RocksDB column family, so
+/// configs keyed by column reject unknown names when they are loaded.
+impl serde::Serialize for DBCol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(<&str>::from(*self))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DBCol {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use strum::IntoEnumIterator;
+
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        DBCol::iter()
+            .find(|col| <&str>::from(*col) == name)
+            .ok_or_else(|| serde::de::Error::custom(format_args!("unknown column `{name}`")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use strum::IntoEnumIterator;
 
+    #[test]
+    fn column_serde_round_trip() {
+        for col in DBCol::iter() {
+            let json = serde_json::to_string(&col).unwrap();
+            assert_eq!(json, format!("\"{}\"", <&str>::from(col)));
+            assert_eq!(serde_json::from_str::<DBCol>(&json).unwrap(), col);
+        }
+        let err = serde_json::from_str::<DBCol>("\"Stat\"").unwrap_err();
+        assert!(err.to_string().contains("unknown column `Stat`"), "{err}");
+    }
+
     #[test]
     fn column_props_sanity() {
         for col in DBCol::iter() {
@@ -45,6 +45,15 @@ pub struct StoreConfig {
     /// the performance of the storage
     pub block_size: bytesize::ByteSize,
 
+    /// Compression settings per column, keyed by the column name, e.g. `"State"`.
+    /// Unknown column names are rejected when the config is loaded. Columns
+    /// which are not listed use `ColumnCompressionConfig::default()`.
+    /// When the settings of a column change, the column is recompressed by
+    /// a background compaction after the node starts. Tools opening the
+    /// database, e.g. the state viewer, don't start the recompression.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub column_compression: HashMap<DBCol, ColumnCompressionConfig>,
+
     /// Trie cache configuration per shard for normal (non-view) caches.
     pub trie_cache: TrieCacheConfig,
     /// Trie cache configuration per shard for view caches.
@@ -100,6 +109,14 @@ pub struct StoreConfig {
     /// TODO (#8826): remove, because creation successfully happened in 1.34.
     pub flat_storage_creation_period: Duration,
 
+    /// Number of epochs for which flat storage keeps the values overwritten
+    /// by moving its head (`DBCol::FlatStateHistory`). It allows to serve view
+    /// queries at canonical blocks whose trie nodes are already garbage
+    /// collected, and serves them without walking the trie. Queries for state
+    /// proofs and state changes (`EXPERIMENTAL_changes`) still require an
+    /// archival node. `0` disables flat state history.
+    pub flat_storage_history_num_epochs: u64,
+
     /// State Snapshot configuration
     pub state_snapshot_config: StateSnapshotConfig,
 
@@ -110,6 +127,50 @@ pub struct StoreConfig {
     pub state_snapshot_compaction_enabled: bool,
 }
 
+/// Compression algorithm used for RocksDB data blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
+/// Compression settings of a single column.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ColumnCompressionConfig {
+    /// Algorithm used for all levels except for the first two, which are
+    /// never compressed, and the bottommost one.
+    pub compression: CompressionAlgorithm,
+    /// Algorithm used for the bottommost level, which holds most of the data.
+    pub bottommost_compression: CompressionAlgorithm,
+    /// Compression level used for the bottommost level only, `None` means the
+    /// default level of `bottommost_compression`. All other levels always use
+    /// the default level of `compression`. Higher levels trade CPU for disk
+    /// space.
+    pub bottommost_compression_level: Option<i32>,
+    /// Size of the dictionary trained for the bottommost level. Dictionaries
+    /// improve compression of small values a lot. Only used with zstd, `0`
+    /// disables dictionary compression.
+    pub dictionary_size: bytesize::ByteSize,
+}
+
+impl Default for ColumnCompressionConfig {
+    fn default() -> Self {
+        Self {
+            compression: CompressionAlgorithm::Lz4,
+            bottommost_compression: CompressionAlgorithm::Zstd,
+            bottommost_compression_level: None,
+            // RocksDB documenation says that 16KB is a typical dictionary size.
+            // We've empirically tuned the dicionary size to twice of that 'typical' size.
+            // See: https://rocksdb.org/blog/2021/05/31/dictionary-compression.html?utm_source=dbplatz
+            dictionary_size: bytesize::ByteSize::kib(32),
+        }
+    }
+}
+
 /// Config used to control state snapshot creation. This is used for state sync and resharding.
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
@@ -197,6 +258,11 @@ impl StoreConfig {
             _ => bytesize::ByteSize::mib(32),
         }
     }
+
+    /// Returns compression settings for given column.
+    pub fn col_compression(&self, col: DBCol) -> ColumnCompressionConfig {
+        self.column_compression.get(&col).cloned().unwrap_or_default()
+    }
 }
 
 impl Default for StoreConfig {
@@ -232,6 +298,8 @@ impl Default for StoreConfig {
             // we use it since then.
             block_size: bytesize::ByteSize::kib(16),
 
+            column_compression: Default::default(),
+
             trie_cache: TrieCacheConfig {
                 default_max_bytes: 500_000_000,
                 // TODO(resharding) The cache size needs to adjusted for every resharding.
@@ -282,6 +350,9 @@ impl Default for StoreConfig {
             // flat storage head quickly. State read work is much more expensive.
             flat_storage_creation_period: Duration::from_secs(1),
 
+            // Flat state history takes extra disk space, so it is disabled by default.
+            flat_storage_history_num_epochs: 0,
+
             state_snapshot_config: Default::default(),
 
             // TODO: To be phased out in favor of state_snapshot_config
@@ -353,3 +424,32 @@ impl Default for TrieCacheConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_compression_config() {
+        let config: StoreConfig = serde_json::from_str(
+            r#"{"column_compression": {"State": {"bottommost_compression_level": 3}}}"#,
+        )
+        .unwrap();
+        let expected = ColumnCompressionConfig {
+            bottommost_compression_level: Some(3),
+            ..ColumnCompressionConfig::default()
+        };
+        assert_eq!(config.col_compression(DBCol::State), expected);
+        assert_eq!(config.col_compression(DBCol::Block), ColumnCompressionConfig::default());
+
+        let json = serde_json::to_string(&config).unwrap();
+        let config: StoreConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.column_compression, HashMap::from([(DBCol::State, expected)]));
+
+        let err = serde_json::from_str::<StoreConfig>(
+            r#"{"column_compression": {"Stat": {"compression": "zstd"}}}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown column `Stat`"), "{err}");
+    }
+}
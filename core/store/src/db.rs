@@ -34,10 +34,17 @@ pub const GENESIS_STATE_ROOTS_KEY: &[u8; 19] = b"GENESIS_STATE_ROOTS";
 pub const COLD_HEAD_KEY: &[u8; 9] = b"COLD_HEAD";
 pub const STATE_SYNC_DUMP_KEY: &[u8; 15] = b"STATE_SYNC_DUMP";
 pub const STATE_SNAPSHOT_KEY: &[u8; 18] = b"STATE_SNAPSHOT_KEY";
+/// Compression settings which all data of the columns was last rewritten with.
+pub const COLUMN_COMPRESSION_KEY: &[u8; 18] = b"COLUMN_COMPRESSION";
 
 // `DBCol::Misc` keys
 pub const FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS_KEY: &[u8] =
     b"FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS";
+/// Prefix of the key storing `FlatStateHistoryStatus`, followed by `shard_uid`.
+pub const FLAT_STATE_HISTORY_STATUS_KEY_PREFIX: &[u8] = b"FLAT_STATE_HISTORY_STATUS";
+/// Accounts whose storage grew the most, as `Vec<(AccountId, i64)>`, saved
+/// by `view-state storage-usage` and exported by the node as metrics.
+pub const STORAGE_USAGE_TOP_GROWERS_KEY: &[u8] = b"STORAGE_USAGE_TOP_GROWERS";
 
 #[derive(Default, Debug)]
 pub struct DBTransaction {
@@ -249,6 +256,7 @@ pub enum StatsValue {
     Sum(i64),
     Percentile(u32, f64),
     ColumnValue(DBCol, i64),
+    ColumnFloatValue(DBCol, f64),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1,13 +1,20 @@
-use crate::config::Mode;
-use crate::db::{refcount, DBIterator, DBOp, DBSlice, DBTransaction, Database, StatsValue};
+use crate::config::{ColumnCompressionConfig, CompressionAlgorithm, Mode};
+use crate::db::{
+    refcount, DBIterator, DBOp, DBSlice, DBTransaction, Database, StatsValue,
+    COLUMN_COMPRESSION_KEY,
+};
 use crate::{metadata, metrics, DBCol, StoreConfig, StoreStatistics, Temperature};
 use ::rocksdb::{
-    BlockBasedOptions, Cache, ColumnFamily, Env, IteratorMode, Options, ReadOptions, WriteBatch, DB,
+    BlockBasedOptions, BottommostLevelCompaction, Cache, ColumnFamily, CompactOptions,
+    DBCompressionType, Env, IteratorMode, Options, ReadOptions, WriteBatch, DB,
 };
 use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
 use std::io;
 use std::ops::Deref;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use strum::IntoEnumIterator;
 use tracing::warn;
 
@@ -41,8 +48,12 @@ static CF_PROPERTY_NAMES: Lazy<Vec<std::ffi::CString>> = Lazy::new(|| {
     ret
 });
 
+/// List of floating point RocksDB properties exported as Prometheus metrics.
+static CF_FLOAT_PROPERTY_NAMES: Lazy<Vec<std::ffi::CString>> =
+    Lazy::new(|| (0..=6).map(::rocksdb::properties::compression_ratio_at_level).collect());
+
 pub struct RocksDB {
-    db: DB,
+    db: Arc<DB>,
     db_opt: Options,
 
     /// Map from [`DBCol`] to a column family handler in the RocksDB.
@@ -52,11 +63,20 @@ pub struct RocksDB {
     /// want.
     cf_handles: enum_map::EnumMap<DBCol, Option<std::ptr::NonNull<ColumnFamily>>>,
 
+    /// Background compaction rewriting columns whose compression settings
+    /// changed since the last start.
+    recompression: Option<Recompression>,
+
     // RAII-style of keeping track of the number of instances of RocksDB and
     // counting total sum of max_open_files.
     _instance_tracker: instance_tracker::InstanceTracker,
 }
 
+struct Recompression {
+    stop: Arc<AtomicBool>,
+    handle: std::thread::JoinHandle<()>,
+}
+
 // DB was already Send+Sync. cf and read_options are const pointers using only functions in
 // this file and safe to share across threads.
 unsafe impl Send for RocksDB {}
@@ -116,8 +136,63 @@ impl RocksDB {
         let counter = instance_tracker::InstanceTracker::try_new(store_config.max_open_files)
             .map_err(io::Error::other)?;
         let (db, db_opt) = Self::open_db(path, store_config, mode, temp, columns)?;
+        let db = Arc::new(db);
         let cf_handles = Self::get_cf_handles(&db, columns);
-        Ok(Self { db, db_opt, cf_handles, _instance_tracker: counter })
+        Ok(Self { db, db_opt, cf_handles, recompression: None, _instance_tracker: counter })
+    }
+
+    /// Compares compression settings of the columns with the ones recorded
+    /// in the database and, if any of them changed, starts a background
+    /// compaction which rewrites those columns with the new settings.
+    ///
+    /// RocksDB applies new settings only to newly written files, so without
+    /// the compaction old data would keep its compression until it is
+    /// naturally compacted, which may never happen for the bottommost level.
+    ///
+    /// The compaction of a large column takes hours, so it is only started
+    /// for long running processes, see `StoreOpener::with_column_recompression`.
+    /// The database must be opened in read-write mode.
+    pub(crate) fn spawn_recompression(&mut self, store_config: &StoreConfig) -> io::Result<()> {
+        let db = &self.db;
+        let Some(misc_cf) = db.cf_handle(col_name(DBCol::BlockMisc)) else {
+            return Ok(());
+        };
+        let columns: Vec<DBCol> =
+            DBCol::iter().filter(|col| self.cf_handles[*col].is_some()).collect();
+        let recorded: BTreeMap<String, ColumnCompressionConfig> =
+            match db.get_cf(misc_cf, COLUMN_COMPRESSION_KEY).map_err(io::Error::other)? {
+                Some(value) => serde_json::from_slice(&value).map_err(io::Error::other)?,
+                None => BTreeMap::new(),
+            };
+        let changed: Vec<DBCol> = columns
+            .iter()
+            .copied()
+            .filter(|col| {
+                let name = <&str>::from(*col);
+                recorded.get(name).cloned().unwrap_or_default()
+                    != store_config.col_compression(*col)
+            })
+            .collect();
+        if changed.is_empty() {
+            return Ok(());
+        }
+        let configured: BTreeMap<String, ColumnCompressionConfig> = columns
+            .iter()
+            .map(|col| (<&str>::from(*col).to_string(), store_config.col_compression(*col)))
+            .filter(|(_, config)| config != &ColumnCompressionConfig::default())
+            .collect();
+        let configured = serde_json::to_vec(&configured).map_err(io::Error::other)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let db = db.clone();
+            let stop = stop.clone();
+            std::thread::Builder::new()
+                .name("rocksdb-recompression".to_string())
+                .spawn(move || recompress_columns(&db, &changed, &configured, &stop))?
+        };
+        self.recompression = Some(Recompression { stop, handle });
+        Ok(())
     }
 
     /// Opens the database with given column families configured.
@@ -424,11 +499,41 @@ impl Database for RocksDB {
     }
 }
 
+/// Compacts `columns` so that all their data is rewritten with the current
+/// compression settings, then records the settings in the database.
+fn recompress_columns(db: &DB, columns: &[DBCol], configured: &[u8], stop: &AtomicBool) {
+    let mut compact_options = CompactOptions::default();
+    compact_options.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+    for col in columns {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(cf) = db.cf_handle(col_name(*col)) else {
+            continue;
+        };
+        tracing::info!(target: "db", column = %col, "Recompressing column");
+        let none = Option::<&[u8]>::None;
+        db.compact_range_cf_opt(cf, none, none, &compact_options);
+    }
+    // Compaction is interrupted when the database is closing, record the
+    // settings only if all columns were rewritten.
+    if stop.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(misc_cf) = db.cf_handle(col_name(DBCol::BlockMisc)) else {
+        return;
+    };
+    if let Err(err) = db.put_cf(misc_cf, COLUMN_COMPRESSION_KEY, configured) {
+        warn!(target: "db", ?err, "Failed to record column compression settings");
+    }
+    tracing::info!(target: "db", "Finished recompressing columns");
+}
+
 /// DB level options
 fn rocksdb_options(store_config: &StoreConfig, mode: Mode) -> Options {
     let mut opts = Options::default();
 
-    set_compression_options(&mut opts);
+    set_compression_options(&mut opts, &ColumnCompressionConfig::default());
     opts.create_missing_column_families(mode.read_write());
     opts.create_if_missing(mode.can_create());
     opts.set_use_fsync(false);
@@ -501,7 +606,8 @@ fn rocksdb_block_based_options(store_config: &StoreConfig, db_col: DBCol) -> Blo
 
 fn rocksdb_column_options(col: DBCol, store_config: &StoreConfig, temp: Temperature) -> Options {
     let mut opts = Options::default();
-    set_compression_options(&mut opts);
+    let compression = store_config.col_compression(col);
+    set_compression_options(&mut opts, &compression);
     opts.set_level_compaction_dynamic_level_bytes(true);
     opts.set_block_based_table_factory(&rocksdb_block_based_options(store_config, col));
 
@@ -519,6 +625,15 @@ fn rocksdb_column_options(col: DBCol, store_config: &StoreConfig, temp: Temperat
     //      https://github.com/facebook/rocksdb/blob/c18c4a081c74251798ad2a1abf83bad417518481/options/options.cc#L588.
     let memtable_memory_budget = 128 * bytesize::MIB as usize;
     opts.optimize_level_style_compaction(memtable_memory_budget);
+    if compression != ColumnCompressionConfig::default() {
+        // Keep the first two levels uncompressed as above, but use the
+        // configured algorithm for the rest.
+        let algorithm = compression_type(compression.compression);
+        let mut per_level = [algorithm; 7];
+        per_level[0] = DBCompressionType::None;
+        per_level[1] = DBCompressionType::None;
+        opts.set_compression_per_level(&per_level);
+    }
 
     opts.set_target_file_size_base(64 * bytesize::MIB);
     if temp == Temperature::Hot && col.is_rc() {
@@ -528,23 +643,33 @@ fn rocksdb_column_options(col: DBCol, store_config: &StoreConfig, temp: Temperat
     opts
 }
 
-fn set_compression_options(opts: &mut Options) {
-    opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
-    opts.set_bottommost_compression_type(rocksdb::DBCompressionType::Zstd);
-    // RocksDB documenation says that 16KB is a typical dictionary size.
-    // We've empirically tuned the dicionary size to twice of that 'typical' size.
+fn compression_type(algorithm: CompressionAlgorithm) -> DBCompressionType {
+    match algorithm {
+        CompressionAlgorithm::None => DBCompressionType::None,
+        CompressionAlgorithm::Snappy => DBCompressionType::Snappy,
+        CompressionAlgorithm::Lz4 => DBCompressionType::Lz4,
+        CompressionAlgorithm::Zstd => DBCompressionType::Zstd,
+    }
+}
+
+fn set_compression_options(opts: &mut Options, config: &ColumnCompressionConfig) {
+    opts.set_compression_type(compression_type(config.compression));
+    opts.set_bottommost_compression_type(compression_type(config.bottommost_compression));
+    let dict_size: i32 = config.dictionary_size.as_u64().try_into().unwrap_or(i32::MAX);
     // Having train data size x100 from dictionary size is a recommendation from RocksDB.
     // See: https://rocksdb.org/blog/2021/05/31/dictionary-compression.html?utm_source=dbplatz
-    let dict_size = 2 * 16384;
-    let max_train_bytes = dict_size * 100;
+    let max_train_bytes = dict_size.saturating_mul(100);
     // We use default parameters of RocksDB here:
     //      window_bits is -14 and is unused (Zlib-specific parameter),
     //      compression_level is 32767 meaning the default compression level for ZSTD,
     //      compression_strategy is 0 and is unused (Zlib-specific parameter).
     // See: https://github.com/facebook/rocksdb/blob/main/include/rocksdb/advanced_options.h#L176:
     opts.set_bottommost_compression_options(
-        /*window_bits */ -14, /*compression_level */ 32767,
-        /*compression_strategy */ 0, dict_size, /*enabled */ true,
+        /*window_bits */ -14,
+        /*compression_level */ config.bottommost_compression_level.unwrap_or(32767),
+        /*compression_strategy */ 0,
+        dict_size,
+        /*enabled */ true,
     );
     opts.set_bottommost_zstd_max_train_bytes(max_train_bytes, true);
 }
@@ -590,6 +715,21 @@ impl RocksDB {
                 result.data.push((stat_name.to_string(), values));
             }
         }
+        for prop_name in CF_FLOAT_PROPERTY_NAMES.deref() {
+            let values = self
+                .cf_handles()
+                .filter_map(|(col, handle)| {
+                    let prop = self.db.property_value_cf(handle, prop_name);
+                    let value = prop.ok()??.parse::<f64>().ok()?;
+                    // RocksDB reports a negative ratio for levels without files.
+                    (value >= 0.0).then_some(StatsValue::ColumnFloatValue(col, value))
+                })
+                .collect::<Vec<_>>();
+            if !values.is_empty() {
+                let stat_name = prop_name.to_str().unwrap();
+                result.data.push((stat_name.to_string(), values));
+            }
+        }
     }
 }
 
@@ -601,7 +741,16 @@ impl Drop for RocksDB {
             let mut env = Env::new().unwrap();
             env.set_background_threads(4);
         }
+        if let Some(recompression) = &self.recompression {
+            recompression.stop.store(true, Ordering::Relaxed);
+        }
+        // This also interrupts the running manual compaction.
         self.db.cancel_all_background_work(true);
+        if let Some(recompression) = self.recompression.take() {
+            if recompression.handle.join().is_err() {
+                warn!(target: "db", "Column recompression thread panicked");
+            }
+        }
     }
 }
 
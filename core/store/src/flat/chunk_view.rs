@@ -2,6 +2,7 @@ use crate::flat::store_helper;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::shard_layout::ShardUId;
 use unc_primitives::state::FlatStateValue;
+use unc_primitives::types::BlockHeight;
 
 use crate::Store;
 
@@ -28,12 +29,27 @@ pub struct FlatStorageChunkView {
     /// Stores the state of the flat storage, for example, where the head is at and which
     /// blocks' state are stored in flat storage.
     flat_storage: FlatStorage,
+    /// If set, values are read from flat state history at this height instead
+    /// of the state at `block_hash`.
+    historical_height: Option<BlockHeight>,
 }
 
 impl FlatStorageChunkView {
     pub fn new(store: Store, block_hash: CryptoHash, flat_storage: FlatStorage) -> Self {
-        Self { store, block_hash, flat_storage }
+        Self { store, block_hash, flat_storage, historical_height: None }
     }
+
+    /// Creates a view reading the state right after applying the final block
+    /// at `height`, which may be older than flat head. See `FlatStorage::get_value_at_height`.
+    pub fn new_historical(
+        store: Store,
+        block_hash: CryptoHash,
+        height: BlockHeight,
+        flat_storage: FlatStorage,
+    ) -> Self {
+        Self { store, block_hash, flat_storage, historical_height: Some(height) }
+    }
+
     /// Returns value reference using raw trie key, taken from the state
     /// corresponding to `FlatStorageChunkView::block_hash`.
     ///
@@ -42,9 +58,22 @@ impl FlatStorageChunkView {
     /// could charge users for the value length before loading the value.
     // TODO (#7327): consider inlining small values, so we could use only one db access.
     pub fn get_value(&self, key: &[u8]) -> Result<Option<FlatStateValue>, crate::StorageError> {
+        if let Some(height) = self.historical_height {
+            return Ok(self.flat_storage.get_value_at_height(height, key)?);
+        }
         self.flat_storage.get_value(&self.block_hash, key)
     }
 
+    /// Returns keys with the given prefix in sorted order if the view reads
+    /// from flat state history, `None` otherwise.
+    pub fn get_historical_keys(
+        &self,
+        prefix: &[u8],
+    ) -> Option<Result<Vec<Vec<u8>>, crate::StorageError>> {
+        let height = self.historical_height?;
+        Some(self.flat_storage.get_keys_at_height(height, prefix).map_err(Into::into))
+    }
+
     pub fn iter_flat_state_entries<'a>(
         &'a self,
         from: Option<&[u8]>,
@@ -8,7 +8,7 @@ use unc_primitives::block::Block;
 use unc_primitives::errors::StorageError;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::shard_layout::ShardUId;
-use unc_primitives::types::{BlockHeight, RawStateChangesWithTrieKey};
+use unc_primitives::types::{BlockHeight, BlockHeightDelta, RawStateChangesWithTrieKey};
 
 use crate::{get_genesis_hash, Store, StoreUpdate};
 
@@ -34,11 +34,26 @@ pub struct FlatStorageManagerInner {
     /// this epoch can share the same `head` and `tail`, similar for shards for the next epoch,
     /// but such overhead is negligible comparing the delta sizes, so we think it's ok.
     flat_storages: Mutex<HashMap<ShardUId, FlatStorage>>,
+    /// Number of blocks for which flat state history is kept, `None` if disabled.
+    history_retention: Option<BlockHeightDelta>,
 }
 
 impl FlatStorageManager {
     pub fn new(store: Store) -> Self {
-        Self(Arc::new(FlatStorageManagerInner { store, flat_storages: Default::default() }))
+        Self::new_with_history_retention(store, None)
+    }
+
+    /// Creates a manager whose flat storages keep flat state history for the
+    /// last `history_retention` blocks, see `FlatStorage::get_value_at_height`.
+    pub fn new_with_history_retention(
+        store: Store,
+        history_retention: Option<BlockHeightDelta>,
+    ) -> Self {
+        Self(Arc::new(FlatStorageManagerInner {
+            store,
+            flat_storages: Default::default(),
+            history_retention,
+        }))
     }
 
     /// When a node starts from an empty database, this function must be called to ensure
@@ -70,8 +85,12 @@ impl FlatStorageManager {
     /// and resharding.
     pub fn create_flat_storage_for_shard(&self, shard_uid: ShardUId) -> Result<(), StorageError> {
         let mut flat_storages = self.0.flat_storages.lock().expect(POISONED_LOCK_ERR);
-        let original_value =
-            flat_storages.insert(shard_uid, FlatStorage::new(self.0.store.clone(), shard_uid)?);
+        let flat_storage = FlatStorage::new_with_history_retention(
+            self.0.store.clone(),
+            shard_uid,
+            self.0.history_retention,
+        )?;
+        let original_value = flat_storages.insert(shard_uid, flat_storage);
         // TODO (#7327): maybe we should propagate the error instead of assert here
         // assert is fine now because this function is only called at construction time, but we
         // will need to be more careful when we want to implement flat storage for resharding
@@ -204,6 +223,33 @@ impl FlatStorageManager {
         Some(FlatStorageChunkView::new(self.0.store.clone(), block_hash, flat_storage))
     }
 
+    /// Whether flat state history of the shard covers the given height.
+    pub fn history_covers_height(&self, shard_uid: ShardUId, height: BlockHeight) -> bool {
+        self.get_flat_storage_for_shard(shard_uid)
+            .map_or(false, |flat_storage| flat_storage.supports_height(height))
+    }
+
+    /// Creates `FlatStorageChunkView` to access state for `shard_uid` right after
+    /// applying the final block `block_hash` at `height`, using flat state history.
+    /// Returns `None` if flat storage is not ready or history doesn't cover `height`.
+    pub fn historical_chunk_view(
+        &self,
+        shard_uid: ShardUId,
+        block_hash: CryptoHash,
+        height: BlockHeight,
+    ) -> Option<FlatStorageChunkView> {
+        let flat_storage = self.get_flat_storage_for_shard(shard_uid)?;
+        if !flat_storage.supports_height(height) {
+            return None;
+        }
+        Some(FlatStorageChunkView::new_historical(
+            self.0.store.clone(),
+            block_hash,
+            height,
+            flat_storage,
+        ))
+    }
+
     pub fn get_shard_uids(&self) -> Vec<ShardUId> {
         let flat_storages = self.0.flat_storages.lock().expect(POISONED_LOCK_ERR);
         flat_storages.keys().cloned().collect()
@@ -24,6 +24,10 @@
 //!                     for example, all block deltas that are stored in flat storage and a representation
 //!                     of the chain formed by these blocks (because we can't access ChainStore
 //!                     inside flat storage).
+//!
+//! Optionally, `FlatStorage` keeps the history of values overwritten when its head moves
+//! (see `StoreConfig::flat_storage_history_num_epochs`). It allows to read the state at
+//! final blocks older than flat head, after their trie nodes are garbage collected.
 
 mod chunk_view;
 pub mod delta;
@@ -43,8 +47,8 @@ pub use manager::FlatStorageManager;
 pub use metrics::FlatStorageCreationMetrics;
 pub use storage::FlatStorage;
 pub use types::{
-    BlockInfo, FetchingStateStatus, FlatStateIterator, FlatStorageCreationStatus, FlatStorageError,
-    FlatStorageReadyStatus, FlatStorageStatus,
+    BlockInfo, FetchingStateStatus, FlatStateHistoryStatus, FlatStateIterator,
+    FlatStorageCreationStatus, FlatStorageError, FlatStorageReadyStatus, FlatStorageStatus,
 };
 
 pub(crate) const POISONED_LOCK_ERR: &str = "The lock was poisoned.";
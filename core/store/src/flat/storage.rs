@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::{Arc, RwLock};
 
 use tracing::{debug, warn};
@@ -6,12 +6,12 @@ use unc_primitives::errors::StorageError;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::shard_layout::ShardUId;
 use unc_primitives::state::FlatStateValue;
-use unc_primitives::types::BlockHeight;
+use unc_primitives::types::{BlockHeight, BlockHeightDelta};
 
-use crate::flat::delta::{BlockWithChangesInfo, CachedFlatStateChanges};
+use crate::flat::delta::{BlockWithChangesInfo, CachedFlatStateChanges, FlatStateChanges};
 use crate::flat::BlockInfo;
-use crate::flat::{FlatStorageReadyStatus, FlatStorageStatus};
-use crate::{Store, StoreUpdate};
+use crate::flat::{FlatStateHistoryStatus, FlatStorageReadyStatus, FlatStorageStatus};
+use crate::{DBCol, Store, StoreUpdate, TrieCachingStorage};
 
 use super::delta::{CachedFlatStateDelta, FlatStateDelta};
 use super::metrics::FlatStorageMetrics;
@@ -48,6 +48,11 @@ pub(crate) struct FlatStorageInner {
     /// The flag has a numerical value and not a bool, to let us detect attempts
     /// to disable move head multiple times.
     move_head_enabled: bool,
+    /// Number of blocks for which values replaced by flat head updates are kept
+    /// in `DBCol::FlatStateHistory`. `None` if history is disabled.
+    history_retention: Option<BlockHeightDelta>,
+    /// The oldest height for which the state can be read from history.
+    history_min_height: BlockHeight,
     metrics: FlatStorageMetrics,
 }
 
@@ -221,6 +226,81 @@ impl FlatStorageInner {
         Ok(new_head)
     }
 
+    fn supports_height(&self, height: BlockHeight) -> bool {
+        self.history_retention.is_some()
+            && self.history_min_height <= height
+            && height <= self.flat_head.height
+    }
+
+    /// Saves values of keys changed by the block at `block_height` which are
+    /// about to be overwritten by moving flat head to that block, and compacts
+    /// history which is older than `history_retention` blocks.
+    fn save_history(
+        &mut self,
+        store_update: &mut StoreUpdate,
+        block_height: BlockHeight,
+        changes: &FlatStateChanges,
+    ) -> Result<(), FlatStorageError> {
+        let Some(history_retention) = self.history_retention else {
+            return Ok(());
+        };
+        let mut old_values = Vec::with_capacity(changes.len());
+        for key in changes.0.keys() {
+            let old_value = store_helper::get_flat_state_value(&self.store, self.shard_uid, key)?
+                .map(|value| self.inline_history_value(value))
+                .transpose()?;
+            old_values.push((key.clone(), old_value));
+        }
+        store_helper::set_flat_state_history(
+            store_update,
+            self.shard_uid,
+            block_height,
+            old_values,
+        );
+
+        let min_height =
+            block_height.saturating_sub(history_retention).max(self.history_min_height);
+        if min_height > self.history_min_height {
+            store_helper::remove_flat_state_history_up_to(
+                &self.store,
+                store_update,
+                self.shard_uid,
+                min_height,
+            )?;
+        }
+        store_helper::set_flat_state_history_status(
+            store_update,
+            self.shard_uid,
+            FlatStateHistoryStatus { min_height, flat_head_height: block_height },
+        );
+        self.history_min_height = min_height;
+        Ok(())
+    }
+
+    /// Values referenced from history must stay readable after GC removes
+    /// them from `DBCol::State`, so references are resolved and stored inline.
+    /// A reference is kept as is only if the value is already missing.
+    fn inline_history_value(
+        &self,
+        value: FlatStateValue,
+    ) -> Result<FlatStateValue, FlatStorageError> {
+        let FlatStateValue::Ref(value_ref) = value else {
+            return Ok(value);
+        };
+        let key =
+            TrieCachingStorage::get_key_from_shard_uid_and_hash(self.shard_uid, &value_ref.hash);
+        let bytes = self.store.get(DBCol::State, &key).map_err(|err| {
+            FlatStorageError::StorageInternalError(format!(
+                "failed to read value {:?} for flat state history: {err}",
+                value_ref.hash
+            ))
+        })?;
+        Ok(match bytes {
+            Some(bytes) => FlatStateValue::Inlined(bytes.to_vec()),
+            None => FlatStateValue::Ref(value_ref),
+        })
+    }
+
     #[cfg(test)]
     pub fn test_get_new_flat_head(
         &self,
@@ -236,6 +316,17 @@ impl FlatStorage {
     /// We also load all blocks with height between flat head to `latest_block_height`
     /// including those on forks into the returned FlatStorage.
     pub fn new(store: Store, shard_uid: ShardUId) -> Result<Self, StorageError> {
+        Self::new_with_history_retention(store, shard_uid, None)
+    }
+
+    /// Same as `new`, but also keeps the values overwritten by flat head updates
+    /// for the last `history_retention` blocks, so that the state can be read
+    /// at heights below flat head with `get_value_at_height`.
+    pub fn new_with_history_retention(
+        store: Store,
+        shard_uid: ShardUId,
+        history_retention: Option<BlockHeightDelta>,
+    ) -> Result<Self, StorageError> {
         let shard_id = shard_uid.shard_id();
         let flat_head = match store_helper::get_flat_storage_status(&store, shard_uid) {
             Ok(FlatStorageStatus::Ready(ready_status)) => ready_status.flat_head,
@@ -268,12 +359,19 @@ impl FlatStorage {
             );
         }
 
+        let history_min_height = match history_retention {
+            Some(_) => init_history(&store, shard_uid, &flat_head)?,
+            None => flat_head.height,
+        };
+
         let inner = FlatStorageInner {
             store,
             shard_uid,
             flat_head,
             deltas,
             move_head_enabled: true,
+            history_retention,
+            history_min_height,
             metrics,
         };
         inner.update_delta_metrics();
@@ -314,6 +412,80 @@ impl FlatStorage {
         Ok(value)
     }
 
+    /// Returns the value of `key` in the state right after applying the block
+    /// at `height`. The height must be between the oldest height kept in flat
+    /// state history and flat head, otherwise `HeightNotInHistory` is returned.
+    pub fn get_value_at_height(
+        &self,
+        height: BlockHeight,
+        key: &[u8],
+    ) -> Result<Option<FlatStateValue>, FlatStorageError> {
+        let guard = self.0.read().expect(super::POISONED_LOCK_ERR);
+        if !guard.supports_height(height) {
+            return Err(FlatStorageError::HeightNotInHistory(height));
+        }
+        if let Some(value) =
+            store_helper::get_flat_state_history_value(&guard.store, guard.shard_uid, key, height)?
+        {
+            return Ok(value);
+        }
+        store_helper::get_flat_state_value(&guard.store, guard.shard_uid, key)
+    }
+
+    /// Returns keys with the given prefix which exist in the state right after
+    /// applying the block at `height`, in sorted order. Keys not changed since
+    /// `height` are read from the flat state at flat head and the rest from
+    /// flat state history, so no trie nodes are needed.
+    pub fn get_keys_at_height(
+        &self,
+        height: BlockHeight,
+        prefix: &[u8],
+    ) -> Result<Vec<Vec<u8>>, FlatStorageError> {
+        let guard = self.0.read().expect(super::POISONED_LOCK_ERR);
+        if !guard.supports_height(height) {
+            return Err(FlatStorageError::HeightNotInHistory(height));
+        }
+        let changed_keys = store_helper::get_flat_state_history_keys_changed_after(
+            &guard.store,
+            guard.shard_uid,
+            height,
+            prefix,
+        )?;
+        let mut keys = BTreeSet::new();
+        for entry in
+            store_helper::iter_flat_state_entries(guard.shard_uid, &guard.store, Some(prefix), None)
+        {
+            let (key, _) = entry?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            if !changed_keys.contains(&key) {
+                keys.insert(key);
+            }
+        }
+        for key in changed_keys {
+            let value = match store_helper::get_flat_state_history_value(
+                &guard.store,
+                guard.shard_uid,
+                &key,
+                height,
+            )? {
+                Some(value) => value,
+                None => store_helper::get_flat_state_value(&guard.store, guard.shard_uid, &key)?,
+            };
+            if value.is_some() {
+                keys.insert(key);
+            }
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Whether the state at `height` can be read with `get_value_at_height`.
+    pub fn supports_height(&self, height: BlockHeight) -> bool {
+        let guard = self.0.read().expect(super::POISONED_LOCK_ERR);
+        guard.supports_height(height)
+    }
+
     /// Update the head of the flat storage, including updating the flat state
     /// in memory and on disk and updating the flat state to reflect the state
     /// at the new head. If updating to given head is not possible, returns an
@@ -364,7 +536,6 @@ impl FlatStorage {
             // path from old to new head. Otherwise we return internal error.
             let changes = store_helper::get_delta_changes(&guard.store, shard_uid, block_hash)?
                 .ok_or_else(|| missing_delta_error(&block_hash))?;
-            changes.apply_to_flat_state(&mut store_update, guard.shard_uid);
             let metadata = guard
                 .deltas
                 .get(&block_hash)
@@ -372,6 +543,10 @@ impl FlatStorage {
                 .metadata;
             let block = metadata.block;
             let block_height = block.height;
+            if guard.history_retention.is_some() {
+                guard.save_history(&mut store_update, block_height, &changes)?;
+            }
+            changes.apply_to_flat_state(&mut store_update, guard.shard_uid);
             store_helper::set_flat_storage_status(
                 &mut store_update,
                 shard_uid,
@@ -441,6 +616,7 @@ impl FlatStorage {
         let shard_uid = guard.shard_uid;
         store_helper::remove_all_flat_state_values(store_update, shard_uid);
         store_helper::remove_all_deltas(store_update, shard_uid);
+        store_helper::remove_all_flat_state_history(store_update, shard_uid);
         store_helper::set_flat_storage_status(store_update, shard_uid, FlatStorageStatus::Empty);
         guard.update_delta_metrics();
         Ok(())
@@ -468,6 +644,37 @@ impl FlatStorage {
     }
 }
 
+/// Makes sure that flat state history of the shard is consistent with flat head
+/// and returns the oldest height covered by it. History written by a previous
+/// run is kept only if flat head didn't move since then without it, otherwise
+/// it would have a gap, so it is reset to start from flat head.
+fn init_history(
+    store: &Store,
+    shard_uid: ShardUId,
+    flat_head: &BlockInfo,
+) -> Result<BlockHeight, StorageError> {
+    let status = store_helper::get_flat_state_history_status(store, shard_uid)?;
+    if let Some(status) = status {
+        if status.flat_head_height == flat_head.height {
+            return Ok(status.min_height);
+        }
+    }
+    tracing::info!(target: "store", ?shard_uid, flat_head_height = flat_head.height, "Resetting flat state history");
+    let mut store_update = store.store_update();
+    store_helper::remove_all_flat_state_history(&mut store_update, shard_uid);
+    store_helper::set_flat_state_history_status(
+        &mut store_update,
+        shard_uid,
+        FlatStateHistoryStatus { min_height: flat_head.height, flat_head_height: flat_head.height },
+    );
+    store_update.commit().map_err(|err| {
+        StorageError::StorageInconsistentState(format!(
+            "failed to reset flat state history for shard {shard_uid:?}: {err}"
+        ))
+    })?;
+    Ok(flat_head.height)
+}
+
 fn missing_delta_error(block_hash: &CryptoHash) -> FlatStorageError {
     FlatStorageError::StorageInternalError(format!("delta does not exist for block {block_hash}"))
 }
@@ -478,12 +685,12 @@ mod tests {
         BlockWithChangesInfo, FlatStateChanges, FlatStateDelta, FlatStateDeltaMetadata,
     };
     use crate::flat::manager::FlatStorageManager;
-    use crate::flat::storage::FlatStorageInner;
+    use crate::flat::storage::{FlatStorage, FlatStorageInner};
     use crate::flat::test_utils::MockChain;
     use crate::flat::types::FlatStorageError;
     use crate::flat::{store_helper, FlatStorageReadyStatus, FlatStorageStatus};
     use crate::test_utils::create_test_store;
-    use crate::StorageError;
+    use crate::{DBCol, StorageError, TrieCachingStorage};
     use assert_matches::assert_matches;
 
     use rand::{thread_rng, Rng};
@@ -1297,4 +1504,134 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn flat_state_history() {
+        init_test_logger();
+        // 1. Create a chain where every block changes key [1], block 12 deletes key [2]
+        //    and block 15 overwrites key [3] which holds a non-inlined value.
+        let num_blocks = 20;
+        let history_retention = 10;
+        let chain = MockChain::liunc_chain(num_blocks);
+        let shard_uid = ShardUId::single_shard();
+        let store = create_test_store();
+        let mut store_update = store.store_update();
+        store_helper::set_flat_storage_status(
+            &mut store_update,
+            shard_uid,
+            FlatStorageStatus::Ready(FlatStorageReadyStatus { flat_head: chain.get_block(0) }),
+        );
+        store_helper::set_flat_state_value(
+            &mut store_update,
+            shard_uid,
+            vec![1],
+            Some(FlatStateValue::value_ref(&[0])),
+        );
+        store_helper::set_flat_state_value(
+            &mut store_update,
+            shard_uid,
+            vec![2],
+            Some(FlatStateValue::value_ref(&[0])),
+        );
+        let large_value = vec![3; FlatStateValue::INLINE_DISK_VALUE_THRESHOLD + 1];
+        let large_value_key =
+            TrieCachingStorage::get_key_from_shard_uid_and_hash(shard_uid, &hash(&large_value));
+        store_update.increment_refcount(DBCol::State, &large_value_key, &large_value);
+        store_helper::set_flat_state_value(
+            &mut store_update,
+            shard_uid,
+            vec![3],
+            Some(FlatStateValue::on_disk(&large_value)),
+        );
+        for i in 1..num_blocks as BlockHeight {
+            let mut changes =
+                FlatStateChanges::from([(vec![1], Some(FlatStateValue::value_ref(&[i as u8])))]);
+            if i == 12 {
+                changes.insert(vec![2], None);
+            }
+            if i == 15 {
+                changes.insert(vec![3], Some(FlatStateValue::inlined(&[15])));
+            }
+            let delta = FlatStateDelta {
+                changes,
+                metadata: FlatStateDeltaMetadata {
+                    block: chain.get_block(i),
+                    prev_block_with_changes: None,
+                },
+            };
+            store_helper::set_delta(&mut store_update, shard_uid, &delta);
+        }
+        store_update.commit().unwrap();
+
+        let flat_storage_manager =
+            FlatStorageManager::new_with_history_retention(store.clone(), Some(history_retention));
+        flat_storage_manager.create_flat_storage_for_shard(shard_uid).unwrap();
+        let flat_storage = flat_storage_manager.get_flat_storage_for_shard(shard_uid).unwrap();
+
+        // 2. Move flat head block by block to the end of the chain.
+        for i in 1..num_blocks as BlockHeight {
+            flat_storage.update_flat_head(&chain.get_block_hash(i), true).unwrap();
+        }
+
+        // Remove the overwritten value from state, as GC would do.
+        let mut store_update = store.store_update();
+        store_update.decrement_refcount(DBCol::State, &large_value_key);
+        store_update.commit().unwrap();
+
+        // 3. Check that history covers exactly the last `history_retention` blocks.
+        let head_height = num_blocks as BlockHeight - 1;
+        let min_height = head_height - history_retention;
+        let check_history = |flat_storage: &FlatStorage| {
+            for height in 0..num_blocks as BlockHeight + 1 {
+                let value = flat_storage.get_value_at_height(height, &[1]);
+                if height < min_height || height > head_height {
+                    assert_matches!(value, Err(FlatStorageError::HeightNotInHistory(h)) if h == height);
+                    continue;
+                }
+                assert_eq!(value.unwrap(), Some(FlatStateValue::value_ref(&[height as u8])));
+                let expected_value = (height < 12).then(|| FlatStateValue::value_ref(&[0]));
+                assert_eq!(flat_storage.get_value_at_height(height, &[2]).unwrap(), expected_value);
+                let expected_value = if height < 15 {
+                    FlatStateValue::inlined(&large_value)
+                } else {
+                    FlatStateValue::inlined(&[15])
+                };
+                assert_eq!(
+                    flat_storage.get_value_at_height(height, &[3]).unwrap(),
+                    Some(expected_value)
+                );
+                let expected_keys: Vec<Vec<u8>> = if height < 12 {
+                    vec![vec![1], vec![2], vec![3]]
+                } else {
+                    vec![vec![1], vec![3]]
+                };
+                assert_eq!(flat_storage.get_keys_at_height(height, &[]).unwrap(), expected_keys);
+                assert_eq!(flat_storage.get_keys_at_height(height, &[3]).unwrap(), vec![vec![3]]);
+            }
+        };
+        check_history(&flat_storage);
+        let num_index_rows = store.iter(DBCol::FlatStateHistoryIndex).count() as BlockHeight;
+        assert_eq!(num_index_rows, head_height - min_height);
+
+        // 4. History must survive the restart.
+        let flat_storage = FlatStorage::new_with_history_retention(
+            store.clone(),
+            shard_uid,
+            Some(history_retention),
+        )
+        .unwrap();
+        check_history(&flat_storage);
+
+        // 5. Historical chunk view is only available for covered heights.
+        assert!(flat_storage_manager
+            .historical_chunk_view(shard_uid, chain.get_block_hash(min_height - 1), min_height - 1)
+            .is_none());
+        let chunk_view = flat_storage_manager
+            .historical_chunk_view(shard_uid, chain.get_block_hash(min_height), min_height)
+            .unwrap();
+        assert_eq!(
+            chunk_view.get_value(&[1]).unwrap(),
+            Some(FlatStateValue::value_ref(&[min_height as u8]))
+        );
+    }
 }
@@ -3,18 +3,23 @@
 
 use super::delta::{FlatStateDelta, FlatStateDeltaMetadata};
 use super::types::{
-    FlatStateIterator, FlatStateValuesInliningMigrationStatus, FlatStorageResult, FlatStorageStatus,
+    FlatStateHistoryStatus, FlatStateIterator, FlatStateValuesInliningMigrationStatus,
+    FlatStorageResult, FlatStorageStatus,
+};
+use crate::db::{
+    FLAT_STATE_HISTORY_STATUS_KEY_PREFIX, FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS_KEY,
 };
-use crate::db::FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS_KEY;
 use crate::flat::delta::{BlockWithChangesInfo, FlatStateChanges, KeyForFlatStateDelta};
 use crate::flat::types::FlatStorageError;
 use crate::flat::FlatStorageReadyStatus;
 use crate::{DBCol, Store, StoreUpdate};
 use borsh::BorshDeserialize;
+use std::collections::BTreeSet;
 use std::io;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::shard_layout::ShardUId;
 use unc_primitives::state::FlatStateValue;
+use unc_primitives::types::BlockHeight;
 
 pub fn get_delta_changes(
     store: &Store,
@@ -229,6 +234,188 @@ pub fn set_flat_storage_status(
         .expect("Borsh should not have failed here")
 }
 
+fn encode_flat_state_history_key_prefix(shard_uid: ShardUId, key: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(8 + 4 + key.len() + 8);
+    buffer.extend_from_slice(&shard_uid.to_bytes());
+    // Key length goes first, so that a key can't be a prefix of another key's rows.
+    buffer.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(key);
+    buffer
+}
+
+fn encode_flat_state_history_key(shard_uid: ShardUId, key: &[u8], height: BlockHeight) -> Vec<u8> {
+    let mut buffer = encode_flat_state_history_key_prefix(shard_uid, key);
+    buffer.extend_from_slice(&height.to_be_bytes());
+    buffer
+}
+
+fn encode_flat_state_history_index_key(shard_uid: ShardUId, height: BlockHeight) -> [u8; 16] {
+    let mut res = [0; 16];
+    res[..8].copy_from_slice(&shard_uid.to_bytes());
+    res[8..].copy_from_slice(&height.to_be_bytes());
+    res
+}
+
+fn flat_state_history_status_key(shard_uid: ShardUId) -> Vec<u8> {
+    let mut key = FLAT_STATE_HISTORY_STATUS_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&shard_uid.to_bytes());
+    key
+}
+
+pub fn get_flat_state_history_status(
+    store: &Store,
+    shard_uid: ShardUId,
+) -> FlatStorageResult<Option<FlatStateHistoryStatus>> {
+    store.get_ser(DBCol::Misc, &flat_state_history_status_key(shard_uid)).map_err(|err| {
+        FlatStorageError::StorageInternalError(format!(
+            "failed to read flat state history status: {err}"
+        ))
+    })
+}
+
+pub fn set_flat_state_history_status(
+    store_update: &mut StoreUpdate,
+    shard_uid: ShardUId,
+    status: FlatStateHistoryStatus,
+) {
+    store_update
+        .set_ser(DBCol::Misc, &flat_state_history_status_key(shard_uid), &status)
+        .expect("Borsh should not have failed here")
+}
+
+/// Saves values which `keys` had before the block at `height` was applied.
+/// `old_values` must contain the value for each key, `None` if the key didn't exist.
+pub(crate) fn set_flat_state_history(
+    store_update: &mut StoreUpdate,
+    shard_uid: ShardUId,
+    height: BlockHeight,
+    old_values: Vec<(Vec<u8>, Option<FlatStateValue>)>,
+) {
+    let mut keys = Vec::with_capacity(old_values.len());
+    for (key, value) in old_values {
+        store_update
+            .set_ser(
+                DBCol::FlatStateHistory,
+                &encode_flat_state_history_key(shard_uid, &key, height),
+                &value,
+            )
+            .expect("Borsh should not have failed here");
+        keys.push(key);
+    }
+    store_update
+        .set_ser(
+            DBCol::FlatStateHistoryIndex,
+            &encode_flat_state_history_index_key(shard_uid, height),
+            &keys,
+        )
+        .expect("Borsh should not have failed here");
+}
+
+/// Returns the value of `key` at the moment right after applying the block at
+/// `height`, if the key was changed by some block after `height`.
+/// `None` means that history has no record for the key, i.e. the value in
+/// `DBCol::FlatState` is still actual for `height`.
+pub(crate) fn get_flat_state_history_value(
+    store: &Store,
+    shard_uid: ShardUId,
+    key: &[u8],
+    height: BlockHeight,
+) -> FlatStorageResult<Option<Option<FlatStateValue>>> {
+    let prefix = encode_flat_state_history_key_prefix(shard_uid, key);
+    // The first change made after `height` stores the value which was actual at `height`.
+    let Some(height_from) = height.checked_add(1) else {
+        return Ok(None);
+    };
+    let lower_bound = encode_flat_state_history_key(shard_uid, key, height_from);
+    let Some(result) = store.iter_range(DBCol::FlatStateHistory, Some(&lower_bound), None).next()
+    else {
+        return Ok(None);
+    };
+    let (db_key, value) = result.map_err(|err| {
+        FlatStorageError::StorageInternalError(format!("FlatStateHistory iterator error: {err}"))
+    })?;
+    if !db_key.starts_with(&prefix) {
+        return Ok(None);
+    }
+    let value = Option::<FlatStateValue>::try_from_slice(&value).map_err(|err| {
+        FlatStorageError::StorageInternalError(format!("invalid FlatStateHistory value: {err}"))
+    })?;
+    Ok(Some(value))
+}
+
+/// Returns keys with the given prefix which were changed by blocks with height
+/// bigger than `height` and are still kept in flat state history.
+pub(crate) fn get_flat_state_history_keys_changed_after(
+    store: &Store,
+    shard_uid: ShardUId,
+    height: BlockHeight,
+    prefix: &[u8],
+) -> FlatStorageResult<BTreeSet<Vec<u8>>> {
+    let Some(height_from) = height.checked_add(1) else {
+        return Ok(BTreeSet::new());
+    };
+    let index_from = encode_flat_state_history_index_key(shard_uid, height_from);
+    let index_to = ShardUId::next_shard_prefix(&shard_uid.to_bytes());
+    let mut changed_keys = BTreeSet::new();
+    for result in store.iter_range(DBCol::FlatStateHistoryIndex, Some(&index_from), Some(&index_to))
+    {
+        let (_, keys) = result.map_err(|err| {
+            FlatStorageError::StorageInternalError(format!(
+                "FlatStateHistoryIndex iterator error: {err}"
+            ))
+        })?;
+        let keys = Vec::<Vec<u8>>::try_from_slice(&keys).map_err(|err| {
+            FlatStorageError::StorageInternalError(format!(
+                "invalid FlatStateHistoryIndex value: {err}"
+            ))
+        })?;
+        changed_keys.extend(keys.into_iter().filter(|key| key.starts_with(prefix)));
+    }
+    Ok(changed_keys)
+}
+
+/// Removes history for all blocks with height not bigger than `height`.
+pub(crate) fn remove_flat_state_history_up_to(
+    store: &Store,
+    store_update: &mut StoreUpdate,
+    shard_uid: ShardUId,
+    height: BlockHeight,
+) -> FlatStorageResult<()> {
+    let index_from = encode_flat_state_history_index_key(shard_uid, 0);
+    let index_to = match height.checked_add(1) {
+        Some(height_to) => encode_flat_state_history_index_key(shard_uid, height_to).to_vec(),
+        None => ShardUId::next_shard_prefix(&shard_uid.to_bytes()).to_vec(),
+    };
+    for result in store.iter_range(DBCol::FlatStateHistoryIndex, Some(&index_from), Some(&index_to))
+    {
+        let (index_key, keys) = result.map_err(|err| {
+            FlatStorageError::StorageInternalError(format!(
+                "FlatStateHistoryIndex iterator error: {err}"
+            ))
+        })?;
+        let block_height = BlockHeight::from_be_bytes(index_key[8..].try_into().unwrap());
+        let keys = Vec::<Vec<u8>>::try_from_slice(&keys).map_err(|err| {
+            FlatStorageError::StorageInternalError(format!(
+                "invalid FlatStateHistoryIndex value: {err}"
+            ))
+        })?;
+        for key in keys {
+            store_update.delete(
+                DBCol::FlatStateHistory,
+                &encode_flat_state_history_key(shard_uid, &key, block_height),
+            );
+        }
+        store_update.delete(DBCol::FlatStateHistoryIndex, &index_key);
+    }
+    Ok(())
+}
+
+pub fn remove_all_flat_state_history(store_update: &mut StoreUpdate, shard_uid: ShardUId) {
+    remove_range_by_shard_uid(store_update, shard_uid, DBCol::FlatStateHistory);
+    remove_range_by_shard_uid(store_update, shard_uid, DBCol::FlatStateHistoryIndex);
+    store_update.delete(DBCol::Misc, &flat_state_history_status_key(shard_uid));
+}
+
 /// Returns iterator over flat storage entries for a given shard and range of
 /// state keys. `None` means that there is no bound in respective direction.
 /// It reads data only from `FlatState` column which represents the state at
@@ -27,6 +27,10 @@ pub enum FlatStorageError {
     /// Internal error, caused by DB or in-memory data corruption. Should result
     /// in panic, because correctness of flat storage is not guaranteed afterwards.
     StorageInternalError(String),
+    /// The requested block height is not covered by the flat state history,
+    /// either because history is disabled or because it was already compacted.
+    /// Includes the requested height.
+    HeightNotInHistory(BlockHeight),
 }
 
 impl From<FlatStorageError> for StorageError {
@@ -39,6 +43,11 @@ impl From<FlatStorageError> for StorageError {
                 ))
             }
             FlatStorageError::StorageInternalError(_) => StorageError::StorageInternalError,
+            FlatStorageError::HeightNotInHistory(height) => {
+                StorageError::FlatStorageBlockNotSupported(format!(
+                    "FlatStorage history does not cover height {height}"
+                ))
+            }
         }
     }
 }
@@ -87,6 +96,17 @@ pub struct FlatStorageReadyStatus {
     pub flat_head: BlockInfo,
 }
 
+/// Range of block heights covered by `DBCol::FlatStateHistory` for a shard.
+/// State can be read for any height in `[min_height, flat_head_height]`.
+#[derive(BorshSerialize, BorshDeserialize, Copy, Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct FlatStateHistoryStatus {
+    /// The oldest height for which the state can be reconstructed.
+    pub min_height: BlockHeight,
+    /// Height of the flat head at the moment history was last written. If it
+    /// doesn't match the actual flat head, the history has a gap and must be reset.
+    pub flat_head_height: BlockHeight,
+}
+
 /// If a node has flat storage enabled but it didn't have flat storage data on disk, its creation should be initiated.
 /// Because this is a heavy work requiring ~5h for testnet rpc node and ~10h for testnet archival node, we do it on
 /// background during regular block processing.
@@ -10,9 +10,9 @@ use unc_chain_configs::{Genesis, GenesisContents};
 use unc_parameters::RuntimeConfigStore;
 use unc_primitives::{
     epoch_manager::EpochConfig,
-    shard_layout::{account_id_to_shard_id, ShardLayout},
+    shard_layout::account_id_to_shard_id,
     state_record::{state_record_to_account_id, StateRecord},
-    types::{AccountId, NumShards, ShardId, StateRoot},
+    types::{AccountId, NumShards, StateRoot},
 };
 
 use crate::{
@@ -106,8 +106,11 @@ fn genesis_state_from_genesis(store: Store, genesis: &Genesis) -> Vec<StateRoot>
     info!(target: "store","distributing records to shards");
 
     genesis.for_each_record(|record: &StateRecord| {
-        shard_account_ids[state_record_to_shard_id(record, &shard_layout) as usize]
-            .insert(state_record_to_account_id(record).clone());
+        // The records without an account, i.e. the global contract code, go to every shard.
+        if let Some(account_id) = state_record_to_account_id(record) {
+            shard_account_ids[account_id_to_shard_id(account_id, &shard_layout) as usize]
+                .insert(account_id.clone());
+        }
         if let StateRecord::Account { account_id, .. } = record {
             if account_id == &genesis.config.protocol_treasury_account {
                 has_protocol_account = true;
@@ -157,7 +160,3 @@ fn genesis_state_from_genesis(store: Store, genesis: &Genesis) -> Vec<StateRoot>
         })
         .collect()
 }
-
-fn state_record_to_shard_id(state_record: &StateRecord, shard_layout: &ShardLayout) -> ShardId {
-    account_id_to_shard_id(state_record_to_account_id(state_record), shard_layout)
-}
@@ -1,7 +1,8 @@
 use crate::flat::FlatStateChanges;
 use crate::{
     get_account, get_received_data, set, set_access_key, set_account, set_code,
-    set_delayed_receipt, set_postponed_receipt, set_received_data, ShardTries, TrieUpdate,
+    set_delayed_receipt, set_global_code, set_postponed_receipt, set_received_data, ShardTries,
+    TrieUpdate,
 };
 
 use std::collections::{HashMap, HashSet};
@@ -59,6 +60,7 @@ impl<'a> StorageComputer<'a> {
             StateRecord::PostponedReceipt(_) => None,
             StateRecord::ReceivedData { .. } => None,
             StateRecord::DelayedReceipt(_) => None,
+            StateRecord::GlobalContractCode { .. } => None,
         };
         if let Some((account_id, storage_usage)) = account_and_storage {
             *self.result.entry(account_id).or_default() += storage_usage;
@@ -177,8 +179,10 @@ impl GenesisStateApplier {
             "processing records…"
         );
         genesis.for_each_record(|record: &StateRecord| {
-            if !account_ids.contains(state_record_to_account_id(record)) {
-                return;
+            if let Some(account_id) = state_record_to_account_id(record) {
+                if !account_ids.contains(account_id) {
+                    return;
+                }
             }
             storage_computer.process_record(record);
             match record {
@@ -243,6 +247,9 @@ impl GenesisStateApplier {
                 StateRecord::DelayedReceipt(receipt) => storage.modify(|state_update| {
                     set_delayed_receipt(state_update, delayed_receipts_indices, &*receipt);
                 }),
+                StateRecord::GlobalContractCode { code } => storage.modify(|state_update| {
+                    set_global_code(state_update, &ContractCode::new(code.clone(), None));
+                }),
             }
         });
 
@@ -12,7 +12,7 @@ use once_cell::sync::Lazy;
 use strum;
 use unc_primitives::transaction::RegisterRsa2048KeysAction;
 
-pub use columns::DBCol;
+pub use columns::{DBCol, DBKeyType};
 pub use db::{
     CHUNK_TAIL_KEY, COLD_HEAD_KEY, FINAL_HEAD_KEY, FORK_TAIL_KEY, GENESIS_JSON_HASH_KEY,
     GENESIS_STATE_ROOTS_KEY, HEADER_HEAD_KEY, HEAD_KEY, LARGEST_TARGET_HEIGHT_KEY,
@@ -23,10 +23,13 @@ use unc_fmt::{AbbrBytes, StorageKey};
 use unc_primitives::account::{AccessKey, Account};
 pub use unc_primitives::errors::{MissingTrieValueContext, StorageError};
 use unc_primitives::hash::CryptoHash;
-use unc_primitives::receipt::{DelayedReceiptIndices, Receipt, ReceivedData};
+use unc_primitives::receipt::{
+    BufferedReceiptIndices, DelayedReceiptIndices, PromiseYieldIndices, PromiseYieldTimeout,
+    Receipt, ReceiptEnum, ReceivedData,
+};
 pub use unc_primitives::shard_layout::ShardUId;
 use unc_primitives::trie_key::{trie_key_parsers, TrieKey};
-use unc_primitives::types::{AccountId, StateRoot};
+use unc_primitives::types::{AccountId, ShardId, StateRoot};
 use unc_vm_runner::logic::{CompiledContract, CompiledContractCache};
 use unc_vm_runner::ContractCode;
 
@@ -37,7 +40,7 @@ pub use crate::trie::{
     estimator, resharding, ApplyStatePartResult, KeyForStateChanges, KeyLookupMode, NibbleSlice,
     PartialStorage, PrefetchApi, PrefetchError, RawTrieNode, RawTrieNodeWithSize, ShardTries,
     StateSnapshot, StateSnapshotConfig, Trie, TrieAccess, TrieCache, TrieCachingStorage,
-    TrieChanges, TrieConfig, TrieDBStorage, TrieStorage, WrappedTrieChanges,
+    TrieChanges, TrieConfig, TrieDBStorage, TrieDiffItem, TrieStorage, WrappedTrieChanges,
 };
 
 pub mod cold_storage;
@@ -744,6 +747,92 @@ pub fn set_delayed_receipt(
         .expect("Next available index for delayed receipt exceeded the integer limit");
 }
 
+pub fn set_promise_yield_receipt(state_update: &mut TrieUpdate, receipt: &Receipt) {
+    match &receipt.receipt {
+        ReceiptEnum::PromiseYield(action_receipt) => {
+            assert!(action_receipt.input_data_ids.len() == 1);
+            let key = TrieKey::PromiseYieldReceipt {
+                receiver_id: receipt.receiver_id.clone(),
+                data_id: action_receipt.input_data_ids[0],
+            };
+            set(state_update, key, receipt);
+        }
+        _ => unreachable!("Expected PromiseYield receipt"),
+    }
+}
+
+pub fn remove_promise_yield_receipt(
+    state_update: &mut TrieUpdate,
+    receiver_id: &AccountId,
+    data_id: CryptoHash,
+) {
+    state_update.remove(TrieKey::PromiseYieldReceipt { receiver_id: receiver_id.clone(), data_id });
+}
+
+pub fn get_promise_yield_receipt(
+    trie: &dyn TrieAccess,
+    receiver_id: &AccountId,
+    data_id: CryptoHash,
+) -> Result<Option<Receipt>, StorageError> {
+    get(trie, &TrieKey::PromiseYieldReceipt { receiver_id: receiver_id.clone(), data_id })
+}
+
+pub fn has_promise_yield_receipt(
+    trie: &dyn TrieAccess,
+    receiver_id: AccountId,
+    data_id: CryptoHash,
+) -> Result<bool, StorageError> {
+    Ok(trie.get(&TrieKey::PromiseYieldReceipt { receiver_id, data_id })?.is_some())
+}
+
+pub fn get_promise_yield_indices(
+    trie: &dyn TrieAccess,
+) -> Result<PromiseYieldIndices, StorageError> {
+    Ok(get(trie, &TrieKey::PromiseYieldIndices)?.unwrap_or_default())
+}
+
+// Adds the given timeout into the end of the promise yield timeout queue in the state.
+pub fn set_promise_yield_timeout(
+    state_update: &mut TrieUpdate,
+    promise_yield_indices: &mut PromiseYieldIndices,
+    timeout: &PromiseYieldTimeout,
+) {
+    set(
+        state_update,
+        TrieKey::PromiseYieldTimeout { index: promise_yield_indices.next_available_index },
+        timeout,
+    );
+    promise_yield_indices.next_available_index = promise_yield_indices
+        .next_available_index
+        .checked_add(1)
+        .expect("Next available index for promise yield timeout exceeded the integer limit");
+}
+
+pub fn get_buffered_receipt_indices(
+    trie: &dyn TrieAccess,
+) -> Result<BufferedReceiptIndices, StorageError> {
+    Ok(get(trie, &TrieKey::BufferedReceiptIndices)?.unwrap_or_default())
+}
+
+// Adds the given receipt into the end of the outgoing buffer of the receiving shard in the state.
+pub fn set_buffered_receipt(
+    state_update: &mut TrieUpdate,
+    buffered_receipt_indices: &mut BufferedReceiptIndices,
+    receiving_shard: ShardId,
+    receipt: &Receipt,
+) {
+    let indices = buffered_receipt_indices.shard_buffers.entry(receiving_shard).or_default();
+    set(
+        state_update,
+        TrieKey::BufferedReceipt { receiving_shard, index: indices.next_available_index },
+        receipt,
+    );
+    indices.next_available_index = indices
+        .next_available_index
+        .checked_add(1)
+        .expect("Next available index for buffered receipt exceeded the integer limit");
+}
+
 pub fn set_access_key(
     state_update: &mut TrieUpdate,
     account_id: AccountId,
@@ -835,6 +924,18 @@ pub fn get_code(
     trie.get(&key).map(|opt| opt.map(|code| ContractCode::new(code, code_hash)))
 }
 
+pub fn set_global_code(state_update: &mut TrieUpdate, code: &ContractCode) {
+    state_update.set(TrieKey::GlobalContractCode { code_hash: *code.hash() }, code.code().to_vec());
+}
+
+pub fn get_global_code(
+    trie: &dyn TrieAccess,
+    code_hash: CryptoHash,
+) -> Result<Option<ContractCode>, StorageError> {
+    let key = TrieKey::GlobalContractCode { code_hash };
+    trie.get(&key).map(|opt| opt.map(|code| ContractCode::new(code, Some(code_hash))))
+}
+
 /// Removes account, code and all access keys associated to it.
 pub fn remove_account(
     state_update: &mut TrieUpdate,
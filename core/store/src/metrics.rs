@@ -1,5 +1,6 @@
+use crate::db::STORAGE_USAGE_TOP_GROWERS_KEY;
 use crate::rocksdb_metrics::export_stats_as_metrics;
-use crate::{NodeStorage, Store, Temperature};
+use crate::{DBCol, NodeStorage, Store, Temperature};
 use actix_rt::ArbiterHandle;
 use once_cell::sync::Lazy;
 use unc_o11y::metrics::{
@@ -7,6 +8,7 @@ use unc_o11y::metrics::{
     try_create_histogram_with_buckets, try_create_int_counter_vec, try_create_int_gauge,
     try_create_int_gauge_vec, Histogram, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
 };
+use unc_primitives::types::AccountId;
 
 pub(crate) static DATABASE_OP_LATENCY_HIST: Lazy<HistogramVec> = Lazy::new(|| {
     try_create_histogram_vec(
@@ -538,6 +540,33 @@ pub static COLD_STORE_MIGRATION_BATCH_WRITE_TIME: Lazy<HistogramVec> = Lazy::new
     .unwrap()
 });
 
+pub static STORAGE_USAGE_TOP_GROWERS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "unc_storage_usage_top_growers_bytes",
+        "Growth of storage usage of the accounts which grew the most, as last computed by the storage-usage tool",
+        &["account_id"],
+    )
+    .unwrap()
+});
+
+/// Exports the top storage growers saved by the `storage-usage` state viewer
+/// command, so that they are served together with the other node metrics.
+fn export_storage_usage_top_growers(store: &Store) {
+    let top_growers: Vec<(AccountId, i64)> =
+        match store.get_ser(DBCol::Misc, STORAGE_USAGE_TOP_GROWERS_KEY) {
+            Ok(Some(top_growers)) => top_growers,
+            Ok(None) => return,
+            Err(err) => {
+                tracing::debug!(target:"metrics", ?err, "Failed to read the top storage growers.");
+                return;
+            }
+        };
+    STORAGE_USAGE_TOP_GROWERS.reset();
+    for (account_id, growth) in top_growers {
+        STORAGE_USAGE_TOP_GROWERS.with_label_values(&[account_id.as_str()]).set(growth);
+    }
+}
+
 fn export_store_stats(store: &Store, temperature: Temperature) {
     if let Some(stats) = store.get_store_statistics() {
         tracing::debug!(target:"metrics", "Exporting the db metrics for {temperature:?} store.");
@@ -569,6 +598,7 @@ pub fn spawn_db_metrics_loop(
             interval.tick().await;
 
             export_store_stats(&hot_store, Temperature::Hot);
+            export_storage_usage_top_growers(&hot_store);
             if let Some(cold_store) = &cold_store {
                 export_store_stats(cold_store, Temperature::Cold);
             }
@@ -584,13 +614,18 @@ mod test {
 
     use actix;
 
+    use crate::db::STORAGE_USAGE_TOP_GROWERS_KEY;
     use crate::db::{StatsValue, StoreStatistics};
     use crate::metadata::{DbKind, DB_VERSION};
-    use crate::test_utils::create_test_node_storage_with_cold;
+    use crate::test_utils::{create_test_node_storage_with_cold, create_test_store};
+    use crate::DBCol;
 
     use unc_o11y::testonly::init_test_logger;
 
-    use super::spawn_db_metrics_loop;
+    use super::{
+        export_storage_usage_top_growers, spawn_db_metrics_loop, STORAGE_USAGE_TOP_GROWERS,
+    };
+    use unc_primitives::types::AccountId;
 
     fn stat(name: &str, count: i64) -> (String, Vec<StatsValue>) {
         (name.into(), vec![StatsValue::Count(count)])
@@ -653,4 +688,17 @@ mod test {
         actix::System::current().stop();
         sys.run().unwrap();
     }
+
+    #[test]
+    fn test_export_storage_usage_top_growers() {
+        let store = create_test_store();
+        let top_growers: Vec<(AccountId, i64)> =
+            vec![("alice.unc".parse().unwrap(), 100), ("bob.unc".parse().unwrap(), -5)];
+        let mut store_update = store.store_update();
+        store_update.set_ser(DBCol::Misc, STORAGE_USAGE_TOP_GROWERS_KEY, &top_growers).unwrap();
+        store_update.commit().unwrap();
+        export_storage_usage_top_growers(&store);
+        assert_eq!(STORAGE_USAGE_TOP_GROWERS.with_label_values(&["alice.unc"]).get(), 100);
+        assert_eq!(STORAGE_USAGE_TOP_GROWERS.with_label_values(&["bob.unc"]).get(), -5);
+    }
 }
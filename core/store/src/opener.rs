@@ -175,6 +175,9 @@ pub struct StoreOpener<'a> {
     /// A migrator which performs database migration if the database has old
     /// version.
     migrator: Option<&'a dyn StoreMigrator>,
+
+    /// Whether to recompress columns whose compression settings changed.
+    column_recompression: bool,
 }
 
 /// Opener for a single RocksDB instance.
@@ -209,6 +212,7 @@ impl<'a> StoreOpener<'a> {
             cold: cold_config.map(|config| DBOpener::new(home_dir, config, Temperature::Cold)),
             archive: archive,
             migrator: None,
+            column_recompression: false,
         }
     }
 
@@ -222,6 +226,18 @@ impl<'a> StoreOpener<'a> {
         self
     }
 
+    /// Configures the opener to recompress columns whose compression settings
+    /// changed since the database was last opened this way.
+    ///
+    /// The recompression runs in a background compaction for as long as the
+    /// storage is open, which may take hours for large columns, so it should
+    /// only be enabled by the node itself and not by short-lived tools.  It
+    /// only has effect when opening in read-write mode.
+    pub fn with_column_recompression(mut self, enabled: bool) -> Self {
+        self.column_recompression = enabled;
+        self
+    }
+
     /// Returns path to the underlying RocksDB database.
     ///
     /// Does not check whether the database actually exists.
@@ -272,13 +288,19 @@ impl<'a> StoreOpener<'a> {
             Snapshot::none()
         };
 
-        let (hot_db, _) = self.hot.open(mode, DB_VERSION)?;
-        let cold_db = self
+        let (mut hot_db, _) = self.hot.open(mode, DB_VERSION)?;
+        let mut cold_db = self
             .cold
             .as_ref()
             .map(|cold| cold.open(mode, DB_VERSION))
             .transpose()?
             .map(|(db, _)| db);
+        if self.column_recompression && mode.read_write() {
+            hot_db.spawn_recompression(self.hot.config)?;
+            if let (Some(cold_db), Some(cold)) = (&mut cold_db, &self.cold) {
+                cold_db.spawn_recompression(cold.config)?;
+            }
+        }
 
         let storage = NodeStorage::from_rocksdb(hot_db, cold_db);
 
@@ -119,6 +119,21 @@ impl RocksDBMetrics {
                         // Writing value for column.
                         gauge.with_label_values(&[<&str>::from(col)]).set(value);
                     }
+                    // Floating point rocksdb property, such as the achieved
+                    // compression ratio, labeled by the column.
+                    StatsValue::ColumnFloatValue(col, value) => {
+                        let key = &stat_name;
+
+                        let gauge = match self.gauges.entry(key.to_string()) {
+                            Entry::Vacant(entry) => entry.insert(try_create_gauge_vec(
+                                &get_prometheus_metric_name(&stat_name),
+                                &stat_name,
+                                &["col"],
+                            )?),
+                            Entry::Occupied(entry) => entry.into_mut(),
+                        };
+                        gauge.with_label_values(&[<&str>::from(col)]).set(value);
+                    }
                 }
             }
         }
@@ -26,6 +26,9 @@ pub struct TrieConfig {
     /// List of shards we will load into memory.
     pub load_mem_tries_for_shards: Vec<ShardUId>,
     pub load_mem_tries_for_all_shards: bool,
+
+    /// Number of epochs to keep flat state history for, `0` if disabled.
+    pub flat_storage_history_num_epochs: u64,
 }
 
 impl TrieConfig {
@@ -39,6 +42,7 @@ impl TrieConfig {
         this.enable_receipt_prefetching = config.enable_receipt_prefetching;
         this.load_mem_tries_for_shards = config.load_mem_tries_for_shards.clone();
         this.load_mem_tries_for_all_shards = config.load_mem_tries_for_all_shards;
+        this.flat_storage_history_num_epochs = config.flat_storage_history_num_epochs;
 
         this
     }
@@ -100,6 +100,27 @@ impl<'a> TrieIterator<'a> {
         Ok(r)
     }
 
+    /// Create an iterator over the sub-trie rooted at node `hash`, which is
+    /// located at `key_nibbles` in `trie`.
+    ///
+    /// Returned keys are full keys, i.e. they include `key_nibbles` as the
+    /// prefix.  The iteration stops after the whole sub-trie is visited.
+    pub(crate) fn new_at_subtree(
+        trie: &'a Trie,
+        hash: &CryptoHash,
+        key_nibbles: &[u8],
+    ) -> Result<Self, StorageError> {
+        let mut r = TrieIterator {
+            trie,
+            trail: Vec::with_capacity(8),
+            key_nibbles: key_nibbles.to_vec(),
+            visited_nodes: None,
+            prune_condition: None,
+        };
+        r.descend_into_node(hash)?;
+        Ok(r)
+    }
+
     /// Position the iterator on the first element with key >= `key`.
     pub fn seek_prefix<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), StorageError> {
         self.seek_nibble_slice(NibbleSlice::new(key.as_ref()), true).map(drop)
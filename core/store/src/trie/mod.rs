@@ -15,6 +15,7 @@ pub use crate::trie::nibble_slice::NibbleSlice;
 pub use crate::trie::prefetching_trie_storage::{PrefetchApi, PrefetchError};
 pub use crate::trie::shard_tries::{KeyForStateChanges, ShardTries, WrappedTrieChanges};
 pub use crate::trie::state_snapshot::{SnapshotError, StateSnapshot, StateSnapshotConfig};
+pub use crate::trie::trie_diff::TrieDiffItem;
 pub use crate::trie::trie_storage::{TrieCache, TrieCachingStorage, TrieDBStorage, TrieStorage};
 use crate::StorageError;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -45,12 +46,14 @@ mod insert_delete;
 pub mod iterator;
 pub mod mem;
 mod nibble_slice;
+pub mod node_access_trace;
 mod prefetching_trie_storage;
 mod raw_node;
 pub mod resharding;
 mod shard_tries;
 mod state_parts;
 mod state_snapshot;
+mod trie_diff;
 mod trie_recording;
 mod trie_storage;
 #[cfg(test)]
@@ -701,6 +704,13 @@ impl Trie {
         self.flat_storage_chunk_view.is_some()
     }
 
+    /// Returns keys with the given prefix in sorted order if values are read
+    /// from flat state history, which works without trie nodes of old blocks.
+    /// `None` for all other tries.
+    pub fn get_historical_keys(&self, prefix: &[u8]) -> Option<Result<Vec<Vec<u8>>, StorageError>> {
+        self.flat_storage_chunk_view.as_ref()?.get_historical_keys(prefix)
+    }
+
     pub fn internal_get_storage_as_caching_storage(&self) -> Option<&TrieCachingStorage> {
         self.storage.as_caching_storage()
     }
@@ -0,0 +1,148 @@
+//! Recording of trie node accesses which go through the shard cache.
+//!
+//! The trace is a text file with one event per line, in the format
+//! `<shard_uid> <kind> <node or value hash> <size>`. Besides reads, it contains
+//! the insertions and removals made in the shard cache when trie changes are
+//! applied. It can be replayed by the `state-viewer simulate-trie-cache`
+//! command to estimate hit rates of shard caches with different capacities
+//! and eviction policies.
+
+use crate::trie::trie_storage::TrieCacheInner;
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::shard_layout::ShardUId;
+
+/// How the shard cache is accessed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrieNodeAccessKind {
+    /// A node or value is read through the shard cache.
+    Read,
+    /// A node or value written by applied trie changes is put to the cache.
+    Insert,
+    /// A node or value deleted by applied trie changes is removed from the
+    /// cache, which the cache may postpone.
+    Remove,
+}
+
+impl fmt::Display for TrieNodeAccessKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Insert => "insert",
+            Self::Remove => "remove",
+        })
+    }
+}
+
+impl FromStr for TrieNodeAccessKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "insert" => Ok(Self::Insert),
+            "remove" => Ok(Self::Remove),
+            _ => Err(format!("invalid trie node access kind: {s:?}")),
+        }
+    }
+}
+
+/// Single access of a trie node or value by hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrieNodeAccess {
+    pub shard_uid: ShardUId,
+    pub kind: TrieNodeAccessKind,
+    pub hash: CryptoHash,
+    /// Size of the node or value in bytes.
+    pub size: usize,
+}
+
+impl TrieNodeAccess {
+    /// Size the entry takes in the shard cache, including the overhead of
+    /// the cache data structures.
+    pub fn cache_entry_size(&self) -> u64 {
+        TrieCacheInner::entry_size(self.size)
+    }
+}
+
+impl fmt::Display for TrieNodeAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.shard_uid, self.kind, self.hash, self.size)
+    }
+}
+
+impl FromStr for TrieNodeAccess {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let (Some(shard_uid), Some(kind), Some(hash), Some(size), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("invalid trie node access: {s:?}"));
+        };
+        Ok(Self {
+            shard_uid: shard_uid.parse()?,
+            kind: kind.parse()?,
+            hash: hash.parse().map_err(|err| format!("invalid hash {hash:?}: {err}"))?,
+            size: size.parse().map_err(|err| format!("invalid size {size:?}: {err}"))?,
+        })
+    }
+}
+
+/// Writes trie node accesses to a file.
+///
+/// Shared by all tries of `ShardTries` it is set for, see
+/// `ShardTries::set_node_access_recorder`. Accesses of the view tries are
+/// not recorded.
+pub struct TrieNodeAccessRecorder {
+    writer: Mutex<BufWriter<std::fs::File>>,
+}
+
+impl TrieNodeAccessRecorder {
+    pub fn new(path: &Path) -> io::Result<Self> {
+        let file = std::fs::File::create(path)?;
+        Ok(Self { writer: Mutex::new(BufWriter::new(file)) })
+    }
+
+    pub fn record(&self, access: TrieNodeAccess) {
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(err) = writeln!(writer, "{access}") {
+            tracing::warn!(target: "store", ?err, "Failed to record trie node access");
+        }
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        self.writer.lock().unwrap().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TrieNodeAccess, TrieNodeAccessKind};
+    use unc_primitives::hash::hash;
+    use unc_primitives::shard_layout::ShardUId;
+
+    #[test]
+    fn trie_node_access_roundtrip() {
+        for kind in
+            [TrieNodeAccessKind::Read, TrieNodeAccessKind::Insert, TrieNodeAccessKind::Remove]
+        {
+            let access = TrieNodeAccess {
+                shard_uid: ShardUId { version: 1, shard_id: 3 },
+                kind,
+                hash: hash(b"node"),
+                size: 42,
+            };
+            assert_eq!(access.to_string().parse::<TrieNodeAccess>(), Ok(access));
+        }
+        assert!("s3.v1 read abc".parse::<TrieNodeAccess>().is_err());
+        assert!("s3.v1 write 11111111111111111111111111111111 1"
+            .parse::<TrieNodeAccess>()
+            .is_err());
+    }
+}
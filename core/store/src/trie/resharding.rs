@@ -1,15 +1,18 @@
 use crate::flat::FlatStateChanges;
-use crate::{get, get_delayed_receipt_indices, set, ShardTries, StoreUpdate, Trie, TrieUpdate};
+use crate::{
+    get, get_delayed_receipt_indices, get_promise_yield_indices, set, ShardTries, StoreUpdate,
+    Trie, TrieUpdate,
+};
 use borsh::BorshDeserialize;
 use bytesize::ByteSize;
 use std::collections::HashMap;
 use unc_primitives::account::id::AccountId;
 use unc_primitives::errors::StorageError;
-use unc_primitives::receipt::Receipt;
+use unc_primitives::receipt::{PromiseYieldTimeout, Receipt};
 use unc_primitives::shard_layout::ShardUId;
 use unc_primitives::state_part::PartId;
 use unc_primitives::trie_key::trie_key_parsers::parse_account_id_from_raw_key;
-use unc_primitives::trie_key::TrieKey;
+use unc_primitives::trie_key::{col, TrieKey};
 use unc_primitives::types::{
     ConsolidatedStateChange, StateChangeCause, StateChangesForResharding, StateRoot,
 };
@@ -40,6 +43,7 @@ impl ShardTries {
     ) -> Result<HashMap<ShardUId, TrieUpdate>, StorageError> {
         let mut trie_updates: HashMap<_, _> = self.get_trie_updates(state_roots);
         let mut insert_receipts = Vec::new();
+        let mut insert_yield_timeouts = Vec::new();
         for ConsolidatedStateChange { trie_key, value } in changes.changes {
             match &trie_key {
                 TrieKey::DelayedReceiptIndices => {}
@@ -55,6 +59,37 @@ impl ShardTries {
                     }
                     None => {}
                 },
+                TrieKey::PromiseYieldIndices => {}
+                TrieKey::PromiseYieldTimeout { index } => match value {
+                    Some(value) => {
+                        let timeout =
+                            PromiseYieldTimeout::try_from_slice(&value).map_err(|err| {
+                                StorageError::StorageInconsistentState(format!(
+                                    "invalid promise yield timeout {:?}, err: {}",
+                                    value, err,
+                                ))
+                            })?;
+                        insert_yield_timeouts.push((*index, timeout));
+                    }
+                    None => {}
+                },
+                // The outgoing receipts buffers are keyed by the receiving shard of the parent
+                // shard layout, so they can't be split between the children.
+                TrieKey::BufferedReceiptIndices | TrieKey::BufferedReceipt { .. } => {
+                    return Err(StorageError::StorageInconsistentState(
+                        "resharding of the outgoing receipts buffers is not supported".to_string(),
+                    ));
+                }
+                // Global contract code is not owned by any account, every child shard keeps
+                // its own copy so that the accounts using it can still run it.
+                TrieKey::GlobalContractCode { .. } => {
+                    for trie_update in trie_updates.values_mut() {
+                        match &value {
+                            Some(value) => trie_update.set(trie_key.clone(), value.clone()),
+                            None => trie_update.remove(trie_key.clone()),
+                        }
+                    }
+                }
                 TrieKey::Account { account_id }
                 | TrieKey::ContractCode { account_id }
                 | TrieKey::AccessKey { account_id, .. }
@@ -63,6 +98,7 @@ impl ShardTries {
                 | TrieKey::PendingDataCount { receiver_id: account_id, .. }
                 | TrieKey::PostponedReceipt { receiver_id: account_id, .. }
                 | TrieKey::Rsa2048Keys { account_id, .. }
+                | TrieKey::PromiseYieldReceipt { receiver_id: account_id, .. }
                 | TrieKey::ContractData { account_id, .. } => {
                     let new_shard_uid = account_id_to_shard_uid(account_id);
                     // we can safely unwrap here because the caller of this function guarantees trie_updates
@@ -94,6 +130,18 @@ impl ShardTries {
             account_id_to_shard_uid,
         )?;
 
+        // Timeouts which were already processed in the parent shard are left in the children
+        // queues. They are harmless, because a timeout of a yield which is already resumed is
+        // skipped when processed.
+        insert_yield_timeouts.sort_by_key(|it| it.0);
+        let insert_yield_timeouts: Vec<_> =
+            insert_yield_timeouts.into_iter().map(|(_, timeout)| timeout).collect();
+        apply_promise_yield_timeouts_to_children_states_impl(
+            &mut trie_updates,
+            &insert_yield_timeouts,
+            account_id_to_shard_uid,
+        )?;
+
         Ok(trie_updates)
     }
 
@@ -134,6 +182,16 @@ impl ShardTries {
     ) -> Result<(StoreUpdate, HashMap<ShardUId, StateRoot>), StorageError> {
         let mut changes_by_shard: HashMap<_, Vec<_>> = HashMap::new();
         for (raw_key, value) in values.into_iter() {
+            // Global contract code is copied to every child shard.
+            if raw_key.first() == Some(&col::GLOBAL_CONTRACT_CODE) {
+                for shard_uid in state_roots.keys() {
+                    changes_by_shard
+                        .entry(*shard_uid)
+                        .or_default()
+                        .push((raw_key.clone(), value.clone()));
+                }
+                continue;
+            }
             if let Some(new_shard_uid) = key_to_shard_id(&raw_key)? {
                 changes_by_shard.entry(new_shard_uid).or_default().push((raw_key, value));
             }
@@ -277,6 +335,56 @@ fn apply_delayed_receipts_to_children_states_impl(
     Ok(())
 }
 
+fn apply_promise_yield_timeouts_to_children_states_impl(
+    trie_updates: &mut HashMap<ShardUId, TrieUpdate>,
+    insert_timeouts: &[PromiseYieldTimeout],
+    account_id_to_shard_uid: &dyn Fn(&AccountId) -> ShardUId,
+) -> Result<(), StorageError> {
+    if insert_timeouts.is_empty() {
+        return Ok(());
+    }
+    let mut promise_yield_indices_by_shard = HashMap::new();
+    for (shard_uid, update) in trie_updates.iter() {
+        promise_yield_indices_by_shard.insert(*shard_uid, get_promise_yield_indices(update)?);
+    }
+
+    for timeout in insert_timeouts {
+        let new_shard_uid: ShardUId = account_id_to_shard_uid(&timeout.account_id);
+        let Some(trie_update) = trie_updates.get_mut(&new_shard_uid) else {
+            let err = format!(
+                "Account {} is in new shard {:?} but state_roots only contains {:?}",
+                timeout.account_id,
+                new_shard_uid,
+                promise_yield_indices_by_shard.keys(),
+            );
+            return Err(StorageError::StorageInconsistentState(err));
+        };
+        let indices = promise_yield_indices_by_shard.get_mut(&new_shard_uid).unwrap();
+        set(
+            trie_update,
+            TrieKey::PromiseYieldTimeout { index: indices.next_available_index },
+            timeout,
+        );
+        indices.next_available_index =
+            indices.next_available_index.checked_add(1).ok_or_else(|| {
+                StorageError::StorageInconsistentState(
+                    "Next available index for promise yield timeout exceeded the integer limit"
+                        .to_string(),
+                )
+            })?;
+    }
+
+    for (shard_uid, trie_update) in trie_updates {
+        set(
+            trie_update,
+            TrieKey::PromiseYieldIndices,
+            promise_yield_indices_by_shard.get(shard_uid).unwrap(),
+        );
+        trie_update.commit(StateChangeCause::Resharding);
+    }
+    Ok(())
+}
+
 /// Retrieve delayed receipts starting with `start_index` until `memory_limit` is hit
 /// return None if there is no delayed receipts with index >= start_index
 pub fn get_delayed_receipts(
@@ -6,6 +6,7 @@ use crate::flat::{FlatStorageManager, FlatStorageStatus};
 use crate::trie::config::TrieConfig;
 use crate::trie::mem::loading::load_trie_from_flat_state_and_delta;
 use crate::trie::mem::updating::apply_memtrie_changes;
+use crate::trie::node_access_trace::{TrieNodeAccess, TrieNodeAccessKind, TrieNodeAccessRecorder};
 use crate::trie::prefetching_trie_storage::PrefetchingThreadsHandle;
 use crate::trie::trie_storage::{TrieCache, TrieCachingStorage};
 use crate::trie::{TrieRefcountAddition, POISONED_LOCK_ERR};
@@ -42,6 +43,8 @@ struct ShardTriesInner {
     state_snapshot: Arc<RwLock<Option<StateSnapshot>>>,
     /// Configures how to make state snapshots.
     state_snapshot_config: StateSnapshotConfig,
+    /// Records trie node accesses of the non-view tries, if set.
+    node_access_recorder: RwLock<Option<Arc<TrieNodeAccessRecorder>>>,
 }
 
 #[derive(Clone)]
@@ -68,9 +71,16 @@ impl ShardTries {
             prefetchers: Default::default(),
             state_snapshot: Arc::new(RwLock::new(None)),
             state_snapshot_config,
+            node_access_recorder: RwLock::new(None),
         }))
     }
 
+    /// Sets the recorder of trie node accesses for tries created from now on.
+    /// Used to collect traces for cache simulations.
+    pub fn set_node_access_recorder(&self, recorder: Option<Arc<TrieNodeAccessRecorder>>) {
+        *self.0.node_access_recorder.write().expect(POISONED_LOCK_ERR) = recorder;
+    }
+
     /// Create caches for all shards according to the trie config.
     fn create_initial_caches(
         config: &TrieConfig,
@@ -130,13 +140,13 @@ impl ShardTries {
                 .clone()
         });
 
-        let storage = Rc::new(TrieCachingStorage::new(
-            self.0.store.clone(),
-            cache,
-            shard_uid,
-            is_view,
-            prefetch_api,
-        ));
+        let mut storage =
+            TrieCachingStorage::new(self.0.store.clone(), cache, shard_uid, is_view, prefetch_api);
+        if !is_view {
+            storage.node_access_recorder =
+                self.0.node_access_recorder.read().expect(POISONED_LOCK_ERR).clone();
+        }
+        let storage = Rc::new(storage);
         let flat_storage_chunk_view = block_hash
             .and_then(|block_hash| self.0.flat_storage_manager.chunk_view(shard_uid, block_hash));
         Trie::new_with_memtries(
@@ -185,6 +195,46 @@ impl ShardTries {
         self.get_trie_for_shard_internal(shard_uid, state_root, true, None)
     }
 
+    /// Returns a view trie which reads values from flat state history at `height`,
+    /// so that the state stays readable after trie nodes of `state_root` are
+    /// garbage collected. `block_hash` must be the final block at `height`.
+    /// Falls back to `get_view_trie_for_shard` if history doesn't cover `height`.
+    pub fn get_view_trie_for_shard_at_height(
+        &self,
+        shard_uid: ShardUId,
+        state_root: StateRoot,
+        block_hash: &CryptoHash,
+        height: BlockHeight,
+    ) -> Trie {
+        let Some(flat_storage_chunk_view) =
+            self.0.flat_storage_manager.historical_chunk_view(shard_uid, *block_hash, height)
+        else {
+            return self.get_view_trie_for_shard(shard_uid, state_root);
+        };
+        let cache = {
+            let mut caches = self.0.view_caches.write().expect(POISONED_LOCK_ERR);
+            caches
+                .entry(shard_uid)
+                .or_insert_with(|| TrieCache::new(&self.0.trie_config, shard_uid, true))
+                .clone()
+        };
+        let storage =
+            Rc::new(TrieCachingStorage::new(self.0.store.clone(), cache, shard_uid, true, None));
+        Trie::new(storage, state_root, Some(flat_storage_chunk_view))
+    }
+
+    pub fn new_trie_update_view_at_height(
+        &self,
+        shard_uid: ShardUId,
+        state_root: StateRoot,
+        block_hash: &CryptoHash,
+        height: BlockHeight,
+    ) -> TrieUpdate {
+        TrieUpdate::new(
+            self.get_view_trie_for_shard_at_height(shard_uid, state_root, block_hash, height),
+        )
+    }
+
     pub fn store_update(&self) -> StoreUpdate {
         StoreUpdate::new(self.get_db().clone())
     }
@@ -210,6 +260,17 @@ impl ShardTries {
     }
 
     pub fn update_cache(&self, ops: Vec<(&CryptoHash, Option<&[u8]>)>, shard_uid: ShardUId) {
+        if let Some(recorder) =
+            self.0.node_access_recorder.read().expect(POISONED_LOCK_ERR).as_ref()
+        {
+            for (hash, value) in &ops {
+                let (kind, size) = match value {
+                    Some(value) => (TrieNodeAccessKind::Insert, value.len()),
+                    None => (TrieNodeAccessKind::Remove, 0),
+                };
+                recorder.record(TrieNodeAccess { shard_uid, kind, hash: **hash, size });
+            }
+        }
         let mut caches = self.0.caches.write().expect(POISONED_LOCK_ERR);
         let cache = caches
             .entry(shard_uid)
@@ -698,6 +759,7 @@ mod test {
             enable_receipt_prefetching: false,
             load_mem_tries_for_shards: Vec::new(),
             load_mem_tries_for_all_shards: false,
+            flat_storage_history_num_epochs: 0,
         };
         let shard_uids = Vec::from([ShardUId::single_shard()]);
         ShardTries::new(
@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::state::ValueRef;
+
+use crate::trie::iterator::TrieIterator;
+use crate::trie::nibble_slice::NibbleSlice;
+use crate::trie::{Children, RawTrieNode, Trie};
+use crate::StorageError;
+
+/// A single key whose value differs between two tries.
+///
+/// `left` and `right` hold the values stored under `key` in the first and
+/// the second trie respectively, or `None` if the key is missing there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieDiffItem {
+    pub key: Vec<u8>,
+    pub left: Option<Vec<u8>>,
+    pub right: Option<Vec<u8>>,
+}
+
+impl Trie {
+    /// Computes all differences between the key-value pairs of `self` and
+    /// `other`, sorted by key.
+    ///
+    /// Both tries are walked side by side and sub-tries with equal hashes are
+    /// skipped, so the cost is proportional to the size of the difference
+    /// rather than the size of the state.  The tries may be backed by
+    /// different storages, e.g. a local database and a state part dump.
+    pub fn diff(&self, other: &Trie) -> Result<Vec<TrieDiffItem>, StorageError> {
+        let mut result = vec![];
+        diff_nodes(self, other, &self.root, &other.root, &mut vec![], &mut result)?;
+        Ok(result)
+    }
+}
+
+fn diff_nodes(
+    left: &Trie,
+    right: &Trie,
+    left_hash: &CryptoHash,
+    right_hash: &CryptoHash,
+    key_nibbles: &mut Vec<u8>,
+    result: &mut Vec<TrieDiffItem>,
+) -> Result<(), StorageError> {
+    if left_hash == right_hash {
+        return Ok(());
+    }
+    let left_node = left.retrieve_raw_node(left_hash, false)?.map(|(_, node)| node.node);
+    let right_node = right.retrieve_raw_node(right_hash, false)?.map(|(_, node)| node.node);
+    match (left_node, right_node) {
+        (
+            Some(left_node @ (RawTrieNode::BranchNoValue(_) | RawTrieNode::BranchWithValue(..))),
+            Some(right_node @ (RawTrieNode::BranchNoValue(_) | RawTrieNode::BranchWithValue(..))),
+        ) => {
+            let (left_value, left_children) = split_branch(left_node);
+            let (right_value, right_children) = split_branch(right_node);
+            diff_values(left, right, left_value, right_value, key_nibbles, result)?;
+            for idx in 0..16u8 {
+                let left_child = left_children[idx].unwrap_or(Trie::EMPTY_ROOT);
+                let right_child = right_children[idx].unwrap_or(Trie::EMPTY_ROOT);
+                key_nibbles.push(idx);
+                diff_nodes(left, right, &left_child, &right_child, key_nibbles, result)?;
+                key_nibbles.pop();
+            }
+        }
+        (
+            Some(RawTrieNode::Extension(left_key, left_child)),
+            Some(RawTrieNode::Extension(right_key, right_child)),
+        ) if left_key == right_key => {
+            let (slice, _) = NibbleSlice::from_encoded(&left_key);
+            key_nibbles.extend(slice.iter());
+            diff_nodes(left, right, &left_child, &right_child, key_nibbles, result)?;
+            key_nibbles.truncate(key_nibbles.len() - slice.len());
+        }
+        (
+            Some(RawTrieNode::Leaf(left_key, left_value)),
+            Some(RawTrieNode::Leaf(right_key, right_value)),
+        ) if left_key == right_key => {
+            let (slice, _) = NibbleSlice::from_encoded(&left_key);
+            key_nibbles.extend(slice.iter());
+            diff_values(left, right, Some(left_value), Some(right_value), key_nibbles, result)?;
+            key_nibbles.truncate(key_nibbles.len() - slice.len());
+        }
+        // The sub-tries have different shapes, so there is nothing to align
+        // them by. Fall back to iterating over both of them and merging the
+        // sorted key-value pairs.
+        _ => diff_iterators(
+            TrieIterator::new_at_subtree(left, left_hash, key_nibbles)?,
+            TrieIterator::new_at_subtree(right, right_hash, key_nibbles)?,
+            result,
+        )?,
+    }
+    Ok(())
+}
+
+fn split_branch(node: RawTrieNode) -> (Option<ValueRef>, Children) {
+    match node {
+        RawTrieNode::BranchNoValue(children) => (None, children),
+        RawTrieNode::BranchWithValue(value, children) => (Some(value), children),
+        _ => unreachable!("expected a branch node"),
+    }
+}
+
+fn diff_values(
+    left: &Trie,
+    right: &Trie,
+    left_value: Option<ValueRef>,
+    right_value: Option<ValueRef>,
+    key_nibbles: &[u8],
+    result: &mut Vec<TrieDiffItem>,
+) -> Result<(), StorageError> {
+    if left_value.as_ref().map(|value| value.hash) == right_value.as_ref().map(|value| value.hash)
+    {
+        return Ok(());
+    }
+    let (key, remainder) = Trie::nibbles_to_bytes(key_nibbles);
+    if !remainder.is_empty() {
+        return Err(StorageError::StorageInconsistentState(format!(
+            "Found a value at odd nibble key {key_nibbles:?}"
+        )));
+    }
+    result.push(TrieDiffItem {
+        key,
+        left: left_value.map(|value| left.retrieve_value(&value.hash)).transpose()?,
+        right: right_value.map(|value| right.retrieve_value(&value.hash)).transpose()?,
+    });
+    Ok(())
+}
+
+fn diff_iterators(
+    left: TrieIterator,
+    right: TrieIterator,
+    result: &mut Vec<TrieDiffItem>,
+) -> Result<(), StorageError> {
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+    loop {
+        let ordering = match (left.peek(), right.peek()) {
+            (None, None) => return Ok(()),
+            (Some(Err(_)), _) => return Err(left.next().unwrap().unwrap_err()),
+            (_, Some(Err(_))) => return Err(right.next().unwrap().unwrap_err()),
+            (Some(Ok(_)), None) => Ordering::Less,
+            (None, Some(Ok(_))) => Ordering::Greater,
+            (Some(Ok((left_key, _))), Some(Ok((right_key, _)))) => left_key.cmp(right_key),
+        };
+        match ordering {
+            Ordering::Less => {
+                let (key, value) = left.next().unwrap()?;
+                result.push(TrieDiffItem { key, left: Some(value), right: None });
+            }
+            Ordering::Greater => {
+                let (key, value) = right.next().unwrap()?;
+                result.push(TrieDiffItem { key, left: None, right: Some(value) });
+            }
+            Ordering::Equal => {
+                let (key, left_value) = left.next().unwrap()?;
+                let (_, right_value) = right.next().unwrap()?;
+                if left_value != right_value {
+                    result.push(TrieDiffItem {
+                        key,
+                        left: Some(left_value),
+                        right: Some(right_value),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::Rng;
+
+    use crate::test_utils::{gen_changes, simplify_changes, test_populate_trie, TestTriesBuilder};
+    use crate::trie::trie_diff::TrieDiffItem;
+    use crate::Trie;
+    use unc_primitives::shard_layout::ShardUId;
+
+    fn to_map(changes: &[(Vec<u8>, Option<Vec<u8>>)]) -> BTreeMap<Vec<u8>, Vec<u8>> {
+        changes
+            .iter()
+            .filter_map(|(key, value)| value.clone().map(|value| (key.clone(), value)))
+            .collect()
+    }
+
+    fn expected_diff(
+        left: &BTreeMap<Vec<u8>, Vec<u8>>,
+        right: &BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> Vec<TrieDiffItem> {
+        let keys: std::collections::BTreeSet<_> = left.keys().chain(right.keys()).collect();
+        keys.into_iter()
+            .map(|key| TrieDiffItem {
+                key: key.clone(),
+                left: left.get(key).cloned(),
+                right: right.get(key).cloned(),
+            })
+            .filter(|item| item.left != item.right)
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let changes = vec![(b"aa".to_vec(), Some(vec![1])), (b"abb".to_vec(), Some(vec![2]))];
+        let tries = TestTriesBuilder::new().build();
+        let shard_uid = ShardUId::single_shard();
+        let root = test_populate_trie(&tries, &Trie::EMPTY_ROOT, shard_uid, changes);
+        let trie = tries.get_trie_for_shard(shard_uid, root);
+        assert_eq!(trie.diff(&trie).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_diff_against_empty() {
+        let changes = vec![(b"aa".to_vec(), Some(vec![1])), (b"abb".to_vec(), Some(vec![2]))];
+        let tries = TestTriesBuilder::new().build();
+        let shard_uid = ShardUId::single_shard();
+        let root = test_populate_trie(&tries, &Trie::EMPTY_ROOT, shard_uid, changes.clone());
+        let trie = tries.get_trie_for_shard(shard_uid, root);
+        let empty = tries.get_trie_for_shard(shard_uid, Trie::EMPTY_ROOT);
+        assert_eq!(empty.diff(&trie).unwrap(), expected_diff(&BTreeMap::new(), &to_map(&changes)));
+    }
+
+    #[test]
+    fn test_diff_random() {
+        let mut rng = rand::thread_rng();
+        let shard_uid = ShardUId::single_shard();
+        for _ in 0..100 {
+            let tries = TestTriesBuilder::new().build();
+            let left_changes = simplify_changes(&gen_changes(&mut rng, 20));
+            let left_root =
+                test_populate_trie(&tries, &Trie::EMPTY_ROOT, shard_uid, left_changes.clone());
+
+            // Derive the right trie from the left one, so that most sub-tries
+            // are shared and skipped by the diff.
+            let left_map = to_map(&left_changes);
+            let mut right_map = left_map.clone();
+            for (key, _) in left_changes.iter() {
+                if rng.gen_bool(0.2) {
+                    right_map.remove(key);
+                } else if rng.gen_bool(0.2) {
+                    right_map.insert(key.clone(), vec![rng.gen::<u8>(), rng.gen::<u8>()]);
+                }
+            }
+            right_map.extend(to_map(&simplify_changes(&gen_changes(&mut rng, 5))));
+            let right_changes = left_map
+                .keys()
+                .filter(|key| !right_map.contains_key(*key))
+                .map(|key| (key.clone(), None))
+                .chain(right_map.iter().map(|(key, value)| (key.clone(), Some(value.clone()))))
+                .collect();
+            let right_root = test_populate_trie(&tries, &left_root, shard_uid, right_changes);
+
+            let left = tries.get_trie_for_shard(shard_uid, left_root);
+            let right = tries.get_trie_for_shard(shard_uid, right_root);
+            assert_eq!(left.diff(&right).unwrap(), expected_diff(&left_map, &right_map));
+            assert_eq!(right.diff(&left).unwrap(), expected_diff(&right_map, &left_map));
+        }
+    }
+}
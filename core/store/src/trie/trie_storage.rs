@@ -1,4 +1,5 @@
 use crate::trie::config::TrieConfig;
+use crate::trie::node_access_trace::{TrieNodeAccess, TrieNodeAccessKind, TrieNodeAccessRecorder};
 use crate::trie::prefetching_trie_storage::PrefetcherResult;
 use crate::trie::POISONED_LOCK_ERR;
 use crate::{metrics, DBCol, MissingTrieValueContext, PrefetchApi, StorageError, Store};
@@ -220,7 +221,7 @@ impl TrieCacheInner {
         self.total_size
     }
 
-    fn entry_size(len: usize) -> u64 {
+    pub(crate) fn entry_size(len: usize) -> u64 {
         len as u64 + Self::PER_ENTRY_OVERHEAD
     }
 }
@@ -364,6 +365,9 @@ pub struct TrieCachingStorage {
     /// The entry point for the runtime to submit prefetch requests.
     pub(crate) prefetch_api: Option<PrefetchApi>,
 
+    /// Records all accesses of the shard cache if set.
+    pub(crate) node_access_recorder: Option<Arc<TrieNodeAccessRecorder>>,
+
     // Counters tracking operations happening inside the shard cache.
     // Stored here to avoid overhead of looking them up on hot paths.
     metrics: TrieCacheInnerMetrics,
@@ -413,7 +417,15 @@ impl TrieCachingStorage {
             prefetch_retry: metrics::PREFETCH_RETRY.with_label_values(&metrics_labels[..1]),
             prefetch_conflict: metrics::PREFETCH_CONFLICT.with_label_values(&metrics_labels[..1]),
         };
-        TrieCachingStorage { store, shard_uid, is_view, shard_cache, prefetch_api, metrics }
+        TrieCachingStorage {
+            store,
+            shard_uid,
+            is_view,
+            shard_cache,
+            prefetch_api,
+            node_access_recorder: None,
+            metrics,
+        }
     }
 
     pub fn get_key_from_shard_uid_and_hash(shard_uid: ShardUId, hash: &CryptoHash) -> [u8; 40] {
@@ -519,6 +531,14 @@ impl TrieStorage for TrieCachingStorage {
             }
         };
 
+        if let Some(recorder) = &self.node_access_recorder {
+            recorder.record(TrieNodeAccess {
+                shard_uid: self.shard_uid,
+                kind: TrieNodeAccessKind::Read,
+                hash: *hash,
+                size: val.len(),
+            });
+        }
         Ok(val)
     }
 
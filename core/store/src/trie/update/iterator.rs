@@ -4,8 +4,6 @@ use std::ops::Bound;
 use crate::trie::update::*;
 use crate::StorageError;
 
-use crate::trie::TrieIterator;
-
 struct MergeIter<'a> {
     left: Peekable<Box<dyn Iterator<Item = (&'a [u8], Option<&'a [u8]>)> + 'a>>,
     right: Peekable<Box<dyn Iterator<Item = (&'a [u8], Option<&'a [u8]>)> + 'a>>,
@@ -35,13 +33,23 @@ impl<'a> Iterator for MergeIter<'a> {
     }
 }
 
-pub struct TrieUpdateIterator<'a>(Option<(Peekable<TrieIterator<'a>>, Peekable<MergeIter<'a>>)>);
+type TrieKeysIter<'a> = Box<dyn Iterator<Item = Result<Vec<u8>, StorageError>> + 'a>;
+
+pub struct TrieUpdateIterator<'a>(Option<(Peekable<TrieKeysIter<'a>>, Peekable<MergeIter<'a>>)>);
 
 impl<'a> TrieUpdateIterator<'a> {
     #![allow(clippy::new_ret_no_self)]
     pub fn new(state_update: &'a TrieUpdate, prefix: &[u8]) -> Result<Self, StorageError> {
-        let mut trie_iter = state_update.trie.iter()?;
-        trie_iter.seek_prefix(prefix)?;
+        // Tries reading from flat state history may not have the nodes of
+        // their root anymore, so their keys are taken from the history.
+        let trie_iter: TrieKeysIter<'a> = match state_update.trie.get_historical_keys(prefix) {
+            Some(keys) => Box::new(keys?.into_iter().map(Ok)),
+            None => {
+                let mut trie_iter = state_update.trie.iter()?;
+                trie_iter.seek_prefix(prefix)?;
+                Box::new(trie_iter.map(|item| item.map(|(key, _)| key)))
+            }
+        };
 
         let end_bound = make_prefix_range_end_bound(prefix);
         let end_bound = if let Some(end_bound) = &end_bound {
@@ -97,7 +105,7 @@ impl<'a> Iterator for TrieUpdateIterator<'a> {
                     return Some(Err(err));
                 }
 
-                (Some(Ok((left_key, _))), Some((right_key, _))) => {
+                (Some(Ok(left_key)), Some((right_key, _))) => {
                     match left_key.as_slice().cmp(right_key) {
                         std::cmp::Ordering::Less => Ordering::Trie,
                         std::cmp::Ordering::Equal => Ordering::Both,
@@ -117,11 +125,11 @@ impl<'a> Iterator for TrieUpdateIterator<'a> {
             // delete the value.
             let trie_item = if res != Ordering::Overlay { iterators.0.next() } else { None };
             if res == Ordering::Trie {
-                if let Some(Ok((key, _))) = trie_item {
+                if let Some(Ok(key)) = trie_item {
                     return Some(Ok(key));
                 }
             } else if let Some((overlay_key, Some(_))) = iterators.1.next() {
-                return Some(Ok(if let Some(Ok((trie_key, _))) = trie_item {
+                return Some(Ok(if let Some(Ok(trie_key)) = trie_item {
                     debug_assert_eq!(trie_key.as_slice(), overlay_key);
                     trie_key
                 } else {
@@ -292,6 +292,10 @@ pub struct Config {
     pub tracked_shards: Vec<ShardId>,
     #[serde(skip_serializing_if = "is_false")]
     pub archive: bool,
+    /// Light nodes follow only the light client blocks received from their peers,
+    /// without tracking any shards or serving blocks and chunks.
+    #[serde(skip_serializing_if = "is_false")]
+    pub light: bool,
     /// If save_trie_changes is not set it will get inferred from the `archive` field as follows:
     /// save_trie_changes = !archive
     /// save_trie_changes should be set to true iff
@@ -367,6 +371,7 @@ impl Default for Config {
             consensus: Consensus::default(),
             tracked_shards: vec![0],
             archive: false,
+            light: false,
             save_trie_changes: None,
             log_summary_style: LogSummaryStyle::Colored,
             log_summary_period: default_log_summary_period(),
@@ -664,6 +669,7 @@ impl UncConfig {
                 chunk_request_retry_period: config.consensus.chunk_request_retry_period,
                 doosmslug_step_period: config.consensus.doomslug_step_period,
                 archive: config.archive,
+                light: config.light,
                 save_trie_changes: config.save_trie_changes.unwrap_or(!config.archive),
                 log_summary_style: config.log_summary_style,
                 gc: config.gc,
@@ -694,6 +700,7 @@ impl UncConfig {
                 network_key_pair.private_key,
                 validator_signer.clone(),
                 config.archive,
+                config.light,
             )?,
             telemetry_config: config.telemetry,
             #[cfg(feature = "json_rpc")]
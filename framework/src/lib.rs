@@ -14,7 +14,7 @@ use std::sync::{Arc, RwLock};
 use tokio::sync::broadcast;
 use tracing::info;
 use unc_async::actix::AddrWithAutoSpanContextExt;
-use unc_async::messaging::{IntoSender, LateBoundSender};
+use unc_async::messaging::{IntoSender, LateBoundSender, Sender};
 use unc_async::time;
 use unc_chain::state_snapshot_actor::{
     get_delete_snapshot_callback, get_make_snapshot_callback, SnapshotCallbacks, StateSnapshotActor,
@@ -28,6 +28,7 @@ use unc_client::sync::adapter::SyncAdapter;
 use unc_client::{start_client, start_view_client, ClientActor, ConfigUpdater, ViewClientActor};
 use unc_epoch_manager::shard_tracker::ShardTracker;
 use unc_epoch_manager::EpochManager;
+use unc_network::shards_manager::ShardsManagerRequestFromNetwork;
 use unc_network::PeerManagerActor;
 use unc_primitives::block::GenesisId;
 use unc_store::flat::FlatStateValuesInliningMigrationHandle;
@@ -77,6 +78,17 @@ pub fn get_default_home() -> PathBuf {
 /// have the type of the node be determined purely based on kind of database
 /// being opened.
 pub fn open_storage(home_dir: &Path, unc_config: &mut UncConfig) -> anyhow::Result<NodeStorage> {
+    open_storage_impl(home_dir, unc_config, false)
+}
+
+/// Opens node’s storage like [`open_storage`].  If `column_recompression` is
+/// set, columns whose compression settings changed are recompressed in the
+/// background, which is only worth it for the long running node.
+fn open_storage_impl(
+    home_dir: &Path,
+    unc_config: &mut UncConfig,
+    column_recompression: bool,
+) -> anyhow::Result<NodeStorage> {
     let migrator = migrations::Migrator::new(unc_config);
     let opener = NodeStorage::opener(
         home_dir,
@@ -84,7 +96,8 @@ pub fn open_storage(home_dir: &Path, unc_config: &mut UncConfig) -> anyhow::Resu
         &unc_config.config.store,
         unc_config.config.cold_store.as_ref(),
     )
-    .with_migrator(&migrator);
+    .with_migrator(&migrator)
+    .with_column_recompression(column_recompression);
     let storage = match opener.open() {
         Ok(storage) => Ok(storage),
         Err(StoreOpenerError::IO(err)) => {
@@ -204,6 +217,8 @@ fn get_split_store(config: &UncConfig, storage: &NodeStorage) -> anyhow::Result<
 pub struct UncNode {
     pub client: Addr<ClientActor>,
     pub view_client: Addr<ViewClientActor>,
+    /// Sender of the network messages to the ShardsManager.
+    pub shards_manager: Sender<ShardsManagerRequestFromNetwork>,
     pub arbiters: Vec<ArbiterHandle>,
     pub rpc_servers: Vec<(&'static str, actix_web::dev::ServerHandle)>,
     /// The cold_store_loop_handle will only be set if the cold store is configured.
@@ -231,7 +246,7 @@ pub fn start_with_config_and_synchronization(
     shutdown_signal: Option<broadcast::Sender<()>>,
     config_updater: Option<ConfigUpdater>,
 ) -> anyhow::Result<UncNode> {
-    let storage = open_storage(home_dir, &mut config)?;
+    let storage = open_storage_impl(home_dir, &mut config, true)?;
     let db_metrics_arbiter = if config.client_config.enable_statistics_export {
         let period = config.client_config.log_summary_period;
         let db_metrics_arbiter_handle = spawn_db_metrics_loop(&storage, period)?;
@@ -423,6 +438,7 @@ pub fn start_with_config_and_synchronization(
     Ok(UncNode {
         client: client_actor,
         view_client,
+        shards_manager: shards_manager_adapter.as_sender(),
         rpc_servers,
         arbiters,
         cold_store_loop_handle,
@@ -53,6 +53,33 @@ pub(crate) static POSTPONED_RECEIPTS_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static CONGESTION_DELAYED_RECEIPTS_GAS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "unc_congestion_delayed_receipts_gas",
+        "The gas of the delayed receipts, as reported in the congestion info of the shard",
+        &["shard_id"],
+    )
+    .unwrap()
+});
+
+pub(crate) static CONGESTION_BUFFERED_RECEIPTS_GAS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "unc_congestion_buffered_receipts_gas",
+        "The gas of the outgoing buffered receipts, as reported in the congestion info of the shard",
+        &["shard_id"],
+    )
+    .unwrap()
+});
+
+pub(crate) static CONGESTION_RECEIPT_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "unc_congestion_receipt_bytes",
+        "The size of the delayed and buffered receipts, as reported in the congestion info of the shard",
+        &["shard_id"],
+    )
+    .unwrap()
+});
+
 pub(crate) static PREPARE_TX_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
     try_create_histogram_vec(
         "unc_prepare_tx_size",
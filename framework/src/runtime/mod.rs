@@ -16,9 +16,12 @@ use unc_chain_configs::{
 };
 use unc_crypto::PublicKey;
 use unc_epoch_manager::{EpochManagerAdapter, EpochManagerHandle};
-use unc_parameters::{ActionCosts, ExtCosts, RuntimeConfigStore};
+use unc_parameters::{ActionCosts, ExtCosts, RuntimeConfig, RuntimeConfigStore};
 use unc_pool::types::PoolIterator;
 use unc_primitives::account::{AccessKey, Account};
+use unc_primitives::congestion_info::{
+    BlockCongestionInfo, CongestionControl, ShardAcceptsTransactions,
+};
 use unc_primitives::errors::{InvalidTxError, RuntimeError, StorageError};
 use unc_primitives::hash::{hash, CryptoHash};
 use unc_primitives::receipt::{DelayedReceiptIndices, Receipt};
@@ -29,11 +32,12 @@ use unc_primitives::shard_layout::{
 };
 use unc_primitives::state_part::PartId;
 use unc_primitives::transaction::SignedTransaction;
-use unc_primitives::trie_key::TrieKey;
+use unc_primitives::trie_key::{col, TrieKey};
 use unc_primitives::types::{
     AccountId, Balance, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, Gas, MerkleHash,
     ShardId, StateChangeCause, StateChangesForResharding, StateRoot, StateRootNode,
 };
+use unc_primitives::utils::index_to_bytes;
 use unc_primitives::version::ProtocolVersion;
 use unc_primitives::views::{
     AccessKeyInfoView, CallResult, ChipView, QueryRequest, QueryResponse, QueryResponseKind,
@@ -44,17 +48,17 @@ use unc_store::flat::FlatStorageManager;
 use unc_store::metadata::DbKind;
 use unc_store::{
     ApplyStatePartResult, DBCol, ShardTries, StateSnapshotConfig, Store,
-    StoreCompiledContractCache, Trie, TrieConfig, WrappedTrieChanges, COLD_HEAD_KEY,
+    StoreCompiledContractCache, Trie, TrieConfig, TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY,
 };
-use unc_vm_runner::logic::CompiledContractCache;
-use unc_vm_runner::precompile_contract;
+use unc_vm_runner::logic::{CompiledContractCache, Config as VMConfig};
 use unc_vm_runner::ContractCode;
+use unc_vm_runner::{get_contract_cache_key, precompile_contract};
 
 use node_runtime::{
     validate_transaction, verify_and_charge_transaction, ApplyState, Runtime,
     ValidatorAccountsUpdate,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -134,7 +138,12 @@ impl NightshadeRuntime {
 
         let runtime = Runtime::new();
         let trie_viewer = TrieViewer::new(trie_viewer_state_size_limit, max_gas_burnt_view);
-        let flat_storage_manager = FlatStorageManager::new(store.clone());
+        let flat_storage_history_retention = (trie_config.flat_storage_history_num_epochs > 0)
+            .then(|| trie_config.flat_storage_history_num_epochs * genesis_config.epoch_length);
+        let flat_storage_manager = FlatStorageManager::new_with_history_retention(
+            store.clone(),
+            flat_storage_history_retention,
+        );
         let shard_uids: Vec<_> = genesis_config.shard_layout.shard_uids().collect();
         let tries = ShardTries::new(
             store.clone(),
@@ -260,6 +269,36 @@ impl NightshadeRuntime {
         Ok(ShardUId { version: shard_version, shard_id: shard_id as u32 })
     }
 
+    /// Returns a trie update for serving view queries at the given block.
+    /// If flat state history covers the height and the block is on the
+    /// canonical chain, the state is read from the history, which keeps
+    /// working after trie nodes of old blocks are garbage collected.
+    fn new_trie_update_view_at_block(
+        &self,
+        shard_uid: ShardUId,
+        state_root: StateRoot,
+        block_hash: &CryptoHash,
+        block_height: BlockHeight,
+    ) -> TrieUpdate {
+        // Checking the history bounds is an in-memory operation, so the
+        // canonical chain lookup is only paid for when history can be used.
+        if !self.get_flat_storage_manager().history_covers_height(shard_uid, block_height) {
+            return self.tries.new_trie_update_view(shard_uid, state_root);
+        }
+        let canonical_hash: Option<CryptoHash> =
+            self.store.get_ser(DBCol::BlockHeight, &index_to_bytes(block_height)).ok().flatten();
+        if canonical_hash.as_ref() == Some(block_hash) {
+            self.tries.new_trie_update_view_at_height(
+                shard_uid,
+                state_root,
+                block_hash,
+                block_height,
+            )
+        } else {
+            self.tries.new_trie_update_view(shard_uid, state_root)
+        }
+    }
+
     fn account_id_to_shard_uid(
         &self,
         account_id: &AccountId,
@@ -290,6 +329,7 @@ impl NightshadeRuntime {
             gas_price,
             challenges_result,
             random_seed,
+            congestion_info,
         } = block;
         let ApplyChunkShardContext {
             shard_id,
@@ -404,6 +444,7 @@ impl NightshadeRuntime {
             block_height,
             prev_block_hash: *prev_block_hash,
             block_hash,
+            shard_id,
             epoch_id,
             epoch_height,
             gas_price,
@@ -419,6 +460,7 @@ impl NightshadeRuntime {
                 is_first_block_of_version,
                 is_first_block_with_chunk_of_version,
             },
+            congestion_info,
         };
 
         let instant = Instant::now();
@@ -463,6 +505,17 @@ impl NightshadeRuntime {
         if let Some(metrics) = apply_result.metrics {
             metrics.report(&shard_label);
         }
+        if let Some(congestion_info) = apply_result.congestion_info {
+            metrics::CONGESTION_DELAYED_RECEIPTS_GAS
+                .with_label_values(&[&shard_label])
+                .set(congestion_info.delayed_receipts_gas().try_into().unwrap_or(i64::MAX));
+            metrics::CONGESTION_BUFFERED_RECEIPTS_GAS
+                .with_label_values(&[&shard_label])
+                .set(congestion_info.buffered_receipts_gas().try_into().unwrap_or(i64::MAX));
+            metrics::CONGESTION_RECEIPT_BYTES
+                .with_label_values(&[&shard_label])
+                .set(congestion_info.receipt_bytes().try_into().unwrap_or(i64::MAX));
+        }
 
         let total_balance_burnt = apply_result
             .stats
@@ -493,6 +546,7 @@ impl NightshadeRuntime {
             total_balance_burnt,
             proof: apply_result.proof,
             processed_delayed_receipts: apply_result.processed_delayed_receipts,
+            congestion_info: apply_result.congestion_info,
         };
 
         Ok(result)
@@ -503,39 +557,35 @@ impl NightshadeRuntime {
         epoch_id: &EpochId,
         contract_codes: Vec<ContractCode>,
     ) -> Result<(), Error> {
-        let _span = tracing::debug_span!(
-            target: "runtime",
-            "precompile_contracts",
-            num_contracts = contract_codes.len())
-        .entered();
         let protocol_version = self.epoch_manager.get_epoch_protocol_version(epoch_id)?;
         let runtime_config = self.runtime_config_store.get_config(protocol_version);
-        let compiled_contract_cache: Option<Box<dyn CompiledContractCache>> =
-            Some(Box::new(StoreCompiledContractCache::new(&self.store)));
-        // Execute precompile_contract in parallel but prevent it from using more than half of all
-        // threads so that node will still function normally.
-        rayon::scope(|scope| {
-            let (slot_sender, slot_receiver) = std::sync::mpsc::channel();
-            // Use up-to half of the threads for the compilation.
-            let max_threads = std::cmp::max(rayon::current_num_threads() / 2, 1);
-            for _ in 0..max_threads {
-                slot_sender.send(()).expect("both sender and receiver are owned here");
-            }
-            for code in contract_codes {
-                slot_receiver.recv().expect("could not receive a slot to compile contract");
-                let contract_cache = compiled_contract_cache.as_deref();
-                let slot_sender = slot_sender.clone();
-                scope.spawn(move |_| {
-                    precompile_contract(&code, &runtime_config.wasm_config, contract_cache).ok();
-                    // If this fails, it just means there won't be any more attempts to recv the
-                    // slots
-                    let _ = slot_sender.send(());
-                });
-            }
-        });
+        precompile_contracts(
+            &self.store,
+            &runtime_config.wasm_config,
+            contract_codes.into_iter().map(Ok::<_, Error>),
+        )?;
         Ok(())
     }
 
+    /// Compiles the distinct contracts deployed in the given state, including global contracts,
+    /// for the VM config of `protocol_version` and stores the results in the compiled contract
+    /// cache. Returns the compiled contract cache keys of the contracts.
+    pub fn precompile_state_contracts(
+        &self,
+        protocol_version: ProtocolVersion,
+        shard_uid: ShardUId,
+        state_root: StateRoot,
+    ) -> Result<Vec<CryptoHash>, Error> {
+        let runtime_config = self.runtime_config_store.get_config(protocol_version);
+        precompile_state_contracts(
+            &self.store,
+            &self.tries,
+            &runtime_config.wasm_config,
+            shard_uid,
+            state_root,
+        )
+    }
+
     fn get_gc_stop_height_impl(&self, block_hash: &CryptoHash) -> Result<BlockHeight, Error> {
         let epoch_manager = self.epoch_manager.read();
         // an epoch must have a first block.
@@ -627,6 +677,74 @@ fn format_total_gas_burnt(gas: Gas) -> String {
     format!("{:.0}", ((gas as f64) / 1e14).ceil() * 100.0)
 }
 
+/// Compiles `contract_codes` and stores the results in the compiled contract cache. Contracts
+/// are taken from the iterator only when there is a free thread to compile them, so at most as
+/// many contracts as there are compilation threads are held in memory at a time. Returns the
+/// compiled contract cache keys of the contracts, or the first error of the iterator.
+fn precompile_contracts<E>(
+    store: &Store,
+    config: &VMConfig,
+    contract_codes: impl Iterator<Item = Result<ContractCode, E>>,
+) -> Result<Vec<CryptoHash>, E> {
+    let _span = tracing::debug_span!(target: "runtime", "precompile_contracts").entered();
+    let compiled_contract_cache: Option<Box<dyn CompiledContractCache>> =
+        Some(Box::new(StoreCompiledContractCache::new(store)));
+    let mut keys = vec![];
+    // Execute precompile_contract in parallel but prevent it from using more than half of all
+    // threads so that node will still function normally. The contracts are taken from the
+    // iterator on the current thread, so it doesn't need to be `Send`.
+    rayon::in_place_scope(|scope| -> Result<(), E> {
+        let (slot_sender, slot_receiver) = std::sync::mpsc::channel();
+        // Use up-to half of the threads for the compilation.
+        let max_threads = std::cmp::max(rayon::current_num_threads() / 2, 1);
+        for _ in 0..max_threads {
+            slot_sender.send(()).expect("both sender and receiver are owned here");
+        }
+        for code in contract_codes {
+            slot_receiver.recv().expect("could not receive a slot to compile contract");
+            let code = code?;
+            keys.push(get_contract_cache_key(&code, config));
+            let contract_cache = compiled_contract_cache.as_deref();
+            let slot_sender = slot_sender.clone();
+            scope.spawn(move |_| {
+                precompile_contract(&code, config, contract_cache).ok();
+                // If this fails, it just means there won't be any more attempts to recv the
+                // slots
+                let _ = slot_sender.send(());
+            });
+        }
+        Ok(())
+    })?;
+    tracing::debug!(target: "runtime", num_contracts = keys.len(), "Precompiled contracts");
+    Ok(keys)
+}
+
+/// Compiles the distinct contracts deployed in the given state, including global contracts,
+/// while walking the trie. See [`precompile_contracts`].
+fn precompile_state_contracts(
+    store: &Store,
+    tries: &ShardTries,
+    config: &VMConfig,
+    shard_uid: ShardUId,
+    state_root: StateRoot,
+) -> Result<Vec<CryptoHash>, Error> {
+    let trie = tries.get_view_trie_for_shard(shard_uid, state_root);
+    let mut iters = vec![];
+    for prefix in [col::CONTRACT_CODE, col::GLOBAL_CONTRACT_CODE] {
+        let mut iter = trie.iter()?;
+        iter.seek_prefix([prefix])?;
+        iters.push(iter);
+    }
+    // Only the hashes of the contracts seen so far are kept to skip duplicates.
+    let mut code_hashes = HashSet::new();
+    let contract_codes = iters
+        .into_iter()
+        .flatten()
+        .map(|item| item.map(|(_, code)| ContractCode::new(code, None)))
+        .filter(|code| code.as_ref().map_or(true, |code| code_hashes.insert(*code.hash())));
+    precompile_contracts(store, config, contract_codes).map_err(Into::into)
+}
+
 impl RuntimeAdapter for NightshadeRuntime {
     fn store(&self) -> &Store {
         &self.store
@@ -730,6 +848,7 @@ impl RuntimeAdapter for NightshadeRuntime {
         shard_id: ShardId,
         state_root: StateRoot,
         next_block_height: BlockHeight,
+        congestion_info: &BlockCongestionInfo,
         pool_iterator: &mut dyn PoolIterator,
         chain_validate: &mut dyn FnMut(&SignedTransaction) -> bool,
         current_protocol_version: ProtocolVersion,
@@ -746,12 +865,29 @@ impl RuntimeAdapter for NightshadeRuntime {
         // Total amount of gas burnt for converting transactions towards receipts.
         let mut total_gas_burnt = 0;
         let mut total_size = 0u64;
-        // TODO: Update gas limit for transactions
-        let transactions_gas_limit = gas_limit / 2;
         let mut transactions = vec![];
         let mut num_checked_transactions = 0;
 
         let runtime_config = self.runtime_config_store.get_config(current_protocol_version);
+        let shard_layout = self.epoch_manager.get_shard_layout(epoch_id)?;
+        let congestion_control = |shard_id: ShardId| {
+            congestion_info.get(&shard_id).map(|info| {
+                CongestionControl::new(
+                    runtime_config.congestion_control_config,
+                    info.congestion_info,
+                    info.missed_chunks_count,
+                )
+            })
+        };
+
+        // TODO: Update gas limit for transactions
+        let mut transactions_gas_limit = gas_limit / 2;
+        // A congested shard converts fewer transactions to receipts, to let its delayed
+        // receipts drain.
+        if let Some(own_congestion_control) = congestion_control(shard_id) {
+            transactions_gas_limit =
+                transactions_gas_limit.min(own_congestion_control.process_tx_limit());
+        }
 
         // To avoid limiting the throughput of the network, we want to include enough receipts to
         // saturate the capacity of the chunk even in case when all of these receipts end up using
@@ -789,6 +925,8 @@ impl RuntimeAdapter for NightshadeRuntime {
                 + runtime_config.wasm_config.ext_costs.gas_cost(ExtCosts::storage_read_value_byte))
             / 3;
 
+        // The pool iterator serves transactions with higher priority fees first, so they are the
+        // ones included when the chunk runs out of space.
         while total_gas_burnt < transactions_gas_limit
             && total_size < size_limit
             && transactions.len() < new_receipt_count_limit
@@ -803,6 +941,17 @@ impl RuntimeAdapter for NightshadeRuntime {
                         continue;
                     }
 
+                    // Transactions to congested shards would only add to their congestion.
+                    let receiver_shard =
+                        account_id_to_shard_id(&tx.transaction.receiver_id, &shard_layout);
+                    if let Some(ShardAcceptsTransactions::No(reason)) =
+                        congestion_control(receiver_shard)
+                            .map(|control| control.shard_accepts_transactions())
+                    {
+                        tracing::trace!(target: "runtime", tx=?tx.get_hash(), ?reason, "discarding transaction to a congested shard");
+                        continue;
+                    }
+
                     // Verifying the validity of the transaction based on the current state.
                     match verify_and_charge_transaction(
                         runtime_config,
@@ -925,10 +1074,30 @@ impl RuntimeAdapter for NightshadeRuntime {
         epoch_id: &EpochId,
         request: &QueryRequest,
     ) -> Result<QueryResponse, unc_chain::unc_chain_primitives::error::QueryError> {
+        let state_update =
+            self.new_trie_update_view_at_block(shard_uid, *state_root, block_hash, block_height);
+        // Flat state history serves point lookups and iteration over keys,
+        // but proofs need trie nodes, which are gone once the block is garbage
+        // collected. Callers pass `Trie::EMPTY_ROOT` if the state root of a
+        // garbage collected block is unknown.
+        if let QueryRequest::ViewState { include_proof: true, .. } = request {
+            if state_update.trie().has_flat_storage_chunk_view()
+                && (state_root == &Trie::EMPTY_ROOT
+                    || state_update.trie().retrieve_root_node().is_err())
+            {
+                return Err(
+                    unc_chain::unc_chain_primitives::error::QueryError::UnsupportedHistoricalQuery {
+                        request_kind: "view_state with proof".to_string(),
+                        block_height,
+                        block_hash: *block_hash,
+                    },
+                );
+            }
+        }
         match request {
             QueryRequest::ViewAccount { account_id } => {
                 let account =
-                    self.view_account(&shard_uid, *state_root, account_id).map_err(|err| {
+                    self.trie_viewer.view_account(&state_update, account_id).map_err(|err| {
                         unc_chain::unc_chain_primitives::error::QueryError::from_view_account_error(
                             err,
                             block_height,
@@ -943,7 +1112,8 @@ impl RuntimeAdapter for NightshadeRuntime {
             }
             QueryRequest::ViewCode { account_id } => {
                 let contract_code = self
-                    .view_contract_code(&shard_uid,  *state_root, account_id)
+                    .trie_viewer
+                    .view_contract_code(&state_update, account_id)
                     .map_err(|err| unc_chain::unc_chain_primitives::error::QueryError::from_view_contract_code_error(err, block_height, *block_hash))?;
                 Ok(QueryResponse {
                     kind: QueryResponseKind::ViewCode(contract_code.into()),
@@ -994,13 +1164,8 @@ impl RuntimeAdapter for NightshadeRuntime {
             }
             QueryRequest::ViewState { account_id, prefix, include_proof } => {
                 let view_state_result = self
-                    .view_state(
-                        &shard_uid,
-                        *state_root,
-                        account_id,
-                        prefix.as_ref(),
-                        *include_proof,
-                    )
+                    .trie_viewer
+                    .view_state(&state_update, account_id, prefix.as_ref(), *include_proof)
                     .map_err(|err| {
                         unc_chain::unc_chain_primitives::error::QueryError::from_view_state_error(
                             err,
@@ -1016,7 +1181,7 @@ impl RuntimeAdapter for NightshadeRuntime {
             }
             QueryRequest::ViewAccessKeyList { account_id } => {
                 let access_key_list =
-                    self.view_access_keys(&shard_uid, *state_root, account_id).map_err(|err| {
+                    self.trie_viewer.view_access_keys(&state_update, account_id).map_err(|err| {
                         unc_chain::unc_chain_primitives::error::QueryError::from_view_access_key_error(
                             err,
                             block_height,
@@ -1039,7 +1204,7 @@ impl RuntimeAdapter for NightshadeRuntime {
             }
             QueryRequest::ViewChipList { account_id } => {
                 let chip_list_result =
-                    self.view_chip_list(&shard_uid, *state_root, account_id).map_err(|err| {
+                    self.trie_viewer.view_chip_list(&state_update, account_id).map_err(|err| {
                         unc_chain::unc_chain_primitives::error::QueryError::from_view_chip_error(
                             err,
                             block_height,
@@ -1060,7 +1225,8 @@ impl RuntimeAdapter for NightshadeRuntime {
             }
             QueryRequest::ViewAccessKey { account_id, public_key } => {
                 let access_key = self
-                    .view_access_key(&shard_uid, *state_root, account_id, public_key)
+                    .trie_viewer
+                    .view_access_key(&state_update, account_id, public_key)
                     .map_err(|err| {
                         unc_chain::unc_chain_primitives::error::QueryError::from_view_access_key_error(
                             err,
@@ -1263,6 +1429,13 @@ impl RuntimeAdapter for NightshadeRuntime {
         Ok(ProtocolConfig { genesis_config, runtime_config })
     }
 
+    fn get_runtime_config(
+        &self,
+        protocol_version: ProtocolVersion,
+    ) -> Result<RuntimeConfig, Error> {
+        Ok(self.runtime_config_store.get_config(protocol_version).as_ref().clone())
+    }
+
     fn will_shard_layout_change_next_epoch(&self, parent_hash: &CryptoHash) -> Result<bool, Error> {
         let epoch_manager = self.epoch_manager.read();
         Ok(epoch_manager.will_shard_layout_change(parent_hash)?)
@@ -1271,6 +1444,49 @@ impl RuntimeAdapter for NightshadeRuntime {
     fn load_mem_tries_on_startup(&self, shard_uids: &[ShardUId]) -> Result<(), StorageError> {
         self.tries.load_mem_tries_for_enabled_shards(shard_uids)
     }
+
+    fn precompile_contracts_for_next_epoch(
+        &self,
+        block_hash: &CryptoHash,
+        state_roots: Vec<(ShardUId, StateRoot)>,
+    ) -> Result<(), Error> {
+        let epoch_id = self.epoch_manager.get_epoch_id(block_hash)?;
+        let next_epoch_id = self.epoch_manager.get_next_epoch_id(block_hash)?;
+        let protocol_version = self.epoch_manager.get_epoch_protocol_version(&epoch_id)?;
+        let next_protocol_version =
+            self.epoch_manager.get_epoch_protocol_version(&next_epoch_id)?;
+        let next_config = self.runtime_config_store.get_config(next_protocol_version).clone();
+        if self.runtime_config_store.get_config(protocol_version).wasm_config
+            == next_config.wasm_config
+        {
+            return Ok(());
+        }
+        info!(
+            target: "runtime",
+            protocol_version,
+            next_protocol_version,
+            "Precompiling contracts for the next protocol version"
+        );
+        let store = self.store.clone();
+        let tries = self.tries.clone();
+        std::thread::Builder::new()
+            .name("precompile_contracts".to_string())
+            .spawn(move || {
+                for (shard_uid, state_root) in state_roots {
+                    if let Err(err) = precompile_state_contracts(
+                        &store,
+                        &tries,
+                        &next_config.wasm_config,
+                        shard_uid,
+                        state_root,
+                    ) {
+                        error!(target: "runtime", ?shard_uid, ?err, "Failed to read contracts");
+                    }
+                }
+            })
+            .map_err(|err| Error::Other(err.to_string()))?;
+        Ok(())
+    }
 }
 
 impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
@@ -1311,7 +1527,8 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         epoch_info_provider: &dyn EpochInfoProvider,
         current_protocol_version: ProtocolVersion,
     ) -> Result<Vec<u8>, node_runtime::state_viewer::errors::CallFunctionError> {
-        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        let state_update =
+            self.new_trie_update_view_at_block(*shard_uid, state_root, block_hash, height);
         let view_state = ViewApplyState {
             block_height: height,
             prev_block_hash: *prev_block_hash,
@@ -16,7 +16,9 @@ use unc_crypto::{InMemorySigner, KeyType, Signer};
 use unc_o11y::testonly::init_test_logger;
 use unc_primitives::block::Tip;
 use unc_primitives::challenge::{ChallengesResult, SlashedValidator};
-use unc_primitives::transaction::{Action, DeleteAccountAction, PledgeAction, TransferAction};
+use unc_primitives::transaction::{
+    Action, DeleteAccountAction, DeployContractAction, PledgeAction, TransferAction,
+};
 use unc_primitives::types::{
     BlockHeightDelta, Nonce, ValidatorId, ValidatorInfoIdentifier, ValidatorKickoutReason,
 };
@@ -84,6 +86,7 @@ impl NightshadeRuntime {
                     gas_price,
                     challenges_result: challenges_result.clone(),
                     random_seed: CryptoHash::default(),
+                    congestion_info: Default::default(),
                 },
                 receipts,
                 transactions,
@@ -147,6 +150,22 @@ impl TestEnv {
         epoch_length: BlockHeightDelta,
         has_reward: bool,
         minimum_pledge_divisor: Option<u64>,
+    ) -> Self {
+        Self::new_with_trie_config(
+            validators,
+            epoch_length,
+            has_reward,
+            minimum_pledge_divisor,
+            Default::default(),
+        )
+    }
+
+    fn new_with_trie_config(
+        validators: Vec<Vec<AccountId>>,
+        epoch_length: BlockHeightDelta,
+        has_reward: bool,
+        minimum_pledge_divisor: Option<u64>,
+        trie_config: TrieConfig,
     ) -> Self {
         let (dir, opener) = NodeStorage::test_opener();
         let store = opener.open().unwrap().get_hot_store();
@@ -182,7 +201,7 @@ impl TestEnv {
             None,
             Some(RuntimeConfigStore::free()),
             DEFAULT_GC_NUM_EPOCHS_TO_KEEP,
-            Default::default(),
+            trie_config,
             StateSnapshotConfig {
                 state_snapshot_type: StateSnapshotType::EveryEpoch,
                 home_dir: PathBuf::from(dir.path()),
@@ -1416,6 +1435,99 @@ fn test_trie_and_flat_state_equality() {
     assert_eq!(state_value, view_state_value);
 }
 
+/// Check that queries at blocks covered by flat state history are served even
+/// if the state root is unknown, and only queries for proofs are rejected.
+#[test]
+fn test_query_from_flat_state_history() {
+    let validators: Vec<AccountId> = vec!["test1".parse().unwrap(), "test2".parse().unwrap()];
+    let trie_config = TrieConfig { flat_storage_history_num_epochs: 2, ..Default::default() };
+    let mut env =
+        TestEnv::new_with_trie_config(vec![validators.clone()], 4, false, None, trie_config);
+    let signer =
+        InMemorySigner::from_seed(validators[0].clone(), KeyType::ED25519, validators[0].as_ref());
+    let transfer_tx = SignedTransaction::from_actions(
+        4,
+        signer.account_id.clone(),
+        validators[1].clone(),
+        &signer as &dyn Signer,
+        vec![Action::Transfer(TransferAction { deposit: 10 })],
+        // runtime does not validate block history
+        CryptoHash::default(),
+    );
+    env.step_default(vec![transfer_tx]);
+    let mut state_roots = vec![env.state_roots[0]];
+    for _ in 2..=5 {
+        env.step_default(vec![]);
+        state_roots.push(env.state_roots[0]);
+    }
+
+    // The mock chain is canonical, record it the way the chain would.
+    let mut store_update = env.runtime.store.store_update();
+    for height in 1..=5 {
+        store_update
+            .set_ser(DBCol::BlockHeight, &index_to_bytes(height), &hash(&[height as u8]))
+            .unwrap();
+    }
+    store_update.commit().unwrap();
+    let shard_uid = env.epoch_manager.shard_id_to_uid(0, &EpochId::default()).unwrap();
+    let flat_storage =
+        env.runtime.get_flat_storage_manager().get_flat_storage_for_shard(shard_uid).unwrap();
+    flat_storage.update_flat_head(&hash(&[5]), true).unwrap();
+
+    let query = |height: BlockHeight, state_root: StateRoot, request: QueryRequest| {
+        env.runtime.query(
+            shard_uid,
+            &state_root,
+            height,
+            0,
+            &hash(&[height as u8 - 1]),
+            &hash(&[height as u8]),
+            &EpochId::default(),
+            &request,
+        )
+    };
+    let view_amount = |height: BlockHeight| {
+        let request = QueryRequest::ViewAccount { account_id: validators[1].clone() };
+        match query(height, Trie::EMPTY_ROOT, request).unwrap().kind {
+            QueryResponseKind::ViewAccount(account) => account.amount,
+            kind => panic!("unexpected response {kind:?}"),
+        }
+    };
+    assert_eq!(view_amount(1), TESTING_INIT_BALANCE - TESTING_INIT_PLEDGE);
+    assert_eq!(view_amount(5), TESTING_INIT_BALANCE - TESTING_INIT_PLEDGE + 10);
+
+    // Iteration queries read keys from history and return the same result as
+    // the trie while its nodes are still around.
+    for request in [
+        QueryRequest::ViewState {
+            account_id: validators[1].clone(),
+            prefix: Vec::<u8>::new().into(),
+            include_proof: false,
+        },
+        QueryRequest::ViewAccessKeyList { account_id: validators[1].clone() },
+        QueryRequest::ViewChipList { account_id: validators[1].clone() },
+    ] {
+        let from_history = query(1, Trie::EMPTY_ROOT, request.clone()).unwrap();
+        let from_trie = query(1, state_roots[0], request).unwrap();
+        assert_eq!(from_history.kind, from_trie.kind);
+    }
+
+    // Proofs can't be produced without trie nodes.
+    let request = QueryRequest::ViewState {
+        account_id: validators[1].clone(),
+        prefix: Vec::<u8>::new().into(),
+        include_proof: true,
+    };
+    assert!(matches!(
+        query(1, Trie::EMPTY_ROOT, request.clone()),
+        Err(unc_chain::unc_chain_primitives::error::QueryError::UnsupportedHistoricalQuery {
+            block_height: 1,
+            ..
+        })
+    ));
+    query(1, state_roots[0], request).unwrap();
+}
+
 /// Check that mainnet genesis hash still matches, to make sure that we're still backwards compatible.
 #[test]
 fn test_genesis_hash() {
@@ -1452,3 +1564,42 @@ fn test_genesis_hash() {
         epoch_info.block_producers_settlement()
     );
 }
+
+/// Check that a contract deployed to several accounts is compiled once when walking the state.
+#[test]
+fn test_precompile_state_contracts() {
+    let validators: Vec<AccountId> = vec!["test1".parse().unwrap(), "test2".parse().unwrap()];
+    let mut env = TestEnv::new(vec![validators.clone()], 4, false);
+    // The smallest valid wasm module.
+    let code = b"\0asm\x01\0\0\0".to_vec();
+    let transactions = validators
+        .iter()
+        .map(|account_id| {
+            let signer = InMemorySigner::from_seed(
+                account_id.clone(),
+                KeyType::ED25519,
+                account_id.as_ref(),
+            );
+            SignedTransaction::from_actions(
+                1,
+                account_id.clone(),
+                account_id.clone(),
+                &signer,
+                vec![Action::DeployContract(DeployContractAction { code: code.clone() })],
+                CryptoHash::default(),
+            )
+        })
+        .collect();
+    env.step_default(transactions);
+
+    let shard_uid = env.runtime.get_shard_uid_from_epoch_id(0, &env.head.epoch_id).unwrap();
+    let protocol_version = unc_primitives::version::PROTOCOL_VERSION;
+    let keys = env
+        .runtime
+        .precompile_state_contracts(protocol_version, shard_uid, env.state_roots[0])
+        .unwrap();
+    let config = &env.runtime.runtime_config_store.get_config(protocol_version).wasm_config;
+    let key = get_contract_cache_key(&ContractCode::new(code, None), config);
+    assert_eq!(keys, vec![key]);
+    assert!(StoreCompiledContractCache::new(&env.runtime.store).has(&key).unwrap());
+}
@@ -256,6 +256,7 @@ impl GenesisBuilder {
                     0,
                     self.genesis.config.gas_limit,
                     0,
+                    chunk_header.congestion_info(),
                 ),
             );
         }
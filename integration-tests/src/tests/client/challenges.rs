@@ -392,6 +392,7 @@ fn test_verify_chunk_invalid_state_challenge() {
         &receipts,
         last_block.chunks()[0].prev_outgoing_receipts_root(),
         CryptoHash::default(),
+        None,
         &validator_signer,
         &mut rs,
         PROTOCOL_VERSION,
@@ -627,6 +627,7 @@ fn test_flat_storage_upgrade() {
         actions: vec![],
         nonce: 0,
         block_hash: CryptoHash::default(),
+        priority_fee: None,
     };
 
     // Write key-value pair to state.
@@ -0,0 +1,233 @@
+use assert_matches::assert_matches;
+use framework::config::GenesisExt;
+use framework::test_utils::TestEnvNightshadeSetupExt;
+use std::collections::HashSet;
+use unc_chain::{Chain, ChainGenesis, ChainStoreAccess, Error};
+use unc_chain_configs::Genesis;
+use unc_client::test_utils::TestEnv;
+use unc_client::{LightClient, ProcessTxResponse};
+use unc_crypto::{InMemorySigner, KeyType, Signer};
+use unc_network::types::LightClientExecutionProof;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::merkle::merklize;
+use unc_primitives::transaction::SignedTransaction;
+use unc_primitives::types::{AccountId, BlockHeightDelta, TransactionOrReceiptId};
+use unc_primitives::views::validator_power_and_pledge_view::ValidatorPowerAndPledgeView;
+use unc_primitives::views::{ExecutionOutcomeWithIdView, LightClientBlockView};
+
+const EPOCH_LENGTH: BlockHeightDelta = 5;
+
+fn setup_env() -> TestEnv {
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap()], 1);
+    genesis.config.epoch_length = EPOCH_LENGTH;
+    TestEnv::builder(ChainGenesis::test())
+        .real_epoch_managers(&genesis.config)
+        .nightshade_runtimes(&genesis)
+        .build()
+}
+
+fn produce_blocks(env: &mut TestEnv, count: BlockHeightDelta) {
+    let height = env.clients[0].chain.head().unwrap().height;
+    for height in height + 1..=height + count {
+        env.produce_block(0, height);
+    }
+}
+
+fn new_light_client(env: &TestEnv) -> LightClient {
+    let client = &env.clients[0];
+    LightClient::new(client.chain.genesis(), client.epoch_manager.as_ref()).unwrap()
+}
+
+/// Light client block following `last_block_hash`, as served by a full node to light peers.
+fn next_light_client_block(
+    env: &TestEnv,
+    last_block_hash: &CryptoHash,
+) -> Option<LightClientBlockView> {
+    let chain = &env.clients[0].chain;
+    let last_block_header = chain.get_block_header(last_block_hash).unwrap();
+    let head = chain.head().unwrap();
+    if last_block_header.epoch_id() == &head.epoch_id
+        || last_block_header.next_epoch_id() == &head.epoch_id
+    {
+        let head_header = chain.get_block_header(&head.last_block_hash).unwrap();
+        let block = Chain::create_light_client_block(
+            &head_header,
+            env.clients[0].epoch_manager.as_ref(),
+            chain.chain_store(),
+        )
+        .unwrap();
+        (block.inner_lite.height > last_block_header.height()).then_some(block)
+    } else {
+        let epoch_id = &last_block_header.next_epoch_id().0;
+        chain.chain_store().get_epoch_light_client_block(epoch_id).ok().map(|b| (*b).clone())
+    }
+}
+
+/// Applies the light client blocks until the light client catches up with the chain.
+fn follow_chain(env: &TestEnv, light_client: &mut LightClient) {
+    while let Some(block) = next_light_client_block(env, &light_client.head().hash()) {
+        assert!(light_client.apply_block(block).unwrap());
+    }
+}
+
+/// Proof of the outcome against the light client head, as served by a full node to light peers.
+fn execution_proof(
+    env: &TestEnv,
+    id: &CryptoHash,
+    account_id: &AccountId,
+    light_client_head: &CryptoHash,
+) -> LightClientExecutionProof {
+    let client = &env.clients[0];
+    let outcome = client.chain.get_execution_outcome(id).unwrap();
+    let epoch_id = client.chain.get_block(&outcome.block_hash).unwrap().header().epoch_id().clone();
+    let shard_id = client.epoch_manager.account_id_to_shard_id(account_id, &epoch_id).unwrap();
+    let (block_hash, shard_id) = client
+        .chain
+        .get_next_block_hash_with_new_chunk(&outcome.block_hash, shard_id)
+        .unwrap()
+        .unwrap();
+    let outcome_roots = client
+        .chain
+        .get_block(&block_hash)
+        .unwrap()
+        .chunks()
+        .iter()
+        .map(|header| header.prev_outcome_root())
+        .collect::<Vec<_>>();
+    let mut outcome_proof = ExecutionOutcomeWithIdView::from(outcome);
+    outcome_proof.block_hash = block_hash;
+    LightClientExecutionProof {
+        outcome_proof,
+        outcome_root_proof: merklize(&outcome_roots).1[shard_id as usize].clone(),
+        block_header_lite: client.chain.get_block_header(&block_hash).unwrap().into(),
+        block_proof: client.chain.get_block_proof(&block_hash, light_client_head).unwrap(),
+    }
+}
+
+/// The light client follows the chain from genesis across the epochs, switching to
+/// the block producers announced in `next_bps` of the last block of each epoch.
+#[test]
+fn test_light_client_follows_epochs() {
+    let mut env = setup_env();
+    produce_blocks(&mut env, 4 * EPOCH_LENGTH);
+    let mut light_client = new_light_client(&env);
+    let mut epochs = HashSet::new();
+    while let Some(block) = next_light_client_block(&env, &light_client.head().hash()) {
+        assert!(light_client.apply_block(block.clone()).unwrap());
+        // The same block received again is ignored.
+        assert!(!light_client.apply_block(block).unwrap());
+        epochs.insert(light_client.head().inner_lite.epoch_id);
+    }
+    assert!(epochs.len() >= 3, "{epochs:?}");
+    assert_eq!(
+        light_client.head().inner_lite.epoch_id,
+        env.clients[0].chain.head().unwrap().epoch_id.0
+    );
+}
+
+#[test]
+fn test_light_client_rejects_invalid_approvals() {
+    let mut env = setup_env();
+    produce_blocks(&mut env, 4 * EPOCH_LENGTH);
+    let mut light_client = new_light_client(&env);
+    let block = next_light_client_block(&env, &light_client.head().hash()).unwrap();
+    assert!(block.approvals_after_next.iter().any(Option::is_some));
+
+    // Approvals signed with a key other than the one of the block producer.
+    let mut forged = block.clone();
+    let signer = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "forged");
+    for approval in forged.approvals_after_next.iter_mut().flatten() {
+        *approval = Box::new(signer.sign(b"forged"));
+    }
+    assert_matches!(light_client.apply_block(forged), Err(Error::InvalidSignature));
+
+    // Valid approvals of a different block.
+    let mut forged = block.clone();
+    forged.inner_lite.timestamp_nanosec += 1;
+    assert_matches!(light_client.apply_block(forged), Err(Error::InvalidSignature));
+
+    // No approvals.
+    let mut forged = block.clone();
+    forged.approvals_after_next.iter_mut().for_each(|approval| *approval = None);
+    assert_matches!(light_client.apply_block(forged), Err(Error::NotEnoughApprovals));
+
+    // The rejected blocks didn't affect the light client.
+    assert!(light_client.apply_block(block).unwrap());
+}
+
+#[test]
+fn test_light_client_rejects_forged_next_bps() {
+    let mut env = setup_env();
+    produce_blocks(&mut env, 4 * EPOCH_LENGTH);
+    let mut light_client = new_light_client(&env);
+    // The first block of the next epoch has to announce the block producers of the epoch after.
+    let block = loop {
+        let block = next_light_client_block(&env, &light_client.head().hash()).unwrap();
+        if block.inner_lite.epoch_id == light_client.head().inner_lite.next_epoch_id {
+            break block;
+        }
+        assert!(light_client.apply_block(block).unwrap());
+    };
+
+    let mut forged = block.clone();
+    forged.next_bps = None;
+    assert_matches!(light_client.apply_block(forged), Err(Error::InvalidNextBPHash));
+
+    let mut forged = block.clone();
+    let ValidatorPowerAndPledgeView::V1(bp) = &mut forged.next_bps.as_mut().unwrap()[0];
+    bp.pledge += 1;
+    assert_matches!(light_client.apply_block(forged), Err(Error::InvalidNextBPHash));
+
+    let head = light_client.head().clone();
+    assert!(light_client.apply_block(block.clone()).unwrap());
+    assert_eq!(light_client.head().inner_lite.epoch_id, head.inner_lite.next_epoch_id);
+    assert_eq!(light_client.head().inner_lite.next_epoch_id, block.inner_lite.next_epoch_id);
+}
+
+#[test]
+fn test_light_client_rejects_forged_proofs() {
+    let mut env = setup_env();
+    let account_id: AccountId = "test0".parse().unwrap();
+    let signer = InMemorySigner::from_seed(account_id.clone(), KeyType::ED25519, "test0");
+    let genesis_hash = *env.clients[0].chain.genesis().hash();
+    let tx = SignedTransaction::send_money(
+        1,
+        account_id.clone(),
+        account_id.clone(),
+        &signer,
+        100,
+        genesis_hash,
+    );
+    let tx_hash = tx.get_hash();
+    assert_eq!(env.clients[0].process_tx(tx, false, false), ProcessTxResponse::ValidTx);
+    produce_blocks(&mut env, 4 * EPOCH_LENGTH);
+
+    let mut light_client = new_light_client(&env);
+    follow_chain(&env, &mut light_client);
+    let head_hash = light_client.head().hash();
+    let proof = execution_proof(&env, &tx_hash, &account_id, &head_hash);
+    let id =
+        TransactionOrReceiptId::Transaction { transaction_hash: tx_hash, sender_id: account_id };
+
+    // Proofs which were not requested are ignored.
+    light_client.apply_proof(proof.clone()).unwrap();
+    assert_eq!(light_client.get_proof(&id), None);
+
+    let request = light_client.proof_request(id.clone());
+    assert_eq!(request.light_client_head, head_hash);
+
+    let mut forged = proof.clone();
+    forged.outcome_proof.outcome.logs.push("forged".to_string());
+    assert_matches!(light_client.apply_proof(forged), Err(Error::InvalidOutcomesProof));
+
+    let mut forged = proof.clone();
+    forged.block_header_lite.inner_lite.height += 1;
+    assert_matches!(light_client.apply_proof(forged), Err(Error::InvalidOutcomesProof));
+
+    let mut forged = proof.clone();
+    forged.block_proof = env.clients[0].chain.get_block_proof(&genesis_hash, &head_hash).unwrap();
+    assert_matches!(light_client.apply_proof(forged), Err(Error::InvalidBlockMerkleRoot));
+
+    light_client.apply_proof(proof.clone()).unwrap();
+    assert_eq!(light_client.get_proof(&id), Some(proof));
+}
@@ -6,6 +6,7 @@ mod cold_storage;
 #[cfg(feature = "new_epoch_sync")]
 mod epoch_sync;
 mod flat_storage;
+mod light_client;
 mod process_blocks;
 mod resharding;
 mod runtimes;
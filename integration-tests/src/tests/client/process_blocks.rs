@@ -977,6 +977,7 @@ fn client_sync_headers() {
                             last_block: Some(BlockInfo { height: 5, hash: hash(&[5]) }),
                             tracked_shards: vec![],
                             archival: false,
+                            light: false,
                         },
                     },
                     received_bytes_per_sec: 0,
@@ -1030,6 +1031,7 @@ fn test_process_invalid_tx() {
             receiver_id: "test".parse().unwrap(),
             block_hash: *env.clients[0].chain.genesis().hash(),
             actions: vec![],
+            priority_fee: None,
         },
     );
     for i in 1..12 {
@@ -1048,6 +1050,7 @@ fn test_process_invalid_tx() {
             receiver_id: "test".parse().unwrap(),
             block_hash: hash(&[1]),
             actions: vec![],
+            priority_fee: None,
         },
     );
     assert_eq!(
@@ -1200,6 +1203,7 @@ fn test_bad_orphan() {
             match &mut chunk.inner {
                 ShardChunkHeaderInner::V1(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
                 ShardChunkHeaderInner::V2(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
+                ShardChunkHeaderInner::V3(inner) => inner.prev_outcome_root = CryptoHash([1; 32]),
             }
             chunk.hash = ShardChunkHeaderV3::compute_hash(&chunk.inner);
         }
@@ -61,6 +61,7 @@ fn test_uncvm_upgrade() {
 
         nonce: 0,
         block_hash: CryptoHash::default(),
+        priority_fee: None,
     };
 
     // Run the transaction & collect the logs.
@@ -155,6 +155,7 @@ impl RuntimeUser {
             block_height: 1,
             prev_block_hash: Default::default(),
             block_hash: Default::default(),
+            shard_id: Default::default(),
             block_timestamp: 0,
             epoch_height: 0,
             gas_price: MIN_GAS_PRICE,
@@ -167,6 +168,7 @@ impl RuntimeUser {
             is_new_chunk: true,
             migration_data: Arc::new(MigrationData::default()),
             migration_flags: MigrationFlags::default(),
+            congestion_info: Default::default(),
         }
     }
 
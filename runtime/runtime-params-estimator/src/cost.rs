@@ -206,8 +206,19 @@ pub enum Cost {
     ActionDelegateSendSir,
     ActionDelegateExec,
 
-    /// rsa2048 keys are used for signing transactions. The cost of creating
+    /// Estimates `action_creation_config.register_rsa2048_keys_cost` which is
+    /// charged for `RegisterRsa2048Keys` actions, the same value on sending and
+    /// executing.
+    ///
+    /// Estimation: Measure a transaction that registers new keys for the
+    /// sender. Subtract the base cost of creating a sir-receipt.
     RegisterRsa2048Keys,
+    /// Estimates `action_creation_config.create_rsa2048_challenge_cost` which
+    /// is charged for `CreateRsa2048Challenge` actions, the same value on
+    /// sending and executing.
+    ///
+    /// Estimation: Measure a transaction that challenges keys which are not
+    /// registered. Subtract the base cost of creating a sir-receipt.
     CreateRsa2048Challenge,
     /// Estimates `wasm_config.ext_costs.base` which is intended to be charged
     /// once on every host function call. However, this is currently
@@ -441,6 +452,27 @@ pub enum Cost {
     /// In the end, the cost should be low enough, compared to the base cost,
     /// that it does not matter all that much if we overestimate it a bit.
     Ed25519VerifyByte,
+    /// Estimates `rsa2048_pkcs1v15_verify_base`, which covers the base cost of
+    /// the host function `rsa2048_pkcs1v15_verify` to verify an RSA2048
+    /// PKCS#1 v1.5 signature.
+    ///
+    /// Estimation: Use a fixed key and signature embedded in the test contract
+    /// and verify it `N` times in a loop and divide by `N`. The cost is
+    /// dominated by decoding the DER public key and the modular exponentiation
+    /// with the public exponent. Unlike ED25519, the exponentiation doesn't
+    /// depend on the signature, so testing on a single input is fine.
+    ///
+    /// The host function is only available on nightly, so this requires the
+    /// estimator to be built with the `nightly` feature.
+    Rsa2048Pkcs1v15VerifyBase,
+    /// Estimates `rsa2048_pkcs1v15_verify_byte`, the cost charged per message
+    /// byte in calls to the rsa2048_pkcs1v15_verify host function.
+    ///
+    /// Estimation: Verify a signature for a large message many times, subtract
+    /// the cost estimated for the base and divide the remainder by the total
+    /// bytes of the message. The message is hashed with SHA-256, so the
+    /// result should be close to `sha256_byte`.
+    Rsa2048Pkcs1v15VerifyByte,
     // `storage_write` records a single key-value pair, initially in the
     // prospective changes in-memory hash map, and then once a full block has
     // been processed, in the on-disk trie. If there was already a value
@@ -609,6 +641,24 @@ pub enum Cost {
     AltBn128G1SumBase,
     AltBn128G1SumElement,
 
+    // The `bls12381_*` host functions are only available on nightly, so
+    // estimating them requires the estimator to be built with the `nightly`
+    // feature.
+    Bls12381P1SumBase,
+    Bls12381P1SumElement,
+    Bls12381P2SumBase,
+    Bls12381P2SumElement,
+    Bls12381G1MultiexpBase,
+    Bls12381G1MultiexpElement,
+    Bls12381G2MultiexpBase,
+    Bls12381G2MultiexpElement,
+    Bls12381MapFpToG1Base,
+    Bls12381MapFpToG1Element,
+    Bls12381MapFp2ToG2Base,
+    Bls12381MapFp2ToG2Element,
+    Bls12381PairingCheckBase,
+    Bls12381PairingCheckElement,
+
     // Costs used only in estimator
     //
     /// Costs associated with applying an empty block. This overhead is not
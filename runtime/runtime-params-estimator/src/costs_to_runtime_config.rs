@@ -38,6 +38,7 @@ pub fn costs_to_runtime_config(cost_table: &CostTable) -> anyhow::Result<Runtime
             ..latest_runtime_config.wasm_config
         },
         account_creation_config: AccountCreationConfig::default(),
+        congestion_control_config: latest_runtime_config.congestion_control_config,
     };
     Ok(res)
 }
@@ -52,6 +53,7 @@ fn runtime_fees_config(cost_table: &CostTable) -> anyhow::Result<RuntimeFeesConf
 
     let config_store = RuntimeConfigStore::new(None);
     let actual_fees_config = &config_store.get_config(PROTOCOL_VERSION).fees;
+    let current_fee = |cost: ActionCosts| actual_fees_config.fee(cost).clone();
     let res = RuntimeFeesConfig {
         action_fees: enum_map::enum_map! {
             ActionCosts::create_account => fee(Cost::ActionCreateAccount)?,
@@ -72,6 +74,11 @@ fn runtime_fees_config(cost_table: &CostTable) -> anyhow::Result<RuntimeFeesConf
             ActionCosts::new_data_receipt_byte => fee(Cost::DataReceiptCreationPerByte)?,
             ActionCosts::register_rsa2048_keys => fee(Cost::RegisterRsa2048Keys)?,
             ActionCosts::create_rsa2048_challenge => fee(Cost::CreateRsa2048Challenge)?,
+            // Global contracts are compiled like regular deployments, so they are not
+            // estimated separately and keep the fees of the current config.
+            ActionCosts::deploy_global_contract_base => current_fee(ActionCosts::deploy_global_contract_base),
+            ActionCosts::deploy_global_contract_byte => current_fee(ActionCosts::deploy_global_contract_byte),
+            ActionCosts::use_global_contract => current_fee(ActionCosts::use_global_contract),
         },
         ..actual_fees_config.clone()
     };
@@ -128,6 +135,8 @@ fn estimation(cost: ExtCosts) -> Option<Cost> {
         ExtCosts::ecrecover_base => Cost::EcrecoverBase,
         ExtCosts::ed25519_verify_base => Cost::Ed25519VerifyBase,
         ExtCosts::ed25519_verify_byte => Cost::Ed25519VerifyByte,
+        ExtCosts::rsa2048_pkcs1v15_verify_base => Cost::Rsa2048Pkcs1v15VerifyBase,
+        ExtCosts::rsa2048_pkcs1v15_verify_byte => Cost::Rsa2048Pkcs1v15VerifyByte,
         ExtCosts::log_base => Cost::LogBase,
         ExtCosts::log_byte => Cost::LogByte,
         ExtCosts::storage_write_base => Cost::StorageWriteBase,
@@ -153,6 +162,20 @@ fn estimation(cost: ExtCosts) -> Option<Cost> {
         ExtCosts::alt_bn128_g1_multiexp_element => Cost::AltBn128G1MultiexpElement,
         ExtCosts::alt_bn128_pairing_check_base => Cost::AltBn128PairingCheckBase,
         ExtCosts::alt_bn128_pairing_check_element => Cost::AltBn128PairingCheckElement,
+        ExtCosts::bls12381_p1_sum_base => Cost::Bls12381P1SumBase,
+        ExtCosts::bls12381_p1_sum_element => Cost::Bls12381P1SumElement,
+        ExtCosts::bls12381_p2_sum_base => Cost::Bls12381P2SumBase,
+        ExtCosts::bls12381_p2_sum_element => Cost::Bls12381P2SumElement,
+        ExtCosts::bls12381_g1_multiexp_base => Cost::Bls12381G1MultiexpBase,
+        ExtCosts::bls12381_g1_multiexp_element => Cost::Bls12381G1MultiexpElement,
+        ExtCosts::bls12381_g2_multiexp_base => Cost::Bls12381G2MultiexpBase,
+        ExtCosts::bls12381_g2_multiexp_element => Cost::Bls12381G2MultiexpElement,
+        ExtCosts::bls12381_map_fp_to_g1_base => Cost::Bls12381MapFpToG1Base,
+        ExtCosts::bls12381_map_fp_to_g1_element => Cost::Bls12381MapFpToG1Element,
+        ExtCosts::bls12381_map_fp2_to_g2_base => Cost::Bls12381MapFp2ToG2Base,
+        ExtCosts::bls12381_map_fp2_to_g2_element => Cost::Bls12381MapFp2ToG2Element,
+        ExtCosts::bls12381_pairing_check_base => Cost::Bls12381PairingCheckBase,
+        ExtCosts::bls12381_pairing_check_element => Cost::Bls12381PairingCheckElement,
         _ => return None,
     })
 }
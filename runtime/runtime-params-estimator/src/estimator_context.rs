@@ -47,6 +47,7 @@ pub(crate) struct CachedCosts {
     pub(crate) apply_block: Option<GasCost>,
     pub(crate) touching_trie_node_write: Option<GasCost>,
     pub(crate) ed25519_verify_base: Option<GasCost>,
+    pub(crate) rsa2048_pkcs1v15_verify_base: Option<GasCost>,
 }
 
 impl<'c> EstimatorContext<'c> {
@@ -144,6 +145,7 @@ impl<'c> EstimatorContext<'c> {
             // Epoch length is long enough to avoid corner cases.
             prev_block_hash: Default::default(),
             block_hash: Default::default(),
+            shard_id: Default::default(),
             epoch_id: Default::default(),
             epoch_height: 0,
             gas_price: 0,
@@ -156,6 +158,7 @@ impl<'c> EstimatorContext<'c> {
             is_new_chunk: true,
             migration_data: Arc::new(MigrationData::default()),
             migration_flags: MigrationFlags::default(),
+            congestion_info: Default::default(),
         }
     }
 
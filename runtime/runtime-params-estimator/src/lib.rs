@@ -106,8 +106,9 @@ use unc_crypto::{KeyType, SecretKey};
 use unc_parameters::{ExtCosts, RuntimeConfigStore, RuntimeFeesConfig};
 use unc_primitives::account::{AccessKey, AccessKeyPermission, FunctionCallPermission};
 use unc_primitives::transaction::{
-    Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, PledgeAction, SignedTransaction, TransferAction,
+    Action, AddKeyAction, CreateAccountAction, CreateRsa2048ChallengeAction, DeleteAccountAction,
+    DeleteKeyAction, DeployContractAction, PledgeAction, RegisterRsa2048KeysAction,
+    SignedTransaction, TransferAction,
 };
 use unc_primitives::types::AccountId;
 use unc_primitives::version::PROTOCOL_VERSION;
@@ -192,6 +193,8 @@ static ALL_COSTS: &[(Cost, fn(&mut EstimatorContext) -> GasCost)] = &[
     (Cost::ActionDelegateSendNotSir, action_costs::delegate_send_not_sir),
     (Cost::ActionDelegateSendSir, action_costs::delegate_send_sir),
     (Cost::ActionDelegateExec, action_costs::delegate_exec),
+    (Cost::RegisterRsa2048Keys, action_register_rsa2048_keys),
+    (Cost::CreateRsa2048Challenge, action_create_rsa2048_challenge),
     (Cost::HostFunctionCall, host_function_call),
     (Cost::WasmInstruction, wasm_instruction),
     (Cost::DataReceiptCreationBase, data_receipt_creation_base),
@@ -221,12 +224,28 @@ static ALL_COSTS: &[(Cost, fn(&mut EstimatorContext) -> GasCost)] = &[
     (Cost::EcrecoverBase, ecrecover_base),
     (Cost::Ed25519VerifyBase, ed25519_verify_base),
     (Cost::Ed25519VerifyByte, ed25519_verify_byte),
+    (Cost::Rsa2048Pkcs1v15VerifyBase, rsa2048_pkcs1v15_verify_base),
+    (Cost::Rsa2048Pkcs1v15VerifyByte, rsa2048_pkcs1v15_verify_byte),
     (Cost::AltBn128G1MultiexpBase, alt_bn128g1_multiexp_base),
     (Cost::AltBn128G1MultiexpElement, alt_bn128g1_multiexp_element),
     (Cost::AltBn128G1SumBase, alt_bn128g1_sum_base),
     (Cost::AltBn128G1SumElement, alt_bn128g1_sum_element),
     (Cost::AltBn128PairingCheckBase, alt_bn128_pairing_check_base),
     (Cost::AltBn128PairingCheckElement, alt_bn128_pairing_check_element),
+    (Cost::Bls12381P1SumBase, bls12381_p1_sum_base),
+    (Cost::Bls12381P1SumElement, bls12381_p1_sum_element),
+    (Cost::Bls12381P2SumBase, bls12381_p2_sum_base),
+    (Cost::Bls12381P2SumElement, bls12381_p2_sum_element),
+    (Cost::Bls12381G1MultiexpBase, bls12381_g1_multiexp_base),
+    (Cost::Bls12381G1MultiexpElement, bls12381_g1_multiexp_element),
+    (Cost::Bls12381G2MultiexpBase, bls12381_g2_multiexp_base),
+    (Cost::Bls12381G2MultiexpElement, bls12381_g2_multiexp_element),
+    (Cost::Bls12381MapFpToG1Base, bls12381_map_fp_to_g1_base),
+    (Cost::Bls12381MapFpToG1Element, bls12381_map_fp_to_g1_element),
+    (Cost::Bls12381MapFp2ToG2Base, bls12381_map_fp2_to_g2_base),
+    (Cost::Bls12381MapFp2ToG2Element, bls12381_map_fp2_to_g2_element),
+    (Cost::Bls12381PairingCheckBase, bls12381_pairing_check_base),
+    (Cost::Bls12381PairingCheckElement, bls12381_pairing_check_element),
     (Cost::StorageHasKeyBase, storage_has_key_base),
     (Cost::StorageHasKeyByte, storage_has_key_byte),
     (Cost::StorageReadBase, storage_read_base),
@@ -851,6 +870,52 @@ fn action_delegate_base(ctx: &mut EstimatorContext) -> GasCost {
     total_cost.saturating_sub(&base_cost, &NonNegativeTolerance::PER_MILLE)
 }
 
+fn action_register_rsa2048_keys(ctx: &mut EstimatorContext) -> GasCost {
+    let total_cost = {
+        let mut make_transaction = |tb: &mut TransactionBuilder| -> SignedTransaction {
+            let sender = tb.random_unused_account();
+            let receiver = sender.clone();
+
+            let actions = vec![Action::RegisterRsa2048Keys(Box::new(RegisterRsa2048KeysAction {
+                public_key: SecretKey::from_seed(KeyType::ED25519, sender.as_ref()).public_key(),
+                operation_type: 0,
+                args: vec![0; 128],
+            }))];
+            tb.transaction_from_actions(sender, receiver, actions)
+        };
+        transaction_cost(ctx, &mut make_transaction)
+    };
+
+    let base_cost = action_sir_receipt_creation(ctx);
+
+    total_cost.saturating_sub(&base_cost, &NonNegativeTolerance::PER_MILLE)
+}
+
+fn action_create_rsa2048_challenge(ctx: &mut EstimatorContext) -> GasCost {
+    let total_cost = {
+        let mut make_transaction = |tb: &mut TransactionBuilder| -> SignedTransaction {
+            let sender = tb.random_unused_account();
+            let receiver = sender.clone();
+
+            // The keys are not registered, so this measures the lookup of the keys and the
+            // failed action, which dominates the cost of a challenge.
+            let actions =
+                vec![Action::CreateRsa2048Challenge(Box::new(CreateRsa2048ChallengeAction {
+                    public_key: SecretKey::from_seed(KeyType::ED25519, sender.as_ref())
+                        .public_key(),
+                    challenge_key: "22skMptHjFWNyuEWY22ftn2AbLPSYpmYwGJRGwpNHbTV".parse().unwrap(),
+                    args: vec![0; 128],
+                }))];
+            tb.transaction_from_actions(sender, receiver, actions)
+        };
+        transaction_cost(ctx, &mut make_transaction)
+    };
+
+    let base_cost = action_sir_receipt_creation(ctx);
+
+    total_cost.saturating_sub(&base_cost, &NonNegativeTolerance::PER_MILLE)
+}
+
 fn host_function_call(ctx: &mut EstimatorContext) -> GasCost {
     let block_latency = 0;
     let (total_cost, count) = fn_cost_count(ctx, "base_1M", ExtCosts::base, block_latency);
@@ -1031,6 +1096,36 @@ fn ed25519_verify_byte(ctx: &mut EstimatorContext) -> GasCost {
     byte - base / iteration_bytes
 }
 
+fn rsa2048_pkcs1v15_verify_base(ctx: &mut EstimatorContext) -> GasCost {
+    if ctx.cached.rsa2048_pkcs1v15_verify_base.is_none() {
+        let cost = fn_cost(
+            ctx,
+            "rsa2048_pkcs1v15_verify_32b_500",
+            ExtCosts::rsa2048_pkcs1v15_verify_base,
+            500,
+        );
+        ctx.cached.rsa2048_pkcs1v15_verify_base = Some(cost);
+    }
+    ctx.cached.rsa2048_pkcs1v15_verify_base.clone().unwrap()
+}
+
+fn rsa2048_pkcs1v15_verify_byte(ctx: &mut EstimatorContext) -> GasCost {
+    let base = rsa2048_pkcs1v15_verify_base(ctx);
+    // inside the WASM function, there are 64 calls to `rsa2048_pkcs1v15_verify`.
+    let base_call_num = 64;
+    // each call checks a message of size 16kiB
+    let iteration_bytes = 16384;
+    let total_bytes = base_call_num * iteration_bytes;
+    let byte = fn_cost(
+        ctx,
+        "rsa2048_pkcs1v15_verify_16kib_64",
+        ExtCosts::rsa2048_pkcs1v15_verify_byte,
+        total_bytes,
+    );
+    // need to subtract the base cost, which has already been divided by the number of bytes per iteration
+    byte - base / iteration_bytes
+}
+
 fn alt_bn128g1_multiexp_base(ctx: &mut EstimatorContext) -> GasCost {
     fn_cost(ctx, "alt_bn128_g1_multiexp_1_10", ExtCosts::alt_bn128_g1_multiexp_base, 10)
 }
@@ -1057,6 +1152,60 @@ fn alt_bn128_pairing_check_element(ctx: &mut EstimatorContext) -> GasCost {
     )
 }
 
+fn bls12381_p1_sum_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_p1_sum_1_1k", ExtCosts::bls12381_p1_sum_base, 1000)
+}
+fn bls12381_p1_sum_element(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_p1_sum_10_1k", ExtCosts::bls12381_p1_sum_element, 10 * 1000)
+}
+
+fn bls12381_p2_sum_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_p2_sum_1_1k", ExtCosts::bls12381_p2_sum_base, 1000)
+}
+fn bls12381_p2_sum_element(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_p2_sum_10_1k", ExtCosts::bls12381_p2_sum_element, 10 * 1000)
+}
+
+fn bls12381_g1_multiexp_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_g1_multiexp_1_10", ExtCosts::bls12381_g1_multiexp_base, 10)
+}
+fn bls12381_g1_multiexp_element(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_g1_multiexp_10_10", ExtCosts::bls12381_g1_multiexp_element, 10 * 10)
+}
+
+fn bls12381_g2_multiexp_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_g2_multiexp_1_10", ExtCosts::bls12381_g2_multiexp_base, 10)
+}
+fn bls12381_g2_multiexp_element(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_g2_multiexp_10_10", ExtCosts::bls12381_g2_multiexp_element, 10 * 10)
+}
+
+fn bls12381_map_fp_to_g1_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_map_fp_to_g1_1_100", ExtCosts::bls12381_map_fp_to_g1_base, 100)
+}
+fn bls12381_map_fp_to_g1_element(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_map_fp_to_g1_10_100", ExtCosts::bls12381_map_fp_to_g1_element, 10 * 100)
+}
+
+fn bls12381_map_fp2_to_g2_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_map_fp2_to_g2_1_100", ExtCosts::bls12381_map_fp2_to_g2_base, 100)
+}
+fn bls12381_map_fp2_to_g2_element(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(
+        ctx,
+        "bls12381_map_fp2_to_g2_10_100",
+        ExtCosts::bls12381_map_fp2_to_g2_element,
+        10 * 100,
+    )
+}
+
+fn bls12381_pairing_check_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_pairing_check_1_10", ExtCosts::bls12381_pairing_check_base, 10)
+}
+fn bls12381_pairing_check_element(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "bls12381_pairing_check_10_10", ExtCosts::bls12381_pairing_check_element, 10 * 10)
+}
+
 fn storage_has_key_base(ctx: &mut EstimatorContext) -> GasCost {
     fn_cost_with_setup(
         ctx,
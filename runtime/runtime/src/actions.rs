@@ -14,12 +14,16 @@ use unc_primitives::checked_feature;
 use unc_primitives::config::ViewConfig;
 use unc_primitives::errors::{ActionError, ActionErrorKind, InvalidAccessKeyError, RuntimeError};
 use unc_primitives::hash::CryptoHash;
-use unc_primitives::receipt::{ActionReceipt, Receipt, ReceiptEnum};
+use unc_primitives::receipt::{
+    ActionReceipt, DataReceipt, GlobalContractDistributionReceipt, Receipt, ReceiptEnum,
+};
 use unc_primitives::transaction::{
     Action, AddKeyAction, CreateRsa2048ChallengeAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, FunctionCallAction, PledgeAction, RegisterRsa2048KeysAction,
-    TransferAction,
+    DeployContractAction, DeployContractWithMigrationAction, DeployGlobalContractAction,
+    FunctionCallAction, PledgeAction, RegisterRsa2048KeysAction, TransferAction,
+    UseGlobalContractAction,
 };
+use unc_primitives::trie_key::TrieKey;
 use unc_primitives::types::validator_power::ValidatorPower;
 use unc_primitives::types::{AccountId, BlockHeight, EpochInfoProvider, Gas, TrieCacheMode};
 use unc_primitives::utils::{account_is_valid, create_random_seed};
@@ -28,14 +32,15 @@ use unc_primitives::version::{
 };
 use unc_primitives_core::account::id::AccountType;
 use unc_store::{
-    get_access_key, get_code, get_rsa2048_keys, remove_access_key, remove_account,
-    remove_rsa2048_keys, set_access_key, set_code, set_rsa2048_keys, StorageError, TrieUpdate,
+    get_access_key, get_code, get_global_code, get_rsa2048_keys, remove_access_key, remove_account,
+    remove_rsa2048_keys, set_access_key, set_code, set_global_code, set_rsa2048_keys, StorageError,
+    TrieUpdate,
 };
 use unc_vm_runner::logic::errors::{
     CompilationError, FunctionCallError, InconsistentStateError, VMRunnerError,
 };
 use unc_vm_runner::logic::types::PromiseResult;
-use unc_vm_runner::logic::{VMContext, VMOutcome};
+use unc_vm_runner::logic::{FunctionCallTrace, VMContext, VMOutcome};
 use unc_vm_runner::precompile_contract;
 use unc_vm_runner::ContractCode;
 use unc_wallet_contract::{wallet_contract, wallet_contract_magic_bytes};
@@ -232,6 +237,14 @@ pub(crate) fn action_function_call(
         is_last_action,
         None,
     )?;
+    if let Some(trace) = outcome.trace {
+        unc_vm_runner::logic::trace::record(FunctionCallTrace {
+            receipt_id: receipt.receipt_id,
+            receiver_id: account_id.clone(),
+            method_name: function_call.method_name.clone(),
+            trace,
+        });
+    }
 
     match &outcome.aborted {
         None => {
@@ -288,25 +301,43 @@ pub(crate) fn action_function_call(
     result.logs.extend(outcome.logs);
     result.profile.merge(&outcome.profile);
     if execution_succeeded {
-        let new_receipts: Vec<_> = receipt_manager
+        let mut new_receipts: Vec<_> = receipt_manager
             .action_receipts
             .into_iter()
-            .map(|(receiver_id, receipt)| Receipt {
-                predecessor_id: account_id.clone(),
-                receiver_id,
-                // Actual receipt ID is set in the Runtime.apply_action_receipt(...) in the
-                // "Generating receipt IDs" section
-                receipt_id: CryptoHash::default(),
-                receipt: ReceiptEnum::Action(ActionReceipt {
+            .map(|(receiver_id, receipt)| {
+                let new_action_receipt = ActionReceipt {
                     signer_id: action_receipt.signer_id.clone(),
                     signer_public_key: action_receipt.signer_public_key.clone(),
                     gas_price: action_receipt.gas_price,
                     output_data_receivers: receipt.output_data_receivers,
                     input_data_ids: receipt.input_data_ids,
                     actions: receipt.actions,
-                }),
+                };
+                Receipt {
+                    predecessor_id: account_id.clone(),
+                    receiver_id,
+                    // Actual receipt ID is set in the Runtime.apply_action_receipt(...) in the
+                    // "Generating receipt IDs" section
+                    receipt_id: CryptoHash::default(),
+                    receipt: if receipt.is_promise_yield {
+                        ReceiptEnum::PromiseYield(new_action_receipt)
+                    } else {
+                        ReceiptEnum::Action(new_action_receipt)
+                    },
+                }
             })
             .collect();
+        // Data resuming the yielded receipts goes after the action receipts, so that a receipt
+        // yielded and resumed by the same function call is stored before it's resumed.
+        new_receipts.extend(receipt_manager.data_receipts.into_iter().map(|receipt| Receipt {
+            predecessor_id: account_id.clone(),
+            receiver_id: account_id.clone(),
+            receipt_id: CryptoHash::default(),
+            receipt: ReceiptEnum::PromiseResume(DataReceipt {
+                data_id: receipt.data_id,
+                data: Some(receipt.data),
+            }),
+        }));
 
         account.set_amount(outcome.balance);
         account.set_storage_usage(outcome.storage_usage);
@@ -535,6 +566,161 @@ pub(crate) fn action_deploy_contract(
     Ok(())
 }
 
+/// Stores the code as a global contract. The deployer pays the storage staking for the code only
+/// when the same code has not been deployed as a global contract before, in which case the code
+/// is also sent to every other shard with a distribution receipt.
+pub(crate) fn action_deploy_global_contract(
+    state_update: &mut TrieUpdate,
+    account: &mut Account,
+    result: &mut ActionResult,
+    account_id: &AccountId,
+    deploy_global_contract: &DeployGlobalContractAction,
+    apply_state: &ApplyState,
+    epoch_info_provider: &dyn EpochInfoProvider,
+) -> Result<(), RuntimeError> {
+    let _span = tracing::debug_span!(target: "runtime", "action_deploy_global_contract").entered();
+    let code = ContractCode::new(deploy_global_contract.code.clone(), None);
+    if get_global_code(state_update, *code.hash())?.is_some() {
+        return Ok(());
+    }
+    let storage_config = &apply_state.config.fees.storage_usage_config;
+    account.set_storage_usage(
+        account
+            .storage_usage()
+            .checked_add(code.code().len() as u64 + storage_config.num_extra_bytes_record)
+            .ok_or_else(|| {
+                StorageError::StorageInconsistentState(format!(
+                    "Storage usage integer overflow for account {}",
+                    account_id
+                ))
+            })?,
+    );
+    set_global_code(state_update, &code);
+    // Precompile the contract so that the accounts using it don't have to compile it again.
+    precompile_contract(&code, &apply_state.config.wasm_config, apply_state.cache.as_deref()).ok();
+
+    let shard_layout = epoch_info_provider.shard_layout(&apply_state.epoch_id)?;
+    for target_shard in
+        shard_layout.shard_ids().filter(|&shard_id| shard_id != apply_state.shard_id)
+    {
+        result.new_receipts.push(Receipt {
+            predecessor_id: account_id.clone(),
+            receiver_id: account_id.clone(),
+            receipt_id: CryptoHash::default(),
+            receipt: ReceiptEnum::GlobalContractDistribution(GlobalContractDistributionReceipt {
+                target_shard,
+                code: deploy_global_contract.code.clone(),
+            }),
+        });
+    }
+    Ok(())
+}
+
+/// Stores the copy of a global contract sent by the shard where it was deployed.
+pub(crate) fn apply_global_contract_distribution(
+    state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    distribution: &GlobalContractDistributionReceipt,
+) {
+    let _span =
+        tracing::debug_span!(target: "runtime", "apply_global_contract_distribution").entered();
+    let code = ContractCode::new(distribution.code.clone(), None);
+    set_global_code(state_update, &code);
+    precompile_contract(&code, &apply_state.config.wasm_config, apply_state.cache.as_deref()).ok();
+}
+
+/// Makes the account run the global contract with the given code hash. The code previously
+/// deployed to the account is removed together with its storage usage.
+pub(crate) fn action_use_global_contract(
+    state_update: &mut TrieUpdate,
+    account: &mut Account,
+    result: &mut ActionResult,
+    account_id: &AccountId,
+    use_global_contract: &UseGlobalContractAction,
+) -> Result<(), StorageError> {
+    let _span = tracing::debug_span!(target: "runtime", "action_use_global_contract").entered();
+    let code_hash = use_global_contract.code_hash;
+    if get_global_code(state_update, code_hash)?.is_none() {
+        result.result = Err(ActionErrorKind::GlobalContractDoesNotExist { code_hash }.into());
+        return Ok(());
+    }
+    let prev_code = get_code(state_update, account_id, Some(account.code_hash()))?;
+    if let Some(prev_code) = prev_code {
+        account.set_storage_usage(
+            account.storage_usage().saturating_sub(prev_code.code().len() as u64),
+        );
+        state_update.remove(TrieKey::ContractCode { account_id: account_id.clone() });
+    }
+    account.set_code_hash(code_hash);
+    Ok(())
+}
+
+/// Deploys the code and calls its migration method within the same action. If the migration fails,
+/// the previous code and storage usage of the account are restored and the action fails with
+/// `ContractMigrationFailed`.
+pub(crate) fn action_deploy_contract_with_migration(
+    state_update: &mut TrieUpdate,
+    apply_state: &ApplyState,
+    account: &mut Account,
+    receipt: &Receipt,
+    action_receipt: &ActionReceipt,
+    promise_results: &[PromiseResult],
+    result: &mut ActionResult,
+    account_id: &AccountId,
+    deploy_contract_with_migration: &DeployContractWithMigrationAction,
+    action_hash: &CryptoHash,
+    config: &RuntimeConfig,
+    is_last_action: bool,
+    epoch_info_provider: &dyn EpochInfoProvider,
+) -> Result<(), RuntimeError> {
+    let _span =
+        tracing::debug_span!(target: "runtime", "action_deploy_contract_with_migration").entered();
+    let DeployContractWithMigrationAction { code, migrate_method_name, migrate_args, gas } =
+        deploy_contract_with_migration;
+    let prev_account = account.clone();
+    let prev_code = get_code(state_update, account_id, Some(account.code_hash()))?;
+
+    let deploy_contract = DeployContractAction { code: code.clone() };
+    action_deploy_contract(state_update, account, account_id, &deploy_contract, apply_state)?;
+    let function_call = FunctionCallAction {
+        method_name: migrate_method_name.clone(),
+        args: migrate_args.clone(),
+        gas: *gas,
+        deposit: 0,
+    };
+    action_function_call(
+        state_update,
+        apply_state,
+        account,
+        receipt,
+        action_receipt,
+        promise_results,
+        result,
+        account_id,
+        &function_call,
+        action_hash,
+        config,
+        is_last_action,
+        epoch_info_provider,
+    )?;
+
+    if let Err(ActionError { kind: ActionErrorKind::FunctionCallError(error), .. }) = &result.result
+    {
+        result.result = Err(ActionErrorKind::ContractMigrationFailed {
+            account_id: account_id.clone(),
+            method_name: migrate_method_name.clone(),
+            error: error.clone(),
+        }
+        .into());
+        *account = prev_account;
+        match prev_code {
+            Some(prev_code) => set_code(state_update, account_id.clone(), &prev_code),
+            None => state_update.remove(TrieKey::ContractCode { account_id: account_id.clone() }),
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn action_delete_account(
     state_update: &mut TrieUpdate,
     account: &mut Option<Account>,
@@ -867,7 +1053,7 @@ pub(crate) fn apply_delegate_action(
 /// Returns Gas amount is required to execute Receipt and all actions it contains
 fn receipt_required_gas(apply_state: &ApplyState, receipt: &Receipt) -> Result<Gas, RuntimeError> {
     Ok(match &receipt.receipt {
-        ReceiptEnum::Action(action_receipt) => {
+        ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
             let mut required_gas = safe_add_gas(
                 total_prepaid_exec_fees(
                     &apply_state.config,
@@ -883,7 +1069,9 @@ fn receipt_required_gas(apply_state: &ApplyState, receipt: &Receipt) -> Result<G
 
             required_gas
         }
-        ReceiptEnum::Data(_) => 0,
+        ReceiptEnum::Data(_)
+        | ReceiptEnum::PromiseResume(_)
+        | ReceiptEnum::GlobalContractDistribution(_) => 0,
     })
 }
 
@@ -1012,7 +1200,10 @@ pub(crate) fn check_actor_permissions(
         Action::DeployContract(_)
         | Action::Pledge(_)
         | Action::AddKey(_)
-        | Action::DeleteKey(_) => {
+        | Action::DeleteKey(_)
+        | Action::DeployGlobalContract(_)
+        | Action::UseGlobalContract(_)
+        | Action::DeployContractWithMigration(_) => {
             if actor_id != account_id {
                 return Err(ActionErrorKind::ActorNoPermission {
                     account_id: account_id.clone(),
@@ -1120,7 +1311,10 @@ pub(crate) fn check_account_existence(
         | Action::DeleteKey(_)
         | Action::DeleteAccount(_)
         | Action::RegisterRsa2048Keys(_)
-        | Action::CreateRsa2048Challenge(_) => {
+        | Action::CreateRsa2048Challenge(_)
+        | Action::DeployGlobalContract(_)
+        | Action::UseGlobalContract(_)
+        | Action::DeployContractWithMigration(_) => {
             if account.is_none() {
                 return Err(ActionErrorKind::AccountDoesNotExist {
                     account_id: account_id.clone(),
@@ -1145,12 +1339,15 @@ mod tests {
 
     use super::*;
     use crate::unc_primitives::shard_layout::ShardUId;
+    use assert_matches::assert_matches;
     use std::sync::Arc;
     use unc_primitives::account::FunctionCallPermission;
     use unc_primitives::action::delegate::NonDelegateAction;
     use unc_primitives::errors::InvalidAccessKeyError;
     use unc_primitives::hash::hash;
     use unc_primitives::runtime::migration_data::MigrationFlags;
+    use unc_primitives::shard_layout::ShardLayout;
+    use unc_primitives::test_utils::MockEpochInfoProvider;
     use unc_primitives::transaction::CreateAccountAction;
     use unc_primitives::trie_key::TrieKey;
     use unc_primitives::types::{EpochId, StateChangeCause};
@@ -1330,6 +1527,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deploy_global_contract_charges_storage_once() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let apply_state = create_apply_state(1);
+        let epoch_info_provider = MockEpochInfoProvider::default()
+            .with_shard_layout(ShardLayout::v1(vec!["b".parse().unwrap()], None, 1));
+        let code = vec![0; 1_000];
+        let deploy = DeployGlobalContractAction { code: code.clone() };
+        let extra_bytes = apply_state.config.fees.storage_usage_config.num_extra_bytes_record;
+
+        let mut alice = Account::new(100, 0, 0, CryptoHash::default(), 100);
+        let mut action_result = ActionResult::default();
+        action_deploy_global_contract(
+            &mut state_update,
+            &mut alice,
+            &mut action_result,
+            &"alice".parse().unwrap(),
+            &deploy,
+            &apply_state,
+            &epoch_info_provider,
+        )
+        .unwrap();
+        assert_eq!(alice.storage_usage(), 100 + code.len() as u64 + extra_bytes);
+        assert_eq!(alice.code_hash(), CryptoHash::default());
+        let global_code = get_global_code(&state_update, hash(&code)).unwrap().unwrap();
+        assert_eq!(global_code.code(), code.as_slice());
+        // The code is sent to the other shard.
+        assert_matches!(
+            &action_result.new_receipts[..],
+            [Receipt {
+                receipt: ReceiptEnum::GlobalContractDistribution(GlobalContractDistributionReceipt {
+                    target_shard: 1,
+                    code: distributed_code,
+                }),
+                ..
+            }] if distributed_code == &code
+        );
+
+        let mut bob = Account::new(100, 0, 0, CryptoHash::default(), 100);
+        let mut action_result = ActionResult::default();
+        action_deploy_global_contract(
+            &mut state_update,
+            &mut bob,
+            &mut action_result,
+            &"bob".parse().unwrap(),
+            &deploy,
+            &apply_state,
+            &epoch_info_provider,
+        )
+        .unwrap();
+        assert_eq!(bob.storage_usage(), 100);
+        assert!(action_result.new_receipts.is_empty());
+    }
+
+    #[test]
+    fn test_use_global_contract_replaces_account_code() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let account_id = "alice".parse::<AccountId>().unwrap();
+        let global_code = ContractCode::new(vec![1; 100], None);
+        set_global_code(&mut state_update, &global_code);
+        let own_code = ContractCode::new(vec![2; 1_000], None);
+        set_code(&mut state_update, account_id.clone(), &own_code);
+        let mut account = Account::new(100, 0, 0, *own_code.hash(), 1_100);
+
+        let mut action_result = ActionResult::default();
+        action_use_global_contract(
+            &mut state_update,
+            &mut account,
+            &mut action_result,
+            &account_id,
+            &UseGlobalContractAction { code_hash: *global_code.hash() },
+        )
+        .unwrap();
+        assert!(action_result.result.is_ok());
+        assert_eq!(account.code_hash(), *global_code.hash());
+        assert_eq!(account.storage_usage(), 100);
+        assert!(get_code(&state_update, &account_id, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_use_global_contract_does_not_exist() {
+        let tries = TestTriesBuilder::new().build();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let mut account = Account::new(100, 0, 0, CryptoHash::default(), 100);
+        let code_hash = hash(&[1; 100]);
+
+        let mut action_result = ActionResult::default();
+        action_use_global_contract(
+            &mut state_update,
+            &mut account,
+            &mut action_result,
+            &"alice".parse().unwrap(),
+            &UseGlobalContractAction { code_hash },
+        )
+        .unwrap();
+        assert_eq!(
+            action_result.result,
+            Err(ActionErrorKind::GlobalContractDoesNotExist { code_hash }.into())
+        );
+        assert_eq!(account.code_hash(), CryptoHash::default());
+    }
+
     fn create_delegate_action_receipt() -> (ActionReceipt, SignedDelegateAction) {
         let signed_delegate_action = SignedDelegateAction {
             delegate_action: DelegateAction {
@@ -1371,6 +1675,7 @@ mod tests {
             block_height,
             prev_block_hash: CryptoHash::default(),
             block_hash: CryptoHash::default(),
+            shard_id: Default::default(),
             epoch_id: EpochId::default(),
             epoch_height: 3,
             gas_price: 2,
@@ -1383,6 +1688,7 @@ mod tests {
             is_new_chunk: false,
             migration_data: Arc::default(),
             migration_flags: MigrationFlags::default(),
+            congestion_info: Default::default(),
         }
     }
 
@@ -2,6 +2,7 @@ use crate::config::{
     safe_add_balance, safe_add_gas, safe_gas_to_balance, total_deposit, total_prepaid_exec_fees,
     total_prepaid_gas, total_prepaid_send_fees,
 };
+use crate::congestion_control::get_buffered_receipt;
 use crate::safe_add_balance_apply;
 use crate::{ApplyStats, DelayedReceiptIndices, ValidatorAccountsUpdate};
 use std::collections::HashSet;
@@ -9,11 +10,14 @@ use unc_parameters::{ActionCosts, RuntimeConfig};
 use unc_primitives::errors::{
     BalanceMismatchError, IntegerOverflowError, RuntimeError, StorageError,
 };
-use unc_primitives::receipt::{Receipt, ReceiptEnum};
+use unc_primitives::receipt::{PromiseYieldTimeout, Receipt, ReceiptEnum};
 use unc_primitives::transaction::SignedTransaction;
 use unc_primitives::trie_key::TrieKey;
-use unc_primitives::types::{AccountId, Balance};
-use unc_store::{get, get_account, get_postponed_receipt, TrieAccess, TrieUpdate};
+use unc_primitives::types::{AccountId, Balance, ShardId};
+use unc_store::{
+    get, get_account, get_buffered_receipt_indices, get_postponed_receipt,
+    get_promise_yield_indices, get_promise_yield_receipt, TrieAccess, TrieUpdate,
+};
 
 /// Returns delayed receipts with given range of indices.
 fn get_delayed_receipts(
@@ -32,13 +36,29 @@ fn get_delayed_receipts(
         .collect()
 }
 
+/// Returns the receipts between the given indices of all outgoing buffers, e.g. the
+/// receipts forwarded from the buffers when given their initial and final first indices.
+fn get_buffered_receipts(
+    state: &dyn TrieAccess,
+    from_indices: impl Iterator<Item = (ShardId, u64)>,
+    to_index: impl Fn(ShardId) -> u64,
+) -> Result<Vec<Receipt>, StorageError> {
+    let mut receipts = vec![];
+    for (receiving_shard, from_index) in from_indices {
+        for index in from_index..to_index(receiving_shard) {
+            receipts.push(get_buffered_receipt(state, receiving_shard, index)?);
+        }
+    }
+    Ok(receipts)
+}
+
 /// Calculates and returns cost of a receipt.
 fn receipt_cost(
     config: &RuntimeConfig,
     receipt: &Receipt,
 ) -> Result<Balance, IntegerOverflowError> {
     Ok(match &receipt.receipt {
-        ReceiptEnum::Action(action_receipt) => {
+        ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
             let mut total_cost = total_deposit(&action_receipt.actions)?;
             if !receipt.predecessor_id.is_system() {
                 let mut total_gas = safe_add_gas(
@@ -55,7 +75,9 @@ fn receipt_cost(
             }
             total_cost
         }
-        ReceiptEnum::Data(_) => 0,
+        ReceiptEnum::Data(_)
+        | ReceiptEnum::PromiseResume(_)
+        | ReceiptEnum::GlobalContractDistribution(_) => 0,
     })
 }
 
@@ -100,6 +122,22 @@ fn total_postponed_receipts_cost(
     })
 }
 
+/// Calculates and returns total costs of all the yielded receipts.
+fn total_promise_yield_receipts_cost(
+    state: &dyn TrieAccess,
+    config: &RuntimeConfig,
+    yield_ids: &HashSet<(AccountId, crate::CryptoHash)>,
+) -> Result<Balance, RuntimeError> {
+    yield_ids.iter().try_fold(0, |total, item| {
+        let (account_id, data_id) = item;
+        let cost = match get_promise_yield_receipt(state, account_id, *data_id)? {
+            None => return Ok(total),
+            Some(receipt) => receipt_cost(config, &receipt)?,
+        };
+        safe_add_balance(total, cost).map_err(|_| RuntimeError::UnexpectedIntegerOverflow)
+    })
+}
+
 pub(crate) fn check_balance(
     config: &RuntimeConfig,
     final_state: &TrieUpdate,
@@ -129,6 +167,35 @@ pub(crate) fn check_balance(
             ..final_delayed_receipt_indices.next_available_index,
     )?;
 
+    // Outgoing buffers
+    let initial_buffered_receipt_indices = get_buffered_receipt_indices(initial_state)?;
+    let final_buffered_receipt_indices = get_buffered_receipt_indices(final_state)?;
+    let final_buffer = |shard_id: ShardId| {
+        final_buffered_receipt_indices.shard_buffers.get(&shard_id).copied().unwrap_or_default()
+    };
+    // Previously buffered receipts that were forwarded this time. They are part of the
+    // outgoing receipts.
+    let forwarded_buffered_receipts = get_buffered_receipts(
+        initial_state,
+        initial_buffered_receipt_indices
+            .shard_buffers
+            .iter()
+            .map(|(&shard_id, indices)| (shard_id, indices.first_index)),
+        |shard_id| final_buffer(shard_id).first_index,
+    )?;
+    // Receipts that were not forwarded this time and are buffered now.
+    let new_buffered_receipts = get_buffered_receipts(
+        final_state,
+        final_buffered_receipt_indices.shard_buffers.keys().map(|&shard_id| {
+            let initial_next_index = initial_buffered_receipt_indices
+                .shard_buffers
+                .get(&shard_id)
+                .map_or(0, |indices| indices.next_available_index);
+            (shard_id, initial_next_index)
+        }),
+        |shard_id| final_buffer(shard_id).next_available_index,
+    )?;
+
     // Accounts
     let mut all_accounts_ids: HashSet<AccountId> = transactions
         .iter()
@@ -165,6 +232,8 @@ pub(crate) fn check_balance(
     let outgoing_receipts_balance = receipts_cost(outgoing_receipts)?;
     let processed_delayed_receipts_balance = receipts_cost(&processed_delayed_receipts)?;
     let new_delayed_receipts_balance = receipts_cost(&new_delayed_receipts)?;
+    let forwarded_buffered_receipts_balance = receipts_cost(&forwarded_buffered_receipts)?;
+    let new_buffered_receipts_balance = receipts_cost(&new_buffered_receipts)?;
     // Postponed actions receipts. The receipts can be postponed and stored with the receiver's
     // account ID when the input data is not received yet.
     // We calculate all potential receipts IDs that might be postponed initially or after the
@@ -175,8 +244,10 @@ pub(crate) fn check_balance(
         .filter_map(|receipt| {
             let account_id = &receipt.receiver_id;
             match &receipt.receipt {
-                ReceiptEnum::Action(_) => Some(Ok((account_id.clone(), receipt.receipt_id))),
-                ReceiptEnum::Data(data_receipt) => {
+                ReceiptEnum::Action(_) | ReceiptEnum::PromiseYield(_) => {
+                    Some(Ok((account_id.clone(), receipt.receipt_id)))
+                }
+                ReceiptEnum::Data(data_receipt) | ReceiptEnum::PromiseResume(data_receipt) => {
                     let result = get(
                         initial_state,
                         &TrieKey::PostponedReceiptId {
@@ -190,6 +261,7 @@ pub(crate) fn check_balance(
                         Ok(Some(receipt_id)) => Some(Ok((account_id.clone(), receipt_id))),
                     }
                 }
+                ReceiptEnum::GlobalContractDistribution(_) => None,
             }
         })
         .collect::<Result<HashSet<_>, StorageError>>()?;
@@ -198,6 +270,49 @@ pub(crate) fn check_balance(
         total_postponed_receipts_cost(initial_state, config, &all_potential_postponed_receipt_ids)?;
     let final_postponed_receipts_balance =
         total_postponed_receipts_cost(final_state, config, &all_potential_postponed_receipt_ids)?;
+    // Yielded receipts are stored under the receiver's account ID and the data ID they wait
+    // for, until they are resumed or time out. The receipts yielded this time are stored right
+    // away, each with a new timeout. A timeout which already expired was replaced by the
+    // outgoing `PromiseResume` receipt.
+    let initial_promise_yield_indices = get_promise_yield_indices(initial_state)?;
+    let final_promise_yield_indices = get_promise_yield_indices(final_state)?;
+    let new_promise_yield_timeouts = (initial_promise_yield_indices.next_available_index
+        ..final_promise_yield_indices.next_available_index)
+        .filter_map(|index| {
+            get::<PromiseYieldTimeout>(final_state, &TrieKey::PromiseYieldTimeout { index })
+                .transpose()
+        })
+        .collect::<Result<Vec<_>, StorageError>>()?;
+    let all_potential_promise_yield_ids = incoming_receipts
+        .iter()
+        .chain(processed_delayed_receipts.iter())
+        .chain(outgoing_receipts.iter())
+        .filter_map(|receipt| match &receipt.receipt {
+            ReceiptEnum::PromiseYield(action_receipt) => action_receipt
+                .input_data_ids
+                .first()
+                .map(|data_id| (receipt.receiver_id.clone(), *data_id)),
+            ReceiptEnum::PromiseResume(data_receipt) => {
+                Some((receipt.receiver_id.clone(), data_receipt.data_id))
+            }
+            ReceiptEnum::Action(_)
+            | ReceiptEnum::Data(_)
+            | ReceiptEnum::GlobalContractDistribution(_) => None,
+        })
+        .chain(
+            new_promise_yield_timeouts
+                .into_iter()
+                .map(|timeout| (timeout.account_id, timeout.data_id)),
+        )
+        .collect::<HashSet<_>>();
+    let initial_postponed_receipts_balance = safe_add_balance(
+        initial_postponed_receipts_balance,
+        total_promise_yield_receipts_cost(initial_state, config, &all_potential_promise_yield_ids)?,
+    )?;
+    let final_postponed_receipts_balance = safe_add_balance(
+        final_postponed_receipts_balance,
+        total_promise_yield_receipts_cost(final_state, config, &all_potential_promise_yield_ids)?,
+    )?;
     // Sum it up
 
     let initial_balance = safe_add_balance_apply!(
@@ -205,13 +320,15 @@ pub(crate) fn check_balance(
         initial_accounts_balance,
         incoming_receipts_balance,
         processed_delayed_receipts_balance,
-        initial_postponed_receipts_balance
+        initial_postponed_receipts_balance,
+        forwarded_buffered_receipts_balance
     );
     let final_balance = safe_add_balance_apply!(
         final_accounts_balance,
         outgoing_receipts_balance,
         new_delayed_receipts_balance,
         final_postponed_receipts_balance,
+        new_buffered_receipts_balance,
         stats.tx_burnt_amount,
         stats.slashed_burnt_amount,
         stats.other_burnt_amount
@@ -224,11 +341,13 @@ pub(crate) fn check_balance(
             incoming_receipts_balance,
             processed_delayed_receipts_balance,
             initial_postponed_receipts_balance,
+            forwarded_buffered_receipts_balance,
             // Outputs
             final_accounts_balance,
             outgoing_receipts_balance,
             new_delayed_receipts_balance,
             final_postponed_receipts_balance,
+            new_buffered_receipts_balance,
             tx_burnt_amount: stats.tx_burnt_amount,
             slashed_burnt_amount: stats.slashed_burnt_amount,
             other_burnt_amount: stats.other_burnt_amount,
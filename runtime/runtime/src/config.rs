@@ -8,7 +8,10 @@ use unc_primitives::errors::IntegerOverflowError;
 // Just re-exporting RuntimeConfig for backwards compatibility.
 use unc_parameters::{transfer_exec_fee, transfer_send_fee, ActionCosts, RuntimeConfig};
 pub use unc_primitives::num_rational::Rational32;
-use unc_primitives::transaction::{Action, DeployContractAction, Transaction};
+use unc_primitives::transaction::{
+    Action, DeployContractAction, DeployContractWithMigrationAction, DeployGlobalContractAction,
+    Transaction,
+};
 use unc_primitives::types::{AccountId, Balance, Compute, Gas};
 
 /// Describes the cost of converting this transaction into a receipt.
@@ -24,7 +27,8 @@ pub struct TransactionCost {
     pub receipt_gas_price: Balance,
     /// Total costs in tokens for this transaction (including all deposits).
     pub total_cost: Balance,
-    /// The amount of tokens burnt by converting this transaction to a receipt.
+    /// The amount of tokens burnt by converting this transaction to a receipt, including the
+    /// priority fee.
     pub burnt_amount: Balance,
 }
 
@@ -141,6 +145,34 @@ pub fn total_send_fees(
             CreateRsa2048Challenge(_) => {
                 fees.fee(ActionCosts::create_rsa2048_challenge).send_fee(sender_is_receiver)
             }
+            DeployGlobalContract(DeployGlobalContractAction { code }) => {
+                let num_bytes = code.len() as u64;
+                fees.fee(ActionCosts::deploy_global_contract_base).send_fee(sender_is_receiver)
+                    + fees
+                        .fee(ActionCosts::deploy_global_contract_byte)
+                        .send_fee(sender_is_receiver)
+                        * num_bytes
+            }
+            UseGlobalContract(_) => {
+                fees.fee(ActionCosts::use_global_contract).send_fee(sender_is_receiver)
+            }
+            DeployContractWithMigration(action) => {
+                let DeployContractWithMigrationAction {
+                    code,
+                    migrate_method_name,
+                    migrate_args,
+                    ..
+                } = action.as_ref();
+                let num_code_bytes = code.len() as u64;
+                let num_call_bytes =
+                    migrate_method_name.as_bytes().len() as u64 + migrate_args.len() as u64;
+                fees.fee(ActionCosts::deploy_contract_base).send_fee(sender_is_receiver)
+                    + fees.fee(ActionCosts::deploy_contract_byte).send_fee(sender_is_receiver)
+                        * num_code_bytes
+                    + fees.fee(ActionCosts::function_call_base).send_fee(sender_is_receiver)
+                    + fees.fee(ActionCosts::function_call_byte).send_fee(sender_is_receiver)
+                        * num_call_bytes
+            }
         };
         result = safe_add_gas(result, delta)?;
     }
@@ -224,6 +256,24 @@ pub fn exec_fee(config: &RuntimeConfig, action: &Action, receiver_id: &AccountId
         Delegate(_) => fees.fee(ActionCosts::delegate).exec_fee(),
         RegisterRsa2048Keys(_) => fees.fee(ActionCosts::register_rsa2048_keys).exec_fee(),
         CreateRsa2048Challenge(_) => fees.fee(ActionCosts::create_rsa2048_challenge).exec_fee(),
+        DeployGlobalContract(DeployGlobalContractAction { code }) => {
+            let num_bytes = code.len() as u64;
+            fees.fee(ActionCosts::deploy_global_contract_base).exec_fee()
+                + fees.fee(ActionCosts::deploy_global_contract_byte).exec_fee() * num_bytes
+        }
+        UseGlobalContract(_) => fees.fee(ActionCosts::use_global_contract).exec_fee(),
+        DeployContractWithMigration(action) => {
+            let DeployContractWithMigrationAction {
+                code, migrate_method_name, migrate_args, ..
+            } = action.as_ref();
+            let num_code_bytes = code.len() as u64;
+            let num_call_bytes =
+                migrate_method_name.as_bytes().len() as u64 + migrate_args.len() as u64;
+            fees.fee(ActionCosts::deploy_contract_base).exec_fee()
+                + fees.fee(ActionCosts::deploy_contract_byte).exec_fee() * num_code_bytes
+                + fees.fee(ActionCosts::function_call_base).exec_fee()
+                + fees.fee(ActionCosts::function_call_byte).exec_fee() * num_call_bytes
+        }
     }
 }
 
@@ -274,7 +324,9 @@ pub fn tx_cost(
         gas_remaining,
         total_prepaid_exec_fees(config, &transaction.actions, &transaction.receiver_id)?,
     )?;
-    let burnt_amount = safe_gas_to_balance(gas_price, gas_burnt)?;
+    // The priority fee is burnt together with the gas spent on converting the transaction.
+    let burnt_amount =
+        safe_add_balance(safe_gas_to_balance(gas_price, gas_burnt)?, transaction.tip())?;
     let remaining_gas_amount = safe_gas_to_balance(receipt_gas_price, gas_remaining)?;
     let mut total_cost = safe_add_balance(burnt_amount, remaining_gas_amount)?;
     total_cost = safe_add_balance(total_cost, total_deposit(&transaction.actions)?)?;
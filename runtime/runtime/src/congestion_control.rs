@@ -0,0 +1,258 @@
+use crate::config::{safe_add_gas, total_prepaid_exec_fees, total_prepaid_gas};
+use std::collections::HashMap;
+use unc_parameters::RuntimeConfig;
+use unc_primitives::congestion_info::{BlockCongestionInfo, CongestionControl, CongestionInfo};
+use unc_primitives::errors::{IntegerOverflowError, RuntimeError, StorageError};
+use unc_primitives::receipt::{
+    BufferedReceiptIndices, DelayedReceiptIndices, Receipt, ReceiptEnum,
+};
+use unc_primitives::shard_layout::ShardLayout;
+use unc_primitives::trie_key::TrieKey;
+use unc_primitives::types::{Gas, ShardId};
+use unc_store::{
+    get, get_buffered_receipt_indices, set, set_buffered_receipt, TrieAccess, TrieUpdate,
+};
+
+/// Gas attributed to a receipt while it's in the delayed queue or in an outgoing buffer.
+///
+/// This is the gas the receipt may still burn on the receiving shard, i.e. its prepaid gas
+/// and execution fees. Data and global contract distribution receipts carry no gas.
+pub(crate) fn receipt_congestion_gas(
+    receipt: &Receipt,
+    config: &RuntimeConfig,
+) -> Result<Gas, IntegerOverflowError> {
+    match &receipt.receipt {
+        ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
+            safe_add_gas(
+                total_prepaid_gas(&action_receipt.actions)?,
+                total_prepaid_exec_fees(config, &action_receipt.actions, &receipt.receiver_id)?,
+            )
+        }
+        ReceiptEnum::Data(_)
+        | ReceiptEnum::PromiseResume(_)
+        | ReceiptEnum::GlobalContractDistribution(_) => Ok(0),
+    }
+}
+
+/// Size of a receipt in the state, used for the memory congestion.
+pub(crate) fn receipt_size(receipt: &Receipt) -> Result<u64, IntegerOverflowError> {
+    let size = borsh::object_length(receipt).map_err(|_| IntegerOverflowError)?;
+    size.try_into().map_err(|_| IntegerOverflowError)
+}
+
+/// Accounts for a receipt added to the delayed receipts queue.
+pub(crate) fn add_delayed_receipt(
+    congestion_info: &mut Option<CongestionInfo>,
+    receipt: &Receipt,
+    config: &RuntimeConfig,
+) -> Result<(), IntegerOverflowError> {
+    if let Some(congestion_info) = congestion_info {
+        congestion_info.add_delayed_receipt_gas(receipt_congestion_gas(receipt, config)?)?;
+        congestion_info.add_receipt_bytes(receipt_size(receipt)?)?;
+    }
+    Ok(())
+}
+
+/// Accounts for a receipt removed from the delayed receipts queue.
+pub(crate) fn remove_delayed_receipt(
+    congestion_info: &mut Option<CongestionInfo>,
+    receipt: &Receipt,
+    config: &RuntimeConfig,
+) -> Result<(), IntegerOverflowError> {
+    if let Some(congestion_info) = congestion_info {
+        congestion_info.remove_delayed_receipt_gas(receipt_congestion_gas(receipt, config)?)?;
+        congestion_info.remove_receipt_bytes(receipt_size(receipt)?)?;
+    }
+    Ok(())
+}
+
+/// Computes the congestion info of the shard from its delayed receipts queue and outgoing
+/// buffers. Used when the congestion info of the shard isn't known yet, i.e. for the first
+/// chunk after congestion control was enabled or after resharding.
+pub(crate) fn bootstrap_congestion_info(
+    state: &dyn TrieAccess,
+    config: &RuntimeConfig,
+    shard_id: ShardId,
+) -> Result<CongestionInfo, RuntimeError> {
+    let mut congestion_info = CongestionInfo::default();
+    congestion_info.set_allowed_shard(shard_id);
+
+    let delayed_receipt_indices: DelayedReceiptIndices =
+        get(state, &TrieKey::DelayedReceiptIndices)?.unwrap_or_default();
+    for index in delayed_receipt_indices.first_index..delayed_receipt_indices.next_available_index {
+        let receipt: Receipt =
+            get(state, &TrieKey::DelayedReceipt { index })?.ok_or_else(|| {
+                StorageError::StorageInconsistentState(format!(
+                    "Delayed receipt #{} should be in the state",
+                    index
+                ))
+            })?;
+        congestion_info.add_delayed_receipt_gas(receipt_congestion_gas(&receipt, config)?)?;
+        congestion_info.add_receipt_bytes(receipt_size(&receipt)?)?;
+    }
+
+    let buffered_receipt_indices = get_buffered_receipt_indices(state)?;
+    for (&receiving_shard, indices) in &buffered_receipt_indices.shard_buffers {
+        for index in indices.first_index..indices.next_available_index {
+            let receipt = get_buffered_receipt(state, receiving_shard, index)?;
+            congestion_info.add_buffered_receipt_gas(receipt_congestion_gas(&receipt, config)?)?;
+            congestion_info.add_receipt_bytes(receipt_size(&receipt)?)?;
+        }
+    }
+    Ok(congestion_info)
+}
+
+pub(crate) fn get_buffered_receipt(
+    state: &dyn TrieAccess,
+    receiving_shard: ShardId,
+    index: u64,
+) -> Result<Receipt, StorageError> {
+    get(state, &TrieKey::BufferedReceipt { receiving_shard, index })?.ok_or_else(|| {
+        StorageError::StorageInconsistentState(format!(
+            "Buffered receipt #{} to shard {} should be in the state",
+            index, receiving_shard
+        ))
+    })
+}
+
+/// Sends the outgoing receipts of a chunk to their receiving shards, within the gas each of
+/// them allows this shard to forward. The receipts which don't fit are kept in the outgoing
+/// buffer of their receiving shard, to be forwarded by the next chunks in order.
+pub(crate) struct ReceiptSink<'a> {
+    own_shard_id: ShardId,
+    shard_layout: ShardLayout,
+    config: &'a RuntimeConfig,
+    congestion_info: &'a mut CongestionInfo,
+    buffered_receipt_indices: BufferedReceiptIndices,
+    initial_buffered_receipt_indices: BufferedReceiptIndices,
+    /// Gas that may still be forwarded to each shard in this chunk.
+    outgoing_limit: HashMap<ShardId, Gas>,
+    /// Receipts leaving the shard with this chunk.
+    outgoing_receipts: Vec<Receipt>,
+}
+
+impl<'a> ReceiptSink<'a> {
+    pub(crate) fn new(
+        state: &dyn TrieAccess,
+        own_shard_id: ShardId,
+        shard_layout: ShardLayout,
+        config: &'a RuntimeConfig,
+        congestion_info: &'a mut CongestionInfo,
+        block_congestion_info: &BlockCongestionInfo,
+    ) -> Result<Self, StorageError> {
+        let outgoing_limit = block_congestion_info
+            .iter()
+            .filter(|(&shard_id, _)| shard_id != own_shard_id)
+            .map(|(&shard_id, info)| {
+                let control = CongestionControl::new(
+                    config.congestion_control_config,
+                    info.congestion_info,
+                    info.missed_chunks_count,
+                );
+                (shard_id, control.outgoing_gas_limit(own_shard_id))
+            })
+            .collect();
+        let buffered_receipt_indices = get_buffered_receipt_indices(state)?;
+        Ok(Self {
+            own_shard_id,
+            shard_layout,
+            config,
+            congestion_info,
+            initial_buffered_receipt_indices: buffered_receipt_indices.clone(),
+            buffered_receipt_indices,
+            outgoing_limit,
+            outgoing_receipts: vec![],
+        })
+    }
+
+    /// Forwards the previously buffered receipts, oldest first, as long as they fit into the
+    /// outgoing limits of their receiving shards.
+    pub(crate) fn forward_from_buffers(
+        &mut self,
+        state_update: &mut TrieUpdate,
+    ) -> Result<(), RuntimeError> {
+        let receiving_shards: Vec<ShardId> =
+            self.buffered_receipt_indices.shard_buffers.keys().copied().collect();
+        for receiving_shard in receiving_shards {
+            loop {
+                let indices = self.buffered_receipt_indices.shard_buffers[&receiving_shard];
+                if indices.first_index >= indices.next_available_index {
+                    break;
+                }
+                let receipt =
+                    get_buffered_receipt(state_update, receiving_shard, indices.first_index)?;
+                let gas = receipt_congestion_gas(&receipt, self.config)?;
+                if !self.try_consume_limit(receiving_shard, gas) {
+                    break;
+                }
+                state_update.remove(TrieKey::BufferedReceipt {
+                    receiving_shard,
+                    index: indices.first_index,
+                });
+                // Math checked above: first_index is less than next_available_index
+                self.buffered_receipt_indices
+                    .shard_buffers
+                    .get_mut(&receiving_shard)
+                    .expect("the buffer was looked up above")
+                    .first_index += 1;
+                self.congestion_info.remove_buffered_receipt_gas(gas)?;
+                self.congestion_info.remove_receipt_bytes(receipt_size(&receipt)?)?;
+                self.outgoing_receipts.push(receipt);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a new outgoing receipt, or buffers it if its receiving shard already has
+    /// buffered receipts or can't take its gas in this chunk.
+    pub(crate) fn forward_or_buffer_receipt(
+        &mut self,
+        state_update: &mut TrieUpdate,
+        receipt: Receipt,
+    ) -> Result<(), RuntimeError> {
+        let receiving_shard = receipt.receiver_shard_id(&self.shard_layout);
+        let gas = receipt_congestion_gas(&receipt, self.config)?;
+        let buffer_is_empty = self
+            .buffered_receipt_indices
+            .shard_buffers
+            .get(&receiving_shard)
+            .map_or(true, |indices| indices.len() == 0);
+        if buffer_is_empty && self.try_consume_limit(receiving_shard, gas) {
+            self.outgoing_receipts.push(receipt);
+            return Ok(());
+        }
+        self.congestion_info.add_buffered_receipt_gas(gas)?;
+        self.congestion_info.add_receipt_bytes(receipt_size(&receipt)?)?;
+        set_buffered_receipt(
+            state_update,
+            &mut self.buffered_receipt_indices,
+            receiving_shard,
+            &receipt,
+        );
+        Ok(())
+    }
+
+    /// Writes the updated buffer indices to the state and returns the receipts leaving the
+    /// shard with this chunk.
+    pub(crate) fn finalize(self, state_update: &mut TrieUpdate) -> Vec<Receipt> {
+        if self.buffered_receipt_indices != self.initial_buffered_receipt_indices {
+            set(state_update, TrieKey::BufferedReceiptIndices, &self.buffered_receipt_indices);
+        }
+        self.outgoing_receipts
+    }
+
+    /// Receipts to the own shard, and to shards whose congestion isn't known, aren't limited.
+    fn try_consume_limit(&mut self, receiving_shard: ShardId, gas: Gas) -> bool {
+        if receiving_shard == self.own_shard_id {
+            return true;
+        }
+        match self.outgoing_limit.get_mut(&receiving_shard) {
+            None => true,
+            Some(limit) if *limit >= gas => {
+                *limit -= gas;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+}
@@ -8,7 +8,10 @@ use unc_primitives::types::{
 use unc_primitives::utils::create_data_id;
 use unc_primitives::version::ProtocolVersion;
 use unc_primitives_core::types::Power;
-use unc_store::{get_code, KeyLookupMode, TrieUpdate, TrieUpdateValuePtr};
+use unc_store::{
+    get_code, get_global_code, has_promise_yield_receipt, KeyLookupMode, TrieUpdate,
+    TrieUpdateValuePtr,
+};
 use unc_vm_runner::logic::errors::{AnyError, VMLogicError};
 use unc_vm_runner::logic::types::ReceiptIndex;
 use unc_vm_runner::logic::{External, StorageGetMode, ValuePtr};
@@ -86,8 +89,13 @@ impl<'a> RuntimeExt<'a> {
         self.account_id
     }
 
+    /// Returns the code of the account, falling back to the global contract code with the same
+    /// hash for accounts which use a global contract.
     pub fn get_code(&self, code_hash: CryptoHash) -> Result<Option<ContractCode>, StorageError> {
-        get_code(self.trie_update, self.account_id, Some(code_hash))
+        match get_code(self.trie_update, self.account_id, Some(code_hash))? {
+            Some(code) => Ok(Some(code)),
+            None => get_global_code(self.trie_update, code_hash),
+        }
     }
 
     pub fn create_storage_key(&self, key: &[u8]) -> TrieKey {
@@ -225,6 +233,33 @@ impl<'a> External for RuntimeExt<'a> {
         self.receipt_manager.create_receipt(data_ids, receipt_indices, receiver_id)
     }
 
+    fn create_promise_yield_receipt(
+        &mut self,
+        receiver_id: AccountId,
+    ) -> Result<(ReceiptIndex, CryptoHash), VMLogicError> {
+        let data_id = self.generate_data_id();
+        let receipt_index =
+            self.receipt_manager.create_promise_yield_receipt(data_id, receiver_id)?;
+        Ok((receipt_index, data_id))
+    }
+
+    fn submit_promise_resume_data(
+        &mut self,
+        data_id: CryptoHash,
+        data: Vec<u8>,
+    ) -> Result<bool, VMLogicError> {
+        // The yielded receipt is either stored in the state by a previous function call, or
+        // was created by the current one and is not sent yet.
+        let is_yielded =
+            has_promise_yield_receipt(self.trie_update, self.account_id.clone(), data_id)
+                .map_err(wrap_storage_error)?
+                || self.receipt_manager.has_promise_yield_receipt(data_id);
+        if !is_yielded {
+            return Ok(false);
+        }
+        Ok(self.receipt_manager.create_promise_resume_data_receipt(data_id, data))
+    }
+
     fn append_action_create_account(
         &mut self,
         receipt_index: ReceiptIndex,
@@ -324,6 +359,36 @@ impl<'a> External for RuntimeExt<'a> {
         self.receipt_manager.append_action_delete_account(receipt_index, beneficiary_id)
     }
 
+    fn append_action_register_rsa2048_keys(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: unc_crypto::PublicKey,
+        operation_type: u8,
+        args: Vec<u8>,
+    ) {
+        self.receipt_manager.append_action_register_rsa2048_keys(
+            receipt_index,
+            public_key,
+            operation_type,
+            args,
+        )
+    }
+
+    fn append_action_create_rsa2048_challenge(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: unc_crypto::PublicKey,
+        challenge_key: unc_crypto::PublicKey,
+        args: Vec<u8>,
+    ) {
+        self.receipt_manager.append_action_create_rsa2048_challenge(
+            receipt_index,
+            public_key,
+            challenge_key,
+            args,
+        )
+    }
+
     fn get_receipt_receiver(&self, receipt_index: ReceiptIndex) -> &AccountId {
         self.receipt_manager.get_receipt_receiver(receipt_index)
     }
@@ -4,6 +4,7 @@ use crate::config::{
     exec_fee, safe_add_balance, safe_add_compute, safe_add_gas, safe_gas_to_balance, total_deposit,
     total_prepaid_exec_fees, total_prepaid_gas,
 };
+use crate::congestion_control::{bootstrap_congestion_info, ReceiptSink};
 use crate::prefetch::TriePrefetcher;
 use crate::verifier::{check_storage_pledge, validate_receipt, StorageStakingError};
 pub use crate::verifier::{
@@ -15,10 +16,12 @@ use unc_parameters::{ActionCosts, RuntimeConfig};
 pub use unc_primitives;
 use unc_primitives::account::Account;
 use unc_primitives::checked_feature;
+use unc_primitives::congestion_info::{CongestionControl, CongestionInfo};
 use unc_primitives::errors::{ActionError, ActionErrorKind, RuntimeError, TxExecutionError};
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::receipt::{
-    ActionReceipt, DataReceipt, DelayedReceiptIndices, Receipt, ReceiptEnum, ReceivedData,
+    ActionReceipt, DataReceipt, DelayedReceiptIndices, PromiseYieldIndices, PromiseYieldTimeout,
+    Receipt, ReceiptEnum, ReceivedData,
 };
 pub use unc_primitives::runtime::apply_state::ApplyState;
 use unc_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
@@ -39,12 +42,15 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::debug;
 use unc_primitives::utils::{
-    create_action_hash, create_receipt_id_from_receipt, create_receipt_id_from_transaction,
+    create_action_hash, create_receipt_id_from_data_id, create_receipt_id_from_receipt,
+    create_receipt_id_from_transaction,
 };
 use unc_primitives::version::{ProtocolFeature, ProtocolVersion};
 use unc_store::{
-    get, get_account, get_postponed_receipt, get_received_data, remove_postponed_receipt, set,
-    set_account, set_delayed_receipt, set_postponed_receipt, set_received_data, PartialStorage,
+    get, get_account, get_postponed_receipt, get_promise_yield_indices, get_promise_yield_receipt,
+    get_received_data, has_promise_yield_receipt, remove_postponed_receipt,
+    remove_promise_yield_receipt, set, set_account, set_delayed_receipt, set_postponed_receipt,
+    set_promise_yield_receipt, set_promise_yield_timeout, set_received_data, PartialStorage,
     StorageError, Trie, TrieChanges, TrieUpdate,
 };
 use unc_store::{set_access_key, set_code};
@@ -58,6 +64,7 @@ mod actions;
 pub mod adapter;
 mod balance_checker;
 pub mod config;
+mod congestion_control;
 pub mod ext;
 mod metrics;
 mod prefetch;
@@ -122,6 +129,8 @@ pub struct ApplyResult {
     pub proof: Option<PartialStorage>,
     pub delayed_receipts_count: u64,
     pub metrics: Option<metrics::ApplyMetrics>,
+    /// Congestion info of the shard after applying the chunk, if congestion control is enabled.
+    pub congestion_info: Option<CongestionInfo>,
 }
 
 #[derive(Debug)]
@@ -473,6 +482,43 @@ impl Runtime {
                     create_rsa2048_challenge,
                 )?;
             }
+            Action::DeployGlobalContract(deploy_global_contract) => {
+                action_deploy_global_contract(
+                    state_update,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    &mut result,
+                    account_id,
+                    deploy_global_contract,
+                    apply_state,
+                    epoch_info_provider,
+                )?;
+            }
+            Action::UseGlobalContract(use_global_contract) => {
+                action_use_global_contract(
+                    state_update,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    &mut result,
+                    account_id,
+                    use_global_contract,
+                )?;
+            }
+            Action::DeployContractWithMigration(deploy_contract_with_migration) => {
+                action_deploy_contract_with_migration(
+                    state_update,
+                    apply_state,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    receipt,
+                    action_receipt,
+                    promise_results,
+                    &mut result,
+                    account_id,
+                    deploy_contract_with_migration,
+                    action_hash,
+                    &apply_state.config,
+                    action_index + 1 == actions.len(),
+                    epoch_info_provider,
+                )?;
+            }
         };
         Ok(result)
     }
@@ -488,9 +534,12 @@ impl Runtime {
         validator_pledge_proposals: &mut Vec<ValidatorPledge>,
         stats: &mut ApplyStats,
         epoch_info_provider: &dyn EpochInfoProvider,
+        promise_yield_indices: &mut PromiseYieldIndices,
     ) -> Result<ExecutionOutcomeWithId, RuntimeError> {
         let action_receipt = match &receipt.receipt {
-            ReceiptEnum::Action(action_receipt) => action_receipt,
+            ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
+                action_receipt
+            }
             _ => unreachable!("given receipt should be an action receipt"),
         };
         let account_id = &receipt.receiver_id;
@@ -702,7 +751,8 @@ impl Runtime {
                     .expect("the receipt for the given receipt index should exist")
                     .receipt
                 {
-                    ReceiptEnum::Action(ref mut new_action_receipt) => new_action_receipt
+                    ReceiptEnum::Action(ref mut new_action_receipt)
+                    | ReceiptEnum::PromiseYield(ref mut new_action_receipt) => new_action_receipt
                         .output_data_receivers
                         .extend_from_slice(&action_receipt.output_data_receivers),
                     _ => unreachable!("the receipt should be an action receipt"),
@@ -728,6 +778,7 @@ impl Runtime {
         }
 
         // Generating receipt IDs
+        let mut has_promise_yield = false;
         let receipt_ids = result
             .new_receipts
             .into_iter()
@@ -742,15 +793,36 @@ impl Runtime {
                 );
 
                 new_receipt.receipt_id = receipt_id;
-                let is_action = matches!(&new_receipt.receipt, ReceiptEnum::Action(_));
-                outgoing_receipts.push(new_receipt);
-                if is_action {
-                    Some(receipt_id)
-                } else {
-                    None
+                match &new_receipt.receipt {
+                    // The yielded receipt never leaves the account, so it's stored right away
+                    // and can be resumed by the following receipts of the same chunk.
+                    ReceiptEnum::PromiseYield(_) => {
+                        Self::store_promise_yield_receipt(
+                            state_update,
+                            apply_state,
+                            &new_receipt,
+                            promise_yield_indices,
+                        );
+                        has_promise_yield = true;
+                        Some(receipt_id)
+                    }
+                    ReceiptEnum::Action(_) => {
+                        outgoing_receipts.push(new_receipt);
+                        Some(receipt_id)
+                    }
+                    ReceiptEnum::Data(_)
+                    | ReceiptEnum::PromiseResume(_)
+                    | ReceiptEnum::GlobalContractDistribution(_) => {
+                        outgoing_receipts.push(new_receipt);
+                        None
+                    }
                 }
             })
             .collect();
+        if has_promise_yield {
+            state_update
+                .commit(StateChangeCause::ReceiptProcessing { receipt_hash: receipt.get_hash() });
+        }
 
         let status = match result.result {
             Ok(ReturnData::ReceiptIndex(receipt_index)) => {
@@ -854,6 +926,38 @@ impl Runtime {
         Ok(gas_deficit_amount)
     }
 
+    /// Stores a receipt yielded by `promise_yield_create` and enqueues the timeout resuming it
+    /// without the data. The receipt has exactly one input data dependency, which is delivered
+    /// by a `PromiseResume` receipt.
+    ///
+    /// The timeout queue is resumed in order and stops at the first timeout which didn't expire,
+    /// so it relies on `expires_at` never decreasing along the queue. This holds as long as all
+    /// timeouts use the same `yield_timeout_length_in_blocks`; shortening the length requires
+    /// a migration of the queued timeouts.
+    fn store_promise_yield_receipt(
+        state_update: &mut TrieUpdate,
+        apply_state: &ApplyState,
+        receipt: &Receipt,
+        promise_yield_indices: &mut PromiseYieldIndices,
+    ) {
+        let ReceiptEnum::PromiseYield(action_receipt) = &receipt.receipt else {
+            unreachable!("given receipt should be a yielded receipt");
+        };
+        set_promise_yield_receipt(state_update, receipt);
+        let expires_at = apply_state.block_height.saturating_add(
+            apply_state.config.wasm_config.limit_config.yield_timeout_length_in_blocks,
+        );
+        set_promise_yield_timeout(
+            state_update,
+            promise_yield_indices,
+            &PromiseYieldTimeout {
+                account_id: receipt.receiver_id.clone(),
+                data_id: action_receipt.input_data_ids[0],
+                expires_at,
+            },
+        );
+    }
+
     fn process_receipt(
         &self,
         state_update: &mut TrieUpdate,
@@ -864,6 +968,7 @@ impl Runtime {
         validator_pledge_proposals: &mut Vec<ValidatorPledge>,
         stats: &mut ApplyStats,
         epoch_info_provider: &dyn EpochInfoProvider,
+        promise_yield_indices: &mut PromiseYieldIndices,
     ) -> Result<Option<ExecutionOutcomeWithId>, RuntimeError> {
         let account_id = &receipt.receiver_id;
         match receipt.receipt {
@@ -933,6 +1038,7 @@ impl Runtime {
                                 validator_pledge_proposals,
                                 stats,
                                 epoch_info_provider,
+                                promise_yield_indices,
                             )
                             .map(Some);
                     } else {
@@ -988,6 +1094,7 @@ impl Runtime {
                             validator_pledge_proposals,
                             stats,
                             epoch_info_provider,
+                            promise_yield_indices,
                         )
                         .map(Some);
                 } else {
@@ -1005,6 +1112,50 @@ impl Runtime {
                     set_postponed_receipt(state_update, receipt);
                 }
             }
+            ReceiptEnum::PromiseYield(_) => {
+                // Yielded receipts are stored when they are created, but a receipt forwarded
+                // before is stored the same way.
+                Self::store_promise_yield_receipt(
+                    state_update,
+                    apply_state,
+                    receipt,
+                    promise_yield_indices,
+                );
+            }
+            ReceiptEnum::PromiseResume(ref data_receipt) => {
+                // Received the input data of a yielded receipt. The yielded receipt is always
+                // stored before, so if it's missing the yield was already resumed or timed out
+                // and the data is ignored.
+                if let Some(yield_receipt) =
+                    get_promise_yield_receipt(state_update, account_id, data_receipt.data_id)?
+                {
+                    remove_promise_yield_receipt(state_update, account_id, data_receipt.data_id);
+                    set_received_data(
+                        state_update,
+                        account_id.clone(),
+                        data_receipt.data_id,
+                        &ReceivedData { data: data_receipt.data.clone() },
+                    );
+                    // Executing the yielded receipt. It will read the input data and clean it up
+                    // from the state.
+                    return self
+                        .apply_action_receipt(
+                            state_update,
+                            apply_state,
+                            &yield_receipt,
+                            outgoing_receipts,
+                            validator_power_proposals,
+                            validator_pledge_proposals,
+                            stats,
+                            epoch_info_provider,
+                            promise_yield_indices,
+                        )
+                        .map(Some);
+                }
+            }
+            ReceiptEnum::GlobalContractDistribution(ref distribution) => {
+                apply_global_contract_distribution(state_update, apply_state, distribution);
+            }
         };
         // We didn't trigger execution, so we need to commit the state.
         state_update
@@ -1281,6 +1432,11 @@ impl Runtime {
         let mut delayed_receipts_indices: DelayedReceiptIndices =
             get(&state_update, &TrieKey::DelayedReceiptIndices)?.unwrap_or_default();
         let initial_delayed_receipt_indices = delayed_receipts_indices.clone();
+        let mut promise_yield_indices: PromiseYieldIndices =
+            get_promise_yield_indices(&state_update)?;
+        let initial_promise_yield_indices = promise_yield_indices.clone();
+        let congestion_control_enabled =
+            checked_feature!("stable", CongestionControl, apply_state.current_protocol_version);
 
         if !apply_state.is_new_chunk
             && apply_state.current_protocol_version
@@ -1301,9 +1457,34 @@ impl Runtime {
                 proof,
                 delayed_receipts_count: delayed_receipts_indices.len(),
                 metrics: None,
+                // The state doesn't change without a new chunk, and neither does the congestion.
+                congestion_info: if congestion_control_enabled {
+                    apply_state
+                        .congestion_info
+                        .get(&apply_state.shard_id)
+                        .map(|info| info.congestion_info)
+                } else {
+                    None
+                },
             });
         }
 
+        // The congestion info of the shard as of the previous chunk is published in the chunk
+        // header. If it isn't there, it's the first chunk with congestion control, so it's
+        // computed from the state.
+        let mut own_congestion_info = if congestion_control_enabled {
+            match apply_state.congestion_info.get(&apply_state.shard_id) {
+                Some(info) => Some(info.congestion_info),
+                None => Some(bootstrap_congestion_info(
+                    &state_update,
+                    &apply_state.config,
+                    apply_state.shard_id,
+                )?),
+            }
+        } else {
+            None
+        };
+
         let mut outgoing_receipts = Vec::new();
         let mut validator_power_proposals = vec![];
         let mut validator_pledge_proposals = vec![];
@@ -1374,6 +1555,7 @@ impl Runtime {
                 &mut validator_pledge_proposals,
                 &mut stats,
                 epoch_info_provider,
+                &mut promise_yield_indices,
             );
             let node_counter_after = state_update.trie().get_trie_nodes_count();
             tracing::trace!(target: "runtime", ?node_counter_before, ?node_counter_after);
@@ -1422,6 +1604,11 @@ impl Runtime {
                 )?;
             } else {
                 set_delayed_receipt(&mut state_update, &mut delayed_receipts_indices, receipt);
+                congestion_control::add_delayed_receipt(
+                    &mut own_congestion_info,
+                    receipt,
+                    &apply_state.config,
+                )?;
             }
         }
         metrics.local_receipts_done(total_gas_burnt, total_compute_usage);
@@ -1461,6 +1648,11 @@ impl Runtime {
             state_update.remove(key);
             // Math checked above: first_index is less than next_available_index
             delayed_receipts_indices.first_index += 1;
+            congestion_control::remove_delayed_receipt(
+                &mut own_congestion_info,
+                &receipt,
+                &apply_state.config,
+            )?;
             process_receipt(
                 &receipt,
                 &mut state_update,
@@ -1495,18 +1687,106 @@ impl Runtime {
                 )?;
             } else {
                 set_delayed_receipt(&mut state_update, &mut delayed_receipts_indices, receipt);
+                congestion_control::add_delayed_receipt(
+                    &mut own_congestion_info,
+                    receipt,
+                    &apply_state.config,
+                )?;
             }
         }
         metrics.incoming_receipts_done(total_gas_burnt, total_compute_usage);
 
+        // Resume the yielded receipts whose timeout has expired, without the input data.
+        while promise_yield_indices.first_index < promise_yield_indices.next_available_index {
+            if total_compute_usage >= compute_limit {
+                break;
+            }
+            let key = TrieKey::PromiseYieldTimeout { index: promise_yield_indices.first_index };
+            let timeout: PromiseYieldTimeout = get(&state_update, &key)?.ok_or_else(|| {
+                StorageError::StorageInconsistentState(format!(
+                    "Promise yield timeout #{} should be in the state",
+                    promise_yield_indices.first_index
+                ))
+            })?;
+            // The timeouts are ordered by `expires_at`, see `store_promise_yield_receipt`.
+            if timeout.expires_at > apply_state.block_height {
+                break;
+            }
+            // The yield may have been resumed already, in which case there is nothing to do.
+            if has_promise_yield_receipt(
+                &state_update,
+                timeout.account_id.clone(),
+                timeout.data_id,
+            )? {
+                // The resume receipt goes to the current shard. If the yield was also resumed by
+                // a receipt processed in this chunk, that resume is ordered first and this one is
+                // ignored.
+                outgoing_receipts.push(Receipt {
+                    predecessor_id: timeout.account_id.clone(),
+                    receiver_id: timeout.account_id,
+                    receipt_id: create_receipt_id_from_data_id(
+                        apply_state.current_protocol_version,
+                        &timeout.data_id,
+                        &apply_state.prev_block_hash,
+                        &apply_state.block_hash,
+                    ),
+                    receipt: ReceiptEnum::PromiseResume(DataReceipt {
+                        data_id: timeout.data_id,
+                        data: None,
+                    }),
+                });
+            }
+            state_update.remove(key);
+            // Math checked above: first_index is less than next_available_index
+            promise_yield_indices.first_index += 1;
+        }
+
         // No more receipts are executed on this trie, stop any pending prefetches on it.
         if let Some(prefetcher) = &prefetcher {
             prefetcher.clear();
         }
 
+        // With congestion control, the outgoing receipts are forwarded only as far as the
+        // receiving shards allow, the rest waits in the outgoing buffers.
+        let outgoing_receipts = if let Some(congestion_info) = &mut own_congestion_info {
+            let shard_layout = epoch_info_provider.shard_layout(&apply_state.epoch_id)?;
+            let all_shards: Vec<_> = shard_layout.shard_ids().collect();
+            let mut receipt_sink = ReceiptSink::new(
+                &state_update,
+                apply_state.shard_id,
+                shard_layout,
+                &apply_state.config,
+                congestion_info,
+                &apply_state.congestion_info,
+            )?;
+            receipt_sink.forward_from_buffers(&mut state_update)?;
+            for receipt in outgoing_receipts {
+                receipt_sink.forward_or_buffer_receipt(&mut state_update, receipt)?;
+            }
+            let outgoing_receipts = receipt_sink.finalize(&mut state_update);
+
+            let mut congestion_control = CongestionControl::new(
+                apply_state.config.congestion_control_config,
+                *congestion_info,
+                0,
+            );
+            congestion_control.finalize_allowed_shard(
+                apply_state.shard_id,
+                &all_shards,
+                apply_state.block_height,
+            );
+            *congestion_info = *congestion_control.congestion_info();
+            outgoing_receipts
+        } else {
+            outgoing_receipts
+        };
+
         if delayed_receipts_indices != initial_delayed_receipt_indices {
             set(&mut state_update, TrieKey::DelayedReceiptIndices, &delayed_receipts_indices);
         }
+        if promise_yield_indices != initial_promise_yield_indices {
+            set(&mut state_update, TrieKey::PromiseYieldIndices, &promise_yield_indices);
+        }
         check_balance(
             &apply_state.config,
             &state_update,
@@ -1558,6 +1838,7 @@ impl Runtime {
             proof,
             delayed_receipts_count: delayed_receipts_indices.len(),
             metrics: Some(metrics),
+            congestion_info: own_congestion_info,
         })
     }
 
@@ -1601,7 +1882,8 @@ mod tests {
     use unc_primitives::shard_layout::ShardUId;
     use unc_primitives::test_utils::{account_new, MockEpochInfoProvider};
     use unc_primitives::transaction::{
-        AddKeyAction, DeleteKeyAction, DeployContractAction, FunctionCallAction, TransferAction,
+        AddKeyAction, DeleteKeyAction, DeployContractAction, DeployContractWithMigrationAction,
+        FunctionCallAction, TransferAction,
     };
     use unc_primitives::types::MerkleHash;
     use unc_primitives::version::PROTOCOL_VERSION;
@@ -1714,6 +1996,7 @@ mod tests {
             block_height: 1,
             prev_block_hash: Default::default(),
             block_hash: Default::default(),
+            shard_id: Default::default(),
             epoch_id: Default::default(),
             epoch_height: 0,
             gas_price: GAS_PRICE,
@@ -1726,6 +2009,7 @@ mod tests {
             is_new_chunk: true,
             migration_data: Arc::new(MigrationData::default()),
             migration_flags: MigrationFlags::default(),
+            congestion_info: Default::default(),
         };
 
         (runtime, tries, root, apply_state, signer, MockEpochInfoProvider::default())
@@ -2702,6 +2986,825 @@ mod tests {
             assert_matches!(second.outcome.status, ExecutionStatus::Failure(_));
         });
     }
+
+    #[test]
+    fn test_deploy_contract_with_migration() {
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_atto(1_000_000), to_atto(500_000), to_tera(5), 10u64.pow(15));
+
+        let rs_code = unc_test_contracts::rs_contract().to_vec();
+        let migrate_receipt = create_receipt_with_actions(
+            alice_account(),
+            signer.clone(),
+            vec![Action::DeployContractWithMigration(Box::new(
+                DeployContractWithMigrationAction {
+                    code: rs_code.clone(),
+                    migrate_method_name: "ext_sha256".to_string(),
+                    migrate_args: b"migrate".to_vec(),
+                    gas: 10u64.pow(14),
+                },
+            ))],
+        );
+        // The trivial contract has no methods, so its migration must fail and
+        // leave the previously deployed code in place.
+        let failed_migrate_receipt = create_receipt_with_actions(
+            alice_account(),
+            signer,
+            vec![Action::DeployContractWithMigration(Box::new(
+                DeployContractWithMigrationAction {
+                    code: unc_test_contracts::trivial_contract().to_vec(),
+                    migrate_method_name: "migrate".to_string(),
+                    migrate_args: vec![],
+                    gas: 10u64.pow(14),
+                },
+            ))],
+        );
+
+        let apply_result = runtime
+            .apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &[migrate_receipt.clone(), failed_migrate_receipt.clone()],
+                &[],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap();
+        let mut store_update = tries.store_update();
+        let root = tries.apply_all(
+            &apply_result.trie_changes,
+            ShardUId::single_shard(),
+            &mut store_update,
+        );
+        store_update.commit().unwrap();
+
+        assert_matches!(&apply_result.outcomes[..], [first, second] => {
+            assert_eq!(first.id, migrate_receipt.receipt_id);
+            assert_matches!(first.outcome.status, ExecutionStatus::SuccessValue(_));
+
+            assert_eq!(second.id, failed_migrate_receipt.receipt_id);
+            assert_matches!(
+                &second.outcome.status,
+                ExecutionStatus::Failure(TxExecutionError::ActionError(ActionError {
+                    kind: ActionErrorKind::ContractMigrationFailed { method_name, .. },
+                    ..
+                })) if method_name == "migrate"
+            );
+        });
+
+        let state_update = tries.new_trie_update(ShardUId::single_shard(), root);
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        assert_eq!(account.code_hash(), hash(&rs_code));
+    }
+
+    #[test]
+    fn test_trace_function_calls() {
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_atto(1_000_000), to_atto(500_000), to_tera(5), 10u64.pow(15));
+
+        let receipt = create_receipt_with_actions(
+            alice_account(),
+            signer,
+            vec![
+                Action::DeployContract(DeployContractAction {
+                    code: unc_test_contracts::rs_contract().to_vec(),
+                }),
+                Action::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: "write_key_value".to_string(),
+                    args: [10u64.to_le_bytes(), 20u64.to_le_bytes()].concat(),
+                    gas: 10u64.pow(14),
+                    deposit: 0,
+                })),
+            ],
+        );
+
+        let apply = || {
+            runtime
+                .apply(
+                    tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                    &None,
+                    &apply_state,
+                    &[receipt.clone()],
+                    &[],
+                    &epoch_info_provider,
+                    Default::default(),
+                )
+                .unwrap()
+        };
+        let untraced_result = apply();
+        let (traced_result, traces) = unc_vm_runner::logic::with_tracing(apply);
+        assert_eq!(traced_result.state_root, untraced_result.state_root);
+        assert_eq!(traced_result.outcomes, untraced_result.outcomes);
+
+        assert_matches!(&traces[..], [trace] => {
+            assert_eq!(trace.receipt_id, receipt.receipt_id);
+            assert_eq!(trace.receiver_id, alice_account());
+            assert_eq!(trace.method_name, "write_key_value");
+            assert!(trace.trace.steps.iter().any(|step| step.event
+                == unc_vm_runner::logic::trace::TraceEvent::StorageWrite {
+                    key: 10u64.to_le_bytes().to_vec(),
+                    value: 20u64.to_le_bytes().to_vec(),
+                    evicted: None,
+                }));
+        });
+    }
+
+    #[cfg(feature = "nightly")]
+    mod yield_resume {
+        use super::*;
+
+        /// Chunks applied on a single shard with the nightly test contract deployed on
+        /// `alice_account()`. The outgoing receipts of a chunk are applied in the next one.
+        struct TestEnv {
+            runtime: Runtime,
+            tries: ShardTries,
+            root: CryptoHash,
+            apply_state: ApplyState,
+            signer: Arc<InMemorySigner>,
+            epoch_info_provider: MockEpochInfoProvider,
+            outgoing_receipts: Vec<Receipt>,
+            nonce: u64,
+        }
+
+        impl TestEnv {
+            fn new(yield_timeout_length_in_blocks: u64) -> Self {
+                let (runtime, tries, root, mut apply_state, signer, _) =
+                    setup_runtime(to_atto(1_000_000), 0, 0, 10u64.pow(15));
+                let mut config = RuntimeConfig::test();
+                config.wasm_config.limit_config.yield_timeout_length_in_blocks =
+                    yield_timeout_length_in_blocks;
+                apply_state.config = Arc::new(config);
+                apply_state.block_height = 0;
+                let mut env = Self {
+                    runtime,
+                    tries,
+                    root,
+                    apply_state,
+                    signer,
+                    epoch_info_provider: MockEpochInfoProvider::default(),
+                    outgoing_receipts: vec![],
+                    nonce: 0,
+                };
+                let deploy = env.receipt(Action::DeployContract(DeployContractAction {
+                    code: unc_test_contracts::nightly_rs_contract().to_vec(),
+                }));
+                env.apply(&[deploy]);
+                env
+            }
+
+            fn receipt(&mut self, action: Action) -> Receipt {
+                let mut receipt =
+                    create_receipt_with_actions(alice_account(), self.signer.clone(), vec![action]);
+                self.nonce += 1;
+                receipt.receipt_id = hash(&self.nonce.to_le_bytes());
+                receipt
+            }
+
+            fn call(&mut self, method_name: &str, args: Vec<u8>) -> Receipt {
+                self.receipt(Action::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: method_name.to_string(),
+                    args,
+                    gas: 10u64.pow(14),
+                    deposit: 0,
+                })))
+            }
+
+            fn resume_call(&mut self, data_id: &CryptoHash, payload: &[u8]) -> Receipt {
+                self.call("call_yield_resume", [&data_id.as_bytes()[..], payload].concat())
+            }
+
+            /// Applies the next chunk with the outgoing receipts of the previous chunk followed
+            /// by the given receipts. Applying checks the balance, so it covers the accounting of
+            /// the yielded receipts.
+            fn apply(&mut self, receipts: &[Receipt]) -> ApplyResult {
+                self.apply_state.block_height += 1;
+                let incoming_receipts = std::mem::take(&mut self.outgoing_receipts)
+                    .into_iter()
+                    .chain(receipts.to_vec());
+                let apply_result = self
+                    .runtime
+                    .apply(
+                        self.tries.get_trie_for_shard(ShardUId::single_shard(), self.root),
+                        &None,
+                        &self.apply_state,
+                        &incoming_receipts.collect::<Vec<_>>(),
+                        &[],
+                        &self.epoch_info_provider,
+                        Default::default(),
+                    )
+                    .unwrap();
+                let mut store_update = self.tries.store_update();
+                self.root = self.tries.apply_all(
+                    &apply_result.trie_changes,
+                    ShardUId::single_shard(),
+                    &mut store_update,
+                );
+                store_update.commit().unwrap();
+                self.outgoing_receipts = apply_result.outgoing_receipts.clone();
+                apply_result
+            }
+
+            /// Yields in a new chunk, returning the data id and the id of the yielded receipt.
+            fn create_yield(&mut self) -> (CryptoHash, CryptoHash) {
+                let create = self.call("call_yield_create", vec![]);
+                let apply_result = self.apply(&[create.clone()]);
+                let outcome = outcome(&apply_result, &create.receipt_id);
+                let data_id = match &outcome.status {
+                    ExecutionStatus::SuccessValue(value) => {
+                        CryptoHash::try_from(value.as_slice()).unwrap()
+                    }
+                    status => panic!("unexpected status {status:?}"),
+                };
+                (data_id, outcome.receipt_ids[0])
+            }
+
+            fn has_yield(&self, data_id: &CryptoHash) -> bool {
+                let state = self.tries.new_trie_update(ShardUId::single_shard(), self.root);
+                has_promise_yield_receipt(&state, alice_account(), *data_id).unwrap()
+            }
+
+            fn promise_yield_indices(&self) -> PromiseYieldIndices {
+                let state = self.tries.new_trie_update(ShardUId::single_shard(), self.root);
+                get_promise_yield_indices(&state).unwrap()
+            }
+        }
+
+        fn outcome<'a>(apply_result: &'a ApplyResult, id: &CryptoHash) -> &'a ExecutionOutcome {
+            &apply_result
+                .outcomes
+                .iter()
+                .find(|outcome| &outcome.id == id)
+                .unwrap_or_else(|| panic!("no outcome for {id}"))
+                .outcome
+        }
+
+        fn success_value(apply_result: &ApplyResult, id: &CryptoHash) -> Vec<u8> {
+            match &outcome(apply_result, id).status {
+                ExecutionStatus::SuccessValue(value) => value.clone(),
+                status => panic!("unexpected status {status:?}"),
+            }
+        }
+
+        #[test]
+        fn test_yield_then_resume_with_data() {
+            let mut env = TestEnv::new(10);
+            let (data_id, yield_receipt_id) = env.create_yield();
+            assert!(env.has_yield(&data_id));
+            assert_eq!(env.promise_yield_indices().next_available_index, 1);
+
+            let resume = env.resume_call(&data_id, b"payload");
+            let apply_result = env.apply(&[resume.clone()]);
+            assert_eq!(success_value(&apply_result, &resume.receipt_id), [1]);
+            assert!(env.outgoing_receipts.iter().any(|receipt| matches!(
+                &receipt.receipt,
+                ReceiptEnum::PromiseResume(data) if data.data_id == data_id
+            )));
+
+            let apply_result = env.apply(&[]);
+            assert_eq!(success_value(&apply_result, &yield_receipt_id), b"payload");
+            assert!(!env.has_yield(&data_id));
+
+            // The timeout of the resumed yield is dropped without effect.
+            for _ in 0..10 {
+                let apply_result = env.apply(&[]);
+                assert!(apply_result.outcomes.iter().all(|outcome| outcome.id != yield_receipt_id));
+            }
+            let indices = env.promise_yield_indices();
+            assert_eq!(indices.first_index, indices.next_available_index);
+        }
+
+        #[test]
+        fn test_yield_timeout_without_data() {
+            let mut env = TestEnv::new(3);
+            let (data_id, yield_receipt_id) = env.create_yield();
+            let created_at = env.apply_state.block_height;
+
+            while env.apply_state.block_height < created_at + 3 {
+                let apply_result = env.apply(&[]);
+                assert!(apply_result.outcomes.iter().all(|outcome| outcome.id != yield_receipt_id));
+                assert!(env.has_yield(&data_id));
+            }
+            // The timeout expired in the last chunk and resumed the yield without the data.
+            assert_matches!(
+                &env.outgoing_receipts[..],
+                [Receipt { receipt: ReceiptEnum::PromiseResume(data), .. }]
+                    if data.data_id == data_id && data.data.is_none()
+            );
+            let apply_result = env.apply(&[]);
+            assert_eq!(success_value(&apply_result, &yield_receipt_id), b"timeout");
+            assert!(!env.has_yield(&data_id));
+        }
+
+        #[test]
+        fn test_yield_double_resume() {
+            let mut env = TestEnv::new(10);
+            let (data_id, yield_receipt_id) = env.create_yield();
+
+            // Both resumes are accepted while the yield is still stored, only the first one is
+            // delivered.
+            let first = env.resume_call(&data_id, b"first");
+            let second = env.resume_call(&data_id, b"second");
+            let apply_result = env.apply(&[first.clone(), second.clone()]);
+            assert_eq!(success_value(&apply_result, &first.receipt_id), [1]);
+            assert_eq!(success_value(&apply_result, &second.receipt_id), [1]);
+            let apply_result = env.apply(&[]);
+            assert_eq!(success_value(&apply_result, &yield_receipt_id), b"first");
+
+            // Once the yielded receipt was executed, resuming fails.
+            let third = env.resume_call(&data_id, b"third");
+            let apply_result = env.apply(&[third.clone()]);
+            assert_eq!(success_value(&apply_result, &third.receipt_id), [0]);
+            assert!(env
+                .outgoing_receipts
+                .iter()
+                .all(|receipt| !matches!(receipt.receipt, ReceiptEnum::PromiseResume(_))));
+        }
+
+        #[test]
+        fn test_yield_resume_after_timeout() {
+            let mut env = TestEnv::new(1);
+            let (data_id, yield_receipt_id) = env.create_yield();
+            env.apply(&[]);
+            let apply_result = env.apply(&[]);
+            assert_eq!(success_value(&apply_result, &yield_receipt_id), b"timeout");
+
+            let resume = env.resume_call(&data_id, b"payload");
+            let apply_result = env.apply(&[resume.clone()]);
+            assert_eq!(success_value(&apply_result, &resume.receipt_id), [0]);
+            let apply_result = env.apply(&[]);
+            assert!(apply_result.outcomes.iter().all(|outcome| outcome.id != yield_receipt_id));
+        }
+
+        #[test]
+        fn test_yield_resume_in_the_same_chunk() {
+            let mut env = TestEnv::new(10);
+            // The resume follows the yield in the same chunk, before the chunk with the
+            // outgoing receipts of the yield.
+            let create = env.call("call_yield_create", vec![]);
+            let resume = env.call("call_yield_resume_last", b"payload".to_vec());
+            let apply_result = env.apply(&[create.clone(), resume.clone()]);
+            assert_eq!(success_value(&apply_result, &resume.receipt_id), [1]);
+            let yield_receipt_id = outcome(&apply_result, &create.receipt_id).receipt_ids[0];
+
+            let apply_result = env.apply(&[]);
+            assert_eq!(success_value(&apply_result, &yield_receipt_id), b"payload");
+        }
+
+        #[test]
+        fn test_yield_timeout_in_the_same_chunk() {
+            // With no timeout, the yield is resumed by the chunk which created it and the
+            // balance checker has to account for a yielded receipt which is stored, but whose
+            // timeout is already gone.
+            let mut env = TestEnv::new(0);
+            let (data_id, yield_receipt_id) = env.create_yield();
+            assert!(env.has_yield(&data_id));
+            let indices = env.promise_yield_indices();
+            assert_eq!(indices.first_index, indices.next_available_index);
+            let apply_result = env.apply(&[]);
+            assert_eq!(success_value(&apply_result, &yield_receipt_id), b"timeout");
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    mod congestion {
+        use super::*;
+        use crate::congestion_control::{receipt_congestion_gas, receipt_size};
+        use std::collections::BTreeMap;
+        use unc_primitives::congestion_info::{BlockCongestionInfo, ExtendedCongestionInfo};
+        use unc_primitives::shard_layout::ShardLayout;
+        use unc_primitives::types::{ChunkExtra, ShardId};
+
+        const OWN_SHARD: ShardId = 0;
+        const OTHER_SHARD: ShardId = 1;
+
+        /// Chunks of the shard of `alice_account()` in a layout with `bob_account()` on the other
+        /// shard, where at most two transfers may be forwarded to a shard per chunk.
+        struct TestEnv {
+            runtime: Runtime,
+            tries: ShardTries,
+            root: CryptoHash,
+            apply_state: ApplyState,
+            signer: Arc<InMemorySigner>,
+            epoch_info_provider: MockEpochInfoProvider,
+            /// Congestion info of the own shard as of the previous chunk, if known.
+            own_congestion_info: Option<CongestionInfo>,
+            other_congestion_info: ExtendedCongestionInfo,
+            nonce: u64,
+        }
+
+        impl TestEnv {
+            fn new() -> Self {
+                let (runtime, tries, root, mut apply_state, signer, _) =
+                    setup_runtime(to_atto(1_000_000), 0, 0, 10u64.pow(15));
+                let mut config = RuntimeConfig::test();
+                let transfer_gas = transfer_gas(&config);
+                config.congestion_control_config.max_outgoing_gas = 2 * transfer_gas;
+                config.congestion_control_config.min_outgoing_gas = 2 * transfer_gas;
+                apply_state.config = Arc::new(config);
+                let epoch_info_provider = MockEpochInfoProvider::default()
+                    .with_shard_layout(ShardLayout::v1(vec!["b".parse().unwrap()], None, 1));
+                Self {
+                    runtime,
+                    tries,
+                    root,
+                    apply_state,
+                    signer,
+                    epoch_info_provider,
+                    own_congestion_info: Some(CongestionInfo::default()),
+                    other_congestion_info: ExtendedCongestionInfo::default(),
+                    nonce: 0,
+                }
+            }
+
+            /// A transfer of `deposit` to the other shard, so that the transfers can be told
+            /// apart in the outgoing receipts.
+            fn send_money(&mut self, deposit: Balance) -> SignedTransaction {
+                self.nonce += 1;
+                SignedTransaction::send_money(
+                    self.nonce,
+                    alice_account(),
+                    bob_account(),
+                    &*self.signer,
+                    deposit,
+                    CryptoHash::default(),
+                )
+            }
+
+            /// Applies the next chunk and feeds its congestion info to the following one.
+            fn apply(
+                &mut self,
+                receipts: &[Receipt],
+                transactions: &[SignedTransaction],
+            ) -> ApplyResult {
+                let mut shards_congestion_info = BTreeMap::new();
+                if let Some(info) = self.own_congestion_info {
+                    shards_congestion_info.insert(OWN_SHARD, ExtendedCongestionInfo::new(info, 0));
+                }
+                shards_congestion_info.insert(OTHER_SHARD, self.other_congestion_info);
+                self.apply_state.congestion_info = BlockCongestionInfo::new(shards_congestion_info);
+                self.apply_state.block_height += 1;
+                let apply_result = self
+                    .runtime
+                    .apply(
+                        self.tries.get_trie_for_shard(ShardUId::single_shard(), self.root),
+                        &None,
+                        &self.apply_state,
+                        receipts,
+                        transactions,
+                        &self.epoch_info_provider,
+                        Default::default(),
+                    )
+                    .unwrap();
+                let mut store_update = self.tries.store_update();
+                self.root = self.tries.apply_all(
+                    &apply_result.trie_changes,
+                    ShardUId::single_shard(),
+                    &mut store_update,
+                );
+                store_update.commit().unwrap();
+                self.own_congestion_info = apply_result.congestion_info;
+                apply_result
+            }
+
+            fn transfer_gas(&self) -> Gas {
+                transfer_gas(&self.apply_state.config)
+            }
+        }
+
+        fn transfer_gas(config: &RuntimeConfig) -> Gas {
+            let actions = [Action::Transfer(TransferAction { deposit: 1 })];
+            total_prepaid_exec_fees(config, &actions, &bob_account()).unwrap()
+        }
+
+        fn deposits(receipts: &[Receipt]) -> Vec<Balance> {
+            receipts
+                .iter()
+                .map(|receipt| match &receipt.receipt {
+                    ReceiptEnum::Action(ActionReceipt { actions, .. }) => match actions[..] {
+                        [Action::Transfer(TransferAction { deposit })] => deposit,
+                        _ => panic!("unexpected actions {actions:?}"),
+                    },
+                    receipt => panic!("unexpected receipt {receipt:?}"),
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_receipts_over_gas_limit_are_buffered_and_drained_in_order() {
+            let mut env = TestEnv::new();
+            let transfer_gas = env.transfer_gas();
+            let transactions: Vec<_> = (1..=5).map(|deposit| env.send_money(deposit)).collect();
+            let apply_result = env.apply(&[], &transactions);
+            assert_eq!(deposits(&apply_result.outgoing_receipts), [1, 2]);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            assert_eq!(congestion_info.buffered_receipts_gas(), 3 * transfer_gas as u128);
+            assert!(congestion_info.receipt_bytes() > 0);
+
+            // A new receipt waits behind the buffered ones, even if it fits into the limit.
+            let transaction = env.send_money(6);
+            let apply_result = env.apply(&[], &[transaction]);
+            assert_eq!(deposits(&apply_result.outgoing_receipts), [3, 4]);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            assert_eq!(congestion_info.buffered_receipts_gas(), 2 * transfer_gas as u128);
+
+            let apply_result = env.apply(&[], &[]);
+            assert_eq!(deposits(&apply_result.outgoing_receipts), [5, 6]);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            assert_eq!(congestion_info.buffered_receipts_gas(), 0);
+            assert_eq!(congestion_info.receipt_bytes(), 0);
+
+            let apply_result = env.apply(&[], &[]);
+            assert!(apply_result.outgoing_receipts.is_empty());
+        }
+
+        #[test]
+        fn test_receipts_to_shard_over_memory_limit_are_buffered() {
+            let mut env = TestEnv::new();
+            let transfer_gas = env.transfer_gas();
+            // The other shard is full and lets only itself send receipts to it.
+            let mut full_info = CongestionInfo::default();
+            full_info
+                .add_receipt_bytes(
+                    env.apply_state
+                        .config
+                        .congestion_control_config
+                        .max_congestion_memory_consumption,
+                )
+                .unwrap();
+            full_info.set_allowed_shard(OTHER_SHARD);
+            env.other_congestion_info = ExtendedCongestionInfo::new(full_info, 0);
+
+            let transactions: Vec<_> = (1..=3).map(|deposit| env.send_money(deposit)).collect();
+            let apply_result = env.apply(&[], &transactions);
+            assert!(apply_result.outgoing_receipts.is_empty());
+            let congestion_info = apply_result.congestion_info.unwrap();
+            assert_eq!(congestion_info.buffered_receipts_gas(), 3 * transfer_gas as u128);
+
+            // Once this shard is allowed, the receipts are forwarded within the limit for
+            // the allowed shard.
+            full_info.set_allowed_shard(OWN_SHARD);
+            env.other_congestion_info = ExtendedCongestionInfo::new(full_info, 0);
+            let allowed_shard_outgoing_gas =
+                env.apply_state.config.congestion_control_config.allowed_shard_outgoing_gas;
+            let apply_result = env.apply(&[], &[]);
+            let forwarded = (allowed_shard_outgoing_gas / transfer_gas).min(3);
+            let expected: Vec<Balance> = (1..=forwarded as Balance).collect();
+            assert_eq!(deposits(&apply_result.outgoing_receipts), expected);
+
+            // The memory is freed on the other shard, the rest goes out in order.
+            env.other_congestion_info = ExtendedCongestionInfo::default();
+            let apply_result = env.apply(&[], &[]);
+            let expected: Vec<Balance> = (forwarded as Balance + 1..=3).collect();
+            assert_eq!(deposits(&apply_result.outgoing_receipts), expected);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            assert_eq!(congestion_info.buffered_receipts_gas(), 0);
+            assert_eq!(congestion_info.receipt_bytes(), 0);
+        }
+
+        #[test]
+        fn test_bootstrap_congestion_info() {
+            let mut env = TestEnv::new();
+            // Delay all receipts before congestion control is enabled.
+            env.apply_state.current_protocol_version =
+                ProtocolFeature::CongestionControl.protocol_version() - 1;
+            env.apply_state.gas_limit = Some(0);
+            env.own_congestion_info = None;
+            let receipts = generate_receipts(to_atto(1), 3);
+            let apply_result = env.apply(&receipts, &[]);
+            assert_eq!(apply_result.delayed_receipts_count, 3);
+            assert_eq!(apply_result.congestion_info, None);
+
+            // The first chunk with congestion control has no congestion info of its own shard
+            // in the block and computes it from the delayed receipts.
+            env.apply_state.current_protocol_version = PROTOCOL_VERSION;
+            let apply_result = env.apply(&[], &[]);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            let config = &env.apply_state.config;
+            let delayed_gas: Gas = receipts
+                .iter()
+                .map(|receipt| receipt_congestion_gas(receipt, config).unwrap())
+                .sum();
+            let delayed_bytes: u64 =
+                receipts.iter().map(|receipt| receipt_size(receipt).unwrap()).sum();
+            assert_eq!(congestion_info.delayed_receipts_gas(), delayed_gas as u128);
+            assert_eq!(congestion_info.buffered_receipts_gas(), 0);
+            assert_eq!(congestion_info.receipt_bytes(), delayed_bytes);
+            assert_eq!(congestion_info.allowed_shard(), OWN_SHARD);
+
+            // It's published in the chunk extra.
+            let chunk_extra = ChunkExtra::new(
+                &apply_result.state_root,
+                CryptoHash::default(),
+                vec![],
+                vec![],
+                0,
+                0,
+                0,
+                apply_result.congestion_info,
+            );
+            assert_matches!(chunk_extra, ChunkExtra::V3(_));
+            assert_eq!(chunk_extra.congestion_info(), Some(congestion_info));
+
+            // The following chunks carry it over, down to zero once the queue is processed.
+            env.apply_state.gas_limit = Some(10u64.pow(15));
+            let apply_result = env.apply(&[], &[]);
+            assert_eq!(apply_result.delayed_receipts_count, 0);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            assert_eq!(congestion_info.delayed_receipts_gas(), 0);
+        }
+
+        #[test]
+        fn test_congestion_info_with_missed_chunks() {
+            let mut env = TestEnv::new();
+            let transactions: Vec<_> = (1..=3).map(|deposit| env.send_money(deposit)).collect();
+            let apply_result = env.apply(&[], &transactions);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            let state_root = apply_result.state_root;
+
+            // Without a new chunk nothing is forwarded and the congestion info stays the same.
+            env.apply_state.is_new_chunk = false;
+            let apply_result = env.apply(&[], &[]);
+            assert!(apply_result.outgoing_receipts.is_empty());
+            assert_eq!(apply_result.state_root, state_root);
+            assert_eq!(apply_result.congestion_info, Some(congestion_info));
+
+            // The chunks missed by the receiving shard don't limit the receipts sent to it.
+            env.apply_state.is_new_chunk = true;
+            let max_missed_chunks =
+                env.apply_state.config.congestion_control_config.max_congestion_missed_chunks;
+            env.other_congestion_info =
+                ExtendedCongestionInfo::new(CongestionInfo::default(), max_missed_chunks);
+            let apply_result = env.apply(&[], &[]);
+            assert_eq!(deposits(&apply_result.outgoing_receipts), [3]);
+            let congestion_info = apply_result.congestion_info.unwrap();
+            assert_eq!(congestion_info.buffered_receipts_gas(), 0);
+        }
+
+        #[cfg(feature = "nightly")]
+        mod global_contracts {
+            use super::*;
+            use unc_primitives::receipt::GlobalContractDistributionReceipt;
+            use unc_primitives::shard_layout::ShardLayout;
+            use unc_primitives::transaction::{
+                DeployGlobalContractAction, UseGlobalContractAction,
+            };
+            use unc_store::get_global_code;
+
+            /// Applies a chunk of the shard `shard_id` on top of `root` and returns the new root.
+            fn apply_chunk(
+                runtime: &Runtime,
+                tries: &ShardTries,
+                root: CryptoHash,
+                apply_state: &mut ApplyState,
+                shard_id: ShardId,
+                receipts: &[Receipt],
+                epoch_info_provider: &MockEpochInfoProvider,
+            ) -> (CryptoHash, ApplyResult) {
+                apply_state.shard_id = shard_id;
+                apply_state.block_height += 1;
+                let apply_result = runtime
+                    .apply(
+                        tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                        &None,
+                        apply_state,
+                        receipts,
+                        &[],
+                        epoch_info_provider,
+                        Default::default(),
+                    )
+                    .unwrap();
+                let mut store_update = tries.store_update();
+                let root = tries.apply_all(
+                    &apply_result.trie_changes,
+                    ShardUId::single_shard(),
+                    &mut store_update,
+                );
+                store_update.commit().unwrap();
+                (root, apply_result)
+            }
+
+            /// A global contract deployed by `alice_account()` on shard 0 only becomes usable by
+            /// `bob_account()` on shard 1 once the distribution receipt is applied there.
+            #[test]
+            fn test_global_contract_is_distributed_to_other_shards() {
+                let (runtime, tries, alice_root, mut apply_state, signer, _) =
+                    setup_runtime(to_atto(1_000_000), 0, 0, 10u64.pow(15));
+                let epoch_info_provider = MockEpochInfoProvider::default()
+                    .with_shard_layout(ShardLayout::v1(vec!["b".parse().unwrap()], None, 1));
+
+                let mut bob_state =
+                    tries.new_trie_update(ShardUId::single_shard(), MerkleHash::default());
+                let mut bob = account_new(to_atto(1_000_000), hash(&[]));
+                bob.set_storage_usage(182);
+                set_account(&mut bob_state, bob_account(), &bob);
+                bob_state.commit(StateChangeCause::InitialState);
+                let trie_changes = bob_state.finalize().unwrap().1;
+                let mut store_update = tries.store_update();
+                let bob_root =
+                    tries.apply_all(&trie_changes, ShardUId::single_shard(), &mut store_update);
+                store_update.commit().unwrap();
+
+                let code = unc_test_contracts::rs_contract().to_vec();
+                let code_hash = hash(&code);
+                let deploy = create_receipt_with_actions(
+                    alice_account(),
+                    signer.clone(),
+                    vec![Action::DeployGlobalContract(DeployGlobalContractAction {
+                        code: code.clone(),
+                    })],
+                );
+                let (_, apply_result) = apply_chunk(
+                    &runtime,
+                    &tries,
+                    alice_root,
+                    &mut apply_state,
+                    0,
+                    &[deploy],
+                    &epoch_info_provider,
+                );
+                let distribution = apply_result
+                    .outgoing_receipts
+                    .iter()
+                    .find(|receipt| {
+                        matches!(receipt.receipt, ReceiptEnum::GlobalContractDistribution(_))
+                    })
+                    .unwrap()
+                    .clone();
+                assert_matches!(
+                    &distribution.receipt,
+                    ReceiptEnum::GlobalContractDistribution(GlobalContractDistributionReceipt {
+                        target_shard: 1,
+                        code: distributed_code,
+                    }) if *distributed_code == code
+                );
+
+                let use_global = |receipt_id: u64| {
+                    let mut receipt = create_receipt_with_actions(
+                        bob_account(),
+                        signer.clone(),
+                        vec![Action::UseGlobalContract(UseGlobalContractAction { code_hash })],
+                    );
+                    receipt.receipt_id = hash(&receipt_id.to_le_bytes());
+                    receipt
+                };
+
+                // Before the distribution the code is unknown on the shard of bob.
+                let (bob_root, apply_result) = apply_chunk(
+                    &runtime,
+                    &tries,
+                    bob_root,
+                    &mut apply_state,
+                    1,
+                    &[use_global(1)],
+                    &epoch_info_provider,
+                );
+                assert_matches!(
+                    &apply_result.outcomes[..],
+                    [ExecutionOutcomeWithId { outcome, .. }] => {
+                        assert_matches!(outcome.status, ExecutionStatus::Failure(_));
+                    }
+                );
+
+                let (bob_root, _) = apply_chunk(
+                    &runtime,
+                    &tries,
+                    bob_root,
+                    &mut apply_state,
+                    1,
+                    &[distribution],
+                    &epoch_info_provider,
+                );
+                let trie = tries.get_trie_for_shard(ShardUId::single_shard(), bob_root);
+                assert_eq!(get_global_code(&trie, code_hash).unwrap().unwrap().code(), &code[..]);
+
+                let mut call = create_receipt_with_actions(
+                    bob_account(),
+                    signer.clone(),
+                    vec![Action::FunctionCall(Box::new(FunctionCallAction {
+                        method_name: "write_block_height".to_string(),
+                        args: vec![],
+                        gas: 10u64.pow(14),
+                        deposit: 0,
+                    }))],
+                );
+                call.receipt_id = hash(&3u64.to_le_bytes());
+                let (_, apply_result) = apply_chunk(
+                    &runtime,
+                    &tries,
+                    bob_root,
+                    &mut apply_state,
+                    1,
+                    &[use_global(2), call],
+                    &epoch_info_provider,
+                );
+                assert_matches!(&apply_result.outcomes[..], [used, called] => {
+                    assert_matches!(used.outcome.status, ExecutionStatus::SuccessValue(_));
+                    assert_matches!(called.outcome.status, ExecutionStatus::SuccessValue(_));
+                });
+            }
+        }
+    }
 }
 
 /// Interface provided for gas cost estimations.
@@ -2710,10 +3813,11 @@ pub mod estimator {
     use unc_primitives::receipt::Receipt;
     use unc_primitives::runtime::apply_state::ApplyState;
     use unc_primitives::transaction::ExecutionOutcomeWithId;
+    use unc_primitives::trie_key::TrieKey;
     use unc_primitives::types::validator_power::ValidatorPower;
     use unc_primitives::types::validator_stake::ValidatorPledge;
     use unc_primitives::types::EpochInfoProvider;
-    use unc_store::TrieUpdate;
+    use unc_store::{get_promise_yield_indices, set, TrieUpdate};
 
     use crate::ApplyStats;
 
@@ -2729,7 +3833,9 @@ pub mod estimator {
         stats: &mut ApplyStats,
         epoch_info_provider: &dyn EpochInfoProvider,
     ) -> Result<ExecutionOutcomeWithId, RuntimeError> {
-        Runtime {}.apply_action_receipt(
+        let mut promise_yield_indices = get_promise_yield_indices(state_update)?;
+        let initial_promise_yield_indices = promise_yield_indices.clone();
+        let outcome = Runtime {}.apply_action_receipt(
             state_update,
             apply_state,
             receipt,
@@ -2738,6 +3844,11 @@ pub mod estimator {
             validator_pledge_proposals,
             stats,
             epoch_info_provider,
-        )
+            &mut promise_yield_indices,
+        )?;
+        if promise_yield_indices != initial_promise_yield_indices {
+            set(state_update, TrieKey::PromiseYieldIndices, &promise_yield_indices);
+        }
+        Ok(outcome)
     }
 }
@@ -1,7 +1,8 @@
 use unc_crypto::PublicKey;
 use unc_primitives::action::{
-    Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, FunctionCallAction, PledgeAction, TransferAction,
+    Action, AddKeyAction, CreateAccountAction, CreateRsa2048ChallengeAction, DeleteAccountAction,
+    DeleteKeyAction, DeployContractAction, FunctionCallAction, PledgeAction,
+    RegisterRsa2048KeysAction, TransferAction,
 };
 use unc_primitives::errors::RuntimeError;
 use unc_primitives::receipt::DataReceiver;
@@ -30,11 +31,23 @@ pub struct ReceiptMetadata {
     pub input_data_ids: Vec<CryptoHash>,
     /// A list of actions to process when all input_data_ids are filled
     pub actions: Vec<Action>,
+    /// Whether the receipt is created by `promise_yield_create` and waits to be resumed.
+    pub is_promise_yield: bool,
+}
+
+/// Data resuming a yielded receipt, submitted by `promise_yield_resume`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataReceiptMetadata {
+    /// The input data id of the yielded receipt.
+    pub data_id: CryptoHash,
+    /// The payload passed to the yielded receipt.
+    pub data: Vec<u8>,
 }
 
 #[derive(Default, Clone, PartialEq)]
 pub struct ReceiptManager {
     pub(super) action_receipts: ActionReceipts,
+    pub(super) data_receipts: Vec<DataReceiptMetadata>,
     pub(super) gas_weights: Vec<(FunctionCallActionIndex, GasWeight)>,
 }
 
@@ -97,13 +110,63 @@ impl ReceiptManager {
                 .push(DataReceiver { data_id: *data_id, receiver_id: receiver_id.clone() });
         }
 
-        let new_receipt =
-            ReceiptMetadata { output_data_receivers: vec![], input_data_ids, actions: vec![] };
+        let new_receipt = ReceiptMetadata {
+            output_data_receivers: vec![],
+            input_data_ids,
+            actions: vec![],
+            is_promise_yield: false,
+        };
+        let new_receipt_index = self.action_receipts.len() as ReceiptIndex;
+        self.action_receipts.push((receiver_id, new_receipt));
+        Ok(new_receipt_index)
+    }
+
+    /// Create a yielded receipt which will be executed once the data `input_data_id` is
+    /// submitted by `promise_yield_resume` or the yield times out.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_data_id` - the data id the receipt is waiting for
+    /// * `receiver_id` - account id of the receiver of the receipt created
+    pub(super) fn create_promise_yield_receipt(
+        &mut self,
+        input_data_id: CryptoHash,
+        receiver_id: AccountId,
+    ) -> Result<ReceiptIndex, VMLogicError> {
+        let new_receipt = ReceiptMetadata {
+            output_data_receivers: vec![],
+            input_data_ids: vec![input_data_id],
+            actions: vec![],
+            is_promise_yield: true,
+        };
         let new_receipt_index = self.action_receipts.len() as ReceiptIndex;
         self.action_receipts.push((receiver_id, new_receipt));
         Ok(new_receipt_index)
     }
 
+    /// Returns whether a yielded receipt waiting for `data_id` was created by the current
+    /// function call.
+    pub(super) fn has_promise_yield_receipt(&self, data_id: CryptoHash) -> bool {
+        self.action_receipts
+            .iter()
+            .any(|(_, receipt)| receipt.is_promise_yield && receipt.input_data_ids == [data_id])
+    }
+
+    /// Create a data receipt resuming the yielded receipt waiting for `data_id`.
+    ///
+    /// Returns `false` if the yielded receipt was already resumed by the current function call.
+    pub(super) fn create_promise_resume_data_receipt(
+        &mut self,
+        data_id: CryptoHash,
+        data: Vec<u8>,
+    ) -> bool {
+        if self.data_receipts.iter().any(|receipt| receipt.data_id == data_id) {
+            return false;
+        }
+        self.data_receipts.push(DataReceiptMetadata { data_id, data });
+        true
+    }
+
     /// Attach the [`CreateAccountAction`] action to an existing receipt.
     ///
     /// # Arguments
@@ -350,12 +413,70 @@ impl ReceiptManager {
         Ok(())
     }
 
+    /// Attach the [`RegisterRsa2048KeysAction`] action to an existing receipt.
+    ///
+    /// # Arguments
+    ///
+    /// * `receipt_index` - an index of Receipt to append an action
+    /// * `public_key` - a public key of the rsa2048 keys to register
+    /// * `operation_type` - whether the keys are added or deleted
+    /// * `args` - arguments of the registration, such as the miner id and power
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `receipt_index` does not refer to a known receipt.
+    pub(super) fn append_action_register_rsa2048_keys(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: PublicKey,
+        operation_type: u8,
+        args: Vec<u8>,
+    ) {
+        self.append_action(
+            receipt_index,
+            Action::RegisterRsa2048Keys(Box::new(RegisterRsa2048KeysAction {
+                public_key,
+                operation_type,
+                args,
+            })),
+        );
+    }
+
+    /// Attach the [`CreateRsa2048ChallengeAction`] action to an existing receipt.
+    ///
+    /// # Arguments
+    ///
+    /// * `receipt_index` - an index of Receipt to append an action
+    /// * `public_key` - a public key of the challenged rsa2048 keys
+    /// * `challenge_key` - a key to bind the validator power to
+    /// * `args` - arguments of the challenge, such as the miner id and power
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `receipt_index` does not refer to a known receipt.
+    pub(super) fn append_action_create_rsa2048_challenge(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: PublicKey,
+        challenge_key: PublicKey,
+        args: Vec<u8>,
+    ) {
+        self.append_action(
+            receipt_index,
+            Action::CreateRsa2048Challenge(Box::new(CreateRsa2048ChallengeAction {
+                public_key,
+                challenge_key,
+                args,
+            })),
+        );
+    }
+
     /// Distribute the provided `gas` between receipts managed by this `ReceiptManager` according
     /// to their assigned weights.
     ///
     /// Returns the amount of gas distributed (either `0` or `unused_gas`.)
     pub(super) fn distribute_gas(&mut self, unused_gas: Gas) -> Result<Gas, RuntimeError> {
-        let ReceiptManager { action_receipts, gas_weights } = self;
+        let ReceiptManager { action_receipts, gas_weights, .. } = self;
         let gas_weight_sum: u128 = gas_weights.iter().map(|(_, gv)| u128::from(gv.0)).sum();
         if gas_weight_sum == 0 || unused_gas == 0 {
             return Ok(0);
@@ -17,7 +17,7 @@ use unc_primitives::trie_key::trie_key_parsers;
 use unc_primitives::types::{AccountId, EpochInfoProvider, Gas};
 use unc_primitives::views::{ChipView, StateItem, ViewApplyState, ViewStateResult};
 use unc_primitives_core::config::ViewConfig;
-use unc_store::{get_access_key, get_account, get_code, TrieUpdate};
+use unc_store::{get_access_key, get_account, get_code, get_global_code, TrieUpdate};
 use unc_vm_runner::logic::ReturnData;
 use unc_vm_runner::ContractCode;
 
@@ -64,10 +64,12 @@ impl TrieViewer {
         account_id: &AccountId,
     ) -> Result<ContractCode, errors::ViewContractCodeError> {
         let account = self.view_account(state_update, account_id)?;
-        get_code(state_update, account_id, Some(account.code_hash()))?.ok_or_else(|| {
-            errors::ViewContractCodeError::NoContractCode {
-                contract_account_id: account_id.clone(),
-            }
+        let code = match get_code(state_update, account_id, Some(account.code_hash()))? {
+            Some(code) => Some(code),
+            None => get_global_code(state_update, account.code_hash())?,
+        };
+        code.ok_or_else(|| errors::ViewContractCodeError::NoContractCode {
+            contract_account_id: account_id.clone(),
         })
     }
 
@@ -256,6 +258,25 @@ impl TrieViewer {
         let mut values = vec![];
         let query = trie_key_parsers::get_raw_prefix_for_contract_data(account_id, prefix);
         let acc_sep_len = query.len() - prefix.len();
+        // Proofs need trie nodes, everything else can be read from flat
+        // state history if the trie reads from it.
+        if !include_proof {
+            if let Some(keys) = state_update.trie().get_historical_keys(&query) {
+                for key in keys? {
+                    let value = state_update.trie().get(&key)?.ok_or_else(|| {
+                        errors::ViewStateError::InternalError {
+                            error_message: "Unexpected missing key from flat state history"
+                                .to_string(),
+                        }
+                    })?;
+                    values.push(StateItem {
+                        key: key[acc_sep_len..].to_vec().into(),
+                        value: value.into(),
+                    });
+                }
+                return Ok(ViewStateResult { values, proof: vec![] });
+            }
+        }
         let mut iter = state_update.trie().iter()?;
         iter.remember_visited_nodes(include_proof);
         iter.seek_prefix(&query)?;
@@ -307,6 +328,7 @@ impl TrieViewer {
             // Used for legacy reasons
             prev_block_hash: view_state.prev_block_hash,
             block_hash: view_state.block_hash,
+            shard_id: Default::default(),
             epoch_id: view_state.epoch_id.clone(),
             epoch_height: view_state.epoch_height,
             gas_price: 0,
@@ -319,6 +341,7 @@ impl TrieViewer {
             is_new_chunk: false,
             migration_data: Arc::new(MigrationData::default()),
             migration_flags: MigrationFlags::default(),
+            congestion_info: Default::default(),
         };
         let action_receipt = ActionReceipt {
             signer_id: originator_id.clone(),
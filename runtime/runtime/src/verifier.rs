@@ -10,10 +10,13 @@ use unc_primitives::errors::{
     ActionsValidationError, InvalidAccessKeyError, InvalidTxError, ReceiptValidationError,
     RuntimeError,
 };
-use unc_primitives::receipt::{ActionReceipt, DataReceipt, Receipt, ReceiptEnum};
+use unc_primitives::receipt::{
+    ActionReceipt, DataReceipt, GlobalContractDistributionReceipt, Receipt, ReceiptEnum,
+};
 use unc_primitives::transaction::DeleteAccountAction;
 use unc_primitives::transaction::{
-    Action, AddKeyAction, CreateRsa2048ChallengeAction, DeployContractAction, FunctionCallAction,
+    Action, AddKeyAction, CreateRsa2048ChallengeAction, DeployContractAction,
+    DeployContractWithMigrationAction, DeployGlobalContractAction, FunctionCallAction,
     PledgeAction, RegisterRsa2048KeysAction, SignedTransaction,
 };
 use unc_primitives::types::{AccountId, Balance};
@@ -101,6 +104,12 @@ pub fn validate_transaction(
         return Err(InvalidTxError::InvalidSignature.into());
     }
 
+    if transaction.priority_fee.is_some()
+        && !checked_feature!("stable", PriorityFees, current_protocol_version)
+    {
+        return Err(InvalidTxError::PriorityFeeNotSupported.into());
+    }
+
     let transaction_size = signed_transaction.get_size();
     let max_transaction_size = config.wasm_config.limit_config.max_transaction_size;
     if transaction_size > max_transaction_size {
@@ -294,10 +303,15 @@ pub(crate) fn validate_receipt(
     })?;
 
     match &receipt.receipt {
-        ReceiptEnum::Action(action_receipt) => {
+        ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
             validate_action_receipt(limit_config, action_receipt, current_protocol_version)
         }
-        ReceiptEnum::Data(data_receipt) => validate_data_receipt(limit_config, data_receipt),
+        ReceiptEnum::Data(data_receipt) | ReceiptEnum::PromiseResume(data_receipt) => {
+            validate_data_receipt(limit_config, data_receipt)
+        }
+        ReceiptEnum::GlobalContractDistribution(distribution) => {
+            validate_global_contract_distribution_receipt(limit_config, distribution)
+        }
     }
 }
 
@@ -332,6 +346,23 @@ fn validate_data_receipt(
     Ok(())
 }
 
+/// Validates given global contract distribution receipt. Checks that the distributed code
+/// doesn't exceed the contract size limit, the same as when it was deployed.
+fn validate_global_contract_distribution_receipt(
+    limit_config: &LimitConfig,
+    receipt: &GlobalContractDistributionReceipt,
+) -> Result<(), ReceiptValidationError> {
+    if receipt.code.len() as u64 > limit_config.max_contract_size {
+        return Err(ReceiptValidationError::ActionsValidation(
+            ActionsValidationError::ContractSizeExceeded {
+                size: receipt.code.len() as u64,
+                limit: limit_config.max_contract_size,
+            },
+        ));
+    }
+    Ok(())
+}
+
 /// Validates given actions:
 ///
 /// - Checks limits if applicable.
@@ -405,7 +436,29 @@ pub fn validate_action(
         Action::Delegate(a) => validate_delegate_action(limit_config, a, current_protocol_version),
         Action::RegisterRsa2048Keys(a) => validate_register_rsa2048_keys_action(limit_config, a),
         Action::CreateRsa2048Challenge(a) => validate_create_rsa2048_challenge_action(a),
+        Action::DeployGlobalContract(a) => {
+            validate_global_contracts_enabled(current_protocol_version)?;
+            validate_deploy_global_contract_action(limit_config, a)
+        }
+        Action::UseGlobalContract(_) => validate_global_contracts_enabled(current_protocol_version),
+        Action::DeployContractWithMigration(a) => validate_deploy_contract_with_migration_action(
+            limit_config,
+            a,
+            current_protocol_version,
+        ),
+    }
+}
+
+fn validate_global_contracts_enabled(
+    current_protocol_version: ProtocolVersion,
+) -> Result<(), ActionsValidationError> {
+    if !checked_feature!("stable", GlobalContracts, current_protocol_version) {
+        return Err(ActionsValidationError::UnsupportedProtocolFeature {
+            protocol_feature: String::from("GlobalContracts"),
+            version: ProtocolFeature::GlobalContracts.protocol_version(),
+        });
     }
+    Ok(())
 }
 
 fn validate_delegate_action(
@@ -433,6 +486,65 @@ fn validate_deploy_contract_action(
     Ok(())
 }
 
+/// Validates `DeployGlobalContractAction`. Checks that the given contract size doesn't exceed the
+/// limit.
+fn validate_deploy_global_contract_action(
+    limit_config: &LimitConfig,
+    action: &DeployGlobalContractAction,
+) -> Result<(), ActionsValidationError> {
+    if action.code.len() as u64 > limit_config.max_contract_size {
+        return Err(ActionsValidationError::ContractSizeExceeded {
+            size: action.code.len() as u64,
+            limit: limit_config.max_contract_size,
+        });
+    }
+
+    Ok(())
+}
+
+/// Validates `DeployContractWithMigrationAction`. Checks that the protocol feature is enabled, that
+/// the contract size doesn't exceed the limit and that the migration call satisfies the same
+/// limits as a `FunctionCallAction`.
+fn validate_deploy_contract_with_migration_action(
+    limit_config: &LimitConfig,
+    action: &DeployContractWithMigrationAction,
+    current_protocol_version: ProtocolVersion,
+) -> Result<(), ActionsValidationError> {
+    if !checked_feature!("stable", ContractMigration, current_protocol_version) {
+        return Err(ActionsValidationError::UnsupportedProtocolFeature {
+            protocol_feature: String::from("ContractMigration"),
+            version: ProtocolFeature::ContractMigration.protocol_version(),
+        });
+    }
+
+    if action.code.len() as u64 > limit_config.max_contract_size {
+        return Err(ActionsValidationError::ContractSizeExceeded {
+            size: action.code.len() as u64,
+            limit: limit_config.max_contract_size,
+        });
+    }
+
+    if action.gas == 0 {
+        return Err(ActionsValidationError::FunctionCallZeroAttachedGas);
+    }
+
+    if action.migrate_method_name.len() as u64 > limit_config.max_length_method_name {
+        return Err(ActionsValidationError::FunctionCallMethodNameLengthExceeded {
+            length: action.migrate_method_name.len() as u64,
+            limit: limit_config.max_length_method_name,
+        });
+    }
+
+    if action.migrate_args.len() as u64 > limit_config.max_arguments_length {
+        return Err(ActionsValidationError::FunctionCallArgumentsLengthExceeded {
+            length: action.migrate_args.len() as u64,
+            limit: limit_config.max_arguments_length,
+        });
+    }
+
+    Ok(())
+}
+
 /// Validates `FunctionCallAction`. Checks that the method name length doesn't exceed the limit and
 /// the length of the arguments doesn't exceed the limit.
 fn validate_function_call_action(
@@ -568,7 +680,8 @@ mod tests {
     use unc_primitives::hash::{hash, CryptoHash};
     use unc_primitives::test_utils::account_new;
     use unc_primitives::transaction::{
-        CreateAccountAction, DeleteAccountAction, DeleteKeyAction, PledgeAction, TransferAction,
+        CreateAccountAction, DeleteAccountAction, DeleteKeyAction, PledgeAction, Transaction,
+        TransferAction, UseGlobalContractAction,
     };
     use unc_primitives::types::{AccountId, Balance, MerkleHash, StateChangeCause};
     use unc_primitives::version::PROTOCOL_VERSION;
@@ -1494,6 +1607,97 @@ mod tests {
         .expect("valid transaction");
     }
 
+    #[test]
+    fn test_validate_transaction_priority_fee() {
+        let config = RuntimeConfig::test();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+
+        let deposit = 100;
+        let priority_fee = 10 * UNC_BASE;
+        let transaction = Transaction {
+            priority_fee: Some(priority_fee),
+            ..Transaction::new(
+                alice_account(),
+                signer.public_key(),
+                bob_account(),
+                1,
+                CryptoHash::default(),
+            )
+            .transfer(deposit)
+        }
+        .sign(&*signer);
+
+        let version = ProtocolFeature::PriorityFees.protocol_version();
+        assert_eq!(
+            validate_transaction(&config, gas_price, &transaction, true, version - 1)
+                .expect_err("expected an error"),
+            RuntimeError::InvalidTxError(InvalidTxError::PriorityFeeNotSupported),
+        );
+
+        let verification_result = verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            true,
+            None,
+            version,
+        )
+        .expect("valid transaction");
+        // The priority fee is burnt on top of the gas.
+        assert_eq!(
+            verification_result.burnt_amount,
+            Balance::from(verification_result.gas_burnt) * gas_price + priority_fee
+        );
+
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        assert_eq!(
+            account.amount(),
+            TESTING_INIT_BALANCE
+                - Balance::from(verification_result.gas_remaining)
+                    * verification_result.receipt_gas_price
+                - verification_result.burnt_amount
+                - deposit
+        );
+    }
+
+    #[test]
+    fn test_validate_transaction_priority_fee_not_enough_balance() {
+        let config = RuntimeConfig::test();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+
+        let transaction = Transaction {
+            priority_fee: Some(TESTING_INIT_BALANCE),
+            ..Transaction::new(
+                alice_account(),
+                signer.public_key(),
+                bob_account(),
+                1,
+                CryptoHash::default(),
+            )
+            .transfer(100)
+        }
+        .sign(&*signer);
+
+        let err = verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            true,
+            None,
+            ProtocolFeature::PriorityFees.protocol_version(),
+        )
+        .expect_err("expected an error");
+        if let RuntimeError::InvalidTxError(InvalidTxError::NotEnoughBalance { cost, .. }) = err {
+            assert!(cost > TESTING_INIT_BALANCE);
+        } else {
+            panic!("Incorrect error");
+        }
+    }
+
     // Receipts
 
     #[test]
@@ -1849,6 +2053,70 @@ mod tests {
         .expect("valid action");
     }
 
+    #[test]
+    fn test_validate_action_global_contracts() {
+        let version = ProtocolFeature::GlobalContracts.protocol_version();
+        let deploy = Action::DeployGlobalContract(DeployGlobalContractAction { code: vec![1; 5] });
+        let use_global = Action::UseGlobalContract(Box::new(UseGlobalContractAction {
+            code_hash: hash(&[1; 5]),
+        }));
+        validate_action(&test_limit_config(), &deploy, version).expect("valid action");
+        validate_action(&test_limit_config(), &use_global, version).expect("valid action");
+
+        let unsupported = ActionsValidationError::UnsupportedProtocolFeature {
+            protocol_feature: String::from("GlobalContracts"),
+            version,
+        };
+        assert_eq!(
+            validate_action(&test_limit_config(), &deploy, version - 1),
+            Err(unsupported.clone())
+        );
+        assert_eq!(
+            validate_action(&test_limit_config(), &use_global, version - 1),
+            Err(unsupported)
+        );
+
+        let mut limit_config = test_limit_config();
+        limit_config.max_contract_size = 4;
+        assert_eq!(
+            validate_action(&limit_config, &deploy, version),
+            Err(ActionsValidationError::ContractSizeExceeded { size: 5, limit: 4 }),
+        );
+    }
+
+    #[test]
+    fn test_validate_action_deploy_contract_with_migration() {
+        let version = ProtocolFeature::ContractMigration.protocol_version();
+        let action = DeployContractWithMigrationAction {
+            code: vec![1; 5],
+            migrate_method_name: "migrate".to_string(),
+            migrate_args: vec![],
+            gas: 100,
+        };
+        let deploy = Action::DeployContractWithMigration(Box::new(action.clone()));
+        validate_action(&test_limit_config(), &deploy, version).expect("valid action");
+        assert_eq!(
+            validate_action(&test_limit_config(), &deploy, version - 1),
+            Err(ActionsValidationError::UnsupportedProtocolFeature {
+                protocol_feature: String::from("ContractMigration"),
+                version,
+            })
+        );
+
+        let mut limit_config = test_limit_config();
+        limit_config.max_contract_size = 4;
+        assert_eq!(
+            validate_action(&limit_config, &deploy, version),
+            Err(ActionsValidationError::ContractSizeExceeded { size: 5, limit: 4 }),
+        );
+
+        let zero_gas: Action = DeployContractWithMigrationAction { gas: 0, ..action }.into();
+        assert_eq!(
+            validate_action(&test_limit_config(), &zero_gas, version),
+            Err(ActionsValidationError::FunctionCallZeroAttachedGas),
+        );
+    }
+
     #[test]
     fn test_delegate_action_must_be_only_one() {
         let signed_delegate_action = SignedDelegateAction {
@@ -78,7 +78,7 @@ impl StandaloneRuntime {
 
         let mut account_ids: HashSet<AccountId> = HashSet::new();
         genesis.for_each_record(|record: &StateRecord| {
-            account_ids.insert(state_record_to_account_id(record).clone());
+            account_ids.extend(state_record_to_account_id(record).cloned());
         });
         let writers = std::sync::atomic::AtomicUsize::new(0);
         let root = GenesisStateApplier::apply(
@@ -95,6 +95,7 @@ impl StandaloneRuntime {
             block_height: 1,
             prev_block_hash: Default::default(),
             block_hash: Default::default(),
+            shard_id: Default::default(),
             epoch_id: Default::default(),
             epoch_height: 0,
             gas_price: 100,
@@ -107,6 +108,7 @@ impl StandaloneRuntime {
             is_new_chunk: true,
             migration_data: Arc::new(MigrationData::default()),
             migration_flags: MigrationFlags::default(),
+            congestion_info: Default::default(),
         };
 
         Self {
@@ -40,6 +40,20 @@ extern "C" {
     fn alt_bn128_g1_multiexp(value_len: u64, value_ptr: u64, register_id: u64);
     fn alt_bn128_g1_sum(value_len: u64, value_ptr: u64, register_id: u64);
     fn alt_bn128_pairing_check(value_len: u64, value_ptr: u64) -> u64;
+    #[cfg(feature = "nightly")]
+    fn bls12381_p1_sum(value_len: u64, value_ptr: u64, register_id: u64);
+    #[cfg(feature = "nightly")]
+    fn bls12381_p2_sum(value_len: u64, value_ptr: u64, register_id: u64);
+    #[cfg(feature = "nightly")]
+    fn bls12381_g1_multiexp(value_len: u64, value_ptr: u64, register_id: u64);
+    #[cfg(feature = "nightly")]
+    fn bls12381_g2_multiexp(value_len: u64, value_ptr: u64, register_id: u64);
+    #[cfg(feature = "nightly")]
+    fn bls12381_map_fp_to_g1(value_len: u64, value_ptr: u64, register_id: u64);
+    #[cfg(feature = "nightly")]
+    fn bls12381_map_fp2_to_g2(value_len: u64, value_ptr: u64, register_id: u64);
+    #[cfg(feature = "nightly")]
+    fn bls12381_pairing_check(value_len: u64, value_ptr: u64) -> u64;
     fn random_seed(register_id: u64);
     fn sha256(value_len: u64, value_ptr: u64, register_id: u64);
     fn keccak256(value_len: u64, value_ptr: u64, register_id: u64);
@@ -62,6 +76,15 @@ extern "C" {
         pub_key_len: u64,
         pub_key_ptr: u64,
     ) -> u64;
+    #[cfg(feature = "nightly")]
+    fn rsa2048_pkcs1v15_verify(
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> u64;
     // #####################
     // # Miscellaneous API #
     // #####################
@@ -551,6 +574,110 @@ pub unsafe fn ed25519_verify_16kib_64() {
     }
 }
 
+/// DER-encoded SubjectPublicKeyInfo of the RSA2048 key used to measure
+/// `rsa2048_pkcs1v15_verify_base` and `rsa2048_pkcs1v15_verify_byte`.
+#[cfg(feature = "nightly")]
+const RSA2048_PUBLIC_KEY: [u8; 294] = [
+    48, 130, 1, 34, 48, 13, 6, 9, 42, 134, 72, 134, 247, 13, 1, 1, 1, 5, 0, 3, 130, 1, 15, 0, 48,
+    130, 1, 10, 2, 130, 1, 1, 0, 139, 149, 249, 102, 161, 118, 98, 39, 179, 28, 123, 126, 158, 4,
+    143, 11, 42, 249, 55, 66, 123, 5, 230, 94, 254, 124, 26, 102, 244, 188, 8, 127, 97, 138, 109,
+    218, 253, 79, 44, 5, 115, 253, 20, 85, 191, 171, 215, 13, 70, 251, 220, 125, 218, 14, 62, 153,
+    25, 63, 44, 18, 249, 111, 174, 143, 211, 237, 11, 111, 122, 228, 209, 85, 71, 70, 112, 168,
+    176, 208, 90, 41, 143, 109, 176, 250, 130, 139, 52, 122, 52, 23, 79, 86, 136, 143, 51, 207, 42,
+    249, 137, 75, 125, 74, 68, 207, 217, 6, 67, 85, 77, 17, 143, 184, 208, 50, 150, 41, 82, 42,
+    242, 150, 70, 220, 5, 128, 8, 37, 39, 19, 238, 107, 161, 170, 102, 147, 35, 212, 45, 99, 212,
+    140, 156, 250, 52, 205, 130, 166, 189, 157, 38, 154, 185, 38, 15, 75, 107, 115, 165, 16, 173,
+    188, 44, 234, 59, 153, 103, 213, 204, 170, 41, 21, 78, 176, 159, 139, 70, 234, 18, 216, 157,
+    209, 1, 13, 97, 19, 57, 205, 129, 124, 58, 162, 88, 245, 221, 186, 161, 230, 188, 95, 20, 184,
+    108, 205, 146, 57, 182, 88, 0, 35, 173, 106, 183, 103, 50, 64, 183, 153, 203, 219, 88, 35, 9,
+    56, 230, 199, 224, 4, 9, 171, 45, 6, 42, 94, 218, 53, 152, 59, 113, 66, 210, 21, 195, 131, 145,
+    2, 5, 219, 154, 97, 22, 205, 33, 95, 210, 147, 39, 203, 2, 3, 1, 0, 1,
+];
+
+/// Function to measure `rsa2048_pkcs1v15_verify_base`. Also measures `base`,
+/// `read_memory_base`, and `read_memory_byte`. However the modular
+/// exponentiation is much more expensive than reading the inputs so we are
+/// okay overcharging it.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn rsa2048_pkcs1v15_verify_32b_500() {
+    // 32 bytes message ("kajdlfkjalkfjaklfjdkladjfkljadsk")
+    let message: [u8; 32] = [
+        107, 97, 106, 100, 108, 102, 107, 106, 97, 108, 107, 102, 106, 97, 107, 108, 102, 106, 100,
+        107, 108, 97, 100, 106, 102, 107, 108, 106, 97, 100, 115, 107,
+    ];
+
+    let signature: [u8; 256] = [
+        124, 173, 109, 228, 124, 118, 23, 32, 137, 233, 28, 88, 35, 217, 252, 126, 140, 218, 219,
+        42, 206, 250, 192, 244, 104, 101, 67, 22, 54, 197, 155, 142, 106, 92, 197, 59, 11, 141, 24,
+        113, 101, 211, 182, 27, 157, 255, 177, 219, 203, 27, 2, 145, 161, 43, 83, 74, 19, 38, 89,
+        22, 193, 220, 188, 50, 105, 54, 255, 176, 215, 172, 156, 175, 227, 240, 24, 163, 13, 213,
+        102, 239, 71, 149, 56, 245, 33, 252, 223, 83, 156, 86, 149, 49, 228, 72, 89, 116, 204, 155,
+        24, 57, 254, 154, 132, 29, 110, 72, 69, 192, 244, 76, 28, 70, 52, 19, 229, 61, 230, 82, 14,
+        37, 180, 243, 116, 215, 179, 10, 1, 145, 166, 136, 253, 254, 152, 194, 203, 201, 244, 172,
+        180, 206, 252, 137, 194, 161, 186, 105, 170, 251, 51, 189, 10, 33, 55, 186, 228, 175, 15,
+        144, 87, 212, 82, 48, 62, 185, 253, 45, 120, 235, 58, 71, 17, 250, 97, 108, 78, 40, 58,
+        197, 42, 142, 111, 213, 103, 2, 215, 111, 126, 52, 179, 123, 56, 249, 161, 39, 96, 92, 122,
+        170, 73, 14, 120, 229, 165, 241, 224, 224, 135, 156, 56, 89, 103, 68, 65, 255, 47, 122,
+        248, 176, 159, 26, 208, 194, 93, 164, 91, 37, 177, 25, 155, 169, 194, 252, 175, 216, 104,
+        156, 56, 178, 166, 141, 106, 53, 180, 75, 93, 241, 47, 217, 40, 164, 24, 183, 222, 185, 32,
+        198,
+    ];
+
+    for _ in 0..500 {
+        let result = rsa2048_pkcs1v15_verify(
+            signature.len() as _,
+            signature.as_ptr() as _,
+            message.len() as _,
+            message.as_ptr() as _,
+            RSA2048_PUBLIC_KEY.len() as _,
+            RSA2048_PUBLIC_KEY.as_ptr() as _,
+        );
+        // check that result was positive, as negative results could have exited
+        // early and do not reflect the full cost.
+        assert!(result == 1);
+    }
+}
+
+/// Function to measure `rsa2048_pkcs1v15_verify_byte`.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn rsa2048_pkcs1v15_verify_16kib_64() {
+    // 16kB bytes message
+    let message = [b'a'; 16384];
+
+    let signature: [u8; 256] = [
+        56, 183, 54, 217, 72, 208, 14, 188, 49, 51, 62, 3, 77, 67, 57, 180, 42, 82, 237, 221, 87,
+        233, 255, 236, 148, 128, 215, 175, 203, 17, 18, 199, 23, 77, 42, 140, 138, 10, 91, 8, 184,
+        17, 152, 162, 9, 47, 16, 207, 169, 192, 156, 186, 62, 86, 180, 84, 242, 145, 245, 121, 183,
+        240, 133, 226, 174, 248, 127, 142, 103, 142, 8, 45, 118, 197, 129, 136, 187, 93, 93, 164,
+        215, 91, 48, 67, 194, 88, 222, 168, 152, 171, 203, 222, 245, 61, 253, 12, 106, 83, 193, 25,
+        152, 245, 233, 239, 127, 180, 201, 194, 204, 150, 19, 113, 230, 242, 99, 172, 251, 197,
+        100, 19, 117, 223, 117, 5, 252, 139, 249, 135, 43, 191, 222, 152, 108, 186, 149, 247, 222,
+        246, 140, 113, 214, 190, 139, 179, 125, 72, 253, 133, 0, 59, 108, 91, 10, 138, 102, 129,
+        112, 97, 28, 131, 133, 190, 115, 60, 230, 53, 56, 12, 42, 144, 67, 183, 36, 147, 178, 97,
+        80, 104, 236, 199, 61, 132, 0, 244, 248, 3, 165, 111, 112, 209, 88, 82, 92, 113, 158, 182,
+        142, 87, 73, 57, 227, 46, 11, 91, 141, 252, 19, 244, 204, 166, 54, 210, 194, 193, 197, 219,
+        150, 91, 88, 228, 216, 12, 244, 86, 238, 128, 104, 72, 251, 78, 253, 133, 213, 218, 56, 90,
+        139, 155, 82, 108, 40, 202, 80, 109, 155, 158, 62, 51, 161, 176, 40, 254, 126, 55, 125,
+        211,
+    ];
+
+    for _ in 0..64 {
+        let result = rsa2048_pkcs1v15_verify(
+            signature.len() as _,
+            signature.as_ptr() as _,
+            message.len() as _,
+            message.as_ptr() as _,
+            RSA2048_PUBLIC_KEY.len() as _,
+            RSA2048_PUBLIC_KEY.as_ptr() as _,
+        );
+        // check that result was positive, as negative results could have exited
+        // early and do not reflect the full cost.
+        assert!(result == 1);
+    }
+}
+
 #[repr(C)]
 struct MultiexpElem([u8; 64], [u8; 32]);
 
@@ -719,6 +846,246 @@ pub unsafe fn alt_bn128_pairing_check_10_10() {
     }
 }
 
+/// Generator of the G1 subgroup of BLS12-381, in the uncompressed encoding
+/// expected by the `bls12381_*` host functions.
+#[cfg(feature = "nightly")]
+const BLS12381_G1: [u8; 96] = [
+    23, 241, 211, 167, 49, 151, 215, 148, 38, 149, 99, 140, 79, 169, 172, 15, 195, 104, 140, 79,
+    151, 116, 185, 5, 161, 78, 58, 63, 23, 27, 172, 88, 108, 85, 232, 63, 249, 122, 26, 239, 251,
+    58, 240, 10, 219, 34, 198, 187, 8, 179, 244, 129, 227, 170, 160, 241, 160, 158, 48, 237, 116,
+    29, 138, 228, 252, 245, 224, 149, 213, 208, 10, 246, 0, 219, 24, 203, 44, 4, 179, 237, 208, 60,
+    199, 68, 162, 136, 138, 228, 12, 170, 35, 41, 70, 197, 231, 225,
+];
+
+/// Generator of the G2 subgroup of BLS12-381, in the uncompressed encoding
+/// expected by the `bls12381_*` host functions.
+#[cfg(feature = "nightly")]
+const BLS12381_G2: [u8; 192] = [
+    19, 224, 43, 96, 82, 113, 159, 96, 125, 172, 211, 160, 136, 39, 79, 101, 89, 107, 208, 208,
+    153, 32, 182, 26, 181, 218, 97, 187, 220, 127, 80, 73, 51, 76, 241, 18, 19, 148, 93, 87, 229,
+    172, 125, 5, 93, 4, 43, 126, 2, 74, 162, 178, 240, 143, 10, 145, 38, 8, 5, 39, 45, 197, 16, 81,
+    198, 228, 122, 212, 250, 64, 59, 2, 180, 81, 11, 100, 122, 227, 209, 119, 11, 172, 3, 38, 168,
+    5, 187, 239, 212, 128, 86, 200, 193, 33, 189, 184, 6, 6, 196, 160, 46, 167, 52, 204, 50, 172,
+    210, 176, 43, 194, 139, 153, 203, 62, 40, 126, 133, 167, 99, 175, 38, 116, 146, 171, 87, 46,
+    153, 171, 63, 55, 13, 39, 92, 236, 29, 161, 170, 169, 7, 95, 240, 95, 121, 190, 12, 229, 213,
+    39, 114, 125, 110, 17, 140, 201, 205, 198, 218, 46, 53, 26, 173, 253, 155, 170, 140, 189, 211,
+    167, 109, 66, 154, 105, 81, 96, 209, 44, 146, 58, 201, 204, 59, 172, 162, 137, 225, 147, 84,
+    134, 8, 184, 40, 1,
+];
+
+/// Scalar used in multiexp measurements. All bits are set, so that the
+/// multiplication can't take any shortcuts.
+#[cfg(feature = "nightly")]
+const BLS12381_SCALAR: [u8; 32] = [0xff; 32];
+
+/// Field element used in map-to-curve measurements. Any value below the
+/// modulus works, the cost of the map doesn't depend on it.
+#[cfg(feature = "nightly")]
+const BLS12381_FP: [u8; 48] = [0x05; 48];
+
+/// Builds the input for a `bls12381_*` host function by repeating the
+/// concatenation of `element` parts until the buffer is full.
+#[cfg(feature = "nightly")]
+fn bls12381_input<const N: usize>(element: &[&[u8]]) -> [u8; N] {
+    let mut res = [0u8; N];
+    let mut pos = 0;
+    while pos < N {
+        for part in element {
+            res[pos..pos + part.len()].copy_from_slice(part);
+            pos += part.len();
+        }
+    }
+    res
+}
+
+// Function to measure `bls12381_p1_sum_base` and `bls12381_p1_sum_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `p1_sum` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute p1_sum on 1 element 1k times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_p1_sum_1_1k() {
+    let buffer: [u8; 97] = bls12381_input(&[&[0], &BLS12381_G1]);
+    for _ in 0..1000 {
+        bls12381_p1_sum(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_p1_sum_base` and `bls12381_p1_sum_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `p1_sum` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute p1_sum on 10 elements 1k times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_p1_sum_10_1k() {
+    let buffer: [u8; 970] = bls12381_input(&[&[0], &BLS12381_G1]);
+    for _ in 0..1000 {
+        bls12381_p1_sum(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_p2_sum_base` and `bls12381_p2_sum_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `p2_sum` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute p2_sum on 1 element 1k times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_p2_sum_1_1k() {
+    let buffer: [u8; 193] = bls12381_input(&[&[0], &BLS12381_G2]);
+    for _ in 0..1000 {
+        bls12381_p2_sum(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_p2_sum_base` and `bls12381_p2_sum_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `p2_sum` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute p2_sum on 10 elements 1k times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_p2_sum_10_1k() {
+    let buffer: [u8; 1930] = bls12381_input(&[&[0], &BLS12381_G2]);
+    for _ in 0..1000 {
+        bls12381_p2_sum(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_g1_multiexp_base` and `bls12381_g1_multiexp_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `g1_multiexp` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute g1_multiexp on 1 element 10 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_g1_multiexp_1_10() {
+    let buffer: [u8; 128] = bls12381_input(&[&BLS12381_G1, &BLS12381_SCALAR]);
+    for _ in 0..10 {
+        bls12381_g1_multiexp(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_g1_multiexp_base` and `bls12381_g1_multiexp_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `g1_multiexp` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute g1_multiexp on 10 elements 10 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_g1_multiexp_10_10() {
+    let buffer: [u8; 1280] = bls12381_input(&[&BLS12381_G1, &BLS12381_SCALAR]);
+    for _ in 0..10 {
+        bls12381_g1_multiexp(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_g2_multiexp_base` and `bls12381_g2_multiexp_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `g2_multiexp` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute g2_multiexp on 1 element 10 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_g2_multiexp_1_10() {
+    let buffer: [u8; 224] = bls12381_input(&[&BLS12381_G2, &BLS12381_SCALAR]);
+    for _ in 0..10 {
+        bls12381_g2_multiexp(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_g2_multiexp_base` and `bls12381_g2_multiexp_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `g2_multiexp` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute g2_multiexp on 10 elements 10 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_g2_multiexp_10_10() {
+    let buffer: [u8; 2240] = bls12381_input(&[&BLS12381_G2, &BLS12381_SCALAR]);
+    for _ in 0..10 {
+        bls12381_g2_multiexp(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_map_fp_to_g1_base` and `bls12381_map_fp_to_g1_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `map_fp_to_g1` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute map_fp_to_g1 on 1 element 100 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_map_fp_to_g1_1_100() {
+    let buffer: [u8; 48] = bls12381_input(&[&BLS12381_FP]);
+    for _ in 0..100 {
+        bls12381_map_fp_to_g1(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_map_fp_to_g1_base` and `bls12381_map_fp_to_g1_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `map_fp_to_g1` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute map_fp_to_g1 on 10 elements 100 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_map_fp_to_g1_10_100() {
+    let buffer: [u8; 480] = bls12381_input(&[&BLS12381_FP]);
+    for _ in 0..100 {
+        bls12381_map_fp_to_g1(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_map_fp2_to_g2_base` and `bls12381_map_fp2_to_g2_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `map_fp2_to_g2` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute map_fp2_to_g2 on 1 element 100 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_map_fp2_to_g2_1_100() {
+    let buffer: [u8; 96] = bls12381_input(&[&BLS12381_FP, &BLS12381_FP]);
+    for _ in 0..100 {
+        bls12381_map_fp2_to_g2(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_map_fp2_to_g2_base` and `bls12381_map_fp2_to_g2_element`. Also measures `base`, `write_register_base`,
+// and `write_register_byte`. However `map_fp2_to_g2` computation is more expensive than register writing
+// so we are okay overcharging it.
+// Compute map_fp2_to_g2 on 10 elements 100 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_map_fp2_to_g2_10_100() {
+    let buffer: [u8; 960] = bls12381_input(&[&BLS12381_FP, &BLS12381_FP]);
+    for _ in 0..100 {
+        bls12381_map_fp2_to_g2(buffer.len() as u64, buffer.as_ptr() as u64, 0);
+    }
+}
+
+// Function to measure `bls12381_pairing_check_base` and `bls12381_pairing_check_element`. Also measures `base`, `read_memory_base`,
+// and `read_memory_byte`. However `pairing_check` computation is more expensive than memory reading
+// so we are okay overcharging it.
+// Compute pairing_check on 1 element 10 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_pairing_check_1_10() {
+    let buffer: [u8; 288] = bls12381_input(&[&BLS12381_G1, &BLS12381_G2]);
+    for _ in 0..10 {
+        let result = bls12381_pairing_check(buffer.len() as u64, buffer.as_ptr() as u64);
+        // the pairing of the generators isn't one, so the check fails, but it's
+        // computed in full regardless.
+        assert!(result == 0);
+    }
+}
+
+// Function to measure `bls12381_pairing_check_base` and `bls12381_pairing_check_element`. Also measures `base`, `read_memory_base`,
+// and `read_memory_byte`. However `pairing_check` computation is more expensive than memory reading
+// so we are okay overcharging it.
+// Compute pairing_check on 10 elements 10 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn bls12381_pairing_check_10_10() {
+    let buffer: [u8; 2880] = bls12381_input(&[&BLS12381_G1, &BLS12381_G2]);
+    for _ in 0..10 {
+        let result = bls12381_pairing_check(buffer.len() as u64, buffer.as_ptr() as u64);
+        // the pairing of the generators isn't one, so the check fails, but it's
+        // computed in full regardless.
+        assert!(result == 0);
+    }
+}
+
 // ###############
 // # Storage API #
 // ###############
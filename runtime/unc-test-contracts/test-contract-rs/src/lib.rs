@@ -170,6 +170,26 @@ extern "C" {
     fn alt_bn128_g1_sum(value_len: u64, value_ptr: u64, register_id: u64);
     #[cfg(feature = "latest_protocol")]
     fn alt_bn128_pairing_check(value_len: u64, value_ptr: u64) -> u64;
+    // ################
+    // # Yield/Resume #
+    // ################
+    #[cfg(feature = "nightly")]
+    fn promise_yield_create(
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        gas_weight: u64,
+        register_id: u64,
+    ) -> u64;
+    #[cfg(feature = "nightly")]
+    fn promise_yield_resume(
+        data_id_len: u64,
+        data_id_ptr: u64,
+        payload_len: u64,
+        payload_ptr: u64,
+    ) -> u32;
 }
 
 macro_rules! ext_test {
@@ -1176,7 +1196,11 @@ pub unsafe fn sanity_check() {
     // #################
     let pledge = [0u8; size_of::<u128>()];
     let validator_id = input_args["validator_id"].as_str().unwrap().as_bytes();
-    validator_stake(validator_id.len() as u64, validator_id.as_ptr() as u64, pledge.as_ptr() as u64);
+    validator_stake(
+        validator_id.len() as u64,
+        validator_id.as_ptr() as u64,
+        pledge.as_ptr() as u64,
+    );
     validator_total_stake(pledge.as_ptr() as u64);
 
     // ###################
@@ -1244,3 +1268,89 @@ pub unsafe fn sanity_check_panic_utf8() {
     let data = b"xyz";
     panic_utf8(data.len() as u64, data.as_ptr() as u64);
 }
+
+#[cfg(feature = "nightly")]
+const YIELD_DATA_ID_KEY: &[u8] = b"yield_data_id";
+
+/// Yields a call to `yield_callback` and returns the data id which resumes it. The data id
+/// is also stored for `call_yield_resume_last`.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn call_yield_create() {
+    let method_name = b"yield_callback";
+    promise_yield_create(
+        method_name.len() as u64,
+        method_name.as_ptr() as u64,
+        0,
+        0,
+        50_000_000_000_000,
+        0,
+        0,
+    );
+    let data_id = vec![0u8; register_len(0) as usize];
+    read_register(0, data_id.as_ptr() as u64);
+    storage_write(
+        YIELD_DATA_ID_KEY.len() as u64,
+        YIELD_DATA_ID_KEY.as_ptr() as u64,
+        data_id.len() as u64,
+        data_id.as_ptr() as u64,
+        1,
+    );
+    value_return(data_id.len() as u64, data_id.as_ptr() as u64);
+}
+
+#[cfg(feature = "nightly")]
+unsafe fn yield_resume(data_id: &[u8], payload: &[u8]) {
+    let result = promise_yield_resume(
+        data_id.len() as u64,
+        data_id.as_ptr() as u64,
+        payload.len() as u64,
+        payload.as_ptr() as u64,
+    ) as u8;
+    value_return(1, &result as *const u8 as u64);
+}
+
+/// Resumes the yield with the data id in the first 32 bytes of the input, passing the rest
+/// of the input as the payload. Returns 1 if the yield was resumed and 0 otherwise.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn call_yield_resume() {
+    input(0);
+    let data = vec![0u8; register_len(0) as usize];
+    read_register(0, data.as_ptr() as u64);
+    let (data_id, payload) = data.split_at(32);
+    yield_resume(data_id, payload);
+}
+
+/// Resumes the yield created by the last `call_yield_create` with the input as the payload.
+/// Returns 1 if the yield was resumed and 0 otherwise.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn call_yield_resume_last() {
+    input(0);
+    let payload = vec![0u8; register_len(0) as usize];
+    read_register(0, payload.as_ptr() as u64);
+    assert_eq!(
+        storage_read(YIELD_DATA_ID_KEY.len() as u64, YIELD_DATA_ID_KEY.as_ptr() as u64, 0),
+        1
+    );
+    let data_id = vec![0u8; register_len(0) as usize];
+    read_register(0, data_id.as_ptr() as u64);
+    yield_resume(&data_id, &payload);
+}
+
+/// Callback of the yield created in `call_yield_create`. Returns the payload of the resume,
+/// or `timeout` if the yield timed out.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn yield_callback() {
+    assert_eq!(promise_results_count(), 1);
+    if promise_result(0, 0) == 1 {
+        let payload = vec![0u8; register_len(0) as usize];
+        read_register(0, payload.as_ptr() as u64);
+        value_return(payload.len() as u64, payload.as_ptr() as u64);
+    } else {
+        let result = b"timeout";
+        value_return(result.len() as u64, result.as_ptr() as u64);
+    }
+}
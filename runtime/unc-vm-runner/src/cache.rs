@@ -3,10 +3,11 @@ use crate::logic::errors::{CacheError, CompilationError};
 use crate::logic::{CompiledContract, CompiledContractCache, Config};
 use crate::runner::VMKindExt;
 use crate::ContractCode;
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::{Arc, Mutex};
+use unc_crypto::{PublicKey, Signature, Signer};
 use unc_parameters::vm::VMKind;
 use unc_primitives_core::hash::CryptoHash;
 
@@ -44,6 +45,8 @@ fn vm_hash(vm_kind: VMKind) -> u64 {
     }
 }
 
+/// The key only depends on the code hash and the VM configuration, so all accounts using the
+/// same global contract share a single compiled artifact.
 pub fn get_contract_cache_key(code: &ContractCode, config: &Config) -> CryptoHash {
     let _span = tracing::debug_span!(target: "vm", "get_key").entered();
     let key = ContractCacheKey::Version4 {
@@ -109,3 +112,81 @@ pub fn precompile_contract(
     }
     runtime.precompile(code, cache)
 }
+
+/// Compiled contracts exported from the cache of one node, to be imported into the cache of
+/// another node running the same VM build, so that it doesn't need to compile them itself.
+#[derive(Debug, Clone, Default, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct ContractCacheBundle {
+    pub entries: Vec<(CryptoHash, CompiledContract)>,
+}
+
+/// Compiled code is executed natively, so bundles are only accepted from trusted signers.
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct SignedContractCacheBundle {
+    pub bundle: ContractCacheBundle,
+    pub signer: PublicKey,
+    pub signature: Signature,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ContractCacheBundleError {
+    #[error("bundle is signed by {0}, which is not a trusted key")]
+    UntrustedSigner(PublicKey),
+    #[error("bundle signature is invalid")]
+    InvalidSignature,
+}
+
+impl ContractCacheBundle {
+    /// Collects the cached artifacts stored under `keys`. Keys missing from the cache are skipped.
+    pub fn export(
+        cache: &dyn CompiledContractCache,
+        keys: impl IntoIterator<Item = CryptoHash>,
+    ) -> Result<Self, CacheError> {
+        let mut entries = vec![];
+        for key in keys {
+            if let Some(compiled) = cache.get(&key).map_err(CacheError::ReadError)? {
+                entries.push((key, compiled));
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Writes the artifacts of the bundle that are not in `cache` yet and returns their number.
+    pub fn import(self, cache: &dyn CompiledContractCache) -> Result<usize, CacheError> {
+        let mut imported = 0;
+        for (key, compiled) in self.entries {
+            if cache.has(&key).map_err(CacheError::ReadError)? {
+                continue;
+            }
+            cache.put(&key, compiled).map_err(CacheError::WriteError)?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    pub fn sign(self, signer: &dyn Signer) -> SignedContractCacheBundle {
+        let hash = CryptoHash::hash_borsh(&self);
+        SignedContractCacheBundle {
+            signature: signer.sign(hash.as_ref()),
+            signer: signer.public_key(),
+            bundle: self,
+        }
+    }
+}
+
+impl SignedContractCacheBundle {
+    /// Returns the bundle if it was signed by one of `trusted_keys`.
+    pub fn verify(
+        self,
+        trusted_keys: &[PublicKey],
+    ) -> Result<ContractCacheBundle, ContractCacheBundleError> {
+        if !trusted_keys.contains(&self.signer) {
+            return Err(ContractCacheBundleError::UntrustedSigner(self.signer));
+        }
+        let hash = CryptoHash::hash_borsh(&self.bundle);
+        if !self.signature.verify(hash.as_ref(), &self.signer) {
+            return Err(ContractCacheBundleError::InvalidSignature);
+        }
+        Ok(self.bundle)
+    }
+}
@@ -1,6 +1,9 @@
-const REFERENCE_TYPES: bool = false;
+use crate::logic::ContractPrepareVersion;
+use unc_parameters::vm::Config;
+
+// Not part of `wasm_feature_expansion`: the unc-vm singlepass compiler only supports single-value
+// block and function results.
 const MULTI_VALUE: bool = false;
-const BULK_MEMORY: bool = false;
 const SIMD: bool = false;
 const THREADS: bool = false;
 const TAIL_CALL: bool = false;
@@ -18,16 +21,27 @@ const MEMORY_CONTROL: bool = false;
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) struct WasmFeatures {
     sign_extension: bool,
+    bulk_memory: bool,
+    reference_types: bool,
 }
 
-impl From<crate::logic::ContractPrepareVersion> for WasmFeatures {
-    fn from(version: crate::logic::ContractPrepareVersion) -> Self {
+impl From<&Config> for WasmFeatures {
+    fn from(config: &Config) -> Self {
+        let version = config.limit_config.contract_prepare_version;
         let sign_extension = match version {
-            crate::logic::ContractPrepareVersion::V0 => false,
-            crate::logic::ContractPrepareVersion::V1 => false,
-            crate::logic::ContractPrepareVersion::V2 => true,
+            ContractPrepareVersion::V0 => false,
+            ContractPrepareVersion::V1 => false,
+            ContractPrepareVersion::V2 => true,
         };
-        WasmFeatures { sign_extension }
+        // The V0 and V1 preparation is based on pwasm-utils, which does not understand these
+        // proposals.
+        let feature_expansion =
+            config.wasm_feature_expansion && version == ContractPrepareVersion::V2;
+        WasmFeatures {
+            sign_extension,
+            bulk_memory: feature_expansion,
+            reference_types: feature_expansion,
+        }
     }
 }
 
@@ -37,11 +51,11 @@ impl From<WasmFeatures> for finite_wasm::wasmparser::WasmFeatures {
             floats: true,
             mutable_global: true,
             sign_extension: f.sign_extension,
+            reference_types: f.reference_types,
+            bulk_memory: f.bulk_memory,
 
-            reference_types: REFERENCE_TYPES,
             // wasmer singlepass compiler requires multi_value return values to be disabled.
             multi_value: MULTI_VALUE,
-            bulk_memory: BULK_MEMORY,
             simd: SIMD,
             threads: THREADS,
             tail_call: TAIL_CALL,
@@ -60,7 +74,7 @@ impl From<WasmFeatures> for finite_wasm::wasmparser::WasmFeatures {
 }
 
 impl From<WasmFeatures> for wasmparser::WasmFeatures {
-    fn from(_: WasmFeatures) -> Self {
+    fn from(f: WasmFeatures) -> Self {
         // /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\
         //
         // There are features that this version of wasmparser enables by default, but pwasm
@@ -75,9 +89,9 @@ impl From<WasmFeatures> for wasmparser::WasmFeatures {
         //
         // /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\
         wasmparser::WasmFeatures {
-            reference_types: REFERENCE_TYPES,
+            reference_types: f.reference_types,
             multi_value: MULTI_VALUE,
-            bulk_memory: BULK_MEMORY,
+            bulk_memory: f.bulk_memory,
             simd: SIMD,
             threads: THREADS,
             tail_call: TAIL_CALL,
@@ -102,11 +116,11 @@ impl From<WasmFeatures> for unc_vm_types::Features {
         Self {
             mutable_global: true,
             sign_extension: f.sign_extension,
+            reference_types: f.reference_types,
+            bulk_memory: f.bulk_memory,
 
             threads: THREADS,
-            reference_types: REFERENCE_TYPES,
             simd: SIMD,
-            bulk_memory: BULK_MEMORY,
             multi_value: MULTI_VALUE,
             tail_call: TAIL_CALL,
             multi_memory: MULTI_MEMORY,
@@ -136,9 +150,10 @@ impl From<WasmFeatures> for wasmer_types::Features {
         Self {
             module_linking: false, // old version of component model
             threads: THREADS,
-            reference_types: REFERENCE_TYPES,
+            // Wasmer2 is not used by the protocol versions that enable these proposals.
+            reference_types: false,
             simd: SIMD,
-            bulk_memory: BULK_MEMORY,
+            bulk_memory: false,
             multi_value: MULTI_VALUE,
             tail_call: TAIL_CALL,
             multi_memory: MULTI_MEMORY,
@@ -153,9 +168,12 @@ impl From<WasmFeatures> for wasmtime::Config {
     fn from(_: WasmFeatures) -> Self {
         let mut config = wasmtime::Config::default();
         config.wasm_threads(THREADS);
-        config.wasm_reference_types(REFERENCE_TYPES);
+        // Only unc-vm charges `memory.copy`, `memory.fill` and the other bulk operations by the
+        // number of items they process, so contracts using these proposals fail to compile with
+        // wasmtime rather than run with a fixed cost per operation.
+        config.wasm_reference_types(false);
         config.wasm_simd(SIMD);
-        config.wasm_bulk_memory(BULK_MEMORY);
+        config.wasm_bulk_memory(false);
         config.wasm_multi_value(MULTI_VALUE);
         config.wasm_multi_memory(MULTI_MEMORY);
         config.wasm_memory64(MEMORY64);
@@ -125,6 +125,13 @@ imports! {
         pub_key_len: u64,
         pub_key_ptr: u64
     ] -> [u64]>,
+    #[rsa2048_verify] rsa2048_pkcs1v15_verify<[sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64
+    ] -> [u64]>,
     #[math_extension] ripemd160<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     #[math_extension] ecrecover<[hash_len: u64, hash_ptr: u64, sign_len: u64, sig_ptr: u64, v: u64, malleability_flag: u64, register_id: u64] -> [u64]>,
     // #####################
@@ -221,6 +228,41 @@ imports! {
         beneficiary_id_len: u64,
         beneficiary_id_ptr: u64
     ] -> []>,
+    #[rsa2048_actions] promise_batch_action_register_rsa2048_keys<[
+        promise_index: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+        operation_type: u64,
+        args_len: u64,
+        args_ptr: u64
+    ] -> []>,
+    #[rsa2048_actions] promise_batch_action_create_rsa2048_challenge<[
+        promise_index: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+        challenge_key_len: u64,
+        challenge_key_ptr: u64,
+        args_len: u64,
+        args_ptr: u64
+    ] -> []>,
+    // ############################
+    // # Promise Yield/Resume API #
+    // ############################
+    #[yield_resume_host_functions] promise_yield_create<[
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        gas_weight: u64,
+        register_id: u64
+    ] -> [u64]>,
+    #[yield_resume_host_functions] promise_yield_resume<[
+        data_id_len: u64,
+        data_id_ptr: u64,
+        payload_len: u64,
+        payload_ptr: u64
+    ] -> [u32]>,
     // #######################
     // # Promise API results #
     // #######################
@@ -253,6 +295,16 @@ imports! {
     #[alt_bn128] alt_bn128_g1_sum<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     #[alt_bn128] alt_bn128_pairing_check<[value_len: u64, value_ptr: u64] -> [u64]>,
     // #############
+    // # BLS12-381 #
+    // #############
+    #[bls12381] bls12381_p1_sum<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
+    #[bls12381] bls12381_p2_sum<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
+    #[bls12381] bls12381_g1_multiexp<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
+    #[bls12381] bls12381_g2_multiexp<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
+    #[bls12381] bls12381_map_fp_to_g1<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
+    #[bls12381] bls12381_map_fp2_to_g2<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
+    #[bls12381] bls12381_pairing_check<[value_len: u64, value_ptr: u64] -> [u64]>,
+    // #############
     // #  Sandbox  #
     // #############
     ##["sandbox"] sandbox_debug_log<[len: u64, ptr: u64] -> []>,
@@ -555,7 +607,17 @@ pub(crate) mod unc_vm {
                             // lifetime and so it is safe to dereference the `env` pointer which is
                             // known to be derived from a valid `&'vmlogic mut VMLogic<'_>` in the
                             // first place.
-                            unsafe { (*env).$func( $( $arg_name, )* ) }
+                            let logic = unsafe { &mut *env };
+                            if IS_GAS {
+                                return logic.$func( $( $arg_name, )* );
+                            }
+                            let step = logic.trace_host_call(
+                                stringify!($name),
+                                &[$( u64::from($arg_name) ),*],
+                            );
+                            let result = logic.$func( $( $arg_name, )* );
+                            logic.trace_host_call_done(step);
+                            result
                         }));
                         // We want to ensure that the only kind of error that host function calls
                         // return are VMLogicError. This is important because we later attempt to
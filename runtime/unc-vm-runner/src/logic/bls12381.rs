@@ -0,0 +1,260 @@
+//! BLS12-381 curve operations backing the `bls12381_*` host functions.
+//!
+//! Points are encoded in the uncompressed ZCash format: big-endian coordinates
+//! with the three most significant bits of the first byte used as flags. Only
+//! the infinity flag (`0x40`) is accepted; compressed points are rejected.
+//! Elements of Fp2 are encoded as `c1 || c0`.
+
+use super::{HostError, VMLogicError};
+use blst::{
+    blst_fp, blst_fp12, blst_fp2, blst_p1, blst_p1_affine, blst_p2, blst_p2_affine, BLST_ERROR,
+};
+
+const BOOL_SIZE: usize = 1;
+const SCALAR_SIZE: usize = 256 / 8;
+const FP_SIZE: usize = 48;
+const FP2_SIZE: usize = FP_SIZE * 2;
+const G1_SIZE: usize = FP_SIZE * 2;
+const G2_SIZE: usize = FP2_SIZE * 2;
+
+/// Set in the first byte of a serialized point if it's in compressed form.
+const COMPRESSION_FLAG: u8 = 0x80;
+
+pub(super) struct InvalidInput {
+    pub(super) msg: String,
+}
+
+impl InvalidInput {
+    fn new(msg: &str, bad_value: &[u8]) -> InvalidInput {
+        let msg = format!("{msg}: {bad_value:X?}");
+        InvalidInput { msg }
+    }
+}
+
+impl From<InvalidInput> for VMLogicError {
+    fn from(err: InvalidInput) -> Self {
+        HostError::Bls12381InvalidInput { msg: err.msg }.into()
+    }
+}
+
+pub(super) fn split_elements<const ELEMENT_SIZE: usize>(
+    data: &[u8],
+) -> Result<&[[u8; ELEMENT_SIZE]], InvalidInput> {
+    stdx::as_chunks_exact(data).map_err(|e| InvalidInput { msg: e.to_string() })
+}
+
+const P1_SUM_ELEMENT_SIZE: usize = BOOL_SIZE + G1_SIZE;
+
+pub(super) fn p1_sum(
+    elements: &[[u8; P1_SUM_ELEMENT_SIZE]],
+) -> Result<[u8; G1_SIZE], InvalidInput> {
+    let mut res = blst_p1::default();
+    for chunk in elements {
+        let (sign, point) = stdx::split_array(chunk);
+        let sign = decode_bool(sign)?;
+        let point = decode_g1(point, false)?;
+        let mut point = p1_from_affine(&point);
+        let mut sum = blst_p1::default();
+        unsafe {
+            blst::blst_p1_cneg(&mut point, sign);
+            blst::blst_p1_add_or_double(&mut sum, &res, &point);
+        }
+        res = sum;
+    }
+    Ok(encode_g1(&res))
+}
+
+const P2_SUM_ELEMENT_SIZE: usize = BOOL_SIZE + G2_SIZE;
+
+pub(super) fn p2_sum(
+    elements: &[[u8; P2_SUM_ELEMENT_SIZE]],
+) -> Result<[u8; G2_SIZE], InvalidInput> {
+    let mut res = blst_p2::default();
+    for chunk in elements {
+        let (sign, point) = stdx::split_array(chunk);
+        let sign = decode_bool(sign)?;
+        let point = decode_g2(point, false)?;
+        let mut point = p2_from_affine(&point);
+        let mut sum = blst_p2::default();
+        unsafe {
+            blst::blst_p2_cneg(&mut point, sign);
+            blst::blst_p2_add_or_double(&mut sum, &res, &point);
+        }
+        res = sum;
+    }
+    Ok(encode_g2(&res))
+}
+
+const G1_MULTIEXP_ELEMENT_SIZE: usize = G1_SIZE + SCALAR_SIZE;
+
+pub(super) fn g1_multiexp(
+    elements: &[[u8; G1_MULTIEXP_ELEMENT_SIZE]],
+) -> Result<[u8; G1_SIZE], InvalidInput> {
+    let mut res = blst_p1::default();
+    for chunk in elements {
+        let (point, scalar): (_, &[u8; SCALAR_SIZE]) = stdx::split_array(chunk);
+        let point = p1_from_affine(&decode_g1(point, true)?);
+        let mut product = blst_p1::default();
+        let mut sum = blst_p1::default();
+        unsafe {
+            blst::blst_p1_mult(&mut product, &point, scalar.as_ptr(), SCALAR_SIZE * 8);
+            blst::blst_p1_add_or_double(&mut sum, &res, &product);
+        }
+        res = sum;
+    }
+    Ok(encode_g1(&res))
+}
+
+const G2_MULTIEXP_ELEMENT_SIZE: usize = G2_SIZE + SCALAR_SIZE;
+
+pub(super) fn g2_multiexp(
+    elements: &[[u8; G2_MULTIEXP_ELEMENT_SIZE]],
+) -> Result<[u8; G2_SIZE], InvalidInput> {
+    let mut res = blst_p2::default();
+    for chunk in elements {
+        let (point, scalar): (_, &[u8; SCALAR_SIZE]) = stdx::split_array(chunk);
+        let point = p2_from_affine(&decode_g2(point, true)?);
+        let mut product = blst_p2::default();
+        let mut sum = blst_p2::default();
+        unsafe {
+            blst::blst_p2_mult(&mut product, &point, scalar.as_ptr(), SCALAR_SIZE * 8);
+            blst::blst_p2_add_or_double(&mut sum, &res, &product);
+        }
+        res = sum;
+    }
+    Ok(encode_g2(&res))
+}
+
+pub(super) fn map_fp_to_g1(elements: &[[u8; FP_SIZE]]) -> Result<Vec<u8>, InvalidInput> {
+    let mut res = Vec::with_capacity(elements.len() * G1_SIZE);
+    for chunk in elements {
+        let fp = decode_fp(chunk)?;
+        let mut point = blst_p1::default();
+        unsafe { blst::blst_map_to_g1(&mut point, &fp, std::ptr::null()) };
+        res.extend_from_slice(&encode_g1(&point));
+    }
+    Ok(res)
+}
+
+pub(super) fn map_fp2_to_g2(elements: &[[u8; FP2_SIZE]]) -> Result<Vec<u8>, InvalidInput> {
+    let mut res = Vec::with_capacity(elements.len() * G2_SIZE);
+    for chunk in elements {
+        let fp2 = decode_fp2(chunk)?;
+        let mut point = blst_p2::default();
+        unsafe { blst::blst_map_to_g2(&mut point, &fp2, std::ptr::null()) };
+        res.extend_from_slice(&encode_g2(&point));
+    }
+    Ok(res)
+}
+
+const PAIRING_CHECK_ELEMENT_SIZE: usize = G1_SIZE + G2_SIZE;
+
+pub(super) fn pairing_check(
+    elements: &[[u8; PAIRING_CHECK_ELEMENT_SIZE]],
+) -> Result<bool, InvalidInput> {
+    let mut acc = unsafe { *blst::blst_fp12_one() };
+    for chunk in elements {
+        let (g1, g2) = stdx::split_array(chunk);
+        let g1 = decode_g1(g1, true)?;
+        let g2 = decode_g2(g2, true)?;
+        // Pairings with the point at infinity are equal to one and don't affect the product.
+        if unsafe { blst::blst_p1_affine_is_inf(&g1) || blst::blst_p2_affine_is_inf(&g2) } {
+            continue;
+        }
+        let mut pairing = blst_fp12::default();
+        let mut product = blst_fp12::default();
+        unsafe {
+            blst::blst_miller_loop(&mut pairing, &g2, &g1);
+            blst::blst_fp12_mul(&mut product, &acc, &pairing);
+        }
+        acc = product;
+    }
+    let mut res = blst_fp12::default();
+    unsafe { blst::blst_final_exp(&mut res, &acc) };
+    Ok(unsafe { blst::blst_fp12_is_one(&res) })
+}
+
+fn p1_from_affine(point: &blst_p1_affine) -> blst_p1 {
+    let mut res = blst_p1::default();
+    unsafe { blst::blst_p1_from_affine(&mut res, point) };
+    res
+}
+
+fn p2_from_affine(point: &blst_p2_affine) -> blst_p2 {
+    let mut res = blst_p2::default();
+    unsafe { blst::blst_p2_from_affine(&mut res, point) };
+    res
+}
+
+fn encode_g1(point: &blst_p1) -> [u8; G1_SIZE] {
+    let mut res = [0u8; G1_SIZE];
+    unsafe { blst::blst_p1_serialize(res.as_mut_ptr(), point) };
+    res
+}
+
+fn encode_g2(point: &blst_p2) -> [u8; G2_SIZE] {
+    let mut res = [0u8; G2_SIZE];
+    unsafe { blst::blst_p2_serialize(res.as_mut_ptr(), point) };
+    res
+}
+
+/// Decodes an uncompressed G1 point, checking that it lies on the curve and, if
+/// `subgroup_check` is set, that it belongs to the prime order subgroup.
+fn decode_g1(raw: &[u8; G1_SIZE], subgroup_check: bool) -> Result<blst_p1_affine, InvalidInput> {
+    if raw[0] & COMPRESSION_FLAG != 0 {
+        return Err(InvalidInput::new("compressed g1 point", raw));
+    }
+    let mut point = blst_p1_affine::default();
+    if unsafe { blst::blst_p1_deserialize(&mut point, raw.as_ptr()) } != BLST_ERROR::BLST_SUCCESS {
+        return Err(InvalidInput::new("invalid g1", raw));
+    }
+    if subgroup_check && !unsafe { blst::blst_p1_affine_in_g1(&point) } {
+        return Err(InvalidInput::new("g1 point is not in the subgroup", raw));
+    }
+    Ok(point)
+}
+
+/// Decodes an uncompressed G2 point, checking that it lies on the curve and, if
+/// `subgroup_check` is set, that it belongs to the prime order subgroup.
+fn decode_g2(raw: &[u8; G2_SIZE], subgroup_check: bool) -> Result<blst_p2_affine, InvalidInput> {
+    if raw[0] & COMPRESSION_FLAG != 0 {
+        return Err(InvalidInput::new("compressed g2 point", raw));
+    }
+    let mut point = blst_p2_affine::default();
+    if unsafe { blst::blst_p2_deserialize(&mut point, raw.as_ptr()) } != BLST_ERROR::BLST_SUCCESS {
+        return Err(InvalidInput::new("invalid g2", raw));
+    }
+    if subgroup_check && !unsafe { blst::blst_p2_affine_in_g2(&point) } {
+        return Err(InvalidInput::new("g2 point is not in the subgroup", raw));
+    }
+    Ok(point)
+}
+
+fn decode_fp(raw: &[u8; FP_SIZE]) -> Result<blst_fp, InvalidInput> {
+    let mut fp = blst_fp::default();
+    let mut encoded = [0u8; FP_SIZE];
+    unsafe {
+        blst::blst_fp_from_bendian(&mut fp, raw.as_ptr());
+        blst::blst_bendian_from_fp(encoded.as_mut_ptr(), &fp);
+    }
+    // Values which are not reduced modulo p don't survive the round trip.
+    if &encoded != raw {
+        return Err(InvalidInput::new("invalid fp", raw));
+    }
+    Ok(fp)
+}
+
+fn decode_fp2(raw: &[u8; FP2_SIZE]) -> Result<blst_fp2, InvalidInput> {
+    let (c1, c0) = stdx::split_array(raw);
+    let c1 = decode_fp(c1)?;
+    let c0 = decode_fp(c0)?;
+    Ok(blst_fp2 { fp: [c0, c1] })
+}
+
+fn decode_bool(raw: &[u8; BOOL_SIZE]) -> Result<bool, InvalidInput> {
+    match raw {
+        [0] => Ok(false),
+        [1] => Ok(true),
+        _ => Err(InvalidInput::new("invalid bool", raw)),
+    }
+}
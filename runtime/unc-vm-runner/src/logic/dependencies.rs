@@ -270,6 +270,33 @@ pub trait External {
         receiver_id: AccountId,
     ) -> Result<ReceiptIndex, VMLogicError>;
 
+    /// Create a receipt on the current account which will be executed once the data
+    /// identified by the returned data id is submitted through `submit_promise_resume_data`, or
+    /// after it times out.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver_id` - account id of the receiver of the receipt created
+    fn create_promise_yield_receipt(
+        &mut self,
+        receiver_id: AccountId,
+    ) -> Result<(ReceiptIndex, CryptoHash), VMLogicError>;
+
+    /// Submit the data resuming a receipt created by `create_promise_yield_receipt`.
+    ///
+    /// Returns `false` if there is no yielded receipt waiting for `data_id` on the current
+    /// account, e.g. because it was already resumed or has timed out.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_id` - the data id of the yielded receipt
+    /// * `data` - the payload resuming the receipt
+    fn submit_promise_resume_data(
+        &mut self,
+        data_id: CryptoHash,
+        data: Vec<u8>,
+    ) -> Result<bool, VMLogicError>;
+
     /// Attach the [`CreateAccountAction`] action to an existing receipt.
     ///
     /// # Arguments
@@ -437,6 +464,46 @@ pub trait External {
         beneficiary_id: AccountId,
     ) -> Result<(), VMLogicError>;
 
+    /// Attach the [`RegisterRsa2048KeysAction`] action to an existing receipt.
+    ///
+    /// # Arguments
+    ///
+    /// * `receipt_index` - an index of Receipt to append an action
+    /// * `public_key` - a public key of the rsa2048 keys to register
+    /// * `operation_type` - whether the keys are added or deleted
+    /// * `args` - arguments of the registration, such as the miner id and power
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `receipt_index` does not refer to a known receipt.
+    fn append_action_register_rsa2048_keys(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: PublicKey,
+        operation_type: u8,
+        args: Vec<u8>,
+    );
+
+    /// Attach the [`CreateRsa2048ChallengeAction`] action to an existing receipt.
+    ///
+    /// # Arguments
+    ///
+    /// * `receipt_index` - an index of Receipt to append an action
+    /// * `public_key` - a public key of the challenged rsa2048 keys
+    /// * `challenge_key` - a key to bind the validator power to
+    /// * `args` - arguments of the challenge, such as the miner id and power
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `receipt_index` does not refer to a known receipt.
+    fn append_action_create_rsa2048_challenge(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: PublicKey,
+        challenge_key: PublicKey,
+        args: Vec<u8>,
+    );
+
     /// # Panic
     ///
     /// Panics if `ReceiptIndex` is invalid.
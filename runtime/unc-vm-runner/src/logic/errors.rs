@@ -136,6 +136,10 @@ pub enum PrepareError {
     TooManyFunctions,
     /// Contract contains too many locals.
     TooManyLocals,
+    /// Contract contains too many tables.
+    TooManyTables,
+    /// Contract contains a table with too many elements.
+    TooManyTableElements,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, strum::IntoStaticStr)]
@@ -206,6 +210,16 @@ pub enum HostError {
     /// Invalid input to ed25519 signature verification function (e.g. signature cannot be
     /// derived from bytes).
     Ed25519VerifyInvalidInput { msg: String },
+    /// Invalid input to RSA2048 signature verification function (e.g. signature of a wrong
+    /// length).
+    Rsa2048VerifyInvalidInput { msg: String },
+    /// Yield-resume payload is too large.
+    YieldPayloadLength { length: u64, limit: u64 },
+    /// Malformed data id passed to `promise_yield_resume`.
+    DataIdMalformed,
+    /// Invalid input to bls12381 family of functions (e.g. point which isn't
+    /// on the curve or in the subgroup).
+    Bls12381InvalidInput { msg: String },
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -286,6 +300,8 @@ impl fmt::Display for PrepareError {
             Memory => "Error creating memory.",
             TooManyFunctions => "Too many functions in contract.",
             TooManyLocals => "Too many locals declared in the contract.",
+            TooManyTables => "Too many tables declared in the contract.",
+            TooManyTableElements => "Too many elements in a table declared in the contract.",
         })
     }
 }
@@ -435,6 +451,15 @@ impl std::fmt::Display for HostError {
             Ed25519VerifyInvalidInput { msg } => {
                 write!(f, "ED25519 signature verification error: {}", msg)
             }
+            Rsa2048VerifyInvalidInput { msg } => {
+                write!(f, "RSA2048 signature verification error: {}", msg)
+            }
+            YieldPayloadLength { length, limit } => write!(
+                f,
+                "Yield resume payload is {length} bytes which exceeds the {limit} byte limit"
+            ),
+            DataIdMalformed => write!(f, "Malformed data id for yield resume"),
+            Bls12381InvalidInput { msg } => write!(f, "BLS12-381 invalid input: {}", msg),
         }
     }
 }
@@ -2,6 +2,7 @@ use super::context::VMContext;
 use super::dependencies::{External, MemSlice, MemoryLike};
 use super::errors::{FunctionCallError, InconsistentStateError};
 use super::gas_counter::{FastGasCounter, GasCounter};
+use super::trace::{self, ExecutionTrace, TraceEvent};
 use super::types::{PromiseIndex, PromiseResult, ReceiptIndex, ReturnData};
 use super::utils::split_method_names;
 use super::ValuePtr;
@@ -14,6 +15,7 @@ use unc_parameters::{
     transfer_exec_fee, transfer_send_fee, ActionCosts, ExtCosts, RuntimeFeesConfig,
 };
 use unc_primitives_core::config::ViewConfig;
+use unc_primitives_core::hash::CryptoHash;
 use unc_primitives_core::types::{
     AccountId, Balance, Compute, EpochHeight, Gas, GasWeight, StorageUsage,
 };
@@ -67,6 +69,9 @@ pub struct VMLogic<'a> {
 
     /// Stores the amount of stack space remaining
     remaining_stack: u64,
+
+    /// Steps of this call recorded so far, if tracing is enabled.
+    trace: Option<ExecutionTrace>,
 }
 
 /// Promises API allows to create a DAG-structure that defines dependencies between smart contract
@@ -166,6 +171,7 @@ impl<'a> VMLogic<'a> {
             promises: vec![],
             total_log_length: 0,
             remaining_stack: u64::from(config.limit_config.max_stack_height),
+            trace: trace::is_enabled().then(ExecutionTrace::default),
         }
     }
 
@@ -902,6 +908,276 @@ impl<'a> VMLogic<'a> {
         Ok(res as u64)
     }
 
+    /// Computes the sum of signed points on the BLS12-381 curve \sum_i
+    /// (-1)^{sign_i} p_{1 i}.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - sequence of (sign:bool, p1:E1), where
+    ///   E1 is point (x:Fp, y:Fp) on BLS12-381,
+    ///   BLS12-381 is Y^2 = X^3 + 4 curve over Fp.
+    ///
+    ///   `value` is encoded as packed `[(u8, [u8; 96])]` slice, where points use the
+    ///   uncompressed big-endian ZCash encoding. `0u8` is positive sign, `1u8` -- negative.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers
+    /// use more memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If point coordinates are not on curve, the point is compressed, sign is not 0 or 1,
+    /// or `value.len()%97!=0`, the function returns `Bls12381InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes +
+    /// bls12381_p1_sum_base + bls12381_p1_sum_element * num_elements`
+    pub fn bls12381_p1_sum(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(bls12381_p1_sum_base)?;
+        let data = get_memory_or_register!(self, value_ptr, value_len)?;
+
+        let elements = super::bls12381::split_elements(&data)?;
+        self.gas_counter.pay_per(bls12381_p1_sum_element, elements.len() as u64)?;
+
+        let res = super::bls12381::p1_sum(elements)?;
+
+        self.registers.set(&mut self.gas_counter, &self.config.limit_config, register_id, res)
+    }
+
+    /// Computes the sum of signed points on the BLS12-381 twist \sum_i
+    /// (-1)^{sign_i} p_{2 i}.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - sequence of (sign:bool, p2:E2), where
+    ///   E2 is point (x:Fp2, y:Fp2) on BLS12-381 twist,
+    ///   BLS12-381 twist is Y^2 = X^3 + 4(u + 1) curve over Fp2.
+    ///
+    ///   `value` is encoded as packed `[(u8, [u8; 192])]` slice, where points use the
+    ///   uncompressed big-endian ZCash encoding and Fp2 elements are encoded as `c1 || c0`.
+    ///   `0u8` is positive sign, `1u8` -- negative.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers
+    /// use more memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If point coordinates are not on curve, the point is compressed, sign is not 0 or 1,
+    /// or `value.len()%193!=0`, the function returns `Bls12381InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes +
+    /// bls12381_p2_sum_base + bls12381_p2_sum_element * num_elements`
+    pub fn bls12381_p2_sum(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(bls12381_p2_sum_base)?;
+        let data = get_memory_or_register!(self, value_ptr, value_len)?;
+
+        let elements = super::bls12381::split_elements(&data)?;
+        self.gas_counter.pay_per(bls12381_p2_sum_element, elements.len() as u64)?;
+
+        let res = super::bls12381::p2_sum(elements)?;
+
+        self.registers.set(&mut self.gas_counter, &self.config.limit_config, register_id, res)
+    }
+
+    /// Computes multiexp on the G1 subgroup of BLS12-381 curve \sum_i s_i g_{1 i}.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - sequence of (g1:G1, s:u256), where G1 is a point in the
+    ///   prime order subgroup of BLS12-381.
+    ///
+    ///   `value` is encoded as packed `[([u8; 96], u256)]` slice, where points use the
+    ///   uncompressed big-endian ZCash encoding and scalars are little-endian.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers
+    /// use more memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If point coordinates are not on curve, point is not in the subgroup, the point is
+    /// compressed or `value.len()%128!=0`, the function returns `Bls12381InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes +
+    /// bls12381_g1_multiexp_base + bls12381_g1_multiexp_element * num_elements`
+    pub fn bls12381_g1_multiexp(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(bls12381_g1_multiexp_base)?;
+        let data = get_memory_or_register!(self, value_ptr, value_len)?;
+
+        let elements = super::bls12381::split_elements(&data)?;
+        self.gas_counter.pay_per(bls12381_g1_multiexp_element, elements.len() as u64)?;
+
+        let res = super::bls12381::g1_multiexp(elements)?;
+
+        self.registers.set(&mut self.gas_counter, &self.config.limit_config, register_id, res)
+    }
+
+    /// Computes multiexp on the G2 subgroup of BLS12-381 twist \sum_i s_i g_{2 i}.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - sequence of (g2:G2, s:u256), where G2 is a point in the
+    ///   prime order subgroup of BLS12-381 twist.
+    ///
+    ///   `value` is encoded as packed `[([u8; 192], u256)]` slice, where points use the
+    ///   uncompressed big-endian ZCash encoding and scalars are little-endian.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers
+    /// use more memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If point coordinates are not on curve, point is not in the subgroup, the point is
+    /// compressed or `value.len()%224!=0`, the function returns `Bls12381InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes +
+    /// bls12381_g2_multiexp_base + bls12381_g2_multiexp_element * num_elements`
+    pub fn bls12381_g2_multiexp(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(bls12381_g2_multiexp_base)?;
+        let data = get_memory_or_register!(self, value_ptr, value_len)?;
+
+        let elements = super::bls12381::split_elements(&data)?;
+        self.gas_counter.pay_per(bls12381_g2_multiexp_element, elements.len() as u64)?;
+
+        let res = super::bls12381::g2_multiexp(elements)?;
+
+        self.registers.set(&mut self.gas_counter, &self.config.limit_config, register_id, res)
+    }
+
+    /// Maps each field element to a point in the G1 subgroup of BLS12-381 curve using
+    /// the simplified SWU map, and writes the concatenated points to the register.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - sequence of big-endian `Fp` elements, encoded as packed `[[u8; 48]]` slice.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers
+    /// use more memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If an element is not in the field or `value.len()%48!=0`, the function returns
+    /// `Bls12381InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes +
+    /// bls12381_map_fp_to_g1_base + bls12381_map_fp_to_g1_element * num_elements`
+    pub fn bls12381_map_fp_to_g1(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(bls12381_map_fp_to_g1_base)?;
+        let data = get_memory_or_register!(self, value_ptr, value_len)?;
+
+        let elements = super::bls12381::split_elements(&data)?;
+        self.gas_counter.pay_per(bls12381_map_fp_to_g1_element, elements.len() as u64)?;
+
+        let res = super::bls12381::map_fp_to_g1(elements)?;
+
+        self.registers.set(&mut self.gas_counter, &self.config.limit_config, register_id, res)
+    }
+
+    /// Maps each field element to a point in the G2 subgroup of BLS12-381 twist using
+    /// the simplified SWU map, and writes the concatenated points to the register.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - sequence of `Fp2` elements, encoded as packed `[[u8; 96]]` slice,
+    ///   where each element is `c1 || c0` with big-endian components.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers
+    /// use more memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If an element is not in the field or `value.len()%96!=0`, the function returns
+    /// `Bls12381InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + write_register_base + write_register_byte * num_bytes +
+    /// bls12381_map_fp2_to_g2_base + bls12381_map_fp2_to_g2_element * num_elements`
+    pub fn bls12381_map_fp2_to_g2(
+        &mut self,
+        value_len: u64,
+        value_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(bls12381_map_fp2_to_g2_base)?;
+        let data = get_memory_or_register!(self, value_ptr, value_len)?;
+
+        let elements = super::bls12381::split_elements(&data)?;
+        self.gas_counter.pay_per(bls12381_map_fp2_to_g2_element, elements.len() as u64)?;
+
+        let res = super::bls12381::map_fp2_to_g2(elements)?;
+
+        self.registers.set(&mut self.gas_counter, &self.config.limit_config, register_id, res)
+    }
+
+    /// Computes pairing check on BLS12-381 curve.
+    /// \prod_i e(g_{1 i}, g_{2 i}) should be equal one, e(g1, g2) is Ate pairing.
+    ///
+    /// Returns `1` if the check passes and `0` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - sequence of (g1:G1, g2:G2), where G1 and G2 are points in the
+    ///   prime order subgroups of BLS12-381 curve and its twist.
+    ///
+    ///   `value` is encoded as packed `[([u8; 96], [u8; 192])]` slice, where points use the
+    ///   uncompressed big-endian ZCash encoding.
+    ///
+    /// # Errors
+    ///
+    /// If `value_len + value_ptr` points outside the memory or the registers
+    /// use more memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If point coordinates are not on curve, point is not in the subgroup, the point is
+    /// compressed or `value.len()%288!=0`, the function returns `Bls12381InvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `base + bls12381_pairing_check_base + bls12381_pairing_check_element * num_elements`
+    pub fn bls12381_pairing_check(&mut self, value_len: u64, value_ptr: u64) -> Result<u64> {
+        self.gas_counter.pay_base(bls12381_pairing_check_base)?;
+        let data = get_memory_or_register!(self, value_ptr, value_len)?;
+
+        let elements = super::bls12381::split_elements(&data)?;
+        self.gas_counter.pay_per(bls12381_pairing_check_element, elements.len() as u64)?;
+
+        let res = super::bls12381::pairing_check(elements)?;
+
+        Ok(res as u64)
+    }
+
     /// Writes random seed into the register.
     ///
     /// # Errors
@@ -1209,6 +1485,80 @@ impl<'a> VMLogic<'a> {
         }
     }
 
+    /// Verify an RSA2048 RSASSA-PKCS1-v1_5 signature with SHA-256 given a
+    /// message and a public key. The public key is a DER-encoded
+    /// SubjectPublicKeyInfo, the same encoding as used by
+    /// `unc_crypto::Rsa2048PublicKey`.
+    ///
+    /// Returns a bool indicating success (1) or failure (0) as a `u64`. A
+    /// public key which can't be decoded or isn't a 2048-bit key is a failure.
+    ///
+    /// # Errors
+    ///
+    /// * If the public key's size is not equal to 294, or signature size is
+    ///   not equal to 256, returns [HostError::Rsa2048VerifyInvalidInput].
+    /// * If any of the signature, message or public key arguments are out of
+    ///   memory bounds, returns [`HostError::MemoryAccessViolation`]
+    ///
+    /// # Cost
+    ///
+    /// Each input can either be in memory or in a register, see
+    /// [`Self::ed25519_verify`] for the definition of `input_cost`.
+    ///
+    /// `input_cost(num_bytes_signature) + input_cost(num_bytes_message) +
+    ///  input_cost(num_bytes_public_key) + rsa2048_pkcs1v15_verify_base +
+    ///  rsa2048_pkcs1v15_verify_byte * num_bytes_message`
+    pub fn rsa2048_pkcs1v15_verify(
+        &mut self,
+        signature_len: u64,
+        signature_ptr: u64,
+        message_len: u64,
+        message_ptr: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+    ) -> Result<u64> {
+        use rsa::pkcs8::DecodePublicKey;
+        use rsa::traits::PublicKeyParts;
+        use sha2::Digest;
+
+        const SIGNATURE_LENGTH: usize = 256;
+        const PUBLIC_KEY_LENGTH: usize = 294;
+
+        self.gas_counter.pay_base(rsa2048_pkcs1v15_verify_base)?;
+
+        let signature = get_memory_or_register!(self, signature_ptr, signature_len)?;
+        if signature.len() != SIGNATURE_LENGTH {
+            return Err(HostError::Rsa2048VerifyInvalidInput {
+                msg: "invalid signature length".to_string(),
+            }
+            .into());
+        }
+        let signature = signature.into_owned();
+
+        let message = get_memory_or_register!(self, message_ptr, message_len)?;
+        self.gas_counter.pay_per(rsa2048_pkcs1v15_verify_byte, message.len() as u64)?;
+        let hashed = sha2::Sha256::digest(&message);
+
+        let public_key = {
+            let vec = get_memory_or_register!(self, public_key_ptr, public_key_len)?;
+            if vec.len() != PUBLIC_KEY_LENGTH {
+                return Err(HostError::Rsa2048VerifyInvalidInput {
+                    msg: "invalid public key length".to_string(),
+                }
+                .into());
+            }
+            match rsa::RsaPublicKey::from_public_key_der(&vec) {
+                Ok(public_key) if public_key.size() == SIGNATURE_LENGTH => public_key,
+                _ => return Ok(false as u64),
+            }
+        };
+
+        match public_key.verify(rsa::Pkcs1v15Sign::new::<sha2::Sha256>(), &hashed, &signature) {
+            Err(_) => Ok(false as u64),
+            Ok(()) => Ok(true as u64),
+        }
+    }
+
     /// Consume gas. Counts both towards `burnt_gas` and `used_gas`.
     ///
     /// # Errors
@@ -2035,6 +2385,248 @@ impl<'a> VMLogic<'a> {
         Ok(())
     }
 
+    /// Appends `RegisterRsa2048Keys` action to the batch of actions for the given promise pointed
+    /// by `promise_idx`.
+    ///
+    /// # Errors
+    ///
+    /// * If `promise_idx` does not correspond to an existing promise returns `InvalidPromiseIndex`.
+    /// * If the promise pointed by the `promise_idx` is an ephemeral promise created by
+    /// `promise_and` returns `CannotAppendActionToJointPromise`.
+    /// * If the given public key is not a valid (e.g. wrong length) returns `InvalidPublicKey`.
+    /// * If `operation_type` does not fit into `u8` returns `IntegerOverflow`.
+    /// * If `public_key_len + public_key_ptr` or `args_len + args_ptr` points outside the memory
+    /// of the guest or host returns `MemoryAccessViolation`.
+    /// * If called as view function returns `ProhibitedInView`.
+    ///
+    /// # Cost
+    ///
+    /// `burnt_gas := base + dispatch action base fee + cost of reading public key and args from memory `
+    /// `used_gas := burnt_gas + exec action base fee`
+    pub fn promise_batch_action_register_rsa2048_keys(
+        &mut self,
+        promise_idx: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+        operation_type: u64,
+        args_len: u64,
+        args_ptr: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(base)?;
+        if self.context.is_view() {
+            return Err(HostError::ProhibitedInView {
+                method_name: "promise_batch_action_register_rsa2048_keys".to_string(),
+            }
+            .into());
+        }
+        let public_key = self.get_public_key(public_key_ptr, public_key_len)?;
+        let operation_type =
+            u8::try_from(operation_type).map_err(|_| HostError::IntegerOverflow)?;
+        let args = get_memory_or_register!(self, args_ptr, args_len)?.into_owned();
+        let (receipt_idx, sir) = self.promise_idx_to_receipt_idx_with_sir(promise_idx)?;
+        self.pay_action_base(ActionCosts::register_rsa2048_keys, sir)?;
+        self.ext.append_action_register_rsa2048_keys(
+            receipt_idx,
+            public_key.decode()?,
+            operation_type,
+            args,
+        );
+        Ok(())
+    }
+
+    /// Appends `CreateRsa2048Challenge` action to the batch of actions for the given promise
+    /// pointed by `promise_idx`.
+    ///
+    /// # Errors
+    ///
+    /// * If `promise_idx` does not correspond to an existing promise returns `InvalidPromiseIndex`.
+    /// * If the promise pointed by the `promise_idx` is an ephemeral promise created by
+    /// `promise_and` returns `CannotAppendActionToJointPromise`.
+    /// * If any of the given public keys is not a valid (e.g. wrong length) returns
+    /// `InvalidPublicKey`.
+    /// * If `public_key_len + public_key_ptr`, `challenge_key_len + challenge_key_ptr` or
+    /// `args_len + args_ptr` points outside the memory of the guest or host returns
+    /// `MemoryAccessViolation`.
+    /// * If called as view function returns `ProhibitedInView`.
+    ///
+    /// # Cost
+    ///
+    /// `burnt_gas := base + dispatch action base fee + cost of reading public keys and args from memory `
+    /// `used_gas := burnt_gas + exec action base fee`
+    pub fn promise_batch_action_create_rsa2048_challenge(
+        &mut self,
+        promise_idx: u64,
+        public_key_len: u64,
+        public_key_ptr: u64,
+        challenge_key_len: u64,
+        challenge_key_ptr: u64,
+        args_len: u64,
+        args_ptr: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(base)?;
+        if self.context.is_view() {
+            return Err(HostError::ProhibitedInView {
+                method_name: "promise_batch_action_create_rsa2048_challenge".to_string(),
+            }
+            .into());
+        }
+        let public_key = self.get_public_key(public_key_ptr, public_key_len)?;
+        let challenge_key = self.get_public_key(challenge_key_ptr, challenge_key_len)?;
+        let args = get_memory_or_register!(self, args_ptr, args_len)?.into_owned();
+        let (receipt_idx, sir) = self.promise_idx_to_receipt_idx_with_sir(promise_idx)?;
+        self.pay_action_base(ActionCosts::create_rsa2048_challenge, sir)?;
+        self.ext.append_action_create_rsa2048_challenge(
+            receipt_idx,
+            public_key.decode()?,
+            challenge_key.decode()?,
+            args,
+        );
+        Ok(())
+    }
+
+    /// Creates a promise that will execute a method on the current account with given arguments.
+    /// The promise is postponed until it's resumed by `promise_yield_resume` with the data id
+    /// written into the register `register_id`, or until it times out. The method receives the
+    /// payload passed to `promise_yield_resume` as its only promise result, or a failed result
+    /// on timeout.
+    ///
+    /// # Errors
+    ///
+    /// * If `method_name_len + method_name_ptr` or `arguments_len + arguments_ptr` points outside
+    /// the memory of the guest or host returns `MemoryAccessViolation`.
+    /// * If called as view function returns `ProhibitedInView`.
+    /// * If the total number of promises exceeds `max_promises_per_function_call_action` limit
+    ///   returns `NumPromisesExceeded`.
+    ///
+    /// # Returns
+    ///
+    /// Index of the new promise that uniquely identifies it within the current execution of the
+    /// method.
+    ///
+    /// # Cost
+    ///
+    /// `burnt_gas := base + yield_create_base + yield_create_byte * num_bytes + cost of reading
+    ///  the method name and arguments + dispatch cost of the receipt and the function call`.
+    /// `used_gas := burnt_gas + exec cost of the receipt and the function call`.
+    pub fn promise_yield_create(
+        &mut self,
+        method_name_len: u64,
+        method_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: Gas,
+        gas_weight: u64,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.gas_counter.pay_base(base)?;
+        if self.context.is_view() {
+            return Err(HostError::ProhibitedInView {
+                method_name: "promise_yield_create".to_string(),
+            }
+            .into());
+        }
+        self.gas_counter.pay_base(yield_create_base)?;
+
+        let method_name = get_memory_or_register!(self, method_name_ptr, method_name_len)?;
+        if method_name.is_empty() {
+            return Err(HostError::EmptyMethodName.into());
+        }
+        let arguments = get_memory_or_register!(self, arguments_ptr, arguments_len)?;
+        let method_name = method_name.into_owned();
+        let arguments = arguments.into_owned();
+
+        // Input can't be large enough to overflow
+        let num_bytes = method_name.len() as u64 + arguments.len() as u64;
+        self.gas_counter.pay_per(yield_create_byte, num_bytes)?;
+        // The yielded receipt is executed on the current account and depends on a single data
+        // receipt which is produced either by the resume or by the timeout.
+        self.pay_gas_for_new_receipt(true, &[true])?;
+        self.pay_action_base(ActionCosts::function_call_base, true)?;
+        self.pay_action_per_byte(ActionCosts::function_call_byte, num_bytes, true)?;
+        // Prepaid gas
+        self.gas_counter.prepay_gas(gas)?;
+
+        let (new_receipt_idx, data_id) =
+            self.ext.create_promise_yield_receipt(self.context.current_account_id.clone())?;
+        let new_promise_idx = self.checked_push_promise(Promise::Receipt(new_receipt_idx))?;
+        self.ext.append_action_function_call_weight(
+            new_receipt_idx,
+            method_name,
+            arguments,
+            0,
+            gas,
+            GasWeight(gas_weight),
+        )?;
+
+        self.registers.set(
+            &mut self.gas_counter,
+            &self.config.limit_config,
+            register_id,
+            data_id.as_ref(),
+        )?;
+        Ok(new_promise_idx)
+    }
+
+    /// Resumes the promise created by `promise_yield_create` on the current account with the
+    /// given payload. The yielded method is executed with the payload as its promise result.
+    ///
+    /// # Errors
+    ///
+    /// * If `data_id_len + data_id_ptr` or `payload_len + payload_ptr` points outside the memory
+    /// of the guest or host returns `MemoryAccessViolation`.
+    /// * If called as view function returns `ProhibitedInView`.
+    /// * If the payload is longer than `max_yield_payload_size` returns `YieldPayloadLength`.
+    /// * If the data id is not 32 bytes long returns `DataIdMalformed`.
+    ///
+    /// # Returns
+    ///
+    /// `1` if the yielded promise is found and resumed, `0` if there is no such promise on the
+    /// current account, e.g. because it was already resumed or has timed out.
+    ///
+    /// # Cost
+    ///
+    /// `base + yield_resume_base + yield_resume_byte * payload_len + cost of reading the data
+    ///  id and the payload`.
+    pub fn promise_yield_resume(
+        &mut self,
+        data_id_len: u64,
+        data_id_ptr: u64,
+        payload_len: u64,
+        payload_ptr: u64,
+    ) -> Result<u32> {
+        self.gas_counter.pay_base(base)?;
+        if self.context.is_view() {
+            return Err(HostError::ProhibitedInView {
+                method_name: "promise_yield_resume".to_string(),
+            }
+            .into());
+        }
+        self.gas_counter.pay_base(yield_resume_base)?;
+
+        // The length is checked before the payload is read, so that a contract can't make us
+        // read more than the limit. A missing register is reported when reading the payload.
+        let length = if payload_len == u64::MAX {
+            self.registers.get_len(payload_ptr).unwrap_or(0)
+        } else {
+            payload_len
+        };
+        if length > self.config.limit_config.max_yield_payload_size {
+            return Err(HostError::YieldPayloadLength {
+                length,
+                limit: self.config.limit_config.max_yield_payload_size,
+            }
+            .into());
+        }
+        self.gas_counter.pay_per(yield_resume_byte, length)?;
+
+        let data_id = get_memory_or_register!(self, data_id_ptr, data_id_len)?;
+        let payload = get_memory_or_register!(self, payload_ptr, payload_len)?;
+        let data_id: [u8; CryptoHash::LENGTH] =
+            (&*data_id).try_into().map_err(|_| HostError::DataIdMalformed)?;
+        let payload = payload.into_owned();
+        self.ext.submit_promise_resume_data(CryptoHash(data_id), payload).map(u32::from)
+    }
+
     /// If the current function is invoked by a callback we can access the execution results of the
     /// promises that caused the callback. This function returns the number of complete and
     /// incomplete callbacks.
@@ -2424,6 +3016,14 @@ impl<'a> VMLogic<'a> {
 
         self.gas_counter.add_trie_fees(&nodes_delta)?;
         self.ext.storage_set(&key, &value)?;
+        if let Some(trace) = self.trace.as_mut() {
+            let event = TraceEvent::StorageWrite {
+                key: key.to_vec(),
+                value: value.to_vec(),
+                evicted: evicted.clone(),
+            };
+            trace.push(event, self.gas_counter.burnt_gas(), self.gas_counter.used_gas());
+        }
         let storage_config = &self.fees_config.storage_usage_config;
         match evicted {
             Some(old_value) => {
@@ -2523,6 +3123,10 @@ impl<'a> VMLogic<'a> {
             tn_mem_reads = nodes_delta.mem_reads,
         );
 
+        if let Some(trace) = self.trace.as_mut() {
+            let event = TraceEvent::StorageRead { key: key.to_vec(), value: read.clone() };
+            trace.push(event, self.gas_counter.burnt_gas(), self.gas_counter.used_gas());
+        }
         match read {
             Some(value) => {
                 self.registers.set(
@@ -2598,6 +3202,10 @@ impl<'a> VMLogic<'a> {
         );
 
         self.gas_counter.add_trie_fees(&nodes_delta)?;
+        if let Some(trace) = self.trace.as_mut() {
+            let event = TraceEvent::StorageRemove { key: key.to_vec(), removed: removed.clone() };
+            trace.push(event, self.gas_counter.burnt_gas(), self.gas_counter.used_gas());
+        }
         let storage_config = &self.fees_config.storage_usage_config;
         match removed {
             Some(value) => {
@@ -2788,6 +3396,11 @@ impl<'a> VMLogic<'a> {
         profile.compute_wasm_instruction_cost(burnt_gas);
         let compute_usage = profile.total_compute_usage(&self.config.ext_costs);
 
+        let mut trace = self.trace;
+        if let Some(trace) = trace.as_mut() {
+            trace.push_wasm_execution(burnt_gas, used_gas);
+        }
+
         VMOutcome {
             balance: self.current_account_balance,
             storage_usage: self.current_storage_usage,
@@ -2798,6 +3411,27 @@ impl<'a> VMLogic<'a> {
             logs: self.logs,
             profile,
             aborted: None,
+            trace,
+        }
+    }
+
+    /// Records a call to the host function `name` in the execution trace, if
+    /// tracing is enabled. The wasm code executed since the previous step is
+    /// recorded before it.
+    ///
+    /// Returns the index of the recorded step, which should be passed to
+    /// [`Self::trace_host_call_done`] once the host function returns.
+    pub fn trace_host_call(&mut self, name: &str, args: &[u64]) -> Option<usize> {
+        let trace = self.trace.as_mut()?;
+        trace.push_wasm_execution(self.gas_counter.burnt_gas(), self.gas_counter.used_gas());
+        let event = TraceEvent::HostCall { name: name.to_string(), args: args.to_vec() };
+        Some(trace.push(event, self.gas_counter.burnt_gas(), self.gas_counter.used_gas()))
+    }
+
+    /// Updates the gas of a host call step recorded by [`Self::trace_host_call`].
+    pub fn trace_host_call_done(&mut self, step: Option<usize>) {
+        if let (Some(trace), Some(step)) = (self.trace.as_mut(), step) {
+            trace.set_gas(step, self.gas_counter.burnt_gas(), self.gas_counter.used_gas());
         }
     }
 
@@ -2810,7 +3444,12 @@ impl<'a> VMLogic<'a> {
     /// motivation to (only) have this simple fee.
     pub fn add_contract_loading_fee(&mut self, code_len: u64) -> Result<()> {
         self.gas_counter.pay_per(contract_loading_bytes, code_len)?;
-        self.gas_counter.pay_base(contract_loading_base)
+        self.gas_counter.pay_base(contract_loading_base)?;
+        if let Some(trace) = self.trace.as_mut() {
+            let event = TraceEvent::ContractLoading;
+            trace.push(event, self.gas_counter.burnt_gas(), self.gas_counter.used_gas());
+        }
+        Ok(())
     }
 
     /// Gets pointer to the fast gas counter.
@@ -2906,6 +3545,8 @@ pub struct VMOutcome {
     /// Data collected from making a contract call
     pub profile: ProfileDataV3,
     pub aborted: Option<FunctionCallError>,
+    /// Steps of the call, recorded only when tracing is enabled.
+    pub trace: Option<ExecutionTrace>,
 }
 
 impl VMOutcome {
@@ -2938,6 +3579,7 @@ impl VMOutcome {
             logs: Vec::new(),
             profile: ProfileDataV3::default(),
             aborted: Some(error),
+            trace: None,
         }
     }
 
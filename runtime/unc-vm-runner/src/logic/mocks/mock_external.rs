@@ -16,6 +16,14 @@ pub enum MockAction {
         receipt_indices: Vec<ReceiptIndex>,
         receiver_id: AccountId,
     },
+    YieldCreate {
+        data_id: CryptoHash,
+        receiver_id: AccountId,
+    },
+    YieldResume {
+        data_id: CryptoHash,
+        data: Vec<u8>,
+    },
     CreateAccount {
         receipt_index: ReceiptIndex,
     },
@@ -62,6 +70,18 @@ pub enum MockAction {
         public_key: unc_crypto::PublicKey,
         nonce: u64,
     },
+    RegisterRsa2048Keys {
+        receipt_index: ReceiptIndex,
+        public_key: unc_crypto::PublicKey,
+        operation_type: u8,
+        args: Vec<u8>,
+    },
+    CreateRsa2048Challenge {
+        receipt_index: ReceiptIndex,
+        public_key: unc_crypto::PublicKey,
+        challenge_key: unc_crypto::PublicKey,
+        args: Vec<u8>,
+    },
 }
 
 #[derive(Default, Clone)]
@@ -179,6 +199,28 @@ impl External for MockedExternal {
         Ok(index as u64)
     }
 
+    fn create_promise_yield_receipt(
+        &mut self,
+        receiver_id: AccountId,
+    ) -> Result<(ReceiptIndex, CryptoHash), crate::logic::VMLogicError> {
+        let index = self.action_log.len();
+        let data_id = self.generate_data_id();
+        self.action_log.push(MockAction::YieldCreate { data_id, receiver_id });
+        Ok((index as u64, data_id))
+    }
+
+    fn submit_promise_resume_data(
+        &mut self,
+        data_id: CryptoHash,
+        data: Vec<u8>,
+    ) -> Result<bool, crate::logic::VMLogicError> {
+        let yielded = self.action_log.iter().any(
+            |action| matches!(action, MockAction::YieldCreate { data_id: id, .. } if id == &data_id),
+        );
+        self.action_log.push(MockAction::YieldResume { data_id, data });
+        Ok(yielded)
+    }
+
     fn append_action_create_account(
         &mut self,
         receipt_index: ReceiptIndex,
@@ -280,9 +322,40 @@ impl External for MockedExternal {
         Ok(())
     }
 
+    fn append_action_register_rsa2048_keys(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: unc_crypto::PublicKey,
+        operation_type: u8,
+        args: Vec<u8>,
+    ) {
+        self.action_log.push(MockAction::RegisterRsa2048Keys {
+            receipt_index,
+            public_key,
+            operation_type,
+            args,
+        });
+    }
+
+    fn append_action_create_rsa2048_challenge(
+        &mut self,
+        receipt_index: ReceiptIndex,
+        public_key: unc_crypto::PublicKey,
+        challenge_key: unc_crypto::PublicKey,
+        args: Vec<u8>,
+    ) {
+        self.action_log.push(MockAction::CreateRsa2048Challenge {
+            receipt_index,
+            public_key,
+            challenge_key,
+            args,
+        });
+    }
+
     fn get_receipt_receiver(&self, receipt_index: ReceiptIndex) -> &AccountId {
         match &self.action_log[receipt_index as usize] {
-            MockAction::CreateReceipt { receiver_id, .. } => receiver_id,
+            MockAction::CreateReceipt { receiver_id, .. }
+            | MockAction::YieldCreate { receiver_id, .. } => receiver_id,
             _ => panic!("not a valid receipt index!"),
         }
     }
@@ -4,6 +4,7 @@ use types::AccountId;
 use unc_primitives_core::hash::CryptoHash;
 
 mod alt_bn128;
+mod bls12381;
 mod context;
 mod dependencies;
 pub mod errors;
@@ -13,6 +14,7 @@ pub mod mocks;
 pub mod test_utils;
 #[cfg(test)]
 mod tests;
+pub mod trace;
 pub mod types;
 mod utils;
 mod vmstate;
@@ -22,6 +24,7 @@ pub use dependencies::{External, MemSlice, MemoryLike, ValuePtr};
 pub use errors::{HostError, VMLogicError};
 pub use gas_counter::with_ext_cost_counter;
 pub use logic::{VMLogic, VMOutcome};
+pub use trace::{with_tracing, ExecutionTrace, FunctionCallTrace};
 pub use types::ReturnData;
 pub use unc_parameters::vm::{Config, ContractPrepareVersion, LimitConfig, StorageGetMode};
 pub use unc_primitives_core::types::ProtocolVersion;
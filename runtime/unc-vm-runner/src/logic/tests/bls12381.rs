@@ -0,0 +1,425 @@
+use crate::logic::tests::vm_logic_builder::{TestVMLogic, VMLogicBuilder};
+use crate::logic::{HostError, VMLogicError};
+
+const G1_SIZE: usize = 96;
+const G2_SIZE: usize = 192;
+
+/// Generator of G1, encoded as `x || y`.
+const G1: &str = concat!(
+    "17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+    "08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1",
+);
+
+/// Generator of G2, encoded as `x.c1 || x.c0 || y.c1 || y.c0`.
+const G2: &str = concat!(
+    "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e",
+    "024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+    "0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be",
+    "0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801",
+);
+
+/// `(u, P)` known-answer vectors of the `BLS12381G1_XMD:SHA-256_SSWU_*` suites of the
+/// hash-to-curve draft, where `P` is the sum of the points `u` maps to.
+const MAP_FP_TO_G1_VECTORS: &[(&str, &str)] = &[
+    // NU, msg = ""
+    (
+        concat!(
+            "156c8a6a2c184569d69a76be144b5cdc5141d2d2ca4fe341f011e25e3969c55ad9e9b9ce2eb833c81a908e5fa4ac5f03",
+        ),
+        concat!(
+            "184bb665c37ff561a89ec2122dd343f20e0f4cbcaec84e3c3052ea81d1834e192c426074b02ed3dca4e7676ce4ce48ba",
+            "04407b8d35af4dacc809927071fc0405218f1401a6d15af775810e4e460064bcc9468beeba82fdc751be70476c888bf3",
+        ),
+    ),
+    // NU, msg = "abc"
+    (
+        concat!(
+            "147e1ed29f06e4c5079b9d14fc89d2820d32419b990c1c7bb7dbea2a36a045124b31ffbde7c99329c05c559af1c6cc82",
+        ),
+        concat!(
+            "009769f3ab59bfd551d53a5f846b9984c59b97d6842b20a2c565baa167945e3d026a3755b6345df8ec7e6acb6868ae6d",
+            "1532c00cf61aa3d0ce3e5aa20c3b531a2abd2c770a790a2613818303c6b830ffc0ecf6c357af3317b9575c567f11cd2c",
+        ),
+    ),
+    // RO, msg = ""
+    (
+        concat!(
+            "0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+            "019b9bd7979f12657976de2884c7cce192b82c177c80e0ec604436a7f538d231552f0d96d9f7babe5fa3b19b3ff25ac9",
+        ),
+        concat!(
+            "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+            "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+        ),
+    ),
+    // RO, msg = "abc"
+    (
+        concat!(
+            "0d921c33f2bad966478a03ca35d05719bdf92d347557ea166e5bba579eea9b83e9afa5c088573c2281410369fbd32951",
+            "003574a00b109ada2f26a37a91f9d1e740dffd8d69ec0c35e1e9f4652c7dba61123e9dd2e76c655d956e2b3462611139",
+        ),
+        concat!(
+            "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+            "0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+        ),
+    ),
+];
+
+/// `(u, P)` known-answer vectors of the `BLS12381G2_XMD:SHA-256_SSWU_*` suites of the
+/// hash-to-curve draft, where `P` is the sum of the points `u` maps to.
+const MAP_FP2_TO_G2_VECTORS: &[(&str, &str)] = &[
+    // NU, msg = ""
+    (
+        concat!(
+            "02829ce3c021339ccb5caf3e187f6370e1e2a311dec9b75363117063ab2015603ff52c3d3b98f19c2f65575e99e8b78c",
+            "07355d25caf6e7f2f0cb2812ca0e513bd026ed09dda65b177500fa31714e09ea0ded3a078b526bed3307f804d4b93b04",
+        ),
+        concat!(
+            "126b855e9e69b1f691f816e48ac6977664d24d99f8724868a184186469ddfd4617367e94527d4b74fc86413483afb35b",
+            "00e7f4568a82b4b7dc1f14c6aaa055edf51502319c723c4dc2688c7fe5944c213f510328082396515734b6612c4e7bb7",
+            "1498aadcf7ae2b345243e281ae076df6de84455d766ab6fcdaad71fab60abb2e8b980a440043cd305db09d283c895e3d",
+            "0caead0fd7b6176c01436833c79d305c78be307da5f6af6c133c47311def6ff1e0babf57a0fb5539fce7ee12407b0a42",
+        ),
+    ),
+    // NU, msg = "abc"
+    (
+        concat!(
+            "0a1ae7999ea9bab1dcc9ef8887a6cb6e8f1e22566015428d220b7eec90ffa70ad1f624018a9ad11e78d588bd3617f9f2",
+            "138879a9559e24cecee8697b8b4ad32cced053138ab913b99872772dc753a2967ed50aabc907937aefb2439ba06cc50c",
+        ),
+        concat!(
+            "0296238ea82c6d4adb3c838ee3cb2346049c90b96d602d7bb1b469b905c9228be25c627bffee872def773d5b2a2eb57d",
+            "108ed59fd9fae381abfd1d6bce2fd2fa220990f0f837fa30e0f27914ed6e1454db0d1ee957b219f61da6ff8be0d6441f",
+            "153606c417e59fb331b7ae6bce4fbf7c5190c33ce9402b5ebe2b70e44fca614f3f1382a3625ed5493843d0b0a652fc3f",
+            "033f90f6057aadacae7963b0a0b379dd46750c1c94a6357c99b65f63b79e321ff50fe3053330911c56b6ceea08fee656",
+        ),
+    ),
+    // RO, msg = ""
+    (
+        concat!(
+            "05a2acec64114845711a54199ea339abd125ba38253b70a92c876df10598bd1986b739cad67961eb94f7076511b3b39a",
+            "03dbc2cce174e91ba93cbb08f26b917f98194a2ea08d1cce75b2b9cc9f21689d80bd79b594a613d0a68eb807dfdc1cf8",
+            "145a81e418d4010cc027a68f14391b30074e89e60ee7a22f87217b2f6eb0c4b94c9115b436e6fa4607e95a98de30a435",
+            "02f99798e8a5acdeed60d7e18e9120521ba1f47ec090984662846bc825de191b5b7641148c0dbc237726a334473eee94",
+        ),
+        concat!(
+            "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+            "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+            "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+            "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+        ),
+    ),
+    // RO, msg = "abc"
+    (
+        concat!(
+            "01c8067bf4c0ba709aa8b9abc3d1cef589a4758e09ef53732d670fd8739a7274e111ba2fcaa71b3d33df2a3a0c8529dd",
+            "15f7c0aa8f6b296ab5ff9c2c7581ade64f4ee6f1bf18f55179ff44a2cf355fa53dd2a2158c5ecb17d7c52f63e7195771",
+            "08b852331c96ed983e497ebc6dee9b75e373d923b729194af8e72a051ea586f3538a6ebb1e80881a082fa2b24df9f566",
+            "187111d5e088b6b9acfdfad078c4dacf72dcd17ca17c82be35e79f8c372a693f60a033b461d81b025864a0ad051a06e4",
+        ),
+        concat!(
+            "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+            "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+            "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+            "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+        ),
+    ),
+];
+
+/// `2 * G1`, as in the `g1+g1=2*g1` vector of EIP-2537.
+const G1_DOUBLE: &str = concat!(
+    "0572cbea904d67468808c8eb50a9450c9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e",
+    "166a9d8cabc673a322fda673779d8e3822ba3ecb8670e461f73bb9021d5fd76a4c56d9d4cd16bd1bba86881979749d28",
+);
+
+/// `-(6 * G1)`.
+const G1_MINUS_SIX: &str = concat!(
+    "06e82f6da4520f85c5d27d8f329eccfa05944fd1096b20734c894966d12a9e2a9a9744529d7212d33883113a0cadb909",
+    "022901b141a9daabba0acdf56c7a9ca7819db2bb9b92848d7b0885e0b57c1695d6c307cebda4d19f13259775ba9c632f",
+);
+
+/// `2 * G2`, as in the `g2+g2=2*g2` vector of EIP-2537.
+const G2_DOUBLE: &str = concat!(
+    "0a4edef9c1ed7f729f520e47730a124fd70662a904ba1074728114d1031e1572c6c886f6b57ec72a6178288c47c33577",
+    "1638533957d540a9d2370f17cc7ed5863bc0b995b8825e0ee1ea1e1e4d00dbae81f14b0bf3611b78c952aacab827a053",
+    "0f6d4552fa65dd2638b361543f887136a43253d9c66c411697003f7a13c308f5422e1aa0a59c8967acdefd8b6e36ccf3",
+    "0468fb440d82b0630aeb8dca2b5256789a66da69bf91009cbfe6bd221e47aa8ae88dece9764bf3bd999d95d71e4c9899",
+);
+
+/// `3 * G2`.
+const G2_TRIPLE: &str = concat!(
+    "09380275bbc8e5dcea7dc4dd7e0550ff2ac480905396eda55062650f8d251c96eb480673937cc6d9d6a44aaa56ca66dc",
+    "122915c824a0857e2ee414a3dccb23ae691ae54329781315a0c75df1c04d6d7a50a030fc866f09d516020ef82324afae",
+    "08f239ba329b3967fe48d718a36cfe5f62a7e42e0bf1c1ed714150a166bfbd6bcf6b3b58b975b9edea56d53f23a0e849",
+    "0b21da7955969e61010c7a1abc1a6f0136961d1e3b20b1a7326ac738fef5c721479dfd948b52fdf2455e44813ecfd892",
+);
+
+/// `r - 1`, where `r` is the order of the subgroup, encoded as little-endian.
+const R_MINUS_ONE: &str =
+    concat!("00000000fffffffffe5bfeff02a4bd5305d8a10908d83933487d9d2953a7ed73",);
+
+/// Scalar of the multiexp vectors, encoded as little-endian.
+const SCALAR: &str = concat!("c1b9a7f5e3d1c9b7f5d3e1a9c7a0b2a8a4d6f496abd8e6c4c38ea4a8a2fed02b",);
+
+/// `SCALAR * G1 + (r - 1) * P`, where `P` is the first point of `MAP_FP_TO_G1_VECTORS`.
+const G1_MULTIEXP: &str = concat!(
+    "0e677053ccdb8dfe40a373b951cc24bb17e0c2f173271172bba0555a197f416983a71aa170ae67bf26001a8f3bae364a",
+    "13be7aa8387a7470564f5d5aefb317aa58d193c4be8b5ab662e26f20dae9c75f3bac8d47e2d91862e389ab57a7ecaeea",
+);
+
+/// `SCALAR * G2 + (r - 1) * Q`, where `Q` is the first point of `MAP_FP2_TO_G2_VECTORS`.
+const G2_MULTIEXP: &str = concat!(
+    "16f5459e8befa014fc1914af5a1d7b07dd5a2a635c046679d99e03b68761b4518bfd4c034874b4c564cf5ff0ff4ec2ac",
+    "0a913681f8c2ceae1e6fe0621216e1f090a39e84f225095260fb7cad47909b6fdfb5c5dd2486951c0fdcbc1eaddce31e",
+    "09142e8f27709368fefbb8272a075f2d727cc9f6aef3e47a8a0926232dd588845faaac125a59e9697f17894d47945b1a",
+    "04f559c43f1ed6852b3903288e87fcf601deff10f9a031af1600a4e025b988bd09aeafed187cbc95af021cc869e29ab4",
+);
+
+/// A point on the G1 curve outside of the prime order subgroup.
+const G1_NOT_IN_SUBGROUP: &str = concat!(
+    "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000004",
+    "0a989badd40d6212b33cffc3f3763e9bc760f988c9926b26da9dd85e928483446346b8ed00e1de5d5ea93e354abe706c",
+);
+
+/// A point on the G2 curve outside of the prime order subgroup.
+const G2_NOT_IN_SUBGROUP: &str = concat!(
+    "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002",
+    "02d27e0ec3356299a346a09ad7dc4ef68a483c3aed53f9139d2f929a3eecebf72082e5e58c6da24ee32e03040c406d4f",
+    "013a59858b6809fca4d9a3b6539246a70051a3c88899964a42bc9a69cf9acdd9dd387cfa9086b894185b9a46a402be73",
+);
+
+fn g1() -> Vec<u8> {
+    hex::decode(G1).unwrap()
+}
+
+fn g2() -> Vec<u8> {
+    hex::decode(G2).unwrap()
+}
+
+fn infinity(size: usize) -> Vec<u8> {
+    let mut res = vec![0u8; size];
+    res[0] = 0x40;
+    res
+}
+
+fn scalar(value: u64) -> Vec<u8> {
+    let mut res = value.to_le_bytes().to_vec();
+    res.resize(32, 0);
+    res
+}
+
+fn signed(sign: u8, point: &[u8]) -> Vec<u8> {
+    [&[sign][..], point].concat()
+}
+
+#[track_caller]
+fn call(
+    input: &[u8],
+    f: impl FnOnce(&mut TestVMLogic, u64, u64) -> Result<(), VMLogicError>,
+) -> Result<Vec<u8>, String> {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let input = logic.internal_mem_write(input);
+    match f(&mut logic, input.len, input.ptr) {
+        Ok(()) => Ok(logic.registers().get_for_free(0).unwrap().to_vec()),
+        Err(VMLogicError::HostError(HostError::Bls12381InvalidInput { msg })) => Err(msg),
+        Err(err) => panic!("unexpected error: `{}`", err),
+    }
+}
+
+fn p1_sum(input: &[u8]) -> Result<Vec<u8>, String> {
+    call(input, |logic, len, ptr| logic.bls12381_p1_sum(len, ptr, 0))
+}
+
+fn p2_sum(input: &[u8]) -> Result<Vec<u8>, String> {
+    call(input, |logic, len, ptr| logic.bls12381_p2_sum(len, ptr, 0))
+}
+
+fn g1_multiexp(input: &[u8]) -> Result<Vec<u8>, String> {
+    call(input, |logic, len, ptr| logic.bls12381_g1_multiexp(len, ptr, 0))
+}
+
+fn g2_multiexp(input: &[u8]) -> Result<Vec<u8>, String> {
+    call(input, |logic, len, ptr| logic.bls12381_g2_multiexp(len, ptr, 0))
+}
+
+fn map_fp_to_g1(input: &[u8]) -> Result<Vec<u8>, String> {
+    call(input, |logic, len, ptr| logic.bls12381_map_fp_to_g1(len, ptr, 0))
+}
+
+fn map_fp2_to_g2(input: &[u8]) -> Result<Vec<u8>, String> {
+    call(input, |logic, len, ptr| logic.bls12381_map_fp2_to_g2(len, ptr, 0))
+}
+
+fn pairing_check(input: &[u8]) -> Result<u64, String> {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let input = logic.internal_mem_write(input);
+    match logic.bls12381_pairing_check(input.len, input.ptr) {
+        Ok(res) => Ok(res),
+        Err(VMLogicError::HostError(HostError::Bls12381InvalidInput { msg })) => Err(msg),
+        Err(err) => panic!("unexpected error: `{}`", err),
+    }
+}
+
+#[track_caller]
+fn assert_err<T: std::fmt::Debug>(res: Result<T, String>, expected: &str) {
+    let err = res.unwrap_err();
+    assert!(err.contains(expected), "expected `{expected}` error, got {err}");
+}
+
+#[test]
+fn test_bls12381_p1_sum() {
+    let g = g1();
+    assert_eq!(p1_sum(&[]).unwrap(), infinity(G1_SIZE));
+    assert_eq!(p1_sum(&signed(0, &g)).unwrap(), g);
+
+    let double = p1_sum(&[signed(0, &g), signed(0, &g)].concat()).unwrap();
+    assert_eq!(double, g1_multiexp(&[g.clone(), scalar(2)].concat()).unwrap());
+
+    let zero = p1_sum(&[signed(0, &g), signed(1, &g)].concat()).unwrap();
+    assert_eq!(zero, infinity(G1_SIZE));
+
+    assert_err(p1_sum(b"XXXX"), "cannot be precisely split into chunks of size 97");
+    assert_err(p1_sum(&signed(2, &g)), "invalid bool");
+
+    let mut compressed = g.clone();
+    compressed[0] |= 0x80;
+    assert_err(p1_sum(&signed(0, &compressed)), "compressed g1 point");
+
+    let mut not_on_curve = g;
+    not_on_curve[G1_SIZE - 1] ^= 1;
+    assert_err(p1_sum(&signed(0, &not_on_curve)), "invalid g1");
+}
+
+#[test]
+fn test_bls12381_p2_sum() {
+    let g = g2();
+    assert_eq!(p2_sum(&[]).unwrap(), infinity(G2_SIZE));
+    assert_eq!(p2_sum(&signed(0, &g)).unwrap(), g);
+
+    let double = p2_sum(&[signed(0, &g), signed(0, &g)].concat()).unwrap();
+    assert_eq!(double, g2_multiexp(&[g.clone(), scalar(2)].concat()).unwrap());
+
+    let zero = p2_sum(&[signed(0, &g), signed(1, &g)].concat()).unwrap();
+    assert_eq!(zero, infinity(G2_SIZE));
+
+    assert_err(p2_sum(b"XXXX"), "cannot be precisely split into chunks of size 193");
+    assert_err(p2_sum(&signed(2, &g)), "invalid bool");
+
+    let mut not_on_curve = g;
+    not_on_curve[G2_SIZE - 1] ^= 1;
+    assert_err(p2_sum(&signed(0, &not_on_curve)), "invalid g2");
+}
+
+#[test]
+fn test_bls12381_multiexp() {
+    let g = g1();
+    assert_eq!(g1_multiexp(&[]).unwrap(), infinity(G1_SIZE));
+    assert_eq!(g1_multiexp(&[g.clone(), scalar(1)].concat()).unwrap(), g);
+    assert_eq!(g1_multiexp(&[g.clone(), scalar(0)].concat()).unwrap(), infinity(G1_SIZE));
+
+    let three = g1_multiexp(&[g.clone(), scalar(3)].concat()).unwrap();
+    let one_plus_two = g1_multiexp(&[g.clone(), scalar(1), g.clone(), scalar(2)].concat());
+    assert_eq!(three, one_plus_two.unwrap());
+    assert_err(g1_multiexp(&g), "cannot be precisely split into chunks of size 128");
+
+    let g = g2();
+    assert_eq!(g2_multiexp(&[g.clone(), scalar(1)].concat()).unwrap(), g);
+    assert_err(g2_multiexp(&g), "cannot be precisely split into chunks of size 224");
+}
+
+#[test]
+fn test_bls12381_map_to_curve() {
+    let fp = [0u8; 48];
+    let p1 = map_fp_to_g1(&[fp, fp].concat()).unwrap();
+    assert_eq!(p1.len(), 2 * G1_SIZE);
+    // The resulting points must be in the subgroup, which multiexp checks.
+    g1_multiexp(&[&p1[..G1_SIZE], &scalar(1)].concat()).unwrap();
+
+    let fp2 = [0u8; 96];
+    let p2 = map_fp2_to_g2(&fp2).unwrap();
+    assert_eq!(p2.len(), G2_SIZE);
+    g2_multiexp(&[&p2[..], &scalar(1)].concat()).unwrap();
+
+    let not_in_field = [0xffu8; 48];
+    assert_err(map_fp_to_g1(&not_in_field), "invalid fp");
+    assert_err(map_fp2_to_g2(&[not_in_field, [0u8; 48]].concat()), "invalid fp");
+}
+
+#[test]
+fn test_bls12381_pairing_check() {
+    let (p, q) = (g1(), g2());
+    let neg_p = p1_sum(&signed(1, &p)).unwrap();
+
+    assert_eq!(pairing_check(&[]).unwrap(), 1);
+    assert_eq!(pairing_check(&[p.clone(), q.clone()].concat()).unwrap(), 0);
+    assert_eq!(pairing_check(&[infinity(G1_SIZE), q.clone()].concat()).unwrap(), 1);
+    assert_eq!(pairing_check(&[p.clone(), q.clone(), neg_p, q.clone()].concat()).unwrap(), 1);
+
+    assert_err(pairing_check(&p), "cannot be precisely split into chunks of size 288");
+}
+
+#[test]
+fn test_bls12381_map_fp_to_g1_vectors() {
+    for (u, expected) in MAP_FP_TO_G1_VECTORS {
+        let points = map_fp_to_g1(&hex::decode(u).unwrap()).unwrap();
+        let summands: Vec<_> = points.chunks(G1_SIZE).map(|point| signed(0, point)).collect();
+        assert_eq!(p1_sum(&summands.concat()).unwrap(), hex::decode(expected).unwrap());
+    }
+}
+
+#[test]
+fn test_bls12381_map_fp2_to_g2_vectors() {
+    for (u, expected) in MAP_FP2_TO_G2_VECTORS {
+        let points = map_fp2_to_g2(&hex::decode(u).unwrap()).unwrap();
+        let summands: Vec<_> = points.chunks(G2_SIZE).map(|point| signed(0, point)).collect();
+        assert_eq!(p2_sum(&summands.concat()).unwrap(), hex::decode(expected).unwrap());
+    }
+}
+
+#[test]
+fn test_bls12381_multiexp_vectors() {
+    let double = hex::decode(G1_DOUBLE).unwrap();
+    assert_eq!(p1_sum(&[signed(0, &g1()), signed(0, &g1())].concat()).unwrap(), double);
+    assert_eq!(g1_multiexp(&[g1(), scalar(2)].concat()).unwrap(), double);
+    let (k, r_minus_one) = (hex::decode(SCALAR).unwrap(), hex::decode(R_MINUS_ONE).unwrap());
+    let p = hex::decode(MAP_FP_TO_G1_VECTORS[0].1).unwrap();
+    let input = [g1(), k.clone(), p, r_minus_one.clone()].concat();
+    assert_eq!(g1_multiexp(&input).unwrap(), hex::decode(G1_MULTIEXP).unwrap());
+
+    let double = hex::decode(G2_DOUBLE).unwrap();
+    assert_eq!(p2_sum(&[signed(0, &g2()), signed(0, &g2())].concat()).unwrap(), double);
+    assert_eq!(g2_multiexp(&[g2(), scalar(2)].concat()).unwrap(), double);
+    let q = hex::decode(MAP_FP2_TO_G2_VECTORS[0].1).unwrap();
+    let input = [g2(), k, q, r_minus_one].concat();
+    assert_eq!(g2_multiexp(&input).unwrap(), hex::decode(G2_MULTIEXP).unwrap());
+}
+
+#[test]
+fn test_bls12381_pairing_check_vectors() {
+    let p = hex::decode(G1_DOUBLE).unwrap();
+    let q = hex::decode(G2_TRIPLE).unwrap();
+    let minus_six_p = hex::decode(G1_MINUS_SIX).unwrap();
+    // e(2 * G1, 3 * G2) = e(6 * G1, G2)
+    let input = [p.clone(), q.clone(), minus_six_p.clone(), g2()].concat();
+    assert_eq!(pairing_check(&input).unwrap(), 1);
+    // e(2 * G1, 2 * G2) != e(6 * G1, G2)
+    let input = [p, hex::decode(G2_DOUBLE).unwrap(), minus_six_p, g2()].concat();
+    assert_eq!(pairing_check(&input).unwrap(), 0);
+}
+
+#[test]
+fn test_bls12381_subgroup_check() {
+    let p = hex::decode(G1_NOT_IN_SUBGROUP).unwrap();
+    let q = hex::decode(G2_NOT_IN_SUBGROUP).unwrap();
+    // Sums only check that the points are on the curve.
+    assert_eq!(p1_sum(&signed(0, &p)).unwrap(), p);
+    assert_eq!(p2_sum(&signed(0, &q)).unwrap(), q);
+
+    assert_err(g1_multiexp(&[p.clone(), scalar(1)].concat()), "g1 point is not in the subgroup");
+    assert_err(g2_multiexp(&[q.clone(), scalar(1)].concat()), "g2 point is not in the subgroup");
+    assert_err(pairing_check(&[p, g2()].concat()), "g1 point is not in the subgroup");
+    assert_err(pairing_check(&[g1(), q].concat()), "g2 point is not in the subgroup");
+}
@@ -2,9 +2,11 @@ use crate::logic::mocks::mock_external::MockedExternal;
 use crate::logic::tests::helpers::*;
 use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
 use crate::logic::types::PromiseResult;
+use crate::logic::HostError;
 
 use serde_json;
 use unc_crypto::PublicKey;
+use unc_primitives_core::hash::hash;
 
 fn vm_receipts<'a>(ext: &'a MockedExternal) -> Vec<impl serde::Serialize + 'a> {
     ext.action_log.clone()
@@ -437,6 +439,196 @@ fn test_promise_batch_action_pledge() {
     .assert_eq(&serde_json::to_string_pretty(&vm_receipts(&logic_builder.ext)).unwrap());
 }
 
+#[test]
+fn test_promise_batch_action_rsa2048() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let index = promise_batch_create(&mut logic, "rick.test").expect("should create a promise");
+    let key = borsh::to_vec(
+        &"ed25519:5do5nkAEVhL8iteDvXNgxi4pWK78Y7DDadX11ArFNyrf".parse::<PublicKey>().unwrap(),
+    )
+    .unwrap();
+    let challenge_key = borsh::to_vec(
+        &"ed25519:6DSjZ8mvsRZDvFqFxo8tCKePG96omXW7eVYVSySmDk8e".parse::<PublicKey>().unwrap(),
+    )
+    .unwrap();
+
+    let key = logic.internal_mem_write(&key);
+    let challenge_key = logic.internal_mem_write(&challenge_key);
+    let args = logic.internal_mem_write(b"args");
+
+    logic
+        .promise_batch_action_register_rsa2048_keys(123, key.len, key.ptr, 0, args.len, args.ptr)
+        .expect_err("shouldn't accept not existent promise index");
+    logic
+        .promise_batch_action_register_rsa2048_keys(
+            index, key.len, key.ptr, 256, args.len, args.ptr,
+        )
+        .expect_err("shouldn't accept operation type not fitting into u8");
+    logic
+        .promise_batch_action_register_rsa2048_keys(index, 0, key.ptr, 0, args.len, args.ptr)
+        .expect_err("shouldn't accept invalid public key");
+
+    logic
+        .promise_batch_action_register_rsa2048_keys(index, key.len, key.ptr, 1, args.len, args.ptr)
+        .expect("should add an action to register rsa2048 keys");
+    logic
+        .promise_batch_action_create_rsa2048_challenge(
+            index,
+            key.len,
+            key.ptr,
+            challenge_key.len,
+            challenge_key.ptr,
+            args.len,
+            args.ptr,
+        )
+        .expect("should add an action to create rsa2048 challenge");
+    expect_test::expect![[r#"
+        [
+          {
+            "CreateReceipt": {
+              "receipt_indices": [],
+              "receiver_id": "rick.test"
+            }
+          },
+          {
+            "RegisterRsa2048Keys": {
+              "receipt_index": 0,
+              "public_key": "ed25519:5do5nkAEVhL8iteDvXNgxi4pWK78Y7DDadX11ArFNyrf",
+              "operation_type": 1,
+              "args": [
+                97,
+                114,
+                103,
+                115
+              ]
+            }
+          },
+          {
+            "CreateRsa2048Challenge": {
+              "receipt_index": 0,
+              "public_key": "ed25519:5do5nkAEVhL8iteDvXNgxi4pWK78Y7DDadX11ArFNyrf",
+              "challenge_key": "ed25519:6DSjZ8mvsRZDvFqFxo8tCKePG96omXW7eVYVSySmDk8e",
+              "args": [
+                97,
+                114,
+                103,
+                115
+              ]
+            }
+          }
+        ]"#]]
+    .assert_eq(&serde_json::to_string_pretty(&vm_receipts(&logic_builder.ext)).unwrap());
+}
+
+#[test]
+fn test_promise_yield_create_and_resume() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let method_name = logic.internal_mem_write(b"callback");
+    let args = logic.internal_mem_write(b"args");
+
+    logic
+        .promise_yield_create(0, method_name.ptr, args.len, args.ptr, 0, 1, 0)
+        .expect_err("shouldn't accept empty method name");
+    let index = logic
+        .promise_yield_create(method_name.len, method_name.ptr, args.len, args.ptr, 0, 1, 0)
+        .expect("should create a yielded promise");
+    assert_eq!(index, 0);
+    let data_id = hash(&0u64.to_le_bytes());
+    logic.assert_read_register(data_id.as_bytes(), 0);
+
+    let payload = logic.internal_mem_write(b"payload");
+    let short_data_id = logic.internal_mem_write(&[0; 31]);
+    assert_eq!(
+        logic.promise_yield_resume(short_data_id.len, short_data_id.ptr, payload.len, payload.ptr),
+        Err(HostError::DataIdMalformed.into())
+    );
+    let long_payload = logic.internal_mem_write(&[0; 1025]);
+    assert_eq!(
+        logic.promise_yield_resume(u64::MAX, 0, long_payload.len, long_payload.ptr),
+        Err(HostError::YieldPayloadLength { length: 1025, limit: 1024 }.into())
+    );
+    // The length is checked before the payload is read.
+    assert_eq!(
+        logic.promise_yield_resume(u64::MAX, 0, 1025, u64::MAX - 1024),
+        Err(HostError::YieldPayloadLength { length: 1025, limit: 1024 }.into())
+    );
+    let unknown_data_id = logic.internal_mem_write(hash(b"unknown").as_bytes());
+    assert_eq!(
+        logic.promise_yield_resume(
+            unknown_data_id.len,
+            unknown_data_id.ptr,
+            payload.len,
+            payload.ptr
+        ),
+        Ok(0)
+    );
+    assert_eq!(logic.promise_yield_resume(u64::MAX, 0, payload.len, payload.ptr), Ok(1));
+    expect_test::expect![[r#"
+        [
+          {
+            "YieldCreate": {
+              "data_id": "CoRutESHXR94goeNsP5Za7RKyKrK8AYLQa4PwvT2934w",
+              "receiver_id": "alice.unc"
+            }
+          },
+          {
+            "FunctionCallWeight": {
+              "receipt_index": 0,
+              "method_name": [
+                99,
+                97,
+                108,
+                108,
+                98,
+                97,
+                99,
+                107
+              ],
+              "args": [
+                97,
+                114,
+                103,
+                115
+              ],
+              "attached_deposit": 0,
+              "prepaid_gas": 0,
+              "gas_weight": 1
+            }
+          },
+          {
+            "YieldResume": {
+              "data_id": "CzjEZ6Z6DjDqRFkHp9mpBmJvc2KH9bY3dkgAtrAKFscP",
+              "data": [
+                112,
+                97,
+                121,
+                108,
+                111,
+                97,
+                100
+              ]
+            }
+          },
+          {
+            "YieldResume": {
+              "data_id": "CoRutESHXR94goeNsP5Za7RKyKrK8AYLQa4PwvT2934w",
+              "data": [
+                112,
+                97,
+                121,
+                108,
+                111,
+                97,
+                100
+              ]
+            }
+          }
+        ]"#]]
+    .assert_eq(&serde_json::to_string_pretty(&vm_receipts(&logic_builder.ext)).unwrap());
+}
+
 #[test]
 fn test_promise_batch_action_add_key_with_function_call() {
     let mut logic_builder = VMLogicBuilder::default();
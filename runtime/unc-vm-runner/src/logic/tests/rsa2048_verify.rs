@@ -0,0 +1,301 @@
+use crate::logic::tests::helpers::*;
+use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
+use crate::logic::HostError;
+use crate::logic::VMLogicError;
+use crate::map;
+use std::collections::HashMap;
+use unc_parameters::ExtCosts;
+
+// The vectors below were generated with an independent implementation
+// (pyca/cryptography): a 2048-bit key with the public exponent 65537, encoded
+// as a DER SubjectPublicKeyInfo, and an RSASSA-PKCS1-v1_5 signature with
+// SHA-256 over `MESSAGE`.
+
+/// Public key which produced `SIGNATURE`.
+const PUBLIC_KEY: &str = concat!(
+    "30820122300d06092a864886f70d01010105000382010f003082010a0282010100a3e3f7cf64cc25aed9966f",
+    "f7c2b4dd146af3cea1d79e80c59e1447294d118d648877ff3f19f28ba41f64e1ddea705091f34ec85229b56e",
+    "11a2e71e62e8926d4b97dbcde14657234d9d572c0c6068562c62498418d8666b938a79a3f2ed779a84e066cd",
+    "5cdfb29bb4061290001f1741e5dd791a43952e42191b80e27025ee80167d4a6e5db07dfce05d1541b4510b5c",
+    "a60970624f7a7237baa0d43d5641da247dbfef0e3c7902b034eccc4836f5f7e5fbb1bf1f3d3b8d7018b89152",
+    "3a38b17728f4c815333bc92766bf591cf1f3bfb3dbb0e206bb2d805457d14ae7557128f769526a41b8afca53",
+    "3519060dbc27a2f46c47460da248a652d60527a34ebc5b0e070203010001",
+);
+
+/// Unrelated 2048-bit public key.
+const OTHER_PUBLIC_KEY: &str = concat!(
+    "30820122300d06092a864886f70d01010105000382010f003082010a0282010100bc3ad29751f1812427af29",
+    "118283927b0f38cf3629003543114fd9b277e75bb0d0a728005f734460fe24ccb59b524a08cbf3e05aae99df",
+    "97e99b24ca8218e115cd60857bdcdf1dda40c744814f0b00c0630476f150d4c766d6863ba2673d848786af79",
+    "d6cf6f5c2b83267d98f6014480403101f3de2db219cb088564262b94ecf8c3de03238136a00a1f261abe6349",
+    "fb2998b4bacd6b28bc2dbaa707c998e6f6271703fb397362601658567161f6847bf85cf10d845ccbeeae5a7e",
+    "5245a9a4f9ddded1333beda1a2bdc95504957b46a55ed80f862bf82f28e6269ddaa9eef44caf9f099bd41d94",
+    "47c2dd2bea7ff8eaa1c3ea27785fc6c7fe1ce8504ec754d7870203010001",
+);
+
+/// Signature of `MESSAGE` by the key of `PUBLIC_KEY`.
+const SIGNATURE: &str = concat!(
+    "8b9da6710d4a512b8ad6b96f174de3f233a33a856691356413af3de7b480baa6e88c9a4115904db53ca0a9da",
+    "6ce26855ec039f63e7d60fd03393e70fd2319c4c40348ef227e69f966968d6d257cca83d4598e30d135a8afe",
+    "8f0c6e444a6c7d148002177d90aab4352fd23841716324baea4c2c5e58c0db36c449f47631550619a3046f38",
+    "ed0dab56b21c86f42805efdc3cf983280a5a76d04e6c3d85217d99036b182e8f422e81d26a9a86c5595a6351",
+    "2f09226b85107f6aa484f0dba33c76d9362bed35998ed1d2123b4cc3b7b809d45fff846d52869ca81e4fe411",
+    "82dbe497aa1d4ef78ff07b03bf54979be6ea0b035ced58d9f47c6436e1fe660e8b93d483",
+);
+
+const MESSAGE: &[u8] = b"chip telemetry: temperature=42C";
+
+fn public_key() -> Vec<u8> {
+    hex::decode(PUBLIC_KEY).unwrap()
+}
+
+fn signature() -> Vec<u8> {
+    hex::decode(SIGNATURE).unwrap()
+}
+
+#[track_caller]
+fn check_rsa2048_verify(
+    signature_len: u64,
+    signature: &[u8],
+    message_len: u64,
+    message: &[u8],
+    public_key_len: u64,
+    public_key: &[u8],
+    want: Result<u64, HostError>,
+    want_costs: HashMap<ExtCosts, u64>,
+) {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+
+    let signature_ptr = if signature_len == u64::MAX {
+        logic.wrapped_internal_write_register(1, &signature).unwrap();
+        1
+    } else {
+        logic.internal_mem_write(signature).ptr
+    };
+
+    let message_ptr = if message_len == u64::MAX {
+        logic.wrapped_internal_write_register(2, &message).unwrap();
+        2
+    } else {
+        logic.internal_mem_write(message).ptr
+    };
+
+    let public_key_ptr = if public_key_len == u64::MAX {
+        logic.wrapped_internal_write_register(3, &public_key).unwrap();
+        3
+    } else {
+        logic.internal_mem_write(public_key).ptr
+    };
+
+    let result = logic.rsa2048_pkcs1v15_verify(
+        signature_len,
+        signature_ptr,
+        message_len,
+        message_ptr,
+        public_key_len,
+        public_key_ptr,
+    );
+
+    let want = want.map_err(VMLogicError::HostError);
+    assert_eq!(want, result);
+    assert_costs(want_costs);
+}
+
+#[test]
+fn test_rsa2048_verify_conformance() {
+    let signature = signature();
+    let public_key = public_key();
+
+    // Valid signature.
+    check_rsa2048_verify(
+        256,
+        &signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        294,
+        &public_key,
+        Ok(1),
+        map! {
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::read_memory_byte: 581,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+
+    // Tampered message.
+    let mut message = MESSAGE.to_vec();
+    message[0] ^= 1;
+    check_rsa2048_verify(
+        256,
+        &signature,
+        message.len() as u64,
+        &message,
+        294,
+        &public_key,
+        Ok(0),
+        map! {
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::read_memory_byte: 581,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+
+    // Tampered signature.
+    let mut bad_signature = signature.clone();
+    bad_signature[255] ^= 1;
+    check_rsa2048_verify(
+        256,
+        &bad_signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        294,
+        &public_key,
+        Ok(0),
+        map! {
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::read_memory_byte: 581,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+
+    // Signature made by another key.
+    check_rsa2048_verify(
+        256,
+        &signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        294,
+        &hex::decode(OTHER_PUBLIC_KEY).unwrap(),
+        Ok(0),
+        map! {
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::read_memory_byte: 581,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+
+    // Public key which is not a valid DER encoding.
+    let mut bad_public_key = public_key.clone();
+    bad_public_key[0] = 0;
+    check_rsa2048_verify(
+        256,
+        &signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        294,
+        &bad_public_key,
+        Ok(0),
+        map! {
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::read_memory_byte: 581,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+}
+
+#[test]
+fn test_rsa2048_verify_invalid_input() {
+    let signature = signature();
+    let public_key = public_key();
+
+    check_rsa2048_verify(
+        255,
+        &signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        294,
+        &public_key,
+        Err(HostError::Rsa2048VerifyInvalidInput { msg: "invalid signature length".to_string() }),
+        map! {
+            ExtCosts::read_memory_base: 1,
+            ExtCosts::read_memory_byte: 255,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+        },
+    );
+    check_rsa2048_verify(
+        256,
+        &signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        293,
+        &public_key,
+        Err(HostError::Rsa2048VerifyInvalidInput { msg: "invalid public key length".to_string() }),
+        map! {
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::read_memory_byte: 580,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+}
+
+// tests for data being read from registers
+#[test]
+fn test_rsa2048_verify_check_registers() {
+    let signature = signature();
+    let public_key = public_key();
+
+    check_rsa2048_verify(
+        u64::MAX,
+        &signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        294,
+        &public_key,
+        Ok(1),
+        map! {
+            ExtCosts::write_register_base: 1,
+            ExtCosts::write_register_byte: 256,
+
+            ExtCosts::read_register_base: 1,
+            ExtCosts::read_register_byte: 256,
+            ExtCosts::read_memory_base: 2,
+            ExtCosts::read_memory_byte: 325,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+    check_rsa2048_verify(
+        256,
+        &signature,
+        MESSAGE.len() as u64,
+        MESSAGE,
+        u64::MAX,
+        &public_key,
+        Ok(1),
+        map! {
+            ExtCosts::write_register_base: 1,
+            ExtCosts::write_register_byte: 294,
+
+            ExtCosts::read_register_base: 1,
+            ExtCosts::read_register_byte: 294,
+            ExtCosts::read_memory_base: 2,
+            ExtCosts::read_memory_byte: 287,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_byte: 31,
+        },
+    );
+    check_rsa2048_verify(
+        u64::MAX,
+        &[0],
+        MESSAGE.len() as u64,
+        MESSAGE,
+        294,
+        &public_key,
+        Err(HostError::Rsa2048VerifyInvalidInput { msg: "invalid signature length".to_string() }),
+        map! {
+            ExtCosts::write_register_base: 1,
+            ExtCosts::write_register_byte: 1,
+
+            ExtCosts::read_register_base: 1,
+            ExtCosts::read_register_byte: 1,
+            ExtCosts::rsa2048_pkcs1v15_verify_base: 1,
+        },
+    );
+}
@@ -31,6 +31,10 @@ fn test_prohibited_view_methods() {
     test_prohibited!(promise_batch_action_add_key_with_function_call, 0, 0, 0, 0, 0, 0, 0, 0, 0);
     test_prohibited!(promise_batch_action_delete_key, 0, 0, 0);
     test_prohibited!(promise_batch_action_delete_account, 0, 0, 0);
+    test_prohibited!(promise_batch_action_register_rsa2048_keys, 0, 0, 0, 0, 0, 0);
+    test_prohibited!(promise_batch_action_create_rsa2048_challenge, 0, 0, 0, 0, 0, 0, 0);
+    test_prohibited!(promise_yield_create, 0, 0, 0, 0, 0, 0, 0);
+    test_prohibited!(promise_yield_resume, 0, 0, 0, 0);
     test_prohibited!(promise_results_count);
     test_prohibited!(promise_result, 0, 0);
     test_prohibited!(promise_return, 0);
@@ -0,0 +1,146 @@
+//! Opt-in tracing of contract execution.
+//!
+//! While [`with_tracing`] runs, every contract call made on the current thread
+//! records the host functions it invokes, the storage it touches and the gas
+//! burnt and used after each of those steps. The trace of a call is returned
+//! in [`VMOutcome::trace`](super::VMOutcome::trace) and the runtime, which
+//! knows what receipt the call belongs to, hands it back with [`record`].
+//!
+//! Tracing never charges gas and never changes the result of a call, so a
+//! traced execution is identical to an untraced one. This makes it safe to
+//! replay historical receipts with tracing enabled.
+//!
+//! Gas charged for wasm instructions is only traced per stretch of wasm code
+//! executed between two host calls, see [`TraceEvent::WasmExecution`], and not
+//! per basic block or per instruction. The compiled code charges the gas of
+//! each basic block inline, without calling into the host, and tracing it
+//! would need different machine code for traced calls, which would then
+//! differ from the executions being debugged.
+
+use serde_with::base64::Base64;
+use serde_with::serde_as;
+use std::cell::RefCell;
+use unc_primitives_core::hash::CryptoHash;
+use unc_primitives_core::types::{AccountId, Gas};
+
+thread_local! {
+    static TRACES: RefCell<Option<Vec<FunctionCallTrace>>> = RefCell::new(None);
+}
+
+/// Runs `f` with execution tracing enabled on the current thread and returns
+/// the traces recorded while it ran, in execution order.
+pub fn with_tracing<R>(f: impl FnOnce() -> R) -> (R, Vec<FunctionCallTrace>) {
+    struct ResetGuard(Option<Vec<FunctionCallTrace>>);
+
+    impl Drop for ResetGuard {
+        fn drop(&mut self) {
+            TRACES.with(|traces| *traces.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _guard = ResetGuard(TRACES.with(|traces| traces.replace(Some(Vec::new()))));
+    let res = f();
+    let traces = TRACES.with(|traces| traces.borrow_mut().take()).unwrap_or_default();
+    (res, traces)
+}
+
+pub(crate) fn is_enabled() -> bool {
+    TRACES.with(|traces| traces.borrow().is_some())
+}
+
+/// Records the trace of a finished function call, if tracing is enabled.
+pub fn record(trace: FunctionCallTrace) {
+    TRACES.with(|traces| {
+        if let Some(traces) = traces.borrow_mut().as_mut() {
+            traces.push(trace);
+        }
+    });
+}
+
+/// Trace of a function call made while applying a receipt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCallTrace {
+    pub receipt_id: CryptoHash,
+    pub receiver_id: AccountId,
+    pub method_name: String,
+    pub trace: ExecutionTrace,
+}
+
+/// Steps taken by a single contract call, in execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExecutionTrace {
+    pub steps: Vec<TraceStep>,
+    /// Gas burnt once the last completed step has completed. Storage steps
+    /// are pushed while their host call runs, so it's not always the gas of
+    /// the last step.
+    #[serde(skip)]
+    last_burnt_gas: Gas,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TraceStep {
+    pub event: TraceEvent,
+    /// Gas burnt by the call once the step has completed.
+    pub burnt_gas: Gas,
+    /// Gas used by the call once the step has completed.
+    pub used_gas: Gas,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TraceEvent {
+    /// Loading of the contract code, charged before it starts executing.
+    ContractLoading,
+    /// Execution of wasm code since the previous step. The gas of the step
+    /// includes the gas charged for all the basic blocks executed in between.
+    WasmExecution,
+    /// A call to a host function with its raw wasm arguments. Storage events
+    /// caused by the call follow it in the trace.
+    HostCall { name: String, args: Vec<u64> },
+    StorageRead {
+        #[serde_as(as = "Base64")]
+        key: Vec<u8>,
+        #[serde_as(as = "Option<Base64>")]
+        value: Option<Vec<u8>>,
+    },
+    StorageWrite {
+        #[serde_as(as = "Base64")]
+        key: Vec<u8>,
+        #[serde_as(as = "Base64")]
+        value: Vec<u8>,
+        #[serde_as(as = "Option<Base64>")]
+        evicted: Option<Vec<u8>>,
+    },
+    StorageRemove {
+        #[serde_as(as = "Base64")]
+        key: Vec<u8>,
+        #[serde_as(as = "Option<Base64>")]
+        removed: Option<Vec<u8>>,
+    },
+}
+
+impl ExecutionTrace {
+    /// Appends a step and returns its index.
+    pub(crate) fn push(&mut self, event: TraceEvent, burnt_gas: Gas, used_gas: Gas) -> usize {
+        self.steps.push(TraceStep { event, burnt_gas, used_gas });
+        self.last_burnt_gas = self.last_burnt_gas.max(burnt_gas);
+        self.steps.len() - 1
+    }
+
+    /// Appends a `WasmExecution` step if gas has been burnt since the last
+    /// step.
+    pub(crate) fn push_wasm_execution(&mut self, burnt_gas: Gas, used_gas: Gas) {
+        if burnt_gas > self.last_burnt_gas {
+            self.push(TraceEvent::WasmExecution, burnt_gas, used_gas);
+        }
+    }
+
+    /// Updates the gas of a previously pushed step once it has completed.
+    pub(crate) fn set_gas(&mut self, step: usize, burnt_gas: Gas, used_gas: Gas) {
+        if let Some(step) = self.steps.get_mut(step) {
+            step.burnt_gas = burnt_gas;
+            step.used_gas = used_gas;
+            self.last_burnt_gas = self.last_burnt_gas.max(burnt_gas);
+        }
+    }
+}
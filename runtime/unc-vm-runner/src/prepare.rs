@@ -30,7 +30,7 @@ pub fn prepare_contract(
         (kind != VMKind::NearVm) || (prepare == crate::logic::ContractPrepareVersion::V2),
         "NearVM only works with contract prepare version V2",
     );
-    let features = crate::features::WasmFeatures::from(prepare);
+    let features = crate::features::WasmFeatures::from(config);
     match prepare {
         crate::logic::ContractPrepareVersion::V0 => {
             // NB: v1 here is not a bug, we are reusing the code.
@@ -143,6 +143,32 @@ mod tests {
         })
     }
 
+    #[test]
+    fn table_limits() {
+        let mut config = test_vm_config();
+        config.wasm_feature_expansion = true;
+        config.limit_config.max_tables_per_contract = Some(1);
+        config.limit_config.max_elements_per_contract_table = Some(10);
+        with_vm_variants(&config, |kind| {
+            let r = parse_and_prepare_wat(&config, kind, r#"(module (table 10 funcref))"#);
+            assert_matches!(r, Ok(_));
+
+            let r = parse_and_prepare_wat(&config, kind, r#"(module (table 11 funcref))"#);
+            assert_matches!(r, Err(PrepareError::TooManyTableElements));
+
+            // A larger declared maximum is accepted, the table just can't grow past the limit.
+            let r = parse_and_prepare_wat(&config, kind, r#"(module (table 1 100 funcref))"#);
+            assert_matches!(r, Ok(_));
+
+            let r = parse_and_prepare_wat(
+                &config,
+                kind,
+                r#"(module (table 1 funcref) (table 1 externref))"#,
+            );
+            assert_matches!(r, Err(PrepareError::TooManyTables));
+        })
+    }
+
     #[test]
     fn imports() {
         let config = test_vm_config();
@@ -80,6 +80,7 @@ impl<'a> PrepareContext<'a> {
                 }
                 wp::Payload::TableSection(reader) => {
                     self.ensure_import_section();
+                    self.validate_tables(&reader)?;
                     self.validator
                         .table_section(&reader)
                         .map_err(|_| PrepareError::Deserialization)?;
@@ -199,6 +200,27 @@ impl<'a> PrepareContext<'a> {
         Ok(std::mem::replace(&mut self.output_code, Vec::new()))
     }
 
+    /// Applies the table limits. Tables can't be imported, so the table section declares all
+    /// tables of the module.
+    fn validate_tables(&self, reader: &wp::TableSectionReader) -> Result<(), PrepareError> {
+        let limits = &self.config.limit_config;
+        if let Some(max_tables) = limits.max_tables_per_contract {
+            if reader.count() > max_tables {
+                return Err(PrepareError::TooManyTables);
+            }
+        }
+        if let Some(max_elements) = limits.max_elements_per_contract_table {
+            for table in reader.clone() {
+                let table = table.map_err(|_| PrepareError::Deserialization)?;
+                if usize::try_from(table.ty.initial).map_or(true, |initial| initial > max_elements)
+                {
+                    return Err(PrepareError::TooManyTableElements);
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn transform_import_section(
         &mut self,
         reader: &wp::ImportSectionReader,
@@ -370,7 +392,7 @@ mod test {
         let mut config = test_vm_config();
         let prepare_version = ContractPrepareVersion::V2;
         config.limit_config.contract_prepare_version = prepare_version;
-        let features = crate::features::WasmFeatures::from(prepare_version);
+        let features = crate::features::WasmFeatures::from(&config);
         bolero::check!().for_each(|input: &[u8]| {
             // DO NOT use ArbitraryModule. We do want modules that may be invalid here, if they pass our validation step!
             if let Ok(_) = crate::prepare::prepare_v1::validate_contract(input, features, &config) {
@@ -397,7 +419,7 @@ mod test {
         let mut config = test_vm_config();
         let prepare_version = ContractPrepareVersion::V2;
         config.limit_config.contract_prepare_version = prepare_version;
-        let features = crate::features::WasmFeatures::from(prepare_version);
+        let features = crate::features::WasmFeatures::from(&config);
 
         bolero::check!().for_each(|input: &[u8]| {
             // DO NOT use ArbitraryModule. We do want modules that may be invalid here, if they pass our validation step!
@@ -10,10 +10,11 @@ use crate::runner::VMKindExt;
 use crate::runner::VMResult;
 use crate::wasmer2_runner::Wasmer2VM;
 use crate::ContractCode;
-use crate::{prepare, MockCompiledContractCache};
+use crate::{prepare, ContractCacheBundle, ContractCacheBundleError, MockCompiledContractCache};
 use assert_matches::assert_matches;
 use std::io;
 use std::sync::atomic::{AtomicBool, Ordering};
+use unc_crypto::{InMemorySigner, KeyType};
 use unc_parameters::vm::VMKind;
 use unc_parameters::RuntimeFeesConfig;
 use unc_primitives_core::hash::CryptoHash;
@@ -75,6 +76,37 @@ fn test_does_not_cache_io_error() {
     })
 }
 
+#[test]
+fn test_contract_cache_bundle() {
+    let key = CryptoHash::hash_bytes(b"cached");
+    let compiled = CompiledContract::Code(vec![1, 2, 3]);
+    let source = MockCompiledContractCache::default();
+    source.put(&key, compiled.clone()).unwrap();
+    let bundle =
+        ContractCacheBundle::export(&source, [key, CryptoHash::hash_bytes(b"missing")]).unwrap();
+    assert_eq!(bundle.entries, vec![(key, compiled.clone())]);
+
+    let signer = InMemorySigner::from_seed("node".parse().unwrap(), KeyType::ED25519, "node");
+    let other = InMemorySigner::from_seed("other".parse().unwrap(), KeyType::ED25519, "other");
+    let signed = bundle.sign(&signer);
+    assert_matches!(
+        signed.clone().verify(&[other.public_key.clone()]),
+        Err(ContractCacheBundleError::UntrustedSigner(_))
+    );
+    let mut tampered = signed.clone();
+    tampered.bundle.entries[0].1 = CompiledContract::Code(vec![4, 5, 6]);
+    assert_matches!(
+        tampered.verify(&[signer.public_key.clone()]),
+        Err(ContractCacheBundleError::InvalidSignature)
+    );
+
+    let bundle = signed.verify(&[signer.public_key.clone()]).unwrap();
+    let target = MockCompiledContractCache::default();
+    assert_eq!(bundle.clone().import(&target).unwrap(), 1);
+    assert_eq!(target.get(&key).unwrap(), Some(compiled));
+    assert_eq!(bundle.import(&target).unwrap(), 0);
+}
+
 fn make_cached_contract_call_vm(
     config: &Config,
     cache: &dyn CompiledContractCache,
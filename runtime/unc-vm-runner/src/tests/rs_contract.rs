@@ -1,5 +1,6 @@
 use crate::logic::errors::{FunctionCallError, HostError, WasmTrap};
 use crate::logic::mocks::mock_external::{MockAction, MockedExternal};
+use crate::logic::trace::TraceEvent;
 use crate::logic::types::ReturnData;
 use crate::logic::Config;
 use crate::runner::VMKindExt;
@@ -82,6 +83,55 @@ pub fn test_read_write() {
     });
 }
 
+#[test]
+pub fn test_read_write_trace() {
+    let config = test_vm_config();
+    with_vm_variants(&config, |vm_kind: VMKind| {
+        let code = test_contract(vm_kind);
+        let mut fake_external = MockedExternal::new();
+        let fees = RuntimeFeesConfig::test();
+        let runtime = vm_kind.runtime(config.clone()).expect("runtime has not been compiled");
+
+        let context = create_context(encode(&[10u64, 20u64]));
+        let outcome = runtime
+            .run(&code, "write_key_value", &mut fake_external, context, &fees, &[], None)
+            .unwrap();
+        assert_eq!(outcome.trace, None);
+
+        let context = create_context(encode(&[10u64, 20u64]));
+        let (outcome, _) = crate::logic::with_tracing(|| {
+            runtime.run(&code, "write_key_value", &mut fake_external, context, &fees, &[], None)
+        });
+        let outcome = outcome.unwrap();
+        let steps = outcome.trace.expect("tracing was enabled").steps;
+        let write = steps
+            .iter()
+            .find(|step| matches!(step.event, TraceEvent::StorageWrite { .. }))
+            .expect("storage write was not traced");
+        assert_eq!(
+            write.event,
+            TraceEvent::StorageWrite {
+                key: encode(&[10]),
+                value: encode(&[20]),
+                evicted: Some(encode(&[20])),
+            }
+        );
+        assert_eq!(steps[0].event, TraceEvent::ContractLoading);
+        assert!(steps.iter().any(|step| step.event == TraceEvent::WasmExecution));
+        assert!(steps.iter().all(|step| step.burnt_gas <= outcome.burnt_gas));
+        if vm_kind == VMKind::NearVm {
+            let host_calls: Vec<_> = steps
+                .iter()
+                .filter_map(|step| match &step.event {
+                    TraceEvent::HostCall { name, .. } => Some(name.as_str()),
+                    _ => None,
+                })
+                .collect();
+            assert!(host_calls.contains(&"storage_write"), "{host_calls:?}");
+        }
+    });
+}
+
 macro_rules! def_test_ext {
     ($name:ident, $method:expr, $expected:expr, $input:expr, $validator:expr) => {
         #[test]
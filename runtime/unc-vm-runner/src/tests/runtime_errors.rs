@@ -497,6 +497,95 @@ fn test_memory_grow() {
         "#]]);
 }
 
+/// `memory.fill` and the other bulk memory operations are charged a regular operation for every
+/// started 8 bytes they touch. Only unc-vm meters them by size.
+#[test]
+#[cfg(all(feature = "unc_vm", target_arch = "x86_64"))]
+fn test_bulk_memory_gas() {
+    use crate::logic::mocks::mock_external::MockedExternal;
+    use crate::runner::VMKindExt;
+    use crate::ContractCode;
+    use unc_parameters::vm::VMKind;
+    use unc_parameters::RuntimeFeesConfig;
+
+    let mut config = super::test_vm_config();
+    config.wasm_feature_expansion = true;
+    let regular_op_cost = u64::from(config.regular_op_cost);
+    // All the lengths below encode into the same number of bytes, so that the contracts have the
+    // same size and loading cost.
+    let fill_gas = |len: u32| {
+        let wasm = wat::parse_str(format!(
+            r#"(module
+                (memory 1)
+                (func (export "main")
+                  (memory.fill (i32.const 0) (i32.const 42) (i32.const {len}))))"#
+        ))
+        .unwrap();
+        let runtime =
+            VMKind::NearVm.runtime(config.clone()).expect("runtime has not been compiled");
+        let outcome = runtime
+            .run(
+                &ContractCode::new(wasm, None),
+                "main",
+                &mut MockedExternal::new(),
+                super::create_context(vec![]),
+                &RuntimeFeesConfig::test(),
+                &[],
+                None,
+            )
+            .expect("execution failed");
+        assert!(outcome.aborted.is_none(), "{:?}", outcome.aborted);
+        outcome.burnt_gas
+    };
+    let base = fill_gas(16384);
+    assert_eq!(fill_gas(16385) - base, regular_op_cost);
+    assert_eq!(fill_gas(16392) - base, regular_op_cost);
+    assert_eq!(fill_gas(65536) - base, 6144 * regular_op_cost);
+}
+
+/// `table.grow` fails once the table would exceed `max_elements_per_contract_table`, even if the
+/// table declares a larger maximum.
+#[test]
+#[cfg(all(feature = "unc_vm", target_arch = "x86_64"))]
+fn test_table_grow_limit() {
+    use crate::logic::mocks::mock_external::MockedExternal;
+    use crate::runner::VMKindExt;
+    use crate::ContractCode;
+    use unc_parameters::vm::VMKind;
+    use unc_parameters::RuntimeFeesConfig;
+
+    let mut config = super::test_vm_config();
+    config.wasm_feature_expansion = true;
+    config.limit_config.max_elements_per_contract_table = Some(10);
+    // Traps unless growing the table by `delta` elements returns `expected`.
+    let grow = |delta: u32, expected: i32| {
+        let wasm = wat::parse_str(format!(
+            r#"(module
+                (table 2 100 funcref)
+                (func (export "main")
+                  (if (i32.ne (table.grow (ref.null func) (i32.const {delta})) (i32.const {expected}))
+                    (then unreachable))))"#
+        ))
+        .unwrap();
+        let runtime =
+            VMKind::NearVm.runtime(config.clone()).expect("runtime has not been compiled");
+        let outcome = runtime
+            .run(
+                &ContractCode::new(wasm, None),
+                "main",
+                &mut MockedExternal::new(),
+                super::create_context(vec![]),
+                &RuntimeFeesConfig::test(),
+                &[],
+                None,
+            )
+            .expect("execution failed");
+        assert!(outcome.aborted.is_none(), "{:?}", outcome.aborted);
+    };
+    grow(8, 2);
+    grow(9, -1);
+}
+
 fn bad_import_global(env: &str) -> Vec<u8> {
     wat::parse_str(format!(
         r#"
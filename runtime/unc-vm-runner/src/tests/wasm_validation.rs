@@ -34,6 +34,7 @@ static REFERENCE_TYPES: &str = r#"
 
 static BULK_MEMORY: &str = r#"
 (module
+  (memory 1)
   (func $entry (result i32) i32.const 0)
   (func (export "memory.copy") (param i32 i32 i32)
     local.get 0
@@ -47,7 +48,7 @@ static MULTI_VALUE: &str = r#"
 (module
   (func $entry (result i32) i32.const 0)
   (func $pick0 (param i64) (result i64 i64)
-    (get_local 0) (get_local 0))
+    (local.get 0) (local.get 0))
 )
 "#;
 
@@ -93,21 +94,52 @@ static EXPECTED_UNSUPPORTED: &[(&str, &str)] = &[
     // ("module_linking", MODULE_LINKING),
     ("tail_call", TAIL_CALL),
     ("multi_value", MULTI_VALUE),
-    ("bulk_memory", BULK_MEMORY),
-    ("reference_types", REFERENCE_TYPES),
     ("threads", THREADS),
     ("simd", SIMD),
 ];
 
+/// The proposals stay unsupported with `wasm_feature_expansion` too. In particular multi-value
+/// is not part of the expansion, as the unc-vm singlepass compiler can't compile multi-value
+/// blocks and function results.
 #[test]
 fn ensure_fails_verification() {
-    let config = test_vm_config();
-    with_vm_variants(&config, |kind| {
-        for (feature_name, wat) in EXPECTED_UNSUPPORTED {
+    for wasm_feature_expansion in [false, true] {
+        let config = unc_parameters::vm::Config { wasm_feature_expansion, ..test_vm_config() };
+        with_vm_variants(&config, |kind| {
+            for (feature_name, wat) in EXPECTED_UNSUPPORTED {
+                let wasm = wat::parse_str(wat).expect("parsing test wat should succeed");
+                if let Ok(_) = prepare_contract(&wasm, &config, kind) {
+                    panic!(
+                        "wasm containing use of {} feature did not fail to prepare",
+                        feature_name
+                    );
+                }
+            }
+        });
+    }
+}
+
+/// Proposals that are only enabled with `wasm_feature_expansion`.
+static FEATURE_EXPANSION: &[(&str, &str)] =
+    &[("bulk_memory", BULK_MEMORY), ("reference_types", REFERENCE_TYPES)];
+
+#[test]
+fn ensure_feature_expansion_is_gated() {
+    let mut disabled = test_vm_config();
+    disabled.wasm_feature_expansion = false;
+    let enabled = unc_parameters::vm::Config { wasm_feature_expansion: true, ..disabled.clone() };
+    with_vm_variants(&enabled, |kind| {
+        for (feature_name, wat) in FEATURE_EXPANSION {
             let wasm = wat::parse_str(wat).expect("parsing test wat should succeed");
-            if let Ok(_) = prepare_contract(&wasm, &config, kind) {
+            if let Ok(_) = prepare_contract(&wasm, &disabled, kind) {
                 panic!("wasm containing use of {} feature did not fail to prepare", feature_name);
             }
+            if let Err(err) = prepare_contract(&wasm, &enabled, kind) {
+                panic!(
+                    "wasm containing use of {} feature failed to prepare: {}",
+                    feature_name, err
+                );
+            }
         }
     });
 }
@@ -264,8 +264,7 @@ impl NearVM {
             })
             .clone();
 
-        let features =
-            crate::features::WasmFeatures::from(config.limit_config.contract_prepare_version);
+        let features = crate::features::WasmFeatures::from(&config);
         Self {
             config,
             engine: Universal::new(compiler)
@@ -566,7 +565,13 @@ impl unc_vm_vm::Tunables for &NearVM {
         style: &unc_vm_vm::TableStyle,
         vm_definition_location: std::ptr::NonNull<unc_vm_vm::VMTableDefinition>,
     ) -> Result<std::sync::Arc<dyn unc_vm_vm::Table>, String> {
-        // This is called when instantiating a module.
+        // This is called when instantiating a module. Capping the maximum makes `table.grow`
+        // fail once the table would exceed the limit, just like with a declared maximum.
+        let mut ty = *ty;
+        if let Some(max_elements) = self.config.limit_config.max_elements_per_contract_table {
+            let max_elements = u32::try_from(max_elements).unwrap_or(u32::MAX);
+            ty.maximum = Some(ty.maximum.map_or(max_elements, |maximum| maximum.min(max_elements)));
+        }
         Ok(Arc::new(LinearTable::from_definition(&ty, &style, vm_definition_location)?))
     }
 
@@ -574,6 +579,11 @@ impl unc_vm_vm::Tunables for &NearVM {
         u64::from(self.config.regular_op_cost).saturating_mul((stack_size + 7) / 8)
     }
 
+    fn bulk_memory_gas_cost(&self) -> u64 {
+        // Same as for the stack initialization: a regular operation per 8 bytes.
+        u64::from(self.config.regular_op_cost)
+    }
+
     /// Instrumentation configuration: stack limiter config
     fn stack_limiter_cfg(&self) -> Box<dyn finite_wasm::max_stack::SizeConfig> {
         Box::new(MaxStackCfg)
@@ -143,8 +143,7 @@ impl WasmtimeVM {
     }
 
     pub(crate) fn default_wasmtime_config(&self) -> wasmtime::Config {
-        let features =
-            crate::features::WasmFeatures::from(self.config.limit_config.contract_prepare_version);
+        let features = crate::features::WasmFeatures::from(&self.config);
         let mut config = wasmtime::Config::from(features);
         config.max_wasm_stack(1024 * 1024 * 1024); // wasm stack metering is implemented by instrumentation, we don't want wasmtime to trap before that
         config
@@ -93,6 +93,9 @@ pub(crate) struct FuncGen<'a> {
     /// Cost for initializing the stack of the function
     stack_init_gas_cost: u64,
 
+    /// Cost charged by bulk operations per 8 bytes of memory or per table element
+    bulk_memory_gas_cost: u32,
+
     /// Iterator over the gas instrumentation points
     gas_iter: iter::Peekable<iter::Zip<slice::Iter<'a, usize>, slice::Iter<'a, u64>>>,
 
@@ -371,6 +374,28 @@ impl<'a> FuncGen<'a> {
         self.machine.release_temp_gpr(cost_reg);
     }
 
+    /// Charge gas for a bulk operation that processes `count` items, where `count` is the i32
+    /// operand of the operation. With `per_word` set the items are bytes of memory, and a
+    /// regular cost is charged for every started 8 bytes; otherwise every item is charged.
+    ///
+    /// This must be called before the registers of `count` are released.
+    fn emit_gas_per_item(&mut self, count: Location, per_word: bool) {
+        if self.bulk_memory_gas_cost == 0 {
+            return;
+        }
+        let cost_reg = self.machine.acquire_temp_gpr().unwrap();
+        // A 32-bit move zero-extends into the whole register.
+        self.assembler.emit_mov(Size::S32, count, Location::GPR(cost_reg));
+        if per_word {
+            self.assembler.emit_add(Size::S64, Location::Imm32(7), Location::GPR(cost_reg));
+            self.assembler.emit_shr(Size::S64, Location::Imm8(3), Location::GPR(cost_reg));
+        }
+        // Neither operand is wider than 32 bits, so the product can't overflow.
+        self.assembler.emit_imul_imm32_gpr64(self.bulk_memory_gas_cost, cost_reg);
+        self.emit_gas(Location::GPR(cost_reg));
+        self.machine.release_temp_gpr(cost_reg);
+    }
+
     /// Emit a gas charge operation. The gas amount is stored in `cost_location`, which must be either an imm32 or a GPR
     // (this is because emit_add can only take up to an imm32)
     fn emit_gas(&mut self, cost_location: Location) {
@@ -1700,6 +1725,7 @@ impl<'a> FuncGen<'a> {
         local_func_index: LocalFunctionIndex,
         calling_convention: CallingConvention,
         stack_init_gas_cost: u64,
+        bulk_memory_gas_cost: u64,
         gas_offsets: &'a [usize],
         gas_costs: &'a [u64],
         _gas_kinds: &'a [InstrumentationKind],
@@ -1740,6 +1766,12 @@ impl<'a> FuncGen<'a> {
             calling_convention,
             signature,
             stack_init_gas_cost,
+            // `emit_gas_per_item` multiplies by this cost as a sign-extended imm32.
+            bulk_memory_gas_cost: i32::try_from(bulk_memory_gas_cost)
+                .map(|cost| cost as u32)
+                .map_err(|_| CodegenError {
+                    message: "bulk memory gas cost does not fit into an i32".to_string(),
+                })?,
             gas_iter: gas_offsets.iter().zip(gas_costs.iter()).peekable(),
             stack_size: u32::try_from(stack_size).map_err(|_| CodegenError {
                 message: "one function has a stack more than u32::MAX deep".to_string(),
@@ -5098,6 +5130,7 @@ impl<'a> FuncGen<'a> {
                 let len = self.value_stack.pop().unwrap();
                 let src = self.value_stack.pop().unwrap();
                 let dst = self.value_stack.pop().unwrap();
+                self.emit_gas_per_item(len, true);
                 self.machine.release_locations_only_regs(&[len, src, dst]);
 
                 self.assembler.emit_mov(
@@ -5148,6 +5181,7 @@ impl<'a> FuncGen<'a> {
                 let len = self.value_stack.pop().unwrap();
                 let src_pos = self.value_stack.pop().unwrap();
                 let dst_pos = self.value_stack.pop().unwrap();
+                self.emit_gas_per_item(len, true);
                 self.machine.release_locations_only_regs(&[len, src_pos, dst_pos]);
 
                 let memory_index = MemoryIndex::new(src_mem as usize);
@@ -5182,6 +5216,7 @@ impl<'a> FuncGen<'a> {
                 let len = self.value_stack.pop().unwrap();
                 let val = self.value_stack.pop().unwrap();
                 let dst = self.value_stack.pop().unwrap();
+                self.emit_gas_per_item(len, true);
                 self.machine.release_locations_only_regs(&[len, val, dst]);
 
                 let memory_index = MemoryIndex::new(mem as usize);
@@ -7505,6 +7540,7 @@ impl<'a> FuncGen<'a> {
                 let table_index = TableIndex::new(index as _);
                 let delta = self.value_stack.pop().unwrap();
                 let init_value = self.value_stack.pop().unwrap();
+                self.emit_gas_per_item(delta, false);
                 self.machine.release_locations_only_regs(&[delta, init_value]);
 
                 self.assembler.emit_mov(
@@ -7544,6 +7580,7 @@ impl<'a> FuncGen<'a> {
                 let len = self.value_stack.pop().unwrap();
                 let src = self.value_stack.pop().unwrap();
                 let dest = self.value_stack.pop().unwrap();
+                self.emit_gas_per_item(len, false);
                 self.machine.release_locations_only_regs(&[len, src, dest]);
 
                 self.assembler.emit_mov(
@@ -7575,6 +7612,7 @@ impl<'a> FuncGen<'a> {
                 let len = self.value_stack.pop().unwrap();
                 let val = self.value_stack.pop().unwrap();
                 let dest = self.value_stack.pop().unwrap();
+                self.emit_gas_per_item(len, false);
                 self.machine.release_locations_only_regs(&[len, val, dest]);
 
                 self.assembler.emit_mov(
@@ -7603,6 +7641,7 @@ impl<'a> FuncGen<'a> {
                 let len = self.value_stack.pop().unwrap();
                 let src = self.value_stack.pop().unwrap();
                 let dest = self.value_stack.pop().unwrap();
+                self.emit_gas_per_item(len, false);
                 self.machine.release_locations_only_regs(&[len, src, dest]);
 
                 self.assembler.emit_mov(
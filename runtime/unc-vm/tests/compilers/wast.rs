@@ -21,10 +21,15 @@ pub fn run_wast(mut config: crate::Config, wast_path: &str) -> anyhow::Result<()
     let try_nan_canonicalization = wast_path.contains("nan-canonicalization");
     let mut features = Features::default();
     let is_bulkmemory = wast_path.contains("bulk-memory");
+    let is_reference_types = wast_path.contains("reference-types");
     let is_simd = wast_path.contains("simd");
     if is_bulkmemory {
         features.bulk_memory(true);
     }
+    if is_reference_types {
+        features.bulk_memory(true);
+        features.reference_types(true);
+    }
     if is_simd {
         features.simd(true);
     }
@@ -59,6 +59,10 @@ pub trait Tunables: Sync {
 
     /// Cost for initializing a stack frame
     fn stack_init_gas_cost(&self, frame_size: u64) -> u64;
+
+    /// Cost charged by bulk memory and table operations for every 8 bytes of memory or every
+    /// table element they touch
+    fn bulk_memory_gas_cost(&self) -> u64;
 }
 
 #[doc(hidden)]
@@ -118,4 +122,9 @@ impl Tunables for TestTunables {
     fn stack_init_gas_cost(&self, _frame_size: u64) -> u64 {
         unimplemented!()
     }
+
+    fn bulk_memory_gas_cost(&self) -> u64 {
+        // Bulk operations are not charged by size.
+        0
+    }
 }
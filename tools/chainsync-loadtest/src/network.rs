@@ -11,8 +11,8 @@ use unc_async::time;
 use unc_network::concurrency::ctx;
 use unc_network::concurrency::scope;
 use unc_network::types::{
-    AccountIdOrPeerTrackingShard, PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg,
-    ReasonForBan, StateResponseInfo,
+    AccountIdOrPeerTrackingShard, LightClientExecutionProof, LightClientProofRequest,
+    PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg, ReasonForBan, StateResponseInfo,
 };
 use unc_network::types::{
     FullPeerInfo, NetworkInfo, NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest,
@@ -27,7 +27,7 @@ use unc_primitives::sharding::ChunkHash;
 use unc_primitives::sharding::ShardChunkHeader;
 use unc_primitives::transaction::SignedTransaction;
 use unc_primitives::types::{AccountId, EpochId, ShardId};
-use unc_primitives::views::FinalExecutionOutcomeView;
+use unc_primitives::views::{FinalExecutionOutcomeView, LightClientBlockView};
 
 #[derive(Default, Debug)]
 pub struct Stats {
@@ -308,4 +308,34 @@ impl unc_network::client::Client for Network {
     async fn chunk_state_witness(&self, _witness: ChunkStateWitness) {}
 
     async fn chunk_endorsement(&self, _endorsement: ChunkEndorsement) {}
+
+    async fn light_client_block_request(
+        &self,
+        _last_block_hash: CryptoHash,
+    ) -> Option<Box<LightClientBlockView>> {
+        None
+    }
+
+    async fn light_client_block(
+        &self,
+        _block: LightClientBlockView,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+
+    async fn light_client_proof_request(
+        &self,
+        _request: LightClientProofRequest,
+    ) -> Option<Box<LightClientExecutionProof>> {
+        None
+    }
+
+    async fn light_client_proof(
+        &self,
+        _proof: LightClientExecutionProof,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
 }
@@ -0,0 +1,60 @@
+import { useQuery } from 'react-query';
+import { fetchPeerReputations } from './api';
+import './PeerReputationView.scss';
+
+type PeerReputationViewProps = {
+    addr: string;
+};
+
+export const PeerReputationView = ({ addr }: PeerReputationViewProps) => {
+    const {
+        data: peerReputations,
+        error,
+        isLoading,
+    } = useQuery(['peerReputations', addr], () => fetchPeerReputations(addr));
+
+    if (isLoading) {
+        return <div>Loading...</div>;
+    } else if (error) {
+        return <div className="error">{(error as Error).stack}</div>;
+    }
+
+    const peer_reputations = peerReputations!.status_response.PeerReputations;
+
+    return (
+        <div className="peer-reputation-view">
+            <table>
+                <thead>
+                    <th>Peer ID</th>
+                    <th>Score</th>
+                    <th>Avg Latency</th>
+                    <th>Useful Data</th>
+                    <th>Invalid Messages</th>
+                    <th>Timeouts</th>
+                    <th>Demoted</th>
+                </thead>
+                <tbody>
+                    {peer_reputations.peers.map(
+                        (peer) => {
+                            return (
+                                <tr key={peer.peer_id} className={peer.demoted ? 'demoted' : ''}>
+                                    <td>{peer.peer_id}</td>
+                                    <td>{peer.score.toFixed(2)}</td>
+                                    <td>
+                                        {peer.avg_latency_millis === null
+                                            ? '-'
+                                            : `${peer.avg_latency_millis}ms`}
+                                    </td>
+                                    <td>{(peer.useful_bytes / 1e6).toFixed(2)}MB</td>
+                                    <td>{peer.invalid_data.toFixed(2)}</td>
+                                    <td>{peer.timeouts.toFixed(2)}</td>
+                                    <td>{peer.demoted ? 'yes' : 'no'}</td>
+                                </tr>
+                            );
+                        }
+                    )}
+                </tbody>
+            </table>
+        </div>
+    );
+};
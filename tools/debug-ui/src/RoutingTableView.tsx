@@ -112,6 +112,61 @@ export const RoutingTableView = ({ addr }: RoutingTableViewProps) => {
                     })}
                 </tbody>
             </table>
+            <br/>
+            <p><b>Route Comparison (V1 vs V2)</b></p>
+            <table>
+                <thead>
+                    <th>Peer ID</th>
+                    <th>V1 Distance</th>
+                    <th>V2 Distance</th>
+                    <th>V1 Next Hops</th>
+                    <th>V2 Next Hops</th>
+                </thead>
+                <tbody>
+                    {routingInfo.route_comparison.map((route) => {
+                        const mismatch = route.v1_distance !== route.v2_distance;
+                        return (
+                            <tr key={route.peer_id} className={mismatch ? 'mismatch' : ''}>
+                                <td>{route.peer_id.substring(8, 14)}...</td>
+                                <td>{route.v1_distance ?? '_'}</td>
+                                <td>{route.v2_distance ?? '_'}</td>
+                                <td>{route.v1_next_hops.map((p) => p.substring(8, 14)).join(', ')}</td>
+                                <td>{route.v2_next_hops.map((p) => p.substring(8, 14)).join(', ')}</td>
+                            </tr>
+                        );
+                    })}
+                </tbody>
+            </table>
+            <br/>
+            <p><b>Routed Message Traces</b></p>
+            <table>
+                <thead>
+                    <th>Message Hash</th>
+                    <th>Type</th>
+                    <th>Target</th>
+                    <th>Sent</th>
+                    <th>Hops</th>
+                    <th>Round Trip</th>
+                </thead>
+                <tbody>
+                    {routingInfo.routed_message_traces.map((trace) => {
+                        return (
+                            <tr key={trace.msg_hash}>
+                                <td>{trace.msg_hash.substring(0, 6)}...</td>
+                                <td>{trace.body_type}</td>
+                                <td>{trace.target.substring(8, 14)}...</td>
+                                <td>{toHumanTime(Math.floor(Date.now() / 1000) - trace.sent_at)} ago</td>
+                                <td>{trace.hops === null
+                                    ? 'not delivered'
+                                    : trace.hops.map((p) => p.substring(8, 14)).join(' -> ')}</td>
+                                <td>{trace.round_trip_millis === null
+                                    ? '_'
+                                    : formatDurationInMillis(trace.round_trip_millis)}</td>
+                            </tr>
+                        );
+                    })}
+                </tbody>
+            </table>
         </div>
     );
 };
@@ -322,6 +322,25 @@ export interface RoutingTableView {
     local_edges: { [peer_id: string]: EdgeView };
     peer_distances: { [peer_id: string]: PeerRoutesView };
     my_distances: { [peer_id: string]:  number };
+    routed_message_traces: RoutedMessageTraceView[];
+    route_comparison: RouteComparisonView[];
+}
+
+export interface RoutedMessageTraceView {
+    msg_hash: string;
+    body_type: string;
+    target: string;
+    sent_at: number;
+    hops: string[] | null;
+    round_trip_millis: number | null;
+}
+
+export interface RouteComparisonView {
+    peer_id: string;
+    v1_distance: number | null;
+    v2_distance: number | null;
+    v1_next_hops: string[];
+    v2_next_hops: string[];
 }
 
 export interface RoutingTableResponse {
@@ -347,6 +366,26 @@ export interface SnapshotHostsResponse {
     };
 }
 
+export interface PeerReputationView {
+    peer_id: string,
+    score: number,
+    avg_latency_millis: number | null,
+    useful_bytes: number,
+    invalid_data: number,
+    timeouts: number,
+    demoted: boolean,
+}
+
+export interface PeerReputationsView {
+    peers: PeerReputationView[],
+}
+
+export interface PeerReputationsResponse {
+    status_response: {
+        PeerReputations: PeerReputationsView;
+    };
+}
+
 export type DroppedReason = 'HeightProcessed' | 'TooManyProcessingBlocks';
 
 export type BlockProcessingStatus =
@@ -465,6 +504,13 @@ export async function fetchSnapshotHosts(
     return await response.json();
 }
 
+export async function fetchPeerReputations(
+    addr: string
+): Promise<PeerReputationsResponse> {
+    const response = await fetch(`http://${addr}/debug/api/peer_reputations`);
+    return await response.json();
+}
+
 export async function fetchChainProcessingStatus(
     addr: string
 ): Promise<ChainProcessingStatusResponse> {
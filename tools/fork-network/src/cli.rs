@@ -595,6 +595,8 @@ impl ForkNetworkCommand {
                         }
                         index_delayed_receipt += 1;
                     }
+                    // The global contract code isn't owned by any account, there's nothing to map.
+                    StateRecord::GlobalContractCode { .. } => {}
                 }
                 records_parsed += 1;
             } else {
@@ -103,6 +103,9 @@ pub fn map_records<P: AsRef<Path>>(
                 }
                 records_seq.serialize_element(&r).unwrap();
             }
+            StateRecord::GlobalContractCode { .. } => {
+                records_seq.serialize_element(&r).unwrap();
+            }
         };
     })?;
     for account_id in accounts {
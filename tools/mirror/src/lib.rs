@@ -374,6 +374,7 @@ impl From<QueryError> for ChainError {
             | QueryError::NoContractCode { .. }
             | QueryError::UnknownAccessKey { .. }
             | QueryError::GarbageCollectedBlock { .. }
+            | QueryError::UnsupportedHistoricalQuery { .. }
             | QueryError::UnknownBlock { .. } => Self::Unknown,
             _ => Self::other(err),
         }
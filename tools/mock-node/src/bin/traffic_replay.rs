@@ -0,0 +1,60 @@
+//! A binary that replays a network traffic recording into a node, to reproduce
+//! the exact sequence of messages that the recording node has received.
+
+use anyhow::Context;
+use mock_node::replay::{replay, replay_loop, ReplayLoopBuilder, ReplayNode};
+use std::path::PathBuf;
+use unc_chain_configs::GenesisValidationMode;
+use unc_crypto::PublicKey;
+use unc_network::recorder::read_recording;
+use unc_o11y::testonly::init_integration_logger;
+use unc_primitives::network::PeerId;
+
+/// Program to replay the traffic recorded by a node (see `traffic_recording` in the
+/// experimental network config) into a node started from the given home dir.
+///
+/// The node's Client and ShardsManager are run without networking on a test loop, and the
+/// recorded inbound messages are delivered to them in the recorded order and with the recorded
+/// delays (in virtual time), one at a time.
+///
+/// For the replay to be meaningful, the home dir should contain the state of the recording
+/// node from before the recording has started, e.g. a copy of its data dir.
+///
+/// Example
+///
+/// ```console
+/// $ traffic-replay ~/replay_home_dir ~/.unc/traffic
+/// ```
+#[derive(clap::Parser)]
+struct Cli {
+    /// Home dir of the node to replay the traffic into.
+    home_dir: PathBuf,
+    /// Directory with the recording.
+    recording_dir: PathBuf,
+    /// Replay only the messages exchanged with the peer with this public key.
+    #[clap(long)]
+    peer_id: Option<PublicKey>,
+}
+
+fn main() -> anyhow::Result<()> {
+    init_integration_logger();
+    let args: Cli = clap::Parser::parse();
+    let mut recording = read_recording(&args.recording_dir).with_context(|| {
+        format!("Error reading recording from {}", args.recording_dir.display())
+    })?;
+    if let Some(peer_id) = args.peer_id.map(PeerId::new) {
+        recording.retain(|msg| msg.peer_id.as_ref() == Some(&peer_id));
+    }
+    tracing::info!(target: "mock_node", messages = recording.len(), "loaded recording");
+
+    let unc_config = framework::config::load_config(&args.home_dir, GenesisValidationMode::Full)
+        .context("Error loading config")?;
+    let node_id = unc_config.network_config.node_id();
+    let builder = ReplayLoopBuilder::new();
+    let node = ReplayNode::from_config(&args.home_dir, unc_config, &builder)
+        .context("Error opening the node")?;
+    let mut test_loop = replay_loop(builder, node);
+    let stats = replay(&mut test_loop, node_id, recording);
+    tracing::info!(target: "mock_node", ?stats, "replay finished");
+    Ok(())
+}
@@ -18,6 +18,7 @@ use unc_network::types::{PartialEncodedChunkRequestMsg, PartialEncodedChunkRespo
 use unc_primitives::sharding::ChunkHash;
 use unc_primitives::types::{BlockHeight, ShardId};
 
+pub mod replay;
 pub mod setup;
 
 // For now this is a simple struct with one field just to leave the door
@@ -0,0 +1,490 @@
+//! Replays a network traffic recording (see `unc_network::recorder`) into a node.
+//!
+//! The node's `Client` and `ShardsManager` are driven directly by a `TestLoop` instead of
+//! running in actix actors: the recorded messages are scheduled at the same relative times
+//! at which they were recorded, and every message sent between the components is an event
+//! of the loop, so the whole replay runs on the fake clock of the loop and the order in which
+//! the node processes the messages is deterministic.
+//!
+//! Only the message handling is replayed. The timers of the `ClientActor` (block production,
+//! sync, doomslug) are not run, and the `Client` itself still reads the real time.
+
+use derive_enum_from_into::{EnumFrom, EnumTryInto};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use unc_async::messaging::{CanSend, CanSendAsync, IntoSender, Sender};
+use unc_async::test_loop::delay_sender::DelaySender;
+use unc_async::test_loop::event_handler::LoopEventHandler;
+use unc_async::test_loop::{TestLoop, TestLoopBuilder};
+use unc_async::time;
+use unc_chain::ChainGenesis;
+use unc_chunks::adapter::ShardsManagerRequestFromClient;
+use unc_chunks::client::ShardsManagerResponse;
+use unc_chunks::test_loop::{
+    forward_client_request_to_shards_manager, forward_network_request_to_shards_manager,
+};
+use unc_chunks::ShardsManager;
+use unc_client::sync::adapter::SyncAdapter;
+use unc_client::{Client, SyncStatus};
+use unc_epoch_manager::shard_tracker::ShardTracker;
+use unc_epoch_manager::EpochManager;
+use unc_network::client as network_client;
+use unc_network::recorder::replay::Replayer;
+use unc_network::recorder::RecordedMessage;
+use unc_network::shards_manager::ShardsManagerRequestFromNetwork;
+use unc_network::types::{
+    LightClientExecutionProof, LightClientProofRequest, NetworkInfo, PeerManagerMessageRequest,
+    PeerManagerMessageResponse, ReasonForBan, SetChainInfo, StateResponseInfo,
+};
+use unc_primitives::block::{Approval, Block, BlockHeader};
+use unc_primitives::block_header::ApprovalType;
+use unc_primitives::challenge::Challenge;
+use unc_primitives::chunk_validation::{ChunkEndorsement, ChunkStateWitness};
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::network::{AnnounceAccount, PeerId};
+use unc_primitives::transaction::SignedTransaction;
+use unc_primitives::types::{AccountId, EpochId, ShardId};
+use unc_primitives::views::{FinalExecutionOutcomeView, LightClientBlockView};
+use unc_store::genesis::initialize_genesis_state;
+
+/// Seed of the Client's randomness, fixed so that replays are reproducible.
+const RNG_SEED: [u8; 32] = [0; 32];
+
+/// Messages delivered to the Client by the network.
+#[derive(Debug)]
+pub enum ClientRequestFromNetwork {
+    Block { block: Block, peer_id: PeerId, was_requested: bool },
+    BlockHeaders { headers: Vec<BlockHeader>, peer_id: PeerId },
+    BlockApproval { approval: Approval, peer_id: PeerId },
+    Transaction { transaction: SignedTransaction, is_forwarded: bool },
+    Challenge(Challenge),
+    ChunkStateWitness(ChunkStateWitness),
+    ChunkEndorsement(ChunkEndorsement),
+}
+
+#[derive(EnumTryInto, EnumFrom, Debug)]
+pub enum ReplayEvent {
+    Recorded(RecordedMessage),
+    NetworkToClient(ClientRequestFromNetwork),
+    NetworkToShardsManager(ShardsManagerRequestFromNetwork),
+    ClientToShardsManager(ShardsManagerRequestFromClient),
+    ShardsManagerToClient(ShardsManagerResponse),
+}
+
+pub type ReplayLoopBuilder = TestLoopBuilder<ReplayEvent>;
+pub type ReplayLoop = TestLoop<ReplayNode, ReplayEvent>;
+
+/// Outcome of a replay.
+#[derive(Debug, Default, Clone)]
+pub struct ReplayStats {
+    /// Number of messages delivered to the node.
+    pub replayed: usize,
+    /// Number of messages which failed to parse or for which the node would have banned the peer.
+    pub failed: usize,
+}
+
+/// The components of the node which process the replayed messages.
+#[derive(derive_more::AsMut)]
+pub struct ReplayNode {
+    pub client: Client,
+    pub shards_manager: ShardsManager,
+    pub stats: ReplayStats,
+}
+
+impl ReplayNode {
+    /// `client` has to send its requests to the ShardsManager to `sender`.
+    /// The ShardsManager is created on top of the client's chain, with the loop's clock.
+    pub fn new(clock: time::Clock, mut client: Client, sender: DelaySender<ReplayEvent>) -> Self {
+        // There are no peers to sync from, the node only follows the recording.
+        client.sync_status = SyncStatus::NoSync;
+        let shards_manager = ShardsManager::new(
+            clock,
+            client.validator_signer.as_ref().map(|signer| signer.validator_id().clone()),
+            client.epoch_manager.clone(),
+            client.shard_tracker.clone(),
+            Sender::noop(),
+            sender.into_sender(),
+            client.chain.chain_store().new_read_only_chunks_store(),
+            client.chain.head().expect("chain is initialized by the client"),
+            client.chain.header_head().expect("chain is initialized by the client"),
+        );
+        Self { client, shards_manager, stats: ReplayStats::default() }
+    }
+
+    /// Opens the node from the given home dir the same way `framework::start_with_config`
+    /// does, but without starting any actors.
+    pub fn from_config(
+        home_dir: &Path,
+        mut config: framework::UncConfig,
+        builder: &ReplayLoopBuilder,
+    ) -> anyhow::Result<Self> {
+        let storage = framework::open_storage(home_dir, &mut config)?;
+        let store = storage.get_hot_store();
+        initialize_genesis_state(store.clone(), &config.genesis, Some(home_dir));
+        let epoch_manager = EpochManager::new_arc_handle(store.clone(), &config.genesis.config);
+        let shard_tracker = ShardTracker::new(epoch_manager.clone());
+        let runtime = framework::NightshadeRuntime::from_config(
+            home_dir,
+            store,
+            &config,
+            epoch_manager.clone(),
+        );
+        let state_sync_adapter =
+            Arc::new(RwLock::new(SyncAdapter::new(Sender::noop(), Sender::noop())));
+        let client = Client::new(
+            config.client_config,
+            ChainGenesis::new(&config.genesis),
+            epoch_manager,
+            shard_tracker,
+            state_sync_adapter,
+            runtime,
+            Arc::new(NoPeers).into(),
+            builder.sender().into_sender(),
+            config.validator_signer,
+            true,
+            RNG_SEED,
+            None,
+        )?;
+        Ok(Self::new(builder.clock(), client, builder.sender()))
+    }
+}
+
+/// Builds the loop driving the node's components.
+pub fn replay_loop(builder: ReplayLoopBuilder, node: ReplayNode) -> ReplayLoop {
+    let mut test_loop = builder.build(node);
+    test_loop.register_handler(handle_network_request_in_client().widen());
+    test_loop.register_handler(handle_shards_manager_response_in_client().widen());
+    test_loop.register_handler(forward_client_request_to_shards_manager().widen());
+    test_loop.register_handler(forward_network_request_to_shards_manager().widen());
+    test_loop
+}
+
+/// Delivers the recorded messages to the node and runs the loop until the recorded time of
+/// the last one. `node_id` is the id of the node which has recorded the traffic.
+/// Has to be called at most once per loop.
+pub fn replay(
+    test_loop: &mut ReplayLoop,
+    node_id: PeerId,
+    recording: Vec<RecordedMessage>,
+) -> ReplayStats {
+    let mut remaining = VecDeque::from(recording);
+    let Some(first) = remaining.pop_front() else {
+        return ReplayStats::default();
+    };
+    // Messages recorded by different PeerActors may be slightly out of order,
+    // in which case they are delivered without a delay.
+    let mut duration = time::Duration::ZERO;
+    let mut prev = first.time();
+    for msg in &remaining {
+        duration += (msg.time() - prev).max(time::Duration::ZERO);
+        prev = msg.time();
+    }
+
+    let sender = test_loop.sender();
+    let mut replayer = Replayer::new(
+        node_id,
+        Arc::new(TestLoopClient { sender: sender.clone() }),
+        sender.clone().into_sender(),
+    );
+    test_loop.register_handler(
+        LoopEventHandler::new(move |msg: RecordedMessage, stats: &mut ReplayStats, ctx| {
+            // The Client and the ShardsManager only receive events of the loop,
+            // so the replayer never waits on anything.
+            match futures::executor::block_on(replayer.replay(&ctx.clock, &msg)) {
+                Ok(()) => stats.replayed += 1,
+                Err(err) => {
+                    tracing::warn!(target: "mock_node", ?err, ?msg, "failed to replay message");
+                    stats.failed += 1;
+                }
+            }
+            // Messages are scheduled one at a time, since the event queue of the loop is bounded.
+            if let Some(next) = remaining.pop_front() {
+                let delay = (next.time() - msg.time()).max(time::Duration::ZERO);
+                ctx.sender.send_with_delay(next, delay);
+            }
+            Ok(())
+        })
+        .widen(),
+    );
+    sender.send_with_delay(first.into(), time::Duration::ZERO);
+    test_loop.run_for(duration);
+    test_loop.data.stats.clone()
+}
+
+/// Mirrors the handlers of the `ClientActor` for the messages received from the network.
+fn handle_network_request_in_client() -> LoopEventHandler<Client, ClientRequestFromNetwork> {
+    LoopEventHandler::new_simple(|request, client: &mut Client| {
+        match request {
+            ClientRequestFromNetwork::Block { block, peer_id, was_requested } => {
+                let blocks_at_height = client
+                    .chain
+                    .chain_store()
+                    .get_all_block_hashes_by_height(block.header().height());
+                if was_requested || blocks_at_height.map_or(true, |blocks| blocks.is_empty()) {
+                    client.receive_block(block, peer_id, was_requested, Arc::new(|_| {}));
+                } else {
+                    tracing::debug!(target: "mock_node", hash = ?block.hash(), "Skipping unrequested block");
+                }
+            }
+            ClientRequestFromNetwork::BlockHeaders { headers, peer_id } => {
+                if let Err(err) = client.sync_block_headers(headers) {
+                    if err.is_bad_data() {
+                        tracing::warn!(target: "mock_node", ?err, ?peer_id, "Peer would have been banned for sending invalid block headers");
+                    }
+                }
+            }
+            ClientRequestFromNetwork::BlockApproval { approval, peer_id } => {
+                client.collect_block_approval(&approval, ApprovalType::PeerApproval(peer_id));
+            }
+            ClientRequestFromNetwork::Transaction { transaction, is_forwarded } => {
+                client.process_tx(transaction, is_forwarded, false);
+            }
+            ClientRequestFromNetwork::Challenge(challenge) => {
+                if let Err(err) = client.process_challenge(challenge) {
+                    tracing::error!(target: "mock_node", ?err, "Error processing challenge");
+                }
+            }
+            ClientRequestFromNetwork::ChunkStateWitness(witness) => {
+                if let Err(err) = client.process_chunk_state_witness(witness) {
+                    tracing::error!(target: "mock_node", ?err, "Error processing chunk state witness");
+                }
+            }
+            ClientRequestFromNetwork::ChunkEndorsement(endorsement) => {
+                if let Err(err) = client.process_chunk_endorsement(endorsement) {
+                    tracing::error!(target: "mock_node", ?err, "Error processing chunk endorsement");
+                }
+            }
+        }
+        // The ClientActor does it once the chunks are applied, on the rayon threads.
+        client.finish_blocks_in_processing();
+    })
+}
+
+/// Mirrors the handler of the `ClientActor` for the messages of the ShardsManager.
+fn handle_shards_manager_response_in_client() -> LoopEventHandler<Client, ShardsManagerResponse> {
+    LoopEventHandler::new_simple(|response, client: &mut Client| match response {
+        ShardsManagerResponse::ChunkCompleted { partial_chunk, shard_chunk } => {
+            client.on_chunk_completed(partial_chunk, shard_chunk, Arc::new(|_| {}));
+            client.finish_blocks_in_processing();
+        }
+        ShardsManagerResponse::InvalidChunk(encoded_chunk) => {
+            client.on_invalid_chunk(encoded_chunk);
+        }
+        ShardsManagerResponse::ChunkHeaderReadyForInclusion { chunk_header, chunk_producer } => {
+            client.on_chunk_header_ready_for_inclusion(chunk_header, chunk_producer);
+        }
+    })
+}
+
+/// `unc_network::client::Client` which turns the messages into events of the loop.
+/// Requests are answered with nothing, since the responses would be dropped by the
+/// replayer anyway, and the peer is never banned, since the events are processed later.
+struct TestLoopClient {
+    sender: DelaySender<ReplayEvent>,
+}
+
+impl TestLoopClient {
+    fn send(&self, request: ClientRequestFromNetwork) {
+        self.sender.send(request);
+    }
+}
+
+#[async_trait::async_trait]
+impl network_client::Client for TestLoopClient {
+    async fn tx_status_request(
+        &self,
+        _account_id: AccountId,
+        _tx_hash: CryptoHash,
+    ) -> Option<Box<FinalExecutionOutcomeView>> {
+        None
+    }
+
+    async fn tx_status_response(&self, _tx_result: FinalExecutionOutcomeView) {}
+
+    async fn state_request_header(
+        &self,
+        _shard_id: ShardId,
+        _sync_hash: CryptoHash,
+    ) -> Result<Option<StateResponseInfo>, ReasonForBan> {
+        Ok(None)
+    }
+
+    async fn state_request_part(
+        &self,
+        _shard_id: ShardId,
+        _sync_hash: CryptoHash,
+        _part_id: u64,
+    ) -> Result<Option<StateResponseInfo>, ReasonForBan> {
+        Ok(None)
+    }
+
+    // State sync isn't replayed.
+    async fn state_response(&self, _info: StateResponseInfo) {}
+
+    async fn block_approval(&self, approval: Approval, peer_id: PeerId) {
+        self.send(ClientRequestFromNetwork::BlockApproval { approval, peer_id });
+    }
+
+    async fn transaction(&self, transaction: SignedTransaction, is_forwarded: bool) {
+        self.send(ClientRequestFromNetwork::Transaction { transaction, is_forwarded });
+    }
+
+    async fn block_request(&self, _hash: CryptoHash) -> Option<Box<Block>> {
+        None
+    }
+
+    async fn block_headers_request(&self, _hashes: Vec<CryptoHash>) -> Option<Vec<BlockHeader>> {
+        None
+    }
+
+    async fn block(&self, block: Block, peer_id: PeerId, was_requested: bool) {
+        self.send(ClientRequestFromNetwork::Block { block, peer_id, was_requested });
+    }
+
+    async fn block_headers(
+        &self,
+        headers: Vec<BlockHeader>,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        self.send(ClientRequestFromNetwork::BlockHeaders { headers, peer_id });
+        Ok(())
+    }
+
+    async fn challenge(&self, challenge: Challenge) {
+        self.send(ClientRequestFromNetwork::Challenge(challenge));
+    }
+
+    async fn network_info(&self, _info: NetworkInfo) {}
+
+    async fn announce_account(
+        &self,
+        _accounts: Vec<(AnnounceAccount, Option<EpochId>)>,
+    ) -> Result<Vec<AnnounceAccount>, ReasonForBan> {
+        Ok(vec![])
+    }
+
+    async fn chunk_state_witness(&self, witness: ChunkStateWitness) {
+        self.send(ClientRequestFromNetwork::ChunkStateWitness(witness));
+    }
+
+    async fn chunk_endorsement(&self, endorsement: ChunkEndorsement) {
+        self.send(ClientRequestFromNetwork::ChunkEndorsement(endorsement));
+    }
+
+    async fn light_client_block_request(
+        &self,
+        _last_block_hash: CryptoHash,
+    ) -> Option<Box<LightClientBlockView>> {
+        None
+    }
+
+    // The replayed node is never a light client.
+    async fn light_client_block(
+        &self,
+        _block: LightClientBlockView,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+
+    async fn light_client_proof_request(
+        &self,
+        _request: LightClientProofRequest,
+    ) -> Option<Box<LightClientExecutionProof>> {
+        None
+    }
+
+    async fn light_client_proof(
+        &self,
+        _proof: LightClientExecutionProof,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+}
+
+/// Drops everything the node sends to the network, since there are no peers to send it to.
+struct NoPeers;
+
+impl CanSendAsync<PeerManagerMessageRequest, Result<PeerManagerMessageResponse, ()>> for NoPeers {
+    fn send_async(
+        &self,
+        _message: PeerManagerMessageRequest,
+    ) -> BoxFuture<'static, Result<PeerManagerMessageResponse, ()>> {
+        async { Err(()) }.boxed()
+    }
+}
+
+impl CanSend<PeerManagerMessageRequest> for NoPeers {
+    fn send(&self, _message: PeerManagerMessageRequest) {}
+}
+
+impl CanSend<SetChainInfo> for NoPeers {
+    fn send(&self, _message: SetChainInfo) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay, replay_loop, NoPeers, ReplayLoopBuilder, ReplayNode};
+    use std::sync::Arc;
+    use unc_async::time;
+    use unc_chain::test_utils::ValidatorSchedule;
+    use unc_chain::ChainGenesis;
+    use unc_client::test_utils::{setup_client, TestEnv, TEST_SEED};
+    use unc_crypto::{KeyType, SecretKey};
+    use unc_network::recorder::{Direction, RecordedMessage};
+    use unc_network::types::PeerMessage;
+    use unc_primitives::network::PeerId;
+    use unc_store::test_utils::create_test_store;
+
+    /// Blocks received by one node are replayed into a fresh node, which ends up with the same chain.
+    #[test]
+    fn test_replay_blocks() {
+        const NUM_BLOCKS: u64 = 5;
+        let mut env = TestEnv::builder(ChainGenesis::test()).build();
+        for height in 1..=NUM_BLOCKS {
+            env.produce_block(0, height);
+        }
+        let peer_id = PeerId::new(SecretKey::from_random(KeyType::ED25519).public_key());
+        let start = time::Utc::UNIX_EPOCH;
+        let recording = (1..=NUM_BLOCKS)
+            .map(|height| {
+                let block = env.clients[0].chain.get_block_by_height(height).unwrap();
+                RecordedMessage::new(
+                    start + time::Duration::milliseconds(100 * height as i64),
+                    Direction::Inbound,
+                    Some(peer_id.clone()),
+                    &PeerMessage::Block(block),
+                )
+            })
+            .collect();
+
+        let builder = ReplayLoopBuilder::new();
+        let client = setup_client(
+            create_test_store(),
+            ValidatorSchedule::new()
+                .block_producers_per_epoch(vec![vec!["test0".parse().unwrap()]]),
+            None,
+            false,
+            Arc::new(NoPeers).into(),
+            Arc::new(builder.sender()).into(),
+            ChainGenesis::test(),
+            TEST_SEED,
+            false,
+            true,
+        );
+        let node = ReplayNode::new(builder.clock(), client, builder.sender());
+        let mut test_loop = replay_loop(builder, node);
+        let node_id = PeerId::new(SecretKey::from_random(KeyType::ED25519).public_key());
+        let stats = replay(&mut test_loop, node_id, recording);
+        assert_eq!(stats.replayed, NUM_BLOCKS as usize);
+        assert_eq!(stats.failed, 0);
+        assert_eq!(
+            test_loop.data.client.chain.head().unwrap().last_block_hash,
+            env.clients[0].chain.head().unwrap().last_block_hash
+        );
+    }
+}
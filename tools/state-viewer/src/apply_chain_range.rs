@@ -243,6 +243,7 @@ fn apply_block_from_range(
                 ApplyChunkBlockContext::from_header(
                     block.header(),
                     prev_block.header().next_gas_price(),
+                    block.block_congestion_info(),
                 ),
                 &receipts,
                 chunk.transactions(),
@@ -268,6 +269,7 @@ fn apply_block_from_range(
                 ApplyChunkBlockContext::from_header(
                     block.header(),
                     block.header().next_gas_price(),
+                    block.block_congestion_info(),
                 ),
                 &[],
                 &[],
@@ -284,6 +286,7 @@ fn apply_block_from_range(
         apply_result.total_gas_burnt,
         genesis.config.gas_limit,
         apply_result.total_balance_burnt,
+        apply_result.congestion_info,
     );
 
     let state_update =
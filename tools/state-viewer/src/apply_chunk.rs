@@ -15,7 +15,6 @@ use unc_epoch_manager::{EpochManagerAdapter, EpochManagerHandle};
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::merkle::combine_hash;
 use unc_primitives::receipt::Receipt;
-use unc_primitives::shard_layout;
 use unc_primitives::sharding::{ChunkHash, ReceiptProof};
 use unc_primitives::state_sync::ReceiptProofResponse;
 use unc_primitives::types::{BlockHeight, ShardId};
@@ -152,6 +151,7 @@ pub(crate) fn apply_chunk(
                 ),
                 gas_price,
                 random_seed: hash("random seed".as_ref()),
+                congestion_info: prev_block.block_congestion_info(),
             },
             &receipts,
             transactions,
@@ -186,8 +186,7 @@ fn find_tx_or_receipt(
             if &receipt.get_hash() == hash {
                 let shard_layout =
                     epoch_manager.get_shard_layout_from_prev_block(chunk.prev_block())?;
-                let to_shard =
-                    shard_layout::account_id_to_shard_id(&receipt.receiver_id, &shard_layout);
+                let to_shard = receipt.receiver_shard_id(&shard_layout);
                 return Ok(Some((HashType::Receipt, to_shard)));
             }
         }
@@ -394,10 +393,7 @@ fn apply_receipt_in_chunk(
                     if receipt.get_hash() == *id {
                         let shard_layout =
                             epoch_manager.get_shard_layout_from_prev_block(chunk.prev_block())?;
-                        let to_shard = shard_layout::account_id_to_shard_id(
-                            &receipt.receiver_id,
-                            &shard_layout,
-                        );
+                        let to_shard = receipt.receiver_shard_id(&shard_layout);
                         to_apply.insert((height, to_shard));
                         println!(
                             "found receipt in chunk {}. Receiver is in shard {}",
@@ -1,17 +1,22 @@
 use crate::commands::*;
 use crate::contract_accounts::ContractAccountFilter;
 use crate::rocksdb_stats::get_rocksdb_stats;
+use crate::storage_usage::StorageUsageCmd;
+use crate::trie_cache_simulation::SimulateTrieCacheCmd;
+use crate::trie_diff::TrieDiffCmd;
 use crate::trie_iteration_benchmark::TrieIterationBenchmarkCmd;
 
 use framework::{load_config, UncConfig};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use unc_chain_configs::{GenesisChangeConfig, GenesisValidationMode};
+use unc_crypto::PublicKey;
 use unc_primitives::account::id::AccountId;
 use unc_primitives::hash::CryptoHash;
 use unc_primitives::sharding::ChunkHash;
 use unc_primitives::trie_key::col;
 use unc_primitives::types::{BlockHeight, ShardId};
+use unc_primitives::version::ProtocolVersion;
 use unc_store::{Mode, NodeStorage, Store, Temperature};
 
 #[derive(clap::Subcommand)]
@@ -64,6 +69,11 @@ pub enum StateViewerSubCommand {
     /// Print `EpochInfo` of an epoch given by `--epoch_id` or by `--epoch_height`.
     #[clap(alias = "epoch_info")]
     EpochInfo(EpochInfoCmd),
+    /// Compile the contracts of the current state for a protocol version and export the
+    /// compiled artifacts as a signed bundle, to be imported by other nodes.
+    ExportContractCache(ExportContractCacheCmd),
+    /// Import a bundle of compiled contracts created by `export-contract-cache`.
+    ImportContractCache(ImportContractCacheCmd),
     /// Looks up a certain partial chunk.
     #[clap(alias = "partial_chunks")]
     PartialChunks(PartialChunksCmd),
@@ -76,6 +86,9 @@ pub enum StateViewerSubCommand {
     RocksDBStats(RocksDBStatsCmd),
     /// Reads all rows of a DB column and deserializes keys and values and prints them.
     ScanDbColumn(ScanDbColumnCmd),
+    /// Replays a trace of trie node accesses recorded by `apply-range` to
+    /// estimate shard cache hit rates with other capacities and policies.
+    SimulateTrieCache(SimulateTrieCacheCmd),
     /// Iterates over a trie and prints the StateRecords.
     State,
     /// Dumps or applies StateChanges.
@@ -86,6 +99,12 @@ pub enum StateViewerSubCommand {
     /// Iterates over the Flat State and prints some statistics.
     /// e.g. large accounts, total, average and median size, middle account
     StateStats(StateStatsCmd),
+    /// Computes the storage used by each account over a range of epochs
+    /// from StateChanges, as CSV or JSON.
+    StorageUsage(StorageUsageCmd),
+    /// Print the differences between two state tries, e.g. of two nodes
+    /// that disagree on a state root.
+    TrieDiff(TrieDiffCmd),
     /// Benchmark how long does it take to iterate the trie.
     TrieIterationBenchmark(TrieIterationBenchmarkCmd),
     /// View head of the storage.
@@ -139,17 +158,28 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::DumpStateRedis(cmd) => cmd.run(home_dir, unc_config, store),
             StateViewerSubCommand::DumpTx(cmd) => cmd.run(home_dir, unc_config, store),
             StateViewerSubCommand::EpochInfo(cmd) => cmd.run(unc_config, store),
+            StateViewerSubCommand::ExportContractCache(cmd) => cmd.run(home_dir, unc_config, store),
+            StateViewerSubCommand::ImportContractCache(cmd) => cmd.run(store),
             StateViewerSubCommand::PartialChunks(cmd) => cmd.run(unc_config, store),
             StateViewerSubCommand::Receipts(cmd) => cmd.run(unc_config, store),
             StateViewerSubCommand::Replay(cmd) => cmd.run(unc_config, store),
             StateViewerSubCommand::RocksDBStats(cmd) => cmd.run(store_opener.path()),
             StateViewerSubCommand::ScanDbColumn(cmd) => cmd.run(store),
+            StateViewerSubCommand::SimulateTrieCache(cmd) => {
+                let trie_cache = &unc_config.config.store.trie_cache;
+                cmd.run(
+                    trie_cache.default_max_bytes,
+                    trie_cache.shard_cache_deletions_queue_capacity,
+                )
+            }
             StateViewerSubCommand::State => state(home_dir, unc_config, store),
             StateViewerSubCommand::StateChanges(cmd) => cmd.run(home_dir, unc_config, store),
             StateViewerSubCommand::StateParts(cmd) => cmd.run(home_dir, unc_config, store),
             StateViewerSubCommand::StateStats(cmd) => cmd.run(home_dir, unc_config, store),
+            StateViewerSubCommand::StorageUsage(cmd) => cmd.run(unc_config, store),
             StateViewerSubCommand::ViewChain(cmd) => cmd.run(unc_config, store),
             StateViewerSubCommand::ViewTrie(cmd) => cmd.run(store),
+            StateViewerSubCommand::TrieDiff(cmd) => cmd.run(unc_config, store),
             StateViewerSubCommand::TrieIterationBenchmark(cmd) => cmd.run(unc_config, store),
         }
     }
@@ -215,6 +245,11 @@ pub struct ApplyRangeCmd {
     sequential: bool,
     #[clap(long)]
     use_flat_storage: bool,
+    /// Records accesses of trie nodes which reach the shard cache to the
+    /// file, for `simulate-trie-cache`. Requires sequential processing to
+    /// keep the order of accesses.
+    #[clap(long, value_parser, requires = "sequential")]
+    trace_trie_node_accesses: Option<PathBuf>,
 }
 
 impl ApplyRangeCmd {
@@ -231,6 +266,7 @@ impl ApplyRangeCmd {
             self.only_contracts,
             self.sequential,
             self.use_flat_storage,
+            self.trace_trie_node_accesses,
         );
     }
 }
@@ -241,12 +277,17 @@ pub struct ApplyReceiptCmd {
     hash: String,
     #[clap(long)]
     use_flat_storage: bool,
+    /// Print the host calls, storage accesses and gas of every function call
+    /// made by the receipt.
+    #[clap(long)]
+    trace: bool,
 }
 
 impl ApplyReceiptCmd {
     pub fn run(self, home_dir: &Path, unc_config: UncConfig, store: Store) {
         let hash = CryptoHash::from_str(&self.hash).unwrap();
-        apply_receipt(home_dir, unc_config, store, hash, self.use_flat_storage).unwrap();
+        apply_receipt(home_dir, unc_config, store, hash, self.use_flat_storage, self.trace)
+            .unwrap();
     }
 }
 
@@ -462,6 +503,51 @@ impl EpochInfoCmd {
     }
 }
 
+#[derive(clap::Parser)]
+pub struct ExportContractCacheCmd {
+    /// Protocol version to compile the contracts for. Defaults to the protocol version of the
+    /// next epoch.
+    #[clap(long)]
+    protocol_version: Option<ProtocolVersion>,
+    /// Key to sign the bundle with. Defaults to the node key.
+    #[clap(long, value_parser)]
+    key_file: Option<PathBuf>,
+    #[clap(long, value_parser)]
+    output: PathBuf,
+}
+
+impl ExportContractCacheCmd {
+    pub fn run(self, home_dir: &Path, unc_config: UncConfig, store: Store) {
+        let key_file =
+            self.key_file.unwrap_or_else(|| home_dir.join(&unc_config.config.node_key_file));
+        export_contract_cache(
+            self.protocol_version,
+            &key_file,
+            &self.output,
+            home_dir,
+            unc_config,
+            store,
+        )
+        .unwrap()
+    }
+}
+
+#[derive(clap::Parser)]
+pub struct ImportContractCacheCmd {
+    #[clap(long, value_parser)]
+    input: PathBuf,
+    /// Public key of a node whose bundles are accepted, e.g. the node key of the exporting node.
+    /// Can be given several times.
+    #[clap(long = "trusted-key", required = true)]
+    trusted_keys: Vec<PublicKey>,
+}
+
+impl ImportContractCacheCmd {
+    pub fn run(self, store: Store) {
+        import_contract_cache(&self.input, &self.trusted_keys, store).unwrap()
+    }
+}
+
 #[derive(clap::Parser)]
 pub struct PartialChunksCmd {
     #[clap(long)]
@@ -6,6 +6,7 @@ use crate::state_dump::state_dump;
 use crate::state_dump::state_dump_redis;
 use crate::tx_dump::dump_tx_from_block;
 use crate::{apply_chunk, epoch_info};
+use borsh::BorshDeserialize;
 use bytesize::ByteSize;
 use framework::{NightshadeRuntime, UncConfig};
 use itertools::GroupBy;
@@ -13,7 +14,7 @@ use itertools::Itertools;
 use node_runtime::adapter::ViewRuntimeAdapter;
 use serde_json::json;
 use std::collections::BinaryHeap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -28,6 +29,7 @@ use unc_chain::types::RuntimeAdapter;
 use unc_chain::types::RuntimeStorageConfig;
 use unc_chain::{ChainStore, ChainStoreAccess, ChainStoreUpdate, Error};
 use unc_chain_configs::GenesisChangeConfig;
+use unc_crypto::{InMemorySigner, PublicKey};
 use unc_epoch_manager::types::BlockHeaderInfo;
 use unc_epoch_manager::EpochManagerHandle;
 use unc_epoch_manager::{EpochManager, EpochManagerAdapter};
@@ -43,12 +45,18 @@ use unc_primitives::state_record::StateRecord;
 use unc_primitives::trie_key::col::NON_DELAYED_RECEIPT_COLUMNS;
 use unc_primitives::trie_key::TrieKey;
 use unc_primitives::types::{chunk_extra::ChunkExtra, BlockHeight, ShardId, StateRoot};
+use unc_primitives::version::ProtocolVersion;
 use unc_primitives_core::types::Gas;
 use unc_store::flat::FlatStorageChunkView;
 use unc_store::flat::FlatStorageManager;
 use unc_store::test_utils::create_test_store;
+use unc_store::trie::node_access_trace::TrieNodeAccessRecorder;
 use unc_store::TrieStorage;
-use unc_store::{DBCol, Store, Trie, TrieCache, TrieCachingStorage, TrieConfig, TrieDBStorage};
+use unc_store::{
+    DBCol, Store, StoreCompiledContractCache, Trie, TrieCache, TrieCachingStorage, TrieConfig,
+    TrieDBStorage,
+};
+use unc_vm_runner::{ContractCacheBundle, SignedContractCacheBundle};
 use yansi::Color::Red;
 
 pub(crate) fn apply_block(
@@ -102,6 +110,7 @@ pub(crate) fn apply_block(
                 ApplyChunkBlockContext::from_header(
                     block.header(),
                     prev_block.header().next_gas_price(),
+                    block.block_congestion_info(),
                 ),
                 &receipts,
                 chunk.transactions(),
@@ -125,6 +134,7 @@ pub(crate) fn apply_block(
                 ApplyChunkBlockContext::from_header(
                     block.header(),
                     block.header().next_gas_price(),
+                    block.block_congestion_info(),
                 ),
                 &[],
                 &[],
@@ -209,12 +219,16 @@ pub(crate) fn apply_range(
     only_contracts: bool,
     sequential: bool,
     use_flat_storage: bool,
+    trace_trie_node_accesses: Option<PathBuf>,
 ) {
     let mut csv_file = csv_file.map(|filename| std::fs::File::create(filename).unwrap());
 
     let epoch_manager = EpochManager::new_arc_handle(store.clone(), &unc_config.genesis.config);
     let runtime =
         NightshadeRuntime::from_config(home_dir, store.clone(), &unc_config, epoch_manager.clone());
+    let recorder =
+        trace_trie_node_accesses.map(|path| Arc::new(TrieNodeAccessRecorder::new(&path).unwrap()));
+    runtime.get_tries().set_node_access_recorder(recorder.clone());
     apply_chain_range(
         store,
         &unc_config.genesis,
@@ -229,6 +243,9 @@ pub(crate) fn apply_range(
         sequential,
         use_flat_storage,
     );
+    if let Some(recorder) = recorder {
+        recorder.flush().unwrap();
+    }
 }
 
 pub(crate) fn apply_receipt(
@@ -237,19 +254,39 @@ pub(crate) fn apply_receipt(
     store: Store,
     hash: CryptoHash,
     use_flat_storage: bool,
+    trace: bool,
 ) -> anyhow::Result<()> {
     let epoch_manager = EpochManager::new_arc_handle(store.clone(), &unc_config.genesis.config);
     let runtime =
         NightshadeRuntime::from_config(home_dir, store.clone(), &unc_config, epoch_manager.clone());
-    apply_chunk::apply_receipt(
-        unc_config.genesis.config.genesis_height,
-        epoch_manager.as_ref(),
-        runtime.as_ref(),
-        store,
-        hash,
-        use_flat_storage,
-    )
-    .map(|_| ())
+    let apply = || {
+        apply_chunk::apply_receipt(
+            unc_config.genesis.config.genesis_height,
+            epoch_manager.as_ref(),
+            runtime.as_ref(),
+            store,
+            hash,
+            use_flat_storage,
+        )
+    };
+    if !trace {
+        return apply().map(|_| ());
+    }
+    let (result, traces) = unc_vm_runner::logic::with_tracing(apply);
+    result?;
+    let traces: Vec<_> = traces.into_iter().filter(|trace| trace.receipt_id == hash).collect();
+    if traces.is_empty() {
+        println!("Receipt {} did not execute any function calls", hash);
+    }
+    for trace in traces {
+        println!(
+            "Trace of {} called on {}:\n{}",
+            trace.method_name,
+            trace.receiver_id,
+            serde_json::to_string_pretty(&trace.trace.steps)?
+        );
+    }
+    Ok(())
 }
 
 pub(crate) fn apply_tx(
@@ -711,6 +748,7 @@ pub(crate) fn resulting_chunk_extra(result: &ApplyChunkResult, gas_limit: Gas) -
         result.total_gas_burnt,
         gas_limit,
         result.total_balance_burnt,
+        result.congestion_info,
     )
 }
 
@@ -1060,6 +1098,57 @@ pub(crate) fn clear_cache(store: Store) {
     store_update.commit().unwrap();
 }
 
+/// Compiles the contracts of the latest state for the VM config of `protocol_version`, or of the
+/// next epoch if not given, and writes the compiled artifacts into a bundle signed with the key
+/// from `key_file`.
+pub(crate) fn export_contract_cache(
+    protocol_version: Option<ProtocolVersion>,
+    key_file: &Path,
+    output: &Path,
+    home_dir: &Path,
+    unc_config: UncConfig,
+    store: Store,
+) -> anyhow::Result<()> {
+    let signer = InMemorySigner::from_file(key_file)?;
+    let (epoch_manager, runtime, state_roots, header) =
+        load_trie(store.clone(), home_dir, &unc_config);
+    let protocol_version = match protocol_version {
+        Some(protocol_version) => protocol_version,
+        None => epoch_manager.get_epoch_protocol_version(header.next_epoch_id())?,
+    };
+    let mut keys = HashSet::new();
+    for (shard_id, state_root) in state_roots.iter().enumerate() {
+        let shard_uid = epoch_manager.shard_id_to_uid(shard_id as ShardId, header.epoch_id())?;
+        keys.extend(runtime.precompile_state_contracts(
+            protocol_version,
+            shard_uid,
+            *state_root,
+        )?);
+    }
+    let bundle = ContractCacheBundle::export(&StoreCompiledContractCache::new(&store), keys)?;
+    println!(
+        "Exporting {} compiled contracts for protocol version {} into {}",
+        bundle.entries.len(),
+        protocol_version,
+        output.display()
+    );
+    fs::write(output, borsh::to_vec(&bundle.sign(&signer))?)?;
+    Ok(())
+}
+
+/// Imports a bundle written by [`export_contract_cache`] if it is signed by one of `trusted_keys`.
+pub(crate) fn import_contract_cache(
+    input: &Path,
+    trusted_keys: &[PublicKey],
+    store: Store,
+) -> anyhow::Result<()> {
+    let signed_bundle = SignedContractCacheBundle::try_from_slice(&fs::read(input)?)?;
+    let bundle = signed_bundle.verify(trusted_keys)?;
+    let imported = bundle.import(&StoreCompiledContractCache::new(&store))?;
+    println!("Imported {} compiled contracts from {}", imported, input.display());
+    Ok(())
+}
+
 /// Prints the state statistics for all shards. Please note that it relies on
 /// the live flat storage and may break if the node is not stopped.
 pub(crate) fn print_state_stats(home_dir: &Path, store: Store, unc_config: UncConfig) {
@@ -1144,12 +1233,10 @@ fn get_state_stats_group_by<'a>(
                 let key_size = key.len() as u64;
                 let value_size = value.len() as u64;
                 let size = ByteSize::b(key_size + value_size);
-                let state_record = StateRecord::from_raw_key_value(key, value);
-                state_record.map(|state_record| StateStatsStateRecord {
-                    account_id: state_record_to_account_id(&state_record).clone(),
-                    state_record,
-                    size,
-                })
+                let state_record = StateRecord::from_raw_key_value(key, value)?;
+                // The global contract code isn't owned by any account.
+                let account_id = state_record_to_account_id(&state_record)?.clone();
+                Some(StateStatsStateRecord { account_id, state_record, size })
             })
         })
         .into_iter();
@@ -136,6 +136,9 @@ pub(crate) enum ActionType {
     Delegate,
     RegisterRsa2048Keys,
     CreateRsa2048Challenge,
+    DeployGlobalContract,
+    UseGlobalContract,
+    DeployContractWithMigration,
 }
 
 impl ContractAccount {
@@ -328,7 +331,8 @@ fn try_find_actions_spawned_by_receipt(
                         ContractAccountError::MissingOutgoingReceipt(*outgoing_receipt_id)
                     })?;
                     match outgoing_receipt.receipt {
-                        ReceiptEnum::Action(action_receipt) => {
+                        ReceiptEnum::Action(action_receipt)
+                        | ReceiptEnum::PromiseYield(action_receipt) => {
                             for action in &action_receipt.actions {
                                 let action_type = match action {
                                     Action::CreateAccount(_) => ActionType::CreateAccount,
@@ -346,6 +350,13 @@ fn try_find_actions_spawned_by_receipt(
                                     Action::CreateRsa2048Challenge(_) => {
                                         ActionType::CreateRsa2048Challenge
                                     }
+                                    Action::DeployGlobalContract(_) => {
+                                        ActionType::DeployGlobalContract
+                                    }
+                                    Action::UseGlobalContract(_) => ActionType::UseGlobalContract,
+                                    Action::DeployContractWithMigration(_) => {
+                                        ActionType::DeployContractWithMigration
+                                    }
                                 };
                                 entry
                                     .actions
@@ -353,12 +364,14 @@ fn try_find_actions_spawned_by_receipt(
                                     .insert(action_type);
                             }
                         }
-                        ReceiptEnum::Data(_) => {
+                        ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => {
                             entry
                                 .actions
                                 .get_or_insert_with(Default::default)
                                 .insert(ActionType::DataReceipt);
                         }
+                        // Not spawned by the contract, the code goes to the other shards.
+                        ReceiptEnum::GlobalContractDistribution(_) => {}
                     }
                 }
             }
@@ -11,6 +11,9 @@ mod scan_db;
 mod state_changes;
 mod state_dump;
 mod state_parts;
+mod storage_usage;
+mod trie_cache_simulation;
+mod trie_diff;
 mod trie_iteration_benchmark;
 mod tx_dump;
 
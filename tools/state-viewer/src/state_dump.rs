@@ -17,6 +17,7 @@ use unc_epoch_manager::EpochManagerAdapter;
 use unc_epoch_manager::EpochManagerHandle;
 use unc_primitives::account::id::AccountId;
 use unc_primitives::block::BlockHeader;
+use unc_primitives::hash::hash;
 use unc_primitives::state_record::state_record_to_account_id;
 use unc_primitives::state_record::StateRecord;
 use unc_primitives::types::{AccountInfo, Balance, StateRoot};
@@ -206,10 +207,11 @@ fn should_include_record(
 ) -> bool {
     match account_allowlist {
         None => true,
-        Some(allowlist) => {
-            let current_account_id = state_record_to_account_id(record);
-            allowlist.contains(current_account_id)
-        }
+        // The global contract code isn't owned by any account and is always included.
+        Some(allowlist) => match state_record_to_account_id(record) {
+            Some(current_account_id) => allowlist.contains(current_account_id),
+            None => true,
+        },
     }
 }
 
@@ -233,6 +235,8 @@ fn iterate_over_records(
         }
     };
     let mut total_supply = 0;
+    // Every shard stores the global contracts, each of them is dumped once.
+    let mut global_contract_code_hashes = HashSet::new();
     for (shard_id, state_root) in state_roots.iter().enumerate() {
         let trie = runtime
             .get_trie_for_shard(shard_id as u64, last_block_header.prev_hash(), *state_root, false)
@@ -243,6 +247,11 @@ fn iterate_over_records(
                 if !should_include_record(&sr, &account_allowlist) {
                     continue;
                 }
+                if let StateRecord::GlobalContractCode { code } = &sr {
+                    if !global_contract_code_hashes.insert(hash(code)) {
+                        continue;
+                    }
+                }
                 if let StateRecord::Account { account_id, account } = &mut sr {
                     if account.pledging() > 0 {
                         let pledge = *validators.get(account_id).map(|(_, _, s)| s).unwrap_or(&0);
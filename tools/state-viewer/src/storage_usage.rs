@@ -0,0 +1,492 @@
+use crate::trie_diff::{key_account_id, key_type};
+use borsh::BorshDeserialize;
+use framework::UncConfig;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+use std::rc::Rc;
+use strum::IntoEnumIterator;
+use unc_chain::{ChainStore, ChainStoreAccess};
+use unc_epoch_manager::{EpochManager, EpochManagerAdapter};
+use unc_primitives::account::Account;
+use unc_primitives::block_header::BlockHeader;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::trie_key::col;
+use unc_primitives::types::{AccountId, EpochHeight, EpochId};
+use unc_store::db::STORAGE_USAGE_TOP_GROWERS_KEY;
+use unc_store::{
+    DBCol, DBKeyType, KeyForStateChanges, ShardUId, Store, Trie, TrieChanges, TrieDBStorage,
+};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub(crate) enum StorageUsageFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// Computes a time series of storage used by each account from `StateChanges`.
+///
+/// For every epoch in the range the command reports the `storage_usage` of
+/// every account changed in that epoch, together with the number of bytes
+/// added or removed per trie key type (`ContractData`, `AccessKey`, ...),
+/// and the number of bytes the blocks of the epoch wrote per DB column.
+/// The node must keep `StateChanges` for the whole range, i.e. be archival.
+#[derive(clap::Parser)]
+pub struct StorageUsageCmd {
+    /// Height of the first epoch to analyse.
+    #[clap(long)]
+    epoch_height_from: EpochHeight,
+    /// Height of the last epoch to analyse. Defaults to the epoch of the chain head.
+    #[clap(long)]
+    epoch_height_to: Option<EpochHeight>,
+    /// Output format.
+    #[clap(long, value_enum, default_value_t)]
+    format: StorageUsageFormat,
+    /// File to write the output to. Prints to stdout if not set.
+    #[clap(long, value_parser)]
+    output: Option<PathBuf>,
+    /// Number of accounts with the largest storage growth to report.
+    #[clap(long, default_value_t = 10)]
+    top_n: usize,
+    /// Saves the top growers in the database, from where the node exports
+    /// them as the `unc_storage_usage_top_growers_bytes` metric. Requires
+    /// `--readwrite`.
+    #[clap(long)]
+    save_top_growers: bool,
+}
+
+/// Storage changes of a single account during an epoch.
+#[derive(serde::Serialize, Default)]
+struct AccountStorageUsage {
+    /// `storage_usage` of the account at the end of the epoch, if the account
+    /// record was changed in the epoch.
+    storage_usage: Option<u64>,
+    /// Bytes added (positive) or removed (negative) per trie key type.
+    deltas: BTreeMap<&'static str, i64>,
+}
+
+#[derive(serde::Serialize)]
+struct EpochStorageUsage {
+    epoch_height: EpochHeight,
+    epoch_id: CryptoHash,
+    first_block_height: u64,
+    last_block_height: u64,
+    /// Bytes added or removed per trie key type over all accounts.
+    totals: BTreeMap<&'static str, i64>,
+    /// Bytes written per DB column by the blocks of the epoch. Only columns
+    /// keyed by block or chunk hash are included, together with `State`
+    /// which is accounted from the trie changes of the blocks.
+    columns: BTreeMap<&'static str, u64>,
+    accounts: BTreeMap<AccountId, AccountStorageUsage>,
+}
+
+#[derive(serde::Serialize)]
+struct StorageGrower {
+    account_id: AccountId,
+    growth: i64,
+}
+
+#[derive(serde::Serialize)]
+struct StorageUsageReport {
+    epochs: Vec<EpochStorageUsage>,
+    top_growers: Vec<StorageGrower>,
+}
+
+/// `storage_usage` of an account before and after the analysed range.
+struct AccountGrowth {
+    initial: u64,
+    last: u64,
+}
+
+impl StorageUsageCmd {
+    pub fn run(self, unc_config: UncConfig, store: Store) {
+        let report = self.compute(&unc_config, store.clone()).unwrap();
+        let mut out: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path).unwrap())),
+            None => Box::new(std::io::stdout().lock()),
+        };
+        match self.format {
+            StorageUsageFormat::Csv => write_csv(&mut out, &report).unwrap(),
+            StorageUsageFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &report).unwrap();
+                writeln!(out).unwrap();
+            }
+        }
+        if self.save_top_growers {
+            save_top_growers(&store, &report.top_growers).unwrap();
+        }
+    }
+
+    fn compute(&self, unc_config: &UncConfig, store: Store) -> anyhow::Result<StorageUsageReport> {
+        let epoch_manager = EpochManager::new_arc_handle(store.clone(), &unc_config.genesis.config);
+        let chain_store = ChainStore::new(
+            store.clone(),
+            unc_config.genesis.config.genesis_height,
+            unc_config.client_config.save_trie_changes,
+        );
+        let head = chain_store.head()?;
+        let epoch_height_to = match self.epoch_height_to {
+            Some(epoch_height) => epoch_height,
+            None => epoch_manager.get_epoch_info(&head.epoch_id)?.epoch_height(),
+        };
+        anyhow::ensure!(
+            self.epoch_height_from <= epoch_height_to,
+            "--epoch-height-from must be less than or equal to --epoch-height-to"
+        );
+
+        let mut epoch_heights: HashMap<EpochId, EpochHeight> = HashMap::new();
+        // Size of key and value of every trie key seen so far, to compute deltas.
+        let mut sizes: HashMap<Vec<u8>, u64> = HashMap::new();
+        let mut growth: HashMap<AccountId, AccountGrowth> = HashMap::new();
+        let mut epochs: Vec<EpochStorageUsage> = vec![];
+
+        for height in chain_store.tail()?..=head.height {
+            let header = match chain_store.get_block_header_by_height(height) {
+                Ok(header) => header,
+                // Skipped heights have no blocks.
+                Err(unc_chain::Error::DBNotFoundErr(_)) => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let epoch_id = header.epoch_id().clone();
+            let epoch_height = match epoch_heights.get(&epoch_id) {
+                Some(epoch_height) => *epoch_height,
+                None => {
+                    let epoch_height = epoch_manager.get_epoch_info(&epoch_id)?.epoch_height();
+                    epoch_heights.insert(epoch_id.clone(), epoch_height);
+                    epoch_height
+                }
+            };
+            if epoch_height < self.epoch_height_from {
+                continue;
+            }
+            if epoch_height > epoch_height_to {
+                break;
+            }
+            if epochs.last().map(|epoch| epoch.epoch_height) != Some(epoch_height) {
+                tracing::info!(target: "state-viewer", epoch_height, height, "Processing epoch");
+                epochs.push(EpochStorageUsage {
+                    epoch_height,
+                    epoch_id: epoch_id.0,
+                    first_block_height: height,
+                    last_block_height: height,
+                    totals: BTreeMap::new(),
+                    columns: BTreeMap::new(),
+                    accounts: BTreeMap::new(),
+                });
+            }
+            let epoch = epochs.last_mut().unwrap();
+            epoch.last_block_height = height;
+            process_block(
+                &header,
+                &store,
+                &chain_store,
+                epoch_manager.as_ref(),
+                &mut sizes,
+                &mut growth,
+                epoch,
+            )?;
+            process_block_columns(&header, &store, &chain_store, epoch)?;
+        }
+
+        let mut top_growers: Vec<StorageGrower> = growth
+            .into_iter()
+            .map(|(account_id, growth)| StorageGrower {
+                account_id,
+                growth: growth.last as i64 - growth.initial as i64,
+            })
+            .collect();
+        top_growers.sort_by(|a, b| b.growth.cmp(&a.growth).then(a.account_id.cmp(&b.account_id)));
+        top_growers.truncate(self.top_n);
+        Ok(StorageUsageReport { epochs, top_growers })
+    }
+}
+
+/// Accounts all state changes of the block in `epoch`.
+fn process_block(
+    header: &BlockHeader,
+    store: &Store,
+    chain_store: &ChainStore,
+    epoch_manager: &dyn EpochManagerAdapter,
+    sizes: &mut HashMap<Vec<u8>, u64>,
+    growth: &mut HashMap<AccountId, AccountGrowth>,
+    epoch: &mut EpochStorageUsage,
+) -> anyhow::Result<()> {
+    // Tries at the previous block, to look up values of keys seen for the first time.
+    let mut prev_tries: HashMap<ShardUId, Trie> = HashMap::new();
+    for row in KeyForStateChanges::for_block(header.hash()).find_rows_iter(store) {
+        let (_, changes) = row?;
+        let key = changes.trie_key.to_vec();
+        let Some(account_id) = key_account_id(&key) else {
+            continue;
+        };
+        let Some(new_value) = changes.changes.last().map(|change| change.data.clone()) else {
+            continue;
+        };
+
+        let old_size = match sizes.get(&key) {
+            Some(size) => *size,
+            None => {
+                // The previous block may belong to an epoch with another shard layout.
+                let prev_epoch_id = epoch_manager.get_epoch_id(header.prev_hash())?;
+                let shard_id = epoch_manager.account_id_to_shard_id(&account_id, &prev_epoch_id)?;
+                let shard_uid = epoch_manager.shard_id_to_uid(shard_id, &prev_epoch_id)?;
+                let trie = match prev_tries.entry(shard_uid) {
+                    std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        let state_root = *chain_store
+                            .get_chunk_extra(header.prev_hash(), &shard_uid)?
+                            .state_root();
+                        entry.insert(Trie::new(
+                            Rc::new(TrieDBStorage::new(store.clone(), shard_uid)),
+                            state_root,
+                            None,
+                        ))
+                    }
+                };
+                let old_value = trie.get(&key)?;
+                if key[0] == col::ACCOUNT {
+                    let initial = match &old_value {
+                        Some(value) => Account::try_from_slice(value)?.storage_usage(),
+                        None => 0,
+                    };
+                    growth
+                        .entry(account_id.clone())
+                        .or_insert(AccountGrowth { initial, last: initial });
+                }
+                old_value.map_or(0, |value| (key.len() + value.len()) as u64)
+            }
+        };
+        let new_size = new_value.as_ref().map_or(0, |value| (key.len() + value.len()) as u64);
+        sizes.insert(key.clone(), new_size);
+
+        let delta = new_size as i64 - old_size as i64;
+        let key_type = key_type(&key);
+        *epoch.totals.entry(key_type).or_default() += delta;
+        let account_usage = epoch.accounts.entry(account_id.clone()).or_default();
+        *account_usage.deltas.entry(key_type).or_default() += delta;
+        if key[0] == col::ACCOUNT {
+            let storage_usage = match &new_value {
+                Some(value) => Account::try_from_slice(value)?.storage_usage(),
+                None => 0,
+            };
+            account_usage.storage_usage = Some(storage_usage);
+            if let Some(growth) = growth.get_mut(&account_id) {
+                growth.last = storage_usage;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Accounts the bytes written by the block to the DB columns in `epoch`.
+fn process_block_columns(
+    header: &BlockHeader,
+    store: &Store,
+    chain_store: &ChainStore,
+    epoch: &mut EpochStorageUsage,
+) -> anyhow::Result<()> {
+    let block_hash = header.hash();
+    for col in DBCol::iter() {
+        if col.key_type().first() != Some(&DBKeyType::BlockHash) {
+            continue;
+        }
+        let mut size = 0;
+        for row in store.iter_prefix(col, block_hash.as_ref()) {
+            let (key, value) = row?;
+            size += (key.len() + value.len()) as u64;
+        }
+        if size > 0 {
+            *epoch.columns.entry(col.into()).or_default() += size;
+        }
+    }
+
+    // Trie nodes and values are keyed by their hash, so they are taken from
+    // the trie changes, which are only kept with `save_trie_changes`.
+    let mut state_size = 0;
+    for row in store.iter_prefix_ser::<TrieChanges>(DBCol::TrieChanges, block_hash.as_ref()) {
+        let (_, trie_changes) = row?;
+        for insertion in trie_changes.insertions() {
+            // Keys are `shard_uid` followed by the hash.
+            state_size += (8 + CryptoHash::LENGTH + insertion.payload().len()) as u64;
+        }
+    }
+    if state_size > 0 {
+        *epoch.columns.entry(DBCol::State.into()).or_default() += state_size;
+    }
+
+    // Chunks are keyed by chunk hash. Blocks without a body, e.g. on nodes
+    // which only keep headers for old heights, are skipped.
+    let Ok(block) = chain_store.get_block(block_hash) else {
+        return Ok(());
+    };
+    for chunk_header in block.chunks().iter() {
+        if chunk_header.height_included() != header.height() {
+            continue;
+        }
+        let chunk_hash = chunk_header.chunk_hash();
+        for col in [DBCol::Chunks, DBCol::PartialChunks] {
+            if let Some(value) = store.get(col, chunk_hash.as_ref())? {
+                *epoch.columns.entry(col.into()).or_default() +=
+                    (CryptoHash::LENGTH + value.len()) as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_csv(out: &mut dyn Write, report: &StorageUsageReport) -> std::io::Result<()> {
+    writeln!(out, "epoch_height,account_id,key_type,bytes_delta,storage_usage")?;
+    for epoch in &report.epochs {
+        // Totals over all accounts use an empty account id.
+        for (key_type, delta) in &epoch.totals {
+            writeln!(out, "{},,{},{},", epoch.epoch_height, key_type, delta)?;
+        }
+        // Bytes written per DB column use an empty account id and the
+        // column name prefixed with `DBCol::` as the key type.
+        for (column, size) in &epoch.columns {
+            writeln!(out, "{},,DBCol::{},{},", epoch.epoch_height, column, size)?;
+        }
+        for (account_id, usage) in &epoch.accounts {
+            let storage_usage = usage.storage_usage.map(|v| v.to_string()).unwrap_or_default();
+            for (key_type, delta) in &usage.deltas {
+                writeln!(
+                    out,
+                    "{},{},{},{},{}",
+                    epoch.epoch_height, account_id, key_type, delta, storage_usage
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Saves the top growers for the node to export them as metrics.
+fn save_top_growers(store: &Store, top_growers: &[StorageGrower]) -> anyhow::Result<()> {
+    let top_growers: Vec<(AccountId, i64)> =
+        top_growers.iter().map(|grower| (grower.account_id.clone(), grower.growth)).collect();
+    let mut store_update = store.store_update();
+    store_update.set_ser(DBCol::Misc, STORAGE_USAGE_TOP_GROWERS_KEY, &top_growers)?;
+    store_update.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use framework::config::{Config, GenesisExt};
+    use framework::test_utils::TestEnvNightshadeSetupExt;
+    use std::sync::Arc;
+    use unc_chain::ChainGenesis;
+    use unc_chain_configs::Genesis;
+    use unc_client::test_utils::TestEnv;
+    use unc_client::ProcessTxResponse;
+    use unc_crypto::{InMemorySigner, KeyFile, KeyType, PublicKey, SecretKey};
+    use unc_primitives::transaction::{Action, DeployContractAction, SignedTransaction};
+    use unc_primitives::validator_signer::InMemoryValidatorSigner;
+    use unc_store::test_utils::create_test_store;
+
+    fn storage_usage_cmd() -> StorageUsageCmd {
+        StorageUsageCmd {
+            epoch_height_from: 0,
+            epoch_height_to: None,
+            format: StorageUsageFormat::Json,
+            output: None,
+            top_n: 1,
+            save_top_growers: false,
+        }
+    }
+
+    /// Deploys a contract and checks that its storage is reported per key
+    /// type, per account, per DB column and among the top growers.
+    #[test]
+    fn test_storage_usage() {
+        let epoch_length = 4;
+        let mut genesis =
+            Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+        genesis.config.epoch_length = epoch_length;
+        let mut env = TestEnv::builder(ChainGenesis::new(&genesis))
+            .real_epoch_managers(&genesis.config)
+            .nightshade_runtimes(&genesis)
+            .build();
+        let unc_config = UncConfig::new(
+            Config::default(),
+            genesis,
+            KeyFile {
+                account_id: "test".parse().unwrap(),
+                public_key: PublicKey::empty(KeyType::ED25519),
+                secret_key: SecretKey::from_random(KeyType::ED25519),
+            },
+            Some(Arc::new(InMemoryValidatorSigner::from_random(
+                "test".parse().unwrap(),
+                KeyType::ED25519,
+            ))),
+        )
+        .unwrap();
+
+        let genesis_hash = *env.clients[0].chain.genesis().hash();
+        let signer = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
+        let code = unc_test_contracts::backwards_compatible_rs_contract().to_vec();
+        let tx = SignedTransaction::from_actions(
+            1,
+            "test0".parse().unwrap(),
+            "test0".parse().unwrap(),
+            &signer,
+            vec![Action::DeployContract(DeployContractAction { code: code.clone() })],
+            genesis_hash,
+        );
+        assert_eq!(env.clients[0].process_tx(tx, false, false), ProcessTxResponse::ValidTx);
+        for height in 1..=epoch_length * 2 + 1 {
+            env.produce_block(0, height);
+        }
+
+        let store = env.clients[0].chain.chain_store().store().clone();
+        let report = storage_usage_cmd().compute(&unc_config, store.clone()).unwrap();
+
+        let contract_code: i64 =
+            report.epochs.iter().filter_map(|epoch| epoch.totals.get("ContractCode")).sum();
+        // The key of the contract code is the column byte and the account id.
+        assert_eq!(contract_code, (1 + "test0".len() + code.len()) as i64);
+        let account = report
+            .epochs
+            .iter()
+            .find_map(|epoch| epoch.accounts.get(&"test0".parse::<AccountId>().unwrap()))
+            .unwrap();
+        assert_eq!(account.deltas.get("ContractCode"), Some(&contract_code));
+        assert!(account.storage_usage.is_some());
+
+        for column in ["Block", "BlockHeader", "StateChanges", "State"] {
+            assert!(
+                report
+                    .epochs
+                    .iter()
+                    .any(|epoch| epoch.columns.get(column).is_some_and(|size| *size > 0)),
+                "no growth reported for {column}"
+            );
+        }
+
+        assert_eq!(report.top_growers.len(), 1);
+        assert_eq!(report.top_growers[0].account_id.as_str(), "test0");
+        assert!(report.top_growers[0].growth >= code.len() as i64);
+
+        let mut csv = vec![];
+        write_csv(&mut csv, &report).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("epoch_height,account_id,key_type,bytes_delta,storage_usage\n"));
+        assert!(csv.lines().any(|line| line.contains(",,DBCol::Block,")));
+        assert!(csv.lines().any(|line| line.contains(",test0,ContractCode,")));
+    }
+
+    #[test]
+    fn test_save_top_growers() {
+        let store = create_test_store();
+        let top_growers = vec![
+            StorageGrower { account_id: "alice".parse().unwrap(), growth: 100 },
+            StorageGrower { account_id: "bob".parse().unwrap(), growth: -5 },
+        ];
+        save_top_growers(&store, &top_growers).unwrap();
+        let saved: Vec<(AccountId, i64)> =
+            store.get_ser(DBCol::Misc, STORAGE_USAGE_TOP_GROWERS_KEY).unwrap().unwrap();
+        assert_eq!(saved, vec![("alice".parse().unwrap(), 100), ("bob".parse().unwrap(), -5)]);
+    }
+}
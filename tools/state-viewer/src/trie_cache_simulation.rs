@@ -0,0 +1,515 @@
+use bytesize::ByteSize;
+use lru::LruCache;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::BufRead;
+use std::path::PathBuf;
+use unc_primitives::hash::CryptoHash;
+use unc_store::trie::node_access_trace::{TrieNodeAccess, TrieNodeAccessKind};
+use unc_store::{ShardUId, TrieConfig};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum EvictionPolicy {
+    /// Least recently used entries are evicted first, as in the shard cache.
+    Lru,
+    /// Adaptive replacement cache, balancing recency and frequency.
+    Arc,
+}
+
+/// Replays a trace of trie node accesses against shard caches with different
+/// capacities and eviction policies and reports their hit rates.
+///
+/// The trace is recorded with `apply-range --sequential --trace-trie-node-accesses`.
+/// Only accesses which reached the shard cache are in the trace, i.e. the
+/// ones not served by the chunk cache or by flat storage. Insertions and
+/// removals made by applied trie changes are replayed too, so that the
+/// simulated caches hold the same nodes as the shard cache would. Removals
+/// are postponed by a deletions queue, as in the shard cache.
+#[derive(clap::Parser)]
+pub struct SimulateTrieCacheCmd {
+    /// File with the trace of trie node accesses.
+    #[clap(long, value_parser)]
+    trace: PathBuf,
+    /// Cache capacities to simulate, e.g. `50MB`. Defaults to the
+    /// `trie_cache.default_max_bytes` of the node config.
+    #[clap(long)]
+    capacity: Vec<ByteSize>,
+    /// Overrides the capacity for a shard, e.g. `s3.v1=3GB`, like
+    /// `trie_cache.per_shard_max_bytes` does.
+    #[clap(long, value_parser = parse_shard_capacity)]
+    shard_capacity: Vec<(ShardUId, ByteSize)>,
+    /// Eviction policies to simulate.
+    #[clap(long, value_enum, default_values_t = [EvictionPolicy::Lru, EvictionPolicy::Arc])]
+    policy: Vec<EvictionPolicy>,
+    /// Simulate a single cache shared by all shards, with the capacity being
+    /// the total budget, instead of a cache per shard.
+    #[clap(long)]
+    shared: bool,
+}
+
+fn parse_shard_capacity(s: &str) -> Result<(ShardUId, ByteSize), String> {
+    let (shard_uid, capacity) =
+        s.split_once('=').ok_or_else(|| format!("expected <shard_uid>=<capacity>, got {s:?}"))?;
+    Ok((shard_uid.parse()?, capacity.parse()?))
+}
+
+impl SimulateTrieCacheCmd {
+    pub fn run(self, default_capacity: u64, deletions_queue_capacity: usize) {
+        let capacities = if self.capacity.is_empty() {
+            vec![default_capacity]
+        } else {
+            self.capacity.iter().map(ByteSize::as_u64).collect()
+        };
+        let shard_capacities: HashMap<ShardUId, u64> = self
+            .shard_capacity
+            .iter()
+            .map(|(shard_uid, capacity)| (*shard_uid, capacity.0))
+            .collect();
+
+        let mut simulations = vec![];
+        for &policy in &self.policy {
+            for &capacity in &capacities {
+                simulations.push(Simulation::new(
+                    policy,
+                    capacity,
+                    shard_capacities.clone(),
+                    deletions_queue_capacity,
+                    self.shared,
+                ));
+            }
+        }
+
+        let file = std::fs::File::open(&self.trace).unwrap();
+        for line in std::io::BufReader::new(file).lines() {
+            let access: TrieNodeAccess = line.unwrap().parse().unwrap();
+            for simulation in &mut simulations {
+                simulation.access(&access);
+            }
+        }
+
+        println!(
+            "{:<6} {:>12} {:>8} {:>14} {:>14} {:>9}",
+            "policy", "capacity", "shard", "accesses", "hits", "hit rate"
+        );
+        for simulation in &simulations {
+            simulation.print();
+        }
+    }
+}
+
+#[derive(Default)]
+struct HitStats {
+    accesses: u64,
+    hits: u64,
+}
+
+impl HitStats {
+    fn hit_rate(&self) -> f64 {
+        if self.accesses == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.accesses as f64
+        }
+    }
+}
+
+/// Simulation of one policy with one capacity.
+struct Simulation {
+    policy: EvictionPolicy,
+    capacity: u64,
+    shard_capacities: HashMap<ShardUId, u64>,
+    deletions_queue_capacity: usize,
+    shared: bool,
+    /// Caches by shard, or a single cache under `ShardUId::single_shard` if shared.
+    caches: HashMap<ShardUId, Box<dyn SimulatedCache>>,
+    stats: BTreeMap<ShardUId, HitStats>,
+}
+
+impl Simulation {
+    fn new(
+        policy: EvictionPolicy,
+        capacity: u64,
+        shard_capacities: HashMap<ShardUId, u64>,
+        deletions_queue_capacity: usize,
+        shared: bool,
+    ) -> Self {
+        Self {
+            policy,
+            capacity,
+            shard_capacities,
+            deletions_queue_capacity,
+            shared,
+            caches: HashMap::new(),
+            stats: BTreeMap::new(),
+        }
+    }
+
+    fn access(&mut self, access: &TrieNodeAccess) {
+        let cache_key = if self.shared { ShardUId::single_shard() } else { access.shard_uid };
+        let capacity = if self.shared {
+            self.capacity
+        } else {
+            *self.shard_capacities.get(&cache_key).unwrap_or(&self.capacity)
+        };
+        let policy = self.policy;
+        let deletions_queue_capacity = self.deletions_queue_capacity;
+        let cache = self.caches.entry(cache_key).or_insert_with(|| match policy {
+            EvictionPolicy::Lru => {
+                Box::new(LruSimulatedCache::new(capacity, deletions_queue_capacity))
+            }
+            EvictionPolicy::Arc => {
+                Box::new(ArcSimulatedCache::new(capacity, deletions_queue_capacity))
+            }
+        });
+        // Same as the shard cache, large values are never cached.
+        let cacheable = access.size < TrieConfig::max_cached_value_size();
+        let hit = match access.kind {
+            TrieNodeAccessKind::Read => {
+                cacheable && cache.access(access.hash, access.cache_entry_size())
+            }
+            TrieNodeAccessKind::Insert => {
+                if cacheable {
+                    cache.access(access.hash, access.cache_entry_size());
+                } else {
+                    cache.remove(access.hash);
+                }
+                return;
+            }
+            TrieNodeAccessKind::Remove => {
+                cache.remove(access.hash);
+                return;
+            }
+        };
+        let stats = self.stats.entry(access.shard_uid).or_default();
+        stats.accesses += 1;
+        stats.hits += hit as u64;
+    }
+
+    fn print(&self) {
+        let policy = format!("{:?}", self.policy).to_lowercase();
+        let capacity = ByteSize(self.capacity).to_string();
+        let mut total = HitStats::default();
+        for (shard_uid, stats) in &self.stats {
+            total.accesses += stats.accesses;
+            total.hits += stats.hits;
+            println!(
+                "{:<6} {:>12} {:>8} {:>14} {:>14} {:>8.2}%",
+                policy,
+                capacity,
+                shard_uid.to_string(),
+                stats.accesses,
+                stats.hits,
+                stats.hit_rate() * 100.0
+            );
+        }
+        let shard = if self.shared { "shared" } else { "all" };
+        println!(
+            "{:<6} {:>12} {:>8} {:>14} {:>14} {:>8.2}%",
+            policy,
+            capacity,
+            shard,
+            total.accesses,
+            total.hits,
+            total.hit_rate() * 100.0
+        );
+    }
+}
+
+trait SimulatedCache {
+    /// Accesses an entry of the given size, inserting it on a miss.
+    /// Returns whether the access was a hit.
+    fn access(&mut self, key: CryptoHash, size: u64) -> bool;
+
+    /// Removes an entry once it is pushed out of the deletions queue.
+    fn remove(&mut self, key: CryptoHash);
+}
+
+/// Queue postponing removals of entries, like the deletions queue of
+/// `TrieCache`: with forks, nodes removed by one block may still be read
+/// while processing another one.
+struct DeletionsQueue {
+    capacity: usize,
+    queue: VecDeque<CryptoHash>,
+}
+
+impl DeletionsQueue {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, queue: VecDeque::new() }
+    }
+
+    /// Queues the key and returns the key which has to be removed now, if any.
+    fn put(&mut self, key: CryptoHash) -> Option<CryptoHash> {
+        self.queue.push_back(key);
+        if self.queue.len() > self.capacity {
+            self.queue.pop_front()
+        } else {
+            None
+        }
+    }
+
+    fn pop(&mut self) -> Option<CryptoHash> {
+        self.queue.pop_front()
+    }
+}
+
+/// Size-aware LRU cache, the same policy as in `TrieCache`.
+struct LruSimulatedCache {
+    capacity: u64,
+    size: u64,
+    entries: LruCache<CryptoHash, u64>,
+    deletions: DeletionsQueue,
+}
+
+impl LruSimulatedCache {
+    fn new(capacity: u64, deletions_queue_capacity: usize) -> Self {
+        Self {
+            capacity,
+            size: 0,
+            entries: LruCache::unbounded(),
+            deletions: DeletionsQueue::new(deletions_queue_capacity),
+        }
+    }
+
+    fn pop(&mut self, key: &CryptoHash) {
+        if let Some(size) = self.entries.pop(key) {
+            self.size -= size;
+        }
+    }
+}
+
+impl SimulatedCache for LruSimulatedCache {
+    fn access(&mut self, key: CryptoHash, size: u64) -> bool {
+        if self.entries.get(&key).is_some() {
+            return true;
+        }
+        while self.size + size > self.capacity {
+            // Same as the shard cache, entries waiting for deletion go first.
+            if let Some(deleted) = self.deletions.pop() {
+                self.pop(&deleted);
+                continue;
+            }
+            match self.entries.pop_lru() {
+                Some((_, evicted_size)) => self.size -= evicted_size,
+                None => return false,
+            }
+        }
+        self.entries.put(key, size);
+        self.size += size;
+        false
+    }
+
+    fn remove(&mut self, key: CryptoHash) {
+        if !self.entries.contains(&key) {
+            return;
+        }
+        if let Some(deleted) = self.deletions.put(key) {
+            self.pop(&deleted);
+        }
+    }
+}
+
+/// LRU list of entries with the total size of the entries.
+struct SizedList {
+    size: u64,
+    entries: LruCache<CryptoHash, u64>,
+}
+
+impl SizedList {
+    fn new() -> Self {
+        Self { size: 0, entries: LruCache::unbounded() }
+    }
+
+    fn contains(&self, key: &CryptoHash) -> bool {
+        self.entries.contains(key)
+    }
+
+    fn put(&mut self, key: CryptoHash, size: u64) {
+        self.entries.put(key, size);
+        self.size += size;
+    }
+
+    fn remove(&mut self, key: &CryptoHash) -> Option<u64> {
+        let size = self.entries.pop(key)?;
+        self.size -= size;
+        Some(size)
+    }
+
+    fn pop_lru(&mut self) -> Option<(CryptoHash, u64)> {
+        let (key, size) = self.entries.pop_lru()?;
+        self.size -= size;
+        Some((key, size))
+    }
+}
+
+/// Adaptive replacement cache (Megiddo & Modha) adapted to entries of
+/// different sizes: all list lengths and the target `p` are in bytes.
+///
+/// `t1` holds entries seen once recently, `t2` entries seen at least twice.
+/// `b1` and `b2` are ghost lists of keys recently evicted from `t1` and `t2`,
+/// a hit in them shifts the target size of `t1` towards recency or frequency.
+struct ArcSimulatedCache {
+    capacity: u64,
+    /// Target size of `t1` in bytes.
+    p: u64,
+    t1: SizedList,
+    t2: SizedList,
+    b1: SizedList,
+    b2: SizedList,
+    deletions: DeletionsQueue,
+}
+
+impl ArcSimulatedCache {
+    fn new(capacity: u64, deletions_queue_capacity: usize) -> Self {
+        Self {
+            capacity,
+            p: 0,
+            t1: SizedList::new(),
+            t2: SizedList::new(),
+            b1: SizedList::new(),
+            b2: SizedList::new(),
+            deletions: DeletionsQueue::new(deletions_queue_capacity),
+        }
+    }
+
+    /// Removes a deleted entry. It doesn't go to a ghost list, as it won't be
+    /// accessed again.
+    fn pop(&mut self, key: &CryptoHash) {
+        if self.t1.remove(key).is_none() {
+            self.t2.remove(key);
+        }
+    }
+
+    /// Evicts entries from `t1` or `t2` to their ghost lists until an entry
+    /// of `size` bytes fits. Entries waiting for deletion are removed first.
+    fn replace(&mut self, size: u64, in_b2: bool) {
+        while self.t1.size + self.t2.size + size > self.capacity {
+            if let Some(deleted) = self.deletions.pop() {
+                self.pop(&deleted);
+                continue;
+            }
+            let from_t1 =
+                self.t1.size > 0 && (self.t1.size > self.p || (in_b2 && self.t1.size == self.p));
+            let evicted = if from_t1 { self.t1.pop_lru() } else { self.t2.pop_lru() };
+            match evicted {
+                Some((key, evicted_size)) => {
+                    if from_t1 {
+                        self.b1.put(key, evicted_size)
+                    } else {
+                        self.b2.put(key, evicted_size)
+                    }
+                }
+                None => match self.t1.pop_lru() {
+                    Some((key, evicted_size)) => self.b1.put(key, evicted_size),
+                    None => return,
+                },
+            }
+        }
+    }
+
+    /// Keeps the ghost lists within the capacity.
+    fn trim_ghosts(&mut self) {
+        while self.t1.size + self.b1.size > self.capacity && self.b1.pop_lru().is_some() {}
+        while self.t1.size + self.t2.size + self.b1.size + self.b2.size > 2 * self.capacity
+            && self.b2.pop_lru().is_some()
+        {}
+    }
+}
+
+impl SimulatedCache for ArcSimulatedCache {
+    fn access(&mut self, key: CryptoHash, size: u64) -> bool {
+        if self.t1.remove(&key).is_some() || self.t2.remove(&key).is_some() {
+            self.t2.put(key, size);
+            return true;
+        }
+        if size > self.capacity {
+            return false;
+        }
+        if self.b1.contains(&key) {
+            let ratio = (self.b2.size / self.b1.size.max(1)).max(1);
+            self.p = (self.p + ratio * size).min(self.capacity);
+            self.b1.remove(&key);
+            self.replace(size, false);
+            self.t2.put(key, size);
+        } else if self.b2.contains(&key) {
+            let ratio = (self.b1.size / self.b2.size.max(1)).max(1);
+            self.p = self.p.saturating_sub(ratio * size);
+            self.b2.remove(&key);
+            self.replace(size, true);
+            self.t2.put(key, size);
+        } else {
+            self.replace(size, false);
+            self.t1.put(key, size);
+        }
+        self.trim_ghosts();
+        false
+    }
+
+    fn remove(&mut self, key: CryptoHash) {
+        if !self.t1.contains(&key) && !self.t2.contains(&key) {
+            return;
+        }
+        if let Some(deleted) = self.deletions.put(key) {
+            self.pop(&deleted);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArcSimulatedCache, LruSimulatedCache, SimulatedCache};
+    use unc_primitives::hash::hash;
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let mut cache = LruSimulatedCache::new(20, 0);
+        let (a, b, c) = (hash(b"a"), hash(b"b"), hash(b"c"));
+        assert!(!cache.access(a, 10));
+        assert!(!cache.access(b, 10));
+        assert!(cache.access(a, 10));
+        assert!(!cache.access(c, 10));
+        assert!(cache.access(a, 10));
+        assert!(!cache.access(b, 10));
+    }
+
+    #[test]
+    fn arc_resists_scans() {
+        let mut lru = LruSimulatedCache::new(100, 0);
+        let mut arc = ArcSimulatedCache::new(100, 0);
+        let hot: Vec<_> = (0..5u32).map(|i| hash(&i.to_le_bytes())).collect();
+        let (mut lru_hits, mut arc_hits) = (0, 0);
+        for round in 0..100u32 {
+            for key in &hot {
+                lru_hits += lru.access(*key, 10) as u32;
+                arc_hits += arc.access(*key, 10) as u32;
+            }
+            // A scan of keys which are never accessed again.
+            for i in 0..6u32 {
+                let key = hash(&(1000 + round * 6 + i).to_le_bytes());
+                lru.access(key, 10);
+                arc.access(key, 10);
+            }
+        }
+        assert!(arc_hits > lru_hits, "arc: {arc_hits}, lru: {lru_hits}");
+    }
+
+    #[test]
+    fn removals_are_postponed() {
+        let caches: [Box<dyn SimulatedCache>; 2] =
+            [Box::new(LruSimulatedCache::new(30, 1)), Box::new(ArcSimulatedCache::new(30, 1))];
+        for mut cache in caches {
+            let (a, b, c, d) = (hash(b"a"), hash(b"b"), hash(b"c"), hash(b"d"));
+            cache.access(a, 10);
+            cache.access(b, 10);
+            cache.access(c, 10);
+            // Removal of `a` waits in the queue, so it is still a hit.
+            cache.remove(a);
+            assert!(cache.access(a, 10));
+            // Removal of `b` pushes `a` out of the queue.
+            cache.remove(b);
+            assert!(!cache.access(a, 10));
+            // Queued `b` is evicted before the least recently used `c`.
+            cache.access(d, 10);
+            assert!(cache.access(c, 10));
+            assert!(!cache.access(b, 10));
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use borsh::BorshDeserialize;
+use framework::UncConfig;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use unc_primitives::challenge::PartialState;
+use unc_primitives::hash::CryptoHash;
+use unc_primitives::state_record::StateRecord;
+use unc_primitives::trie_key::col;
+use unc_primitives::trie_key::trie_key_parsers::{
+    parse_account_id_from_raw_key, parse_account_id_from_rsa_key_key,
+};
+use unc_primitives::types::{AccountId, StateRoot};
+use unc_store::{Mode, NodeStorage, PartialStorage, ShardUId, Store, Trie, TrieDBStorage};
+
+/// Compares two state tries and prints every key whose value differs.
+///
+/// The left trie is always read from the local database.  The right trie is
+/// read from the local database as well, unless `--right-home` or
+/// `--right-state-parts-dir` are given.
+#[derive(clap::Parser)]
+pub struct TrieDiffCmd {
+    /// State root of the left trie.
+    #[clap(long)]
+    left_root: StateRoot,
+    /// State root of the right trie.
+    #[clap(long)]
+    right_root: StateRoot,
+    /// The id of the shard both state roots belong to.
+    #[clap(long)]
+    shard_id: u32,
+    /// The shard version based on the shard layout.
+    /// You can find the shard version by using the `view-state view-chain` command.
+    #[clap(long)]
+    shard_version: u32,
+    /// Home directory of another node whose database holds the right trie.
+    #[clap(long, value_parser, conflicts_with = "right_state_parts_dir")]
+    right_home: Option<PathBuf>,
+    /// Directory with all state parts of the right trie, e.g. as written by
+    /// `view-state state-parts dump`.
+    #[clap(long, value_parser)]
+    right_state_parts_dir: Option<PathBuf>,
+    /// Limits how many differences are printed to the output.
+    #[clap(long)]
+    limit: Option<usize>,
+}
+
+impl TrieDiffCmd {
+    pub fn run(self, unc_config: UncConfig, store: Store) {
+        let shard_uid = ShardUId { version: self.shard_version, shard_id: self.shard_id };
+        let left = get_trie(store.clone(), self.left_root, shard_uid);
+        let right = if let Some(right_home) = &self.right_home {
+            let right_storage = NodeStorage::opener(
+                right_home,
+                unc_config.config.archive,
+                &unc_config.config.store,
+                None,
+            )
+            .open_in_mode(Mode::ReadOnly)
+            .unwrap();
+            get_trie(right_storage.get_hot_store(), self.right_root, shard_uid)
+        } else if let Some(dir) = &self.right_state_parts_dir {
+            get_trie_from_state_parts(dir, self.right_root).unwrap()
+        } else {
+            get_trie(store, self.right_root, shard_uid)
+        };
+
+        let diff = left.diff(&right).unwrap();
+        tracing::info!(target: "state-viewer", num_differences = diff.len(), "Computed the trie diff");
+        let mut out = std::io::stdout().lock();
+        for item in diff.into_iter().take(self.limit.unwrap_or(usize::MAX)) {
+            print_diff_item(&mut out, item.key, item.left, item.right).unwrap();
+        }
+    }
+}
+
+fn get_trie(store: Store, root: StateRoot, shard_uid: ShardUId) -> Trie {
+    Trie::new(Rc::new(TrieDBStorage::new(store, shard_uid)), root, None)
+}
+
+/// Builds a trie from all state parts found in `dir`.
+///
+/// Each file in the directory is expected to hold a single borsh-serialized
+/// `PartialState`.  The parts must cover the whole state for the diff to be
+/// complete.
+fn get_trie_from_state_parts(dir: &Path, root: StateRoot) -> anyhow::Result<Trie> {
+    let mut nodes = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let PartialState::TrieValues(values) =
+            PartialState::try_from_slice(&std::fs::read(&path)?)?;
+        nodes.extend(values);
+    }
+    tracing::info!(target: "state-viewer", ?dir, num_nodes = nodes.len(), "Loaded state parts");
+    Ok(Trie::from_recorded_storage(
+        PartialStorage { nodes: PartialState::TrieValues(nodes) },
+        root,
+        false,
+    ))
+}
+
+pub(crate) fn key_type(key: &[u8]) -> &'static str {
+    if key[0] == col::DELAYED_RECEIPT_OR_INDICES {
+        return "DelayedReceiptOrIndices";
+    }
+    if key[0] == col::RSA2048_KEY {
+        return "Rsa2048Keys";
+    }
+    if key[0] == col::PROMISE_YIELD_INDICES {
+        return "PromiseYieldIndices";
+    }
+    if key[0] == col::PROMISE_YIELD_TIMEOUT {
+        return "PromiseYieldTimeout";
+    }
+    if key[0] == col::BUFFERED_RECEIPT_INDICES {
+        return "BufferedReceiptIndices";
+    }
+    if key[0] == col::BUFFERED_RECEIPT {
+        return "BufferedReceipt";
+    }
+    if key[0] == col::GLOBAL_CONTRACT_CODE {
+        return "GlobalContractCode";
+    }
+    col::NON_DELAYED_RECEIPT_COLUMNS
+        .iter()
+        .find_map(|(column, name)| (*column == key[0]).then_some(*name))
+        .unwrap_or("Unknown")
+}
+
+pub(crate) fn key_account_id(key: &[u8]) -> Option<AccountId> {
+    if key[0] == col::RSA2048_KEY {
+        return parse_account_id_from_rsa_key_key(key).ok();
+    }
+    parse_account_id_from_raw_key(key).ok().flatten()
+}
+
+fn print_diff_item(
+    out: &mut dyn Write,
+    key: Vec<u8>,
+    left: Option<Vec<u8>>,
+    right: Option<Vec<u8>>,
+) -> std::io::Result<()> {
+    let account_id = key_account_id(&key).map(|account_id| account_id.to_string());
+    writeln!(
+        out,
+        "{} account:{} key:{}",
+        key_type(&key),
+        account_id.as_deref().unwrap_or("-"),
+        unc_primitives::serialize::to_base64(&key),
+    )?;
+    for (sign, value) in [("-", left), ("+", right)] {
+        let Some(value) = value else {
+            continue;
+        };
+        let hash = CryptoHash::hash_bytes(&value);
+        match StateRecord::from_raw_key_value(key.clone(), value) {
+            Some(state_record) => writeln!(out, "  {sign} {state_record}")?,
+            None => writeln!(out, "  {sign} value_hash:{hash}")?,
+        }
+    }
+    Ok(())
+}
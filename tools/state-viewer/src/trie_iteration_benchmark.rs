@@ -197,7 +197,7 @@ impl TrieIterationBenchmarkCmd {
             };
             tracing::trace!(
                 target: "trie-iteration-benchmark",
-                "visiting column {} account id {}",
+                "visiting column {} account id {:?}",
                 &state_record.get_type_string(),state_record_to_account_id(&state_record)
             );
 
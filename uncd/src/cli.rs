@@ -405,6 +405,11 @@ pub(super) struct RunCmd {
     /// archival node, it cannot be run in non-archival mode.
     #[clap(long)]
     archive: bool,
+    /// Run as a light node, which follows only the light client blocks served by
+    /// its peers and requests execution proofs on demand, without tracking any
+    /// shards.  Light node can't be a validator.
+    #[clap(long, conflicts_with = "archive")]
+    light: bool,
     /// Set the boot nodes to bootstrap network from.
     #[clap(long)]
     boot_nodes: Option<String>,
@@ -508,6 +513,16 @@ impl RunCmd {
         if self.archive {
             unc_config.client_config.archive = true;
         }
+        if self.light {
+            if unc_config.validator_signer.is_some() {
+                eprintln!("Light node can't run with a validator key");
+                std::process::exit(1);
+            }
+            unc_config.config.light = true;
+            unc_config.config.tracked_shards.clear();
+            unc_config.client_config.light = true;
+            unc_config.network_config.light = true;
+        }
         if self.max_gas_burnt_view.is_some() {
             unc_config.client_config.max_gas_burnt_view = self.max_gas_burnt_view;
         }